@@ -12,10 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+};
 
+use chrono::{DateTime, Utc};
 use derive_more::Debug;
+use serde::Serialize;
 use tokio::fs;
+use walkdir::WalkDir;
 
 const SOURCES_FOLDER_NAME: &str = "sources";
 const BOOTSTRAP_DATA_FOLDER_NAME: &str = "bootstrap_data";
@@ -152,6 +159,149 @@ impl DataCollectionStorage {
     }
 }
 
+// What has been stored in a data collection. Records are the lines of the collection's
+// JSONL files, and the byte size covers every file in the collection.
+#[derive(Clone, Debug, Serialize)]
+pub struct DataCollectionSummary {
+    pub id: String,
+    pub byte_size: u64,
+    pub created_time: Option<DateTime<Utc>>,
+    pub file_count: usize,
+    pub record_count: usize,
+    pub source_record_counts: HashMap<String, usize>,
+}
+
+// A page of the records stored in a data collection, along with the total number of
+// records so callers can tell when they have them all.
+#[derive(Clone, Debug, Serialize)]
+pub struct DataCollectionRecordPage {
+    pub offset: usize,
+    pub records: Vec<serde_json::Value>,
+    pub total: usize,
+}
+
+impl DataCollectionStorage {
+    pub async fn get_summary(&self) -> anyhow::Result<DataCollectionSummary> {
+        let metadata = fs::metadata(&self.path).await?;
+        // Not every file system records creation times, so fall back to the modified time.
+        let created_time = metadata
+            .created()
+            .or_else(|_| metadata.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+
+        let path = self.path.clone();
+        let sources_path = self.sources_path.clone();
+        let (byte_size, file_count, record_count, source_record_counts) =
+            tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+                let mut byte_size = 0;
+                let mut file_count = 0;
+                let mut record_count = 0;
+                let mut source_record_counts = HashMap::new();
+
+                for entry in WalkDir::new(&path) {
+                    let entry = entry?;
+                    if !entry.file_type().is_file() {
+                        continue;
+                    }
+
+                    byte_size += entry.metadata()?.len();
+                    file_count += 1;
+
+                    if !is_record_file(entry.path()) {
+                        continue;
+                    }
+
+                    let file_record_count = read_records(entry.path())?.len();
+                    record_count += file_record_count;
+
+                    // Records under the sources folder are also counted against their source.
+                    if let Ok(relative_path) = entry.path().strip_prefix(&sources_path) {
+                        if let Some(source_id) = relative_path.iter().next() {
+                            *source_record_counts
+                                .entry(source_id.to_string_lossy().to_string())
+                                .or_insert(0) += file_record_count;
+                        }
+                    }
+                }
+
+                Ok((byte_size, file_count, record_count, source_record_counts))
+            })
+            .await??;
+
+        Ok(DataCollectionSummary {
+            id: self.id.clone(),
+            byte_size,
+            created_time,
+            file_count,
+            record_count,
+            source_record_counts,
+        })
+    }
+
+    // Takes up to limit records starting at offset, or all remaining records if limit is None.
+    // Records are read from the collection's JSONL files in path order.
+    pub async fn get_record_page(
+        &self,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> anyhow::Result<DataCollectionRecordPage> {
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut record_files: Vec<PathBuf> = WalkDir::new(&path)
+                .into_iter()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file() && is_record_file(entry.path()))
+                .map(|entry| entry.into_path())
+                .collect();
+            record_files.sort();
+
+            let mut records = Vec::new();
+            let mut total = 0;
+            for file_path in record_files {
+                for line in read_records(&file_path)? {
+                    let in_page = total >= offset
+                        && match limit {
+                            Some(limit) => records.len() < limit,
+                            None => true,
+                        };
+                    if in_page {
+                        records.push(serde_json::from_str(&line).map_err(|e| {
+                            anyhow::anyhow!("Error parsing record in {:?}: {}", file_path, e)
+                        })?);
+                    }
+                    total += 1;
+                }
+            }
+
+            Ok(DataCollectionRecordPage {
+                offset: offset.min(total),
+                records,
+                total,
+            })
+        })
+        .await?
+    }
+}
+
+fn is_record_file(path: &std::path::Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()) == Some("jsonl")
+}
+
+// Reads the non-empty lines of a JSONL file.
+fn read_records(path: &std::path::Path) -> anyhow::Result<Vec<String>> {
+    let reader = BufReader::new(std::fs::File::open(path)?);
+    let mut records = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            records.push(line);
+        }
+    }
+
+    Ok(records)
+}
+
 #[derive(Clone, Debug)]
 pub struct DataCollectionSourceStorage {
     pub id: String,
@@ -219,3 +369,82 @@ impl DataCollectionQueryStorage {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    async fn populate_collection(parent_path: PathBuf) -> anyhow::Result<DataCollectionStorage> {
+        let store =
+            DataCollectionStore::new("data_collections".to_string(), parent_path, false).await?;
+        let collection = store
+            .get_data_collection_storage("collection-1", false)
+            .await?;
+
+        for (source_id, file_name, first, count) in [
+            ("source-1", "changes_00001.jsonl", 0, 3),
+            ("source-1", "changes_00002.jsonl", 3, 2),
+            ("source-2", "changes_00001.jsonl", 5, 1),
+        ] {
+            let source = collection.get_source_storage(source_id, false).await?;
+            let lines: Vec<String> = (first..first + count)
+                .map(|seq| json!({ "seq": seq }).to_string())
+                .collect();
+            fs::write(
+                source.path.join(CHANGE_LOG_FOLDER_NAME).join(file_name),
+                lines.join("\n") + "\n",
+            )
+            .await?;
+        }
+        fs::write(collection.path.join("notes.txt"), "not a record").await?;
+
+        Ok(collection)
+    }
+
+    #[tokio::test]
+    async fn test_get_summary() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let collection = populate_collection(dir.path().to_path_buf()).await?;
+
+        let summary = collection.get_summary().await?;
+        assert_eq!(summary.id, "collection-1");
+        assert_eq!(summary.file_count, 4);
+        assert_eq!(summary.record_count, 6);
+        assert_eq!(summary.source_record_counts["source-1"], 5);
+        assert_eq!(summary.source_record_counts["source-2"], 1);
+        assert!(summary.byte_size > 0);
+        assert!(summary.created_time.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_record_page() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let collection = populate_collection(dir.path().to_path_buf()).await?;
+
+        let seqs = |page: &DataCollectionRecordPage| -> Vec<i64> {
+            page.records
+                .iter()
+                .map(|r| r["seq"].as_i64().unwrap())
+                .collect()
+        };
+
+        let page = collection.get_record_page(0, None).await?;
+        assert_eq!(seqs(&page), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(page.total, 6);
+
+        let page = collection.get_record_page(2, Some(3)).await?;
+        assert_eq!(seqs(&page), vec![2, 3, 4]);
+        assert_eq!(page.offset, 2);
+
+        let page = collection.get_record_page(10, Some(3)).await?;
+        assert!(page.records.is_empty());
+        assert_eq!(page.offset, 6);
+        assert_eq!(page.total, 6);
+
+        Ok(())
+    }
+}
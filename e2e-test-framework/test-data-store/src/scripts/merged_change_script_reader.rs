@@ -0,0 +1,307 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    path::PathBuf,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures::Stream;
+
+use super::{
+    change_script_file_reader::ChangeScriptReader, ChangeFinishRecord, ChangeHeaderRecord,
+    ChangeScriptRecord, SequencedChangeScriptRecord,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MergedChangeScriptReaderError {
+    #[error("At least one script is required to create a MergedChangeScriptReader")]
+    NoScripts,
+}
+
+// Reads several change scripts as if they were a single script, interleaving their records by
+// recorded time (a k-way merge). Each script's offsets are rebased onto the earliest script
+// start time, which becomes the start time of the merged Header. Records with equal times are
+// returned in the order the scripts were provided. Sequence numbers are reassigned across the
+// merged script, and the Finish records of the individual scripts are replaced by a single
+// Finish record once every script is exhausted.
+pub struct MergedChangeScriptReader {
+    readers: Vec<ChangeScriptReader>,
+    // The start of each script relative to the merged start time.
+    start_offsets_ns: Vec<u64>,
+    // The last offset read from each script, used in place of missing offsets.
+    last_offsets_ns: Vec<u64>,
+    // The next record from each script, or None once the script is exhausted.
+    next_records: Vec<Option<SequencedChangeScriptRecord>>,
+    // The script whose next record was returned and must be read before the next merge.
+    refill_index: Option<usize>,
+    header: ChangeHeaderRecord,
+    finish_offset_ns: u64,
+    finished: bool,
+    seq: u64,
+    offset_ns: u64,
+}
+
+impl MergedChangeScriptReader {
+    pub fn new(scripts: Vec<Vec<PathBuf>>) -> anyhow::Result<Self> {
+        if scripts.is_empty() {
+            return Err(MergedChangeScriptReaderError::NoScripts.into());
+        }
+
+        let readers = scripts
+            .into_iter()
+            .map(ChangeScriptReader::new)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let start_times: Vec<_> = readers
+            .iter()
+            .map(|reader| reader.get_header().start_time)
+            .collect();
+        let start_time = *start_times.iter().min().unwrap();
+        let start_offsets_ns = start_times
+            .iter()
+            .map(|time| (*time - start_time).num_nanoseconds().unwrap_or(0) as u64)
+            .collect();
+
+        let script_count = readers.len();
+        let mut reader = MergedChangeScriptReader {
+            readers,
+            start_offsets_ns,
+            last_offsets_ns: vec![0; script_count],
+            next_records: vec![None; script_count],
+            refill_index: None,
+            header: ChangeHeaderRecord {
+                start_time,
+                description: format!("Merged from {} scripts.", script_count),
+            },
+            finish_offset_ns: 0,
+            finished: false,
+            seq: 0,
+            offset_ns: 0,
+        };
+
+        for index in 0..script_count {
+            reader.read_next_record(index)?;
+        }
+
+        Ok(reader)
+    }
+
+    // Function to get the header record of the merged script.
+    pub fn get_header(&self) -> ChangeHeaderRecord {
+        self.header.clone()
+    }
+
+    // Reads the next record from the script at the given index, rebasing its offset onto the
+    // merged start time. Finish records mark the script as exhausted.
+    fn read_next_record(&mut self, index: usize) -> anyhow::Result<()> {
+        self.next_records[index] = None;
+
+        let mut seq_rec = match self.readers[index].next() {
+            Some(result) => result?,
+            None => return Ok(()),
+        };
+
+        // Missing offsets are treated as the same as the previous record's offset.
+        let offset_ns = seq_rec.offset_ns.max(self.last_offsets_ns[index]);
+        self.last_offsets_ns[index] = offset_ns;
+        seq_rec.offset_ns = self.start_offsets_ns[index] + offset_ns;
+
+        if let ChangeScriptRecord::Finish(_) = seq_rec.record {
+            self.finish_offset_ns = self.finish_offset_ns.max(seq_rec.offset_ns);
+        } else {
+            self.next_records[index] = Some(seq_rec);
+        }
+
+        Ok(())
+    }
+
+    fn get_next_record(&mut self) -> anyhow::Result<SequencedChangeScriptRecord> {
+        if let Some(index) = self.refill_index.take() {
+            self.read_next_record(index)?;
+        }
+
+        // Take the earliest record; min_by_key returns the first script when offsets are equal.
+        let next_index = self
+            .next_records
+            .iter()
+            .enumerate()
+            .filter_map(|(index, rec)| rec.as_ref().map(|rec| (index, rec.offset_ns)))
+            .min_by_key(|(_, offset_ns)| *offset_ns)
+            .map(|(index, _)| index);
+
+        let mut seq_rec = match next_index {
+            Some(index) => {
+                self.refill_index = Some(index);
+                self.next_records[index].take().unwrap()
+            }
+            None => {
+                self.finished = true;
+                let offset_ns = self.finish_offset_ns.max(self.offset_ns);
+                SequencedChangeScriptRecord {
+                    record: ChangeScriptRecord::Finish(ChangeFinishRecord {
+                        offset_ns,
+                        description: "Auto generated at end of merged script.".to_string(),
+                    }),
+                    seq: self.seq,
+                    offset_ns,
+                }
+            }
+        };
+
+        seq_rec.seq = self.seq;
+        self.seq += 1;
+        self.offset_ns = seq_rec.offset_ns;
+
+        match &mut seq_rec.record {
+            ChangeScriptRecord::Label(r) => r.offset_ns = seq_rec.offset_ns,
+            ChangeScriptRecord::PauseCommand(r) => r.offset_ns = seq_rec.offset_ns,
+            ChangeScriptRecord::SourceChange(r) => r.offset_ns = seq_rec.offset_ns,
+            ChangeScriptRecord::Finish(r) => r.offset_ns = seq_rec.offset_ns,
+            ChangeScriptRecord::Comment(_) | ChangeScriptRecord::Header(_) => {}
+        }
+
+        Ok(seq_rec)
+    }
+}
+
+impl Iterator for MergedChangeScriptReader {
+    type Item = anyhow::Result<SequencedChangeScriptRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            None
+        } else {
+            Some(self.get_next_record())
+        }
+    }
+}
+
+impl Stream for MergedChangeScriptReader {
+    type Item = anyhow::Result<SequencedChangeScriptRecord>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(item) = self.next() {
+            Poll::Ready(Some(item))
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::fs;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn source_change(offset_ns: u64, id: &str) -> serde_json::Value {
+        json!({
+            "kind": "SourceChange",
+            "offset_ns": offset_ns,
+            "source_change_event": {
+                "op": "i",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "db", "table": "node", "ts_ns": 0, "lsn": 0 },
+                    "before": null,
+                    "after": { "id": id, "labels": ["Item"], "properties": {} }
+                }
+            }
+        })
+    }
+
+    fn write_script(
+        folder: &std::path::Path,
+        name: &str,
+        records: &[serde_json::Value],
+    ) -> Vec<PathBuf> {
+        let path = folder.join(format!("{}.jsonl", name));
+        let lines: Vec<String> = records.iter().map(|r| r.to_string()).collect();
+        fs::write(&path, lines.join("\n")).unwrap();
+        vec![path]
+    }
+
+    #[test]
+    fn test_merge_two_scripts() {
+        let temp_dir = tempdir().unwrap();
+
+        let script_a = write_script(
+            temp_dir.path(),
+            "a",
+            &[
+                json!({ "kind": "Header", "start_time": "2024-01-01T00:00:00Z" }),
+                source_change(100, "a1"),
+                source_change(300, "a2"),
+                source_change(500, "a3"),
+                json!({ "kind": "Finish", "offset_ns": 500 }),
+            ],
+        );
+        // Starts 100ns after script "a", so its records fall at 200, 300 and 700 on the merged timeline.
+        let script_b = write_script(
+            temp_dir.path(),
+            "b",
+            &[
+                json!({ "kind": "Header", "start_time": "2024-01-01T00:00:00.000000100Z" }),
+                source_change(100, "b1"),
+                source_change(200, "b2"),
+                source_change(600, "b3"),
+            ],
+        );
+
+        let reader = MergedChangeScriptReader::new(vec![script_a, script_b]).unwrap();
+        assert_eq!(
+            reader.get_header().start_time.to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+
+        let records: Vec<_> = reader.map(|rec| rec.unwrap()).collect();
+
+        let ids: Vec<_> = records
+            .iter()
+            .filter_map(|rec| match &rec.record {
+                ChangeScriptRecord::SourceChange(r) => {
+                    Some(r.source_change_event.payload.after["id"].clone())
+                }
+                _ => None,
+            })
+            .collect();
+        // "a2" and "b2" are both at 300, so the earlier script wins the tie.
+        assert_eq!(ids, vec!["a1", "b1", "a2", "b2", "a3", "b3"]);
+
+        let offsets: Vec<_> = records.iter().map(|rec| rec.offset_ns).collect();
+        assert_eq!(offsets, vec![100, 200, 300, 300, 500, 700, 700]);
+        assert!(offsets.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        let seqs: Vec<_> = records.iter().map(|rec| rec.seq).collect();
+        assert_eq!(seqs, (0..7).collect::<Vec<_>>());
+
+        // A single Finish record ends the merged script.
+        assert!(matches!(
+            records.last().unwrap().record,
+            ChangeScriptRecord::Finish(ChangeFinishRecord { offset_ns: 700, .. })
+        ));
+        assert_eq!(
+            records
+                .iter()
+                .filter(|rec| matches!(rec.record, ChangeScriptRecord::Finish(_)))
+                .count(),
+            1
+        );
+    }
+}
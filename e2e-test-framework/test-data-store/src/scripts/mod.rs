@@ -19,6 +19,7 @@ pub mod bootstrap_script_file_reader;
 pub mod bootstrap_script_file_writer;
 pub mod change_script_file_reader;
 pub mod change_script_file_writer;
+pub mod merged_change_script_reader;
 
 pub type SourceChangeEventBefore = serde_json::Value; // Arbitrary JSON object for before
 pub type SourceChangeEventAfter = serde_json::Value; // Arbitrary JSON object for after
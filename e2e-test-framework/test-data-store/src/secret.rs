@@ -0,0 +1,145 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{cell::Cell, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const REDACTED: &str = "***";
+
+thread_local! {
+    static SECRETS_EXPOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Wraps a secret config value so it is redacted in `Debug` and `Serialize` output.
+///
+/// Configs are routinely logged and returned from the Web API, so the wrapped value is
+/// only accessible through [`Secret::expose_secret`], or serialized in full inside
+/// [`with_secrets_exposed`]. Deserialization is transparent.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret<T>(T);
+
+impl<T> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl<T> fmt::Debug for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Secret<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", REDACTED)
+    }
+}
+
+impl<T> Serialize for Secret<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if SECRETS_EXPOSED.with(Cell::get) {
+            self.0.serialize(serializer)
+        } else {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+}
+
+/// Runs `f` with every [`Secret`] it serializes on this thread written out in full.
+///
+/// Only for serializing configs that are persisted and read back, such as stored test
+/// definitions, which would otherwise lose their secrets.
+pub fn with_secrets_exposed<R>(f: impl FnOnce() -> R) -> R {
+    // Restores the previous setting even if f panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SECRETS_EXPOSED.with(|exposed| exposed.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SECRETS_EXPOSED.with(|exposed| exposed.replace(true)));
+    f()
+}
+
+impl<'de, T> Deserialize<'de> for Secret<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_secrets_exposed, Secret};
+
+    #[test]
+    fn test_secret_is_redacted() {
+        let secret: Secret<String> = "my_password".into();
+
+        assert_eq!(format!("{:?}", secret), "***");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"***\"");
+        assert_eq!(secret.expose_secret(), "my_password");
+    }
+
+    #[test]
+    fn test_secret_deserialize() {
+        let secret: Secret<String> = serde_json::from_str("\"my_password\"").unwrap();
+        assert_eq!(secret.expose_secret(), "my_password");
+
+        let secret: Option<Secret<String>> = serde_json::from_str("null").unwrap();
+        assert!(secret.is_none());
+    }
+
+    #[test]
+    fn test_secret_exposed() {
+        let secret: Secret<String> = "my_password".into();
+
+        let json = with_secrets_exposed(|| serde_json::to_string(&secret).unwrap());
+        assert_eq!(json, "\"my_password\"");
+
+        // Secrets are redacted again once the closure returns.
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"***\"");
+    }
+}
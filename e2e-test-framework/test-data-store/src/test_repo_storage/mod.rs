@@ -12,18 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
-use models::{LocalTestDefinition, TestDefinition, TestSourceDefinition};
+use models::{LocalTestDefinition, TestDefinition, TestDefinitionIssue, TestSourceDefinition};
 use serde::Serialize;
 use tokio::fs;
 use walkdir::WalkDir;
 
 use repo_clients::{create_test_repo_client, RemoteTestRepoClient, TestRepoConfig};
 
+use crate::secret::with_secrets_exposed;
+
 pub mod models;
 pub mod repo_clients;
 
+const TEST_REPO_CONFIG_FILE_NAME: &str = "repo.json";
 const TEST_SOURCES_FOLDER_NAME: &str = "sources";
 
 #[derive(Clone, Debug)]
@@ -59,10 +65,16 @@ impl TestRepoStore {
             test_repos: HashMap::new(),
         };
 
-        if initial_repos.is_some() {
-            for repo in initial_repos.unwrap() {
-                store.add_test_repo(repo, false).await?;
-            }
+        // Reload the configs of any repos persisted by a previous process. The configured repos
+        // win over persisted ones with the same ID, so a repo whose config was edited between
+        // runs is updated rather than rejected as a conflicting duplicate.
+        let initial_repos = initial_repos.unwrap_or_default();
+        let configured_ids: HashSet<String> =
+            initial_repos.iter().map(|repo| repo.get_id()).collect();
+        store.load_test_repo_configs(&configured_ids).await?;
+
+        for repo in initial_repos {
+            store.add_test_repo(repo, false).await?;
         }
 
         Ok(store)
@@ -82,15 +94,47 @@ impl TestRepoStore {
         let id = repo_config.get_id();
         let repo_path = self.path.join(&id);
 
+        // Re-adding a repo with an identical config is a no-op, but a differing config
+        // under the same ID is only allowed if the caller asked for it to be replaced.
+        // Literal secret values are masked in the comparison, so only their presence counts.
+        if let Some(existing_config) = self.test_repos.get(&id) {
+            if !replace
+                && serde_json::to_value(existing_config)? != serde_json::to_value(&repo_config)?
+            {
+                anyhow::bail!(
+                    "Test Repo with ID {:?} already exists with a different config",
+                    &id
+                );
+            }
+        }
+
         if replace && repo_path.exists() {
             fs::remove_dir_all(&repo_path).await?;
         }
 
         if !repo_path.exists() {
             fs::create_dir_all(&repo_path).await?;
-            self.test_repos.insert(id.clone(), repo_config.clone());
         }
 
+        // Persist the config so the repo survives a restart. Secrets are only persisted as
+        // env: or file: references; a config with literal secrets would be reloaded with the
+        // masked values and fail to authenticate, so it isn't persisted at all.
+        let config_path = repo_path.join(TEST_REPO_CONFIG_FILE_NAME);
+        if repo_config.has_literal_secrets() {
+            log::warn!(
+                "Test Repo {:?} has literal secrets, so its config won't survive a restart. Use env: or file: secret references to persist it.",
+                &id
+            );
+            if config_path.exists() {
+                fs::remove_file(&config_path).await?;
+            }
+        } else {
+            let json_content = serde_json::to_string_pretty(&repo_config)?;
+            fs::write(&config_path, json_content).await?;
+        }
+
+        self.test_repos.insert(id.clone(), repo_config.clone());
+
         let test_repo_storage = TestRepoStorage {
             id: id.to_string(),
             path: repo_path,
@@ -112,16 +156,63 @@ impl TestRepoStore {
 
     pub async fn get_test_repo_storage(&self, id: &str) -> anyhow::Result<TestRepoStorage> {
         if self.path.join(id).exists() {
-            Ok(TestRepoStorage {
-                id: id.to_string(),
-                path: self.path.join(id),
-                repo_config: self.test_repos.get(id).unwrap().clone(),
-            })
+            match self.test_repos.get(id) {
+                Some(repo_config) => Ok(TestRepoStorage {
+                    id: id.to_string(),
+                    path: self.path.join(id),
+                    repo_config: repo_config.clone(),
+                }),
+                None => {
+                    anyhow::bail!(
+                        "Test Repo folder with ID {:?} exists but its config is not loaded",
+                        &id
+                    );
+                }
+            }
         } else {
             anyhow::bail!("Test Repo with ID {:?} not found", &id);
         }
     }
 
+    async fn load_test_repo_configs(&mut self, skip_ids: &HashSet<String>) -> anyhow::Result<()> {
+        let mut entries = fs::read_dir(&self.path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let config_path = entry.path().join(TEST_REPO_CONFIG_FILE_NAME);
+            if !config_path.exists() {
+                continue;
+            }
+
+            let json_content = fs::read_to_string(&config_path).await?;
+            match serde_json::from_str::<TestRepoConfig>(&json_content) {
+                Ok(repo_config) if repo_config.has_literal_secrets() => {
+                    log::warn!(
+                        "Skipping persisted Test Repo config {:?} with masked literal secrets",
+                        &config_path
+                    );
+                }
+                Ok(repo_config) if skip_ids.contains(&repo_config.get_id()) => {
+                    log::info!(
+                        "Skipping persisted Test Repo config {:?} in favor of the configured one",
+                        &config_path
+                    );
+                }
+                Ok(repo_config) => {
+                    log::info!("Loaded persisted Test Repo config: {:?}", &config_path);
+                    self.test_repos.insert(repo_config.get_id(), repo_config);
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to load persisted Test Repo config {:?}: {}",
+                        &config_path,
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn get_test_repo_ids(&self) -> anyhow::Result<Vec<String>> {
         let mut test_repo_ids = Vec::new();
 
@@ -158,6 +249,8 @@ impl TestRepoStorage {
             &test_def
         );
 
+        check_test_definition_issues(&test_def.test_id, test_def.validate())?;
+
         let test_def_path = self.path.join(format!("{}.test.json", &test_def.test_id));
         let test_path = self.path.join(&test_def.test_id);
 
@@ -165,8 +258,9 @@ impl TestRepoStorage {
             fs::remove_dir_all(&test_path).await?;
         }
 
-        // Write the test definition to a file.
-        let json_content = serde_json::to_string_pretty(&test_def)?;
+        // Write the test definition to a file. Unlike the copies that are logged or returned
+        // from the Web API, the stored definition keeps its secrets.
+        let json_content = with_secrets_exposed(|| serde_json::to_string_pretty(&test_def))?;
         fs::write(test_def_path.clone(), json_content).await?;
 
         self.get_test_storage(&test_def.test_id).await
@@ -192,9 +286,16 @@ impl TestRepoStorage {
             // Download the test definition from the remote test repo.
             let test_repo_client = create_test_repo_client(self.repo_config.clone()).await?;
             test_repo_client
-                .copy_test_definition(id.to_string(), test_def_path)
+                .copy_test_definition(id.to_string(), test_def_path.clone())
                 .await?;
 
+            // Don't keep a definition that can't be run, so a fixed one is downloaded next time.
+            let issues = self.validate_test_definition(id).await;
+            if let Err(e) = issues.and_then(|issues| check_test_definition_issues(id, issues)) {
+                fs::remove_file(&test_def_path).await?;
+                return Err(e);
+            }
+
             self.get_test_storage(id).await
         } else {
             self.get_test_storage(id).await
@@ -215,11 +316,26 @@ impl TestRepoStorage {
             anyhow::bail!("Test with ID {:?} not found", &id);
         } else {
             // Read the test definition file into a string.
-            let json_content = fs::read_to_string(test_definition_path).await?;
-            Ok(serde_json::from_str(&json_content)?)
+            let json_content = fs::read_to_string(&test_definition_path).await?;
+            serde_json::from_str(&json_content).map_err(|e| {
+                anyhow::anyhow!(
+                    "Error parsing Test Definition {:?}: {}",
+                    test_definition_path,
+                    e
+                )
+            })
         }
     }
 
+    // Checks a stored test definition for problems that deserialization doesn't catch, such as
+    // duplicate IDs and references to components that aren't declared.
+    pub async fn validate_test_definition(
+        &self,
+        id: &str,
+    ) -> anyhow::Result<Vec<TestDefinitionIssue>> {
+        Ok(self.get_test_definition(id).await?.validate())
+    }
+
     pub async fn get_test_ids(&self) -> anyhow::Result<Vec<String>> {
         let mut tests = Vec::new();
 
@@ -267,6 +383,19 @@ impl TestRepoStorage {
     }
 }
 
+fn check_test_definition_issues(id: &str, issues: Vec<TestDefinitionIssue>) -> anyhow::Result<()> {
+    if issues.is_empty() {
+        return Ok(());
+    }
+
+    let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+    anyhow::bail!(
+        "Test Definition {:?} is invalid:\n  {}",
+        id,
+        issues.join("\n  ")
+    )
+}
+
 #[derive(Clone, Debug)]
 pub struct TestStorage {
     pub client_config: TestRepoConfig,
@@ -367,6 +496,7 @@ impl TestSourceStorage {
     pub async fn get_script_files(&self) -> anyhow::Result<TestSourceScriptSet> {
         let mut bootstrap_data_script_files = HashMap::new();
         let mut source_change_script_files = Vec::new();
+        let mut merged_source_change_script_files = Vec::new();
 
         if let models::TestSourceDefinition::Script(def) = &self.test_source_definition {
             // Read the bootstrap script files.
@@ -408,36 +538,53 @@ impl TestSourceStorage {
             }
 
             // Read the change log script files.
-            if let Some(models::SourceChangeGeneratorDefinition::Script(scg_def)) =
-                &def.source_change_generator
-            {
-                let source_change_scripts_repo_path = self.path.join(&scg_def.script_file_folder);
-
-                let mut entries = fs::read_dir(&source_change_scripts_repo_path).await?;
-
-                while let Some(entry) = entries.next_entry().await? {
-                    let file_path = entry.path();
-
-                    // Check if it's a file
-                    if file_path.is_file() {
-                        source_change_script_files.push(file_path);
+            match &def.source_change_generator {
+                Some(models::SourceChangeGeneratorDefinition::Script(scg_def)) => {
+                    source_change_script_files = self
+                        .read_change_script_folder(&scg_def.script_file_folder)
+                        .await?;
+                }
+                Some(models::SourceChangeGeneratorDefinition::MergedScript(scg_def)) => {
+                    for folder in &scg_def.script_file_folders {
+                        merged_source_change_script_files
+                            .push(self.read_change_script_folder(folder).await?);
                     }
                 }
+                _ => {}
             }
-
-            // Sort the list of files by the file name to get them in the correct order for processing.
-            source_change_script_files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
         }
 
         Ok(TestSourceScriptSet {
             bootstrap_data_script_files,
+            merged_source_change_script_files,
             source_change_script_files,
         })
     }
+
+    // Lists the files in a folder of change scripts, sorted by file name to get them in the
+    // correct order for processing.
+    async fn read_change_script_folder(&self, folder: &str) -> anyhow::Result<Vec<PathBuf>> {
+        let mut script_files = Vec::new();
+        let mut entries = fs::read_dir(self.path.join(folder)).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let file_path = entry.path();
+
+            // Check if it's a file
+            if file_path.is_file() {
+                script_files.push(file_path);
+            }
+        }
+
+        script_files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+        Ok(script_files)
+    }
 }
 
 #[derive(Clone, Debug, Serialize)]
 pub struct TestSourceScriptSet {
     pub bootstrap_data_script_files: HashMap<String, Vec<PathBuf>>,
+    // The files of each script a MergedScript generator merges, in the order they are listed.
+    pub merged_source_change_script_files: Vec<Vec<PathBuf>>,
     pub source_change_script_files: Vec<PathBuf>,
 }
@@ -20,6 +20,8 @@ use serde::{
     Deserialize, Serialize, Serializer,
 };
 
+use crate::secret::Secret;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TimeMode {
     Live,
@@ -40,10 +42,27 @@ impl FromStr for TimeMode {
         match s.to_lowercase().as_str() {
             "live" => Ok(Self::Live),
             "recorded" => Ok(Self::Recorded),
+            // A rebase time given as nanoseconds since the epoch.
+            nanos if !nanos.is_empty() && nanos.bytes().all(|b| b.is_ascii_digit()) => {
+                match nanos.parse::<u64>() {
+                    Ok(nanos) => Ok(Self::Rebased(nanos)),
+                    Err(e) => anyhow::bail!("Error parsing TimeMode - value:{}, error:{}", s, e),
+                }
+            }
             _ => match chrono::DateTime::parse_from_rfc3339(s) {
-                Ok(t) => Ok(Self::Rebased(t.timestamp_nanos_opt().unwrap() as u64)),
+                Ok(t) => match t.timestamp_nanos_opt().and_then(|n| u64::try_from(n).ok()) {
+                    Some(nanos) => Ok(Self::Rebased(nanos)),
+                    None => anyhow::bail!(
+                        "Error parsing TimeMode - value:{}, error:timestamp must be between 1970 and 2262",
+                        s
+                    ),
+                },
                 Err(e) => {
-                    anyhow::bail!("Error parsing TimeMode - value:{}, error:{}", s, e);
+                    anyhow::bail!(
+                        "Error parsing TimeMode - value:{}, error:{}; expected live, recorded, an RFC3339 timestamp, or nanoseconds since the epoch",
+                        s,
+                        e
+                    );
                 }
             },
         }
@@ -60,13 +79,29 @@ impl std::fmt::Display for TimeMode {
     }
 }
 
+// A serialized TimeMode is a string, except that a rebase time can also be given as a
+// number of nanoseconds since the epoch.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SerializedTimeMode {
+    Nanos(u64),
+    Text(String),
+}
+
 impl<'de> Deserialize<'de> for TimeMode {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let value: String = Deserialize::deserialize(deserializer)?;
-        value.parse::<TimeMode>().map_err(de::Error::custom)
+        match SerializedTimeMode::deserialize(deserializer) {
+            Ok(SerializedTimeMode::Nanos(nanos)) => Ok(Self::Rebased(nanos)),
+            Ok(SerializedTimeMode::Text(value)) => {
+                value.parse::<TimeMode>().map_err(de::Error::custom)
+            }
+            Err(_) => Err(de::Error::custom(
+                "Error parsing TimeMode; expected live, recorded, an RFC3339 timestamp, or nanoseconds since the epoch",
+            )),
+        }
     }
 }
 
@@ -94,6 +129,11 @@ pub enum SpacingMode {
     None,
     Rate(NonZeroU32),
     Recorded,
+    // Emits burst_size events back-to-back, then waits burst_interval_ns before the next burst.
+    Burst {
+        burst_size: NonZeroU32,
+        burst_interval_ns: u64,
+    },
 }
 
 impl Default for SpacingMode {
@@ -109,6 +149,28 @@ impl FromStr for SpacingMode {
         match s.to_lowercase().as_str() {
             "none" => Ok(Self::None),
             "recorded" => Ok(Self::Recorded),
+            burst if burst.starts_with("burst:") => {
+                // Parse the string as burst:<burst_size>:<burst_interval_ns>.
+                let parts: Vec<&str> = burst.split(':').collect();
+                if parts.len() != 3 {
+                    anyhow::bail!(
+                        "Invalid SpacingMode: {}; expected burst:<burst_size>:<burst_interval_ns>",
+                        s
+                    );
+                }
+                let burst_size = parts[1]
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(NonZeroU32::new)
+                    .ok_or_else(|| anyhow::anyhow!("Invalid SpacingMode burst_size: {}", s))?;
+                let burst_interval_ns = parts[2].parse::<u64>().map_err(|e| {
+                    anyhow::anyhow!("Error parsing SpacingMode burst_interval_ns: {}", e)
+                })?;
+                Ok(Self::Burst {
+                    burst_size,
+                    burst_interval_ns,
+                })
+            }
             _ => {
                 // Parse the string as a NonZero<u32>.
                 match s.parse::<u32>() {
@@ -131,6 +193,10 @@ impl std::fmt::Display for SpacingMode {
             Self::None => write!(f, "none"),
             Self::Recorded => write!(f, "recorded"),
             Self::Rate(rate) => write!(f, "{}", rate),
+            Self::Burst {
+                burst_size,
+                burst_interval_ns,
+            } => write!(f, "burst:{}:{}", burst_size, burst_interval_ns),
         }
     }
 }
@@ -154,11 +220,12 @@ impl Serialize for SpacingMode {
             Self::None => serializer.serialize_str("none"),
             Self::Recorded => serializer.serialize_str("recorded"),
             Self::Rate(rate) => serializer.serialize_str(&rate.to_string()),
+            Self::Burst { .. } => serializer.serialize_str(&self.to_string()),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct LocalTestDefinition {
     pub test_id: String,
     pub version: u32,
@@ -174,7 +241,7 @@ pub struct LocalTestDefinition {
     pub sources: Vec<TestSourceDefinition>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct TestDefinition {
     #[serde(skip_deserializing)]
     #[serde(default)]
@@ -225,16 +292,240 @@ impl TestDefinition {
 
         Ok(test_source_definition.clone())
     }
+
+    pub fn validate(&self) -> Vec<TestDefinitionIssue> {
+        validate_test_components(
+            &self.drasi_servers,
+            &self.queries,
+            &self.reactions,
+            &self.sources,
+        )
+    }
+}
+
+impl LocalTestDefinition {
+    pub fn validate(&self) -> Vec<TestDefinitionIssue> {
+        validate_test_components(
+            &self.drasi_servers,
+            &self.queries,
+            &self.reactions,
+            &self.sources,
+        )
+    }
+}
+
+// A problem with a test definition that deserialized successfully but can't be run. The
+// path locates the problem in the definition's JSON, e.g. "sources[1].test_source_id".
+#[derive(Clone, Debug, Serialize, PartialEq)]
+pub struct TestDefinitionIssue {
+    pub path: String,
+    pub message: String,
+}
+
+impl TestDefinitionIssue {
+    fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for TestDefinitionIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+// Checks the references between a test's components and the uniqueness of their IDs.
+fn validate_test_components(
+    drasi_servers: &[TestDrasiServerDefinition],
+    queries: &[TestQueryDefinition],
+    reactions: &[TestReactionDefinition],
+    sources: &[TestSourceDefinition],
+) -> Vec<TestDefinitionIssue> {
+    let mut issues = Vec::new();
+
+    check_unique_ids(
+        &mut issues,
+        "drasi_servers",
+        "id",
+        drasi_servers.iter().map(|server| server.id.as_str()),
+    );
+    check_unique_ids(
+        &mut issues,
+        "queries",
+        "test_query_id",
+        queries.iter().map(|query| query.test_query_id.as_str()),
+    );
+    check_unique_ids(
+        &mut issues,
+        "reactions",
+        "test_reaction_id",
+        reactions
+            .iter()
+            .map(|reaction| reaction.test_reaction_id.as_str()),
+    );
+    check_unique_ids(
+        &mut issues,
+        "sources",
+        "test_source_id",
+        sources.iter().map(|source| match source {
+            TestSourceDefinition::Model(def) => def.common.test_source_id.as_str(),
+            TestSourceDefinition::Script(def) => def.common.test_source_id.as_str(),
+        }),
+    );
+
+    for (i, server) in drasi_servers.iter().enumerate() {
+        let path = format!("drasi_servers[{}].config", i);
+        let config = &server.config;
+
+        check_unique_ids(
+            &mut issues,
+            &format!("{}.sources", path),
+            "id",
+            config.sources.iter().map(|source| source.id.as_str()),
+        );
+        check_unique_ids(
+            &mut issues,
+            &format!("{}.queries", path),
+            "id",
+            config.queries.iter().map(|query| query.id.as_str()),
+        );
+        check_unique_ids(
+            &mut issues,
+            &format!("{}.reactions", path),
+            "id",
+            config.reactions.iter().map(|reaction| reaction.id.as_str()),
+        );
+
+        for (j, query) in config.queries.iter().enumerate() {
+            for (k, source_id) in query.sources.iter().enumerate() {
+                if !config.sources.iter().any(|source| &source.id == source_id) {
+                    issues.push(TestDefinitionIssue::new(
+                        format!("{}.queries[{}].sources[{}]", path, j, k),
+                        format!(
+                            "Query {:?} subscribes to source {:?}, which Drasi server {:?} doesn't declare",
+                            query.id, source_id, server.id
+                        ),
+                    ));
+                }
+            }
+        }
+
+        for (j, reaction) in config.reactions.iter().enumerate() {
+            for (k, query_id) in reaction.queries.iter().enumerate() {
+                if !config.queries.iter().any(|query| &query.id == query_id) {
+                    issues.push(TestDefinitionIssue::new(
+                        format!("{}.reactions[{}].queries[{}]", path, j, k),
+                        format!(
+                            "Reaction {:?} subscribes to query {:?}, which Drasi server {:?} doesn't declare",
+                            reaction.id, query_id, server.id
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    for (i, reaction) in reactions.iter().enumerate() {
+        let handlers = reaction
+            .output_handler
+            .iter()
+            .map(|handler| (format!("reactions[{}].output_handler", i), handler))
+            .chain(
+                reaction
+                    .output_handlers
+                    .iter()
+                    .enumerate()
+                    .map(|(j, handler)| {
+                        (format!("reactions[{}].output_handlers[{}]", i, j), handler)
+                    }),
+            )
+            .collect::<Vec<_>>();
+
+        if handlers.is_empty() {
+            issues.push(TestDefinitionIssue::new(
+                format!("reactions[{}]", i),
+                format!(
+                    "Reaction {:?} has no output_handler or output_handlers",
+                    reaction.test_reaction_id
+                ),
+            ));
+        }
+
+        for (path, handler) in handlers {
+            let (drasi_server_id, reaction_id) = match handler {
+                ReactionHandlerDefinition::DrasiServerCallback(def) => {
+                    (&def.drasi_server_id, &def.reaction_id)
+                }
+                ReactionHandlerDefinition::DrasiServerChannel(def) => {
+                    (&def.drasi_server_id, &def.reaction_id)
+                }
+                _ => continue,
+            };
+
+            match drasi_servers
+                .iter()
+                .find(|server| &server.id == drasi_server_id)
+            {
+                Some(server) => {
+                    if !server
+                        .config
+                        .reactions
+                        .iter()
+                        .any(|reaction| &reaction.id == reaction_id)
+                    {
+                        issues.push(TestDefinitionIssue::new(
+                            format!("{}.reaction_id", path),
+                            format!(
+                                "Reaction {:?} doesn't exist on Drasi server {:?}",
+                                reaction_id, drasi_server_id
+                            ),
+                        ));
+                    }
+                }
+                None => {
+                    issues.push(TestDefinitionIssue::new(
+                        format!("{}.drasi_server_id", path),
+                        format!(
+                            "Drasi server {:?} isn't declared in the test",
+                            drasi_server_id
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    issues
+}
+
+fn check_unique_ids<'a>(
+    issues: &mut Vec<TestDefinitionIssue>,
+    path: &str,
+    id_field: &str,
+    ids: impl Iterator<Item = &'a str>,
+) {
+    let mut seen = std::collections::HashSet::new();
+    for (i, id) in ids.enumerate() {
+        if !seen.insert(id) {
+            issues.push(TestDefinitionIssue::new(
+                format!("{}[{}].{}", path, i, id_field),
+                format!("Duplicate ID {:?}", id),
+            ));
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum TestSourceDefinition {
     Model(ModelTestSourceDefinition),
     Script(ScriptTestSourceDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CommonTestSourceDefinition {
     pub test_source_id: String,
     #[serde(default)]
@@ -243,7 +534,7 @@ pub struct CommonTestSourceDefinition {
     pub subscribers: Vec<QueryId>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ScriptTestSourceDefinition {
     pub bootstrap_data_generator: Option<BootstrapDataGeneratorDefinition>,
     #[serde(flatten)]
@@ -251,50 +542,120 @@ pub struct ScriptTestSourceDefinition {
     pub source_change_generator: Option<SourceChangeGeneratorDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ModelTestSourceDefinition {
     #[serde(flatten)]
     pub common: CommonTestSourceDefinition,
     pub model_data_generator: Option<ModelDataGeneratorDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum BootstrapDataGeneratorDefinition {
+    File(FileBootstrapDataGeneratorDefinition),
     Script(ScriptBootstrapDataGeneratorDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CommonBootstrapDataGeneratorDefinition {
     #[serde(default)]
     pub time_mode: TimeMode,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ScriptBootstrapDataGeneratorDefinition {
     #[serde(flatten)]
     pub common: CommonBootstrapDataGeneratorDefinition,
     pub script_file_folder: String,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// Bootstrap data read from a single JSON file with the same layout as BootstrapData, i.e.
+// {"nodes": {"<label>": [NodeRecord]}, "rels": {"<label>": [RelationRecord]}}.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FileBootstrapDataGeneratorDefinition {
+    #[serde(flatten)]
+    pub common: CommonBootstrapDataGeneratorDefinition,
+    pub file: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum ModelDataGeneratorDefinition {
     BuildingHierarchy(BuildingHierarchyDataGeneratorDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CommonModelDataGeneratorDefinition {
+    // Capacity of the channel that schedules changes. Defaults to 1000.
+    #[serde(default)]
+    pub change_channel_capacity: Option<usize>,
     pub change_count: Option<u64>,
     pub change_interval: Option<(u64, f64, u64, u64)>,
+    // The distribution the time between changes is drawn from. Intervals are always clamped
+    // to the min and max of change_interval.
+    #[serde(default)]
+    pub change_interval_distribution: ChangeIntervalDistribution,
+    // If set, a Stop command received while Running dispatches the change that has already
+    // been prepared before the dispatchers are closed.
+    #[serde(default)]
+    pub drain_on_stop: bool,
+    // Renames SourceChangeEvent fields as the dispatchers that send JSON serialize them,
+    // e.g. {"op": "operation"}. Fields that aren't mapped keep their names.
+    #[serde(default)]
+    pub event_field_names: HashMap<String, String>,
+    #[serde(default)]
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
+    #[serde(default)]
+    pub max_eps: Option<NonZeroU32>,
+    // If set, each dispatcher is fed from its own queue of at most this many batches, so a slow
+    // dispatcher only holds up the generator once its queue is full. If not set, every
+    // dispatcher is sent each batch and the generator waits for all of them to finish.
+    #[serde(default)]
+    pub max_in_flight_dispatches: Option<usize>,
+    // Upper bound on a SpacingMode::Rate spacing. Defaults to 100,000 events per second.
+    #[serde(default)]
+    pub max_spacing_rate: Option<NonZeroU32>,
     pub seed: Option<u64>,
     #[serde(default)]
     pub spacing_mode: SpacingMode,
+    // Divides the time between changes, e.g. 10.0 generates changes ten times faster.
+    #[serde(default = "default_speed_factor")]
+    pub speed_factor: f64,
+    // Finish after this many changes have been dispatched, or after change_count changes,
+    // whichever comes first. Skipped changes aren't dispatched, so they don't count.
+    #[serde(default)]
+    pub stop_after_events: Option<u64>,
+    // Number of seconds over which the current_events_per_sec in the generator state is
+    // averaged. Defaults to 10 seconds.
+    #[serde(default = "default_throughput_window_secs")]
+    pub throughput_window_secs: u64,
     #[serde(default)]
     pub time_mode: TimeMode,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+fn default_throughput_window_secs() -> u64 {
+    10
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum ChangeIntervalDistribution {
+    // Normal distribution with the mean and standard deviation from change_interval.
+    #[default]
+    Normal,
+    // Exponential distribution of the time between changes, i.e. Poisson arrivals at rate
+    // changes per second.
+    Exponential {
+        rate: f64,
+    },
+    // Uniform distribution between min_ns and max_ns inclusive.
+    Uniform {
+        min_ns: u64,
+        max_ns: u64,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct BuildingHierarchyDataGeneratorDefinition {
     #[serde(flatten)]
     pub common: CommonModelDataGeneratorDefinition,
@@ -306,23 +667,37 @@ pub struct BuildingHierarchyDataGeneratorDefinition {
     pub send_initial_inserts: bool,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum SensorDefinition {
     NormalFloat(FloatNormalDistSensorDefinition),
     NormalInt(IntNormalDistSensorDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct FloatNormalDistSensorDefinition {
     pub id: String,
     pub momentum_init: Option<(i32, f64, f64)>, // mean, std_dev, reversal probability
     pub value_change: Option<(f64, f64)>,       // mean, std_dev
     pub value_init: Option<(f64, f64)>,         // mean, std_dev
     pub value_range: Option<(f64, f64)>,        // min, max
+    #[serde(default)]
+    pub seasonality: Option<SensorSeasonalityDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// A sine wave added to a sensor's random walk so its values follow a repeating pattern. The
+// wave is computed from the virtual time elapsed since the first change, so it is the same
+// whatever the SpacingMode.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SensorSeasonalityDefinition {
+    pub amplitude: f64,
+    pub period_ns: u64,
+    // Offset of the wave in radians.
+    #[serde(default)]
+    pub phase: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct IntNormalDistSensorDefinition {
     pub id: String,
     pub momentum_init: Option<(i32, f64, f64)>, // mean, std_dev, reversal probability
@@ -331,32 +706,224 @@ pub struct IntNormalDistSensorDefinition {
     pub value_range: Option<(i64, i64)>,        // min, max
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum SourceChangeGeneratorDefinition {
+    CsvFile(CsvFileSourceChangeGeneratorDefinition),
+    DebeziumFile(DebeziumFileSourceChangeGeneratorDefinition),
+    HttpPoll(HttpPollSourceChangeGeneratorDefinition),
+    LogTail(LogTailSourceChangeGeneratorDefinition),
+    MergedScript(MergedScriptSourceChangeGeneratorDefinition),
     Script(ScriptSourceChangeGeneratorDefinition),
 }
-#[derive(Clone, Debug, Serialize, Deserialize)]
+
+impl SourceChangeGeneratorDefinition {
+    // The folders of change script files the generator reads, in the order they are merged.
+    pub fn script_file_folders(&self) -> Vec<&String> {
+        match self {
+            SourceChangeGeneratorDefinition::MergedScript(def) => {
+                def.script_file_folders.iter().collect()
+            }
+            SourceChangeGeneratorDefinition::Script(def) => vec![&def.script_file_folder],
+            _ => Vec::new(),
+        }
+    }
+}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CommonSourceChangeGeneratorDefinition {
+    // Renames SourceChangeEvent fields as the dispatchers that send JSON serialize them,
+    // e.g. {"op": "operation"}. Fields that aren't mapped keep their names.
+    #[serde(default)]
+    pub event_field_names: HashMap<String, String>,
+    #[serde(default)]
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
+    #[serde(default)]
+    pub max_eps: Option<NonZeroU32>,
+    // Upper bound on a SpacingMode::Rate spacing. Defaults to 100,000 events per second.
+    #[serde(default)]
+    pub max_spacing_rate: Option<NonZeroU32>,
     #[serde(default)]
     pub spacing_mode: SpacingMode,
+    // Divides the recorded time between changes when they are replayed with
+    // SpacingMode::Recorded, e.g. 10.0 replays ten times faster.
+    #[serde(default = "default_speed_factor")]
+    pub speed_factor: f64,
     #[serde(default)]
     pub time_mode: TimeMode,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+fn default_speed_factor() -> f64 {
+    1.0
+}
+
+// JSON Schema that the before and after of each generated SourceChangeEvent are validated
+// against before dispatch. Violations are recorded in the generator state and, if
+// halt_on_violation is set, put the generator into an Error state.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SourceChangeEventSchemaDefinition {
+    pub schema: serde_json::Value,
+    #[serde(default)]
+    pub halt_on_violation: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ScriptSourceChangeGeneratorDefinition {
+    // Number of SourceChange records to dispatch while Running before the generator pauses
+    // itself. If auto_pause_repeat is set, it pauses again after every further
+    // auto_pause_after records; otherwise it only pauses the first time.
+    #[serde(default)]
+    pub auto_pause_after: Option<u64>,
+    #[serde(default = "is_false")]
+    pub auto_pause_repeat: bool,
     #[serde(flatten)]
     pub common: CommonSourceChangeGeneratorDefinition,
     #[serde(default = "is_false")]
     pub ignore_scripted_pause_commands: bool,
     pub script_file_folder: String,
+    // Number of SourceChange records to skip, without dispatching them, the first time the
+    // generator is started.
+    #[serde(default)]
+    pub start_offset: Option<u64>,
 }
 fn is_false() -> bool {
     false
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// Plays several change scripts as a single script, merging their records by recorded time.
+// Records with the same time are played in the order the folders are listed.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MergedScriptSourceChangeGeneratorDefinition {
+    #[serde(default)]
+    pub auto_pause_after: Option<u64>,
+    #[serde(default = "is_false")]
+    pub auto_pause_repeat: bool,
+    #[serde(flatten)]
+    pub common: CommonSourceChangeGeneratorDefinition,
+    #[serde(default = "is_false")]
+    pub ignore_scripted_pause_commands: bool,
+    pub script_file_folders: Vec<String>,
+    #[serde(default)]
+    pub start_offset: Option<u64>,
+}
+
+// Periodically GETs a JSON collection from a REST endpoint and emits insert, update and
+// delete SourceChangeEvents for the items that changed since the previous poll.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HttpPollSourceChangeGeneratorDefinition {
+    #[serde(flatten)]
+    pub common: CommonSourceChangeGeneratorDefinition,
+    pub url: String,
+    // Field of each item that uniquely identifies it across polls.
+    pub key_field: String,
+    // Headers added to every poll. The values are often credentials, so they are redacted.
+    #[serde(default)]
+    pub headers: HashMap<String, Secret<String>>,
+    // Dot separated path to the collection within the response. If not set, the
+    // response must be a JSON array.
+    pub items_path: Option<String>,
+    // Label given to the nodes created from the items. Defaults to the source ID.
+    pub label: Option<String>,
+    pub max_response_bytes: Option<u64>,
+    pub poll_interval_ms: Option<u64>,
+    pub timeout_seconds: Option<u64>,
+}
+
+// Tails an append-only file of JSON lines, dispatching each line appended to the file as a
+// SourceChangeEvent. Unlike the other generators the input is live, so there is no
+// spacing_mode, and the time_mode defaults to live.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LogTailSourceChangeGeneratorDefinition {
+    // Finish after this many lines have been read. If not set, tail until stopped.
+    pub change_count: Option<u64>,
+    #[serde(default)]
+    pub event_field_names: HashMap<String, String>,
+    #[serde(default)]
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
+    // Path of the file, relative to the test source folder. The file doesn't need to exist
+    // when the generator starts.
+    pub log_file: String,
+    #[serde(default)]
+    pub max_eps: Option<NonZeroU32>,
+    // How often the file is checked for new lines. Defaults to 100 ms.
+    pub poll_interval_ms: Option<u64>,
+    #[serde(default = "default_log_tail_time_mode")]
+    pub time_mode: TimeMode,
+}
+
+fn default_log_tail_time_mode() -> TimeMode {
+    TimeMode::Live
+}
+
+// Replays changes recorded in a CSV file. Each row is a change to one node, or to one
+// relation if start_id_column and end_id_column are set. The header row names the columns,
+// and every column other than the op and timestamp columns becomes a property of the element.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CsvFileSourceChangeGeneratorDefinition {
+    #[serde(flatten)]
+    pub common: CommonSourceChangeGeneratorDefinition,
+    // Path of the CSV file, relative to the test source folder.
+    pub csv_file: String,
+    // Interval between changes in nanoseconds, used when there is no timestamp_column.
+    // Defaults to 1 second.
+    pub change_interval: Option<u64>,
+    pub delimiter: Option<char>,
+    pub end_id_column: Option<String>,
+    // Column that uniquely identifies the element that changed.
+    pub key_column: String,
+    // Label given to the elements. Defaults to the source ID.
+    pub label: Option<String>,
+    // Column containing the change op: i, u or d. Defaults to "op".
+    pub op_column: Option<String>,
+    pub start_id_column: Option<String>,
+    // Column containing the time of each change, either an RFC 3339 timestamp or an
+    // integer in timestamp_unit since the Unix epoch.
+    pub timestamp_column: Option<String>,
+    #[serde(default)]
+    pub timestamp_unit: CsvTimestampUnit,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub enum CsvTimestampUnit {
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "us")]
+    Microseconds,
+    #[default]
+    #[serde(rename = "ns")]
+    Nanoseconds,
+}
+
+impl CsvTimestampUnit {
+    pub fn nanos_per_unit(&self) -> u64 {
+        match self {
+            Self::Seconds => 1_000_000_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Microseconds => 1_000,
+            Self::Nanoseconds => 1,
+        }
+    }
+}
+
+// Replays Debezium change events captured in a file, as JSON Lines or a JSON array. Each
+// event's row becomes the properties of a node, with create ("c") and snapshot read ("r")
+// events replayed as inserts. Events may be in the Debezium envelope with or without the
+// schema wrapper.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DebeziumFileSourceChangeGeneratorDefinition {
+    #[serde(flatten)]
+    pub common: CommonSourceChangeGeneratorDefinition,
+    // Path of the Debezium file, relative to the test source folder.
+    pub debezium_file: String,
+    // Column of the row that uniquely identifies the element that changed. Defaults to "id".
+    pub key_field: Option<String>,
+    // Label given to the elements. Defaults to the source.table of each event, or the
+    // source ID if the event has no table.
+    pub label: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum SourceChangeDispatcherDefinition {
     Console(ConsoleSourceChangeDispatcherDefinition),
@@ -367,14 +934,16 @@ pub enum SourceChangeDispatcherDefinition {
     RedisStream(RedisStreamSourceChangeDispatcherDefinition),
     DrasiServerApi(DrasiServerApiSourceChangeDispatcherDefinition),
     DrasiServerChannel(DrasiServerChannelSourceChangeDispatcherDefinition),
+    LsnOrderValidator(LsnOrderValidatorSourceChangeDispatcherDefinition),
+    Postgres(PostgresSourceChangeDispatcherDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ConsoleSourceChangeDispatcherDefinition {
     pub date_time_format: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DaprSourceChangeDispatcherDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
@@ -382,19 +951,40 @@ pub struct DaprSourceChangeDispatcherDefinition {
     pub pubsub_topic: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct JsonlFileSourceChangeDispatcherDefinition {
     pub max_events_per_file: Option<u64>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RedisStreamSourceChangeDispatcherDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
+    // Use a traceparent derived from the TestRun and the events instead of a random one.
+    #[serde(default)]
+    pub propagate_trace_context: bool,
     pub stream_name: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// Writes the changes to Postgres tables, so a Drasi Postgres source can pick them up through
+// logical replication.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PostgresSourceChangeDispatcherDefinition {
+    pub connection_string: Secret<String>,
+    pub max_connections: Option<u32>,
+    pub schema: Option<String>,
+    // Maps an element label to the table that changes to elements with that label are written to.
+    pub tables: HashMap<String, PostgresTableMappingDefinition>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PostgresTableMappingDefinition {
+    pub table: String,
+    // Column that holds the element ID. Defaults to "id".
+    pub key_column: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct HttpSourceChangeDispatcherDefinition {
     pub url: String,
     pub port: u16,
@@ -402,13 +992,24 @@ pub struct HttpSourceChangeDispatcherDefinition {
     pub timeout_seconds: Option<u64>,
     pub batch_events: Option<bool>,
     pub source_id: Option<String>,
+    // Headers added to every request. bearer_token is sent as an Authorization header, and
+    // both it and the header values can reference environment variables as ${VAR}.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    pub bearer_token: Option<Secret<String>>,
+    // Connection pool settings for the dispatcher's HTTP client.
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout_seconds: Option<u64>,
+    // Send a W3C traceparent header with each request. Not supported by the adaptive dispatcher.
+    #[serde(default)]
+    pub propagate_trace_context: bool,
     // Adaptive batching fields
     pub adaptive_enabled: Option<bool>,
     pub batch_size: Option<u64>,
     pub batch_timeout_ms: Option<u64>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct GrpcSourceChangeDispatcherDefinition {
     pub host: String,
     pub port: u16,
@@ -422,7 +1023,7 @@ pub struct GrpcSourceChangeDispatcherDefinition {
     pub batch_timeout_ms: Option<u64>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerApiSourceChangeDispatcherDefinition {
     pub drasi_server_id: String,
     pub source_id: String,
@@ -430,14 +1031,22 @@ pub struct DrasiServerApiSourceChangeDispatcherDefinition {
     pub batch_events: Option<bool>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerChannelSourceChangeDispatcherDefinition {
     pub drasi_server_id: String,
     pub source_id: String,
     pub buffer_size: Option<usize>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// Wraps another dispatcher and checks that the LSNs of the events it dispatches are
+// strictly increasing.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LsnOrderValidatorSourceChangeDispatcherDefinition {
+    pub dispatcher: Box<SourceChangeDispatcherDefinition>,
+    pub error_on_violation: Option<bool>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct TestQueryDefinition {
     #[serde(default)]
     pub test_query_id: String,
@@ -445,24 +1054,130 @@ pub struct TestQueryDefinition {
     pub stop_trigger: Option<StopTriggerDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(try_from = "TestReactionDefinitionConfig")]
 pub struct TestReactionDefinition {
-    #[serde(default)]
     pub test_reaction_id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_handler: Option<ReactionHandlerDefinition>,
+    // Additional handlers for reactions that deliver over more than one channel, e.g. both
+    // HTTP and gRPC. Invocations from all handlers are observed as one reaction.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub output_handlers: Vec<ReactionHandlerDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop_triggers: Option<Vec<StopTriggerDefinition>>,
+    // Results the reaction is expected to receive. The observer reports how many of them were
+    // matched or missing, and how many invocations weren't expected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_results: Option<ExpectedResultsDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// TestReactionDefinition as it is written in a test definition, where output_handler can be
+// a single handler or a list of handlers. The first handler in a list becomes the
+// output_handler and the rest are placed ahead of any output_handlers.
+#[derive(Deserialize)]
+struct TestReactionDefinitionConfig {
+    #[serde(default)]
+    test_reaction_id: String,
+    #[serde(default)]
+    output_handler: Option<serde_json::Value>,
+    #[serde(default)]
+    output_handlers: Vec<ReactionHandlerDefinition>,
+    #[serde(default)]
+    stop_triggers: Option<Vec<StopTriggerDefinition>>,
+    #[serde(default)]
+    expected_results: Option<ExpectedResultsDefinition>,
+}
+
+impl TryFrom<TestReactionDefinitionConfig> for TestReactionDefinition {
+    type Error = serde_json::Error;
+
+    fn try_from(config: TestReactionDefinitionConfig) -> Result<Self, Self::Error> {
+        let mut handlers: Vec<ReactionHandlerDefinition> = match config.output_handler {
+            None | Some(serde_json::Value::Null) => vec![],
+            Some(value @ serde_json::Value::Array(_)) => serde_json::from_value(value)?,
+            Some(value) => vec![serde_json::from_value(value)?],
+        };
+        handlers.extend(config.output_handlers);
+
+        let mut handlers = handlers.into_iter();
+        Ok(Self {
+            test_reaction_id: config.test_reaction_id,
+            output_handler: handlers.next(),
+            output_handlers: handlers.collect(),
+            stop_triggers: config.stop_triggers,
+            expected_results: config.expected_results,
+        })
+    }
+}
+
+// The expected results are either listed inline in results, or loaded from file, a JSON
+// array or JSON Lines file in the test folder. Invocations are matched to expected results by
+// the values at key_paths, which are JSON pointers into the invocation body. The
+// normalizations are applied to both the expected results and the invocations beforehand, so
+// e.g. timestamps recorded with different precisions still match.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ExpectedResultsDefinition {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub key_paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub normalizations: Vec<FieldNormalization>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub results: Option<Vec<serde_json::Value>>,
+}
+
+// The precision that timestamps are truncated to before they are compared.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+// How a field is normalized before it is compared. Numeric timestamps may be epoch seconds,
+// milliseconds, microseconds or nanoseconds (the unit is inferred from the magnitude) and
+// string timestamps must be RFC 3339; both are normalized to a count of precision units since
+// the epoch, so a ns timestamp and a ms timestamp for the same instant compare equal. Floats
+// are rounded to the given number of decimal places.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind")]
+pub enum PrecisionNormalization {
+    Timestamp { precision: TimestampPrecision },
+    Float { decimals: u32 },
+}
+
+// The normalization applied to the fields at a path before they are compared. The field is a
+// dot separated path into the JSON value, e.g. "after.properties.ts", where a "*" segment
+// matches every key of an object or every element of an array.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FieldNormalization {
+    pub field: String,
+    #[serde(flatten)]
+    pub precision: PrecisionNormalization,
+}
+
+impl TestReactionDefinition {
+    // All of the reaction's handlers: the output_handler, if any, followed by the
+    // output_handlers.
+    pub fn handler_definitions(&self) -> Vec<ReactionHandlerDefinition> {
+        self.output_handler
+            .iter()
+            .chain(self.output_handlers.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum ResultStreamHandlerDefinition {
     DaprPubSub(DaprPubSubResultStreamHandlerDefinition),
     RedisStream(RedisStreamResultStreamHandlerDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DaprPubSubResultStreamHandlerDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
@@ -470,7 +1185,7 @@ pub struct DaprPubSubResultStreamHandlerDefinition {
     pub pubsub_topic: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RedisStreamResultStreamHandlerDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
@@ -478,24 +1193,39 @@ pub struct RedisStreamResultStreamHandlerDefinition {
     pub process_old_entries: Option<bool>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum StopTriggerDefinition {
     RecordSequenceNumber(RecordSequenceNumberStopTriggerDefinition),
     RecordCount(RecordCountStopTriggerDefinition),
+    MaxDuration(MaxDurationStopTriggerDefinition),
+    ThroughputBelow(ThroughputBelowStopTriggerDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RecordSequenceNumberStopTriggerDefinition {
     pub record_sequence_number: i64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct RecordCountStopTriggerDefinition {
     pub record_count: u64,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MaxDurationStopTriggerDefinition {
+    pub seconds: u64,
+}
+
+// Fires once the invocation rate has stayed below events_per_sec for for_seconds, measured
+// after the first result arrives. Only supported for reactions.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ThroughputBelowStopTriggerDefinition {
+    pub events_per_sec: f64,
+    pub for_seconds: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum ReactionHandlerDefinition {
     Http(HttpReactionHandlerDefinition),
@@ -503,58 +1233,104 @@ pub enum ReactionHandlerDefinition {
     Grpc(GrpcReactionHandlerDefinition),
     DrasiServerCallback(DrasiServerCallbackReactionHandlerDefinition),
     DrasiServerChannel(DrasiServerChannelReactionHandlerDefinition),
+    GraphQl(GraphQlReactionHandlerDefinition),
+    RedisStream(RedisStreamReactionHandlerDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct HttpReactionHandlerDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
     pub path: Option<String>,
     pub correlation_header: Option<String>,
+    // If set, each request is relayed to this URL after it is recorded and the downstream
+    // response is returned to the caller.
+    #[serde(default)]
+    pub forward_to: Option<String>,
+    // If true, requests are rejected with 503 and a Retry-After header when the handler can't
+    // keep up, instead of waiting for room to record them.
+    #[serde(default)]
+    pub backpressure: bool,
+    // JSON pointer to a field in each result that holds its reaction type, e.g. "/op". Short
+    // codes such as "i", "u" and "d" are mapped to "added", "updated" and "deleted". Results
+    // without the field fall back to the type being inferred from the request.
+    #[serde(default)]
+    pub reaction_type_field: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GraphQlReactionHandlerDefinition {
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub path: Option<String>,
+    // Maps the name of each mutation field to the reaction type ("added", "updated" or
+    // "deleted") it is recorded as, e.g. {"addRoom": "added"}.
+    #[serde(default)]
+    pub mutation_types: HashMap<String, String>,
+}
+
+// Reads reaction output from a Redis Stream through a consumer group. The group is
+// created at the start of the stream if it doesn't exist yet.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RedisStreamReactionHandlerDefinition {
+    pub url: Option<String>,
+    pub stream_key: String,
+    pub group: Option<String>,
+    pub consumer_name: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct EventGridReactionHandlerDefinition {
     pub endpoint: Option<String>,
-    pub access_key: Option<String>,
+    pub access_key: Option<Secret<String>>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct GrpcReactionHandlerDefinition {
     pub host: Option<String>,
     pub port: Option<u16>,
     pub correlation_metadata_key: Option<String>,
     pub query_ids: Vec<String>,              // Query IDs to subscribe to
     pub include_initial_state: Option<bool>, // Whether to receive initial state
+    pub tls: Option<GrpcReactionHandlerTlsDefinition>,
+}
+
+// PEM files used to serve the gRPC reaction handler over TLS. If client_ca_path is set,
+// clients must present a certificate signed by that CA (mTLS).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GrpcReactionHandlerTlsDefinition {
+    pub cert_path: String,
+    pub key_path: String,
+    pub client_ca_path: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerCallbackReactionHandlerDefinition {
     pub drasi_server_id: String,
     pub reaction_id: String,
     pub callback_type: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerChannelReactionHandlerDefinition {
     pub drasi_server_id: String,
     pub reaction_id: String,
     pub buffer_size: Option<usize>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum OutputLoggerDefinition {
     Console(ConsoleOutputLoggerDefinition),
     JsonlFile(JsonlFileOutputLoggerDefinition),
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct ConsoleOutputLoggerDefinition {
     pub date_time_format: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct JsonlFileOutputLoggerDefinition {
     pub max_lines_per_file: Option<u64>,
 }
@@ -598,7 +1374,7 @@ impl TryFrom<&str> for QueryId {
 }
 
 /// Test definition for a Drasi Server stored in test repositories
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TestDrasiServerDefinition {
     /// Unique identifier for the server
     pub id: String,
@@ -615,7 +1391,7 @@ pub struct TestDrasiServerDefinition {
 }
 
 /// Runtime configuration for a Drasi Server instance
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerConfig {
     /// Runtime configuration (thread pool size, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -651,7 +1427,7 @@ pub struct DrasiServerConfig {
 }
 
 /// Runtime configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DrasiServerRuntimeConfig {
     /// Number of worker threads
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -671,7 +1447,7 @@ pub struct DrasiServerRuntimeConfig {
 }
 
 /// Storage backend configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum DrasiServerStorageConfig {
     /// In-memory storage (default for tests)
@@ -710,7 +1486,7 @@ fn default_true() -> bool {
 }
 
 /// Authentication configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type")]
 pub enum DrasiServerAuthConfig {
     /// No authentication (default for tests)
@@ -724,14 +1500,14 @@ pub enum DrasiServerAuthConfig {
         username: String,
 
         /// Password
-        password: String,
+        password: Secret<String>,
     },
 
     /// Token-based authentication
     #[serde(rename = "token")]
     Token {
         /// Static token value
-        token: String,
+        token: Secret<String>,
     },
 
     /// OAuth2 authentication
@@ -745,7 +1521,7 @@ pub enum DrasiServerAuthConfig {
 
         /// Client secret
         #[serde(skip_serializing_if = "Option::is_none")]
-        client_secret: Option<String>,
+        client_secret: Option<Secret<String>>,
 
         /// Required scopes
         #[serde(default)]
@@ -754,7 +1530,7 @@ pub enum DrasiServerAuthConfig {
 }
 
 /// Source configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DrasiSourceConfig {
     /// Unique identifier for the source
     pub id: String,
@@ -772,7 +1548,7 @@ pub struct DrasiSourceConfig {
 }
 
 /// Query configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DrasiQueryConfig {
     /// Unique identifier for the query
     pub id: String,
@@ -793,7 +1569,7 @@ pub struct DrasiQueryConfig {
 }
 
 /// Reaction configuration for Drasi Server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DrasiReactionConfig {
     /// Unique identifier for the reaction
     pub id: String,
@@ -868,6 +1644,27 @@ mod tests {
                 assert_eq!(definition.common.time_mode, TimeMode::Recorded);
                 assert_eq!(definition.script_file_folder, "bootstrap_data_scripts");
             }
+            _ => panic!("Expected ScriptBootstrapDataGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_file_bootstrap_data_generator() {
+        let content = r#"
+        {
+            "kind": "File",
+            "file": "bootstrap/data.json"
+        }
+        "#;
+        let bootstrap_data_generator: BootstrapDataGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match bootstrap_data_generator {
+            BootstrapDataGeneratorDefinition::File(definition) => {
+                assert_eq!(definition.common.time_mode, TimeMode::default());
+                assert_eq!(definition.file, "bootstrap/data.json");
+            }
+            _ => panic!("Expected FileBootstrapDataGeneratorDefinition"),
         }
     }
 
@@ -896,6 +1693,197 @@ mod tests {
                 assert_eq!(definition.common.time_mode, TimeMode::Recorded);
                 assert_eq!(definition.script_file_folder, "source_change_scripts");
             }
+            _ => panic!("Expected ScriptSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_merged_script_source_change_generator() {
+        let content = r#"
+        {
+            "kind": "MergedScript",
+            "script_file_folders": ["orders_scripts", "payments_scripts"],
+            "time_mode": "recorded"
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        assert_eq!(
+            source_change_generator.script_file_folders(),
+            vec!["orders_scripts", "payments_scripts"]
+        );
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::MergedScript(definition) => {
+                assert_eq!(definition.common.time_mode, TimeMode::Recorded);
+                assert!(!definition.ignore_scripted_pause_commands);
+            }
+            _ => panic!("Expected MergedScriptSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_csv_file_source_change_generator() {
+        let content = r#"
+        {
+            "kind": "CsvFile",
+            "csv_file": "changes/rooms.csv",
+            "key_column": "room_id",
+            "timestamp_column": "changed_at",
+            "timestamp_unit": "ms",
+            "spacing_mode": "recorded"
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::CsvFile(definition) => {
+                assert_eq!(definition.csv_file, "changes/rooms.csv");
+                assert_eq!(definition.key_column, "room_id");
+                assert_eq!(definition.timestamp_column, Some("changed_at".to_string()));
+                assert_eq!(definition.timestamp_unit, CsvTimestampUnit::Milliseconds);
+                assert_eq!(definition.op_column, None);
+                assert_eq!(definition.common.spacing_mode, SpacingMode::Recorded);
+            }
+            _ => panic!("Expected CsvFileSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_debezium_file_source_change_generator() {
+        let content = r#"
+        {
+            "kind": "DebeziumFile",
+            "debezium_file": "changes/rooms.jsonl",
+            "key_field": "room_id",
+            "time_mode": "recorded"
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::DebeziumFile(definition) => {
+                assert_eq!(definition.debezium_file, "changes/rooms.jsonl");
+                assert_eq!(definition.key_field, Some("room_id".to_string()));
+                assert_eq!(definition.label, None);
+                assert_eq!(definition.common.time_mode, TimeMode::Recorded);
+            }
+            _ => panic!("Expected DebeziumFileSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_http_poll_source_change_generator() {
+        let content = r#"
+        {
+            "kind": "HttpPoll",
+            "url": "http://localhost:8080/api/rooms",
+            "key_field": "room_id",
+            "headers": { "Authorization": "Bearer token" },
+            "items_path": "data.items",
+            "poll_interval_ms": 5000
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::HttpPoll(definition) => {
+                assert_eq!(definition.url, "http://localhost:8080/api/rooms");
+                assert_eq!(definition.key_field, "room_id");
+                assert_eq!(
+                    definition.headers.get("Authorization"),
+                    Some(&"Bearer token".into())
+                );
+                assert_eq!(definition.items_path, Some("data.items".to_string()));
+                assert_eq!(definition.poll_interval_ms, Some(5000));
+                assert_eq!(definition.label, None);
+                assert_eq!(definition.common.time_mode, TimeMode::Recorded);
+            }
+            _ => panic!("Expected HttpPollSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_log_tail_source_change_generator() {
+        let content = r#"
+        {
+            "kind": "LogTail",
+            "log_file": "logs/changes.jsonl",
+            "change_count": 100
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::LogTail(definition) => {
+                assert_eq!(definition.log_file, "logs/changes.jsonl");
+                assert_eq!(definition.change_count, Some(100));
+                assert_eq!(definition.poll_interval_ms, None);
+                assert_eq!(definition.time_mode, TimeMode::Live);
+            }
+            _ => panic!("Expected LogTailSourceChangeGeneratorDefinition"),
+        }
+    }
+
+    #[test]
+    fn test_read_source_change_generator_max_eps() {
+        let content = r#"
+        {
+            "kind": "Script",
+            "script_file_folder": "source_change_scripts",
+            "script_file_list": ["change01.jsonl"],
+            "max_eps": 500
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::Script(definition) => {
+                assert_eq!(definition.common.max_eps, NonZeroU32::new(500));
+                assert_eq!(definition.common.spacing_mode, SpacingMode::Recorded);
+            }
+            _ => panic!("Expected ScriptSourceChangeGeneratorDefinition"),
+        }
+
+        // A zero cap is rejected rather than silently blocking all dispatch.
+        let content = r#"
+        {
+            "kind": "Script",
+            "script_file_folder": "source_change_scripts",
+            "script_file_list": ["change01.jsonl"],
+            "max_eps": 0
+        }
+        "#;
+        assert!(serde_json::from_str::<SourceChangeGeneratorDefinition>(content).is_err());
+    }
+
+    #[test]
+    fn test_read_source_change_generator_event_schema() {
+        let content = r#"
+        {
+            "kind": "Script",
+            "script_file_folder": "source_change_scripts",
+            "event_schema": {
+                "schema": { "type": "object", "required": ["id"] },
+                "halt_on_violation": true
+            }
+        }
+        "#;
+        let source_change_generator: SourceChangeGeneratorDefinition =
+            serde_json::from_str(content).unwrap();
+
+        match source_change_generator {
+            SourceChangeGeneratorDefinition::Script(definition) => {
+                let event_schema = definition.common.event_schema.unwrap();
+                assert_eq!(event_schema.schema["required"][0], "id");
+                assert!(event_schema.halt_on_violation);
+            }
+            _ => panic!("Expected ScriptSourceChangeGeneratorDefinition"),
         }
     }
 
@@ -931,6 +1919,7 @@ mod tests {
                         assert_eq!(definition.common.time_mode, TimeMode::Live);
                         assert_eq!(definition.script_file_folder, "bootstrap_data_scripts");
                     }
+                    _ => panic!("Expected ScriptBootstrapDataGeneratorDefinition"),
                 }
 
                 match source.source_change_generator.as_ref().unwrap() {
@@ -939,6 +1928,7 @@ mod tests {
                         assert_eq!(definition.common.time_mode, TimeMode::Live);
                         assert_eq!(definition.script_file_folder, "source_change_scripts");
                     }
+                    _ => panic!("Expected ScriptSourceChangeGeneratorDefinition"),
                 }
             }
             _ => panic!("Expected ScriptTestSourceDefinition"),
@@ -1033,6 +2023,7 @@ mod tests {
                         assert_eq!(definition.common.time_mode, TimeMode::Live);
                         assert_eq!(definition.script_file_folder, "bootstrap_data_scripts");
                     }
+                    _ => panic!("Expected ScriptBootstrapDataGeneratorDefinition"),
                 }
 
                 match source.source_change_generator.as_ref().unwrap() {
@@ -1041,6 +2032,7 @@ mod tests {
                         assert_eq!(definition.common.time_mode, TimeMode::Live);
                         assert_eq!(definition.script_file_folder, "source_change_scripts");
                     }
+                    _ => panic!("Expected ScriptSourceChangeGeneratorDefinition"),
                 }
             }
             _ => panic!("Expected ScriptTestSourceDefinition"),
@@ -1160,6 +2152,129 @@ mod tests {
         assert!(reaction.stop_triggers.is_none());
     }
 
+    #[test]
+    fn test_read_reaction_with_multiple_handlers() {
+        let reaction: TestReactionDefinition = serde_json::from_str(
+            r#"{
+                "test_reaction_id": "reaction1",
+                "output_handler": { "kind": "Http", "port": 8080 },
+                "output_handlers": [
+                    { "kind": "Grpc", "port": 50052, "query_ids": [] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let handlers = reaction.handler_definitions();
+        assert_eq!(handlers.len(), 2);
+        assert!(matches!(handlers[0], ReactionHandlerDefinition::Http(_)));
+        assert!(matches!(handlers[1], ReactionHandlerDefinition::Grpc(_)));
+
+        // A reaction with a single handler has no output_handlers.
+        let reaction: TestReactionDefinition = serde_json::from_str(
+            r#"{ "test_reaction_id": "reaction2", "output_handler": { "kind": "Http" } }"#,
+        )
+        .unwrap();
+        assert!(reaction.output_handlers.is_empty());
+        assert_eq!(reaction.handler_definitions().len(), 1);
+    }
+
+    #[test]
+    fn test_read_reaction_with_output_handler_list() {
+        let reaction: TestReactionDefinition = serde_json::from_str(
+            r#"{
+                "test_reaction_id": "reaction1",
+                "output_handler": [
+                    { "kind": "Http", "port": 8080 },
+                    { "kind": "Grpc", "port": 50052, "query_ids": [] }
+                ],
+                "output_handlers": [{ "kind": "Http", "port": 8081 }]
+            }"#,
+        )
+        .unwrap();
+
+        let handlers = reaction.handler_definitions();
+        assert_eq!(handlers.len(), 3);
+        assert!(matches!(handlers[0], ReactionHandlerDefinition::Http(_)));
+        assert!(matches!(handlers[1], ReactionHandlerDefinition::Grpc(_)));
+        assert!(matches!(handlers[2], ReactionHandlerDefinition::Http(_)));
+
+        // A round trip writes the single and additional handlers separately.
+        let json = serde_json::to_value(&reaction).unwrap();
+        assert!(json["output_handler"].is_object());
+        assert_eq!(json["output_handlers"].as_array().unwrap().len(), 2);
+        let reread: TestReactionDefinition = serde_json::from_value(json).unwrap();
+        assert_eq!(reread, reaction);
+
+        // A bad handler in the list is still reported.
+        let result: Result<TestReactionDefinition, _> = serde_json::from_str(
+            r#"{ "test_reaction_id": "reaction2", "output_handler": [{ "kind": "Smoke" }] }"#,
+        );
+        assert!(result.is_err());
+    }
+
+    fn drasi_server_test_definition() -> serde_json::Value {
+        serde_json::json!({
+            "version": 1,
+            "description": null,
+            "test_folder": null,
+            "drasi_servers": [{
+                "id": "server-1",
+                "name": "Server 1",
+                "config": {
+                    "sources": [{ "id": "facilities-db", "source_type": "internal.application" }],
+                    "queries": [{ "id": "all-rooms", "query": "MATCH (r:Room) RETURN r", "sources": ["facilities-db"] }],
+                    "reactions": [{ "id": "alerts", "reaction_type": "internal.application", "queries": ["all-rooms"] }]
+                }
+            }],
+            "reactions": [{
+                "test_reaction_id": "reaction-1",
+                "output_handler": { "kind": "DrasiServerChannel", "drasi_server_id": "server-1", "reaction_id": "alerts" }
+            }],
+            "sources": [
+                { "kind": "Script", "test_source_id": "source-1" },
+                { "kind": "Model", "test_source_id": "source-2" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_validate_test_definition_duplicate_source_ids() {
+        let mut content = drasi_server_test_definition();
+        let test_definition: TestDefinition = serde_json::from_value(content.clone()).unwrap();
+        assert_eq!(test_definition.validate(), Vec::new());
+
+        content["sources"][1]["test_source_id"] = serde_json::json!("source-1");
+        let test_definition: TestDefinition = serde_json::from_value(content).unwrap();
+        assert_eq!(
+            test_definition.validate(),
+            vec![TestDefinitionIssue::new(
+                "sources[1].test_source_id",
+                "Duplicate ID \"source-1\""
+            )]
+        );
+    }
+
+    #[test]
+    fn test_validate_test_definition_dangling_query_source() {
+        let mut content = drasi_server_test_definition();
+        content["drasi_servers"][0]["config"]["queries"][0]["sources"] =
+            serde_json::json!(["facilities-db", "hr-db"]);
+        content["reactions"][0]["output_handler"]["reaction_id"] = serde_json::json!("missing");
+        let test_definition: TestDefinition = serde_json::from_value(content).unwrap();
+
+        let issues = test_definition.validate();
+        let paths: Vec<&str> = issues.iter().map(|issue| issue.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "drasi_servers[0].config.queries[0].sources[1]",
+                "reactions[0].output_handler.reaction_id"
+            ]
+        );
+        assert!(issues[0].message.contains("\"hr-db\""));
+    }
+
     #[test]
     fn test_parse_actual_config_file() {
         // Test parsing the actual config file structure
@@ -1274,6 +2389,16 @@ mod tests {
             "1000".parse::<SpacingMode>().unwrap(),
             SpacingMode::Rate(NonZeroU32::new(1000).unwrap())
         );
+        assert_eq!(
+            "Burst:10:500000000".parse::<SpacingMode>().unwrap(),
+            SpacingMode::Burst {
+                burst_size: NonZeroU32::new(10).unwrap(),
+                burst_interval_ns: 500_000_000
+            }
+        );
+        assert!("burst:0:500000000".parse::<SpacingMode>().is_err());
+        assert!("burst:10".parse::<SpacingMode>().is_err());
+        assert!("burst:10:-1".parse::<SpacingMode>().is_err());
     }
 
     #[test]
@@ -1284,6 +2409,14 @@ mod tests {
             SpacingMode::Rate(NonZeroU32::new(1000).unwrap()).to_string(),
             "1000"
         );
+        assert_eq!(
+            SpacingMode::Burst {
+                burst_size: NonZeroU32::new(10).unwrap(),
+                burst_interval_ns: 500_000_000
+            }
+            .to_string(),
+            "burst:10:500000000"
+        );
     }
 
     #[test]
@@ -1346,5 +2479,28 @@ mod tests {
             .unwrap() as u64;
         let time_mode: TimeMode = serde_json::from_str(json).unwrap();
         assert_eq!(time_mode, TimeMode::Rebased(parsed_time));
+
+        // Raw nanoseconds are accepted as a number or a string.
+        let time_mode: TimeMode = serde_json::from_str("1631628720000000000").unwrap();
+        assert_eq!(time_mode, TimeMode::Rebased(parsed_time));
+        let time_mode: TimeMode = serde_json::from_str(r#""1631628720000000000""#).unwrap();
+        assert_eq!(time_mode, TimeMode::Rebased(parsed_time));
+
+        // The serialized form round trips.
+        let json = serde_json::to_string(&TimeMode::Rebased(parsed_time)).unwrap();
+        let time_mode: TimeMode = serde_json::from_str(&json).unwrap();
+        assert_eq!(time_mode, TimeMode::Rebased(parsed_time));
+    }
+
+    #[test]
+    fn test_time_mode_deserialize_errors() {
+        let error = serde_json::from_str::<TimeMode>(r#""2021-09-14 14:12""#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("RFC3339"), "{}", error);
+
+        assert!(serde_json::from_str::<TimeMode>(r#""1969-12-31T00:00:00Z""#).is_err());
+        assert!(serde_json::from_str::<TimeMode>("-1").is_err());
+        assert!(serde_json::from_str::<TimeMode>("1.5").is_err());
     }
 }
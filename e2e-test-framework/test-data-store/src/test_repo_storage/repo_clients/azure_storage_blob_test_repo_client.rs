@@ -12,27 +12,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use async_trait::async_trait;
-use azure_storage::prelude::*;
+use azure_core::{error::ErrorKind, RetryOptions, StatusCode};
+use azure_identity::{DefaultAzureCredential, TokenCredentialOptions};
+use azure_storage::{prelude::*, CloudLocation};
 use azure_storage_blobs::container::operations::BlobItem;
 use azure_storage_blobs::prelude::*;
 use futures::stream::StreamExt;
+use serde::Deserialize;
 use tokio::{fs::File, io::AsyncWriteExt};
 
 use crate::test_repo_storage::models::{
     BootstrapDataGeneratorDefinition, SourceChangeGeneratorDefinition, TestSourceDefinition,
 };
 
-use super::{AzureStorageBlobTestRepoConfig, CommonTestRepoConfig, RemoteTestRepoClient};
+use super::{
+    decompress_gz_files, AzureStorageBlobTestRepoConfig, CommonTestRepoConfig, RemoteTestRepoClient,
+};
+
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+// How downloads from the repo are retried after transient failures.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            base_delay: RETRY_BASE_DELAY,
+            max_delay: RETRY_MAX_DELAY,
+        }
+    }
+
+    // Exponential backoff with jitter, so clients that failed together don't retry together.
+    fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        delay / 2 + delay.mul_f64(rand::random::<f64>() / 2.0)
+    }
+}
 
 #[derive(Debug)]
 pub struct AzureStorageBlobTestRepoClientSettings {
     pub force_cache_refresh: bool,
-    pub storage_account_name: String,
+    pub retry_policy: RetryPolicy,
     pub storage_container: String,
     pub storage_credentials: StorageCredentials,
+    pub storage_location: CloudLocation,
     pub storage_root_path: String,
     pub test_repo_id: String,
 }
@@ -42,23 +84,81 @@ impl AzureStorageBlobTestRepoClientSettings {
         common_config: CommonTestRepoConfig,
         unique_config: AzureStorageBlobTestRepoConfig,
     ) -> anyhow::Result<Self> {
-        // Create storage credentials from the account name and access key.
-        let storage_credentials = StorageCredentials::access_key(
-            unique_config.account_name.clone(),
-            unique_config.access_key.clone(),
-        );
+        let storage_credentials = create_storage_credentials(&unique_config).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to create credentials for test repo {}: {}",
+                common_config.id,
+                e
+            )
+        })?;
 
         Ok(Self {
             force_cache_refresh: unique_config.force_cache_refresh,
-            storage_account_name: unique_config.account_name.clone(),
+            retry_policy: RetryPolicy::new(unique_config.max_retries),
             storage_container: unique_config.container.clone(),
             storage_credentials,
+            storage_location: CloudLocation::Public {
+                account: unique_config.account_name.clone(),
+            },
             storage_root_path: unique_config.root_path,
             test_repo_id: common_config.id.clone(),
         })
     }
 }
 
+// The contents of an AzureStorageBlobTestRepoConfig credential_file.
+#[derive(Deserialize)]
+struct AzureStorageCredentialFile {
+    account_name: Option<String>,
+    access_key: String,
+}
+
+// Uses the first credential source that is configured: the access key, then the credential
+// file, then managed identity.
+fn create_storage_credentials(
+    config: &AzureStorageBlobTestRepoConfig,
+) -> anyhow::Result<StorageCredentials> {
+    if let Some(access_key) = &config.access_key {
+        return Ok(StorageCredentials::access_key(
+            config.account_name.clone(),
+            access_key.resolve()?,
+        ));
+    }
+
+    if let Some(path) = &config.credential_file {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read credential file {:?}: {}", path, e))?;
+        let file: AzureStorageCredentialFile = serde_json::from_str(&contents)
+            .map_err(|e| anyhow::anyhow!("Invalid credential file {:?}: {}", path, e))?;
+
+        if let Some(account_name) = &file.account_name {
+            if account_name != &config.account_name {
+                anyhow::bail!(
+                    "Credential file {:?} is for account {}, not {}",
+                    path,
+                    account_name,
+                    config.account_name
+                );
+            }
+        }
+
+        return Ok(StorageCredentials::access_key(
+            config.account_name.clone(),
+            file.access_key,
+        ));
+    }
+
+    if config.use_managed_identity {
+        let credential = DefaultAzureCredential::create(TokenCredentialOptions::default())
+            .map_err(|e| anyhow::anyhow!("Failed to create DefaultAzureCredential: {}", e))?;
+        return Ok(StorageCredentials::token_credential(Arc::new(credential)));
+    }
+
+    anyhow::bail!(
+        "No credentials configured; set access_key, credential_file or use_managed_identity"
+    )
+}
+
 #[derive(Debug)]
 pub struct AzureStorageBlobTestRepoClient {
     pub settings: AzureStorageBlobTestRepoClientSettings,
@@ -83,10 +183,13 @@ impl AzureStorageBlobTestRepoClient {
     }
 
     fn create_container_client(&self) -> anyhow::Result<ContainerClient> {
-        let container_client = ClientBuilder::new(
-            self.settings.storage_account_name.clone(),
+        // Retries are handled by with_retries, which restarts a download from the beginning,
+        // so the SDK's own retries are turned off.
+        let container_client = ClientBuilder::with_location(
+            self.settings.storage_location.clone(),
             self.settings.storage_credentials.clone(),
         )
+        .retry(RetryOptions::none())
         .container_client(self.settings.storage_container.clone());
 
         Ok(container_client)
@@ -103,9 +206,13 @@ impl AzureStorageBlobTestRepoClient {
             local_folder
         );
 
-        let mut file_path_list =
-            download_test_repo_folder(self.create_container_client()?, local_folder, repo_folder)
-                .await?;
+        let mut file_path_list = download_test_repo_folder(
+            self.create_container_client()?,
+            local_folder,
+            repo_folder,
+            self.settings.retry_policy,
+        )
+        .await?;
         log::trace!("Bootstrap Script Files: {:?}", file_path_list);
 
         // Sort the list of files by the file name to get them in the correct order for processing.
@@ -147,9 +254,13 @@ impl AzureStorageBlobTestRepoClient {
             local_folder
         );
 
-        let mut file_path_list =
-            download_test_repo_folder(self.create_container_client()?, local_folder, repo_folder)
-                .await?;
+        let mut file_path_list = download_test_repo_folder(
+            self.create_container_client()?,
+            local_folder,
+            repo_folder,
+            self.settings.retry_policy,
+        )
+        .await?;
         log::trace!("Change Scripts Files: {:?}", file_path_list);
 
         // Sort the list of files by the file name to get them in the correct order for processing.
@@ -188,6 +299,7 @@ impl RemoteTestRepoClient for AzureStorageBlobTestRepoClient {
         download_test_repo_file(
             self.create_container_client()?.blob_client(&remote_path),
             test_def_path,
+            self.settings.retry_policy,
         )
         .await?;
 
@@ -224,37 +336,183 @@ impl RemoteTestRepoClient for AzureStorageBlobTestRepoClient {
                     .await?;
             }
 
+            // Bootstrap Data File
+            if let Some(BootstrapDataGeneratorDefinition::File(bs_def)) =
+                &def.bootstrap_data_generator
+            {
+                let repo_path = format!(
+                    "{}/{}/sources/{}/{}",
+                    self.settings.storage_root_path,
+                    test_data_folder,
+                    def.common.test_source_id,
+                    &bs_def.file
+                );
+                let local_path = test_source_data_path.join(&bs_def.file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.create_container_client()?.blob_client(&repo_path),
+                    local_path,
+                    self.settings.retry_policy,
+                )
+                .await?;
+            }
+
             // Source Change Script Files
-            if let Some(SourceChangeGeneratorDefinition::Script(sc_def)) =
+            if let Some(sc_def) = &def.source_change_generator {
+                for script_file_folder in sc_def.script_file_folders() {
+                    let repo_path = format!(
+                        "{}/{}/sources/{}/{}/",
+                        self.settings.storage_root_path,
+                        test_data_folder,
+                        def.common.test_source_id,
+                        script_file_folder
+                    );
+                    let local_path = test_source_data_path.join(script_file_folder);
+                    self.download_change_script_files(repo_path, local_path)
+                        .await?;
+                }
+            }
+
+            // Source Change CSV File
+            if let Some(SourceChangeGeneratorDefinition::CsvFile(csv_def)) =
                 &def.source_change_generator
             {
-                // TODO: Currently we only have a single folder to download. In the future we might have a list of files.
                 let repo_path = format!(
-                    "{}/{}/sources/{}/{}/",
+                    "{}/{}/sources/{}/{}",
                     self.settings.storage_root_path,
                     test_data_folder,
                     def.common.test_source_id,
-                    &sc_def.script_file_folder
+                    &csv_def.csv_file
                 );
-                let local_path = test_source_data_path.join(&sc_def.script_file_folder);
-                self.download_change_script_files(repo_path, local_path)
-                    .await?;
+                let local_path = test_source_data_path.join(&csv_def.csv_file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.create_container_client()?.blob_client(&repo_path),
+                    local_path,
+                    self.settings.retry_policy,
+                )
+                .await?;
+            }
+
+            // Source Change Debezium File
+            if let Some(SourceChangeGeneratorDefinition::DebeziumFile(debezium_def)) =
+                &def.source_change_generator
+            {
+                let repo_path = format!(
+                    "{}/{}/sources/{}/{}",
+                    self.settings.storage_root_path,
+                    test_data_folder,
+                    def.common.test_source_id,
+                    &debezium_def.debezium_file
+                );
+                let local_path = test_source_data_path.join(&debezium_def.debezium_file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.create_container_client()?.blob_client(&repo_path),
+                    local_path,
+                    self.settings.retry_policy,
+                )
+                .await?;
             }
         }
 
+        decompress_gz_files(test_source_data_path).await?;
+
         Ok(())
     }
 }
 
+// Transient failures are retried: I/O errors such as timeouts and dropped connections,
+// throttling (408 and 429) and server errors (5xx). Anything else, e.g. a 404 for a missing
+// blob, fails straight away.
+fn is_retryable(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<azure_core::Error>().map(|e| e.kind()) {
+        Some(ErrorKind::HttpResponse { status, .. }) => {
+            *status == StatusCode::RequestTimeout
+                || *status == StatusCode::TooManyRequests
+                || status.is_server_error()
+        }
+        Some(ErrorKind::Io) => true,
+        _ => false,
+    }
+}
+
+// Runs the operation, running it again after a backoff each time it fails with a retryable
+// error, up to the policy's max_retries.
+async fn with_retries<T, F, Fut>(
+    policy: RetryPolicy,
+    description: &str,
+    mut operation: F,
+) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < policy.max_retries && is_retryable(&e) => {
+                let delay = policy.delay(attempt);
+                attempt += 1;
+                log::warn!(
+                    "Failed to {} on attempt {} of {}, retrying in {:?}: {}",
+                    description,
+                    attempt,
+                    policy.max_retries + 1,
+                    delay,
+                    e
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn list_test_repo_folder(
+    container_client: &ContainerClient,
+    remote_repo_folder: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut stream = container_client
+        .list_blobs()
+        .prefix(remote_repo_folder.to_string())
+        .into_stream();
+
+    let mut blob_names = vec![];
+    while let Some(result) = stream.next().await {
+        let blob_list = result?;
+        for blob_item in blob_list.blobs.items {
+            match blob_item {
+                BlobItem::Blob(blob) => blob_names.push(blob.name),
+                BlobItem::BlobPrefix(prefix) => {
+                    log::trace!("Ignoring Blob Prefix: {:?}", prefix.name);
+                }
+            }
+        }
+    }
+
+    Ok(blob_names)
+}
+
 async fn download_test_repo_folder(
     container_client: ContainerClient,
     local_repo_folder: PathBuf,
     remote_repo_folder: String,
+    retry_policy: RetryPolicy,
 ) -> anyhow::Result<Vec<PathBuf>> {
-    let mut stream = container_client
-        .list_blobs()
-        .prefix(remote_repo_folder.clone())
-        .into_stream();
+    let blob_names = with_retries(
+        retry_policy,
+        &format!("list blobs in {}", remote_repo_folder),
+        || list_test_repo_folder(&container_client, &remote_repo_folder),
+    )
+    .await?;
 
     // Create the local folder if it doesn't exist.
     if !local_repo_folder.exists() {
@@ -269,54 +527,46 @@ async fn download_test_repo_folder(
     // Vector of local file paths being downloaded.
     let mut local_file_paths = vec![];
 
-    while let Some(result) = stream.next().await {
-        let blob_list = result?;
-        for blob_item in blob_list.blobs.items {
-            match blob_item {
-                BlobItem::Blob(blob) => {
-                    let blob_name = blob.name;
-
-                    // Create the local file path for the blob.
-                    let stripped_blob_file_name =
-                        blob_name.strip_prefix(&remote_repo_folder).unwrap();
-                    let local_file_path = local_repo_folder.clone().join(stripped_blob_file_name);
-
-                    // Process the blob as a directory if it doesn't have an extension.
-                    match local_file_path.extension() {
-                        Some(ext) if ext == "jsonl" => {
-                            // Add the local file path to the list of files being downloaded.
-                            local_file_paths.push(local_file_path.clone());
-
-                            let task = tokio::spawn(download_test_repo_file(
-                                container_client.blob_client(&blob_name),
-                                local_file_path,
-                            ));
-
-                            tasks.push(task);
-                        }
-                        None => {
-                            log::trace!("Creating directory: {:?}", local_file_path);
-                            tokio::fs::create_dir_all(local_file_path).await?;
-                        }
-                        _ => {}
-                    }
-                }
-                BlobItem::BlobPrefix(prefix) => {
-                    log::trace!("Ignoring Blob Prefix: {:?}", prefix.name);
-                }
+    for blob_name in blob_names {
+        // Create the local file path for the blob.
+        let stripped_blob_file_name = blob_name.strip_prefix(&remote_repo_folder).unwrap();
+        let local_file_path = local_repo_folder.clone().join(stripped_blob_file_name);
+
+        // Process the blob as a directory if it doesn't have an extension.
+        match local_file_path.extension() {
+            Some(ext) if ext == "jsonl" || ext == "gz" => {
+                // Add the local file path to the list of files being downloaded.
+                local_file_paths.push(local_file_path.clone());
+
+                let task = tokio::spawn(download_test_repo_file(
+                    container_client.blob_client(&blob_name),
+                    local_file_path,
+                    retry_policy,
+                ));
+
+                tasks.push(task);
+            }
+            None => {
+                log::trace!("Creating directory: {:?}", local_file_path);
+                tokio::fs::create_dir_all(local_file_path).await?;
             }
+            _ => {}
         }
     }
 
-    match futures::future::try_join_all(tasks).await {
-        Ok(_) => Ok(local_file_paths),
-        Err(e) => Err(e.into()),
+    // Each task returns the result of its download, which must be checked as well as
+    // whether the task itself completed.
+    for result in futures::future::try_join_all(tasks).await? {
+        result?;
     }
+
+    Ok(local_file_paths)
 }
 
 async fn download_test_repo_file(
     blob_client: BlobClient,
     local_file_path: PathBuf,
+    retry_policy: RetryPolicy,
 ) -> anyhow::Result<()> {
     log::debug!(
         "Downloading  file {} to {}",
@@ -324,6 +574,16 @@ async fn download_test_repo_file(
         local_file_path.to_str().unwrap()
     );
 
+    // A retried download starts over, recreating the local file.
+    with_retries(
+        retry_policy,
+        &format!("download blob {}", blob_client.blob_name()),
+        || download_blob(&blob_client, &local_file_path),
+    )
+    .await
+}
+
+async fn download_blob(blob_client: &BlobClient, local_file_path: &Path) -> anyhow::Result<()> {
     // Create the local file to hold the blob data.
     let mut local_file = File::create(local_file_path).await?;
 
@@ -336,7 +596,7 @@ async fn download_test_repo_file(
         while let Some(value) = body.next().await {
             match value {
                 Ok(bytes) => {
-                    let _ = local_file.write_all(&bytes).await;
+                    local_file.write_all(&bytes).await?;
                 }
                 Err(e) => {
                     log::error!("Error getting blob data: {}", e);
@@ -348,3 +608,298 @@ async fn download_test_repo_file(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use crate::test_repo_storage::repo_clients::SecretRef;
+
+    use super::*;
+
+    fn test_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        }
+    }
+
+    fn config(
+        access_key: Option<&str>,
+        credential_file: Option<PathBuf>,
+    ) -> AzureStorageBlobTestRepoConfig {
+        AzureStorageBlobTestRepoConfig {
+            account_name: "test_account_name".to_string(),
+            access_key: access_key.map(SecretRef::from),
+            container: "test_container".to_string(),
+            credential_file,
+            force_cache_refresh: false,
+            max_retries: 3,
+            root_path: "test_root_path".to_string(),
+            use_managed_identity: false,
+        }
+    }
+
+    fn common_config() -> CommonTestRepoConfig {
+        CommonTestRepoConfig {
+            id: "azure_repo".to_string(),
+            local_tests: Vec::new(),
+        }
+    }
+
+    fn http_error(status: StatusCode) -> anyhow::Error {
+        azure_core::Error::message(
+            ErrorKind::HttpResponse {
+                status,
+                error_code: None,
+            },
+            "mock response",
+        )
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_failures() -> anyhow::Result<()> {
+        // A mock download whose first two requests fail with a 503 and a timeout.
+        let attempts = &AtomicU32::new(0);
+        let data = with_retries(test_policy(3), "download mock blob", || async move {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(http_error(StatusCode::ServiceUnavailable)),
+                1 => Err(azure_core::Error::message(ErrorKind::Io, "timed out").into()),
+                _ => Ok("blob data"),
+            }
+        })
+        .await?;
+
+        assert_eq!(data, "blob data");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_permanent_failures() {
+        let attempts = &AtomicU32::new(0);
+        let result: anyhow::Result<()> =
+            with_retries(test_policy(3), "download mock blob", || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(http_error(StatusCode::NotFound))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let attempts = &AtomicU32::new(0);
+        let result: anyhow::Result<()> =
+            with_retries(test_policy(2), "download mock blob", || async move {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                Err(http_error(StatusCode::TooManyRequests))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    // A response scripted for the stub blob server.
+    enum StubResponse {
+        Status(u16),
+        DropConnection,
+        Blob(&'static str),
+    }
+
+    // Starts a stub blob server that answers each request with the next scripted response,
+    // closing the connection after every request. Returns the server's uri and a count of
+    // the requests it received.
+    async fn start_stub_blob_server(
+        responses: Vec<StubResponse>,
+    ) -> anyhow::Result<(String, Arc<AtomicU32>)> {
+        use tokio::io::AsyncReadExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let uri = format!("http://{}", listener.local_addr()?);
+        let requests = Arc::new(AtomicU32::new(0));
+
+        let request_count = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+
+                // Read the request headers; the GET requests sent have no body.
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                request_count.fetch_add(1, Ordering::SeqCst);
+
+                let response = match response {
+                    StubResponse::Status(status) => format!(
+                        "HTTP/1.1 {} Stub\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                        status
+                    ),
+                    StubResponse::DropConnection => continue,
+                    StubResponse::Blob(body) => format!(
+                        "HTTP/1.1 200 OK\r\n\
+                         content-length: {}\r\n\
+                         content-type: application/octet-stream\r\n\
+                         date: Mon, 06 Jan 2025 00:00:00 GMT\r\n\
+                         etag: \"0x8DD2E2B1A2B3C4D\"\r\n\
+                         last-modified: Mon, 06 Jan 2025 00:00:00 GMT\r\n\
+                         x-ms-blob-type: BlockBlob\r\n\
+                         x-ms-creation-time: Mon, 06 Jan 2025 00:00:00 GMT\r\n\
+                         x-ms-request-id: 6f1e3d2c-0000-4000-8000-000000000001\r\n\
+                         x-ms-server-encrypted: true\r\n\
+                         connection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        Ok((uri, requests))
+    }
+
+    fn stub_client(uri: String) -> AzureStorageBlobTestRepoClient {
+        AzureStorageBlobTestRepoClient {
+            settings: AzureStorageBlobTestRepoClientSettings {
+                force_cache_refresh: false,
+                retry_policy: test_policy(3),
+                storage_container: "test_container".to_string(),
+                storage_credentials: StorageCredentials::access_key(
+                    "test_account_name",
+                    "dGVzdF9rZXk=".to_string(),
+                ),
+                storage_location: CloudLocation::Custom {
+                    account: "test_account_name".to_string(),
+                    uri,
+                },
+                storage_root_path: "test_root_path".to_string(),
+                test_repo_id: "azure_repo".to_string(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_retries_transient_responses() -> anyhow::Result<()> {
+        let (uri, requests) = start_stub_blob_server(vec![
+            StubResponse::Status(503),
+            StubResponse::Status(429),
+            StubResponse::DropConnection,
+            StubResponse::Blob("blob data"),
+        ])
+        .await?;
+        let client = stub_client(uri);
+        let dir = tempfile::tempdir()?;
+        let local_file_path = dir.path().join("blob.jsonl");
+
+        download_test_repo_file(
+            client.create_container_client()?.blob_client("blob.jsonl"),
+            local_file_path.clone(),
+            client.settings.retry_policy,
+        )
+        .await?;
+
+        assert_eq!(std::fs::read_to_string(&local_file_path)?, "blob data");
+        // One request per scripted response shows the SDK isn't retrying on its own.
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_fails_on_permanent_response() -> anyhow::Result<()> {
+        let (uri, requests) =
+            start_stub_blob_server(vec![StubResponse::Status(404), StubResponse::Blob("")]).await?;
+        let client = stub_client(uri);
+        let dir = tempfile::tempdir()?;
+
+        let result = download_test_repo_file(
+            client.create_container_client()?.blob_client("blob.jsonl"),
+            dir.path().join("blob.jsonl"),
+            client.settings.retry_policy,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(!is_retryable(&result.err().unwrap()));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy::new(3);
+        for attempt in 0..10 {
+            let full = RETRY_BASE_DELAY
+                .saturating_mul(2u32.pow(attempt))
+                .min(RETRY_MAX_DELAY);
+            let delay = policy.delay(attempt);
+            assert!(delay >= full / 2 && delay <= full);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_credentials_from_file() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("azure.json");
+        std::fs::write(
+            &path,
+            r#"{ "account_name": "test_account_name", "access_key": "dGVzdF9rZXk=" }"#,
+        )?;
+
+        let client =
+            AzureStorageBlobTestRepoClient::new(common_config(), config(None, Some(path.clone())))
+                .await;
+        assert!(client.is_ok());
+
+        // A file for another account is rejected.
+        let other_account = AzureStorageBlobTestRepoConfig {
+            account_name: "other_account".to_string(),
+            ..config(None, Some(path))
+        };
+        let err = AzureStorageBlobTestRepoClient::new(common_config(), other_account)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("is for account test_account_name"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_credentials_required() {
+        let err = AzureStorageBlobTestRepoClient::new(common_config(), config(None, None))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No credentials configured"));
+
+        let missing_file = config(None, Some(PathBuf::from("/nonexistent/azure.json")));
+        assert!(
+            AzureStorageBlobTestRepoClient::new(common_config(), missing_file)
+                .await
+                .is_err()
+        );
+
+        // The access key takes precedence, so an unreadable file isn't used.
+        let both = config(
+            Some("dGVzdF9rZXk="),
+            Some(PathBuf::from("/nonexistent/azure.json")),
+        );
+        assert!(AzureStorageBlobTestRepoClient::new(common_config(), both)
+            .await
+            .is_ok());
+    }
+}
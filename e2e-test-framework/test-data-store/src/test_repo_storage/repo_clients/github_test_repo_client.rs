@@ -19,11 +19,12 @@ use reqwest::Client;
 use serde_json::Value;
 use tokio::{fs::File, io::AsyncWriteExt};
 
-use crate::test_repo_storage::models::{
-    BootstrapDataGeneratorDefinition, SourceChangeGeneratorDefinition, TestSourceDefinition,
-};
+use crate::secret::Secret;
+use crate::test_repo_storage::models::{BootstrapDataGeneratorDefinition, TestSourceDefinition};
 
-use super::{CommonTestRepoConfig, GithubTestRepoConfig, RemoteTestRepoClient};
+use super::{
+    decompress_gz_files, CommonTestRepoConfig, GithubTestRepoConfig, RemoteTestRepoClient,
+};
 
 #[derive(Debug)]
 pub struct GithubTestRepoClientSettings {
@@ -33,7 +34,7 @@ pub struct GithubTestRepoClientSettings {
     pub branch: String,
     pub root_path: String,
     pub test_repo_id: String,
-    pub token: Option<String>,
+    pub token: Option<Secret<String>>,
 }
 
 impl GithubTestRepoClientSettings {
@@ -48,7 +49,10 @@ impl GithubTestRepoClientSettings {
             branch: unique_config.branch,
             root_path: unique_config.root_path,
             test_repo_id: common_config.id.clone(),
-            token: unique_config.token,
+            token: unique_config
+                .token
+                .map(|token| token.resolve().map(Secret::new))
+                .transpose()?,
         })
     }
 }
@@ -90,7 +94,10 @@ impl GithubTestRepoClient {
         if let Some(token) = &settings.token {
             headers.insert(
                 reqwest::header::AUTHORIZATION,
-                reqwest::header::HeaderValue::from_str(&format!("token {}", token))?,
+                reqwest::header::HeaderValue::from_str(&format!(
+                    "token {}",
+                    token.expose_secret()
+                ))?,
             );
         }
 
@@ -233,24 +240,47 @@ impl RemoteTestRepoClient for GithubTestRepoClient {
                     .await?;
             }
 
-            // Source Change Script Files
-            if let Some(SourceChangeGeneratorDefinition::Script(sc_def)) =
-                &def.source_change_generator
+            // Bootstrap Data File
+            if let Some(BootstrapDataGeneratorDefinition::File(bs_def)) =
+                &def.bootstrap_data_generator
             {
-                // TODO: Currently we only have a single folder to download. In the future we might have a list of files.
-                let repo_path = format!(
-                    "{}/{}/sources/{}/{}/",
+                let remote_path = format!(
+                    "{}/{}/sources/{}/{}",
                     self.settings.root_path,
                     test_data_folder,
                     def.common.test_source_id,
-                    &sc_def.script_file_folder
+                    &bs_def.file
                 );
-                let local_path = test_source_data_path.join(&sc_def.script_file_folder);
-                self.download_change_script_files(repo_path, local_path)
-                    .await?;
+                download_github_repo_file(
+                    self.client.clone(),
+                    self.settings.owner.clone(),
+                    self.settings.repo.clone(),
+                    self.settings.branch.clone(),
+                    remote_path,
+                    test_source_data_path.join(&bs_def.file),
+                )
+                .await?;
+            }
+
+            // Source Change Script Files
+            if let Some(sc_def) = &def.source_change_generator {
+                for script_file_folder in sc_def.script_file_folders() {
+                    let repo_path = format!(
+                        "{}/{}/sources/{}/{}/",
+                        self.settings.root_path,
+                        test_data_folder,
+                        def.common.test_source_id,
+                        script_file_folder
+                    );
+                    let local_path = test_source_data_path.join(script_file_folder);
+                    self.download_change_script_files(repo_path, local_path)
+                        .await?;
+                }
             }
         }
 
+        decompress_gz_files(test_source_data_path).await?;
+
         Ok(())
     }
 }
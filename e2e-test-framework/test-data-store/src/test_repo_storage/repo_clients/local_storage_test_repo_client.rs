@@ -20,7 +20,9 @@ use tokio::{fs, io};
 
 use crate::test_repo_storage::models::TestSourceDefinition;
 
-use super::{CommonTestRepoConfig, LocalStorageTestRepoConfig, RemoteTestRepoClient};
+use super::{
+    decompress_gz_files, CommonTestRepoConfig, LocalStorageTestRepoConfig, RemoteTestRepoClient,
+};
 
 #[derive(Debug)]
 pub struct LocalStorageTestRepoClientSettings {
@@ -151,6 +153,7 @@ impl RemoteTestRepoClient for LocalStorageTestRepoClient {
                 }
 
                 copy_dir_tree(source, test_source_data_path.clone()).await?;
+                decompress_gz_files(test_source_data_path).await?;
             }
         }
 
@@ -183,10 +186,10 @@ async fn copy_dir_tree(source: PathBuf, destination: PathBuf) -> io::Result<()>
             // Add a new task for directory recursion
             tasks.push(tokio::spawn(copy_dir_tree_task(path, dest_path)));
         } else {
-            // If the file is a jsonl file, add the copy task to the list.
+            // If the file is a jsonl file or a gzipped script, add the copy task to the list.
             let extension = path.extension().and_then(|ext| ext.to_str());
 
-            if let Some("jsonl") = extension {
+            if let Some("jsonl") | Some("gz") = extension {
                 tasks.push(tokio::spawn(async move {
                     fs::copy(path, dest_path).await.map(|_| ())
                 }));
@@ -204,3 +207,75 @@ async fn copy_dir_tree(source: PathBuf, destination: PathBuf) -> io::Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use crate::scripts::{change_script_file_reader::ChangeScriptReader, ChangeScriptRecord};
+
+    use super::*;
+
+    const CHANGE_SCRIPT: &str = r#"{"kind":"Header","start_time":"2024-01-01T00:00:00Z","description":"Gzipped script"}
+{"kind":"Label","offset_ns":1000,"label":"first"}
+{"kind":"Finish","offset_ns":2000,"description":"Done"}
+"#;
+
+    #[tokio::test]
+    async fn test_copy_test_source_content_decompresses_gz_files() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let scripts_path = source_dir
+            .path()
+            .join("test_data/sources/source1/source_change_scripts");
+        std::fs::create_dir_all(&scripts_path).unwrap();
+
+        let mut encoder = GzEncoder::new(
+            std::fs::File::create(scripts_path.join("change01.jsonl.gz")).unwrap(),
+            Compression::default(),
+        );
+        encoder.write_all(CHANGE_SCRIPT.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        std::fs::write(scripts_path.join("change02.jsonl"), CHANGE_SCRIPT).unwrap();
+
+        let client = LocalStorageTestRepoClient::new(
+            CommonTestRepoConfig {
+                id: "repo".to_string(),
+                local_tests: Vec::new(),
+            },
+            LocalStorageTestRepoConfig {
+                source_path: Some(source_dir.path().to_string_lossy().into_owned()),
+            },
+        )
+        .await
+        .unwrap();
+
+        let test_source_def: TestSourceDefinition =
+            serde_json::from_str(r#"{ "kind": "Script", "test_source_id": "source1" }"#).unwrap();
+        let cache_dir = tempfile::tempdir().unwrap();
+        let test_source_data_path = cache_dir.path().join("source1");
+        client
+            .copy_test_source_content(
+                "test_data".to_string(),
+                &test_source_def,
+                test_source_data_path.clone(),
+            )
+            .await
+            .unwrap();
+
+        let cached_scripts_path = test_source_data_path.join("source_change_scripts");
+        assert!(!cached_scripts_path.join("change01.jsonl.gz").exists());
+        assert_eq!(
+            std::fs::read_to_string(cached_scripts_path.join("change02.jsonl")).unwrap(),
+            CHANGE_SCRIPT
+        );
+
+        let reader =
+            ChangeScriptReader::new(vec![cached_scripts_path.join("change01.jsonl")]).unwrap();
+        assert_eq!(reader.get_header().description, "Gzipped script");
+        let records: Vec<_> = reader.map(|r| r.unwrap().record).collect();
+        assert!(matches!(&records[0], ChangeScriptRecord::Label(r) if r.label == "first"));
+        assert!(matches!(&records[1], ChangeScriptRecord::Finish(_)));
+    }
+}
@@ -12,21 +12,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{fmt, path::PathBuf, str::FromStr};
 
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{
+    de::{self, Deserializer},
+    Deserialize, Serialize, Serializer,
+};
 
 use azure_storage_blob_test_repo_client::AzureStorageBlobTestRepoClient;
 use github_test_repo_client::GithubTestRepoClient;
+use s3_test_repo_client::S3TestRepoClient;
 
 use super::models::{LocalTestDefinition, TestSourceDefinition};
+use crate::secret::Secret;
 
 pub mod azure_storage_blob_test_repo_client;
 pub mod github_test_repo_client;
 pub mod local_storage_test_repo_client;
+pub mod s3_test_repo_client;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "kind")]
 pub enum TestRepoConfig {
     AzureStorageBlob {
@@ -47,6 +53,12 @@ pub enum TestRepoConfig {
         #[serde(flatten)]
         unique_config: LocalStorageTestRepoConfig,
     },
+    S3 {
+        #[serde(flatten)]
+        common_config: CommonTestRepoConfig,
+        #[serde(flatten)]
+        unique_config: S3TestRepoConfig,
+    },
 }
 
 impl TestRepoConfig {
@@ -55,6 +67,7 @@ impl TestRepoConfig {
             TestRepoConfig::AzureStorageBlob { common_config, .. } => common_config.id.clone(),
             TestRepoConfig::GitHub { common_config, .. } => common_config.id.clone(),
             TestRepoConfig::LocalStorage { common_config, .. } => common_config.id.clone(),
+            TestRepoConfig::S3 { common_config, .. } => common_config.id.clone(),
         }
     }
 
@@ -65,38 +78,67 @@ impl TestRepoConfig {
             }
             TestRepoConfig::GitHub { common_config, .. } => common_config.local_tests.clone(),
             TestRepoConfig::LocalStorage { common_config, .. } => common_config.local_tests.clone(),
+            TestRepoConfig::S3 { common_config, .. } => common_config.local_tests.clone(),
         }
     }
+
+    // Whether any of the config's secrets are literal values. Literal values are masked when
+    // the config is serialized, so a config that has them can't be persisted and reloaded.
+    pub fn has_literal_secrets(&self) -> bool {
+        let secrets = match self {
+            TestRepoConfig::AzureStorageBlob { unique_config, .. } => {
+                vec![&unique_config.access_key]
+            }
+            TestRepoConfig::GitHub { unique_config, .. } => vec![&unique_config.token],
+            TestRepoConfig::LocalStorage { .. } => Vec::new(),
+            TestRepoConfig::S3 { unique_config, .. } => vec![
+                &unique_config.access_key_id,
+                &unique_config.secret_access_key,
+                &unique_config.session_token,
+            ],
+        };
+
+        secrets
+            .into_iter()
+            .any(|secret| matches!(secret, Some(SecretRef::Literal(_))))
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CommonTestRepoConfig {
     pub id: String,
     #[serde(default)]
     pub local_tests: Vec<LocalTestDefinition>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+// The client authenticates with the first of access_key, credential_file or
+// use_managed_identity that is set. The credential file is a JSON file holding an access_key
+// and, optionally, the account_name it belongs to.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AzureStorageBlobTestRepoConfig {
     pub account_name: String,
-    #[serde(serialize_with = "mask_secret")]
-    pub access_key: String,
+    #[serde(default)]
+    pub access_key: Option<SecretRef>,
     pub container: String,
+    #[serde(default)]
+    pub credential_file: Option<PathBuf>,
     #[serde(default = "is_false")]
     pub force_cache_refresh: bool,
+    // Number of times a download is retried after a transient failure (timeout, 429 or 5xx).
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
     pub root_path: String,
+    #[serde(default = "is_false")]
+    pub use_managed_identity: bool,
 }
 fn is_false() -> bool {
     false
 }
-fn mask_secret<S>(_: &str, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    serializer.serialize_str("******")
+fn default_max_retries() -> u32 {
+    3
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct GithubTestRepoConfig {
     #[serde(default = "drasi_project")]
     pub owner: String,
@@ -107,7 +149,7 @@ pub struct GithubTestRepoConfig {
     #[serde(default = "is_false")]
     pub force_cache_refresh: bool,
     pub root_path: String,
-    pub token: Option<String>,
+    pub token: Option<SecretRef>,
 }
 
 fn drasi_project() -> String {
@@ -122,11 +164,102 @@ fn main_branch() -> String {
     "main".to_string()
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct LocalStorageTestRepoConfig {
     pub source_path: Option<String>,
 }
 
+// If access_key_id and secret_access_key are not set, the S3 client uses the default
+// AWS credential chain. endpoint_url overrides the S3 endpoint, e.g. for a MinIO server.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct S3TestRepoConfig {
+    pub access_key_id: Option<SecretRef>,
+    pub bucket: String,
+    pub endpoint_url: Option<String>,
+    #[serde(default = "is_false")]
+    pub force_cache_refresh: bool,
+    #[serde(default)]
+    pub prefix: String,
+    pub region: String,
+    pub secret_access_key: Option<SecretRef>,
+    pub session_token: Option<SecretRef>,
+}
+
+// A reference to a secret used by a test repo client. The secret can be read from an
+// environment variable ("env:VAR"), read from a file ("file:/path"), or provided as a
+// literal value. References are resolved when the client is created, and literal values
+// are masked when serialized so they are never persisted or returned by the API.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SecretRef {
+    Env(String),
+    File(PathBuf),
+    Literal(Secret<String>),
+}
+
+impl SecretRef {
+    pub fn resolve(&self) -> anyhow::Result<String> {
+        match self {
+            SecretRef::Env(var) => std::env::var(var).map_err(|e| {
+                anyhow::anyhow!("Failed to read secret from env var {:?}: {}", var, e)
+            }),
+            SecretRef::File(path) => std::fs::read_to_string(path)
+                .map(|secret| secret.trim_end().to_string())
+                .map_err(|e| anyhow::anyhow!("Failed to read secret from file {:?}: {}", path, e)),
+            SecretRef::Literal(secret) => Ok(secret.expose_secret().clone()),
+        }
+    }
+}
+
+impl FromStr for SecretRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        if let Some(var) = s.strip_prefix("env:") {
+            Ok(SecretRef::Env(var.to_string()))
+        } else if let Some(path) = s.strip_prefix("file:") {
+            Ok(SecretRef::File(PathBuf::from(path)))
+        } else {
+            Ok(SecretRef::Literal(Secret::new(s.to_string())))
+        }
+    }
+}
+
+impl From<&str> for SecretRef {
+    fn from(s: &str) -> Self {
+        // Parsing a SecretRef never fails.
+        s.parse().unwrap()
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretRef::Env(var) => write!(f, "env:{}", var),
+            SecretRef::File(path) => write!(f, "file:{}", path.display()),
+            SecretRef::Literal(secret) => write!(f, "{}", secret),
+        }
+    }
+}
+
+impl Serialize for SecretRef {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretRef {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: String = Deserialize::deserialize(deserializer)?;
+        value.parse::<SecretRef>().map_err(de::Error::custom)
+    }
+}
+
 #[async_trait]
 pub trait RemoteTestRepoClient: Send + Sync {
     async fn copy_test_definition(
@@ -186,5 +319,126 @@ pub async fn create_test_repo_client(
             )
             .await
         }
+        TestRepoConfig::S3 {
+            common_config,
+            unique_config,
+        } => S3TestRepoClient::new(common_config, unique_config).await,
+    }
+}
+
+// Repos can store script files gzipped to save bandwidth. Every file under path that ends in
+// .gz is decompressed next to itself without the suffix and then removed, so the script readers
+// only ever see plain files. Other files are left as they are.
+pub(crate) async fn decompress_gz_files(path: PathBuf) -> anyhow::Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        for entry in walkdir::WalkDir::new(&path) {
+            let entry = entry?;
+            let gz_path = entry.path();
+            if !entry.file_type().is_file()
+                || gz_path.extension().and_then(|ext| ext.to_str()) != Some("gz")
+            {
+                continue;
+            }
+
+            let plain_path = gz_path.with_extension("");
+            log::debug!("Decompressing {:?} to {:?}", gz_path, plain_path);
+
+            let mut decoder = flate2::read::GzDecoder::new(std::fs::File::open(gz_path)?);
+            let mut plain_file = std::fs::File::create(&plain_path)?;
+            std::io::copy(&mut decoder, &mut plain_file).map_err(|e| {
+                anyhow::anyhow!("Error decompressing script file {:?}: {}", gz_path, e)
+            })?;
+            std::fs::remove_file(gz_path)?;
+        }
+        Ok(())
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{SecretRef, TestRepoConfig};
+
+    #[test]
+    fn test_parse_secret_ref() {
+        assert_eq!(
+            "env:REPO_ACCESS_KEY".parse::<SecretRef>().unwrap(),
+            SecretRef::Env("REPO_ACCESS_KEY".to_string())
+        );
+        assert_eq!(
+            "file:/run/secrets/key".parse::<SecretRef>().unwrap(),
+            SecretRef::File(PathBuf::from("/run/secrets/key"))
+        );
+        assert_eq!(
+            "plain_key".parse::<SecretRef>().unwrap(),
+            SecretRef::Literal("plain_key".into())
+        );
+    }
+
+    #[test]
+    fn test_serialize_secret_ref() {
+        let env_ref: SecretRef = "env:REPO_ACCESS_KEY".into();
+        assert_eq!(
+            serde_json::to_string(&env_ref).unwrap(),
+            "\"env:REPO_ACCESS_KEY\""
+        );
+
+        let literal_ref: SecretRef = "plain_key".into();
+        assert_eq!(serde_json::to_string(&literal_ref).unwrap(), "\"***\"");
+        assert!(!format!("{:?}", literal_ref).contains("plain_key"));
+    }
+
+    #[test]
+    fn test_resolve_secret_ref() {
+        std::env::set_var("TEST_RESOLVE_SECRET_REF", "env_secret");
+        let env_ref: SecretRef = "env:TEST_RESOLVE_SECRET_REF".into();
+        assert_eq!(env_ref.resolve().unwrap(), "env_secret");
+
+        let missing_ref: SecretRef = "env:TEST_RESOLVE_SECRET_REF_MISSING".into();
+        assert!(missing_ref.resolve().is_err());
+
+        let literal_ref: SecretRef = "plain_key".into();
+        assert_eq!(literal_ref.resolve().unwrap(), "plain_key");
+    }
+
+    #[test]
+    fn test_deserialize_s3_test_repo_config() {
+        let config: TestRepoConfig = serde_json::from_str(
+            r#"{
+                "kind": "S3",
+                "id": "s3_repo",
+                "bucket": "test-repo",
+                "region": "us-west-2",
+                "prefix": "tests",
+                "access_key_id": "env:AWS_ACCESS_KEY_ID",
+                "secret_access_key": "env:AWS_SECRET_ACCESS_KEY"
+            }"#,
+        )
+        .unwrap();
+
+        match config {
+            TestRepoConfig::S3 {
+                common_config,
+                unique_config,
+            } => {
+                assert_eq!(common_config.id, "s3_repo");
+                assert_eq!(unique_config.bucket, "test-repo");
+                assert_eq!(unique_config.region, "us-west-2");
+                assert_eq!(unique_config.prefix, "tests");
+                assert_eq!(
+                    unique_config.access_key_id,
+                    Some(SecretRef::Env("AWS_ACCESS_KEY_ID".to_string()))
+                );
+                assert_eq!(unique_config.endpoint_url, None);
+                assert!(!unique_config.force_cache_refresh);
+            }
+            _ => panic!("Expected an S3 test repo config"),
+        }
     }
 }
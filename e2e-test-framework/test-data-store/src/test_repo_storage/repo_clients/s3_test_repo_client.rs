@@ -0,0 +1,503 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::HashMap, path::PathBuf};
+
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::{config::Credentials, Client};
+use tokio::{fs::File, io::AsyncWriteExt};
+
+use crate::test_repo_storage::models::{
+    BootstrapDataGeneratorDefinition, SourceChangeGeneratorDefinition, TestSourceDefinition,
+};
+
+use super::{decompress_gz_files, CommonTestRepoConfig, RemoteTestRepoClient, S3TestRepoConfig};
+
+#[derive(Debug)]
+pub struct S3TestRepoClientSettings {
+    pub bucket: String,
+    pub endpoint_url: Option<String>,
+    pub force_cache_refresh: bool,
+    pub prefix: String,
+    pub region: String,
+    pub test_repo_id: String,
+}
+
+impl S3TestRepoClientSettings {
+    pub async fn new(
+        common_config: CommonTestRepoConfig,
+        unique_config: S3TestRepoConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            bucket: unique_config.bucket,
+            endpoint_url: unique_config.endpoint_url,
+            force_cache_refresh: unique_config.force_cache_refresh,
+            prefix: unique_config.prefix.trim_matches('/').to_string(),
+            region: unique_config.region,
+            test_repo_id: common_config.id.clone(),
+        })
+    }
+
+    // The object key for a path relative to the repo prefix.
+    fn object_key(&self, path: &str) -> String {
+        if self.prefix.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.prefix, path)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct S3TestRepoClient {
+    pub settings: S3TestRepoClientSettings,
+    client: Client,
+}
+
+impl S3TestRepoClient {
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        common_config: CommonTestRepoConfig,
+        unique_config: S3TestRepoConfig,
+    ) -> anyhow::Result<Box<dyn RemoteTestRepoClient + Send + Sync>> {
+        Ok(Box::new(Self::create(common_config, unique_config).await?))
+    }
+
+    async fn create(
+        common_config: CommonTestRepoConfig,
+        unique_config: S3TestRepoConfig,
+    ) -> anyhow::Result<Self> {
+        log::debug!(
+            "Creating S3TestRepoClient from common_config:{:?} and unique_config:{:?}, ",
+            common_config,
+            unique_config
+        );
+
+        let client = create_s3_client(&unique_config).await?;
+        let settings = S3TestRepoClientSettings::new(common_config, unique_config).await?;
+        log::trace!("Creating S3TestRepoClient with settings: {:?}, ", settings);
+
+        Ok(Self { settings, client })
+    }
+
+    async fn download_bootstrap_script_files(
+        &self,
+        repo_folder: String,
+        local_folder: PathBuf,
+    ) -> anyhow::Result<HashMap<String, Vec<PathBuf>>> {
+        log::debug!(
+            "Downloading Bootstrap Script Files from {:?} to {:?}",
+            repo_folder,
+            local_folder
+        );
+
+        let mut file_path_list = self
+            .download_test_repo_folder(local_folder, repo_folder)
+            .await?;
+        log::trace!("Bootstrap Script Files: {:?}", file_path_list);
+
+        // Sort the list of files by the file name to get them in the correct order for processing.
+        file_path_list.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+        // Group the files by the data type name, which is the parent folder name of the file.
+        let mut file_path_map: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for file_path in file_path_list {
+            let data_type_name = file_path
+                .parent()
+                .unwrap()
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string();
+            file_path_map
+                .entry(data_type_name)
+                .or_default()
+                .push(file_path);
+        }
+        log::trace!("Bootstrap Script Map: {:?}", file_path_map);
+
+        Ok(file_path_map)
+    }
+
+    async fn download_change_script_files(
+        &self,
+        repo_folder: String,
+        local_folder: PathBuf,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        log::debug!(
+            "Downloading Source Change Script Files from {:?} to {:?}",
+            repo_folder,
+            local_folder
+        );
+
+        let mut file_path_list = self
+            .download_test_repo_folder(local_folder, repo_folder)
+            .await?;
+        log::trace!("Change Scripts Files: {:?}", file_path_list);
+
+        // Sort the list of files by the file name to get them in the correct order for processing.
+        file_path_list.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+        Ok(file_path_list)
+    }
+
+    // Downloads the .jsonl objects under the remote folder, keeping their relative paths.
+    async fn download_test_repo_folder(
+        &self,
+        local_repo_folder: PathBuf,
+        remote_repo_folder: String,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let mut pages = self
+            .client
+            .list_objects_v2()
+            .bucket(&self.settings.bucket)
+            .prefix(&remote_repo_folder)
+            .into_paginator()
+            .send();
+
+        // Create the local folder if it doesn't exist.
+        if !local_repo_folder.exists() {
+            tokio::fs::create_dir_all(&local_repo_folder).await?;
+        }
+
+        // Each task downloads a single file. All downloads must be complete before returning.
+        let mut tasks = vec![];
+        let mut local_file_paths = vec![];
+
+        while let Some(page) = pages.next().await {
+            let page = page?;
+            for object in page.contents() {
+                let Some(key) = object.key() else {
+                    continue;
+                };
+
+                let stripped_key = key.strip_prefix(&remote_repo_folder).unwrap_or(key);
+                let local_file_path = local_repo_folder.join(stripped_key);
+
+                match local_file_path.extension() {
+                    Some(ext) if ext == "jsonl" || ext == "gz" => {
+                        if let Some(parent) = local_file_path.parent() {
+                            tokio::fs::create_dir_all(parent).await?;
+                        }
+                        local_file_paths.push(local_file_path.clone());
+
+                        tasks.push(tokio::spawn(download_test_repo_file(
+                            self.client.clone(),
+                            self.settings.bucket.clone(),
+                            key.to_string(),
+                            local_file_path,
+                        )));
+                    }
+                    _ => log::trace!("Ignoring S3 object: {:?}", key),
+                }
+            }
+        }
+
+        for result in futures::future::try_join_all(tasks).await? {
+            result?;
+        }
+
+        Ok(local_file_paths)
+    }
+}
+
+#[async_trait]
+impl RemoteTestRepoClient for S3TestRepoClient {
+    async fn copy_test_definition(
+        &self,
+        test_id: String,
+        test_def_path: PathBuf,
+    ) -> anyhow::Result<()> {
+        log::debug!(
+            "Copying TestDefinition - {:?} to folder {:?}",
+            test_id,
+            test_def_path
+        );
+
+        // If the TestDefinition already exists, return an error.
+        if test_def_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Test Definition ID: {} already exists in location {:?}",
+                test_id,
+                test_def_path
+            ));
+        }
+
+        let remote_path = self.settings.object_key(&format!("{}.test.json", test_id));
+
+        download_test_repo_file(
+            self.client.clone(),
+            self.settings.bucket.clone(),
+            remote_path,
+            test_def_path,
+        )
+        .await
+    }
+
+    async fn copy_test_source_content(
+        &self,
+        test_data_folder: String,
+        test_source_def: &TestSourceDefinition,
+        test_source_data_path: PathBuf,
+    ) -> anyhow::Result<()> {
+        if let TestSourceDefinition::Script(def) = test_source_def {
+            log::debug!(
+                "Copying Test Source Content for {:?} to {:?}",
+                def.common.test_source_id,
+                test_source_data_path
+            );
+
+            let source_path = |path: &str| {
+                self.settings.object_key(&format!(
+                    "{}/sources/{}/{}",
+                    test_data_folder, def.common.test_source_id, path
+                ))
+            };
+
+            // Bootstrap Data Script Files
+            if let Some(BootstrapDataGeneratorDefinition::Script(bs_def)) =
+                &def.bootstrap_data_generator
+            {
+                let repo_path = format!("{}/", source_path(&bs_def.script_file_folder));
+                let local_path = test_source_data_path.join(&bs_def.script_file_folder);
+                self.download_bootstrap_script_files(repo_path, local_path)
+                    .await?;
+            }
+
+            // Bootstrap Data File
+            if let Some(BootstrapDataGeneratorDefinition::File(bs_def)) =
+                &def.bootstrap_data_generator
+            {
+                let local_path = test_source_data_path.join(&bs_def.file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.client.clone(),
+                    self.settings.bucket.clone(),
+                    source_path(&bs_def.file),
+                    local_path,
+                )
+                .await?;
+            }
+
+            // Source Change Script Files
+            if let Some(sc_def) = &def.source_change_generator {
+                for script_file_folder in sc_def.script_file_folders() {
+                    let repo_path = format!("{}/", source_path(script_file_folder));
+                    let local_path = test_source_data_path.join(script_file_folder);
+                    self.download_change_script_files(repo_path, local_path)
+                        .await?;
+                }
+            }
+
+            // Source Change CSV File
+            if let Some(SourceChangeGeneratorDefinition::CsvFile(csv_def)) =
+                &def.source_change_generator
+            {
+                let local_path = test_source_data_path.join(&csv_def.csv_file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.client.clone(),
+                    self.settings.bucket.clone(),
+                    source_path(&csv_def.csv_file),
+                    local_path,
+                )
+                .await?;
+            }
+
+            // Source Change Debezium File
+            if let Some(SourceChangeGeneratorDefinition::DebeziumFile(debezium_def)) =
+                &def.source_change_generator
+            {
+                let local_path = test_source_data_path.join(&debezium_def.debezium_file);
+                if let Some(parent) = local_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+                download_test_repo_file(
+                    self.client.clone(),
+                    self.settings.bucket.clone(),
+                    source_path(&debezium_def.debezium_file),
+                    local_path,
+                )
+                .await?;
+            }
+        }
+
+        decompress_gz_files(test_source_data_path).await?;
+
+        Ok(())
+    }
+}
+
+// Explicit credentials are used if configured. Otherwise the client falls back to the
+// default AWS credential chain, e.g. environment variables, profiles or instance roles.
+async fn create_s3_client(config: &S3TestRepoConfig) -> anyhow::Result<Client> {
+    let mut loader =
+        aws_config::defaults(BehaviorVersion::latest()).region(Region::new(config.region.clone()));
+
+    match (&config.access_key_id, &config.secret_access_key) {
+        (Some(access_key_id), Some(secret_access_key)) => {
+            let session_token = match &config.session_token {
+                Some(token) => Some(token.resolve()?),
+                None => None,
+            };
+            loader = loader.credentials_provider(Credentials::new(
+                access_key_id.resolve()?,
+                secret_access_key.resolve()?,
+                session_token,
+                None,
+                "S3TestRepoConfig",
+            ));
+        }
+        (None, None) => {}
+        _ => anyhow::bail!("S3 test repo access_key_id and secret_access_key must be set together"),
+    }
+
+    let sdk_config = loader.load().await;
+    let mut builder = aws_sdk_s3::config::Builder::from(&sdk_config);
+    if let Some(endpoint_url) = &config.endpoint_url {
+        // S3 compatible servers such as MinIO generally don't support virtual hosted buckets.
+        builder = builder.endpoint_url(endpoint_url).force_path_style(true);
+    }
+
+    Ok(Client::from_conf(builder.build()))
+}
+
+async fn download_test_repo_file(
+    client: Client,
+    bucket: String,
+    key: String,
+    local_file_path: PathBuf,
+) -> anyhow::Result<()> {
+    log::debug!(
+        "Downloading file {} to {}",
+        key,
+        local_file_path.to_str().unwrap()
+    );
+
+    let mut object = client
+        .get_object()
+        .bucket(&bucket)
+        .key(&key)
+        .send()
+        .await
+        .map_err(|e| anyhow::anyhow!("Error getting S3 object {}/{}: {}", bucket, key, e))?;
+
+    // Create the local file to hold the object data.
+    let mut local_file = File::create(local_file_path).await?;
+    while let Some(bytes) = object.body.try_next().await? {
+        local_file.write_all(&bytes).await?;
+    }
+    local_file.flush().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_s3::primitives::ByteStream;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn config(endpoint_url: Option<String>) -> S3TestRepoConfig {
+        S3TestRepoConfig {
+            access_key_id: Some("minioadmin".into()),
+            bucket: "test-repo".to_string(),
+            endpoint_url,
+            force_cache_refresh: false,
+            prefix: "/tests/".to_string(),
+            region: "us-east-1".to_string(),
+            secret_access_key: Some("minioadmin".into()),
+            session_token: None,
+        }
+    }
+
+    fn common_config() -> CommonTestRepoConfig {
+        CommonTestRepoConfig {
+            id: "s3_repo".to_string(),
+            local_tests: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_s3_client_settings() -> anyhow::Result<()> {
+        let client = S3TestRepoClient::create(common_config(), config(None)).await?;
+        assert_eq!(client.settings.prefix, "tests");
+        assert_eq!(
+            client.settings.object_key("test_1.test.json"),
+            "tests/test_1.test.json"
+        );
+
+        let partial_credentials = S3TestRepoConfig {
+            secret_access_key: None,
+            ..config(None)
+        };
+        assert!(
+            S3TestRepoClient::create(common_config(), partial_credentials)
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    // Uses an S3 compatible MinIO server, e.g.
+    //   docker run -p 9000:9000 minio/minio server /data
+    // The endpoint can be changed with the S3_TEST_REPO_ENDPOINT environment variable.
+    #[tokio::test]
+    #[ignore = "Requires a local MinIO server"]
+    async fn test_s3_copy_test_definition() -> anyhow::Result<()> {
+        let endpoint = std::env::var("S3_TEST_REPO_ENDPOINT")
+            .unwrap_or_else(|_| "http://localhost:9000".to_string());
+        let client = S3TestRepoClient::create(common_config(), config(Some(endpoint))).await?;
+
+        let _ = client
+            .client
+            .create_bucket()
+            .bucket("test-repo")
+            .send()
+            .await;
+        client
+            .client
+            .put_object()
+            .bucket("test-repo")
+            .key("tests/test_1.test.json")
+            .body(ByteStream::from_static(br#"{"test_id": "test_1"}"#))
+            .send()
+            .await?;
+
+        let temp_dir = TempDir::new()?;
+        let test_def_path = temp_dir.path().join("test_1.test.json");
+        client
+            .copy_test_definition("test_1".to_string(), test_def_path.clone())
+            .await?;
+
+        let content = tokio::fs::read_to_string(&test_def_path).await?;
+        assert_eq!(content, r#"{"test_id": "test_1"}"#);
+
+        // An existing test definition is not overwritten.
+        assert!(client
+            .copy_test_definition("test_1".to_string(), test_def_path)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{fmt, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use serde::Serialize;
 use serde_json::Value;
@@ -29,6 +33,8 @@ const REACTION_OUTPUT_LOG_FOLDER_NAME: &str = "output_log";
 
 const DRASI_SERVERS_FOLDER_NAME: &str = "drasi_servers";
 
+const TEST_RUN_SUMMARY_FILE_NAME: &str = "test_run_summary.json";
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize)]
 pub struct TestRunId {
     pub test_id: String,
@@ -298,6 +304,19 @@ impl TestRunStore {
         Ok(self.path.join(test_run_id.to_string()).exists())
     }
 
+    // Deletes the output of a single TestRun, leaving the rest of the store intact.
+    // Returns false if there was no output to delete.
+    pub async fn delete_test_run(&self, test_run_id: &TestRunId) -> anyhow::Result<bool> {
+        let test_run_path = self.path.join(test_run_id.to_string());
+        if !test_run_path.exists() {
+            return Ok(false);
+        }
+
+        log::debug!("Deleting TestRun output in folder: {:?}", &test_run_path);
+        fs::remove_dir_all(&test_run_path).await?;
+        Ok(true)
+    }
+
     pub async fn get_test_run_ids(&self) -> anyhow::Result<Vec<TestRunId>> {
         let mut test_run_ids = Vec::new();
 
@@ -546,6 +565,46 @@ impl TestRunStorage {
 
         Ok(test_run_drasi_servers)
     }
+
+    // Reads the test_run_summary.json written by each component of the TestRun.
+    // Components that have not yet written a summary are omitted.
+    pub async fn get_test_run_summaries(&self) -> anyhow::Result<TestRunSummaries> {
+        Ok(TestRunSummaries {
+            drasi_servers: read_test_run_summaries(&self.drasi_servers_path).await?,
+            queries: read_test_run_summaries(&self.queries_path).await?,
+            reactions: read_test_run_summaries(&self.reactions_path).await?,
+            sources: read_test_run_summaries(&self.sources_path).await?,
+        })
+    }
+}
+
+async fn read_test_run_summaries(path: &Path) -> anyhow::Result<HashMap<String, Value>> {
+    let mut summaries = HashMap::new();
+
+    if path.exists() {
+        let mut entries = fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let summary_path = entry.path().join(TEST_RUN_SUMMARY_FILE_NAME);
+            if !summary_path.exists() {
+                continue;
+            }
+
+            if let Some(folder_name) = entry.file_name().to_str() {
+                let content = fs::read_to_string(&summary_path).await?;
+                summaries.insert(folder_name.to_string(), serde_json::from_str(&content)?);
+            }
+        }
+    }
+
+    Ok(summaries)
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TestRunSummaries {
+    pub drasi_servers: HashMap<String, Value>,
+    pub queries: HashMap<String, Value>,
+    pub reactions: HashMap<String, Value>,
+    pub sources: HashMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -557,7 +616,7 @@ pub struct TestRunQueryStorage {
 
 impl TestRunQueryStorage {
     pub async fn write_test_run_summary(&self, summary: &Value) -> anyhow::Result<()> {
-        let summary_path = self.path.join("test_run_summary.json");
+        let summary_path = self.path.join(TEST_RUN_SUMMARY_FILE_NAME);
         fs::write(summary_path, serde_json::to_string_pretty(summary)?).await?;
         Ok(())
     }
@@ -572,7 +631,7 @@ pub struct TestRunSourceStorage {
 
 impl TestRunSourceStorage {
     pub async fn write_test_run_summary(&self, summary: &Value) -> anyhow::Result<()> {
-        let summary_path = self.path.join("test_run_summary.json");
+        let summary_path = self.path.join(TEST_RUN_SUMMARY_FILE_NAME);
         fs::write(summary_path, serde_json::to_string_pretty(summary)?).await?;
         Ok(())
     }
@@ -587,7 +646,7 @@ pub struct TestRunReactionStorage {
 
 impl TestRunReactionStorage {
     pub async fn write_test_run_summary(&self, summary: &Value) -> anyhow::Result<()> {
-        let summary_path = self.path.join("test_run_summary.json");
+        let summary_path = self.path.join(TEST_RUN_SUMMARY_FILE_NAME);
         fs::write(summary_path, serde_json::to_string_pretty(summary)?).await?;
         Ok(())
     }
@@ -601,7 +660,7 @@ pub struct TestRunDrasiServerStorage {
 
 impl TestRunDrasiServerStorage {
     pub async fn write_test_run_summary(&self, summary: &Value) -> anyhow::Result<()> {
-        let summary_path = self.path.join("test_run_summary.json");
+        let summary_path = self.path.join(TEST_RUN_SUMMARY_FILE_NAME);
         fs::write(summary_path, serde_json::to_string_pretty(summary)?).await?;
         Ok(())
     }
@@ -27,6 +27,8 @@ mod tests {
             data_collection_folder: None,
             delete_on_start: None,
             delete_on_stop: None,
+            prefetch_concurrency: None,
+            prefetch_tests: None,
             test_repo_folder: None,
             test_run_folder: None,
         };
@@ -123,6 +125,8 @@ mod tests {
                 data_collection_folder: None,
                 delete_on_start: None,
                 delete_on_stop: None,
+                prefetch_concurrency: None,
+                prefetch_tests: None,
                 test_repo_folder: None,
                 test_run_folder: None,
             };
@@ -145,6 +149,8 @@ mod tests {
                 data_collection_folder: None,
                 delete_on_start: None,
                 delete_on_stop: None,
+                prefetch_concurrency: None,
+                prefetch_tests: None,
                 test_repo_folder: None,
                 test_run_folder: None,
             };
@@ -215,4 +221,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_test_run_summaries() -> anyhow::Result<()> {
+        let (data_store, test_run_id, _temp_dir) = setup_test_env().await?;
+
+        let source_id = TestRunSourceId::new(&test_run_id, "source-001");
+        let query_id = TestRunQueryId::new(&test_run_id, "query-001");
+        let reaction_id = TestRunReactionId::new(&test_run_id, "reaction-001");
+
+        let source_storage = data_store.get_test_run_source_storage(&source_id).await?;
+        let _query_storage = data_store.get_test_run_query_storage(&query_id).await?;
+        let _reaction_storage = data_store
+            .get_test_run_reaction_storage(&reaction_id)
+            .await?;
+
+        source_storage
+            .write_test_run_summary(&serde_json::json!({ "num_source_change_records": 10 }))
+            .await?;
+
+        let summaries = data_store
+            .get_test_run_storage(&test_run_id)
+            .await?
+            .get_test_run_summaries()
+            .await?;
+
+        // Only components that have written a summary are included
+        assert_eq!(summaries.sources.len(), 1);
+        assert_eq!(
+            summaries.sources["source-001"]["num_source_change_records"],
+            10
+        );
+        assert!(summaries.queries.is_empty());
+        assert!(summaries.reactions.is_empty());
+        assert!(summaries.drasi_servers.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_test_run_storage() -> anyhow::Result<()> {
+        let (data_store, test_run_id, _temp_dir) = setup_test_env().await?;
+        let other_run_id = TestRunId::new("test-repo", "test-001", "run-002");
+
+        let reaction_id = TestRunReactionId::new(&test_run_id, "reaction-001");
+        let reaction_storage = data_store
+            .get_test_run_reaction_storage(&reaction_id)
+            .await?;
+        let other_storage = data_store.get_test_run_storage(&other_run_id).await?;
+
+        assert!(data_store.delete_test_run_storage(&test_run_id).await?);
+
+        // Only the deleted TestRun's output is removed
+        assert!(!reaction_storage.path.exists());
+        assert!(other_storage.path.exists());
+        assert!(!data_store.contains_test_run(&test_run_id).await?);
+
+        // Deleting again is not an error
+        assert!(!data_store.delete_test_run_storage(&test_run_id).await?);
+
+        Ok(())
+    }
 }
@@ -172,7 +172,7 @@ pub enum OutputHandlerConfig {
     /// Future: EventGrid reaction handler
     EventGridReaction {
         endpoint: String,
-        access_key: String,
+        access_key: test_data_store::secret::Secret<String>,
     },
     /// Future: Dapr pubsub handler
     DaprPubSub { pubsub_name: String, topic: String },
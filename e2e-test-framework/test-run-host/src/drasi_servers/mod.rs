@@ -14,8 +14,11 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::Duration;
 
+use async_trait::async_trait;
 use derive_more::Debug;
 use drasi_server::{server_core::DrasiServerCore, ApplicationHandle, RuntimeConfig};
 use serde::{Deserialize, Serialize};
@@ -57,6 +60,20 @@ fn default_start_immediately() -> bool {
     true
 }
 
+impl TestRunDrasiServerConfig {
+    /// Creates a config for the TestDrasiServer with the same defaults used when deserializing.
+    pub fn new(test_drasi_server_id: &str) -> Self {
+        Self {
+            start_immediately: default_start_immediately(),
+            test_drasi_server_id: test_drasi_server_id.to_string(),
+            test_run_overrides: None,
+            test_id: None,
+            test_repo_id: None,
+            test_run_id: None,
+        }
+    }
+}
+
 /// Overrides for Drasi Server configuration at runtime
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct TestRunDrasiServerOverrides {
@@ -68,8 +85,19 @@ pub struct TestRunDrasiServerOverrides {
 
     /// Override log level (trace, debug, info, warn, error)
     pub log_level: Option<String>,
+
+    /// Seconds to wait for the DrasiServerCore to initialize and start (default 60)
+    #[serde(default)]
+    pub startup_timeout_seconds: Option<u64>,
+
+    /// Fail startup if any configured source or reaction doesn't yield an ApplicationHandle
+    /// (default false)
+    #[serde(default)]
+    pub require_all_handles: bool,
 }
 
+const DEFAULT_STARTUP_TIMEOUT_SECONDS: u64 = 60;
+
 impl TryFrom<&TestRunDrasiServerConfig> for TestRunId {
     type Error = ParseTestRunIdError;
 
@@ -159,6 +187,128 @@ impl TestRunDrasiServerDefinition {
 
         config
     }
+
+    /// Get the time allowed for the DrasiServerCore to initialize and start
+    pub fn startup_timeout(&self) -> Duration {
+        let seconds = self
+            .test_run_overrides
+            .as_ref()
+            .and_then(|overrides| overrides.startup_timeout_seconds)
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT_SECONDS);
+        Duration::from_secs(seconds)
+    }
+
+    /// Whether every configured source and reaction must yield an ApplicationHandle
+    pub fn require_all_handles(&self) -> bool {
+        self.test_run_overrides
+            .as_ref()
+            .is_some_and(|overrides| overrides.require_all_handles)
+    }
+}
+
+/// The DrasiServerCore managers that hand out ApplicationHandles, so that handle collection
+/// can be tested against a stub core
+#[async_trait]
+pub(crate) trait ApplicationHandleSource: Send + Sync {
+    type Handle: Send;
+
+    async fn get_source_handle(&self, id: &str) -> Option<Self::Handle>;
+    async fn get_reaction_handle(&self, id: &str) -> Option<Self::Handle>;
+}
+
+#[async_trait]
+impl ApplicationHandleSource for DrasiServerCore {
+    type Handle = ApplicationHandle;
+
+    async fn get_source_handle(&self, id: &str) -> Option<ApplicationHandle> {
+        self.source_manager()
+            .get_application_handle(id)
+            .await
+            .map(ApplicationHandle::source_only)
+    }
+
+    async fn get_reaction_handle(&self, id: &str) -> Option<ApplicationHandle> {
+        self.reaction_manager()
+            .get_application_handle(id)
+            .await
+            .map(ApplicationHandle::reaction_only)
+    }
+}
+
+/// ApplicationHandles collected for the configured sources and reactions
+pub(crate) struct CollectedApplicationHandles<H> {
+    pub handles: HashMap<String, H>,
+    /// Components that didn't yield a handle, e.g. "source 'orders'"
+    pub missing: Vec<String>,
+}
+
+/// Gets the ApplicationHandles of the configured sources and reactions. Queries don't have
+/// application handles.
+pub(crate) async fn collect_application_handles<S: ApplicationHandleSource + ?Sized>(
+    core: &S,
+    config: &TestDrasiServerConfig,
+) -> CollectedApplicationHandles<S::Handle> {
+    let mut collected = CollectedApplicationHandles {
+        handles: HashMap::new(),
+        missing: Vec::new(),
+    };
+
+    for source_config in &config.sources {
+        match core.get_source_handle(&source_config.id).await {
+            Some(handle) => {
+                collected.handles.insert(source_config.id.clone(), handle);
+            }
+            None => collected
+                .missing
+                .push(format!("source '{}'", source_config.id)),
+        }
+    }
+
+    for reaction_config in &config.reactions {
+        match core.get_reaction_handle(&reaction_config.id).await {
+            Some(handle) => {
+                collected.handles.insert(reaction_config.id.clone(), handle);
+            }
+            None => collected
+                .missing
+                .push(format!("reaction '{}'", reaction_config.id)),
+        }
+    }
+
+    collected
+}
+
+/// Creates a DrasiServerCore from the RuntimeConfig, then initializes and starts it.
+async fn start_drasi_server_core(
+    runtime_config: Arc<RuntimeConfig>,
+) -> anyhow::Result<Arc<DrasiServerCore>> {
+    // Log configuration summary
+    log::info!(
+        "Creating DrasiServerCore with {} sources, {} queries, {} reactions pre-configured",
+        runtime_config.sources.len(),
+        runtime_config.queries.len(),
+        runtime_config.reactions.len()
+    );
+
+    // Create the DrasiServerCore instance
+    let mut core = DrasiServerCore::new(runtime_config);
+
+    // Initialize the core to create all components
+    log::info!("Initializing DrasiServerCore to create components...");
+    core.initialize()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to initialize DrasiServerCore: {}", e))?;
+
+    // Store the core after initialization but before starting
+    let core = Arc::new(core);
+
+    // Start the core to start all auto-start components
+    log::info!("Starting DrasiServerCore to start auto-start components...");
+    core.start()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to start DrasiServerCore: {}", e))?;
+
+    Ok(core)
 }
 
 /// State of a test run Drasi Server
@@ -262,6 +412,16 @@ impl TestRunDrasiServer {
     }
 
     pub async fn start(&self) -> anyhow::Result<()> {
+        self.start_with(start_drasi_server_core).await
+    }
+
+    /// Starts the server, using start_core to create and start the DrasiServerCore from the
+    /// RuntimeConfig. The startup is bounded by the definition's startup timeout.
+    pub(crate) async fn start_with<F, Fut>(&self, start_core: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(Arc<RuntimeConfig>) -> Fut,
+        Fut: Future<Output = anyhow::Result<Arc<DrasiServerCore>>>,
+    {
         let mut state = self.state.write().await;
 
         match &*state {
@@ -322,31 +482,13 @@ impl TestRunDrasiServer {
                     reactions: drasi_reactions,
                 });
 
-                // Create the DrasiServerCore instance
-                let mut core = DrasiServerCore::new(runtime_config);
-
-                // Log configuration summary
-                log::info!(
-                    "Created DrasiServerCore with {} sources, {} queries, {} reactions pre-configured",
-                    config.sources.len(),
-                    config.queries.len(),
-                    config.reactions.len()
-                );
-
-                // Initialize the core to create all components
-                log::info!("Initializing DrasiServerCore to create components...");
-                core.initialize()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to initialize DrasiServerCore: {}", e))?;
-
-                // Store the core after initialization but before starting
-                let core = Arc::new(core);
-
-                // Start the core to start all auto-start components
-                log::info!("Starting DrasiServerCore to start auto-start components...");
-                core.start()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Failed to start DrasiServerCore: {}", e))?;
+                let core = self
+                    .await_startup(
+                        &mut state,
+                        self.definition.startup_timeout(),
+                        start_core(runtime_config),
+                    )
+                    .await?;
 
                 // Store configured component names for validation
                 let configured_source_names: std::collections::HashSet<String> =
@@ -393,61 +535,19 @@ impl TestRunDrasiServer {
                 }
 
                 // Get and store application handles from the core managers
+                let collected = collect_application_handles(core.as_ref(), &config).await;
+                for missing in &collected.missing {
+                    log::warn!(
+                        "Could not get ApplicationHandle for {} on Drasi Server {}",
+                        missing,
+                        self.definition.id
+                    );
+                }
+                self.verify_application_handles(&mut state, &collected.missing)
+                    .await?;
                 {
                     let mut stored_handles = self.application_handles.write().await;
-                    stored_handles.clear();
-
-                    // Get handles from source manager for configured sources
-                    for source_config in &config.sources {
-                        if let Some(handle) = core
-                            .source_manager()
-                            .get_application_handle(&source_config.id)
-                            .await
-                        {
-                            stored_handles.insert(
-                                source_config.id.clone(),
-                                ApplicationHandle::source_only(handle),
-                            );
-                            log::info!(
-                                "Stored ApplicationHandle for source '{}' on Drasi Server {}",
-                                source_config.id,
-                                self.definition.id
-                            );
-                        } else {
-                            log::warn!(
-                                "Could not get ApplicationHandle for source '{}' on Drasi Server {}",
-                                source_config.id,
-                                self.definition.id
-                            );
-                        }
-                    }
-
-                    // Get handles from reaction manager for configured reactions
-                    for reaction_config in &config.reactions {
-                        if let Some(handle) = core
-                            .reaction_manager()
-                            .get_application_handle(&reaction_config.id)
-                            .await
-                        {
-                            stored_handles.insert(
-                                reaction_config.id.clone(),
-                                ApplicationHandle::reaction_only(handle),
-                            );
-                            log::info!(
-                                "Stored ApplicationHandle for reaction '{}' on Drasi Server {}",
-                                reaction_config.id,
-                                self.definition.id
-                            );
-                        } else {
-                            log::warn!(
-                                "Could not get ApplicationHandle for reaction '{}' on Drasi Server {}",
-                                reaction_config.id,
-                                self.definition.id
-                            );
-                        }
-                    }
-
-                    // Note: Query manager doesn't provide application handles
+                    *stored_handles = collected.handles;
 
                     log::info!(
                         "Stored {} application handles for Drasi Server {} after starting",
@@ -507,6 +607,61 @@ impl TestRunDrasiServer {
         }
     }
 
+    /// Waits for the DrasiServerCore startup to finish. If it takes longer than the timeout,
+    /// the server moves to the Error state rather than blocking the test run indefinitely.
+    pub(crate) async fn await_startup<F, T>(
+        &self,
+        state: &mut TestRunDrasiServerState,
+        timeout: Duration,
+        startup: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Future<Output = anyhow::Result<T>>,
+    {
+        match tokio::time::timeout(timeout, startup).await {
+            Ok(result) => result,
+            Err(_) => {
+                let message = format!(
+                    "DrasiServerCore startup timed out after {} seconds",
+                    timeout.as_secs_f64()
+                );
+                log::error!("Drasi Server {}: {}", self.definition.id, message);
+                *state = TestRunDrasiServerState::Error {
+                    error_time: chrono::Utc::now(),
+                    message: message.clone(),
+                };
+                anyhow::bail!(message)
+            }
+        }
+    }
+
+    /// Checks that every configured component yielded an ApplicationHandle. Missing handles
+    /// are only logged unless require_all_handles is set, in which case the server moves to
+    /// the Error state and releases the DrasiServerCore.
+    pub(crate) async fn verify_application_handles(
+        &self,
+        state: &mut TestRunDrasiServerState,
+        missing: &[String],
+    ) -> anyhow::Result<()> {
+        if missing.is_empty() || !self.definition.require_all_handles() {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Drasi Server components did not yield an ApplicationHandle: {}",
+            missing.join(", ")
+        );
+        log::error!("Drasi Server {}: {}", self.definition.id, message);
+
+        *self.drasi_core.write().await = None;
+        self.application_handles.write().await.clear();
+        *state = TestRunDrasiServerState::Error {
+            error_time: chrono::Utc::now(),
+            message: message.clone(),
+        };
+        anyhow::bail!(message)
+    }
+
     pub async fn stop(&self, reason: Option<String>) -> anyhow::Result<()> {
         let mut state = self.state.write().await;
 
@@ -14,12 +14,17 @@
 
 #[cfg(test)]
 mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use async_trait::async_trait;
+
     use crate::drasi_servers::{
+        collect_application_handles, ApplicationHandleSource, TestRunDrasiServer,
         TestRunDrasiServerConfig, TestRunDrasiServerDefinition, TestRunDrasiServerOverrides,
         TestRunDrasiServerState,
     };
     use test_data_store::test_repo_storage::models::{
-        DrasiServerConfig, TestDrasiServerDefinition,
+        DrasiReactionConfig, DrasiServerConfig, DrasiSourceConfig, TestDrasiServerDefinition,
     };
 
     #[test]
@@ -307,6 +312,8 @@ mod tests {
                 auth: None,
                 storage: None,
                 log_level: Some("trace".to_string()),
+                startup_timeout_seconds: None,
+                require_all_handles: false,
             }),
         };
 
@@ -317,4 +324,165 @@ mod tests {
         let effective_config = definition.effective_config();
         assert_eq!(effective_config.log_level, Some("trace".to_string()));
     }
+
+    // A stub core that only has handles for the components it was given.
+    struct StubCore {
+        components: HashSet<String>,
+    }
+
+    #[async_trait]
+    impl ApplicationHandleSource for StubCore {
+        type Handle = String;
+
+        async fn get_source_handle(&self, id: &str) -> Option<String> {
+            self.components.get(id).cloned()
+        }
+
+        async fn get_reaction_handle(&self, id: &str) -> Option<String> {
+            self.components.get(id).cloned()
+        }
+    }
+
+    async fn create_server(
+        overrides: TestRunDrasiServerOverrides,
+    ) -> (TestRunDrasiServer, tempfile::TempDir) {
+        let test_drasi_server_def = TestDrasiServerDefinition {
+            id: "test-server".to_string(),
+            name: "Test Server".to_string(),
+            description: None,
+            config: DrasiServerConfig {
+                runtime: None,
+                auth: None,
+                storage: None,
+                sources: vec![DrasiSourceConfig {
+                    id: "test-source".to_string(),
+                    source_type: "internal.application".to_string(),
+                    auto_start: true,
+                    properties: std::collections::HashMap::new(),
+                }],
+                queries: vec![],
+                reactions: vec![DrasiReactionConfig {
+                    id: "test-reaction".to_string(),
+                    reaction_type: "internal.application".to_string(),
+                    queries: vec![],
+                    auto_start: true,
+                    properties: std::collections::HashMap::new(),
+                }],
+                log_level: None,
+                extra: std::collections::HashMap::new(),
+            },
+        };
+
+        let run_config = TestRunDrasiServerConfig {
+            start_immediately: false,
+            test_id: Some("test".to_string()),
+            test_repo_id: Some("test_repo".to_string()),
+            test_run_id: Some("test_run_001".to_string()),
+            test_drasi_server_id: "test-server".to_string(),
+            test_run_overrides: Some(overrides),
+        };
+
+        let definition =
+            TestRunDrasiServerDefinition::new(run_config, test_drasi_server_def).unwrap();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let storage_path = temp_dir.path().to_path_buf();
+        let test_run_storage = test_data_store::test_run_storage::TestRunStorage {
+            id: definition.id.test_run_id.clone(),
+            path: storage_path.clone(),
+            queries_path: storage_path.join("queries"),
+            reactions_path: storage_path.join("reactions"),
+            sources_path: storage_path.join("sources"),
+            drasi_servers_path: storage_path.join("drasi_servers"),
+        };
+        let storage = test_run_storage
+            .get_drasi_server_storage(&definition.id, true)
+            .await
+            .unwrap();
+
+        let server = TestRunDrasiServer::new(definition, storage).await.unwrap();
+        (server, temp_dir)
+    }
+
+    fn overrides(
+        startup_timeout_seconds: Option<u64>,
+        require_all_handles: bool,
+    ) -> TestRunDrasiServerOverrides {
+        TestRunDrasiServerOverrides {
+            auth: None,
+            storage: None,
+            log_level: None,
+            startup_timeout_seconds,
+            require_all_handles,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drasi_server_startup_timeout() {
+        let (server, _temp_dir) = create_server(overrides(Some(1), false)).await;
+        assert_eq!(server.definition.startup_timeout(), Duration::from_secs(1));
+
+        // A stub core whose startup never completes, standing in for a hung DrasiServerCore.
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            server.start_with(|_| std::future::pending()),
+        )
+        .await
+        .expect("start should give up at the startup timeout");
+
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+        match server.get_state().await {
+            TestRunDrasiServerState::Error { message, .. } => {
+                assert!(message.contains("timed out"))
+            }
+            state => panic!("Expected server to be in error, but got {:?}", state),
+        }
+        assert!(server.start().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drasi_server_require_all_handles() {
+        // The stub core has no handle for the reaction.
+        let core = StubCore {
+            components: HashSet::from(["test-source".to_string()]),
+        };
+
+        // Without the flag, missing handles are only logged.
+        let (server, _temp_dir) = create_server(overrides(None, false)).await;
+        let collected =
+            collect_application_handles(&core, &server.definition.effective_config()).await;
+        assert_eq!(collected.handles.len(), 1);
+        assert_eq!(collected.missing, vec!["reaction 'test-reaction'"]);
+        {
+            let mut state = server.state.write().await;
+            assert!(server
+                .verify_application_handles(&mut state, &collected.missing)
+                .await
+                .is_ok());
+        }
+        assert_eq!(
+            server.get_state().await,
+            TestRunDrasiServerState::Uninitialized
+        );
+
+        // With the flag, the server moves to the Error state.
+        let (server, _temp_dir) = create_server(overrides(None, true)).await;
+        let collected =
+            collect_application_handles(&core, &server.definition.effective_config()).await;
+        {
+            let mut state = server.state.write().await;
+            let result = server
+                .verify_application_handles(&mut state, &collected.missing)
+                .await;
+            assert!(result.is_err());
+        }
+        match server.get_state().await {
+            TestRunDrasiServerState::Error { message, .. } => {
+                assert!(message.contains("reaction 'test-reaction'"));
+                assert!(!message.contains("test-source"));
+            }
+            state => panic!("Expected server to be in error, but got {:?}", state),
+        }
+        assert!(server.start().await.is_err());
+    }
 }
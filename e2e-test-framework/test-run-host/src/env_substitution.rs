@@ -0,0 +1,387 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Environment variable substitution for TestRun configs.
+//!
+//! `${VAR}` is replaced with the value of the environment variable `VAR`, and it is an
+//! error if `VAR` is not set. `${VAR:-default}` uses `default` when `VAR` is unset or
+//! empty, and `$${` produces a literal `${`. Substitution is only applied to the ids,
+//! endpoints and credentials of the configs, not to arbitrary JSON values, so it is
+//! clear which fields can reference the environment.
+
+use test_data_store::{
+    secret::Secret,
+    test_repo_storage::models::{
+        DrasiServerAuthConfig, DrasiServerStorageConfig, SourceChangeDispatcherDefinition,
+    },
+};
+
+use crate::{
+    drasi_servers::TestRunDrasiServerConfig, progress_webhook::ProgressWebhookConfig,
+    reactions::TestRunReactionConfig, sources::TestRunSourceConfig, TestRunConfig,
+};
+
+// Resolves the value of an environment variable, or None if it isn't set.
+pub type EnvLookup = dyn Fn(&str) -> Option<String>;
+
+pub fn process_env_lookup(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+pub trait SubstituteEnvVars {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()>;
+}
+
+pub fn substitute_env_vars_in_str(value: &str, lookup: &EnvLookup) -> anyhow::Result<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(dollar) = rest.find('$') {
+        result.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        if let Some(escaped) = after.strip_prefix("${") {
+            result.push_str("${");
+            rest = escaped;
+        } else if let Some(body) = after.strip_prefix('{') {
+            let Some(end) = body.find('}') else {
+                anyhow::bail!("Unterminated environment variable reference in {:?}", value);
+            };
+
+            let expression = &body[..end];
+            let (name, default) = match expression.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (expression, None),
+            };
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                anyhow::bail!(
+                    "Invalid environment variable name {:?} in {:?}",
+                    name,
+                    value
+                );
+            }
+
+            match (lookup(name), default) {
+                (Some(var_value), None) => result.push_str(&var_value),
+                (Some(var_value), Some(_)) if !var_value.is_empty() => result.push_str(&var_value),
+                (_, Some(default)) => result.push_str(default),
+                (None, None) => anyhow::bail!(
+                    "Environment variable {} is not set and has no default (use ${{{}:-default}})",
+                    name,
+                    name
+                ),
+            }
+            rest = &body[end + 1..];
+        } else {
+            result.push('$');
+            rest = after;
+        }
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+impl SubstituteEnvVars for String {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        if self.contains('$') {
+            *self = substitute_env_vars_in_str(self, lookup)?;
+        }
+        Ok(())
+    }
+}
+
+impl SubstituteEnvVars for Secret<String> {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        if self.expose_secret().contains('$') {
+            *self = Secret::new(substitute_env_vars_in_str(self.expose_secret(), lookup)?);
+        }
+        Ok(())
+    }
+}
+
+impl<T: SubstituteEnvVars> SubstituteEnvVars for Option<T> {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        match self {
+            Some(value) => value.substitute_env_vars(lookup),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: SubstituteEnvVars> SubstituteEnvVars for Vec<T> {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        for value in self.iter_mut() {
+            value.substitute_env_vars(lookup)?;
+        }
+        Ok(())
+    }
+}
+
+impl SubstituteEnvVars for TestRunConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        self.test_id.substitute_env_vars(lookup)?;
+        self.test_repo_id.substitute_env_vars(lookup)?;
+        self.test_run_id.substitute_env_vars(lookup)?;
+        self.progress_webhook.substitute_env_vars(lookup)?;
+        self.drasi_servers.substitute_env_vars(lookup)?;
+        self.reactions.substitute_env_vars(lookup)?;
+        self.sources.substitute_env_vars(lookup)
+    }
+}
+
+impl SubstituteEnvVars for ProgressWebhookConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        self.url.substitute_env_vars(lookup)
+    }
+}
+
+impl SubstituteEnvVars for TestRunSourceConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        self.test_source_id.substitute_env_vars(lookup)?;
+        self.test_id.substitute_env_vars(lookup)?;
+        self.test_repo_id.substitute_env_vars(lookup)?;
+        self.test_run_id.substitute_env_vars(lookup)?;
+        if let Some(overrides) = self.test_run_overrides.as_mut() {
+            overrides
+                .source_change_dispatchers
+                .substitute_env_vars(lookup)?;
+        }
+        Ok(())
+    }
+}
+
+impl SubstituteEnvVars for TestRunReactionConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        self.test_reaction_id.substitute_env_vars(lookup)?;
+        self.test_id.substitute_env_vars(lookup)?;
+        self.test_repo_id.substitute_env_vars(lookup)?;
+        self.test_run_id.substitute_env_vars(lookup)
+    }
+}
+
+impl SubstituteEnvVars for TestRunDrasiServerConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        self.test_drasi_server_id.substitute_env_vars(lookup)?;
+        self.test_id.substitute_env_vars(lookup)?;
+        self.test_repo_id.substitute_env_vars(lookup)?;
+        self.test_run_id.substitute_env_vars(lookup)?;
+        if let Some(overrides) = self.test_run_overrides.as_mut() {
+            overrides.auth.substitute_env_vars(lookup)?;
+            overrides.storage.substitute_env_vars(lookup)?;
+            overrides.log_level.substitute_env_vars(lookup)?;
+        }
+        Ok(())
+    }
+}
+
+impl SubstituteEnvVars for SourceChangeDispatcherDefinition {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        match self {
+            SourceChangeDispatcherDefinition::Console(_)
+            | SourceChangeDispatcherDefinition::JsonlFile(_) => Ok(()),
+            SourceChangeDispatcherDefinition::Dapr(def) => {
+                def.host.substitute_env_vars(lookup)?;
+                def.pubsub_name.substitute_env_vars(lookup)?;
+                def.pubsub_topic.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::Http(def) => {
+                def.url.substitute_env_vars(lookup)?;
+                def.endpoint.substitute_env_vars(lookup)?;
+                def.source_id.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::Grpc(def) => {
+                def.host.substitute_env_vars(lookup)?;
+                def.source_id.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::RedisStream(def) => {
+                def.host.substitute_env_vars(lookup)?;
+                def.stream_name.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::DrasiServerApi(def) => {
+                def.drasi_server_id.substitute_env_vars(lookup)?;
+                def.source_id.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::DrasiServerChannel(def) => {
+                def.drasi_server_id.substitute_env_vars(lookup)?;
+                def.source_id.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::LsnOrderValidator(def) => {
+                def.dispatcher.substitute_env_vars(lookup)
+            }
+            SourceChangeDispatcherDefinition::Postgres(def) => {
+                def.connection_string.substitute_env_vars(lookup)?;
+                def.schema.substitute_env_vars(lookup)
+            }
+        }
+    }
+}
+
+impl SubstituteEnvVars for DrasiServerAuthConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        match self {
+            DrasiServerAuthConfig::None => Ok(()),
+            DrasiServerAuthConfig::Basic { username, password } => {
+                username.substitute_env_vars(lookup)?;
+                password.substitute_env_vars(lookup)
+            }
+            DrasiServerAuthConfig::Token { token } => token.substitute_env_vars(lookup),
+            DrasiServerAuthConfig::OAuth2 {
+                provider_url,
+                client_id,
+                client_secret,
+                ..
+            } => {
+                provider_url.substitute_env_vars(lookup)?;
+                client_id.substitute_env_vars(lookup)?;
+                client_secret.substitute_env_vars(lookup)
+            }
+        }
+    }
+}
+
+impl SubstituteEnvVars for DrasiServerStorageConfig {
+    fn substitute_env_vars(&mut self, lookup: &EnvLookup) -> anyhow::Result<()> {
+        match self {
+            DrasiServerStorageConfig::Memory { .. } => Ok(()),
+            DrasiServerStorageConfig::File { path, .. } => path.substitute_env_vars(lookup),
+            DrasiServerStorageConfig::Redis { url, key_prefix } => {
+                url.substitute_env_vars(lookup)?;
+                key_prefix.substitute_env_vars(lookup)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn lookup_from(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_set_variable_is_substituted() {
+        let lookup = lookup_from(&[("HOST", "drasi.example.com"), ("PORT", "8080")]);
+
+        assert_eq!(
+            substitute_env_vars_in_str("http://${HOST}:${PORT}/api", &lookup).unwrap(),
+            "http://drasi.example.com:8080/api"
+        );
+        assert_eq!(
+            substitute_env_vars_in_str("${HOST:-localhost}", &lookup).unwrap(),
+            "drasi.example.com"
+        );
+    }
+
+    #[test]
+    fn test_unset_variable_with_default() {
+        let lookup = lookup_from(&[("EMPTY", "")]);
+
+        assert_eq!(
+            substitute_env_vars_in_str("http://${HOST:-localhost}:${PORT:-}", &lookup).unwrap(),
+            "http://localhost:"
+        );
+        assert_eq!(
+            substitute_env_vars_in_str("${EMPTY:-fallback}", &lookup).unwrap(),
+            "fallback"
+        );
+    }
+
+    #[test]
+    fn test_unset_variable_without_default_is_an_error() {
+        let lookup = lookup_from(&[]);
+
+        let err = substitute_env_vars_in_str("http://${HOST}/api", &lookup).unwrap_err();
+        assert!(err.to_string().contains("HOST is not set"));
+        assert!(substitute_env_vars_in_str("${HOST", &lookup).is_err());
+        assert!(substitute_env_vars_in_str("${BAD NAME}", &lookup).is_err());
+    }
+
+    #[test]
+    fn test_literal_dollars_are_kept() {
+        let lookup = lookup_from(&[]);
+
+        assert_eq!(
+            substitute_env_vars_in_str("cost $5, literal $${HOST}", &lookup).unwrap(),
+            "cost $5, literal ${HOST}"
+        );
+    }
+
+    #[test]
+    fn test_config_fields_are_substituted() {
+        let lookup = lookup_from(&[
+            ("RUN_ID", "run-042"),
+            ("PG_PASSWORD", "hunter2"),
+            ("INGEST_URL", "http://ingest:9000"),
+        ]);
+
+        let mut config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "repo-001",
+            "test_run_id": "${RUN_ID}",
+            "sources": [{
+                "test_source_id": "source-001",
+                "test_run_overrides": {
+                    "source_change_dispatchers": [
+                        {
+                            "kind": "Http",
+                            "url": "${INGEST_URL}",
+                            "port": 9000,
+                            "source_id": "${SOURCE_ID:-source-001}"
+                        },
+                        {
+                            "kind": "Postgres",
+                            "connection_string": "postgres://drasi:${PG_PASSWORD}@db/drasi",
+                            "tables": {}
+                        }
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        config.substitute_env_vars(&lookup).unwrap();
+
+        assert_eq!(config.test_run_id, "run-042");
+        let dispatchers = config.sources[0]
+            .test_run_overrides
+            .as_ref()
+            .unwrap()
+            .source_change_dispatchers
+            .as_ref()
+            .unwrap();
+        match &dispatchers[0] {
+            SourceChangeDispatcherDefinition::Http(def) => {
+                assert_eq!(def.url, "http://ingest:9000");
+                assert_eq!(def.source_id.as_deref(), Some("source-001"));
+            }
+            other => panic!("Unexpected dispatcher {:?}", other),
+        }
+        match &dispatchers[1] {
+            SourceChangeDispatcherDefinition::Postgres(def) => assert_eq!(
+                def.connection_string.expose_secret(),
+                "postgres://drasi:hunter2@db/drasi"
+            ),
+            other => panic!("Unexpected dispatcher {:?}", other),
+        }
+    }
+}
@@ -15,50 +15,78 @@
 use core::fmt;
 use std::{
     collections::{HashMap, HashSet},
+    future::Future,
     sync::Arc,
+    time::Duration,
 };
 
 use derive_more::Debug;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use drasi_servers::{
     TestRunDrasiServer, TestRunDrasiServerConfig, TestRunDrasiServerDefinition,
     TestRunDrasiServerState,
 };
+use env_substitution::{process_env_lookup, SubstituteEnvVars};
+use progress_webhook::{ProgressReporter, ProgressWebhookConfig, TestRunProgress};
 use queries::{
-    query_result_observer::QueryResultObserverCommandResponse,
-    result_stream_loggers::ResultStreamLoggerResult, TestRunQuery, TestRunQueryConfig,
-    TestRunQueryDefinition, TestRunQueryState,
+    query_result_observer::{QueryResultObserverCommandResponse, QueryResultObserverStatus},
+    result_stream_loggers::{ResultStreamLoggerResult, ResultStreamLoggerResultPage},
+    TestRunQuery, TestRunQueryConfig, TestRunQueryDefinition, TestRunQueryState,
 };
 use reactions::{
-    reaction_observer::ReactionObserverCommandResponse, TestRunReaction, TestRunReactionConfig,
-    TestRunReactionDefinition, TestRunReactionState,
+    reaction_observer::{ReactionObserverCommandResponse, ReactionObserverStatus},
+    TestRunReaction, TestRunReactionConfig, TestRunReactionDefinition, TestRunReactionState,
 };
 use sources::{
-    bootstrap_data_generators::BootstrapData, create_test_run_source,
-    source_change_generators::SourceChangeGeneratorCommandResponse, SourceStartMode, TestRunSource,
-    TestRunSourceConfig, TestRunSourceState,
+    bootstrap_data_generators::BootstrapData,
+    create_test_run_source,
+    source_change_generators::{
+        SourceChangeEventSummary, SourceChangeGeneratorCommandResponse,
+        SourceChangeGeneratorDescription, SourceChangeGeneratorStatus,
+    },
+    SourceStartMode, TestRunSource, TestRunSourceConfig, TestRunSourceState,
 };
+use summary_comparison::TestRunSummaryComparison;
 use test_data_store::{
-    test_repo_storage::models::SpacingMode,
+    test_repo_storage::models::{SpacingMode, TestReactionDefinition},
     test_run_storage::{
         TestRunDrasiServerId, TestRunId, TestRunQueryId, TestRunReactionId, TestRunSourceId,
+        TestRunSummaries,
     },
     TestDataStore,
 };
+use test_run_limits::{reaction_channel_capacity, source_channel_capacity};
+use utils::FieldNormalization;
 
 pub mod common;
 pub mod drasi_server_api_impl;
 pub mod drasi_servers;
+pub mod env_substitution;
 pub mod grpc_converters;
+pub mod progress_webhook;
 pub mod queries;
 pub mod reactions;
 pub mod sources;
+pub mod summary_comparison;
+pub mod test_run_config_builder;
+pub mod test_run_limits;
+pub mod test_run_matrix;
 pub mod utils;
 
 // Re-export api_models for use by test-service
 pub use drasi_servers::api_models;
+pub use test_run_config_builder::TestRunConfigBuilder;
+pub use test_run_limits::TestRunLimits;
+pub use test_run_matrix::{TestRunMatrixAxis, TestRunMatrixConfig};
+
+const TEST_RUN_COMPLETION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const QUERY_INITIAL_RESULTS_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_LIFECYCLE_CONCURRENCY: usize = 8;
+// Incremented when the layout of export_test_run's output changes incompatibly.
+const TEST_RUN_EXPORT_FORMAT_VERSION: u64 = 1;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TestRunConfig {
@@ -66,8 +94,14 @@ pub struct TestRunConfig {
     pub test_repo_id: String,
     pub test_run_id: String,
     #[serde(default)]
+    pub artifact_retention: ArtifactRetention,
+    #[serde(default)]
     pub drasi_servers: Vec<TestRunDrasiServerConfig>,
     #[serde(default)]
+    pub limits: TestRunLimits,
+    #[serde(default)]
+    pub progress_webhook: Option<ProgressWebhookConfig>,
+    #[serde(default)]
     pub queries: Vec<TestRunQueryConfig>,
     #[serde(default)]
     pub reactions: Vec<TestRunReactionConfig>,
@@ -78,13 +112,75 @@ pub struct TestRunConfig {
 #[derive(Debug)]
 pub struct TestRun {
     pub id: TestRunId,
+    pub artifact_retention: ArtifactRetention,
+    // Set once the artifact_retention has deleted the TestRun's output, which it can't be
+    // reset without.
+    pub output_deleted: bool,
+    // The effective configuration, including components added or removed after the TestRun
+    // was created. Used to export the TestRun.
+    pub config: TestRunConfig,
+    // The channel capacity allocated by each component, e.g. "Source source-001", which is
+    // checked against the max_channel_capacity limit.
+    pub channel_capacity: HashMap<String, usize>,
     pub drasi_servers: HashMap<String, TestRunDrasiServer>,
+    pub progress_reporter: Option<ProgressReporter>,
+    pub progress_webhook: Option<ProgressWebhookConfig>,
     pub queries: HashMap<String, TestRunQuery>,
     pub reactions: HashMap<String, TestRunReaction>,
     pub sources: HashMap<String, Box<dyn TestRunSource + Send + Sync>>,
     pub status: TestRunStatus,
 }
 
+impl TestRun {
+    // Returns the components that are in an Error state, e.g. "Source source-001".
+    pub async fn failed_components(&self) -> Vec<String> {
+        let mut failed = Vec::new();
+
+        for (id, source) in self.sources.iter() {
+            if let Ok(state) = source.get_state().await {
+                let errored =
+                    state.source_change_generator.status == SourceChangeGeneratorStatus::Error;
+                if errored || (state.stalled && state.fail_on_stall) {
+                    failed.push(format!("Source {}", id));
+                }
+            }
+        }
+
+        for (id, query) in self.queries.iter() {
+            if let Ok(state) = query.get_state().await {
+                if state.query_observer.status == QueryResultObserverStatus::Error {
+                    failed.push(format!("Query {}", id));
+                }
+            }
+        }
+
+        for (id, reaction) in self.reactions.iter() {
+            if let Ok(state) = reaction.get_state().await {
+                if state.reaction_observer.status == ReactionObserverStatus::Error {
+                    failed.push(format!("Reaction {}", id));
+                }
+            }
+        }
+
+        failed.sort();
+        failed
+    }
+
+    // Checks that the TestRun stays within its max_channel_capacity limit if the component
+    // allocates the given channel capacity.
+    fn check_channel_capacity(&self, component: &str, capacity: usize) -> anyhow::Result<()> {
+        let others: usize = self
+            .channel_capacity
+            .iter()
+            .filter(|(id, _)| id.as_str() != component)
+            .map(|(_, capacity)| capacity)
+            .sum();
+        self.config
+            .limits
+            .check_channel_capacity(&self.id, others + capacity)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TestRunStatus {
     Initialized,
@@ -93,12 +189,61 @@ pub enum TestRunStatus {
     Error(String),
 }
 
+impl TestRunStatus {
+    // A TestRun in a terminal status will not change status without an explicit request.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TestRunStatus::Stopped | TestRunStatus::Error(_))
+    }
+}
+
+// Whether a TestRun's output is kept once it is stopped, based on how it ended.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ArtifactRetention {
+    #[default]
+    Always,
+    OnFailure,
+    Never,
+}
+
+impl ArtifactRetention {
+    pub fn should_retain(&self, status: &TestRunStatus) -> bool {
+        match self {
+            ArtifactRetention::Always => true,
+            ArtifactRetention::OnFailure => matches!(status, TestRunStatus::Error(_)),
+            ArtifactRetention::Never => false,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct TestRunHostConfig {
+    // Maximum number of components of the same kind that are started or stopped at once
+    // when starting or stopping a TestRun.
+    #[serde(default)]
+    pub lifecycle_concurrency: Option<usize>,
+    // Normalizations applied to both TestRuns' summaries when they are compared.
+    #[serde(default)]
+    pub summary_normalizations: Vec<FieldNormalization>,
     #[serde(default)]
     pub test_runs: Vec<TestRunConfig>,
 }
 
+// Narrows the TestRunSources returned by get_test_sources. Fields that aren't set match
+// every TestRunSource.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct TestSourceFilter {
+    pub test_repo_id: Option<String>,
+    pub test_id: Option<String>,
+    pub test_run_id: Option<String>,
+    pub status: Option<SourceChangeGeneratorStatus>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TestSourceListEntry {
+    pub id: String,
+    pub status: SourceChangeGeneratorStatus,
+}
+
 // An enum that represents the current state of the TestRunHost.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TestRunHostStatus {
@@ -123,19 +268,45 @@ impl fmt::Display for TestRunHostStatus {
 #[derive(Debug)]
 pub struct TestRunHost {
     data_store: Arc<TestDataStore>,
+    lifecycle_concurrency: usize,
+    summary_normalizations: Vec<FieldNormalization>,
     test_runs: Arc<RwLock<HashMap<TestRunId, TestRun>>>,
     status: Arc<RwLock<TestRunHostStatus>>,
 }
 
 impl TestRunHost {
     pub async fn new(
-        config: TestRunHostConfig,
+        mut config: TestRunHostConfig,
         data_store: Arc<TestDataStore>,
     ) -> anyhow::Result<Self> {
         log::debug!("Creating TestRunHost from {:?}", config);
 
+        // Resolve ${ENV_VAR} references in every TestRunConfig before any component is created,
+        // so a missing variable doesn't leave a partially created TestRunHost.
+        for test_run_config in config.test_runs.iter_mut() {
+            test_run_config
+                .substitute_env_vars(&process_env_lookup)
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Invalid TestRunConfig {}.{}.{}: {}",
+                        test_run_config.test_repo_id,
+                        test_run_config.test_id,
+                        test_run_config.test_run_id,
+                        e
+                    )
+                })?;
+        }
+
+        let lifecycle_concurrency = match config.lifecycle_concurrency {
+            Some(0) => anyhow::bail!("TestRunHost lifecycle_concurrency must be greater than 0"),
+            Some(n) => n,
+            None => DEFAULT_LIFECYCLE_CONCURRENCY,
+        };
+
         let test_run_host = TestRunHost {
             data_store: data_store.clone(),
+            lifecycle_concurrency,
+            summary_normalizations: config.summary_normalizations,
             test_runs: Arc::new(RwLock::new(HashMap::new())),
             status: Arc::new(RwLock::new(TestRunHostStatus::Initialized)),
         };
@@ -176,9 +347,25 @@ impl TestRunHost {
             anyhow::bail!("TestRun already exists with ID: {:?}", test_run_id);
         }
 
+        config
+            .limits
+            .check_sources(&test_run_id, config.sources.len())?;
+        config
+            .limits
+            .check_queries(&test_run_id, config.queries.len())?;
+        config
+            .limits
+            .check_reactions(&test_run_id, config.reactions.len())?;
+
         let mut test_run = TestRun {
             id: test_run_id.clone(),
+            artifact_retention: config.artifact_retention,
+            output_deleted: false,
+            config: config.clone(),
+            channel_capacity: HashMap::new(),
             drasi_servers: HashMap::new(),
+            progress_reporter: None,
+            progress_webhook: config.progress_webhook,
             queries: HashMap::new(),
             reactions: HashMap::new(),
             sources: HashMap::new(),
@@ -222,15 +409,193 @@ impl TestRunHost {
         }
 
         test_run.status = TestRunStatus::Running;
+        self.start_progress_reporter(&mut test_run)?;
         test_runs_lock.insert(test_run_id.clone(), test_run);
 
         Ok(test_run_id)
     }
 
+    // Expands the matrix and adds a TestRun for every combination of its parameters. No
+    // TestRuns are added if the matrix is invalid or any of the derived IDs already exist, and
+    // if adding one of the TestRuns fails, the ones already added are deleted again.
+    pub async fn add_test_run_matrix(
+        &self,
+        matrix: TestRunMatrixConfig,
+    ) -> anyhow::Result<Vec<TestRunId>> {
+        let configs = matrix.expand()?;
+
+        {
+            let test_runs = self.test_runs.read().await;
+            for config in configs.iter() {
+                let test_run_id =
+                    TestRunId::new(&config.test_repo_id, &config.test_id, &config.test_run_id);
+                if test_runs.contains_key(&test_run_id) {
+                    anyhow::bail!("TestRun already exists with ID: {:?}", test_run_id);
+                }
+            }
+        }
+
+        let mut test_run_ids = Vec::new();
+        for config in configs {
+            match self.add_test_run(config).await {
+                Ok(test_run_id) => test_run_ids.push(test_run_id),
+                Err(e) => {
+                    for test_run_id in test_run_ids.iter() {
+                        if let Err(delete_error) = self.delete_test_run(test_run_id).await {
+                            log::error!(
+                                "Error deleting TestRun {} added by a failed matrix: {:?}",
+                                test_run_id,
+                                delete_error
+                            );
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(test_run_ids)
+    }
+
+    // Resolves everything the config references against the test definition, the same way
+    // add_test_run does, but without creating any components or TestRun storage. Returns an
+    // error for anything that doesn't resolve and a warning for anything that resolves but
+    // looks suspicious. The test definition is cached locally if it isn't already.
+    pub async fn validate_test_run(&self, config: &TestRunConfig) -> anyhow::Result<Vec<String>> {
+        let mut warnings = Vec::new();
+
+        let test_run_id =
+            TestRunId::new(&config.test_repo_id, &config.test_id, &config.test_run_id);
+        if self.test_runs.read().await.contains_key(&test_run_id) {
+            warnings.push(format!("TestRun already exists with ID: {}", test_run_id));
+        }
+
+        self.data_store
+            .add_remote_test(&config.test_repo_id, &config.test_id, false)
+            .await?;
+        let test_definition = self
+            .data_store
+            .get_test_definition(&config.test_repo_id, &config.test_id)
+            .await?;
+
+        let mut check_duplicate = |kind: &str, ids: Vec<&String>| {
+            let mut seen = HashSet::new();
+            for id in ids {
+                if !seen.insert(id) {
+                    warnings.push(format!(
+                        "{} {} is configured more than once, only the last is used",
+                        kind, id
+                    ));
+                }
+            }
+        };
+        check_duplicate(
+            "Drasi server",
+            config
+                .drasi_servers
+                .iter()
+                .map(|s| &s.test_drasi_server_id)
+                .collect(),
+        );
+        check_duplicate(
+            "Query",
+            config.queries.iter().map(|q| &q.test_query_id).collect(),
+        );
+        check_duplicate(
+            "Reaction",
+            config
+                .reactions
+                .iter()
+                .map(|r| &r.test_reaction_id)
+                .collect(),
+        );
+        check_duplicate(
+            "Source",
+            config.sources.iter().map(|s| &s.test_source_id).collect(),
+        );
+
+        for server_config in config.drasi_servers.iter() {
+            let test_drasi_server_id = &server_config.test_drasi_server_id;
+            let test_drasi_server_definition = test_definition
+                .drasi_servers
+                .iter()
+                .find(|s| &s.id == test_drasi_server_id)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Drasi server definition not found: {}",
+                        test_drasi_server_id
+                    )
+                })?
+                .clone();
+
+            let mut server_config = server_config.clone();
+            server_config.test_id = Some(config.test_id.clone());
+            server_config.test_repo_id = Some(config.test_repo_id.clone());
+            server_config.test_run_id = Some(config.test_run_id.clone());
+            TestRunDrasiServerDefinition::new(server_config, test_drasi_server_definition)?;
+        }
+
+        for query_config in config.queries.iter() {
+            let test_query_definition =
+                test_definition.get_test_query(&query_config.test_query_id)?;
+
+            if query_config.loggers.is_empty() {
+                warnings.push(format!(
+                    "Query {} has no loggers, its results won't be recorded",
+                    query_config.test_query_id
+                ));
+            }
+
+            let mut query_config = query_config.clone();
+            query_config.test_id = Some(config.test_id.clone());
+            query_config.test_repo_id = Some(config.test_repo_id.clone());
+            query_config.test_run_id = Some(config.test_run_id.clone());
+            TestRunQueryDefinition::new(query_config, test_query_definition)?;
+        }
+
+        for reaction_config in config.reactions.iter() {
+            let test_reaction_definition =
+                test_definition.get_test_reaction(&reaction_config.test_reaction_id)?;
+
+            if reaction_config.output_loggers.is_empty() {
+                warnings.push(format!(
+                    "Reaction {} has no output loggers, its invocations won't be recorded",
+                    reaction_config.test_reaction_id
+                ));
+            }
+
+            let mut reaction_config = reaction_config.clone();
+            reaction_config.test_id = Some(config.test_id.clone());
+            reaction_config.test_repo_id = Some(config.test_repo_id.clone());
+            reaction_config.test_run_id = Some(config.test_run_id.clone());
+
+            // Fails if the reaction has no output handler.
+            let reaction_handler_definitions = test_reaction_definition.handler_definitions();
+            let output_loggers = reaction_config.output_loggers.clone();
+            TestRunReactionDefinition::new(
+                reaction_config,
+                test_reaction_definition,
+                reaction_handler_definitions,
+                output_loggers,
+            )?;
+        }
+
+        for source_config in config.sources.iter() {
+            test_definition.get_test_source(&source_config.test_source_id)?;
+        }
+
+        if config.sources.is_empty() {
+            warnings
+                .push("TestRun has no sources, no source changes will be generated".to_string());
+        }
+
+        Ok(warnings)
+    }
+
     pub async fn initialize_sources(&self, self_ref: Arc<Self>) -> anyhow::Result<()> {
         log::info!("Initializing sources with TestRunHost reference");
 
         let test_runs = self.test_runs.read().await;
+        let mut auto_start_test_run_ids = Vec::new();
         for (test_run_id, test_run) in test_runs.iter() {
             // Set TestRunHost on all sources
             for (source_id, source) in test_run.sources.iter() {
@@ -270,21 +635,75 @@ impl TestRunHost {
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
             }
 
-            // Start auto-start sources AFTER reactions are ready
+            auto_start_test_run_ids.push(test_run_id.clone());
+        }
+        drop(test_runs);
+
+        // Start auto-start sources AFTER reactions are ready
+        for test_run_id in auto_start_test_run_ids {
+            log::info!(
+                "Auto-starting sources in test run {:?} (after reactions are ready)",
+                test_run_id
+            );
+            self.start_auto_sources(&test_run_id).await?;
+        }
+
+        Ok(())
+    }
+
+    // Starts the TestRun's Auto sources, at most lifecycle_concurrency at a time, delaying
+    // each by its start jitter so that multiple sources don't all begin generating changes
+    // at the same instant. The delays are computed up front and the test_runs lock is not
+    // held while waiting them out, so the jitter doesn't block other TestRunHost calls.
+    async fn start_auto_sources(&self, test_run_id: &TestRunId) -> anyhow::Result<()> {
+        let mut starts = Vec::new();
+        {
+            let test_runs = self.test_runs.read().await;
+            let test_run = test_runs
+                .get(test_run_id)
+                .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
+
             for (source_id, source) in test_run.sources.iter() {
                 let state = source.get_state().await?;
                 if state.start_mode == SourceStartMode::Auto {
-                    log::info!(
-                        "Auto-starting source {} in test run {:?} (after reactions are ready)",
-                        source_id,
-                        test_run_id
-                    );
-                    source.start_source_change_generator().await?;
+                    let delay = state
+                        .start_jitter_ms
+                        .map(|max_ms| sources::start_jitter_delay(&state.id, max_ms))
+                        .unwrap_or_default();
+                    starts.push((source_id.clone(), delay));
                 }
             }
         }
 
-        Ok(())
+        // Waiting for absolute deadlines in delay order means a source waiting out its
+        // delay never holds up a source with a shorter one.
+        starts.sort_by_key(|(_, delay)| *delay);
+        let jitter_start = tokio::time::Instant::now();
+
+        run_concurrently(
+            self.lifecycle_concurrency,
+            "start Source",
+            starts.into_iter().map(|(source_id, delay)| {
+                (source_id.clone(), async move {
+                    if !delay.is_zero() {
+                        log::debug!("Delaying start of source {} by {:?}", source_id, delay);
+                        tokio::time::sleep_until(jitter_start + delay).await;
+                    }
+
+                    let test_runs = self.test_runs.read().await;
+                    let source = test_runs
+                        .get(test_run_id)
+                        .and_then(|test_run| test_run.sources.get(&source_id))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("TestRunSource removed before it started")
+                        })?;
+
+                    log::info!("Auto-starting source {}", source_id);
+                    source.start_source_change_generator().await.map(|_| ())
+                })
+            }),
+        )
+        .await
     }
 
     async fn add_drasi_server_to_test_run(
@@ -360,6 +779,30 @@ impl TestRunHost {
         Ok(())
     }
 
+    // Expected results files are relative to the test folder, which the ReactionObserver
+    // doesn't know about, so the path is made absolute here.
+    async fn resolve_expected_results_file(
+        &self,
+        mut test_reaction_definition: TestReactionDefinition,
+        test_repo_id: &str,
+        test_id: &str,
+    ) -> anyhow::Result<TestReactionDefinition> {
+        if let Some(file) = test_reaction_definition
+            .expected_results
+            .as_mut()
+            .and_then(|expected_results| expected_results.file.as_mut())
+        {
+            let test_path = self
+                .data_store
+                .get_test_storage(test_repo_id, test_id)
+                .await?
+                .path;
+            *file = test_path.join(&*file).to_string_lossy().to_string();
+        }
+
+        Ok(test_reaction_definition)
+    }
+
     async fn add_reaction_to_test_run(
         &self,
         test_run: &mut TestRun,
@@ -382,23 +825,24 @@ impl TestRunHost {
             )
             .await?;
 
-        let test_reaction_definition = test_definition.get_test_reaction(&test_reaction_id)?;
+        let test_reaction_definition = self
+            .resolve_expected_results_file(
+                test_definition.get_test_reaction(&test_reaction_id)?,
+                test_run_reaction.test_repo_id.as_ref().unwrap(),
+                test_run_reaction.test_id.as_ref().unwrap(),
+            )
+            .await?;
 
-        let reaction_handler_definition = test_reaction_definition
-            .output_handler
-            .clone()
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No reaction handler defined for reaction {}",
-                    test_reaction_id
-                )
-            })?;
+        let reaction_handler_definitions = test_reaction_definition.handler_definitions();
+        let component = format!("Reaction {}", test_reaction_id);
+        let capacity = reaction_channel_capacity(&reaction_handler_definitions);
+        test_run.check_channel_capacity(&component, capacity)?;
 
         let output_loggers = test_run_reaction.output_loggers.clone();
         let definition = TestRunReactionDefinition::new(
             test_run_reaction,
             test_reaction_definition.clone(),
-            reaction_handler_definition,
+            reaction_handler_definitions,
             output_loggers,
         )?;
 
@@ -406,6 +850,7 @@ impl TestRunHost {
         let output_storage = self.data_store.get_test_run_reaction_storage(&id).await?;
         let test_run_reaction = TestRunReaction::new(definition, output_storage).await?;
 
+        test_run.channel_capacity.insert(component, capacity);
         test_run
             .reactions
             .insert(test_reaction_id, test_run_reaction);
@@ -432,6 +877,10 @@ impl TestRunHost {
             .get_test_source_definition_for_test_run_source(&id)
             .await?;
 
+        let component = format!("Source {}", test_source_id);
+        let capacity = source_channel_capacity(&test_source_definition, &test_run_config);
+        test_run.check_channel_capacity(&component, capacity)?;
+
         let input_storage = self
             .data_store
             .get_test_source_storage_for_test_run_source(&id)
@@ -446,6 +895,7 @@ impl TestRunHost {
         )
         .await?;
 
+        test_run.channel_capacity.insert(component, capacity);
         test_run.sources.insert(test_source_id, test_run_source);
         Ok(())
     }
@@ -481,6 +931,10 @@ impl TestRunHost {
                 query_id
             );
         }
+        test_run
+            .config
+            .limits
+            .check_queries(test_run_id, test_run.queries.len() + 1)?;
 
         // Get the TestRepoStorage that is associated with the Repo for the TestRunQuery
         let repo = self
@@ -494,6 +948,7 @@ impl TestRunHost {
             .get_test_query_definition_for_test_run_query(&id)
             .await?;
 
+        let query_config = test_run_query.clone();
         let definition = TestRunQueryDefinition::new(test_run_query, test_query_definition)?;
         log::trace!("TestRunQueryDefinition: {:?}", &definition);
 
@@ -505,6 +960,7 @@ impl TestRunHost {
         let test_run_query_obj = TestRunQuery::new(definition, output_storage).await?;
 
         test_run.queries.insert(query_id, test_run_query_obj);
+        test_run.config.queries.push(query_config);
 
         Ok(id)
     }
@@ -540,6 +996,10 @@ impl TestRunHost {
                 reaction_id
             );
         }
+        test_run
+            .config
+            .limits
+            .check_reactions(test_run_id, test_run.reactions.len() + 1)?;
 
         // Get the TestRepoStorage that is associated with the Repo for the TestRunReaction
         let repo = self
@@ -558,22 +1018,27 @@ impl TestRunHost {
             )
             .await?;
 
-        let test_reaction_definition = test_definition.get_test_reaction(&reaction_id)?;
+        let test_reaction_definition = self
+            .resolve_expected_results_file(
+                test_definition.get_test_reaction(&reaction_id)?,
+                test_run_reaction.test_repo_id.as_ref().unwrap(),
+                test_run_reaction.test_id.as_ref().unwrap(),
+            )
+            .await?;
 
-        let reaction_handler_definition = test_reaction_definition
-            .output_handler
-            .clone()
-            .ok_or_else(|| {
-                anyhow::anyhow!("No reaction handler defined for reaction {}", reaction_id)
-            })?;
+        let reaction_handler_definitions = test_reaction_definition.handler_definitions();
+        let component = format!("Reaction {}", reaction_id);
+        let capacity = reaction_channel_capacity(&reaction_handler_definitions);
+        test_run.check_channel_capacity(&component, capacity)?;
 
         // Get output_loggers from the config
         let output_loggers = test_run_reaction.output_loggers.clone();
+        let reaction_config = test_run_reaction.clone();
 
         let definition = TestRunReactionDefinition::new(
             test_run_reaction,
             test_reaction_definition.clone(),
-            reaction_handler_definition,
+            reaction_handler_definitions,
             output_loggers,
         )?;
         log::trace!("TestRunReactionDefinition: {:?}", &definition);
@@ -585,9 +1050,11 @@ impl TestRunHost {
         // Create the TestRunReaction and add it to the TestRun.
         let test_run_reaction_obj = TestRunReaction::new(definition, output_storage).await?;
 
+        test_run.channel_capacity.insert(component, capacity);
         test_run
             .reactions
             .insert(reaction_id, test_run_reaction_obj);
+        test_run.config.reactions.push(reaction_config);
 
         Ok(id)
     }
@@ -612,17 +1079,12 @@ impl TestRunHost {
         let source_id = test_run_config.test_source_id.clone();
         let id = TestRunSourceId::new(test_run_id, &source_id);
 
-        let mut test_runs_lock = self.test_runs.write().await;
-        let test_run = test_runs_lock
-            .get_mut(test_run_id)
-            .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
-
-        if test_run.sources.contains_key(&source_id) {
-            anyhow::bail!(
-                "TestRun already contains TestRunSource with ID: {}",
-                source_id
-            );
-        }
+        // Fail fast before resolving storage, which can involve downloading test data.
+        Self::check_test_source_can_be_added(
+            &self.test_runs.read().await,
+            test_run_id,
+            &source_id,
+        )?;
 
         // Get the TestRepoStorage that is associated with the Repo for the TestRunSource
         let repo = self
@@ -647,7 +1109,10 @@ impl TestRunHost {
         // This is where the TestRunSource will write the output to.
         let output_storage = self.data_store.get_test_run_source_storage(&id).await?;
 
-        // Create the TestRunSource and add it to the TestRun.
+        // Create the TestRunSource without holding the lock, so other TestRuns aren't blocked
+        // while it is built.
+        let component = format!("Source {}", source_id);
+        let capacity = source_channel_capacity(&test_source_definition, &test_run_config);
         let test_run_source = create_test_run_source(
             &test_run_config,
             &test_source_definition,
@@ -655,11 +1120,102 @@ impl TestRunHost {
             output_storage,
         )
         .await?;
+
+        // Another add_test_source call for the same ID may have completed while the
+        // TestRunSource was being built, so check again under the write lock before adding it.
+        let mut test_runs_lock = self.test_runs.write().await;
+        Self::check_test_source_can_be_added(&test_runs_lock, test_run_id, &source_id)?;
+        let test_run = test_runs_lock
+            .get_mut(test_run_id)
+            .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
+        test_run.check_channel_capacity(&component, capacity)?;
+
+        test_run.channel_capacity.insert(component, capacity);
         test_run.sources.insert(source_id, test_run_source);
+        test_run.config.sources.push(test_run_config);
 
         Ok(id)
     }
 
+    fn check_test_source_can_be_added(
+        test_runs: &HashMap<TestRunId, TestRun>,
+        test_run_id: &TestRunId,
+        source_id: &str,
+    ) -> anyhow::Result<()> {
+        let test_run = test_runs
+            .get(test_run_id)
+            .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
+
+        if test_run.sources.contains_key(source_id) {
+            anyhow::bail!(
+                "TestRun already contains TestRunSource with ID: {}",
+                source_id
+            );
+        }
+        test_run
+            .config
+            .limits
+            .check_sources(test_run_id, test_run.sources.len() + 1)
+    }
+
+    pub async fn remove_test_source(
+        &self,
+        test_run_source_id: &TestRunSourceId,
+    ) -> anyhow::Result<()> {
+        log::trace!("Removing TestRunSource {:?}", test_run_source_id);
+
+        let mut test_runs_lock = self.test_runs.write().await;
+        let test_run = test_runs_lock
+            .get_mut(&test_run_source_id.test_run_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!("TestRun not found: {:?}", test_run_source_id.test_run_id)
+            })?;
+
+        let status = match test_run.sources.get(&test_run_source_id.test_source_id) {
+            Some(source) => source.get_state().await?.source_change_generator.status,
+            None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
+        };
+
+        // A source that is mid-skip or mid-step must be paused before it can be removed.
+        if matches!(
+            status,
+            SourceChangeGeneratorStatus::Skipping | SourceChangeGeneratorStatus::Stepping
+        ) {
+            anyhow::bail!(
+                "TestRunSource {:?} is {:?}; pause it before removing it",
+                test_run_source_id,
+                status
+            );
+        }
+
+        let source = test_run
+            .sources
+            .remove(&test_run_source_id.test_source_id)
+            .unwrap();
+        test_run
+            .config
+            .sources
+            .retain(|source| source.test_source_id != test_run_source_id.test_source_id);
+        test_run
+            .channel_capacity
+            .remove(&format!("Source {}", test_run_source_id.test_source_id));
+        drop(test_runs_lock);
+
+        // Stop the change generator so its dispatchers are closed and timers cancelled.
+        if matches!(
+            status,
+            SourceChangeGeneratorStatus::Running | SourceChangeGeneratorStatus::Paused
+        ) {
+            source.stop_source_change_generator().await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn contains_test_run(&self, test_run_id: &TestRunId) -> anyhow::Result<bool> {
+        Ok(self.test_runs.read().await.contains_key(test_run_id))
+    }
+
     pub async fn contains_test_source(&self, test_run_source_id: &str) -> anyhow::Result<bool> {
         let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
         let test_runs = self.test_runs.read().await;
@@ -676,6 +1232,74 @@ impl TestRunHost {
         Ok(self.status.read().await.clone())
     }
 
+    // Returns the ID of a component in an Error state, or "TestRunHost" if the TestRunHost
+    // isn't Running. A component whose state can't be read, such as a generator whose thread
+    // failed to initialize, is a failure too. The check stops at the first failure so it is
+    // cheap enough to use as a readiness probe. If strict, Drasi Servers in an Error state are
+    // failures too.
+    pub async fn find_unhealthy_component(&self, strict: bool) -> anyhow::Result<Option<String>> {
+        if self.get_status().await? != TestRunHostStatus::Running {
+            return Ok(Some("TestRunHost".to_string()));
+        }
+
+        let test_runs = self.test_runs.read().await;
+        for test_run in test_runs.values() {
+            for (id, source) in test_run.sources.iter() {
+                let unhealthy = match source.get_state().await {
+                    Ok(state) => {
+                        state.source_change_generator.status == SourceChangeGeneratorStatus::Error
+                    }
+                    // A source with no generator has no generator state to read.
+                    Err(_) if source.describe_source_change_generator().is_err() => false,
+                    Err(e) => {
+                        log::warn!("Error getting state of TestRunSource {}: {:?}", id, e);
+                        true
+                    }
+                };
+                if unhealthy {
+                    return Ok(Some(TestRunSourceId::new(&test_run.id, id).to_string()));
+                }
+            }
+
+            for (id, query) in test_run.queries.iter() {
+                let unhealthy = match query.get_state().await {
+                    Ok(state) => state.query_observer.status == QueryResultObserverStatus::Error,
+                    Err(e) => {
+                        log::warn!("Error getting state of TestRunQuery {}: {:?}", id, e);
+                        true
+                    }
+                };
+                if unhealthy {
+                    return Ok(Some(TestRunQueryId::new(&test_run.id, id).to_string()));
+                }
+            }
+
+            for (id, reaction) in test_run.reactions.iter() {
+                let unhealthy = match reaction.get_state().await {
+                    Ok(state) => state.reaction_observer.status == ReactionObserverStatus::Error,
+                    Err(e) => {
+                        log::warn!("Error getting state of TestRunReaction {}: {:?}", id, e);
+                        true
+                    }
+                };
+                if unhealthy {
+                    return Ok(Some(TestRunReactionId::new(&test_run.id, id).to_string()));
+                }
+            }
+
+            if strict {
+                for (id, server) in test_run.drasi_servers.iter() {
+                    if let TestRunDrasiServerState::Error { .. } = server.get_state().await {
+                        let server_id = TestRunDrasiServerId::new(&test_run.id, id);
+                        return Ok(Some(server_id.to_string()));
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     pub async fn get_source_bootstrap_data(
         &self,
         test_run_source_id: &str,
@@ -726,6 +1350,30 @@ impl TestRunHost {
         }
     }
 
+    // Waits until the query's result observer has recorded at least min_count bootstrap
+    // results, returning the number recorded. Use it after bootstrap to check that Drasi
+    // produced the expected initial result set before sources start streaming changes.
+    // Fails on timeout, or if the observer stops or errors before reaching min_count.
+    pub async fn await_query_initial_results(
+        &self,
+        test_run_query_id: &str,
+        min_count: u64,
+        timeout: Duration,
+    ) -> anyhow::Result<u64> {
+        await_initial_results(test_run_query_id, min_count, timeout, || async {
+            let observer = self
+                .get_test_query_state(test_run_query_id)
+                .await?
+                .query_observer;
+            let count = observer
+                .result_summary
+                .observer_metrics
+                .result_stream_bootstrap_record_count;
+            Ok((count, observer.status))
+        })
+        .await
+    }
+
     pub async fn get_test_query_result_logger_output(
         &self,
         test_run_query_id: &str,
@@ -744,6 +1392,32 @@ impl TestRunHost {
         }
     }
 
+    // Returns up to limit of the query's logger results starting at offset, so large outputs
+    // can be fetched a page at a time. The offset is clamped to the available results.
+    pub async fn get_test_query_result_logger_output_page(
+        &self,
+        test_run_query_id: &str,
+        offset: i64,
+        limit: Option<usize>,
+    ) -> anyhow::Result<ResultStreamLoggerResultPage> {
+        let test_run_query_id = TestRunQueryId::try_from(test_run_query_id)?;
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(&test_run_query_id.test_run_id) {
+            Some(test_run) => match test_run.queries.get(&test_run_query_id.test_query_id) {
+                Some(query) => {
+                    let state = query.get_query_result_observer_state().await?;
+                    Ok(ResultStreamLoggerResultPage::new(
+                        &state.logger_results,
+                        offset,
+                        limit,
+                    ))
+                }
+                None => anyhow::bail!("TestRunQuery not found: {:?}", test_run_query_id),
+            },
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_query_id.test_run_id),
+        }
+    }
+
     pub async fn get_test_source_ids(&self) -> anyhow::Result<Vec<String>> {
         let mut ids = Vec::new();
         let test_runs = self.test_runs.read().await;
@@ -755,24 +1429,90 @@ impl TestRunHost {
         Ok(ids)
     }
 
-    pub async fn get_test_source_state(
+    // Lists the TestRunSources that match the filter, along with the status of their
+    // source change generators. The results are sorted by ID.
+    pub async fn get_test_sources(
+        &self,
+        filter: &TestSourceFilter,
+    ) -> anyhow::Result<Vec<TestSourceListEntry>> {
+        let matches = |value: &String, expected: &Option<String>| {
+            expected.as_ref().is_none_or(|expected| value == expected)
+        };
+
+        let mut sources = Vec::new();
+        let test_runs = self.test_runs.read().await;
+        for test_run in test_runs.values() {
+            if !matches(&test_run.id.test_repo_id, &filter.test_repo_id)
+                || !matches(&test_run.id.test_id, &filter.test_id)
+                || !matches(&test_run.id.test_run_id, &filter.test_run_id)
+            {
+                continue;
+            }
+
+            for (source_id, source) in test_run.sources.iter() {
+                let status = source.get_state().await?.source_change_generator.status;
+                if filter.status.is_none_or(|expected| status == expected) {
+                    sources.push(TestSourceListEntry {
+                        id: format!("{}.{}", test_run.id, source_id),
+                        status,
+                    });
+                }
+            }
+        }
+        sources.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(sources)
+    }
+
+    // Describes what the TestRunSource's generator will do, based on its configuration.
+    pub async fn get_test_source_description(
         &self,
         test_run_source_id: &str,
-    ) -> anyhow::Result<TestRunSourceState> {
+    ) -> anyhow::Result<SourceChangeGeneratorDescription> {
         let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
         let test_runs = self.test_runs.read().await;
         match test_runs.get(&test_run_source_id.test_run_id) {
             Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
-                Some(source) => source.get_state().await,
+                Some(source) => source.describe_source_change_generator(),
                 None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
             },
             None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
         }
     }
 
-    async fn set_status(&self, status: TestRunHostStatus) {
-        let mut write_lock = self.status.write().await;
-        *write_lock = status.clone();
+    // Subscribes to summaries of the SourceChangeEvents the TestRunSource dispatches from now on.
+    pub async fn subscribe_test_source_events(
+        &self,
+        test_run_source_id: &str,
+    ) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(&test_run_source_id.test_run_id) {
+            Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
+                Some(source) => source.subscribe_source_change_events(),
+                None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
+            },
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
+        }
+    }
+
+    pub async fn get_test_source_state(
+        &self,
+        test_run_source_id: &str,
+    ) -> anyhow::Result<TestRunSourceState> {
+        let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(&test_run_source_id.test_run_id) {
+            Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
+                Some(source) => source.get_state().await,
+                None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
+            },
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
+        }
+    }
+
+    async fn set_status(&self, status: TestRunHostStatus) {
+        let mut write_lock = self.status.write().await;
+        *write_lock = status.clone();
     }
 
     pub async fn test_query_pause(
@@ -951,15 +1691,18 @@ impl TestRunHost {
         }
     }
 
+    // Resets the TestRunSource's generator. If a seed is given, generators that produce random
+    // changes use it in place of their configured seed, so a run can be reproduced.
     pub async fn test_source_reset(
         &self,
         test_run_source_id: &str,
+        seed: Option<u64>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
         let test_runs = self.test_runs.read().await;
         match test_runs.get(&test_run_source_id.test_run_id) {
             Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
-                Some(source) => source.reset_source_change_generator().await,
+                Some(source) => source.reset_source_change_generator(seed).await,
                 None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
             },
             None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
@@ -1023,6 +1766,45 @@ impl TestRunHost {
         }
     }
 
+    // Steps the TestRunSource until its event sequence number reaches target_seq, then pauses it.
+    pub async fn test_source_step_to(
+        &self,
+        test_run_source_id: &str,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(&test_run_source_id.test_run_id) {
+            Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
+                Some(source) => {
+                    source
+                        .step_to_source_change_generator(target_seq, spacing_mode)
+                        .await
+                }
+                None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
+            },
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
+        }
+    }
+
+    // Skips all of the TestRunSource's remaining changes without dispatching them. The source
+    // must be paused first.
+    pub async fn test_source_skip_to_end(
+        &self,
+        test_run_source_id: &str,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        let test_run_source_id = TestRunSourceId::try_from(test_run_source_id)?;
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(&test_run_source_id.test_run_id) {
+            Some(test_run) => match test_run.sources.get(&test_run_source_id.test_source_id) {
+                Some(source) => source.skip_to_end_source_change_generator().await,
+                None => anyhow::bail!("TestRunSource not found: {:?}", test_run_source_id),
+            },
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_source_id.test_run_id),
+        }
+    }
+
     pub async fn test_source_stop(
         &self,
         test_run_source_id: &str,
@@ -1088,6 +1870,7 @@ impl TestRunHost {
             .ok_or_else(|| anyhow::anyhow!("Drasi server definition not found: {}", server_id))?
             .clone();
 
+        let server_config = test_run_drasi_server.clone();
         let definition =
             TestRunDrasiServerDefinition::new(test_run_drasi_server, test_drasi_server_definition)?;
         log::trace!("TestRunDrasiServerDefinition: {:?}", &definition);
@@ -1104,6 +1887,7 @@ impl TestRunHost {
         test_run
             .drasi_servers
             .insert(server_id, test_run_drasi_server_obj);
+        test_run.config.drasi_servers.push(server_config);
 
         Ok(id)
     }
@@ -1136,6 +1920,10 @@ impl TestRunHost {
                     .drasi_servers
                     .remove(&test_run_drasi_server_id.test_drasi_server_id)
                 {
+                    test_run.config.drasi_servers.retain(|server| {
+                        server.test_drasi_server_id != test_run_drasi_server_id.test_drasi_server_id
+                    });
+
                     // Stop the server if it's running
                     if matches!(
                         server.get_state().await,
@@ -1210,80 +1998,353 @@ impl TestRunHost {
         }
     }
 
+    // Waits until the TestRun reaches a terminal status or the timeout elapses, returning
+    // the last observed status. Callers should use TestRunStatus::is_terminal to tell the
+    // two outcomes apart.
+    pub async fn wait_for_test_run_completion(
+        &self,
+        test_run_id: &TestRunId,
+        timeout: Duration,
+    ) -> anyhow::Result<TestRunStatus> {
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        loop {
+            let status = self.get_test_run_status(test_run_id).await?;
+            if status.is_terminal() || tokio::time::Instant::now() >= deadline {
+                return Ok(status);
+            }
+
+            tokio::time::sleep_until(std::cmp::min(
+                deadline,
+                tokio::time::Instant::now() + TEST_RUN_COMPLETION_POLL_INTERVAL,
+            ))
+            .await;
+        }
+    }
+
+    pub async fn get_test_run_summaries(
+        &self,
+        test_run_id: &TestRunId,
+    ) -> anyhow::Result<TestRunSummaries> {
+        if !self.test_runs.read().await.contains_key(test_run_id) {
+            anyhow::bail!("TestRun not found: {:?}", test_run_id);
+        }
+
+        self.data_store
+            .get_test_run_storage(test_run_id)
+            .await?
+            .get_test_run_summaries()
+            .await
+    }
+
+    // Compares the summaries written by two TestRuns, typically a baseline and a candidate,
+    // flagging any metric whose relative change exceeds threshold_pct once the summaries have
+    // been normalized.
+    pub async fn compare_summaries(
+        &self,
+        run_a: &TestRunId,
+        run_b: &TestRunId,
+        threshold_pct: f64,
+    ) -> anyhow::Result<TestRunSummaryComparison> {
+        let summaries_a = self.get_test_run_summaries(run_a).await?;
+        let summaries_b = self.get_test_run_summaries(run_b).await?;
+
+        Ok(summary_comparison::compare_summaries(
+            run_a.clone(),
+            &summaries_a,
+            run_b.clone(),
+            &summaries_b,
+            threshold_pct,
+            &self.summary_normalizations,
+        ))
+    }
+
     pub async fn start_test_run(&self, test_run_id: &TestRunId) -> anyhow::Result<()> {
-        let mut test_runs = self.test_runs.write().await;
-        match test_runs.get_mut(test_run_id) {
+        let test_runs = self.test_runs.read().await;
+        match test_runs.get(test_run_id) {
             Some(test_run) => {
+                let limit = self.lifecycle_concurrency;
+
                 // Start drasi servers first
-                for server in test_run.drasi_servers.values() {
-                    if matches!(
-                        server.get_state().await,
-                        TestRunDrasiServerState::Uninitialized { .. }
-                    ) {
-                        server.start().await?;
-                    }
-                }
+                run_concurrently(
+                    limit,
+                    "start Drasi Server",
+                    test_run.drasi_servers.iter().map(|(id, server)| {
+                        (id.clone(), async move {
+                            if matches!(
+                                server.get_state().await,
+                                TestRunDrasiServerState::Uninitialized { .. }
+                            ) {
+                                server.start().await?;
+                            }
+                            Ok::<(), anyhow::Error>(())
+                        })
+                    }),
+                )
+                .await?;
+            }
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_id),
+        }
+        drop(test_runs);
 
-                // Start sources
-                for source in test_run.sources.values() {
-                    let state = source.get_state().await?;
-                    if state.start_mode == SourceStartMode::Auto {
-                        source.start_source_change_generator().await?;
-                    }
-                }
+        // Start sources without holding the lock, since their start jitter can take a while.
+        self.start_auto_sources(test_run_id).await?;
+
+        let mut test_runs = self.test_runs.write().await;
+        match test_runs.get_mut(test_run_id) {
+            Some(test_run) => {
+                let limit = self.lifecycle_concurrency;
 
                 // Start queries
-                for query in test_run.queries.values() {
-                    query.start_query_result_observer().await?;
-                }
+                run_concurrently(
+                    limit,
+                    "start Query",
+                    test_run.queries.iter().map(|(id, query)| {
+                        (id.clone(), async move {
+                            query.start_query_result_observer().await.map(|_| ())
+                        })
+                    }),
+                )
+                .await?;
 
                 // Start reactions
-                for reaction in test_run.reactions.values() {
-                    if reaction.start_immediately {
-                        reaction.start_reaction_observer().await?;
-                    }
-                }
+                run_concurrently(
+                    limit,
+                    "start Reaction",
+                    test_run
+                        .reactions
+                        .iter()
+                        .filter(|(_, reaction)| reaction.start_immediately)
+                        .map(|(id, reaction)| {
+                            (id.clone(), async move {
+                                reaction.start_reaction_observer().await.map(|_| ())
+                            })
+                        }),
+                )
+                .await?;
 
                 test_run.status = TestRunStatus::Running;
+                self.start_progress_reporter(test_run)?;
                 Ok(())
             }
             None => anyhow::bail!("TestRun not found: {:?}", test_run_id),
         }
     }
 
+    // Starts the TestRun's progress webhook, if it has one and it isn't already running.
+    fn start_progress_reporter(&self, test_run: &mut TestRun) -> anyhow::Result<()> {
+        if test_run.progress_reporter.is_none() {
+            if let Some(config) = &test_run.progress_webhook {
+                test_run.progress_reporter = Some(ProgressReporter::start(
+                    config.clone(),
+                    test_run.id.clone(),
+                    self.test_runs.clone(),
+                )?);
+            }
+        }
+        Ok(())
+    }
+
     pub async fn stop_test_run(&self, test_run_id: &TestRunId) -> anyhow::Result<()> {
         let mut test_runs = self.test_runs.write().await;
-        match test_runs.get_mut(test_run_id) {
+        let (result, delete_output) = match test_runs.get_mut(test_run_id) {
             Some(test_run) => {
-                // Stop reactions first
-                for reaction in test_run.reactions.values() {
-                    reaction.stop_reaction_observer().await?;
+                let was_terminal = test_run.status.is_terminal();
+                let result = self.stop_test_run_components(test_run).await;
+
+                // Stopping an already stopped TestRun doesn't change how it ended.
+                if !was_terminal {
+                    // The TestRun failed if a component couldn't be stopped or is in an Error state.
+                    let mut failures = test_run.failed_components().await;
+                    if let Err(e) = &result {
+                        failures.insert(0, format!("{:?}", e));
+                    }
+                    test_run.status = if failures.is_empty() {
+                        TestRunStatus::Stopped
+                    } else {
+                        TestRunStatus::Error(failures.join("; "))
+                    };
+
+                    if let Some(reporter) = test_run.progress_reporter.take() {
+                        let progress =
+                            TestRunProgress::collect(test_run, reporter.elapsed(), true).await;
+                        reporter.finish(progress);
+                    }
                 }
 
-                // Stop queries
-                for query in test_run.queries.values() {
-                    query.stop_query_result_observer().await?;
-                }
+                // The output is deleted after the lock is released, so deleting a large run
+                // doesn't hold up every other TestRun.
+                let delete_output = !was_terminal && Self::mark_output_for_deletion(test_run);
+                (result, delete_output.then(|| test_run.status.clone()))
+            }
+            None => anyhow::bail!("TestRun not found: {:?}", test_run_id),
+        };
+        drop(test_runs);
 
-                // Stop sources
-                for source in test_run.sources.values() {
-                    source.stop_source_change_generator().await?;
-                }
+        if let Some(status) = delete_output {
+            self.delete_test_run_output(test_run_id, &status).await;
+        }
+
+        result
+    }
+
+    // Resets every source change generator, query result observer and reaction observer of a
+    // stopped TestRun to its initial state, so the TestRun can be started again from scratch.
+    // Every component is reset even if others fail; the failures are combined into one error.
+    // The TestRun stays Stopped.
+    pub async fn reset_test_run(&self, test_run_id: &TestRunId) -> anyhow::Result<()> {
+        let test_runs = self.test_runs.write().await;
+        let test_run = test_runs
+            .get(test_run_id)
+            .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
+
+        if test_run.status != TestRunStatus::Stopped {
+            anyhow::bail!(
+                "TestRun {} must be Stopped before it can be reset; it is {:?}",
+                test_run_id,
+                test_run.status
+            );
+        }
+
+        if test_run.output_deleted {
+            anyhow::bail!(
+                "TestRun {} can't be reset because its output was deleted by its artifact_retention {:?}",
+                test_run_id,
+                test_run.artifact_retention
+            );
+        }
+
+        let limit = self.lifecycle_concurrency;
+        let results = [
+            run_concurrently(
+                limit,
+                "reset Source",
+                test_run.sources.iter().map(|(id, source)| {
+                    (id.clone(), async move {
+                        source.reset_source_change_generator(None).await.map(|_| ())
+                    })
+                }),
+            )
+            .await,
+            run_concurrently(
+                limit,
+                "reset Query",
+                test_run.queries.iter().map(|(id, query)| {
+                    (id.clone(), async move {
+                        query.reset_query_result_observer().await.map(|_| ())
+                    })
+                }),
+            )
+            .await,
+            run_concurrently(
+                limit,
+                "reset Reaction",
+                test_run.reactions.iter().map(|(id, reaction)| {
+                    (id.clone(), async move {
+                        reaction.reset_reaction_observer().await.map(|_| ())
+                    })
+                }),
+            )
+            .await,
+        ];
+
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|result| result.err().map(|e| e.to_string()))
+            .collect();
+        if failures.is_empty() {
+            log::info!("TestRun {} reset", test_run_id);
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "Failed to reset TestRun {}: {}",
+                test_run_id,
+                failures.join("; ")
+            )
+        }
+    }
 
-                // Stop drasi servers
-                for server in test_run.drasi_servers.values() {
+    async fn stop_test_run_components(&self, test_run: &TestRun) -> anyhow::Result<()> {
+        let limit = self.lifecycle_concurrency;
+
+        // Stop reactions first
+        run_concurrently(
+            limit,
+            "stop Reaction",
+            test_run.reactions.iter().map(|(id, reaction)| {
+                (id.clone(), async move {
+                    reaction.stop_reaction_observer().await.map(|_| ())
+                })
+            }),
+        )
+        .await?;
+
+        // Stop queries
+        run_concurrently(
+            limit,
+            "stop Query",
+            test_run.queries.iter().map(|(id, query)| {
+                (id.clone(), async move {
+                    query.stop_query_result_observer().await.map(|_| ())
+                })
+            }),
+        )
+        .await?;
+
+        // Stop sources
+        run_concurrently(
+            limit,
+            "stop Source",
+            test_run.sources.iter().map(|(id, source)| {
+                (id.clone(), async move {
+                    source.stop_source_change_generator().await.map(|_| ())
+                })
+            }),
+        )
+        .await?;
+
+        // Stop drasi servers
+        run_concurrently(
+            limit,
+            "stop Drasi Server",
+            test_run.drasi_servers.iter().map(|(id, server)| {
+                (id.clone(), async move {
                     if matches!(
                         server.get_state().await,
                         TestRunDrasiServerState::Running { .. }
                     ) {
                         server.stop(Some("Stopping TestRun".to_string())).await?;
                     }
-                }
+                    Ok::<(), anyhow::Error>(())
+                })
+            }),
+        )
+        .await
+    }
 
-                test_run.status = TestRunStatus::Stopped;
-                Ok(())
-            }
-            None => anyhow::bail!("TestRun not found: {:?}", test_run_id),
+    // Returns true if the TestRun's artifact_retention doesn't keep its output for the status
+    // it ended with and the output hasn't been deleted yet, marking it as deleted. The caller
+    // deletes it with delete_test_run_output once the test_runs lock is released.
+    fn mark_output_for_deletion(test_run: &mut TestRun) -> bool {
+        if test_run.output_deleted || test_run.artifact_retention.should_retain(&test_run.status) {
+            return false;
+        }
+
+        test_run.output_deleted = true;
+        true
+    }
+
+    // Deletes the TestRun's output. Errors are logged so they don't mask the result of the
+    // stop or delete.
+    async fn delete_test_run_output(&self, test_run_id: &TestRunId, status: &TestRunStatus) {
+        match self.data_store.delete_test_run_storage(test_run_id).await {
+            Ok(true) => log::info!(
+                "Deleted output for TestRun {} - status: {:?}",
+                test_run_id,
+                status
+            ),
+            Ok(false) => {}
+            Err(e) => log::error!("Error deleting output for TestRun {}: {:?}", test_run_id, e),
         }
     }
 
@@ -1294,23 +2355,192 @@ impl TestRunHost {
             self.stop_test_run(test_run_id).await?;
         }
 
-        // Remove the test run
-        let mut test_runs = self.test_runs.write().await;
-        test_runs
+        // Remove the test run, then apply its artifact_retention. A TestRun that was stopped
+        // has had it applied already, but one that was never started hasn't.
+        let mut test_run = self
+            .test_runs
+            .write()
+            .await
             .remove(test_run_id)
             .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
+        if Self::mark_output_for_deletion(&mut test_run) {
+            self.delete_test_run_output(test_run_id, &test_run.status)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    // Snapshots the TestRun's effective configuration, along with the configuration each
+    // source change generator resolved from its definition, so the TestRun can be recreated
+    // elsewhere with import_test_run. Seeds that were generated because none was configured
+    // are written into the config, so the recreated TestRun generates the same changes.
+    // Secrets are redacted and must be supplied again before the export is imported.
+    pub async fn export_test_run(
+        &self,
+        test_run_id: &TestRunId,
+    ) -> anyhow::Result<serde_json::Value> {
+        let test_runs = self.test_runs.read().await;
+        let test_run = test_runs
+            .get(test_run_id)
+            .ok_or_else(|| anyhow::anyhow!("TestRun not found: {:?}", test_run_id))?;
 
+        let mut config = test_run.config.clone();
+        let mut source_change_generators = serde_json::Map::new();
+        for source_config in config.sources.iter_mut() {
+            let source = match test_run.sources.get(&source_config.test_source_id) {
+                Some(source) => source,
+                None => continue,
+            };
+
+            let description = source.describe_source_change_generator()?;
+            if let Some(seed) = description.configuration["seed"].as_u64() {
+                source_config.pin_model_data_generator_seed(seed);
+            }
+            source_change_generators.insert(
+                source_config.test_source_id.clone(),
+                serde_json::to_value(description)?,
+            );
+        }
+
+        Ok(serde_json::json!({
+            "format_version": TEST_RUN_EXPORT_FORMAT_VERSION,
+            "test_run_id": test_run_id.to_string(),
+            "test_run_config": config,
+            "source_change_generators": source_change_generators,
+        }))
+    }
+
+    // Reconstructs the TestRunConfig from a TestRun exported with export_test_run. The
+    // TestRun isn't added; pass the config to add_test_run to recreate it.
+    pub fn import_test_run(export: &serde_json::Value) -> anyhow::Result<TestRunConfig> {
+        match export["format_version"].as_u64() {
+            Some(TEST_RUN_EXPORT_FORMAT_VERSION) => {}
+            Some(version) => {
+                anyhow::bail!("Unsupported TestRun export format_version: {}", version)
+            }
+            None => anyhow::bail!("TestRun export has no format_version"),
+        }
+
+        match export.get("test_run_config") {
+            Some(config) => Ok(serde_json::from_value(config.clone())?),
+            None => anyhow::bail!("TestRun export has no test_run_config"),
+        }
+    }
+}
+
+// Runs the component operations concurrently, at most limit at a time. Every operation
+// runs to completion; the failures are then combined into a single error naming each
+// failed component.
+async fn run_concurrently<I, F>(limit: usize, action: &str, operations: I) -> anyhow::Result<()>
+where
+    I: IntoIterator<Item = (String, F)>,
+    F: Future<Output = anyhow::Result<()>>,
+{
+    let failures: Vec<String> = futures::stream::iter(operations)
+        .map(|(id, operation)| async move { (id, operation.await) })
+        .buffer_unordered(limit.max(1))
+        .filter_map(|(id, result)| async move { result.err().map(|e| format!("{}: {:?}", id, e)) })
+        .collect()
+        .await;
+
+    if failures.is_empty() {
         Ok(())
+    } else {
+        anyhow::bail!(
+            "Failed to {} for {} component(s): {}",
+            action,
+            failures.len(),
+            failures.join("; ")
+        )
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use std::sync::Arc;
+// Polls the bootstrap result count and status of a query's result observer until the count
+// reaches min_count. Fails on timeout, or if the observer stops or errors first.
+async fn await_initial_results<F, Fut>(
+    test_run_query_id: &str,
+    min_count: u64,
+    timeout: Duration,
+    mut poll: F,
+) -> anyhow::Result<u64>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<(u64, QueryResultObserverStatus)>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        let (count, status) = poll().await?;
+
+        if count >= min_count {
+            return Ok(count);
+        }
+
+        match status {
+            QueryResultObserverStatus::Error | QueryResultObserverStatus::Stopped => {
+                anyhow::bail!(
+                    "TestRunQuery {} is {:?} with {} of {} initial results",
+                    test_run_query_id,
+                    status,
+                    count,
+                    min_count
+                );
+            }
+            QueryResultObserverStatus::Running | QueryResultObserverStatus::Paused => {}
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            anyhow::bail!(
+                "Timed out after {:?} waiting for TestRunQuery {} initial results: {} of {}",
+                timeout,
+                test_run_query_id,
+                count,
+                min_count
+            );
+        }
 
-    use test_data_store::TestDataStore;
+        tokio::time::sleep_until(std::cmp::min(
+            deadline,
+            tokio::time::Instant::now() + QUERY_INITIAL_RESULTS_POLL_INTERVAL,
+        ))
+        .await;
+    }
+}
 
-    use crate::{TestRunHost, TestRunHostConfig, TestRunHostStatus};
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+
+    use test_data_store::{
+        test_repo_storage::{
+            models::{LocalTestDefinition, SpacingMode},
+            repo_clients::{CommonTestRepoConfig, LocalStorageTestRepoConfig, TestRepoConfig},
+            TestSourceStorage,
+        },
+        test_run_storage::{TestRunId, TestRunSourceId, TestRunSourceStorage},
+        TestDataStore,
+    };
+
+    use crate::{
+        await_initial_results,
+        queries::{query_result_observer::QueryResultObserverStatus, TestRunQueryConfig},
+        run_concurrently,
+        sources::{
+            bootstrap_data_generators::BootstrapData,
+            source_change_generators::{
+                script_source_change_generator::ScriptSourceChangeGenerator, SourceChangeGenerator,
+                SourceChangeGeneratorCommandResponse, SourceChangeGeneratorDescription,
+                SourceChangeGeneratorState, SourceChangeGeneratorStatus,
+            },
+            start_jitter_delay, SourceStartMode, TestRunSource, TestRunSourceConfig,
+            TestRunSourceState,
+        },
+        ArtifactRetention, TestRunConfig, TestRunHost, TestRunHostConfig, TestRunHostStatus,
+        TestRunMatrixConfig, TestRunStatus, TestSourceFilter, QUERY_INITIAL_RESULTS_POLL_INTERVAL,
+    };
 
     #[tokio::test]
     async fn test_new_test_run_host() -> anyhow::Result<()> {
@@ -1328,4 +2558,1099 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_run_concurrently_aggregates_errors() {
+        let operations = (0..5).map(|i| {
+            (format!("component-{}", i), async move {
+                if i % 2 == 0 {
+                    anyhow::bail!("failed {}", i);
+                }
+                Ok(())
+            })
+        });
+
+        let err = run_concurrently(2, "start", operations)
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("3 component(s)"));
+        assert!(err.contains("component-0") && err.contains("component-2"));
+        assert!(!err.contains("component-1"));
+    }
+
+    #[tokio::test]
+    async fn test_new_test_run_host_rejects_zero_concurrency() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+
+        let test_run_host_config = TestRunHostConfig {
+            lifecycle_concurrency: Some(0),
+            ..Default::default()
+        };
+
+        assert!(TestRunHost::new(test_run_host_config, data_store)
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_new_test_run_host_rejects_unset_env_var() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+
+        let test_run_host_config: TestRunHostConfig = serde_json::from_value(serde_json::json!({
+            "test_runs": [{
+                "test_id": "test-001",
+                "test_repo_id": "repo-001",
+                "test_run_id": "${TEST_RUN_HOST_UNSET_TEST_RUN_ID}"
+            }]
+        }))?;
+
+        let err = TestRunHost::new(test_run_host_config, data_store)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("TEST_RUN_HOST_UNSET_TEST_RUN_ID is not set"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_await_query_initial_results_unknown_query() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+        let test_run_host = TestRunHost::new(TestRunHostConfig::default(), data_store)
+            .await
+            .unwrap();
+
+        let result = test_run_host
+            .await_query_initial_results(
+                "test-repo.test-001.run-001.query-001",
+                1,
+                std::time::Duration::from_millis(10),
+            )
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("TestRun not found"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_await_initial_results_reaches_min_count() -> anyhow::Result<()> {
+        tokio::time::pause();
+        let started = tokio::time::Instant::now();
+
+        // Each poll sees one more initial result.
+        let mut polls = 0;
+        let count = await_initial_results("query-001", 3, Duration::from_secs(10), || {
+            polls += 1;
+            let count = polls;
+            async move { Ok((count, QueryResultObserverStatus::Running)) }
+        })
+        .await?;
+
+        assert_eq!(count, 3);
+        assert_eq!(started.elapsed(), 2 * QUERY_INITIAL_RESULTS_POLL_INTERVAL);
+
+        Ok(())
+    }
+
+    async fn query_test_run_host() -> anyhow::Result<(TestRunHost, String)> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        let query_id = test_run_host
+            .add_test_query(&test_run_id, TestRunQueryConfig::new("query-001"))
+            .await?;
+
+        Ok((test_run_host, query_id.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_await_query_initial_results_timeout() -> anyhow::Result<()> {
+        let (test_run_host, query_id) = query_test_run_host().await?;
+
+        // The query has no output handler, so it never sees any initial results.
+        tokio::time::pause();
+        let started = tokio::time::Instant::now();
+        let err = test_run_host
+            .await_query_initial_results(&query_id, 1, Duration::from_secs(1))
+            .await
+            .unwrap_err()
+            .to_string();
+
+        assert!(err.contains("Timed out after 1s"));
+        assert!(err.contains("0 of 1"));
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_await_query_initial_results_stopped() -> anyhow::Result<()> {
+        let (test_run_host, query_id) = query_test_run_host().await?;
+        test_run_host.test_query_stop(&query_id).await?;
+
+        let err = test_run_host
+            .await_query_initial_results(&query_id, 1, Duration::from_secs(10))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("is Stopped with 0 of 1 initial results"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_artifact_retention() {
+        let stopped = TestRunStatus::Stopped;
+        let error = TestRunStatus::Error("Source source-001".to_string());
+
+        assert!(ArtifactRetention::Always.should_retain(&stopped));
+        assert!(ArtifactRetention::Always.should_retain(&error));
+        assert!(!ArtifactRetention::OnFailure.should_retain(&stopped));
+        assert!(ArtifactRetention::OnFailure.should_retain(&error));
+        assert!(!ArtifactRetention::Never.should_retain(&error));
+    }
+
+    #[tokio::test]
+    async fn test_stop_test_run_applies_artifact_retention() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+        let test_run_host = TestRunHost::new(TestRunHostConfig::default(), data_store.clone())
+            .await
+            .unwrap();
+
+        for (run, retention) in [
+            ("run-001", ArtifactRetention::Always),
+            ("run-002", ArtifactRetention::OnFailure),
+        ] {
+            let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": run,
+                "artifact_retention": retention,
+            }))?;
+            let test_run_id = test_run_host.add_test_run(config).await?;
+            data_store.get_test_run_storage(&test_run_id).await?;
+
+            test_run_host.stop_test_run(&test_run_id).await?;
+            assert_eq!(
+                test_run_host.get_test_run_status(&test_run_id).await?,
+                TestRunStatus::Stopped
+            );
+        }
+
+        // Both runs succeeded, so only the run that always keeps its output still has it.
+        let run_id = |run| TestRunId::new("test-repo", "test-001", run);
+        assert!(data_store.contains_test_run(&run_id("run-001")).await?);
+        assert!(!data_store.contains_test_run(&run_id("run-002")).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_delete_test_run_applies_artifact_retention() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+        let test_run_host = TestRunHost::new(TestRunHostConfig::default(), data_store.clone())
+            .await
+            .unwrap();
+
+        for (run, retention) in [
+            ("run-001", ArtifactRetention::Always),
+            ("run-002", ArtifactRetention::Never),
+        ] {
+            let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": run,
+                "artifact_retention": retention,
+            }))?;
+            let test_run_id = test_run_host.add_test_run(config).await?;
+            data_store.get_test_run_storage(&test_run_id).await?;
+
+            // The TestRun is deleted without ever being started.
+            assert_eq!(
+                test_run_host.get_test_run_status(&test_run_id).await?,
+                TestRunStatus::Initialized
+            );
+            test_run_host.delete_test_run(&test_run_id).await?;
+        }
+
+        let run_id = |run| TestRunId::new("test-repo", "test-001", run);
+        assert!(data_store.contains_test_run(&run_id("run-001")).await?);
+        assert!(!data_store.contains_test_run(&run_id("run-002")).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_test_run_after_output_deleted() -> anyhow::Result<()> {
+        let data_store = Arc::new(TestDataStore::new_temp(None).await?);
+        let test_run_host = TestRunHost::new(TestRunHostConfig::default(), data_store.clone())
+            .await
+            .unwrap();
+
+        let mut test_run_ids = Vec::new();
+        for (run, retention) in [
+            ("run-001", ArtifactRetention::Always),
+            ("run-002", ArtifactRetention::Never),
+        ] {
+            let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": run,
+                "artifact_retention": retention,
+            }))?;
+            let test_run_id = test_run_host.add_test_run(config).await?;
+            data_store.get_test_run_storage(&test_run_id).await?;
+            test_run_host.stop_test_run(&test_run_id).await?;
+            test_run_ids.push(test_run_id);
+        }
+
+        // A TestRun that kept its output can be reset, but one whose output was deleted can't
+        // be, and its output isn't recreated.
+        test_run_host.reset_test_run(&test_run_ids[0]).await?;
+        let err = test_run_host
+            .reset_test_run(&test_run_ids[1])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("output was deleted"));
+        assert!(!data_store.contains_test_run(&test_run_ids[1]).await?);
+
+        Ok(())
+    }
+
+    async fn validation_test_run_host() -> anyhow::Result<TestRunHost> {
+        let data_store = Arc::new(
+            TestDataStore::new_temp(Some(vec![TestRepoConfig::LocalStorage {
+                common_config: CommonTestRepoConfig {
+                    id: "test-repo".to_string(),
+                    local_tests: Vec::new(),
+                },
+                unique_config: LocalStorageTestRepoConfig { source_path: None },
+            }]))
+            .await?,
+        );
+
+        let test_definition: LocalTestDefinition = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "version": 1,
+            "description": null,
+            "test_folder": null,
+            "queries": [{ "test_query_id": "query-001" }],
+            "reactions": [
+                { "test_reaction_id": "reaction-001", "output_handler": { "kind": "Http" } }
+            ],
+            "sources": [
+                { "kind": "Script", "test_source_id": "source-001" },
+                {
+                    "kind": "Model",
+                    "test_source_id": "source-002",
+                    "model_data_generator": { "kind": "BuildingHierarchy", "room_sensors": [] }
+                }
+            ],
+        }))?;
+        data_store
+            .add_local_test("test-repo", test_definition, false)
+            .await?;
+
+        TestRunHost::new(TestRunHostConfig::default(), data_store).await
+    }
+
+    #[tokio::test]
+    async fn test_validate_test_run() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "queries": [{ "test_query_id": "query-001" }],
+            "reactions": [{ "test_reaction_id": "reaction-001" }],
+            "sources": [{ "test_source_id": "source-001" }],
+        }))?;
+
+        let warnings = test_run_host.validate_test_run(&config).await?;
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("Query query-001 has no loggers"));
+        assert!(warnings[1].contains("Reaction reaction-001 has no output loggers"));
+
+        // Nothing is created by validation.
+        assert!(test_run_host.get_test_run_ids().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_validate_test_run_missing_source() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "sources": [{ "test_source_id": "source-003" }],
+        }))?;
+
+        let err = test_run_host
+            .validate_test_run(&config)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("source-003"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remove_test_source() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+
+        let source_id = test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+            .await?;
+        test_run_host
+            .test_source_start(&source_id.to_string())
+            .await?;
+        assert_eq!(test_run_host.get_test_source_ids().await?.len(), 1);
+
+        test_run_host.remove_test_source(&source_id).await?;
+        assert!(test_run_host.get_test_source_ids().await?.is_empty());
+        assert!(test_run_host.remove_test_source(&source_id).await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_test_source_concurrent_duplicates() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+
+        let (a, b) = tokio::join!(
+            test_run_host.add_test_source(&test_run_id, TestRunSourceConfig::new("source-002")),
+            test_run_host.add_test_source(&test_run_id, TestRunSourceConfig::new("source-002")),
+        );
+
+        assert_eq!([a.is_ok(), b.is_ok()].iter().filter(|ok| **ok).count(), 1);
+        let err = a.err().or(b.err()).unwrap().to_string();
+        assert!(err.contains("already contains TestRunSource"));
+        assert_eq!(test_run_host.get_test_source_ids().await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_test_source_exceeds_max_sources() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "limits": { "max_sources": 1 },
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+
+        let source_id = test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+            .await?;
+        let err = test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-001"))
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("exceeds its max_sources limit of 1"));
+        assert_eq!(test_run_host.get_test_source_ids().await?.len(), 1);
+
+        // Removing a source frees its place.
+        test_run_host.remove_test_source(&source_id).await?;
+        test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-001"))
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_test_run_exceeds_limits() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "limits": { "max_sources": 1 },
+            "sources": [{ "test_source_id": "source-001" }, { "test_source_id": "source-002" }],
+        }))?;
+        let err = test_run_host.add_test_run(config).await.unwrap_err();
+        assert!(err.to_string().contains("would have 2 sources"));
+        assert!(test_run_host.get_test_run_ids().await?.is_empty());
+
+        // The model source's change channel has the default capacity of 1000.
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "limits": { "max_channel_capacity": 500 },
+            "sources": [{ "test_source_id": "source-002" }],
+        }))?;
+        let err = test_run_host.add_test_run(config).await.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1000 total channel capacity, which exceeds its max_channel_capacity"));
+        assert!(test_run_host.get_test_run_ids().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_test_run_matrix_failure_adds_no_runs() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-000",
+        }))?;
+        test_run_host.add_test_run(config).await?;
+        let test_run_ids = test_run_host.get_test_run_ids().await?;
+
+        // The first combination is added before the second fails on its unknown source.
+        let matrix: TestRunMatrixConfig = serde_json::from_value(serde_json::json!({
+            "base": {
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": "run-001",
+                "sources": [{ "test_source_id": "source-002" }],
+            },
+            "axes": [{
+                "name": "source",
+                "path": "sources[0].test_source_id",
+                "values": ["source-002", "source-003"],
+            }],
+        }))?;
+        let err = test_run_host.add_test_run_matrix(matrix).await.unwrap_err();
+        assert!(err.to_string().contains("source-003"));
+        assert_eq!(test_run_host.get_test_run_ids().await?, test_run_ids);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_test_sources_status_filter() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let mut source_ids = Vec::new();
+        for run in ["run-001", "run-002"] {
+            let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": run,
+            }))?;
+            let test_run_id = test_run_host.add_test_run(config).await?;
+            source_ids.push(
+                test_run_host
+                    .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+                    .await?
+                    .to_string(),
+            );
+        }
+        test_run_host.test_source_start(&source_ids[1]).await?;
+
+        let ids = |filter: TestSourceFilter| {
+            let test_run_host = &test_run_host;
+            async move {
+                test_run_host
+                    .get_test_sources(&filter)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|source| source.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(ids(TestSourceFilter::default()).await, source_ids);
+        let running = TestSourceFilter {
+            status: Some(SourceChangeGeneratorStatus::Running),
+            ..Default::default()
+        };
+        assert_eq!(ids(running).await, vec![source_ids[1].clone()]);
+        let paused = TestSourceFilter {
+            status: Some(SourceChangeGeneratorStatus::Paused),
+            ..Default::default()
+        };
+        assert_eq!(ids(paused).await, vec![source_ids[0].clone()]);
+        let paused_in_run_002 = TestSourceFilter {
+            test_run_id: Some("run-002".to_string()),
+            status: Some(SourceChangeGeneratorStatus::Paused),
+            ..Default::default()
+        };
+        assert!(ids(paused_in_run_002).await.is_empty());
+
+        // Statuses are accepted by name.
+        let filter: TestSourceFilter =
+            serde_json::from_value(serde_json::json!({ "status": "running" }))?;
+        assert_eq!(filter.status, Some(SourceChangeGeneratorStatus::Running));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reset_test_run() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+        let test_definition: LocalTestDefinition = serde_json::from_value(serde_json::json!({
+            "test_id": "test-003",
+            "version": 1,
+            "description": null,
+            "test_folder": null,
+            "sources": [{
+                "kind": "Model",
+                "test_source_id": "source-001",
+                "model_data_generator": {
+                    "kind": "BuildingHierarchy",
+                    "room_sensors": [{
+                        "kind": "NormalFloat",
+                        "id": "temperature",
+                        "value_init": [70.0, 5.0],
+                        "value_change": [1.0, 0.5]
+                    }]
+                }
+            }],
+        }))?;
+        test_run_host
+            .data_store
+            .add_local_test("test-repo", test_definition, false)
+            .await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-003",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        let source_id = test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-001"))
+            .await?
+            .to_string();
+
+        // A TestRun can only be reset once it is stopped.
+        assert!(test_run_host.reset_test_run(&test_run_id).await.is_err());
+
+        test_run_host
+            .test_source_step(&source_id, 5, Some(SpacingMode::None))
+            .await?;
+        for _ in 0..100 {
+            let state = test_run_host.get_test_source_state(&source_id).await?;
+            if state.source_change_generator.event_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        test_run_host.stop_test_run(&test_run_id).await?;
+
+        let state = test_run_host.get_test_source_state(&source_id).await?;
+        assert!(state.source_change_generator.event_count > 0);
+        assert_ne!(state.source_change_generator.state["event_seq_num"], 0);
+
+        test_run_host.reset_test_run(&test_run_id).await?;
+
+        let state = test_run_host.get_test_source_state(&source_id).await?;
+        assert_eq!(state.source_change_generator.event_count, 0);
+        assert_eq!(state.source_change_generator.state["event_seq_num"], 0);
+        assert_eq!(
+            state.source_change_generator.status,
+            SourceChangeGeneratorStatus::Paused
+        );
+        assert_eq!(
+            test_run_host.get_test_run_status(&test_run_id).await?,
+            TestRunStatus::Stopped
+        );
+
+        Ok(())
+    }
+
+    // Steps the TestRunSource once, stops the TestRun so the JsonlFile dispatcher flushes its
+    // output, and returns the SourceChangeEvent that was dispatched.
+    async fn step_and_read_first_event(
+        test_run_host: &TestRunHost,
+        source_id: &TestRunSourceId,
+    ) -> anyhow::Result<serde_json::Value> {
+        let id = source_id.to_string();
+        test_run_host
+            .test_source_step(&id, 1, Some(SpacingMode::None))
+            .await?;
+        for _ in 0..100 {
+            let state = test_run_host.get_test_source_state(&id).await?;
+            if state.source_change_generator.event_count > 0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        test_run_host.stop_test_run(&source_id.test_run_id).await?;
+
+        let storage = test_run_host
+            .data_store
+            .get_test_run_source_storage(source_id)
+            .await?;
+        let mut entries = tokio::fs::read_dir(&storage.source_change_path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let content = tokio::fs::read_to_string(entry.path()).await?;
+            if let Some(line) = content.lines().next() {
+                return Ok(serde_json::from_str(line)?);
+            }
+        }
+        anyhow::bail!("TestRunSource {} dispatched no events", source_id)
+    }
+
+    #[tokio::test]
+    async fn test_export_import_test_run() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+        let test_definition: LocalTestDefinition = serde_json::from_value(serde_json::json!({
+            "test_id": "test-002",
+            "version": 1,
+            "description": null,
+            "test_folder": null,
+            "sources": [{
+                "kind": "Model",
+                "test_source_id": "source-001",
+                "model_data_generator": {
+                    "kind": "BuildingHierarchy",
+                    "room_sensors": [{
+                        "kind": "NormalFloat",
+                        "id": "temperature",
+                        "value_init": [70.0, 5.0],
+                        "value_change": [1.0, 0.5]
+                    }]
+                }
+            }],
+        }))?;
+        test_run_host
+            .data_store
+            .add_local_test("test-repo", test_definition, false)
+            .await?;
+
+        // The source has no seed, so one is generated when it is created.
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-002",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "sources": [{
+                "test_source_id": "source-001",
+                "start_mode": "manual",
+                "test_run_overrides": {
+                    "source_change_dispatchers": [{ "kind": "JsonlFile" }]
+                }
+            }],
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        let source_id = TestRunSourceId::new(&test_run_id, "source-001");
+        let first_event = step_and_read_first_event(&test_run_host, &source_id).await?;
+
+        let export = test_run_host.export_test_run(&test_run_id).await?;
+        assert!(
+            export["test_run_config"]["sources"][0]["test_run_overrides"]["model_data_generator"]
+                ["seed"]
+                .is_u64()
+        );
+        assert_eq!(
+            export["source_change_generators"]["source-001"]["kind"],
+            "BuildingHierarchy"
+        );
+
+        test_run_host.delete_test_run(&test_run_id).await?;
+        let storage = test_run_host
+            .data_store
+            .get_test_run_source_storage(&source_id)
+            .await?;
+        tokio::fs::remove_dir_all(&storage.source_change_path).await?;
+
+        let config = TestRunHost::import_test_run(&export)?;
+        assert_eq!(test_run_host.add_test_run(config).await?, test_run_id);
+        let replayed_event = step_and_read_first_event(&test_run_host, &source_id).await?;
+
+        assert_eq!(replayed_event["op"], first_event["op"]);
+        assert_eq!(
+            replayed_event["payload"]["after"]["id"],
+            first_event["payload"]["after"]["id"]
+        );
+        assert_eq!(
+            replayed_event["payload"]["after"]["properties"],
+            first_event["payload"]["after"]["properties"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_test_run_rejects_unknown_format_version() {
+        let export = serde_json::json!({ "format_version": 99, "test_run_config": {} });
+        let err = TestRunHost::import_test_run(&export)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("format_version: 99"));
+    }
+
+    // A TestRunSource that only supports being started, which moves it from Paused to Running.
+    // A source created in the Error state can't be started. If it wraps a generator, its
+    // generator state is read from that generator instead.
+    #[derive(Debug)]
+    struct StubTestRunSource {
+        id: TestRunSourceId,
+        generator: Option<Box<dyn SourceChangeGenerator + Send + Sync>>,
+        start_mode: SourceStartMode,
+        start_jitter_ms: Option<u64>,
+        status: std::sync::Mutex<SourceChangeGeneratorStatus>,
+    }
+
+    impl StubTestRunSource {
+        fn errored(id: TestRunSourceId) -> Self {
+            Self {
+                id,
+                generator: None,
+                start_mode: SourceStartMode::Manual,
+                start_jitter_ms: None,
+                status: std::sync::Mutex::new(SourceChangeGeneratorStatus::Error),
+            }
+        }
+
+        fn jittered(id: TestRunSourceId, start_jitter_ms: u64) -> Self {
+            Self {
+                id,
+                generator: None,
+                start_mode: SourceStartMode::Auto,
+                start_jitter_ms: Some(start_jitter_ms),
+                status: std::sync::Mutex::new(SourceChangeGeneratorStatus::Paused),
+            }
+        }
+
+        fn with_generator(
+            id: TestRunSourceId,
+            generator: Box<dyn SourceChangeGenerator + Send + Sync>,
+        ) -> Self {
+            Self {
+                id,
+                generator: Some(generator),
+                start_mode: SourceStartMode::Manual,
+                start_jitter_ms: None,
+                status: std::sync::Mutex::new(SourceChangeGeneratorStatus::Paused),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl TestRunSource for StubTestRunSource {
+        fn describe_source_change_generator(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorDescription> {
+            match &self.generator {
+                Some(generator) => Ok(generator.describe()),
+                None => anyhow::bail!("StubTestRunSource has no generator"),
+            }
+        }
+
+        async fn get_bootstrap_data(
+            &self,
+            _node_labels: &HashSet<String>,
+            _rel_labels: &HashSet<String>,
+        ) -> anyhow::Result<BootstrapData> {
+            Ok(BootstrapData::new())
+        }
+
+        async fn get_state(&self) -> anyhow::Result<TestRunSourceState> {
+            Ok(TestRunSourceState {
+                id: self.id.clone(),
+                source_change_generator: self.get_source_change_generator_state().await?,
+                stalled: false,
+                fail_on_stall: false,
+                start_mode: self.start_mode.clone(),
+                start_jitter_ms: self.start_jitter_ms,
+            })
+        }
+
+        async fn get_source_change_generator_state(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorState> {
+            if let Some(generator) = &self.generator {
+                return Ok(generator.get_state().await?.state);
+            }
+
+            Ok(SourceChangeGeneratorState {
+                event_count: 0,
+                state: serde_json::json!({}),
+                status: *self.status.lock().unwrap(),
+            })
+        }
+
+        async fn pause_source_change_generator(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn reset_source_change_generator(
+            &self,
+            _seed: Option<u64>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn skip_source_change_generator(
+            &self,
+            _skips: u64,
+            _spacing_mode: Option<SpacingMode>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn start_source_change_generator(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            {
+                let mut status = self.status.lock().unwrap();
+                if *status == SourceChangeGeneratorStatus::Error {
+                    anyhow::bail!("StubTestRunSource is in the Error state")
+                }
+                *status = SourceChangeGeneratorStatus::Running;
+            }
+            Ok(SourceChangeGeneratorCommandResponse {
+                result: Ok(()),
+                state: self.get_source_change_generator_state().await?,
+            })
+        }
+
+        async fn step_source_change_generator(
+            &self,
+            _steps: u64,
+            _spacing_mode: Option<SpacingMode>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn step_to_source_change_generator(
+            &self,
+            _target_seq: u64,
+            _spacing_mode: Option<SpacingMode>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn skip_to_end_source_change_generator(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+
+        async fn stop_source_change_generator(
+            &self,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            anyhow::bail!("StubTestRunSource can only be started")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_unhealthy_component_healthy() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+            .await?;
+
+        assert_eq!(test_run_host.find_unhealthy_component(false).await?, None);
+        assert_eq!(test_run_host.find_unhealthy_component(true).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_unhealthy_component_source_error() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+            .await?;
+
+        let errored_id = TestRunSourceId::new(&test_run_id, "source-errored");
+        test_run_host
+            .test_runs
+            .write()
+            .await
+            .get_mut(&test_run_id)
+            .unwrap()
+            .sources
+            .insert(
+                "source-errored".to_string(),
+                Box::new(StubTestRunSource::errored(errored_id.clone())),
+            );
+
+        assert_eq!(
+            test_run_host.find_unhealthy_component(false).await?,
+            Some(errored_id.to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_unhealthy_component_generator_failed_to_initialize() -> anyhow::Result<()> {
+        let test_run_host = validation_test_run_host().await?;
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+        test_run_host
+            .add_test_source(&test_run_id, TestRunSourceConfig::new("source-002"))
+            .await?;
+
+        // The generator's script folder doesn't exist, so its processor thread fails to
+        // initialize and exits, and its state can't be read.
+        let dir = tempfile::tempdir()?;
+        let failed_id = TestRunSourceId::new(&test_run_id, "source-failed");
+        let generator_definition = serde_json::json!({
+            "kind": "Script",
+            "script_file_folder": "source_change_scripts"
+        });
+        let generator = ScriptSourceChangeGenerator::new(
+            failed_id.clone(),
+            serde_json::from_value(generator_definition.clone())?,
+            TestSourceStorage {
+                id: "source-failed".to_string(),
+                path: dir.path().join("input"),
+                repo_id: "test-repo".to_string(),
+                test_id: "test-001".to_string(),
+                test_source_definition: serde_json::from_value(serde_json::json!({
+                    "kind": "Script",
+                    "test_source_id": "source-failed",
+                    "source_change_generator": generator_definition
+                }))?,
+            },
+            TestRunSourceStorage {
+                id: failed_id.clone(),
+                path: dir.path().join("output"),
+                source_change_path: dir.path().join("output").join("source_change_dispatchers"),
+            },
+            Vec::new(),
+        )
+        .await?;
+        test_run_host
+            .test_runs
+            .write()
+            .await
+            .get_mut(&test_run_id)
+            .unwrap()
+            .sources
+            .insert(
+                "source-failed".to_string(),
+                Box::new(StubTestRunSource::with_generator(
+                    failed_id.clone(),
+                    Box::new(generator),
+                )),
+            );
+
+        assert_eq!(
+            test_run_host.find_unhealthy_component(false).await?,
+            Some(failed_id.to_string())
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_start_jitter_does_not_block_test_run_host() -> anyhow::Result<()> {
+        let test_run_host = Arc::new(validation_test_run_host().await?);
+
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+        }))?;
+        let test_run_id = test_run_host.add_test_run(config).await?;
+
+        // Pick a source whose jitter delay is long enough to observe the host while it waits.
+        let start_jitter_ms = 2000;
+        let (source_id, delay) = (0..)
+            .map(|i| {
+                let id = TestRunSourceId::new(&test_run_id, &format!("source-jitter-{}", i));
+                let delay = start_jitter_delay(&id, start_jitter_ms);
+                (id, delay)
+            })
+            .find(|(_, delay)| *delay >= Duration::from_millis(500))
+            .unwrap();
+
+        test_run_host
+            .test_runs
+            .write()
+            .await
+            .get_mut(&test_run_id)
+            .unwrap()
+            .sources
+            .insert(
+                source_id.test_source_id.clone(),
+                Box::new(StubTestRunSource::jittered(
+                    source_id.clone(),
+                    start_jitter_ms,
+                )),
+            );
+
+        let host = test_run_host.clone();
+        let run_id = test_run_id.clone();
+        let start = tokio::spawn(async move { host.start_test_run(&run_id).await });
+
+        // While the source waits out its delay, the rest of the host stays responsive.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let status = tokio::time::timeout(
+            Duration::from_millis(100),
+            test_run_host.get_test_run_status(&test_run_id),
+        )
+        .await
+        .expect("get_test_run_status blocked by the start jitter")?;
+        assert_eq!(status, TestRunStatus::Initialized);
+        assert!(!start.is_finished());
+
+        start.await??;
+        assert_eq!(
+            test_run_host.get_test_run_status(&test_run_id).await?,
+            TestRunStatus::Running
+        );
+
+        Ok(())
+    }
 }
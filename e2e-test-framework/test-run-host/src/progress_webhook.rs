@@ -0,0 +1,297 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Periodic progress updates POSTed to a webhook while a TestRun is running.
+//!
+//! Each TestRun with a `progress_webhook` gets a background task that POSTs a compact
+//! [`TestRunProgress`] payload at the configured interval. When the TestRun is stopped
+//! the task POSTs a final payload (with `is_final` set) and exits. Webhook failures are
+//! logged and never affect the TestRun.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    sync::{oneshot, RwLock},
+    task::JoinHandle,
+    time::Instant,
+};
+
+use test_data_store::test_run_storage::TestRunId;
+
+use crate::{TestRun, TestRunStatus};
+
+const DEFAULT_PROGRESS_INTERVAL_MS: u64 = 10_000;
+const DEFAULT_PROGRESS_TIMEOUT_SECONDS: u64 = 10;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProgressWebhookConfig {
+    pub url: String,
+    #[serde(default)]
+    pub interval_ms: Option<u64>,
+    #[serde(default)]
+    pub timeout_seconds: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SourceProgress {
+    pub status: String,
+    pub event_count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReactionProgress {
+    pub status: String,
+    pub invocation_count: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TestRunProgress {
+    pub test_run_id: String,
+    pub status: TestRunStatus,
+    pub elapsed_ms: u64,
+    pub is_final: bool,
+    pub sources: BTreeMap<String, SourceProgress>,
+    pub queries: BTreeMap<String, String>,
+    pub reactions: BTreeMap<String, ReactionProgress>,
+}
+
+impl TestRunProgress {
+    // Collects the current progress of the TestRun. Components whose state can't be read
+    // are reported with an "Unavailable" status rather than failing the whole payload.
+    pub async fn collect(test_run: &TestRun, elapsed: Duration, is_final: bool) -> Self {
+        let mut sources = BTreeMap::new();
+        for (id, source) in test_run.sources.iter() {
+            let progress = match source.get_state().await {
+                Ok(state) => SourceProgress {
+                    status: status_string(&state.source_change_generator.status),
+                    event_count: state.source_change_generator.event_count,
+                },
+                Err(_) => SourceProgress {
+                    status: "Unavailable".to_string(),
+                    event_count: 0,
+                },
+            };
+            sources.insert(id.clone(), progress);
+        }
+
+        let mut queries = BTreeMap::new();
+        for (id, query) in test_run.queries.iter() {
+            let status = match query.get_state().await {
+                Ok(state) => status_string(&state.query_observer.status),
+                Err(_) => "Unavailable".to_string(),
+            };
+            queries.insert(id.clone(), status);
+        }
+
+        let mut reactions = BTreeMap::new();
+        for (id, reaction) in test_run.reactions.iter() {
+            let progress = match reaction.get_state().await {
+                Ok(state) => ReactionProgress {
+                    status: status_string(&state.reaction_observer.status),
+                    invocation_count: state
+                        .reaction_observer
+                        .result_summary
+                        .reaction_invocation_count,
+                },
+                Err(_) => ReactionProgress {
+                    status: "Unavailable".to_string(),
+                    invocation_count: 0,
+                },
+            };
+            reactions.insert(id.clone(), progress);
+        }
+
+        Self {
+            test_run_id: test_run.id.to_string(),
+            status: test_run.status.clone(),
+            elapsed_ms: elapsed.as_millis() as u64,
+            is_final,
+            sources,
+            queries,
+            reactions,
+        }
+    }
+}
+
+// The component status enums serialize to their names; use that as the compact form.
+fn status_string<T: Serialize>(status: &T) -> String {
+    match serde_json::to_value(status) {
+        Ok(serde_json::Value::String(s)) => s,
+        Ok(other) => other.to_string(),
+        Err(_) => "Unknown".to_string(),
+    }
+}
+
+// Handle to the background task that drives the progress webhook for a TestRun.
+#[derive(Debug)]
+pub struct ProgressReporter {
+    started: Instant,
+    finish_tx: Option<oneshot::Sender<TestRunProgress>>,
+    _handle: JoinHandle<()>,
+}
+
+impl ProgressReporter {
+    pub fn start(
+        config: ProgressWebhookConfig,
+        test_run_id: TestRunId,
+        test_runs: Arc<RwLock<HashMap<TestRunId, TestRun>>>,
+    ) -> anyhow::Result<Self> {
+        let interval = Duration::from_millis(
+            config
+                .interval_ms
+                .unwrap_or(DEFAULT_PROGRESS_INTERVAL_MS)
+                .max(1),
+        );
+        let client = Client::builder()
+            .timeout(Duration::from_secs(
+                config
+                    .timeout_seconds
+                    .unwrap_or(DEFAULT_PROGRESS_TIMEOUT_SECONDS),
+            ))
+            .build()?;
+
+        let started = Instant::now();
+        let (finish_tx, finish_rx) = oneshot::channel();
+        let handle = tokio::spawn(progress_reporter_thread(
+            client,
+            config.url,
+            interval,
+            started,
+            test_run_id,
+            test_runs,
+            finish_rx,
+        ));
+
+        Ok(Self {
+            started,
+            finish_tx: Some(finish_tx),
+            _handle: handle,
+        })
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    // Hands the final progress payload to the background task, which POSTs it and exits.
+    // This doesn't wait for the POST, so it is safe to call while holding the TestRun lock.
+    pub fn finish(mut self, progress: TestRunProgress) {
+        if let Some(finish_tx) = self.finish_tx.take() {
+            let _ = finish_tx.send(progress);
+        }
+    }
+}
+
+async fn progress_reporter_thread(
+    client: Client,
+    url: String,
+    interval: Duration,
+    started: Instant,
+    test_run_id: TestRunId,
+    test_runs: Arc<RwLock<HashMap<TestRunId, TestRun>>>,
+    mut finish_rx: oneshot::Receiver<TestRunProgress>,
+) {
+    log::info!(
+        "Progress webhook started for TestRun {} - url: {}, interval: {:?}",
+        test_run_id,
+        url,
+        interval
+    );
+
+    let mut ticker = tokio::time::interval_at(started + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            biased;
+
+            final_progress = &mut finish_rx => {
+                // An error means the reporter was dropped without finishing, e.g. the
+                // TestRun was deleted, so there is no final payload to send.
+                if let Ok(progress) = final_progress {
+                    post_progress(&client, &url, &progress).await;
+                }
+                break;
+            }
+
+            _ = ticker.tick() => {
+                let progress = {
+                    let test_runs = test_runs.read().await;
+                    match test_runs.get(&test_run_id) {
+                        Some(test_run) => {
+                            TestRunProgress::collect(test_run, started.elapsed(), false).await
+                        }
+                        None => break,
+                    }
+                };
+                post_progress(&client, &url, &progress).await;
+            }
+        }
+    }
+
+    log::info!("Progress webhook stopped for TestRun {}", test_run_id);
+}
+
+async fn post_progress(client: &Client, url: &str, progress: &TestRunProgress) {
+    match client.post(url).json(progress).send().await {
+        Ok(response) if !response.status().is_success() => {
+            log::warn!(
+                "Progress webhook {} returned {} for TestRun {}",
+                url,
+                response.status(),
+                progress.test_run_id
+            );
+        }
+        Ok(_) => {}
+        Err(e) => {
+            log::warn!(
+                "Error posting progress for TestRun {} to {}: {:?}",
+                progress.test_run_id,
+                url,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sources::source_change_generators::SourceChangeGeneratorStatus;
+
+    #[test]
+    fn test_status_string() {
+        assert_eq!(
+            status_string(&SourceChangeGeneratorStatus::Running),
+            "Running"
+        );
+        assert_eq!(status_string(&TestRunStatus::Stopped), "Stopped");
+    }
+
+    #[test]
+    fn test_read_progress_webhook_config() {
+        let config: ProgressWebhookConfig =
+            serde_json::from_str(r#"{ "url": "http://localhost:9000/progress" }"#).unwrap();
+
+        assert_eq!(config.url, "http://localhost:9000/progress");
+        assert_eq!(config.interval_ms, None);
+    }
+}
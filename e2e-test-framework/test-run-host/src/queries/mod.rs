@@ -50,6 +50,10 @@ pub struct TestRunQueryOverrides {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TestRunQueryConfig {
+    // Drop result changes whose sequence is not greater than the highest one seen, e.g. those
+    // replayed when the query is restarted.
+    #[serde(default)]
+    pub dedupe_by_sequence: bool,
     #[serde(default = "default_start_immediately")]
     pub start_immediately: bool,
     pub test_query_id: String,
@@ -68,6 +72,22 @@ fn default_start_immediately() -> bool {
     false
 }
 
+impl TestRunQueryConfig {
+    // Creates a config for the TestQuery with the same defaults used when deserializing.
+    pub fn new(test_query_id: &str) -> Self {
+        Self {
+            dedupe_by_sequence: false,
+            start_immediately: default_start_immediately(),
+            test_query_id: test_query_id.to_string(),
+            test_run_overrides: None,
+            loggers: Vec::new(),
+            test_id: None,
+            test_repo_id: None,
+            test_run_id: None,
+        }
+    }
+}
+
 impl TryFrom<&TestRunQueryConfig> for TestRunId {
     type Error = ParseTestRunIdError;
 
@@ -110,6 +130,7 @@ impl fmt::Display for TestRunQueryConfig {
 
 #[derive(Clone, Debug)]
 pub struct TestRunQueryDefinition {
+    pub dedupe_by_sequence: bool,
     pub id: TestRunQueryId,
     pub loggers: Vec<ResultStreamLoggerConfig>,
     pub start_immediately: bool,
@@ -123,6 +144,7 @@ impl TestRunQueryDefinition {
         test_query_definition: TestQueryDefinition,
     ) -> anyhow::Result<Self> {
         Ok(Self {
+            dedupe_by_sequence: test_run_query_config.dedupe_by_sequence,
             id: TestRunQueryId::try_from(&test_run_query_config)?,
             loggers: test_run_query_config.loggers,
             start_immediately: test_run_query_config.start_immediately,
@@ -158,6 +180,7 @@ impl TestRunQuery {
             output_storage,
             definition.loggers,
             definition.test_run_overrides,
+            definition.dedupe_by_sequence,
         )
         .await?;
 
@@ -95,6 +95,7 @@ pub struct QueryResultObserverCommandResponse {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct QueryResultObserverSettings {
+    pub dedupe_by_sequence: bool,
     pub definition: TestQueryDefinition,
     pub id: TestRunQueryId,
     pub loggers: Vec<ResultStreamLoggerConfig>,
@@ -109,6 +110,7 @@ impl QueryResultObserverSettings {
         output_storage: TestRunQueryStorage,
         loggers: Vec<ResultStreamLoggerConfig>,
         test_run_overrides: Option<TestRunQueryOverrides>,
+        dedupe_by_sequence: bool,
     ) -> anyhow::Result<Self> {
         // Start with stop trigger from test definition
         let mut stop_trigger = definition.stop_trigger.clone();
@@ -122,6 +124,7 @@ impl QueryResultObserverSettings {
 
         let settings = Self {
             stop_trigger,
+            dedupe_by_sequence,
             definition,
             id: test_run_query_id,
             loggers,
@@ -164,6 +167,12 @@ pub struct QueryResultObserverExternalState {
     pub result_summary: QueryResultObserverSummary,
     pub settings: QueryResultObserverSettings,
     pub logger_results: Vec<ResultStreamLoggerResult>,
+    // Wall-clock times the first and last query results were observed, or None until a result
+    // arrives. Control signals don't count as results.
+    pub first_result_observed_ns: Option<u64>,
+    pub last_result_observed_ns: Option<u64>,
+    // Result changes dropped by dedupe_by_sequence since the observer was created or reset.
+    pub duplicate_results_dropped: u64,
 }
 
 #[derive(Clone, Debug, Serialize, Default)]
@@ -406,6 +415,7 @@ impl QueryResultObserver {
         output_storage: TestRunQueryStorage,
         loggers: Vec<ResultStreamLoggerConfig>,
         test_run_overrides: Option<TestRunQueryOverrides>,
+        dedupe_by_sequence: bool,
     ) -> anyhow::Result<Self> {
         let settings = QueryResultObserverSettings::new(
             test_run_query_id,
@@ -413,6 +423,7 @@ impl QueryResultObserver {
             output_storage.clone(),
             loggers,
             test_run_overrides,
+            dedupe_by_sequence,
         )
         .await?;
         log::debug!("Creating QueryResultObserver from {:?}", &settings);
@@ -497,6 +508,10 @@ struct QueryResultObserverInternalState {
     status: QueryResultObserverStatus,
     metrics: QueryResultObserverMetrics,
     stop_trigger: Box<dyn StopTrigger + Send + Sync>,
+    first_result_observed_ns: Option<u64>,
+    last_result_observed_ns: Option<u64>,
+    max_result_sequence: Option<i64>,
+    duplicate_results_dropped: u64,
 }
 
 impl QueryResultObserverInternalState {
@@ -548,6 +563,10 @@ impl QueryResultObserverInternalState {
             status: QueryResultObserverStatus::Paused,
             metrics,
             stop_trigger,
+            first_result_observed_ns: None,
+            last_result_observed_ns: None,
+            max_result_sequence: None,
+            duplicate_results_dropped: 0,
         })
     }
 
@@ -625,7 +644,35 @@ impl QueryResultObserverInternalState {
         let _ = join_all(futures).await;
     }
 
+    // Returns true if the record is a result change whose sequence is not greater than the
+    // highest seen so far. Otherwise the record's sequence becomes the highest seen.
+    fn is_duplicate_result(&mut self, record: &QueryHandlerRecord) -> bool {
+        let sequence =
+            match serde_json::from_value::<QueryResultRecord>(record.payload.value.clone()) {
+                Ok(QueryResultRecord::Change(change)) => change.base.sequence,
+                _ => return false,
+            };
+
+        match self.max_result_sequence {
+            Some(max) if sequence <= max => true,
+            _ => {
+                self.max_result_sequence = Some(sequence);
+                false
+            }
+        }
+    }
+
     async fn process_handler_record(&mut self, record: QueryHandlerRecord) -> anyhow::Result<()> {
+        if self.settings.dedupe_by_sequence && self.is_duplicate_result(&record) {
+            self.duplicate_results_dropped += 1;
+            log::debug!(
+                "Dropped duplicate result for TestRunQuery {}: sequence: {:?}",
+                self.settings.id,
+                record.payload.sequence
+            );
+            return Ok(());
+        }
+
         self.log_handler_record(&record).await;
 
         // Extract query result from payload
@@ -652,6 +699,13 @@ impl QueryResultObserverInternalState {
 
         match query_result {
             QueryResultRecord::Change(change) => {
+                let observed_ns = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64;
+                self.first_result_observed_ns.get_or_insert(observed_ns);
+                self.last_result_observed_ns = Some(observed_ns);
+
                 if change.base.metadata.is_some() {
                     self.metrics.update_change_record_time(record_time_ns);
                     self.metrics.result_stream_change_record_count += 1;
@@ -862,6 +916,10 @@ impl QueryResultObserverInternalState {
                 .as_nanos() as u64,
             ..Default::default()
         };
+        self.first_result_observed_ns = None;
+        self.last_result_observed_ns = None;
+        self.max_result_sequence = None;
+        self.duplicate_results_dropped = 0;
 
         Ok(())
     }
@@ -1148,6 +1206,9 @@ impl From<&QueryResultObserverInternalState> for QueryResultObserverExternalStat
             result_summary: QueryResultObserverSummary::from(state),
             settings: state.settings.clone(),
             logger_results: state.logger_results.clone(),
+            first_result_observed_ns: state.first_result_observed_ns,
+            last_result_observed_ns: state.last_result_observed_ns,
+            duplicate_results_dropped: state.duplicate_results_dropped,
         }
     }
 }
@@ -1272,3 +1333,168 @@ impl From<&QueryResultObserverInternalState> for QueryResultObserverSummary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use test_data_store::test_run_storage::TestRunId;
+
+    use crate::queries::{
+        query_output_handler::{QueryHandlerPayload, QueryHandlerType},
+        result_stream_loggers::jsonl_file_logger::JsonlFileResultStreamLoggerConfig,
+    };
+
+    use super::*;
+
+    async fn create_state(dir: &std::path::Path) -> QueryResultObserverInternalState {
+        create_state_with(dir, vec![], false).await
+    }
+
+    async fn create_state_with(
+        dir: &std::path::Path,
+        loggers: Vec<ResultStreamLoggerConfig>,
+        dedupe_by_sequence: bool,
+    ) -> QueryResultObserverInternalState {
+        let test_run_query_id =
+            TestRunQueryId::new(&TestRunId::new("repo", "test", "run"), "query1");
+        let settings = QueryResultObserverSettings {
+            dedupe_by_sequence,
+            definition: TestQueryDefinition {
+                test_query_id: "query1".to_string(),
+                stop_trigger: None,
+            },
+            id: test_run_query_id.clone(),
+            loggers,
+            output_storage: TestRunQueryStorage {
+                id: test_run_query_id,
+                path: dir.to_path_buf(),
+                result_change_path: dir.join("result_changes"),
+            },
+            stop_trigger: None,
+        };
+
+        QueryResultObserverInternalState::initialize(settings)
+            .await
+            .unwrap()
+    }
+
+    fn now_ns() -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    }
+
+    fn handler_record(sequence: u64) -> QueryHandlerRecord {
+        QueryHandlerRecord {
+            handler_type: QueryHandlerType::RedisStream,
+            payload: QueryHandlerPayload {
+                value: serde_json::json!({
+                    "kind": "change",
+                    "queryId": "query1",
+                    "sequence": sequence,
+                    "sourceTimeMs": 0,
+                    "addedResults": [{ "id": sequence }],
+                    "updatedResults": [],
+                    "deletedResults": []
+                }),
+                timestamp: None,
+                sequence: Some(sequence),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_first_and_last_result_observed_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = create_state(dir.path()).await;
+
+        let external = QueryResultObserverExternalState::from(&state);
+        assert_eq!(external.first_result_observed_ns, None);
+        assert_eq!(external.last_result_observed_ns, None);
+
+        let before_first = now_ns();
+        state
+            .process_handler_record(handler_record(1))
+            .await
+            .unwrap();
+        let after_first = now_ns();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let before_last = now_ns();
+        state
+            .process_handler_record(handler_record(2))
+            .await
+            .unwrap();
+        let after_last = now_ns();
+
+        let external = QueryResultObserverExternalState::from(&state);
+        let first = external.first_result_observed_ns.unwrap();
+        let last = external.last_result_observed_ns.unwrap();
+        assert!(before_first <= first && first <= after_first);
+        assert!(before_last <= last && last <= after_last);
+        assert!(last - first >= 20_000_000);
+
+        state.reset().await.unwrap();
+        let external = QueryResultObserverExternalState::from(&state);
+        assert_eq!(external.first_result_observed_ns, None);
+        assert_eq!(external.last_result_observed_ns, None);
+    }
+
+    #[tokio::test]
+    async fn test_dedupe_by_sequence() {
+        let dir = tempfile::tempdir().unwrap();
+        let loggers = vec![ResultStreamLoggerConfig::JsonlFile(
+            JsonlFileResultStreamLoggerConfig {
+                max_lines_per_file: None,
+            },
+        )];
+        let mut state = create_state_with(dir.path(), loggers, true).await;
+
+        // A restart replays 2 and 3, and 5 arrives before 4.
+        for sequence in [1, 2, 3, 2, 3, 4, 6, 5, 6, 7] {
+            state
+                .process_handler_record(handler_record(sequence))
+                .await
+                .unwrap();
+        }
+        state.close_loggers().await.unwrap();
+
+        let log = std::fs::read_to_string(
+            dir.path()
+                .join("result_changes")
+                .join("jsonl_file")
+                .join("results_00000.jsonl"),
+        )
+        .unwrap();
+        let logged: Vec<u64> = log
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["sequence"]
+                    .as_u64()
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(logged, vec![1, 2, 3, 4, 6, 7]);
+
+        let external = QueryResultObserverExternalState::from(&state);
+        assert_eq!(external.duplicate_results_dropped, 4);
+        // Dropped results aren't counted. The test records have no metadata, so they count as
+        // bootstrap results.
+        assert_eq!(state.metrics.result_stream_bootstrap_record_count, 6);
+
+        // Reset forgets the sequences seen, so a replay from the start is kept.
+        state.reset().await.unwrap();
+        assert_eq!(
+            QueryResultObserverExternalState::from(&state).duplicate_results_dropped,
+            0
+        );
+        state
+            .process_handler_record(handler_record(1))
+            .await
+            .unwrap();
+        assert_eq!(state.duplicate_results_dropped, 0);
+    }
+}
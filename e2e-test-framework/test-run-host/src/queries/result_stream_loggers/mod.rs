@@ -66,6 +66,36 @@ pub struct ResultStreamLoggerResult {
     pub output_folder_path: Option<PathBuf>,
 }
 
+// A page of the results of a query's loggers, along with the total number of results so
+// callers can tell when they have them all.
+#[derive(Clone, Debug, Serialize)]
+pub struct ResultStreamLoggerResultPage {
+    pub offset: usize,
+    pub results: Vec<ResultStreamLoggerResult>,
+    pub total: usize,
+}
+
+impl ResultStreamLoggerResultPage {
+    // Takes up to limit results starting at offset, or all remaining results if limit is None.
+    // A negative offset is treated as 0 and an offset past the end gives an empty page.
+    pub fn new(results: &[ResultStreamLoggerResult], offset: i64, limit: Option<usize>) -> Self {
+        let total = results.len();
+        let offset = usize::try_from(offset.max(0))
+            .unwrap_or(usize::MAX)
+            .min(total);
+        let end = match limit {
+            Some(limit) => offset.saturating_add(limit).min(total),
+            None => total,
+        };
+
+        Self {
+            offset,
+            results: results[offset..end].to_vec(),
+            total,
+        }
+    }
+}
+
 #[async_trait]
 pub trait ResultStreamLogger: Send + Sync {
     async fn end_test_run(&mut self) -> anyhow::Result<ResultStreamLoggerResult>;
@@ -119,3 +149,62 @@ pub async fn create_result_stream_loggers(
     }
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger_results(count: usize) -> Vec<ResultStreamLoggerResult> {
+        (0..count)
+            .map(|i| ResultStreamLoggerResult {
+                has_output: true,
+                logger_name: format!("logger-{}", i),
+                output_folder_path: None,
+            })
+            .collect()
+    }
+
+    fn logger_names(results: &[ResultStreamLoggerResult]) -> Vec<String> {
+        results.iter().map(|r| r.logger_name.clone()).collect()
+    }
+
+    #[test]
+    fn test_result_page() {
+        let results = logger_results(5);
+
+        let first = ResultStreamLoggerResultPage::new(&results, 0, Some(3));
+        assert_eq!(first.offset, 0);
+        assert_eq!(first.total, 5);
+        assert_eq!(first.results.len(), 3);
+
+        let second = ResultStreamLoggerResultPage::new(
+            &results,
+            (first.offset + first.results.len()) as i64,
+            Some(3),
+        );
+        assert_eq!(second.offset, 3);
+        assert_eq!(second.total, 5);
+        assert_eq!(second.results.len(), 2);
+
+        let mut pages = first.results;
+        pages.extend(second.results);
+        assert_eq!(logger_names(&pages), logger_names(&results));
+    }
+
+    #[test]
+    fn test_result_page_clamps_offset() {
+        let results = logger_results(5);
+
+        let page = ResultStreamLoggerResultPage::new(&results, -2, Some(2));
+        assert_eq!(page.offset, 0);
+        assert_eq!(logger_names(&page.results), vec!["logger-0", "logger-1"]);
+
+        let page = ResultStreamLoggerResultPage::new(&results, 10, None);
+        assert_eq!(page.offset, 5);
+        assert!(page.results.is_empty());
+        assert_eq!(page.total, 5);
+
+        let page = ResultStreamLoggerResultPage::new(&results, 4, Some(usize::MAX));
+        assert_eq!(logger_names(&page.results), vec!["logger-4"]);
+    }
+}
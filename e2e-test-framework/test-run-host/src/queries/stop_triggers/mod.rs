@@ -68,5 +68,11 @@ pub async fn create_stop_trigger(
             RecordSequenceNumberStopTrigger::new(def)
         }
         StopTriggerDefinition::RecordCount(def) => RecordCountStopTrigger::new(def),
+        StopTriggerDefinition::MaxDuration(_) => {
+            anyhow::bail!("MaxDuration stop triggers are only supported for reactions")
+        }
+        StopTriggerDefinition::ThroughputBelow(_) => {
+            anyhow::bail!("ThroughputBelow stop triggers are only supported for reactions")
+        }
     }
 }
@@ -0,0 +1,291 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+use test_data_store::test_repo_storage::models::ExpectedResultsDefinition;
+
+use crate::utils::{normalize_value, FieldNormalization};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ExpectedResultsSummary {
+    pub matched: u64,
+    pub missing: u64,
+    pub unexpected: u64,
+}
+
+impl ExpectedResultsSummary {
+    pub fn passed(&self) -> bool {
+        self.missing == 0 && self.unexpected == 0
+    }
+}
+
+// Checks the parts of an ExpectedResultsDefinition that don't need the results to be loaded.
+pub fn validate_expected_results(definition: &ExpectedResultsDefinition) -> anyhow::Result<()> {
+    match (&definition.file, &definition.results) {
+        (Some(_), Some(_)) => {
+            anyhow::bail!("expected_results must have either a file or results, not both")
+        }
+        (None, None) => anyhow::bail!("expected_results must have a file or results"),
+        _ => {}
+    }
+
+    if definition.key_paths.is_empty() {
+        anyhow::bail!("expected_results must have at least one key path");
+    }
+    for path in definition.key_paths.iter() {
+        if !path.is_empty() && !path.starts_with('/') {
+            anyhow::bail!(
+                "expected_results key path {:?} is not a JSON pointer; it must start with '/'",
+                path
+            );
+        }
+    }
+
+    Ok(())
+}
+
+// Matches reaction invocations against the expected results. Each expected result can be
+// matched once, so an expected result listed twice needs two matching invocations.
+#[derive(Debug)]
+pub struct ExpectedResultsTracker {
+    key_paths: Vec<String>,
+    normalizations: Vec<FieldNormalization>,
+    // Number of expected results with each key that haven't been matched yet.
+    remaining: HashMap<String, u64>,
+    matched: u64,
+    unexpected: u64,
+}
+
+impl ExpectedResultsTracker {
+    pub fn new(
+        key_paths: Vec<String>,
+        normalizations: Vec<FieldNormalization>,
+        expected: &[Value],
+    ) -> Self {
+        let mut tracker = Self {
+            key_paths,
+            normalizations,
+            remaining: HashMap::new(),
+            matched: 0,
+            unexpected: 0,
+        };
+
+        for result in expected {
+            let key = tracker.key(result);
+            *tracker.remaining.entry(key).or_insert(0) += 1;
+        }
+
+        tracker
+    }
+
+    // Loads the expected results from the definition. The file path is resolved against the
+    // test folder when the reaction is added to the test run.
+    pub async fn load(definition: &ExpectedResultsDefinition) -> anyhow::Result<Self> {
+        validate_expected_results(definition)?;
+
+        let expected = match (&definition.file, &definition.results) {
+            (Some(path), _) => {
+                let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+                    anyhow::anyhow!("Error reading expected results file {:?}: {}", path, e)
+                })?;
+                parse_expected_results(&content).map_err(|e| {
+                    anyhow::anyhow!("Error parsing expected results file {:?}: {}", path, e)
+                })?
+            }
+            (None, Some(results)) => results.clone(),
+            (None, None) => unreachable!("validated above"),
+        };
+
+        Ok(Self::new(
+            definition.key_paths.clone(),
+            definition.normalizations.clone(),
+            &expected,
+        ))
+    }
+
+    // The key is the JSON array of the values at the key paths, with null for missing values,
+    // taken after the value is normalized.
+    fn key(&self, value: &Value) -> String {
+        let value = normalize_value(value, &self.normalizations);
+        let values: Vec<&Value> = self
+            .key_paths
+            .iter()
+            .map(|path| value.pointer(path).unwrap_or(&Value::Null))
+            .collect();
+        serde_json::to_string(&values).unwrap_or_default()
+    }
+
+    pub fn record(&mut self, value: &Value) {
+        let key = self.key(value);
+        match self.remaining.get_mut(&key) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                self.matched += 1;
+            }
+            _ => {
+                log::debug!("Unexpected reaction result with key {}", key);
+                self.unexpected += 1;
+            }
+        }
+    }
+
+    pub fn summary(&self) -> ExpectedResultsSummary {
+        ExpectedResultsSummary {
+            matched: self.matched,
+            missing: self.remaining.values().sum(),
+            unexpected: self.unexpected,
+        }
+    }
+}
+
+// Expected results files hold either a JSON array of results or one result per line.
+fn parse_expected_results(content: &str) -> anyhow::Result<Vec<Value>> {
+    if let Ok(Value::Array(results)) = serde_json::from_str::<Value>(content) {
+        return Ok(results);
+    }
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn definition(file: Option<&str>, results: Option<Vec<Value>>) -> ExpectedResultsDefinition {
+        ExpectedResultsDefinition {
+            file: file.map(|f| f.to_string()),
+            key_paths: vec!["/query_id".to_string(), "/after/id".to_string()],
+            normalizations: Vec::new(),
+            results,
+        }
+    }
+
+    #[test]
+    fn test_tracker_counts() {
+        let expected = vec![
+            json!({"query_id": "q1", "after": {"id": "a"}}),
+            json!({"query_id": "q1", "after": {"id": "b"}}),
+            json!({"query_id": "q1", "after": {"id": "b"}}),
+        ];
+        let mut tracker = ExpectedResultsTracker::new(
+            vec!["/query_id".to_string(), "/after/id".to_string()],
+            Vec::new(),
+            &expected,
+        );
+
+        // Fields outside the key paths don't affect matching.
+        tracker.record(&json!({"query_id": "q1", "after": {"id": "a", "temp": 20}}));
+        tracker.record(&json!({"query_id": "q1", "after": {"id": "b"}}));
+        tracker.record(&json!({"query_id": "q2", "after": {"id": "b"}}));
+        // A repeat is unexpected once the expected result has been matched.
+        tracker.record(&json!({"query_id": "q1", "after": {"id": "a"}}));
+
+        assert_eq!(
+            tracker.summary(),
+            ExpectedResultsSummary {
+                matched: 2,
+                missing: 1,
+                unexpected: 2,
+            }
+        );
+        assert!(!tracker.summary().passed());
+    }
+
+    #[test]
+    fn test_validate_expected_results() {
+        assert!(validate_expected_results(&definition(Some("expected.json"), None)).is_ok());
+        assert!(validate_expected_results(&definition(None, Some(vec![]))).is_ok());
+        assert!(validate_expected_results(&definition(None, None)).is_err());
+        assert!(validate_expected_results(&definition(Some("a.json"), Some(vec![]))).is_err());
+
+        let mut bad_path = definition(None, Some(vec![]));
+        bad_path.key_paths = vec!["query_id".to_string()];
+        assert!(validate_expected_results(&bad_path).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_normalized_key_paths() {
+        // Expected results record ms timestamps and 2 decimal readings; the invocations have
+        // ns timestamps and unrounded readings.
+        let definition: ExpectedResultsDefinition = serde_json::from_value(json!({
+            "key_paths": ["/after/ts", "/after/temp"],
+            "normalizations": [
+                { "field": "after.ts", "kind": "Timestamp", "precision": "Milliseconds" },
+                { "field": "after.temp", "kind": "Float", "decimals": 2 }
+            ],
+            "results": [{ "after": { "ts": 1_700_000_000_123_i64, "temp": 21.5 } }]
+        }))
+        .unwrap();
+
+        let mut tracker = ExpectedResultsTracker::load(&definition).await.unwrap();
+        let ts_ns = 1_700_000_000_123_456_789_i64;
+        tracker.record(&json!({ "after": { "ts": ts_ns, "temp": 21.50001 } }));
+        // A millisecond later doesn't match.
+        tracker.record(&json!({ "after": { "ts": ts_ns + 1_000_000, "temp": 21.5 } }));
+
+        assert_eq!(
+            tracker.summary(),
+            ExpectedResultsSummary {
+                matched: 1,
+                missing: 0,
+                unexpected: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            dir.path().join("expected.jsonl"),
+            "{\"query_id\": \"q1\", \"after\": {\"id\": \"a\"}}\n\n{\"query_id\": \"q1\", \"after\": {\"id\": \"b\"}}\n",
+        )
+        .await
+        .unwrap();
+        tokio::fs::write(
+            dir.path().join("expected.json"),
+            "[{\"query_id\": \"q1\", \"after\": {\"id\": \"a\"}}]",
+        )
+        .await
+        .unwrap();
+
+        let path = |file: &str| dir.path().join(file).to_string_lossy().to_string();
+
+        let tracker =
+            ExpectedResultsTracker::load(&definition(Some(&path("expected.jsonl")), None))
+                .await
+                .unwrap();
+        assert_eq!(tracker.summary().missing, 2);
+
+        let tracker = ExpectedResultsTracker::load(&definition(Some(&path("expected.json")), None))
+            .await
+            .unwrap();
+        assert_eq!(tracker.summary().missing, 1);
+
+        assert!(
+            ExpectedResultsTracker::load(&definition(Some(&path("missing.json")), None))
+                .await
+                .is_err()
+        );
+    }
+}
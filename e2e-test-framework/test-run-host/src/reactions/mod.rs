@@ -23,12 +23,13 @@ use std::fmt;
 use derive_more::Debug;
 use serde::{Deserialize, Serialize};
 
-use output_loggers::OutputLoggerConfig;
+use output_loggers::{JsonlFileOutputLoggerConfig, OutputLoggerConfig};
 use test_data_store::{
     test_repo_storage::models::{ReactionHandlerDefinition, StopTriggerDefinition},
     test_run_storage::{ParseTestRunIdError, TestRunId, TestRunReactionId, TestRunReactionStorage},
 };
 
+pub mod expected_results;
 pub mod output_loggers;
 pub mod reaction_handlers;
 pub mod reaction_observer;
@@ -44,7 +45,16 @@ pub use reaction_output_handler::{
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TestRunReactionOverrides {
+    // JSON paths of the payload fields to keep in logged records, e.g. "$.addedResults[*].id".
+    #[serde(default)]
+    pub project: Option<Vec<String>>,
+    // When payloads are projected, also log the full records to a separate JsonlFile logger.
+    #[serde(default)]
+    pub raw_output_logger: Option<JsonlFileOutputLoggerConfig>,
     pub stop_triggers: Option<Vec<StopTriggerDefinition>>,
+    // Record every stop trigger evaluation to stop_trigger_trace.jsonl. Off by default
+    // because a trace record is written for every trigger on every invocation.
+    pub stop_trigger_trace: Option<bool>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -68,6 +78,21 @@ fn default_start_immediately() -> bool {
     false
 }
 
+impl TestRunReactionConfig {
+    // Creates a config for the TestReaction with the same defaults used when deserializing.
+    pub fn new(test_reaction_id: &str) -> Self {
+        Self {
+            start_immediately: default_start_immediately(),
+            test_reaction_id: test_reaction_id.to_string(),
+            test_run_overrides: None,
+            output_loggers: Vec::new(),
+            test_id: None,
+            test_repo_id: None,
+            test_run_id: None,
+        }
+    }
+}
+
 impl TryFrom<&TestRunReactionConfig> for TestRunId {
     type Error = ParseTestRunIdError;
 
@@ -104,7 +129,7 @@ impl fmt::Display for TestRunReactionConfig {
 pub struct TestRunReactionDefinition {
     pub id: TestRunReactionId,
     pub start_immediately: bool,
-    pub reaction_handler_definition: ReactionHandlerDefinition,
+    pub reaction_handler_definitions: Vec<ReactionHandlerDefinition>,
     pub test_reaction_definition:
         test_data_store::test_repo_storage::models::TestReactionDefinition,
     pub test_run_overrides: Option<TestRunReactionOverrides>,
@@ -115,16 +140,25 @@ impl TestRunReactionDefinition {
     pub fn new(
         test_run_reaction_config: TestRunReactionConfig,
         test_reaction_definition: test_data_store::test_repo_storage::models::TestReactionDefinition,
-        reaction_handler_definition: ReactionHandlerDefinition,
+        reaction_handler_definitions: Vec<ReactionHandlerDefinition>,
         output_loggers: Vec<OutputLoggerConfig>,
     ) -> anyhow::Result<Self> {
         let test_run_id = TestRunId::try_from(&test_run_reaction_config)?;
         let id = TestRunReactionId::new(&test_run_id, &test_run_reaction_config.test_reaction_id);
 
+        if reaction_handler_definitions.is_empty() {
+            anyhow::bail!("No reaction handler defined for reaction {}", id);
+        }
+
+        if let Some(expected_results) = &test_reaction_definition.expected_results {
+            expected_results::validate_expected_results(expected_results)
+                .map_err(|e| anyhow::anyhow!("Invalid expected_results for {}: {}", id, e))?;
+        }
+
         Ok(Self {
             id,
             start_immediately: test_run_reaction_config.start_immediately,
-            reaction_handler_definition,
+            reaction_handler_definitions,
             test_reaction_definition,
             test_run_overrides: test_run_reaction_config.test_run_overrides,
             output_loggers,
@@ -176,11 +210,12 @@ impl TestRunReaction {
 
         let reaction_observer = reaction_observer::ReactionObserver::new(
             definition.id.clone(),
-            definition.reaction_handler_definition.clone(),
+            definition.reaction_handler_definitions.clone(),
             output_storage,
             output_loggers,
             stop_triggers,
             definition.test_run_overrides,
+            definition.test_reaction_definition.expected_results.clone(),
         )
         .await?;
 
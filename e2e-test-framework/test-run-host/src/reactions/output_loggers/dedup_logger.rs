@@ -0,0 +1,396 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! De-duplication output logger for detecting repeated reaction results
+//!
+//! Under at-least-once delivery a reaction can receive the same result more than once.
+//! This logger tracks the key of every record it sees, per query, and reports how many
+//! records repeated a key that was already seen. It only observes records; the other
+//! loggers of the reaction still receive every record.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use test_data_store::test_run_storage::{TestRunReactionId, TestRunReactionStorage};
+
+use crate::common::{HandlerPayload, HandlerRecord};
+use crate::queries::result_stream_record::QueryResultRecord;
+
+use super::{OutputLogger, OutputLoggerResult};
+
+/// Default number of duplicated keys included in the summary.
+const DEFAULT_SAMPLE_SIZE: usize = 10;
+
+/// Configuration for the de-duplication output logger
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DedupOutputLoggerConfig {
+    /// Optional JSON pointer into the payload body to read the key from, e.g. `/id`.
+    /// Defaults to the record id, which is the invocation id of a reaction invocation.
+    pub key_field: Option<String>,
+    /// Optional limit on the number of keys tracked. Records with a new key are counted
+    /// as untracked once the limit is reached.
+    pub max_tracked_keys: Option<usize>,
+    /// Optional number of duplicated keys to include in the summary, defaults to 10
+    pub sample_size: Option<usize>,
+    /// Optional custom filename for the de-duplication summary output
+    pub filename: Option<String>,
+}
+
+/// A key that was seen more than once
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateKey {
+    pub query_id: String,
+    pub key: String,
+}
+
+/// De-duplication summary written when the test run ends
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DedupSummary {
+    /// Test run reaction identifier
+    pub test_run_reaction_id: String,
+    /// Number of records observed
+    pub record_count: u64,
+    /// Number of records whose key had already been seen
+    pub duplicate_count: u64,
+    /// Number of duplicate records keyed by query id
+    pub duplicates_by_query: BTreeMap<String, u64>,
+    /// Sample of the keys that were duplicated, in the order they were first duplicated
+    pub duplicate_key_sample: Vec<DuplicateKey>,
+    /// Number of distinct keys tracked
+    pub tracked_key_count: usize,
+    /// Number of records with a new key that arrived after max_tracked_keys was reached
+    pub untracked_record_count: u64,
+    /// Number of records that had no value at key_field
+    pub records_without_key: u64,
+}
+
+/// De-duplication output logger implementation
+pub struct DedupOutputLogger {
+    /// JSON pointer to the key in the payload body; the record id is used if None
+    key_field: Option<String>,
+    /// Maximum number of keys tracked
+    max_tracked_keys: Option<usize>,
+    /// Maximum number of duplicated keys kept for the summary
+    sample_size: usize,
+    /// Keys seen so far, as (query id, key) pairs
+    seen_keys: HashSet<(String, String)>,
+    /// Summary counters
+    summary: DedupSummary,
+    /// Path where the summary file will be written
+    output_path: PathBuf,
+}
+
+impl DedupOutputLogger {
+    /// Create a new de-duplication output logger
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        test_run_reaction_id: TestRunReactionId,
+        config: &DedupOutputLoggerConfig,
+        output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
+        log::debug!(
+            "Creating DedupOutputLogger for {} from {:?}",
+            test_run_reaction_id,
+            config
+        );
+
+        if let Some(key_field) = &config.key_field {
+            if !key_field.starts_with('/') {
+                anyhow::bail!(
+                    "Invalid Dedup logger key_field {:?}: must be a JSON pointer starting with '/'",
+                    key_field
+                );
+            }
+        }
+
+        let filename = config.filename.clone().unwrap_or_else(|| {
+            format!("dedup_{}.json", chrono::Utc::now().format("%Y%m%d_%H%M%S"))
+        });
+
+        let output_dir = output_storage.reaction_output_path.join("dedup");
+        if !output_dir.exists() {
+            tokio::fs::create_dir_all(&output_dir).await?;
+        }
+
+        Ok(Box::new(Self::with_output_path(
+            test_run_reaction_id,
+            config,
+            output_dir.join(filename),
+        )))
+    }
+
+    fn with_output_path(
+        test_run_reaction_id: TestRunReactionId,
+        config: &DedupOutputLoggerConfig,
+        output_path: PathBuf,
+    ) -> Self {
+        Self {
+            key_field: config.key_field.clone(),
+            max_tracked_keys: config.max_tracked_keys,
+            sample_size: config.sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE),
+            seen_keys: HashSet::new(),
+            summary: DedupSummary {
+                test_run_reaction_id: test_run_reaction_id.to_string(),
+                record_count: 0,
+                duplicate_count: 0,
+                duplicates_by_query: BTreeMap::new(),
+                duplicate_key_sample: Vec::new(),
+                tracked_key_count: 0,
+                untracked_record_count: 0,
+                records_without_key: 0,
+            },
+            output_path,
+        }
+    }
+
+    /// Extract the query id and the dedup key from a record
+    fn extract_query_and_key(&self, record: &HandlerRecord) -> (String, Option<String>) {
+        let (query_id, body) = match &record.payload {
+            HandlerPayload::ResultStream { query_result } => {
+                let query_id = match query_result {
+                    QueryResultRecord::Change(change) => change.base.query_id.clone(),
+                    QueryResultRecord::Control(control) => control.base.query_id.clone(),
+                };
+                (query_id, serde_json::to_value(query_result).ok())
+            }
+            HandlerPayload::ReactionInvocation {
+                query_id,
+                request_body,
+                ..
+            } => {
+                let query_id = if query_id.is_empty() || query_id == "unknown" {
+                    Self::query_id_from_body(request_body)
+                } else {
+                    query_id.clone()
+                };
+                (query_id, Some(request_body.clone()))
+            }
+            HandlerPayload::ReactionOutput { reaction_output } => (
+                Self::query_id_from_body(reaction_output),
+                Some(reaction_output.clone()),
+            ),
+        };
+
+        let key = match &self.key_field {
+            None => Some(record.id.clone()),
+            Some(pointer) => body
+                .as_ref()
+                .and_then(|body| body.pointer(pointer))
+                .filter(|v| !v.is_null())
+                .map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                }),
+        };
+
+        (query_id, key)
+    }
+
+    fn query_id_from_body(body: &serde_json::Value) -> String {
+        body.pointer("/queryId")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    }
+}
+
+#[async_trait]
+impl OutputLogger for DedupOutputLogger {
+    async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+        self.summary.record_count += 1;
+
+        let (query_id, key) = self.extract_query_and_key(record);
+        let Some(key) = key else {
+            self.summary.records_without_key += 1;
+            return Ok(());
+        };
+
+        let entry = (query_id, key);
+        if self.seen_keys.contains(&entry) {
+            self.summary.duplicate_count += 1;
+            let query_duplicates = self
+                .summary
+                .duplicates_by_query
+                .entry(entry.0.clone())
+                .or_default();
+            *query_duplicates += 1;
+
+            let duplicate_key = DuplicateKey {
+                query_id: entry.0,
+                key: entry.1,
+            };
+            if self.summary.duplicate_key_sample.len() < self.sample_size
+                && !self.summary.duplicate_key_sample.contains(&duplicate_key)
+            {
+                self.summary.duplicate_key_sample.push(duplicate_key);
+            }
+        } else if self
+            .max_tracked_keys
+            .is_some_and(|max| self.seen_keys.len() >= max)
+        {
+            self.summary.untracked_record_count += 1;
+        } else {
+            self.seen_keys.insert(entry);
+        }
+
+        Ok(())
+    }
+
+    async fn end_test_run(&mut self) -> anyhow::Result<OutputLoggerResult> {
+        self.summary.tracked_key_count = self.seen_keys.len();
+        log::info!(
+            "DedupOutputLogger for {} saw {} duplicates in {} records ({} untracked)",
+            self.summary.test_run_reaction_id,
+            self.summary.duplicate_count,
+            self.summary.record_count,
+            self.summary.untracked_record_count
+        );
+
+        let summary_json = serde_json::to_value(&self.summary)?;
+        tokio::fs::write(
+            &self.output_path,
+            serde_json::to_string_pretty(&summary_json)?.as_bytes(),
+        )
+        .await?;
+
+        Ok(OutputLoggerResult {
+            has_output: true,
+            logger_name: "Dedup".to_string(),
+            output_folder_path: self.output_path.parent().map(|p| p.to_path_buf()),
+            summary: Some(summary_json),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use test_data_store::test_run_storage::TestRunId;
+
+    fn create_test_logger(
+        temp_dir: &TempDir,
+        config: DedupOutputLoggerConfig,
+    ) -> DedupOutputLogger {
+        let test_run_id = TestRunId::new("test_repo", "test_id", "test_run_001");
+        DedupOutputLogger::with_output_path(
+            TestRunReactionId::new(&test_run_id, "reaction_001"),
+            &config,
+            temp_dir.path().join("dedup.json"),
+        )
+    }
+
+    fn config(key_field: Option<&str>, max_tracked_keys: Option<usize>) -> DedupOutputLoggerConfig {
+        DedupOutputLoggerConfig {
+            key_field: key_field.map(|f| f.to_string()),
+            max_tracked_keys,
+            sample_size: None,
+            filename: None,
+        }
+    }
+
+    fn invocation_record(invocation_id: &str, request_body: serde_json::Value) -> HandlerRecord {
+        HandlerRecord {
+            id: invocation_id.to_string(),
+            sequence: 0,
+            created_time_ns: 0,
+            processed_time_ns: 0,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionInvocation {
+                reaction_type: "http".to_string(),
+                query_id: "unknown".to_string(),
+                request_method: "POST".to_string(),
+                request_path: "/".to_string(),
+                request_body,
+                headers: Default::default(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reports_duplicate_invocation_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = create_test_logger(&temp_dir, config(None, None));
+
+        for invocation_id in ["q1-1", "q1-2", "q1-3", "q1-2", "q1-2"] {
+            let record = invocation_record(invocation_id, serde_json::json!({ "queryId": "q1" }));
+            logger.log_handler_record(&record).await.unwrap();
+        }
+
+        let result = logger.end_test_run().await.unwrap();
+        assert_eq!(result.logger_name, "Dedup");
+
+        let written: DedupSummary = serde_json::from_str(
+            &std::fs::read_to_string(temp_dir.path().join("dedup.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written.record_count, 5);
+        assert_eq!(written.duplicate_count, 2);
+        assert_eq!(written.duplicates_by_query["q1"], 2);
+        assert_eq!(
+            written.duplicate_key_sample,
+            vec![DuplicateKey {
+                query_id: "q1".to_string(),
+                key: "q1-2".to_string()
+            }]
+        );
+        assert_eq!(written.tracked_key_count, 3);
+
+        let summary = result.summary.unwrap();
+        assert_eq!(summary["duplicate_count"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_key_field_is_scoped_per_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = create_test_logger(&temp_dir, config(Some("/result/id"), None));
+
+        let bodies = [
+            serde_json::json!({ "queryId": "q1", "result": { "id": 1 } }),
+            serde_json::json!({ "queryId": "q2", "result": { "id": 1 } }),
+            serde_json::json!({ "queryId": "q1", "result": { "id": 1 } }),
+            serde_json::json!({ "queryId": "q1" }),
+        ];
+        for (i, body) in bodies.into_iter().enumerate() {
+            let record = invocation_record(&format!("inv-{}", i), body);
+            logger.log_handler_record(&record).await.unwrap();
+        }
+
+        let summary = &logger.summary;
+        assert_eq!(summary.duplicate_count, 1);
+        assert_eq!(summary.duplicates_by_query.get("q2"), None);
+        assert_eq!(summary.duplicate_key_sample[0].key, "1");
+        assert_eq!(summary.records_without_key, 1);
+    }
+
+    #[tokio::test]
+    async fn test_max_tracked_keys_counts_overflow() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = create_test_logger(&temp_dir, config(None, Some(2)));
+
+        for invocation_id in ["a", "b", "c", "d", "a", "c"] {
+            let record = invocation_record(invocation_id, serde_json::json!({ "queryId": "q1" }));
+            logger.log_handler_record(&record).await.unwrap();
+        }
+
+        let summary = &logger.summary;
+        assert_eq!(logger.seen_keys.len(), 2);
+        assert_eq!(summary.duplicate_count, 1);
+        assert_eq!(summary.duplicate_key_sample[0].key, "a");
+        assert_eq!(summary.untracked_record_count, 3);
+    }
+}
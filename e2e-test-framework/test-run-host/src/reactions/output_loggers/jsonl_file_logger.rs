@@ -12,13 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::path::PathBuf;
+use std::{collections::VecDeque, path::PathBuf};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::to_string;
 use tokio::{
-    fs::{create_dir_all, File},
+    fs::{create_dir_all, remove_file, File},
     io::{AsyncWriteExt, BufWriter},
 };
 
@@ -30,6 +30,12 @@ use super::{OutputLogger, OutputLoggerError, OutputLoggerResult};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct JsonlFileOutputLoggerConfig {
+    // Start a new file before a record would take the current file past this size.
+    #[serde(default)]
+    pub max_file_bytes: Option<u64>,
+    // Delete the oldest files so no more than this many are kept.
+    #[serde(default)]
+    pub max_files: Option<usize>,
     pub max_lines_per_file: Option<u64>,
 }
 
@@ -37,6 +43,9 @@ pub struct JsonlFileOutputLoggerConfig {
 pub struct JsonlFileOutputLoggerSettings {
     pub folder_path: PathBuf,
     pub log_name: String,
+    pub logger_name: String,
+    pub max_file_bytes: Option<u64>,
+    pub max_files: Option<usize>,
     pub max_lines_per_file: u64,
     pub test_run_reaction_id: TestRunReactionId,
 }
@@ -46,10 +55,21 @@ impl JsonlFileOutputLoggerSettings {
         test_run_reaction_id: TestRunReactionId,
         config: &JsonlFileOutputLoggerConfig,
         folder_path: PathBuf,
+        logger_name: &str,
     ) -> anyhow::Result<Self> {
+        if config.max_file_bytes == Some(0) {
+            anyhow::bail!("max_file_bytes must be greater than 0");
+        }
+        if config.max_files == Some(0) {
+            anyhow::bail!("max_files must be greater than 0");
+        }
+
         Ok(Self {
             folder_path,
             log_name: "outputs".to_string(),
+            logger_name: logger_name.to_string(),
+            max_file_bytes: config.max_file_bytes,
+            max_files: config.max_files,
             max_lines_per_file: config.max_lines_per_file.unwrap_or(10000),
             test_run_reaction_id,
         })
@@ -68,6 +88,27 @@ impl JsonlFileOutputLogger {
         test_run_reaction_id: TestRunReactionId,
         def: &JsonlFileOutputLoggerConfig,
         output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
+        let folder_path = output_storage.reaction_output_path.join("jsonl_file");
+        Self::create(test_run_reaction_id, def, folder_path, "JsonlFile").await
+    }
+
+    // Creates a logger for the full records of a reaction whose other loggers receive
+    // projected payloads. It writes to its own folder so the two outputs aren't mixed.
+    pub async fn new_raw(
+        test_run_reaction_id: TestRunReactionId,
+        def: &JsonlFileOutputLoggerConfig,
+        output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
+        let folder_path = output_storage.reaction_output_path.join("jsonl_file_raw");
+        Self::create(test_run_reaction_id, def, folder_path, "JsonlFileRaw").await
+    }
+
+    async fn create(
+        test_run_reaction_id: TestRunReactionId,
+        def: &JsonlFileOutputLoggerConfig,
+        folder_path: PathBuf,
+        logger_name: &str,
     ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
         log::debug!(
             "Creating JsonlFileOutputLogger for {} from {:?}, ",
@@ -75,8 +116,12 @@ impl JsonlFileOutputLogger {
             def
         );
 
-        let folder_path = output_storage.reaction_output_path.join("jsonl_file");
-        let settings = JsonlFileOutputLoggerSettings::new(test_run_reaction_id, def, folder_path)?;
+        let settings = JsonlFileOutputLoggerSettings::new(
+            test_run_reaction_id,
+            def,
+            folder_path,
+            logger_name,
+        )?;
         log::trace!(
             "Creating JsonlFileOutputLogger with settings {:?}, ",
             settings
@@ -102,8 +147,9 @@ impl OutputLogger for JsonlFileOutputLogger {
 
         Ok(OutputLoggerResult {
             has_output: true,
-            logger_name: "JsonlFile".to_string(),
+            logger_name: self.settings.logger_name.clone(),
             output_folder_path: Some(self.settings.folder_path.clone()),
+            summary: None,
         })
     }
 
@@ -127,7 +173,12 @@ struct ReactionOutputRecordLogWriter {
     next_file_index: usize,
     current_writer: Option<BufWriter<File>>,
     max_size: u64,
+    max_file_bytes: Option<u64>,
+    max_files: Option<usize>,
     current_file_event_count: u64,
+    current_file_bytes: u64,
+    // Paths of the files written so far that haven't been deleted, oldest first.
+    file_paths: VecDeque<String>,
 }
 
 impl ReactionOutputRecordLogWriter {
@@ -138,7 +189,11 @@ impl ReactionOutputRecordLogWriter {
             next_file_index: 0,
             current_writer: None,
             max_size: settings.max_lines_per_file,
+            max_file_bytes: settings.max_file_bytes,
+            max_files: settings.max_files,
             current_file_event_count: 0,
+            current_file_bytes: 0,
+            file_paths: VecDeque::new(),
         };
 
         writer.open_next_file().await?;
@@ -146,19 +201,34 @@ impl ReactionOutputRecordLogWriter {
     }
 
     pub async fn write_record(&mut self, event: &HandlerRecord) -> anyhow::Result<()> {
+        if self.current_writer.is_none() {
+            return Ok(());
+        }
+
+        let json = format!(
+            "{}\n",
+            to_string(event)
+                .map_err(|e| ReactionOutputRecordLogWriterError::FileWriteError(e.to_string()))?
+        );
+
+        // Records are never split across files, so a new file is started before writing a
+        // record that doesn't fit. A record larger than max_file_bytes gets a file of its own.
+        if let Some(max_file_bytes) = self.max_file_bytes {
+            if self.current_file_event_count > 0
+                && self.current_file_bytes + json.len() as u64 > max_file_bytes
+            {
+                self.open_next_file().await?;
+            }
+        }
+
         if let Some(writer) = &mut self.current_writer {
-            let json = format!(
-                "{}\n",
-                to_string(event).map_err(
-                    |e| ReactionOutputRecordLogWriterError::FileWriteError(e.to_string())
-                )?
-            );
             writer
                 .write_all(json.as_bytes())
                 .await
                 .map_err(|e| ReactionOutputRecordLogWriterError::FileWriteError(e.to_string()))?;
 
             self.current_file_event_count += 1;
+            self.current_file_bytes += json.len() as u64;
 
             if self.current_file_event_count >= self.max_size {
                 self.open_next_file().await?;
@@ -191,10 +261,26 @@ impl ReactionOutputRecordLogWriter {
             .await
             .map_err(|_| ReactionOutputRecordLogWriterError::CantOpenFile(file_path.clone()))?;
         self.current_writer = Some(BufWriter::new(file));
+        self.file_paths.push_back(file_path);
 
         // Increment the file index and event count
         self.next_file_index += 1;
         self.current_file_event_count = 0;
+        self.current_file_bytes = 0;
+
+        // Delete the oldest files if there are more than max_files.
+        if let Some(max_files) = self.max_files {
+            while self.file_paths.len() > max_files {
+                if let Some(oldest) = self.file_paths.pop_front() {
+                    remove_file(&oldest).await.map_err(|e| {
+                        ReactionOutputRecordLogWriterError::FileWriteError(format!(
+                            "Can't delete {}: {}",
+                            oldest, e
+                        ))
+                    })?;
+                }
+            }
+        }
 
         Ok(())
     }
@@ -18,24 +18,35 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
 pub use console_logger::{ConsoleOutputLogger, ConsoleOutputLoggerConfig};
+pub use dedup_logger::{DedupOutputLogger, DedupOutputLoggerConfig};
 pub use jsonl_file_logger::{JsonlFileOutputLogger, JsonlFileOutputLoggerConfig};
+pub use parquet_logger::{ParquetCompression, ParquetOutputLogger, ParquetOutputLoggerConfig};
+pub use payload_projection::{PayloadProjection, ProjectingOutputLogger};
 pub use performance_metrics_logger::{
     PerformanceMetricsOutputLogger, PerformanceMetricsOutputLoggerConfig,
 };
+pub use reaction_latency_logger::{ReactionLatencyOutputLogger, ReactionLatencyOutputLoggerConfig};
 use test_data_store::test_run_storage::{TestRunReactionId, TestRunReactionStorage};
 
 use crate::common::HandlerRecord;
 
 pub mod console_logger;
+pub mod dedup_logger;
 pub mod jsonl_file_logger;
+pub mod parquet_logger;
+pub mod payload_projection;
 pub mod performance_metrics_logger;
+pub mod reaction_latency_logger;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(tag = "kind")]
 pub enum OutputLoggerConfig {
     Console(ConsoleOutputLoggerConfig),
+    Dedup(DedupOutputLoggerConfig),
     JsonlFile(JsonlFileOutputLoggerConfig),
+    Parquet(ParquetOutputLoggerConfig),
     PerformanceMetrics(PerformanceMetricsOutputLoggerConfig),
+    ReactionLatency(ReactionLatencyOutputLoggerConfig),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -58,6 +69,18 @@ pub struct OutputLoggerResult {
     pub has_output: bool,
     pub logger_name: String,
     pub output_folder_path: Option<PathBuf>,
+    // Optional logger-specific summary, e.g. the latency percentiles of the ReactionLatency logger.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<serde_json::Value>,
+}
+
+// Records an OutputLogger that failed to log a record. A failed logger receives no further
+// records, but the observer and its other loggers keep running.
+#[derive(Clone, Debug, Serialize)]
+pub struct OutputLoggerFailure {
+    pub logger_index: usize,
+    pub error: String,
+    pub record_sequence: u64,
 }
 
 #[async_trait]
@@ -88,12 +111,21 @@ pub async fn create_output_logger(
     );
     match config {
         OutputLoggerConfig::Console(cfg) => ConsoleOutputLogger::new(test_run_reaction_id, cfg),
+        OutputLoggerConfig::Dedup(cfg) => {
+            DedupOutputLogger::new(test_run_reaction_id, cfg, output_storage).await
+        }
         OutputLoggerConfig::JsonlFile(cfg) => {
             JsonlFileOutputLogger::new(test_run_reaction_id, cfg, output_storage).await
         }
+        OutputLoggerConfig::Parquet(cfg) => {
+            ParquetOutputLogger::new(test_run_reaction_id, cfg, output_storage).await
+        }
         OutputLoggerConfig::PerformanceMetrics(cfg) => {
             PerformanceMetricsOutputLogger::new(test_run_reaction_id, cfg, output_storage).await
         }
+        OutputLoggerConfig::ReactionLatency(cfg) => {
+            ReactionLatencyOutputLogger::new(test_run_reaction_id, cfg, output_storage).await
+        }
     }
 }
 
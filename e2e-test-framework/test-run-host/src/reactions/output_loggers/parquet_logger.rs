@@ -0,0 +1,365 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fs::File,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use arrow::{
+    array::{ArrayRef, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, GzipLevel, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use serde::{Deserialize, Serialize};
+use tokio::fs::create_dir_all;
+
+use test_data_store::test_run_storage::{TestRunReactionId, TestRunReactionStorage};
+
+use crate::{
+    common::{HandlerPayload, HandlerRecord},
+    queries::result_stream_record::QueryResultRecord,
+};
+
+use super::{OutputLogger, OutputLoggerError, OutputLoggerResult};
+
+// The number of records buffered before they are written to the current file as a batch.
+const BATCH_SIZE: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParquetCompression {
+    None,
+    #[default]
+    Snappy,
+    Gzip,
+    Zstd,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(compression: ParquetCompression) -> Self {
+        match compression {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+            ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ParquetOutputLoggerConfig {
+    #[serde(default)]
+    pub compression: ParquetCompression,
+    // Relative paths are resolved against the reaction's output folder.
+    pub folder_path: Option<PathBuf>,
+    pub max_rows_per_file: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct ParquetOutputLoggerSettings {
+    pub compression: ParquetCompression,
+    pub folder_path: PathBuf,
+    pub log_name: String,
+    pub max_rows_per_file: u64,
+    pub test_run_reaction_id: TestRunReactionId,
+}
+
+impl ParquetOutputLoggerSettings {
+    pub fn new(
+        test_run_reaction_id: TestRunReactionId,
+        config: &ParquetOutputLoggerConfig,
+        output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Self> {
+        let folder_path = output_storage
+            .reaction_output_path
+            .join(config.folder_path.clone().unwrap_or("parquet".into()));
+
+        Ok(Self {
+            compression: config.compression,
+            folder_path,
+            log_name: "outputs".to_string(),
+            max_rows_per_file: config.max_rows_per_file.unwrap_or(100000).max(1),
+            test_run_reaction_id,
+        })
+    }
+}
+
+// Writes HandlerRecords to a sequence of Parquet files, each holding at most max_rows_per_file
+// rows. Every file has the same schema: the record metadata and the payload fields common to
+// the payload types are typed columns, and the rest of the payload is a JSON string column.
+pub struct ParquetOutputLogger {
+    buffer: Vec<HandlerRecord>,
+    current_file_row_count: u64,
+    next_file_index: usize,
+    schema: SchemaRef,
+    settings: ParquetOutputLoggerSettings,
+    // ArrowWriter isn't Sync, which an OutputLogger must be; the Mutex is never contended.
+    writer: Option<Mutex<ArrowWriter<File>>>,
+}
+
+impl ParquetOutputLogger {
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        test_run_reaction_id: TestRunReactionId,
+        def: &ParquetOutputLoggerConfig,
+        output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
+        log::debug!(
+            "Creating ParquetOutputLogger for {} from {:?}, ",
+            test_run_reaction_id,
+            def
+        );
+
+        let settings = ParquetOutputLoggerSettings::new(test_run_reaction_id, def, output_storage)?;
+        log::trace!(
+            "Creating ParquetOutputLogger with settings {:?}, ",
+            settings
+        );
+
+        if !std::path::Path::new(&settings.folder_path).exists() {
+            match create_dir_all(&settings.folder_path).await {
+                Ok(_) => {}
+                Err(e) => return Err(OutputLoggerError::Io(e).into()),
+            };
+        }
+
+        Ok(Box::new(Self {
+            buffer: Vec::new(),
+            current_file_row_count: 0,
+            next_file_index: 0,
+            schema: handler_record_schema(),
+            settings,
+            writer: None,
+        }))
+    }
+
+    // Writes the buffered records to the current file, closing the file once it is full.
+    fn flush_buffer(&mut self) -> anyhow::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        if self.writer.is_none() {
+            self.open_next_file()?;
+        }
+
+        let batch = to_record_batch(self.schema.clone(), &self.buffer)?;
+        if let Some(writer) = &mut self.writer {
+            writer
+                .get_mut()
+                .map_err(|_| anyhow::anyhow!("Parquet writer lock poisoned"))?
+                .write(&batch)?;
+        }
+        self.current_file_row_count += self.buffer.len() as u64;
+        self.buffer.clear();
+
+        if self.current_file_row_count >= self.settings.max_rows_per_file {
+            self.close_file()?;
+        }
+
+        Ok(())
+    }
+
+    fn open_next_file(&mut self) -> anyhow::Result<()> {
+        // The file index is a 5 digit zero-padded number to ensure the files are sorted correctly.
+        let file_path = self.settings.folder_path.join(format!(
+            "{}_{:05}.parquet",
+            self.settings.log_name, self.next_file_index
+        ));
+
+        let file = File::create(&file_path).map_err(OutputLoggerError::Io)?;
+        let props = WriterProperties::builder()
+            .set_compression(self.settings.compression.into())
+            .build();
+
+        self.writer = Some(Mutex::new(ArrowWriter::try_new(
+            file,
+            self.schema.clone(),
+            Some(props),
+        )?));
+        self.next_file_index += 1;
+        self.current_file_row_count = 0;
+
+        Ok(())
+    }
+
+    fn close_file(&mut self) -> anyhow::Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer
+                .into_inner()
+                .map_err(|_| anyhow::anyhow!("Parquet writer lock poisoned"))?
+                .close()?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutputLogger for ParquetOutputLogger {
+    async fn end_test_run(&mut self) -> anyhow::Result<OutputLoggerResult> {
+        self.flush_buffer()?;
+        self.close_file()?;
+
+        Ok(OutputLoggerResult {
+            has_output: self.next_file_index > 0,
+            logger_name: "Parquet".to_string(),
+            output_folder_path: Some(self.settings.folder_path.clone()),
+            summary: None,
+        })
+    }
+
+    async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+        self.buffer.push(record.clone());
+
+        // Flush when the buffer fills a batch or the remainder of the current file.
+        let remaining_rows = self.settings.max_rows_per_file - self.current_file_row_count;
+        if self.buffer.len() as u64 >= remaining_rows.min(BATCH_SIZE as u64) {
+            self.flush_buffer()?;
+        }
+
+        Ok(())
+    }
+}
+
+fn handler_record_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("sequence", DataType::UInt64, false),
+        Field::new("created_time_ns", DataType::UInt64, false),
+        Field::new("processed_time_ns", DataType::UInt64, false),
+        Field::new("traceparent", DataType::Utf8, true),
+        Field::new("tracestate", DataType::Utf8, true),
+        Field::new("payload_type", DataType::Utf8, false),
+        Field::new("query_id", DataType::Utf8, true),
+        Field::new("reaction_type", DataType::Utf8, true),
+        Field::new("request_method", DataType::Utf8, true),
+        Field::new("request_path", DataType::Utf8, true),
+        Field::new("payload", DataType::Utf8, false),
+    ]))
+}
+
+// The payload fields of a HandlerRecord, split into typed columns and a JSON remainder.
+struct PayloadColumns {
+    payload_type: &'static str,
+    query_id: Option<String>,
+    reaction_type: Option<String>,
+    request_method: Option<String>,
+    request_path: Option<String>,
+    payload: String,
+}
+
+impl TryFrom<&HandlerPayload> for PayloadColumns {
+    type Error = OutputLoggerError;
+
+    fn try_from(payload: &HandlerPayload) -> Result<Self, Self::Error> {
+        Ok(match payload {
+            HandlerPayload::ResultStream { query_result } => {
+                let query_id = match query_result {
+                    QueryResultRecord::Change(change) => &change.base.query_id,
+                    QueryResultRecord::Control(control) => &control.base.query_id,
+                };
+                PayloadColumns {
+                    payload_type: "ResultStream",
+                    query_id: Some(query_id.clone()),
+                    reaction_type: None,
+                    request_method: None,
+                    request_path: None,
+                    payload: serde_json::to_string(query_result)?,
+                }
+            }
+            HandlerPayload::ReactionInvocation {
+                reaction_type,
+                query_id,
+                request_method,
+                request_path,
+                request_body,
+                headers,
+            } => PayloadColumns {
+                payload_type: "ReactionInvocation",
+                query_id: Some(query_id.clone()),
+                reaction_type: Some(reaction_type.clone()),
+                request_method: Some(request_method.clone()),
+                request_path: Some(request_path.clone()),
+                payload: serde_json::json!({
+                    "request_body": request_body,
+                    "headers": headers,
+                })
+                .to_string(),
+            },
+            HandlerPayload::ReactionOutput { reaction_output } => PayloadColumns {
+                payload_type: "ReactionOutput",
+                query_id: None,
+                reaction_type: None,
+                request_method: None,
+                request_path: None,
+                payload: reaction_output.to_string(),
+            },
+        })
+    }
+}
+
+fn to_record_batch(schema: SchemaRef, records: &[HandlerRecord]) -> anyhow::Result<RecordBatch> {
+    let payloads = records
+        .iter()
+        .map(|record| PayloadColumns::try_from(&record.payload))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|r| r.id.as_str()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|r| r.sequence),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|r| r.created_time_ns),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|r| r.processed_time_ns),
+        )),
+        Arc::new(StringArray::from_iter(
+            records.iter().map(|r| r.traceparent.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter(
+            records.iter().map(|r| r.tracestate.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            payloads.iter().map(|p| p.payload_type),
+        )),
+        Arc::new(StringArray::from_iter(
+            payloads.iter().map(|p| p.query_id.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter(
+            payloads.iter().map(|p| p.reaction_type.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter(
+            payloads.iter().map(|p| p.request_method.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter(
+            payloads.iter().map(|p| p.request_path.as_deref()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            payloads.iter().map(|p| p.payload.as_str()),
+        )),
+    ];
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
@@ -0,0 +1,283 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Projection of reaction payloads onto a subset of their fields.
+//!
+//! Paths use a simple JSON path syntax: field names separated by `.`, with `[*]` selecting
+//! every element of an array, e.g. `$.addedResults[*].id`. The leading `$.` is optional.
+//! The projected payload keeps the structure of the original, but only the selected fields.
+//! Paths that don't exist in a payload are ignored.
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+use crate::common::{HandlerPayload, HandlerRecord};
+
+use super::{OutputLogger, OutputLoggerResult};
+
+#[derive(Clone, Debug, PartialEq)]
+enum PathSegment {
+    Field(String),
+    AllElements,
+}
+
+#[derive(Clone, Debug)]
+pub struct PayloadProjection {
+    paths: Vec<Vec<PathSegment>>,
+}
+
+impl PayloadProjection {
+    pub fn new(paths: &[String]) -> anyhow::Result<Self> {
+        if paths.is_empty() {
+            anyhow::bail!("Payload projection requires at least one path");
+        }
+
+        Ok(Self {
+            paths: paths
+                .iter()
+                .map(|path| parse_path(path))
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+
+    pub fn project_value(&self, value: &Value) -> Value {
+        let mut projected = Value::Null;
+        for path in self.paths.iter() {
+            project_path(value, path, &mut projected);
+        }
+        projected
+    }
+
+    // Projects the body of the record's payload. Everything else in the record, including
+    // the request metadata and headers of a reaction invocation, is kept.
+    pub fn project_record(&self, record: &HandlerRecord) -> HandlerRecord {
+        let mut projected = record.clone();
+        match &mut projected.payload {
+            HandlerPayload::ReactionInvocation { request_body, .. } => {
+                *request_body = self.project_value(request_body);
+            }
+            HandlerPayload::ReactionOutput { reaction_output } => {
+                *reaction_output = self.project_value(reaction_output);
+            }
+            HandlerPayload::ResultStream { .. } => {}
+        }
+        projected
+    }
+}
+
+fn parse_path(path: &str) -> anyhow::Result<Vec<PathSegment>> {
+    let trimmed = path.trim();
+    let body = match trimmed.strip_prefix('$') {
+        Some(rest) => rest.strip_prefix('.').unwrap_or(rest),
+        None => trimmed,
+    };
+
+    let mut segments = Vec::new();
+    if body.is_empty() {
+        return Ok(segments);
+    }
+
+    for part in body.split('.') {
+        let (name, mut brackets) = match part.find('[') {
+            Some(idx) => part.split_at(idx),
+            None => (part, ""),
+        };
+
+        if name.is_empty() && brackets.is_empty() {
+            anyhow::bail!(
+                "Invalid payload projection path {:?}: empty field name",
+                path
+            );
+        }
+        if !name.is_empty() {
+            segments.push(PathSegment::Field(name.to_string()));
+        }
+
+        while !brackets.is_empty() {
+            match brackets.strip_prefix("[*]") {
+                Some(rest) => {
+                    segments.push(PathSegment::AllElements);
+                    brackets = rest;
+                }
+                None => anyhow::bail!(
+                    "Invalid payload projection path {:?}: only [*] is supported in brackets",
+                    path
+                ),
+            }
+        }
+    }
+
+    Ok(segments)
+}
+
+// Copies the part of value selected by path into projected, merging it with whatever
+// other paths have already copied there.
+fn project_path(value: &Value, path: &[PathSegment], projected: &mut Value) {
+    let Some((segment, rest)) = path.split_first() else {
+        *projected = value.clone();
+        return;
+    };
+
+    match (segment, value) {
+        (PathSegment::Field(name), Value::Object(fields)) => {
+            let Some(child) = fields.get(name) else {
+                return;
+            };
+            if projected.is_null() {
+                *projected = Value::Object(Map::new());
+            }
+            if let Value::Object(projected_fields) = projected {
+                let projected_child = projected_fields.entry(name.clone()).or_insert(Value::Null);
+                project_path(child, rest, projected_child);
+            }
+        }
+        (PathSegment::AllElements, Value::Array(elements)) => {
+            if projected.is_null() {
+                *projected = Value::Array(
+                    elements
+                        .iter()
+                        .map(|element| match element {
+                            Value::Object(_) => Value::Object(Map::new()),
+                            _ => Value::Null,
+                        })
+                        .collect(),
+                );
+            }
+            if let Value::Array(projected_elements) = projected {
+                for (element, projected_element) in elements.iter().zip(projected_elements) {
+                    project_path(element, rest, projected_element);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Wraps an OutputLogger so it receives records with projected payloads.
+pub struct ProjectingOutputLogger {
+    logger: Box<dyn OutputLogger + Send + Sync>,
+    projection: PayloadProjection,
+}
+
+impl ProjectingOutputLogger {
+    pub fn new(logger: Box<dyn OutputLogger + Send + Sync>, projection: PayloadProjection) -> Self {
+        Self { logger, projection }
+    }
+}
+
+#[async_trait]
+impl OutputLogger for ProjectingOutputLogger {
+    async fn end_test_run(&mut self) -> anyhow::Result<OutputLoggerResult> {
+        self.logger.end_test_run().await
+    }
+
+    async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+        self.logger
+            .log_handler_record(&self.projection.project_record(record))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn projection(paths: &[&str]) -> PayloadProjection {
+        let paths: Vec<String> = paths.iter().map(|p| p.to_string()).collect();
+        PayloadProjection::new(&paths).unwrap()
+    }
+
+    #[test]
+    fn test_project_value() {
+        let payload = json!({
+            "queryId": "building-comfort",
+            "sequence": 42,
+            "addedResults": [
+                {"id": "room-1", "temperature": 70, "history": [1, 2, 3]},
+                {"id": "room-2", "temperature": 72, "history": [4, 5, 6]},
+            ],
+            "deletedResults": [],
+        });
+
+        let projected = projection(&["$.queryId", "addedResults[*].id", "missing.field"])
+            .project_value(&payload);
+
+        assert_eq!(
+            projected,
+            json!({
+                "queryId": "building-comfort",
+                "addedResults": [{"id": "room-1"}, {"id": "room-2"}],
+            })
+        );
+    }
+
+    #[test]
+    fn test_overlapping_paths_are_merged() {
+        let payload = json!({"a": {"b": 1, "c": 2, "d": 3}});
+
+        let projected = projection(&["a.b", "a", "a.c"]).project_value(&payload);
+        assert_eq!(projected, payload);
+
+        let projected = projection(&["a.b", "a.d"]).project_value(&payload);
+        assert_eq!(projected, json!({"a": {"b": 1, "d": 3}}));
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        assert!(PayloadProjection::new(&[]).is_err());
+        assert!(PayloadProjection::new(&["a..b".to_string()]).is_err());
+        assert!(PayloadProjection::new(&["items[0].id".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_project_record_keeps_metadata() {
+        let record = HandlerRecord {
+            id: "invocation-1".to_string(),
+            sequence: 1,
+            created_time_ns: 1000,
+            processed_time_ns: 2000,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionInvocation {
+                reaction_type: "Http".to_string(),
+                query_id: "query-1".to_string(),
+                request_method: "POST".to_string(),
+                request_path: "/reaction".to_string(),
+                request_body: json!({"id": "room-1", "history": [1, 2, 3]}),
+                headers: HashMap::from([("x-test".to_string(), "1".to_string())]),
+            },
+        };
+
+        let projected = projection(&["id"]).project_record(&record);
+
+        assert_eq!(projected.id, "invocation-1");
+        match projected.payload {
+            HandlerPayload::ReactionInvocation {
+                request_body,
+                request_path,
+                headers,
+                ..
+            } => {
+                assert_eq!(request_body, json!({"id": "room-1"}));
+                assert_eq!(request_path, "/reaction");
+                assert_eq!(headers.get("x-test").map(|s| s.as_str()), Some("1"));
+            }
+            other => panic!("Unexpected payload {:?}", other),
+        }
+    }
+}
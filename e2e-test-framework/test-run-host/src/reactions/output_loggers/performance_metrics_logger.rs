@@ -17,37 +17,66 @@
 //! This logger tracks timing information and record counts to calculate
 //! performance metrics like records per second. It writes a summary file
 //! when the test run ends with detailed performance statistics.
+//!
+//! An optional warmup window excludes startup transients: records received during
+//! warmup are counted in a separate warmup bucket and don't contribute to the
+//! throughput or latency metrics.
 
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use test_data_store::test_run_storage::{TestRunReactionId, TestRunReactionStorage};
 
 use crate::common::HandlerRecord;
+use crate::utils::{system_clock, Clock};
 
-use super::{OutputLogger, OutputLoggerResult};
+use super::{
+    reaction_latency_logger::{LatencyHistogram, QueryLatencySummary},
+    OutputLogger, OutputLoggerResult,
+};
 
 /// Performance metrics data structure
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
-    /// Timestamp in nanoseconds when first record was received
+    /// Timestamp in nanoseconds when first record after warmup was received
     pub start_time_ns: u64,
     /// Timestamp in nanoseconds when test run ended
     pub end_time_ns: u64,
     /// Total duration in nanoseconds
     pub duration_ns: u64,
-    /// Total number of records processed
+    /// Number of records processed after warmup
     pub record_count: u64,
-    /// Records processed per second
+    /// Records processed per second after warmup
     pub records_per_second: f64,
+    /// Percentiles of the time between a record being created and processed, after warmup
+    #[serde(default)]
+    pub latency: Option<QueryLatencySummary>,
+    /// Records received during warmup, if a warmup was configured
+    #[serde(default)]
+    pub warmup: Option<PerformanceMetricsWarmup>,
     /// Test run reaction identifier
     pub test_run_reaction_id: String,
     /// Timestamp when metrics were written
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Records received during the warmup window, which are excluded from the main metrics
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PerformanceMetricsWarmup {
+    /// Timestamp in nanoseconds when the first record was received
+    pub start_time_ns: u64,
+    /// Timestamp in nanoseconds when warmup ended, or when the test run ended if it never did
+    pub end_time_ns: u64,
+    /// Whether warmup ended before the test run did
+    pub completed: bool,
+    /// Number of records received during warmup
+    pub record_count: u64,
+    /// Percentiles of the time between a record being created and processed, during warmup
+    pub latency: QueryLatencySummary,
+}
+
 impl std::fmt::Display for PerformanceMetrics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -57,25 +86,62 @@ impl std::fmt::Display for PerformanceMetrics {
             self.record_count,
             self.duration_ns as f64 / 1_000_000_000.0,
             self.records_per_second
-        )
+        )?;
+        if let Some(warmup) = &self.warmup {
+            write!(
+                f,
+                ", excluding {} warmup records {} at {} ns",
+                warmup.record_count,
+                if warmup.completed {
+                    "ending"
+                } else {
+                    "still in progress"
+                },
+                warmup.end_time_ns
+            )?;
+        }
+        Ok(())
     }
 }
 
 /// Configuration for the performance metrics output logger
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct PerformanceMetricsOutputLoggerConfig {
     /// Optional custom filename for the metrics output
     pub filename: Option<String>,
+    /// Number of records, from the first, to treat as warmup
+    #[serde(default)]
+    pub warmup_records: Option<u64>,
+    /// Seconds, from the first record, to treat as warmup. If warmup_records is also set,
+    /// warmup ends at whichever limit is reached first.
+    #[serde(default)]
+    pub warmup_seconds: Option<f64>,
 }
 
 /// Performance metrics output logger implementation
 pub struct PerformanceMetricsOutputLogger {
-    /// Timestamp in nanoseconds when first record was received
+    /// Source of the record arrival times; tests swap in a ManualClock
+    clock: Arc<dyn Clock>,
+    /// Timestamp in nanoseconds when first record after warmup was received
     start_time_ns: Option<u64>,
     /// Timestamp in nanoseconds when test run ended
     end_time_ns: u64,
-    /// Total number of records received
+    /// Number of records received after warmup
     record_count: u64,
+    /// Latencies of the records received after warmup
+    latency: LatencyHistogram,
+    /// Number of records, from the first, to treat as warmup
+    warmup_records: Option<u64>,
+    /// Nanoseconds, from the first record, to treat as warmup
+    warmup_ns: Option<u64>,
+    /// Timestamp in nanoseconds when the first warmup record was received
+    warmup_start_time_ns: Option<u64>,
+    /// Timestamp in nanoseconds when warmup ended
+    warmup_end_time_ns: Option<u64>,
+    /// Number of records received during warmup
+    warmup_record_count: u64,
+    /// Latencies of the records received during warmup
+    warmup_latency: LatencyHistogram,
     /// Test run reaction identifier
     test_run_reaction_id: TestRunReactionId,
     /// Storage abstraction for writing output files
@@ -130,31 +196,85 @@ impl PerformanceMetricsOutputLogger {
             output_path
         );
 
+        if let Some(warmup_seconds) = config.warmup_seconds {
+            if !warmup_seconds.is_finite() || warmup_seconds < 0.0 {
+                anyhow::bail!(
+                    "warmup_seconds must be a non-negative number, got {}",
+                    warmup_seconds
+                );
+            }
+        }
+
         Ok(Box::new(Self {
+            clock: system_clock(),
             start_time_ns: None,
             end_time_ns: 0,
             record_count: 0,
+            latency: LatencyHistogram::default(),
+            warmup_records: config.warmup_records,
+            warmup_ns: config
+                .warmup_seconds
+                .map(|seconds| (seconds * 1_000_000_000.0) as u64),
+            warmup_start_time_ns: None,
+            warmup_end_time_ns: None,
+            warmup_record_count: 0,
+            warmup_latency: LatencyHistogram::default(),
             test_run_reaction_id,
             output_storage: output_storage.clone(),
             output_path,
         }))
     }
 
-    /// Get current time in nanoseconds since UNIX epoch
-    fn get_current_time_ns() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_nanos() as u64
+    fn has_warmup(&self) -> bool {
+        self.warmup_records.is_some() || self.warmup_ns.is_some()
+    }
+
+    /// Whether a record received at now_ns falls in the warmup window. The first record
+    /// received after either warmup limit is reached ends the warmup.
+    fn is_warmup(&mut self, now_ns: u64) -> bool {
+        if !self.has_warmup() || self.warmup_end_time_ns.is_some() {
+            return false;
+        }
+
+        let warmup_start_time_ns = *self.warmup_start_time_ns.get_or_insert(now_ns);
+        let records_reached = self
+            .warmup_records
+            .is_some_and(|records| self.warmup_record_count >= records);
+        let time_reached = self
+            .warmup_ns
+            .is_some_and(|ns| now_ns.saturating_sub(warmup_start_time_ns) >= ns);
+
+        if records_reached || time_reached {
+            log::debug!(
+                "PerformanceMetricsOutputLogger: Warmup ended at {} ns after {} records",
+                now_ns,
+                self.warmup_record_count
+            );
+            self.warmup_end_time_ns = Some(now_ns);
+            false
+        } else {
+            true
+        }
     }
 }
 
 #[async_trait]
 impl OutputLogger for PerformanceMetricsOutputLogger {
-    async fn log_handler_record(&mut self, _record: &HandlerRecord) -> anyhow::Result<()> {
-        // Set start time on first record
+    async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+        let now_ns = self.clock.now_ns();
+        let latency_ns = record
+            .processed_time_ns
+            .saturating_sub(record.created_time_ns);
+
+        if self.is_warmup(now_ns) {
+            self.warmup_record_count += 1;
+            self.warmup_latency.record(latency_ns);
+            return Ok(());
+        }
+
+        // Set start time on first record after warmup
         if self.start_time_ns.is_none() {
-            self.start_time_ns = Some(Self::get_current_time_ns());
+            self.start_time_ns = Some(now_ns);
             log::debug!(
                 "PerformanceMetricsOutputLogger: First record received at {} ns",
                 self.start_time_ns.unwrap()
@@ -163,6 +283,7 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
 
         // Increment record count
         self.record_count += 1;
+        self.latency.record(latency_ns);
 
         // Log every 1000 records for debugging
         if self.record_count % 1000 == 0 {
@@ -183,7 +304,7 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
         );
 
         // Capture end time
-        self.end_time_ns = Self::get_current_time_ns();
+        self.end_time_ns = self.clock.now_ns();
 
         // Calculate metrics
         let start_time = self.start_time_ns.unwrap_or(self.end_time_ns);
@@ -200,6 +321,16 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
             0.0
         };
 
+        let warmup =
+            self.warmup_start_time_ns
+                .map(|warmup_start_time_ns| PerformanceMetricsWarmup {
+                    start_time_ns: warmup_start_time_ns,
+                    end_time_ns: self.warmup_end_time_ns.unwrap_or(self.end_time_ns),
+                    completed: self.warmup_end_time_ns.is_some(),
+                    record_count: self.warmup_record_count,
+                    latency: QueryLatencySummary::from(&self.warmup_latency),
+                });
+
         // Create metrics struct
         let metrics = PerformanceMetrics {
             start_time_ns: start_time,
@@ -207,6 +338,8 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
             duration_ns,
             record_count: self.record_count,
             records_per_second,
+            latency: Some(QueryLatencySummary::from(&self.latency)),
+            warmup,
             test_run_reaction_id: self.test_run_reaction_id.to_string(),
             timestamp: chrono::Utc::now(),
         };
@@ -240,6 +373,7 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
             has_output: true,
             logger_name: "PerformanceMetrics".to_string(),
             output_folder_path: Some(output_folder),
+            summary: None,
         })
     }
 }
@@ -248,9 +382,12 @@ impl OutputLogger for PerformanceMetricsOutputLogger {
 mod tests {
     use super::*;
     use crate::common::HandlerPayload;
+    use crate::utils::ManualClock;
     use tempfile::TempDir;
     use test_data_store::test_run_storage::{TestRunId, TestRunReactionStorage};
 
+    const MS: u64 = 1_000_000;
+
     async fn create_test_logger() -> (PerformanceMetricsOutputLogger, TempDir) {
         let temp_dir = TempDir::new().unwrap();
         let test_run_id = TestRunId::new("test_repo", "test_id", "test_run_001");
@@ -264,6 +401,7 @@ mod tests {
 
         let _config = PerformanceMetricsOutputLoggerConfig {
             filename: Some("test_metrics.json".to_string()),
+            ..Default::default()
         };
 
         // Create output directory
@@ -273,9 +411,17 @@ mod tests {
         tokio::fs::create_dir_all(&output_dir).await.unwrap();
 
         let logger = PerformanceMetricsOutputLogger {
+            clock: system_clock(),
             start_time_ns: None,
             end_time_ns: 0,
             record_count: 0,
+            latency: LatencyHistogram::default(),
+            warmup_records: None,
+            warmup_ns: None,
+            warmup_start_time_ns: None,
+            warmup_end_time_ns: None,
+            warmup_record_count: 0,
+            warmup_latency: LatencyHistogram::default(),
             test_run_reaction_id,
             output_storage: reaction_storage,
             output_path: output_dir.join("test_metrics.json"),
@@ -397,4 +543,123 @@ mod tests {
         // Even with no records, metrics should be written
         assert_eq!(logger.record_count, 0);
     }
+
+    fn record_with_latency(sequence: u64, latency_ns: u64) -> HandlerRecord {
+        HandlerRecord {
+            id: format!("test_{}", sequence),
+            sequence,
+            created_time_ns: 1000,
+            processed_time_ns: 1000 + latency_ns,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionOutput {
+                reaction_output: serde_json::json!({"test": "data"}),
+            },
+        }
+    }
+
+    fn read_metrics(temp_dir: &TempDir) -> PerformanceMetrics {
+        let metrics_path = temp_dir
+            .path()
+            .join("output")
+            .join("performance_metrics")
+            .join("test_metrics.json");
+        serde_json::from_str(&std::fs::read_to_string(metrics_path).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_warmup_records_are_excluded() {
+        let (mut logger, temp_dir) = create_test_logger().await;
+        let clock = Arc::new(ManualClock::new(1_000 * MS));
+        logger.clock = clock.clone();
+        logger.warmup_records = Some(10);
+
+        // Slow records during warmup, then fast ones once it has ended.
+        for i in 0..10 {
+            logger
+                .log_handler_record(&record_with_latency(i, 500 * MS))
+                .await
+                .unwrap();
+            clock.advance(MS);
+        }
+        for i in 10..30 {
+            logger
+                .log_handler_record(&record_with_latency(i, 2 * MS))
+                .await
+                .unwrap();
+            clock.advance(MS);
+        }
+
+        logger.end_test_run().await.unwrap();
+        let metrics = read_metrics(&temp_dir);
+
+        assert_eq!(metrics.record_count, 20);
+        assert_eq!(metrics.start_time_ns, 1_010 * MS);
+        assert_eq!(metrics.duration_ns, 20 * MS);
+        let latency = metrics.latency.unwrap();
+        assert_eq!(latency.count, 20);
+        assert!(latency.p99_ns <= 2 * MS, "{}", latency.p99_ns);
+        assert!(latency.max_ns <= 2 * MS, "{}", latency.max_ns);
+
+        let warmup = metrics.warmup.unwrap();
+        assert!(warmup.completed);
+        assert_eq!(warmup.record_count, 10);
+        assert_eq!(warmup.start_time_ns, 1_000 * MS);
+        assert_eq!(warmup.end_time_ns, 1_010 * MS);
+        assert!(
+            warmup.latency.p50_ns >= 490 * MS,
+            "{}",
+            warmup.latency.p50_ns
+        );
+    }
+
+    #[tokio::test]
+    async fn test_warmup_seconds() {
+        let (mut logger, temp_dir) = create_test_logger().await;
+        let clock = Arc::new(ManualClock::new(0));
+        logger.clock = clock.clone();
+        logger.warmup_ns = Some(1_000 * MS);
+
+        // A record every 100 ms for 2 seconds; the first 10 fall in the warmup second.
+        for i in 0..20 {
+            let latency_ns = if i < 10 { 300 * MS } else { 5 * MS };
+            logger
+                .log_handler_record(&record_with_latency(i, latency_ns))
+                .await
+                .unwrap();
+            clock.advance(100 * MS);
+        }
+
+        logger.end_test_run().await.unwrap();
+        let metrics = read_metrics(&temp_dir);
+
+        assert_eq!(metrics.record_count, 10);
+        assert!(metrics.latency.unwrap().p99_ns <= 5 * MS);
+        let warmup = metrics.warmup.unwrap();
+        assert_eq!(warmup.record_count, 10);
+        assert_eq!(warmup.end_time_ns, 1_000 * MS);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_not_completed() {
+        let (mut logger, temp_dir) = create_test_logger().await;
+        logger.warmup_records = Some(100);
+
+        for i in 0..5 {
+            logger
+                .log_handler_record(&record_with_latency(i, MS))
+                .await
+                .unwrap();
+        }
+
+        logger.end_test_run().await.unwrap();
+        let metrics = read_metrics(&temp_dir);
+
+        assert_eq!(metrics.record_count, 0);
+        assert_eq!(metrics.records_per_second, 0.0);
+        let warmup = metrics.warmup.unwrap();
+        assert!(!warmup.completed);
+        assert_eq!(warmup.record_count, 5);
+        assert_eq!(warmup.end_time_ns, metrics.end_time_ns);
+    }
 }
@@ -0,0 +1,457 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reaction latency output logger for measuring end-to-end latency
+//!
+//! This logger measures the time between a source change entering the reactivator
+//! (the `reactivatorStart_ns` tracking timestamp) and the reaction invocation that
+//! it produced. Latencies are recorded per query in fixed-size histograms, and a
+//! percentile summary is written when the test run ends.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use test_data_store::test_run_storage::{TestRunReactionId, TestRunReactionStorage};
+
+use crate::common::{HandlerPayload, HandlerRecord};
+use crate::queries::result_stream_record::QueryResultRecord;
+
+use super::{OutputLogger, OutputLoggerResult};
+
+/// Number of linear sub-buckets per power of two; bounds the relative error to 1/64.
+const SUB_BUCKET_COUNT: u64 = 64;
+const SUB_BUCKET_BITS: u32 = 6;
+/// Total number of buckets needed to cover the full u64 range.
+const BUCKET_COUNT: usize = ((64 - SUB_BUCKET_BITS as usize) + 1) * SUB_BUCKET_COUNT as usize;
+
+/// JSON pointers checked, in order, for the source reactivator start timestamp.
+const SOURCE_TIMESTAMP_POINTERS: [&str; 2] = [
+    "/metadata/tracking/source/reactivatorStart_ns",
+    "/reactivatorStart_ns",
+];
+
+/// Configuration for the reaction latency output logger
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReactionLatencyOutputLoggerConfig {
+    /// Optional custom filename for the latency summary output
+    pub filename: Option<String>,
+}
+
+/// Fixed-memory latency histogram using log-linear buckets (HDR style).
+///
+/// Values below 64 are recorded exactly; larger values fall into one of 64 linear
+/// sub-buckets within their power of two, so reported percentiles are within ~1.6%.
+#[derive(Clone, Debug)]
+pub struct LatencyHistogram {
+    buckets: Vec<u64>,
+    count: u64,
+    max: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; BUCKET_COUNT],
+            count: 0,
+            max: 0,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub fn record(&mut self, value: u64) {
+        self.buckets[Self::bucket_index(value)] += 1;
+        self.count += 1;
+        self.max = self.max.max(value);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the value at the given percentile (0-100), or 0 if nothing was recorded.
+    pub fn percentile(&self, percentile: f64) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+
+        let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+        for (index, bucket_count) in self.buckets.iter().enumerate() {
+            cumulative += bucket_count;
+            if cumulative >= rank {
+                return Self::bucket_value(index).min(self.max);
+            }
+        }
+        self.max
+    }
+
+    fn bucket_index(value: u64) -> usize {
+        if value < SUB_BUCKET_COUNT {
+            return value as usize;
+        }
+        let exponent = 63 - value.leading_zeros();
+        let shift = exponent - SUB_BUCKET_BITS;
+        let sub_bucket = (value >> shift) - SUB_BUCKET_COUNT;
+        ((shift as u64 + 1) * SUB_BUCKET_COUNT + sub_bucket) as usize
+    }
+
+    fn bucket_value(index: usize) -> u64 {
+        let index = index as u64;
+        let group = index / SUB_BUCKET_COUNT;
+        let sub_bucket = index % SUB_BUCKET_COUNT;
+        if group == 0 {
+            sub_bucket
+        } else {
+            (SUB_BUCKET_COUNT + sub_bucket) << (group - 1)
+        }
+    }
+}
+
+/// Latency percentiles for a single query
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryLatencySummary {
+    pub count: u64,
+    pub p50_ns: u64,
+    pub p90_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+impl From<&LatencyHistogram> for QueryLatencySummary {
+    fn from(histogram: &LatencyHistogram) -> Self {
+        Self {
+            count: histogram.count(),
+            p50_ns: histogram.percentile(50.0),
+            p90_ns: histogram.percentile(90.0),
+            p99_ns: histogram.percentile(99.0),
+            max_ns: histogram.max(),
+        }
+    }
+}
+
+/// Latency summary written when the test run ends
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReactionLatencySummary {
+    /// Test run reaction identifier
+    pub test_run_reaction_id: String,
+    /// Latency percentiles keyed by query id
+    pub queries: BTreeMap<String, QueryLatencySummary>,
+    /// Number of records that had no parseable source timestamp
+    pub records_without_source_timestamp: u64,
+}
+
+/// Reaction latency output logger implementation
+pub struct ReactionLatencyOutputLogger {
+    /// Latency histograms keyed by query id
+    histograms: BTreeMap<String, LatencyHistogram>,
+    /// Number of records that had no parseable source timestamp
+    records_without_source_timestamp: u64,
+    /// Test run reaction identifier
+    test_run_reaction_id: TestRunReactionId,
+    /// Path where the summary file will be written
+    output_path: PathBuf,
+}
+
+impl ReactionLatencyOutputLogger {
+    /// Create a new reaction latency output logger
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        test_run_reaction_id: TestRunReactionId,
+        config: &ReactionLatencyOutputLoggerConfig,
+        output_storage: &TestRunReactionStorage,
+    ) -> anyhow::Result<Box<dyn OutputLogger + Send + Sync>> {
+        log::debug!(
+            "Creating ReactionLatencyOutputLogger for {} from {:?}",
+            test_run_reaction_id,
+            config
+        );
+
+        let filename = config.filename.clone().unwrap_or_else(|| {
+            format!(
+                "reaction_latency_{}.json",
+                chrono::Utc::now().format("%Y%m%d_%H%M%S")
+            )
+        });
+
+        let output_dir = output_storage.reaction_output_path.join("reaction_latency");
+        if !output_dir.exists() {
+            tokio::fs::create_dir_all(&output_dir).await?;
+        }
+
+        Ok(Box::new(Self {
+            histograms: BTreeMap::new(),
+            records_without_source_timestamp: 0,
+            test_run_reaction_id,
+            output_path: output_dir.join(filename),
+        }))
+    }
+
+    /// Extract the query id and source reactivator start timestamp from a record
+    fn extract_query_and_source_ns(record: &HandlerRecord) -> (String, Option<u64>) {
+        match &record.payload {
+            HandlerPayload::ResultStream { query_result } => {
+                let base = match query_result {
+                    QueryResultRecord::Change(change) => &change.base,
+                    QueryResultRecord::Control(control) => &control.base,
+                };
+                let source_ns = base
+                    .metadata
+                    .as_ref()
+                    .map(|m| m.tracking.source.reactivator_start_ns);
+                (base.query_id.clone(), source_ns)
+            }
+            HandlerPayload::ReactionInvocation {
+                query_id,
+                request_body,
+                ..
+            } => {
+                let query_id = if query_id.is_empty() || query_id == "unknown" {
+                    Self::query_id_from_body(request_body)
+                } else {
+                    query_id.clone()
+                };
+                (query_id, Self::source_ns_from_body(request_body))
+            }
+            HandlerPayload::ReactionOutput { reaction_output } => (
+                Self::query_id_from_body(reaction_output),
+                Self::source_ns_from_body(reaction_output),
+            ),
+        }
+    }
+
+    fn query_id_from_body(body: &serde_json::Value) -> String {
+        body.pointer("/queryId")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    fn source_ns_from_body(body: &serde_json::Value) -> Option<u64> {
+        SOURCE_TIMESTAMP_POINTERS
+            .iter()
+            .find_map(|pointer| body.pointer(pointer))
+            .and_then(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+    }
+
+    fn summary(&self) -> ReactionLatencySummary {
+        ReactionLatencySummary {
+            test_run_reaction_id: self.test_run_reaction_id.to_string(),
+            queries: self
+                .histograms
+                .iter()
+                .map(|(query_id, histogram)| (query_id.clone(), histogram.into()))
+                .collect(),
+            records_without_source_timestamp: self.records_without_source_timestamp,
+        }
+    }
+}
+
+#[async_trait]
+impl OutputLogger for ReactionLatencyOutputLogger {
+    async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+        let (query_id, source_ns) = Self::extract_query_and_source_ns(record);
+
+        // The invocation time is the record creation time; a zero or future source
+        // timestamp can't produce a meaningful latency.
+        match source_ns {
+            Some(source_ns) if source_ns > 0 && source_ns <= record.created_time_ns => {
+                self.histograms
+                    .entry(query_id)
+                    .or_default()
+                    .record(record.created_time_ns - source_ns);
+            }
+            _ => self.records_without_source_timestamp += 1,
+        }
+
+        Ok(())
+    }
+
+    async fn end_test_run(&mut self) -> anyhow::Result<OutputLoggerResult> {
+        let summary = self.summary();
+        log::info!(
+            "ReactionLatencyOutputLogger for {} measured {} queries ({} records without source timestamp)",
+            summary.test_run_reaction_id,
+            summary.queries.len(),
+            summary.records_without_source_timestamp
+        );
+
+        let summary_json = serde_json::to_value(&summary)?;
+        tokio::fs::write(
+            &self.output_path,
+            serde_json::to_string_pretty(&summary_json)?.as_bytes(),
+        )
+        .await?;
+
+        Ok(OutputLoggerResult {
+            has_output: true,
+            logger_name: "ReactionLatency".to_string(),
+            output_folder_path: self.output_path.parent().map(|p| p.to_path_buf()),
+            summary: Some(summary_json),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    use test_data_store::test_run_storage::TestRunId;
+
+    const MS: u64 = 1_000_000;
+
+    fn create_test_logger(temp_dir: &TempDir) -> ReactionLatencyOutputLogger {
+        let test_run_id = TestRunId::new("test_repo", "test_id", "test_run_001");
+        ReactionLatencyOutputLogger {
+            histograms: BTreeMap::new(),
+            records_without_source_timestamp: 0,
+            test_run_reaction_id: TestRunReactionId::new(&test_run_id, "reaction_001"),
+            output_path: temp_dir.path().join("latency.json"),
+        }
+    }
+
+    fn invocation_record(query_id: &str, source_ns: Option<u64>, invoked_ns: u64) -> HandlerRecord {
+        let mut request_body = serde_json::json!({ "queryId": query_id });
+        if let Some(source_ns) = source_ns {
+            request_body["metadata"] = serde_json::json!({
+                "tracking": { "source": { "reactivatorStart_ns": source_ns } }
+            });
+        }
+        HandlerRecord {
+            id: "reaction_001".to_string(),
+            sequence: 0,
+            created_time_ns: invoked_ns,
+            processed_time_ns: invoked_ns,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionInvocation {
+                reaction_type: "http".to_string(),
+                query_id: "unknown".to_string(),
+                request_method: "POST".to_string(),
+                request_path: "/".to_string(),
+                request_body,
+                headers: Default::default(),
+            },
+        }
+    }
+
+    fn assert_close(actual: u64, expected: u64) {
+        let error = (actual as f64 - expected as f64).abs() / expected as f64;
+        assert!(
+            error <= 0.02,
+            "expected ~{} but got {} ({:.2}% error)",
+            expected,
+            actual,
+            error * 100.0
+        );
+    }
+
+    #[test]
+    fn test_histogram_small_values_are_exact() {
+        let mut histogram = LatencyHistogram::default();
+        for value in 1..=10 {
+            histogram.record(value);
+        }
+
+        assert_eq!(histogram.count(), 10);
+        assert_eq!(histogram.percentile(50.0), 5);
+        assert_eq!(histogram.percentile(90.0), 9);
+        assert_eq!(histogram.percentile(100.0), 10);
+        assert_eq!(histogram.max(), 10);
+    }
+
+    #[test]
+    fn test_histogram_bucket_bounds() {
+        for value in [64, 100, 1_000, 123_456_789, u64::MAX / 3, u64::MAX] {
+            let index = LatencyHistogram::bucket_index(value);
+            assert!(index < BUCKET_COUNT);
+            let lower = LatencyHistogram::bucket_value(index);
+            assert!(lower <= value);
+            assert!((value - lower) as f64 / value as f64 <= 1.0 / SUB_BUCKET_COUNT as f64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_percentiles_per_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = create_test_logger(&temp_dir);
+
+        let base_ns = 1_700_000_000_000_000_000;
+        for latency_ms in 1..=100 {
+            let record = invocation_record("q1", Some(base_ns), base_ns + latency_ms * MS);
+            logger.log_handler_record(&record).await.unwrap();
+        }
+        for _ in 0..10 {
+            let record = invocation_record("q2", Some(base_ns), base_ns + 5 * MS);
+            logger.log_handler_record(&record).await.unwrap();
+        }
+
+        let summary = logger.summary();
+        let q1 = &summary.queries["q1"];
+        assert_eq!(q1.count, 100);
+        assert_close(q1.p50_ns, 50 * MS);
+        assert_close(q1.p90_ns, 90 * MS);
+        assert_close(q1.p99_ns, 99 * MS);
+        assert_eq!(q1.max_ns, 100 * MS);
+
+        let q2 = &summary.queries["q2"];
+        assert_eq!(q2.count, 10);
+        assert_close(q2.p50_ns, 5 * MS);
+        assert_eq!(q2.max_ns, 5 * MS);
+    }
+
+    #[tokio::test]
+    async fn test_records_without_source_timestamp() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut logger = create_test_logger(&temp_dir);
+
+        logger
+            .log_handler_record(&invocation_record("q1", None, 10 * MS))
+            .await
+            .unwrap();
+        logger
+            .log_handler_record(&invocation_record("q1", Some(0), 10 * MS))
+            .await
+            .unwrap();
+        logger
+            .log_handler_record(&invocation_record("q1", Some(20 * MS), 10 * MS))
+            .await
+            .unwrap();
+        logger
+            .log_handler_record(&invocation_record("q1", Some(MS), 10 * MS))
+            .await
+            .unwrap();
+
+        let result = logger.end_test_run().await.unwrap();
+        assert_eq!(result.logger_name, "ReactionLatency");
+
+        let written: ReactionLatencySummary = serde_json::from_str(
+            &std::fs::read_to_string(temp_dir.path().join("latency.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(written.records_without_source_timestamp, 3);
+        assert_eq!(written.queries["q1"].count, 1);
+        assert_eq!(written.queries["q1"].max_ns, 9 * MS);
+
+        let summary = result.summary.unwrap();
+        assert_eq!(summary["records_without_source_timestamp"], 3);
+        assert_eq!(summary["queries"]["q1"]["max_ns"], 9 * MS);
+    }
+}
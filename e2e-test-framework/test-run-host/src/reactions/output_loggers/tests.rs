@@ -66,6 +66,8 @@ mod tests {
         };
 
         let config = JsonlFileOutputLoggerConfig {
+            max_file_bytes: None,
+            max_files: None,
             max_lines_per_file: Some(2),
         };
 
@@ -116,6 +118,182 @@ mod tests {
         assert_eq!(lines.len(), 2); // max_lines_per_file = 2
     }
 
+    async fn jsonl_files(output_dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+        let mut entries = fs::read_dir(output_dir).await.unwrap();
+        let mut files = Vec::new();
+        while let Some(entry) = entries.next_entry().await.unwrap() {
+            files.push(entry.path());
+        }
+        files.sort();
+        files
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_file_logger_rotation_by_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_run_id = TestRunId::new("repo", "test", "run");
+        let reaction_id = TestRunReactionId::new(&test_run_id, "reaction1");
+
+        let storage = TestRunReactionStorage {
+            id: reaction_id.clone(),
+            path: temp_dir.path().to_path_buf(),
+            reaction_output_path: temp_dir.path().join("outputs"),
+        };
+
+        let record = |i: u64| HandlerRecord {
+            id: format!("test-{}", i),
+            sequence: i,
+            created_time_ns: i * 1000000,
+            processed_time_ns: (i + 1) * 1000000,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionOutput {
+                reaction_output: serde_json::json!({"iteration": i, "data": "x".repeat(100)}),
+            },
+        };
+        let record_len = serde_json::to_string(&record(0)).unwrap().len() as u64 + 1;
+
+        // Each file holds two records, so ten records are written to five files.
+        let config = JsonlFileOutputLoggerConfig {
+            max_file_bytes: Some(record_len * 2 + record_len / 2),
+            max_files: None,
+            max_lines_per_file: None,
+        };
+        let mut logger = JsonlFileOutputLogger::new(reaction_id.clone(), &config, &storage)
+            .await
+            .unwrap();
+        for i in 0..10 {
+            logger.log_handler_record(&record(i)).await.unwrap();
+        }
+        let result = logger.end_test_run().await.unwrap();
+
+        let output_dir = temp_dir.path().join("outputs").join("jsonl_file");
+        assert_eq!(result.output_folder_path, Some(output_dir.clone()));
+
+        let files = jsonl_files(&output_dir).await;
+        assert_eq!(files.len(), 5);
+        let mut sequences = Vec::new();
+        for file in &files {
+            let content = fs::read_to_string(file).await.unwrap();
+            assert!(content.len() as u64 <= config.max_file_bytes.unwrap());
+            for line in content.lines() {
+                let record: HandlerRecord = serde_json::from_str(line).unwrap();
+                sequences.push(record.sequence);
+            }
+        }
+        assert_eq!(sequences, (0..10).collect::<Vec<_>>());
+
+        // With max_files, only the newest files are kept.
+        let config = JsonlFileOutputLoggerConfig {
+            max_files: Some(2),
+            ..config
+        };
+        fs::remove_dir_all(&output_dir).await.unwrap();
+        let mut logger = JsonlFileOutputLogger::new(reaction_id, &config, &storage)
+            .await
+            .unwrap();
+        for i in 0..10 {
+            logger.log_handler_record(&record(i)).await.unwrap();
+        }
+        logger.end_test_run().await.unwrap();
+
+        let files = jsonl_files(&output_dir).await;
+        let file_names: Vec<_> = files
+            .iter()
+            .map(|file| file.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            file_names,
+            vec!["outputs_00003.jsonl", "outputs_00004.jsonl"]
+        );
+        let content = fs::read_to_string(&files[1]).await.unwrap();
+        let last: HandlerRecord = serde_json::from_str(content.lines().last().unwrap()).unwrap();
+        assert_eq!(last.sequence, 9);
+    }
+
+    #[tokio::test]
+    async fn test_parquet_logger_rotation() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let temp_dir = TempDir::new().unwrap();
+        let test_run_id = TestRunId::new("repo", "test", "run");
+        let reaction_id = TestRunReactionId::new(&test_run_id, "reaction1");
+
+        let storage = TestRunReactionStorage {
+            id: reaction_id.clone(),
+            path: temp_dir.path().to_path_buf(),
+            reaction_output_path: temp_dir.path().join("outputs"),
+        };
+
+        let config = OutputLoggerConfig::Parquet(ParquetOutputLoggerConfig {
+            compression: ParquetCompression::Zstd,
+            folder_path: None,
+            max_rows_per_file: Some(2),
+        });
+
+        let mut logger = create_output_logger(reaction_id, &config, &storage)
+            .await
+            .unwrap();
+
+        // Log 5 records with different payload types to test rotation and a stable schema.
+        for i in 0..5 {
+            let payload = if i % 2 == 0 {
+                HandlerPayload::ReactionOutput {
+                    reaction_output: serde_json::json!({"iteration": i}),
+                }
+            } else {
+                HandlerPayload::ReactionInvocation {
+                    reaction_type: "http".to_string(),
+                    query_id: "query1".to_string(),
+                    request_method: "POST".to_string(),
+                    request_path: "/changes".to_string(),
+                    request_body: serde_json::json!({"iteration": i}),
+                    headers: Default::default(),
+                }
+            };
+            let record = HandlerRecord {
+                id: format!("test-{}", i),
+                sequence: i as u64,
+                created_time_ns: i as u64 * 1000000,
+                processed_time_ns: (i + 1) as u64 * 1000000,
+                traceparent: None,
+                tracestate: None,
+                payload,
+            };
+            assert!(logger.log_handler_record(&record).await.is_ok());
+        }
+
+        let result = logger.end_test_run().await.unwrap();
+        assert!(result.has_output);
+        assert_eq!(result.logger_name, "Parquet");
+
+        let output_dir = temp_dir.path().join("outputs").join("parquet");
+        assert_eq!(result.output_folder_path, Some(output_dir.clone()));
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&output_dir).unwrap() {
+            files.push(entry.unwrap().path());
+        }
+        files.sort();
+        assert_eq!(files.len(), 3);
+        assert!(files[2].ends_with("outputs_00002.parquet"));
+
+        let mut schemas = Vec::new();
+        let mut row_counts = Vec::new();
+        for file in &files {
+            let reader =
+                ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(file).unwrap())
+                    .unwrap()
+                    .build()
+                    .unwrap();
+            schemas.push(reader.schema());
+            row_counts.push(reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>());
+        }
+        assert_eq!(row_counts, vec![2, 2, 1]);
+        assert!(schemas.iter().all(|schema| schema == &schemas[0]));
+        assert!(schemas[0].field_with_name("request_method").is_ok());
+    }
+
     #[tokio::test]
     async fn test_output_logger_factory() {
         let temp_dir = TempDir::new().unwrap();
@@ -138,6 +316,8 @@ mod tests {
 
         // Test creating JSONL file logger via factory
         let jsonl_config = OutputLoggerConfig::JsonlFile(JsonlFileOutputLoggerConfig {
+            max_file_bytes: None,
+            max_files: None,
             max_lines_per_file: Some(100),
         });
         let jsonl_logger = create_output_logger(reaction_id.clone(), &jsonl_config, &storage).await;
@@ -164,6 +344,7 @@ mod tests {
 
         let config = OutputLoggerConfig::PerformanceMetrics(PerformanceMetricsOutputLoggerConfig {
             filename: Some("test_performance.json".to_string()),
+            ..Default::default()
         });
 
         let mut logger = create_output_logger(reaction_id, &config, &reaction_storage)
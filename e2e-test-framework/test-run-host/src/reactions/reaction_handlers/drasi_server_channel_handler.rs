@@ -29,6 +29,8 @@ use crate::reactions::reaction_output_handler::{
     ReactionHandlerType, ReactionInvocation, ReactionOutputHandler,
 };
 
+pub const DEFAULT_CHANNEL_BUFFER_SIZE: usize = 1024;
+
 #[derive(Clone, Debug)]
 pub struct DrasiServerChannelHandlerSettings {
     pub drasi_server_id: TestRunDrasiServerId,
@@ -49,7 +51,9 @@ impl DrasiServerChannelHandlerSettings {
         Ok(Self {
             drasi_server_id,
             reaction_id: definition.reaction_id.clone(),
-            buffer_size: definition.buffer_size.unwrap_or(1024),
+            buffer_size: definition
+                .buffer_size
+                .unwrap_or(DEFAULT_CHANNEL_BUFFER_SIZE),
             test_run_query_id: id,
         })
     }
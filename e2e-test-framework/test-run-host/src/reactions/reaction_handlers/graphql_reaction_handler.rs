@@ -0,0 +1,562 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router, Server,
+};
+use serde::Deserialize;
+use test_data_store::{
+    test_repo_storage::models::GraphQlReactionHandlerDefinition, test_run_storage::TestRunQueryId,
+};
+use tokio::sync::{
+    mpsc::{Receiver, Sender},
+    Notify, RwLock,
+};
+
+use crate::reactions::reaction_output_handler::{
+    ReactionControlSignal, ReactionHandlerError, ReactionHandlerMessage, ReactionHandlerPayload,
+    ReactionHandlerStatus, ReactionHandlerType, ReactionInvocation, ReactionOutputHandler,
+};
+
+const REACTION_TYPES: [&str; 3] = ["added", "updated", "deleted"];
+
+#[derive(Clone, Debug)]
+pub struct GraphQlReactionHandlerSettings {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+    pub mutation_types: HashMap<String, String>,
+    pub test_run_query_id: TestRunQueryId,
+}
+
+impl GraphQlReactionHandlerSettings {
+    pub fn new(
+        id: TestRunQueryId,
+        definition: GraphQlReactionHandlerDefinition,
+    ) -> anyhow::Result<Self> {
+        for (mutation, reaction_type) in &definition.mutation_types {
+            if !REACTION_TYPES.contains(&reaction_type.as_str()) {
+                anyhow::bail!(
+                    "GraphQlReactionHandler mutation {} has reaction type {}, expected one of {:?}",
+                    mutation,
+                    reaction_type,
+                    REACTION_TYPES
+                );
+            }
+        }
+
+        Ok(GraphQlReactionHandlerSettings {
+            host: definition
+                .host
+                .clone()
+                .unwrap_or_else(|| "0.0.0.0".to_string()),
+            port: definition.port.unwrap_or(8081),
+            path: definition
+                .path
+                .clone()
+                .unwrap_or_else(|| "/graphql".to_string()),
+            mutation_types: definition.mutation_types,
+            test_run_query_id: id,
+        })
+    }
+}
+
+// The body of a GraphQL request sent over HTTP POST.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphQlRequest {
+    query: String,
+    #[serde(default)]
+    operation_name: Option<String>,
+    #[serde(default)]
+    variables: Option<serde_json::Value>,
+}
+
+// The parts of a GraphQL mutation the handler records.
+#[derive(Debug, PartialEq)]
+struct GraphQlMutation {
+    // The field invoked by the mutation, e.g. addRoom.
+    field: String,
+    operation_name: Option<String>,
+}
+
+// Parses the operation name and first mutation field from a GraphQL document, e.g.
+// `mutation AddRoom($id: ID!) { addRoom(id: $id) { id } }`. Only mutations are accepted.
+fn parse_mutation(query: &str) -> anyhow::Result<GraphQlMutation> {
+    let query = strip_comments(query);
+    let query = query.trim_start();
+
+    let rest = match query.strip_prefix("mutation") {
+        Some(rest) if !rest.starts_with(is_name_char) => rest,
+        _ => anyhow::bail!("Only GraphQL mutations are supported"),
+    };
+
+    let (header, selection) = match rest.split_once('{') {
+        Some(parts) => parts,
+        None => anyhow::bail!("Mutation has no selection set"),
+    };
+
+    let operation_name = Some(take_name(header.trim_start()))
+        .filter(|name| !name.is_empty())
+        .map(str::to_string);
+
+    // The first name in the selection set is either the field or an alias for it.
+    let selection = selection.trim_start();
+    let first = take_name(selection);
+    if first.is_empty() {
+        anyhow::bail!("Mutation has no fields");
+    }
+    let field = match selection[first.len()..].trim_start().strip_prefix(':') {
+        Some(aliased) => take_name(aliased.trim_start()),
+        None => first,
+    };
+    if field.is_empty() {
+        anyhow::bail!("Mutation alias {} has no field", first);
+    }
+
+    Ok(GraphQlMutation {
+        field: field.to_string(),
+        operation_name,
+    })
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn take_name(s: &str) -> &str {
+    let end = s.find(|c| !is_name_char(c)).unwrap_or(s.len());
+    &s[..end]
+}
+
+fn strip_comments(query: &str) -> String {
+    query
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(code, _)| code))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Clone)]
+struct GraphQlServerState {
+    sequence: Arc<AtomicU64>,
+    settings: GraphQlReactionHandlerSettings,
+    tx: Sender<ReactionHandlerMessage>,
+}
+
+pub struct GraphQlReactionHandler {
+    notifier: Arc<Notify>,
+    settings: GraphQlReactionHandlerSettings,
+    status: Arc<RwLock<ReactionHandlerStatus>>,
+    shutdown_notify: Arc<Notify>,
+}
+
+impl GraphQlReactionHandler {
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        id: TestRunQueryId,
+        definition: GraphQlReactionHandlerDefinition,
+    ) -> anyhow::Result<Box<dyn ReactionOutputHandler + Send + Sync>> {
+        let settings = GraphQlReactionHandlerSettings::new(id, definition)?;
+        log::trace!(
+            "Creating GraphQlReactionHandler with settings {:?}",
+            settings
+        );
+
+        Ok(Box::new(Self {
+            notifier: Arc::new(Notify::new()),
+            settings,
+            status: Arc::new(RwLock::new(ReactionHandlerStatus::Uninitialized)),
+            shutdown_notify: Arc::new(Notify::new()),
+        }))
+    }
+}
+
+#[async_trait]
+impl ReactionOutputHandler for GraphQlReactionHandler {
+    async fn init(&self) -> anyhow::Result<Receiver<ReactionHandlerMessage>> {
+        log::debug!("Initializing GraphQlReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    let (handler_tx_channel, handler_rx_channel) = tokio::sync::mpsc::channel(100);
+
+                    *status = ReactionHandlerStatus::Paused;
+
+                    tokio::spawn(graphql_server_thread(
+                        self.settings.clone(),
+                        self.status.clone(),
+                        self.notifier.clone(),
+                        self.shutdown_notify.clone(),
+                        handler_tx_channel,
+                    ));
+
+                    Ok(handler_rx_channel)
+                }
+                ReactionHandlerStatus::Running => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Running");
+                }
+                ReactionHandlerStatus::Paused => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Paused");
+                }
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        log::debug!("Starting GraphQlReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Can't Start Handler, Handler Uninitialized");
+                }
+                ReactionHandlerStatus::Running => Ok(()),
+                ReactionHandlerStatus::Paused => {
+                    *status = ReactionHandlerStatus::Running;
+                    self.notifier.notify_one();
+                    Ok(())
+                }
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Start Handler, Handler already Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn pause(&self) -> anyhow::Result<()> {
+        log::debug!("Pausing GraphQlReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Can't Pause Handler, Handler Uninitialized");
+                }
+                ReactionHandlerStatus::Running => {
+                    *status = ReactionHandlerStatus::Paused;
+                    Ok(())
+                }
+                ReactionHandlerStatus::Paused => Ok(()),
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Pause Handler, Handler already Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        log::debug!("Stopping GraphQlReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Handler not initialized, current status: Uninitialized");
+                }
+                ReactionHandlerStatus::Running | ReactionHandlerStatus::Paused => {
+                    *status = ReactionHandlerStatus::Stopped;
+                    self.shutdown_notify.notify_one();
+                    Ok(())
+                }
+                ReactionHandlerStatus::Stopped => Ok(()),
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn status(&self) -> ReactionHandlerStatus {
+        *self.status.read().await
+    }
+}
+
+async fn graphql_server_thread(
+    settings: GraphQlReactionHandlerSettings,
+    status: Arc<RwLock<ReactionHandlerStatus>>,
+    notify: Arc<Notify>,
+    shutdown_notify: Arc<Notify>,
+    result_handler_tx_channel: Sender<ReactionHandlerMessage>,
+) {
+    log::debug!("Starting GraphQlReactionHandler Server Thread");
+
+    // Wait for the handler to be started
+    loop {
+        let current_status = {
+            if let Ok(status) = status.try_read() {
+                *status
+            } else {
+                log::warn!("Could not acquire status lock while waiting to start");
+                continue;
+            }
+        };
+
+        match current_status {
+            ReactionHandlerStatus::Running => break,
+            ReactionHandlerStatus::Paused => {
+                log::debug!("GraphQL server waiting to be started");
+                notify.notified().await;
+            }
+            ReactionHandlerStatus::Stopped => {
+                log::debug!("Handler stopped before server could start");
+                return;
+            }
+            _ => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            }
+        }
+    }
+
+    let state = GraphQlServerState {
+        sequence: Arc::new(AtomicU64::new(0)),
+        settings: settings.clone(),
+        tx: result_handler_tx_channel.clone(),
+    };
+
+    let app = Router::new()
+        .route(&settings.path, post(handle_graphql))
+        .with_state(state);
+
+    let addr = match format!("{}:{}", settings.host, settings.port).parse::<SocketAddr>() {
+        Ok(addr) => addr,
+        Err(e) => {
+            log::error!("Failed to parse server address: {}", e);
+            *status.write().await = ReactionHandlerStatus::Error;
+            let _ = result_handler_tx_channel
+                .send(ReactionHandlerMessage::Error(ReactionHandlerError::new(
+                    format!("Invalid GraphQL server address: {}", e),
+                    false,
+                )))
+                .await;
+            return;
+        }
+    };
+
+    log::info!(
+        "GraphQL Reaction Handler listening on http://{}{}",
+        addr,
+        settings.path
+    );
+
+    let server = Server::bind(&addr)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            shutdown_notify.notified().await;
+            log::debug!("GraphQL server received shutdown signal");
+        });
+
+    if let Err(e) = server.await {
+        log::error!("GraphQL server error: {}", e);
+        *status.write().await = ReactionHandlerStatus::Error;
+        let _ = result_handler_tx_channel
+            .send(ReactionHandlerMessage::Error(ReactionHandlerError::new(
+                format!("GraphQL server error: {}", e),
+                false,
+            )))
+            .await;
+    }
+
+    log::debug!("GraphQL server thread shutting down, sending HandlerStopping message");
+    let _ = result_handler_tx_channel
+        .send(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+        .await;
+}
+
+// Responses follow the GraphQL over HTTP conventions, with failures reported in an
+// errors list.
+fn graphql_error(status: StatusCode, message: String) -> (StatusCode, Json<serde_json::Value>) {
+    (
+        status,
+        Json(serde_json::json!({ "errors": [{ "message": message }] })),
+    )
+}
+
+async fn handle_graphql(
+    State(state): State<GraphQlServerState>,
+    headers: HeaderMap,
+    body: String,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let invocation_time_ns = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64;
+
+    let request: GraphQlRequest = match serde_json::from_str(&body) {
+        Ok(request) => request,
+        Err(e) => {
+            return graphql_error(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid GraphQL request: {}", e),
+            )
+        }
+    };
+
+    let mutation = match parse_mutation(&request.query) {
+        Ok(mutation) => mutation,
+        Err(e) => return graphql_error(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+
+    // An operationName in the request selects the operation, so it takes precedence over
+    // the name in the document.
+    let operation_name = request.operation_name.or(mutation.operation_name);
+
+    let reaction_type = state
+        .settings
+        .mutation_types
+        .get(&mutation.field)
+        .cloned()
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let mut header_map = HashMap::new();
+    for (name, value) in headers.iter() {
+        if let Ok(value_str) = value.to_str() {
+            header_map.insert(name.as_str().to_string(), value_str.to_string());
+        }
+    }
+
+    let query_id = state.settings.test_run_query_id.test_query_id.clone();
+    let sequence = state.sequence.fetch_add(1, Ordering::Relaxed);
+
+    log::debug!(
+        "GraphQL Reaction Handler received mutation {} as {} (sequence: {})",
+        mutation.field,
+        reaction_type,
+        sequence
+    );
+
+    let metadata = serde_json::json!({
+        "query_id": query_id,
+        "reaction_type": reaction_type,
+        "mutation": mutation.field,
+        "operation_name": operation_name,
+        "request_path": state.settings.path,
+        "headers": header_map,
+        "traceparent": header_map.get("traceparent"),
+        "tracestate": header_map.get("tracestate"),
+    });
+
+    let invocation = ReactionInvocation {
+        handler_type: ReactionHandlerType::GraphQl,
+        payload: ReactionHandlerPayload {
+            value: request.variables.unwrap_or(serde_json::Value::Null),
+            timestamp: chrono::DateTime::from_timestamp_nanos(invocation_time_ns as i64),
+            invocation_id: Some(format!("{}-{}", query_id, sequence)),
+            metadata: Some(metadata),
+        },
+    };
+
+    match state
+        .tx
+        .send(ReactionHandlerMessage::Invocation(invocation))
+        .await
+    {
+        Ok(_) => {
+            let mut data = serde_json::Map::new();
+            data.insert(mutation.field, serde_json::Value::Null);
+            (StatusCode::OK, Json(serde_json::json!({ "data": data })))
+        }
+        Err(e) => {
+            log::error!("Failed to send reaction message: {}", e);
+            graphql_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Internal Server Error".to_string(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mutation() {
+        assert_eq!(
+            parse_mutation("mutation AddRoom($id: ID!) { addRoom(id: $id) { id } }").unwrap(),
+            GraphQlMutation {
+                field: "addRoom".to_string(),
+                operation_name: Some("AddRoom".to_string()),
+            }
+        );
+
+        // Anonymous mutations, aliases and comments.
+        assert_eq!(
+            parse_mutation("# Remove the room\nmutation {\n  removed: deleteRoom(id: 1)\n}")
+                .unwrap(),
+            GraphQlMutation {
+                field: "deleteRoom".to_string(),
+                operation_name: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_mutation_rejects_non_mutations() {
+        assert!(parse_mutation("query { rooms { id } }").is_err());
+        assert!(parse_mutation("{ rooms { id } }").is_err());
+        assert!(parse_mutation("mutations { addRoom }").is_err());
+        assert!(parse_mutation("mutation AddRoom").is_err());
+        assert!(parse_mutation("mutation { }").is_err());
+    }
+
+    #[test]
+    fn test_settings_reject_unknown_reaction_types() {
+        let id = TestRunQueryId::new(
+            &test_data_store::test_run_storage::TestRunId::new("repo", "test", "run"),
+            "query",
+        );
+        let definition = GraphQlReactionHandlerDefinition {
+            host: None,
+            port: None,
+            path: None,
+            mutation_types: HashMap::from([("addRoom".to_string(), "inserted".to_string())]),
+        };
+
+        assert!(GraphQlReactionHandlerSettings::new(id, definition).is_err());
+    }
+}
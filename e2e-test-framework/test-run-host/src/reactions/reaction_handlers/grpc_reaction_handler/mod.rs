@@ -16,13 +16,17 @@ use std::{net::SocketAddr, sync::Arc};
 
 use async_trait::async_trait;
 use test_data_store::{
-    test_repo_storage::models::GrpcReactionHandlerDefinition, test_run_storage::TestRunQueryId,
+    test_repo_storage::models::{GrpcReactionHandlerDefinition, GrpcReactionHandlerTlsDefinition},
+    test_run_storage::TestRunQueryId,
 };
 use tokio::sync::{
     mpsc::{channel, Receiver, Sender},
     Notify, RwLock,
 };
-use tonic::{transport::Server, Request, Response, Status};
+use tonic::{
+    transport::{Certificate, Identity, Server, ServerTlsConfig},
+    Request, Response, Status,
+};
 use tracing::{debug, error, info, trace};
 
 use crate::grpc_converters::{convert_from_drasi_query_result, drasi};
@@ -45,6 +49,7 @@ pub struct GrpcReactionHandlerSettings {
     pub test_run_query_id: TestRunQueryId,
     pub query_ids: Vec<String>,
     pub include_initial_state: bool,
+    pub tls: Option<GrpcReactionHandlerTlsDefinition>,
 }
 
 impl GrpcReactionHandlerSettings {
@@ -62,6 +67,7 @@ impl GrpcReactionHandlerSettings {
             test_run_query_id: id,
             query_ids: definition.query_ids,
             include_initial_state: definition.include_initial_state.unwrap_or(false),
+            tls: definition.tls,
         })
     }
 
@@ -70,6 +76,41 @@ impl GrpcReactionHandlerSettings {
             .parse()
             .expect("Invalid server address")
     }
+
+    // Loads the server identity and, for mTLS, the client CA from the configured PEM files.
+    // A client CA makes tonic reject clients that don't present a certificate signed by it.
+    pub async fn server_tls_config(&self) -> anyhow::Result<Option<ServerTlsConfig>> {
+        let Some(tls) = &self.tls else {
+            return Ok(None);
+        };
+
+        let cert = tokio::fs::read(&tls.cert_path).await.map_err(|e| {
+            anyhow::anyhow!("Failed to read TLS cert file {}: {}", tls.cert_path, e)
+        })?;
+        let key = tokio::fs::read(&tls.key_path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read TLS key file {}: {}", tls.key_path, e))?;
+
+        let mut config = ServerTlsConfig::new().identity(Identity::from_pem(cert, key));
+        if let Some(client_ca_path) = &tls.client_ca_path {
+            let client_ca = tokio::fs::read(client_ca_path).await.map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to read TLS client CA file {}: {}",
+                    client_ca_path,
+                    e
+                )
+            })?;
+            config = config.client_ca_root(Certificate::from_pem(client_ca));
+        }
+
+        Ok(Some(config))
+    }
+
+    pub fn requires_client_cert(&self) -> bool {
+        self.tls
+            .as_ref()
+            .is_some_and(|tls| tls.client_ca_path.is_some())
+    }
 }
 
 #[derive(Clone)]
@@ -368,8 +409,17 @@ impl ReactionOutputHandler for GrpcReactionHandler {
         info!("Starting Drasi ReactionService server on {}", addr);
         info!("Server configured for query_ids: {:?}", self.settings.query_ids);
 
+        let mut server_builder = Server::builder();
+        if let Some(tls_config) = self.settings.server_tls_config().await? {
+            info!(
+                "Server configured for TLS (client certificate required: {})",
+                self.settings.requires_client_cert()
+            );
+            server_builder = server_builder.tls_config(tls_config)?;
+        }
+
         let handle = tokio::spawn(async move {
-            Server::builder()
+            server_builder
                 .add_service(ReactionServiceServer::new(server_impl))
                 .serve_with_shutdown(addr, async {
                     shutdown_notify_clone.notified().await;
@@ -433,7 +483,113 @@ impl ReactionOutputHandler for GrpcReactionHandler {
         Some(serde_json::json!({
             "endpoint": format!("grpc://{}", self.settings.server_addr()),
             "query_ids": self.settings.query_ids,
+            "tls_enabled": self.settings.tls.is_some(),
+            "client_cert_required": self.settings.requires_client_cert(),
         }))
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use rcgen::{BasicConstraints, Certificate as RcgenCertificate, CertificateParams, IsCa};
+    use test_data_store::test_run_storage::TestRunId;
+    use tonic::transport::{Channel, ClientTlsConfig};
+
+    use super::*;
+    use drasi::v1::reaction_service_client::ReactionServiceClient;
+
+    struct TestPki {
+        ca_pem: String,
+        server: (String, String),
+        client: (String, String),
+    }
+
+    fn signed_cert(ca: &RcgenCertificate, name: &str) -> (String, String) {
+        let cert =
+            RcgenCertificate::from_params(CertificateParams::new(vec![name.to_string()])).unwrap();
+        (
+            cert.serialize_pem_with_signer(ca).unwrap(),
+            cert.serialize_private_key_pem(),
+        )
+    }
+
+    fn create_test_pki() -> TestPki {
+        let mut ca_params = CertificateParams::new(vec![]);
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = RcgenCertificate::from_params(ca_params).unwrap();
+
+        TestPki {
+            ca_pem: ca.serialize_pem().unwrap(),
+            server: signed_cert(&ca, "localhost"),
+            client: signed_cert(&ca, "client"),
+        }
+    }
+
+    async fn health_check(
+        port: u16,
+        ca_pem: &str,
+        client_identity: Option<&(String, String)>,
+    ) -> anyhow::Result<()> {
+        let mut tls = ClientTlsConfig::new()
+            .ca_certificate(Certificate::from_pem(ca_pem))
+            .domain_name("localhost");
+        if let Some((cert, key)) = client_identity {
+            tls = tls.identity(Identity::from_pem(cert, key));
+        }
+
+        let channel = Channel::from_shared(format!("https://127.0.0.1:{}", port))?
+            .tls_config(tls)?
+            .connect()
+            .await?;
+        ReactionServiceClient::new(channel).health_check(()).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_grpc_reaction_handler_mtls() -> anyhow::Result<()> {
+        let temp_dir = tempfile::TempDir::new()?;
+        let pki = create_test_pki();
+        let write = |name: &str, content: &str| -> String {
+            let path = temp_dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            path.to_string_lossy().to_string()
+        };
+
+        let port = portpicker::pick_unused_port().expect("No free port");
+        let definition = GrpcReactionHandlerDefinition {
+            host: Some("127.0.0.1".to_string()),
+            port: Some(port),
+            correlation_metadata_key: None,
+            query_ids: vec![],
+            include_initial_state: None,
+            tls: Some(GrpcReactionHandlerTlsDefinition {
+                cert_path: write("server.pem", &pki.server.0),
+                key_path: write("server.key", &pki.server.1),
+                client_ca_path: Some(write("ca.pem", &pki.ca_pem)),
+            }),
+        };
+
+        let test_run_id = TestRunId::new("repo", "test", "run");
+        let handler =
+            GrpcReactionHandler::new(TestRunQueryId::new(&test_run_id, "reaction"), definition)
+                .await?;
+        let _rx = handler.init().await?;
+        handler.start().await?;
+
+        let metrics = handler.metrics().await.unwrap();
+        assert_eq!(metrics["tls_enabled"], true);
+        assert_eq!(metrics["client_cert_required"], true);
+
+        // A client with a certificate signed by the client CA is accepted.
+        let with_cert = health_check(port, &pki.ca_pem, Some(&pki.client)).await;
+        // A client without a certificate is rejected.
+        let without_cert = health_check(port, &pki.ca_pem, None).await;
+
+        handler.stop().await?;
+
+        assert!(with_cert.is_ok(), "{:?}", with_cert);
+        assert!(without_cert.is_err());
+
+        Ok(())
+    }
+}
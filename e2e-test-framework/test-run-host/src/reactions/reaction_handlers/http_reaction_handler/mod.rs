@@ -12,13 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::SystemTime};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
 
 use async_trait::async_trait;
 use axum::{
     extract::State,
-    http::{HeaderMap, Method, StatusCode},
-    response::IntoResponse,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    response::{IntoResponse, Response},
     routing::any,
     Router, Server,
 };
@@ -26,7 +34,7 @@ use test_data_store::{
     test_repo_storage::models::HttpReactionHandlerDefinition, test_run_storage::TestRunQueryId,
 };
 use tokio::sync::{
-    mpsc::{Receiver, Sender},
+    mpsc::{error::TrySendError, Receiver, Sender},
     Notify, RwLock,
 };
 
@@ -41,6 +49,9 @@ pub struct HttpReactionHandlerSettings {
     pub port: u16,
     pub path: String,
     pub correlation_header: Option<String>,
+    pub forward_to: Option<String>,
+    pub backpressure: bool,
+    pub reaction_type_field: Option<String>,
     pub test_run_query_id: TestRunQueryId,
 }
 
@@ -49,6 +60,15 @@ impl HttpReactionHandlerSettings {
         id: TestRunQueryId,
         definition: HttpReactionHandlerDefinition,
     ) -> anyhow::Result<Self> {
+        if let Some(field) = &definition.reaction_type_field {
+            if !field.starts_with('/') {
+                anyhow::bail!(
+                    "reaction_type_field {:?} is not a JSON pointer; it must start with '/'",
+                    field
+                );
+            }
+        }
+
         Ok(HttpReactionHandlerSettings {
             host: definition
                 .host
@@ -60,18 +80,145 @@ impl HttpReactionHandlerSettings {
                 .clone()
                 .unwrap_or_else(|| "/reaction".to_string()),
             correlation_header: definition.correlation_header,
+            forward_to: definition.forward_to,
+            backpressure: definition.backpressure,
+            reaction_type_field: definition.reaction_type_field,
             test_run_query_id: id,
         })
     }
+
+    // Reads the reaction type from the reaction_type_field of a result, if it is configured and
+    // the result has it.
+    fn reaction_type_from_field(&self, result: &serde_json::Value) -> Option<String> {
+        let value = result
+            .pointer(self.reaction_type_field.as_ref()?)?
+            .as_str()?;
+        let reaction_type = match value.to_lowercase().as_str() {
+            "i" | "insert" | "add" | "added" => "added".to_string(),
+            "u" | "update" | "updated" => "updated".to_string(),
+            "d" | "delete" | "deleted" => "deleted".to_string(),
+            _ => value.to_string(),
+        };
+        Some(reaction_type)
+    }
+}
+
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How long a client should wait before retrying a request rejected because of backpressure.
+const BACKPRESSURE_RETRY_AFTER_SECS: u64 = 1;
+
+// Headers that apply to a single connection and must not be relayed to the forward_to URL.
+const HOP_BY_HOP_HEADERS: [&str; 5] = [
+    "connection",
+    "content-length",
+    "host",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+#[derive(Debug, Default)]
+struct HttpForwardStats {
+    forwarded_count: AtomicU64,
+    failed_count: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+impl HttpForwardStats {
+    fn record_failure(&self, error: String) {
+        self.failed_count.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut last_error) = self.last_error.lock() {
+            *last_error = Some(error);
+        }
+    }
+
+    fn to_json(&self, forward_to: &str) -> serde_json::Value {
+        serde_json::json!({
+            "forward_to": forward_to,
+            "forwarded_count": self.forwarded_count.load(Ordering::Relaxed),
+            "failed_count": self.failed_count.load(Ordering::Relaxed),
+            "last_error": self.last_error.lock().ok().and_then(|e| e.clone()),
+        })
+    }
+}
+
+// Relays requests received by the handler to the forward_to URL.
+#[derive(Clone)]
+struct HttpReactionForwarder {
+    client: reqwest::Client,
+    handler_path: String,
+    stats: Arc<HttpForwardStats>,
+    url: String,
+}
+
+impl HttpReactionForwarder {
+    async fn forward(
+        &self,
+        method: &Method,
+        headers: &HeaderMap,
+        uri: &axum::http::Uri,
+        body: String,
+    ) -> anyhow::Result<Response> {
+        let url = forward_url(&self.url, &self.handler_path, uri);
+
+        let mut request = self
+            .client
+            .request(
+                reqwest::Method::from_bytes(method.as_str().as_bytes())?,
+                &url,
+            )
+            .body(body);
+        for (name, value) in headers.iter() {
+            if !HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+                request = request.header(name.as_str(), value.as_bytes());
+            }
+        }
+
+        let response = request.send().await?;
+        let status = StatusCode::from_u16(response.status().as_u16())?;
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .cloned();
+        let body = response.bytes().await?;
+
+        let mut relayed = (status, body.to_vec()).into_response();
+        if let Some(content_type) = content_type {
+            relayed.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_bytes(content_type.as_bytes())?,
+            );
+        }
+        Ok(relayed)
+    }
+}
+
+// Builds the URL to relay a request to, preserving any sub-path below the handler path
+// (e.g. /reaction/added -> {forward_to}/added) and the query string.
+fn forward_url(forward_to: &str, handler_path: &str, uri: &axum::http::Uri) -> String {
+    let sub_path = uri
+        .path()
+        .strip_prefix(handler_path)
+        .filter(|p| p.is_empty() || p.starts_with('/'))
+        .unwrap_or("");
+
+    let mut url = format!("{}{}", forward_to.trim_end_matches('/'), sub_path);
+    if let Some(query) = uri.query() {
+        url.push('?');
+        url.push_str(query);
+    }
+    url
 }
 
 #[derive(Clone)]
 struct HttpServerState {
+    forwarder: Option<HttpReactionForwarder>,
     tx: Sender<ReactionHandlerMessage>,
     settings: HttpReactionHandlerSettings,
 }
 
 pub struct HttpReactionHandler {
+    forward_stats: Arc<HttpForwardStats>,
     notifier: Arc<Notify>,
     settings: HttpReactionHandlerSettings,
     status: Arc<RwLock<ReactionHandlerStatus>>,
@@ -92,6 +239,7 @@ impl HttpReactionHandler {
         let shutdown_notify = Arc::new(Notify::new());
 
         Ok(Box::new(Self {
+            forward_stats: Arc::new(HttpForwardStats::default()),
             notifier,
             settings,
             status,
@@ -114,6 +262,7 @@ impl ReactionOutputHandler for HttpReactionHandler {
 
                     tokio::spawn(http_server_thread(
                         self.settings.clone(),
+                        self.forward_stats.clone(),
                         self.status.clone(),
                         self.notifier.clone(),
                         self.shutdown_notify.clone(),
@@ -219,12 +368,16 @@ impl ReactionOutputHandler for HttpReactionHandler {
     }
 
     async fn metrics(&self) -> Option<serde_json::Value> {
-        None
+        self.settings
+            .forward_to
+            .as_ref()
+            .map(|forward_to| self.forward_stats.to_json(forward_to))
     }
 }
 
 async fn http_server_thread(
     settings: HttpReactionHandlerSettings,
+    forward_stats: Arc<HttpForwardStats>,
     status: Arc<RwLock<ReactionHandlerStatus>>,
     notify: Arc<Notify>,
     shutdown_notify: Arc<Notify>,
@@ -259,7 +412,24 @@ async fn http_server_thread(
         }
     }
 
+    let forwarder = match &settings.forward_to {
+        Some(url) => match reqwest::Client::builder().timeout(FORWARD_TIMEOUT).build() {
+            Ok(client) => Some(HttpReactionForwarder {
+                client,
+                handler_path: settings.path.clone(),
+                stats: forward_stats,
+                url: url.clone(),
+            }),
+            Err(e) => {
+                log::error!("Failed to create HTTP client for forward_to {}: {}", url, e);
+                None
+            }
+        },
+        None => None,
+    };
+
     let state = HttpServerState {
+        forwarder,
         tx: result_handler_tx_channel.clone(),
         settings: settings.clone(),
     };
@@ -317,14 +487,104 @@ async fn handle_reaction(
     headers: HeaderMap,
     uri: axum::http::Uri,
     body: String,
-) -> impl IntoResponse {
+) -> Response {
+    let recorded_response = record_reaction(&state, &method, &headers, &uri, &body).await;
+
+    // A request rejected because of backpressure isn't forwarded either, so the retry
+    // doesn't reach the downstream consumer twice.
+    if recorded_response.status() == StatusCode::SERVICE_UNAVAILABLE {
+        return recorded_response;
+    }
+
+    // In proxy mode, relay the request downstream after recording it and return the
+    // downstream response. If forwarding fails, the failure is recorded and the caller
+    // gets the handler's own response.
+    match &state.forwarder {
+        Some(forwarder) => match forwarder.forward(&method, &headers, &uri, body).await {
+            Ok(response) => {
+                forwarder
+                    .stats
+                    .forwarded_count
+                    .fetch_add(1, Ordering::Relaxed);
+                response
+            }
+            Err(e) => {
+                log::warn!("Failed to forward reaction to {}: {:?}", forwarder.url, e);
+                forwarder.stats.record_failure(format!("{:?}", e));
+                recorded_response
+            }
+        },
+        None => recorded_response,
+    }
+}
+
+fn backpressure_response() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(
+            header::RETRY_AFTER,
+            BACKPRESSURE_RETRY_AFTER_SECS.to_string(),
+        )],
+        "Service Unavailable",
+    )
+        .into_response()
+}
+
+// Queues the invocations without waiting for room in the channel. They are only queued if
+// there is room for all of them, so a client retrying a rejected request doesn't record any
+// invocation twice. A request with more invocations than the channel can ever hold waits for
+// room instead, as it could never be accepted otherwise.
+async fn queue_invocations_with_backpressure(
+    tx: &Sender<ReactionHandlerMessage>,
+    invocations: Vec<ReactionInvocation>,
+) -> Result<(), Response> {
+    let internal_error = |e: &dyn std::fmt::Display| {
+        log::error!("Failed to send reaction message: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
+    };
+
+    if invocations.is_empty() {
+        return Ok(());
+    }
+
+    if invocations.len() > tx.max_capacity() {
+        for invocation in invocations {
+            tx.send(ReactionHandlerMessage::Invocation(invocation))
+                .await
+                .map_err(|e| internal_error(&e))?;
+        }
+        return Ok(());
+    }
+
+    match tx.try_reserve_many(invocations.len()) {
+        Ok(permits) => {
+            for (permit, invocation) in permits.zip(invocations) {
+                permit.send(ReactionHandlerMessage::Invocation(invocation));
+            }
+            Ok(())
+        }
+        Err(TrySendError::Full(_)) => {
+            log::debug!("Reaction handler channel is full, rejecting request");
+            Err(backpressure_response())
+        }
+        Err(e @ TrySendError::Closed(_)) => Err(internal_error(&e)),
+    }
+}
+
+async fn record_reaction(
+    state: &HttpServerState,
+    method: &Method,
+    headers: &HeaderMap,
+    uri: &axum::http::Uri,
+    body: &str,
+) -> Response {
     let invocation_time_ns = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
         .as_nanos() as u64;
 
     // Parse request body as JSON
-    let request_body: serde_json::Value = match serde_json::from_str(&body) {
+    let request_body: serde_json::Value = match serde_json::from_str(body) {
         Ok(json) => json,
         Err(_) => serde_json::json!({ "raw": body }),
     };
@@ -375,6 +635,7 @@ async fn handle_reaction(
         );
 
         // Process each batch item
+        let mut invocations = Vec::new();
         for (idx, batch_item) in batch_items.iter().enumerate() {
             let query_id = batch_item.get("query_id")
                 .and_then(|v| v.as_str())
@@ -395,16 +656,22 @@ async fn handle_reaction(
 
             // Process each result in the batch
             for (result_idx, result) in results.iter().enumerate() {
-                // Determine reaction type from the result
-                let reaction_type = if result.get("before").is_some() && result.get("after").is_some() {
-                    "updated"
-                } else if result.get("after").is_some() {
-                    "added"
-                } else if result.get("before").is_some() {
-                    "deleted"
-                } else {
-                    "unknown"
-                }.to_string();
+                // Determine reaction type from the configured field, or from the result's shape
+                let reaction_type = state
+                    .settings
+                    .reaction_type_from_field(result)
+                    .unwrap_or_else(|| {
+                        if result.get("before").is_some() && result.get("after").is_some() {
+                            "updated"
+                        } else if result.get("after").is_some() {
+                            "added"
+                        } else if result.get("before").is_some() {
+                            "deleted"
+                        } else {
+                            "unknown"
+                        }
+                        .to_string()
+                    });
 
                 let sequence = (idx * 1000 + result_idx) as u64; // Generate sequence for batch items
 
@@ -437,6 +704,17 @@ async fn handle_reaction(
                     },
                 };
 
+                invocations.push(invocation);
+            }
+        }
+
+        if state.settings.backpressure {
+            if let Err(response) = queue_invocations_with_backpressure(&state.tx, invocations).await
+            {
+                return response;
+            }
+        } else {
+            for invocation in invocations {
                 if let Err(e) = state
                     .tx
                     .send(ReactionHandlerMessage::Invocation(invocation))
@@ -447,7 +725,7 @@ async fn handle_reaction(
             }
         }
 
-        (StatusCode::OK, "Batch processed")
+        (StatusCode::OK, "Batch processed").into_response()
     } else {
         // Handle single event (original logic)
         // Extract sequence from correlation header or request body
@@ -463,20 +741,23 @@ async fn handle_reaction(
                 .unwrap_or(0)
         };
 
-        // Determine reaction type from path or request body
-        let reaction_type = if uri.path().contains("/added") {
-            "added".to_string()
-        } else if uri.path().contains("/updated") {
-            "updated".to_string()
-        } else if uri.path().contains("/deleted") {
-            "deleted".to_string()
-        } else {
-            request_body
-                .get("type")
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string()
-        };
+        // Determine reaction type from the configured field, the path or the request body
+        let reaction_type =
+            if let Some(reaction_type) = state.settings.reaction_type_from_field(&request_body) {
+                reaction_type
+            } else if uri.path().contains("/added") {
+                "added".to_string()
+            } else if uri.path().contains("/updated") {
+                "updated".to_string()
+            } else if uri.path().contains("/deleted") {
+                "deleted".to_string()
+            } else {
+                request_body
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            };
 
         let query_id = state.settings.test_run_query_id.test_query_id.clone();
 
@@ -514,16 +795,308 @@ async fn handle_reaction(
             sequence
         );
 
+        if state.settings.backpressure {
+            return match queue_invocations_with_backpressure(&state.tx, vec![invocation]).await {
+                Ok(()) => (StatusCode::OK, "OK").into_response(),
+                Err(response) => response,
+            };
+        }
+
         match state
             .tx
             .send(ReactionHandlerMessage::Invocation(invocation))
             .await
         {
-            Ok(_) => (StatusCode::OK, "OK"),
+            Ok(_) => (StatusCode::OK, "OK").into_response(),
             Err(e) => {
                 log::error!("Failed to send reaction message: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error")
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal Server Error").into_response()
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forward_url() {
+        let uri = |s: &str| s.parse::<axum::http::Uri>().unwrap();
+
+        assert_eq!(
+            forward_url("http://consumer:9000/in", "/reaction", &uri("/reaction")),
+            "http://consumer:9000/in"
+        );
+        assert_eq!(
+            forward_url(
+                "http://consumer:9000/in/",
+                "/reaction",
+                &uri("/reaction/added?x=1")
+            ),
+            "http://consumer:9000/in/added?x=1"
+        );
+        // Requests outside the handler path (e.g. /batch) go to the forward_to URL itself.
+        assert_eq!(
+            forward_url("http://consumer:9000/in", "/reaction", &uri("/batch")),
+            "http://consumer:9000/in"
+        );
+        assert_eq!(
+            forward_url("http://consumer:9000/in", "/reaction", &uri("/reactions")),
+            "http://consumer:9000/in"
+        );
+    }
+
+    fn backpressure_state(capacity: usize) -> (HttpServerState, Receiver<ReactionHandlerMessage>) {
+        test_state(capacity, true, None)
+    }
+
+    fn test_state(
+        capacity: usize,
+        backpressure: bool,
+        reaction_type_field: Option<&str>,
+    ) -> (HttpServerState, Receiver<ReactionHandlerMessage>) {
+        let definition = HttpReactionHandlerDefinition {
+            host: None,
+            port: None,
+            path: None,
+            correlation_header: None,
+            forward_to: None,
+            backpressure,
+            reaction_type_field: reaction_type_field.map(|f| f.to_string()),
+        };
+        let id = TestRunQueryId::new(
+            &test_data_store::test_run_storage::TestRunId::new("repo", "test", "run"),
+            "query",
+        );
+        let (tx, rx) = tokio::sync::mpsc::channel(capacity);
+        let state = HttpServerState {
+            forwarder: None,
+            tx,
+            settings: HttpReactionHandlerSettings::new(id, definition).unwrap(),
+        };
+        (state, rx)
+    }
+
+    async fn post(state: &HttpServerState, path: &str, body: serde_json::Value) -> Response {
+        handle_reaction(
+            State(state.clone()),
+            Method::POST,
+            HeaderMap::new(),
+            path.parse().unwrap(),
+            body.to_string(),
+        )
+        .await
+    }
+
+    // Sets up proxy mode, relaying to the given URL.
+    fn forwarding_state(
+        url: String,
+    ) -> (
+        HttpServerState,
+        Receiver<ReactionHandlerMessage>,
+        Arc<HttpForwardStats>,
+    ) {
+        let (mut state, rx) = test_state(10, false, None);
+        let stats = Arc::new(HttpForwardStats::default());
+        state.forwarder = Some(HttpReactionForwarder {
+            client: reqwest::Client::builder()
+                .timeout(FORWARD_TIMEOUT)
+                .build()
+                .unwrap(),
+            handler_path: state.settings.path.clone(),
+            stats: stats.clone(),
+            url,
+        });
+        (state, rx, stats)
+    }
+
+    #[tokio::test]
+    async fn test_forward_relays_downstream_error_status() {
+        let app = Router::new().route(
+            "/in",
+            any(|| async { (StatusCode::BAD_GATEWAY, "consumer unavailable") }),
+        );
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        let (state, mut rx, stats) = forwarding_state(format!("http://127.0.0.1:{}/in", port));
+        let event = serde_json::json!({ "type": "added", "data": { "id": 1 } });
+
+        // The downstream status is relayed to the caller, and the invocation is still recorded.
+        let response = post(&state, "/reaction", event).await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert!(matches!(
+            rx.recv().await,
+            Some(ReactionHandlerMessage::Invocation(_))
+        ));
+
+        // The request reached the consumer, so it counts as forwarded rather than failed.
+        let metrics = stats.to_json("forward_to");
+        assert_eq!(metrics["forwarded_count"], 1);
+        assert_eq!(metrics["failed_count"], 0);
+        assert!(metrics["last_error"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_forward_to_unreachable_consumer() {
+        // Bind and release a port so nothing is listening on it.
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let (state, mut rx, stats) = forwarding_state(format!("http://127.0.0.1:{}/in", port));
+        let event = serde_json::json!({ "type": "added", "data": { "id": 1 } });
+
+        // The caller gets the handler's own response and the failure is recorded.
+        for _ in 0..2 {
+            let response = post(&state, "/reaction", event.clone()).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(matches!(
+                rx.recv().await,
+                Some(ReactionHandlerMessage::Invocation(_))
+            ));
+        }
+
+        let metrics = stats.to_json("forward_to");
+        assert_eq!(metrics["forwarded_count"], 0);
+        assert_eq!(metrics["failed_count"], 2);
+        assert!(metrics["last_error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_rejects_when_channel_full() {
+        let (state, mut rx) = backpressure_state(1);
+        let event = serde_json::json!({ "type": "added", "data": { "id": 1 } });
+
+        let response = post(&state, "/reaction", event.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The channel is now full, so the next request is rejected instead of waiting.
+        let response = post(&state, "/reaction", event.clone()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &BACKPRESSURE_RETRY_AFTER_SECS.to_string()
+        );
+
+        // Once the handler catches up, requests are accepted again.
+        assert!(rx.recv().await.is_some());
+        let response = post(&state, "/reaction", event).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The channel is closed once the receiver is dropped.
+        drop(rx);
+        let response = post(&state, "/reaction", serde_json::json!({})).await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_backpressure_queues_batch_all_or_nothing() {
+        let (state, mut rx) = backpressure_state(2);
+        let batch = |count: usize| {
+            let results: Vec<_> = (0..count)
+                .map(|i| serde_json::json!({ "type": "ADD", "data": { "id": i } }))
+                .collect();
+            serde_json::json!([{ "query_id": "query", "results": results }])
+        };
+
+        let response = post(&state, "/reaction", batch(1)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Only one of the two results would fit, so neither is queued.
+        let response = post(&state, "/reaction", batch(2)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        assert!(rx.recv().await.is_some());
+        assert!(rx.try_recv().is_err());
+    }
+
+    async fn recv_reaction_type(rx: &mut Receiver<ReactionHandlerMessage>) -> String {
+        match rx.recv().await {
+            Some(ReactionHandlerMessage::Invocation(invocation)) => invocation.payload.value
+                ["reaction_type"]
+                .as_str()
+                .unwrap()
+                .to_string(),
+            _ => panic!("Expected a reaction invocation"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reaction_type_field_overrides_heuristic() {
+        let (state, mut rx) = test_state(10, false, Some("/op"));
+
+        // With only "after", the heuristic would record this as added.
+        let batch = serde_json::json!([{
+            "query_id": "query",
+            "results": [{ "op": "u", "after": { "id": 1 } }]
+        }]);
+        assert_eq!(
+            post(&state, "/reaction", batch).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(recv_reaction_type(&mut rx).await, "updated");
+
+        let event = serde_json::json!({ "op": "d", "type": "added" });
+        assert_eq!(
+            post(&state, "/reaction/added", event).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(recv_reaction_type(&mut rx).await, "deleted");
+    }
+
+    #[tokio::test]
+    async fn test_reaction_type_field_falls_back_when_missing() {
+        let (state, mut rx) = test_state(10, false, Some("/op"));
+
+        let batch = serde_json::json!([{
+            "query_id": "query",
+            "results": [
+                { "after": { "id": 1 } },
+                { "before": { "id": 1 }, "after": { "id": 1 } },
+                { "op": 1, "before": { "id": 1 } }
+            ]
+        }]);
+        assert_eq!(
+            post(&state, "/reaction", batch).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(recv_reaction_type(&mut rx).await, "added");
+        assert_eq!(recv_reaction_type(&mut rx).await, "updated");
+        // A field that isn't a string is treated as missing.
+        assert_eq!(recv_reaction_type(&mut rx).await, "deleted");
+
+        let event = serde_json::json!({ "type": "added" });
+        assert_eq!(
+            post(&state, "/reaction", event).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(recv_reaction_type(&mut rx).await, "added");
+    }
+
+    #[test]
+    fn test_reaction_type_field_must_be_pointer() {
+        let definition = HttpReactionHandlerDefinition {
+            host: None,
+            port: None,
+            path: None,
+            correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: Some("op".to_string()),
+        };
+        let id = TestRunQueryId::new(
+            &test_data_store::test_run_storage::TestRunId::new("repo", "test", "run"),
+            "query",
+        );
+        assert!(HttpReactionHandlerSettings::new(id, definition).is_err());
+    }
+}
@@ -25,8 +25,11 @@ use crate::common::OutputHandlerMessage;
 
 pub mod drasi_server_callback_handler;
 pub mod drasi_server_channel_handler;
+pub mod graphql_reaction_handler;
 pub mod grpc_reaction_handler;
 pub mod http_reaction_handler;
+pub mod multi_reaction_handler;
+pub mod redis_stream_reaction_handler;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ReactionHandlerStatus {
@@ -100,5 +103,11 @@ pub async fn create_reaction_handler(
         ReactionHandlerDefinition::DrasiServerChannel(definition) => {
             drasi_server_channel_handler::DrasiServerChannelHandler::new(id, definition).await
         }
+        ReactionHandlerDefinition::GraphQl(definition) => {
+            graphql_reaction_handler::GraphQlReactionHandler::new(id, definition).await
+        }
+        ReactionHandlerDefinition::RedisStream(definition) => {
+            redis_stream_reaction_handler::RedisStreamReactionHandler::new(id, definition).await
+        }
     }
 }
@@ -0,0 +1,282 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use async_trait::async_trait;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::reactions::reaction_output_handler::{
+    ReactionControlSignal, ReactionHandlerMessage, ReactionHandlerStatus, ReactionOutputHandler,
+};
+
+const MERGED_CHANNEL_CAPACITY: usize = 1000;
+
+// Fans a reaction out over several handlers, e.g. a reaction that delivers over both HTTP
+// and gRPC, and merges their messages into a single channel so the ReactionObserver sees
+// one stream of invocations. Each invocation keeps the handler_type of the handler that
+// received it.
+pub struct MultiReactionHandler {
+    handlers: Vec<(String, Box<dyn ReactionOutputHandler + Send + Sync>)>,
+}
+
+impl MultiReactionHandler {
+    // Each handler is paired with a name used to key its metrics, normally the handler kind.
+    pub fn new(handlers: Vec<(String, Box<dyn ReactionOutputHandler + Send + Sync>)>) -> Self {
+        Self { handlers }
+    }
+}
+
+#[async_trait]
+impl ReactionOutputHandler for MultiReactionHandler {
+    async fn init(&self) -> anyhow::Result<Receiver<ReactionHandlerMessage>> {
+        let (merged_tx, merged_rx) = channel(MERGED_CHANNEL_CAPACITY);
+
+        // The observer treats a Stop signal as the end of the stream, so it is only
+        // forwarded once every handler has stopped.
+        let running = Arc::new(AtomicUsize::new(self.handlers.len()));
+        for (name, handler) in self.handlers.iter() {
+            let handler_rx = handler.init().await?;
+            tokio::spawn(forward_handler_messages(
+                name.clone(),
+                handler_rx,
+                merged_tx.clone(),
+                running.clone(),
+            ));
+        }
+
+        Ok(merged_rx)
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        for (_, handler) in self.handlers.iter() {
+            handler.start().await?;
+        }
+        Ok(())
+    }
+
+    async fn pause(&self) -> anyhow::Result<()> {
+        for (_, handler) in self.handlers.iter() {
+            handler.pause().await?;
+        }
+        Ok(())
+    }
+
+    // Stops every handler, even if stopping one of them fails, and returns the first error.
+    async fn stop(&self) -> anyhow::Result<()> {
+        let mut result = Ok(());
+        for (name, handler) in self.handlers.iter() {
+            if let Err(e) = handler.stop().await {
+                log::error!("Error stopping {} reaction handler: {}", name, e);
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+
+    async fn status(&self) -> ReactionHandlerStatus {
+        let mut statuses = Vec::with_capacity(self.handlers.len());
+        for (_, handler) in self.handlers.iter() {
+            statuses.push(handler.status().await);
+        }
+        combined_status(&statuses)
+    }
+
+    async fn metrics(&self) -> Option<serde_json::Value> {
+        let mut metrics = serde_json::Map::new();
+        for (name, handler) in self.handlers.iter() {
+            if let Some(handler_metrics) = handler.metrics().await {
+                metrics.insert(name.clone(), handler_metrics);
+            }
+        }
+
+        if metrics.is_empty() {
+            None
+        } else {
+            Some(serde_json::Value::Object(metrics))
+        }
+    }
+
+    async fn set_test_run_host(&self, test_run_host: Arc<crate::TestRunHost>) {
+        for (_, handler) in self.handlers.iter() {
+            handler.set_test_run_host(test_run_host.clone()).await;
+        }
+    }
+}
+
+async fn forward_handler_messages(
+    name: String,
+    mut handler_rx: Receiver<ReactionHandlerMessage>,
+    merged_tx: Sender<ReactionHandlerMessage>,
+    running: Arc<AtomicUsize>,
+) {
+    while let Some(message) = handler_rx.recv().await {
+        if let ReactionHandlerMessage::Control(ReactionControlSignal::Stop) = message {
+            break;
+        }
+
+        if merged_tx.send(message).await.is_err() {
+            log::debug!(
+                "Merged channel closed, no longer forwarding {} messages",
+                name
+            );
+            return;
+        }
+    }
+
+    log::debug!("{} reaction handler stopped", name);
+    if running.fetch_sub(1, Ordering::SeqCst) == 1 {
+        let _ = merged_tx
+            .send(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+            .await;
+    }
+}
+
+// An Error in any handler is an Error overall. Otherwise the combined status is the most
+// active status of any handler, so the reaction is Running while any handler is Running.
+fn combined_status(statuses: &[ReactionHandlerStatus]) -> ReactionHandlerStatus {
+    [
+        ReactionHandlerStatus::Error,
+        ReactionHandlerStatus::Running,
+        ReactionHandlerStatus::Paused,
+        ReactionHandlerStatus::Stopped,
+    ]
+    .into_iter()
+    .find(|status| statuses.contains(status))
+    .unwrap_or(ReactionHandlerStatus::Uninitialized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::sync::Mutex;
+
+    use crate::reactions::reaction_output_handler::{
+        ReactionHandlerPayload, ReactionHandlerType, ReactionInvocation,
+    };
+
+    // A handler whose messages are sent by the test.
+    struct StubHandler {
+        rx: Mutex<Option<Receiver<ReactionHandlerMessage>>>,
+    }
+
+    impl StubHandler {
+        fn new() -> (Sender<ReactionHandlerMessage>, Box<Self>) {
+            let (tx, rx) = channel(10);
+            (
+                tx,
+                Box::new(Self {
+                    rx: Mutex::new(Some(rx)),
+                }),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl ReactionOutputHandler for StubHandler {
+        async fn init(&self) -> anyhow::Result<Receiver<ReactionHandlerMessage>> {
+            self.rx
+                .lock()
+                .await
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("already initialized"))
+        }
+
+        async fn start(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn pause(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn invocation(handler_type: ReactionHandlerType) -> ReactionHandlerMessage {
+        ReactionHandlerMessage::Invocation(ReactionInvocation {
+            handler_type,
+            payload: ReactionHandlerPayload {
+                value: serde_json::json!({}),
+                timestamp: chrono::Utc::now(),
+                invocation_id: None,
+                metadata: None,
+            },
+        })
+    }
+
+    #[tokio::test]
+    async fn test_merges_handler_messages() -> anyhow::Result<()> {
+        let (http_tx, http_handler) = StubHandler::new();
+        let (grpc_tx, grpc_handler) = StubHandler::new();
+        let handler = MultiReactionHandler::new(vec![
+            ("Http".to_string(), http_handler as Box<_>),
+            ("Grpc".to_string(), grpc_handler as Box<_>),
+        ]);
+
+        let mut merged_rx = handler.init().await?;
+
+        http_tx.send(invocation(ReactionHandlerType::Http)).await?;
+        grpc_tx.send(invocation(ReactionHandlerType::Grpc)).await?;
+
+        let mut handler_types = Vec::new();
+        for _ in 0..2 {
+            match merged_rx.recv().await {
+                Some(ReactionHandlerMessage::Invocation(invocation)) => {
+                    handler_types.push(invocation.handler_type)
+                }
+                other => panic!("Expected an invocation, got {:?}", other),
+            }
+        }
+        assert!(handler_types.contains(&ReactionHandlerType::Http));
+        assert!(handler_types.contains(&ReactionHandlerType::Grpc));
+
+        // Stop is only forwarded once both handlers have stopped.
+        http_tx
+            .send(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+            .await?;
+        grpc_tx.send(invocation(ReactionHandlerType::Grpc)).await?;
+        assert!(matches!(
+            merged_rx.recv().await,
+            Some(ReactionHandlerMessage::Invocation(_))
+        ));
+
+        drop(grpc_tx);
+        assert!(matches!(
+            merged_rx.recv().await,
+            Some(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_combined_status() {
+        use ReactionHandlerStatus::*;
+
+        assert_eq!(combined_status(&[Running, Stopped]), Running);
+        assert_eq!(combined_status(&[Running, Error]), Error);
+        assert_eq!(combined_status(&[Paused, Stopped]), Paused);
+        assert_eq!(combined_status(&[Stopped, Stopped]), Stopped);
+        assert_eq!(combined_status(&[Uninitialized]), Uninitialized);
+    }
+}
@@ -0,0 +1,555 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use redis::{
+    aio::MultiplexedConnection,
+    streams::{StreamId, StreamReadOptions, StreamReadReply},
+    AsyncCommands, RedisResult,
+};
+use test_data_store::{
+    test_repo_storage::models::RedisStreamReactionHandlerDefinition,
+    test_run_storage::TestRunQueryId,
+};
+use tokio::sync::{
+    mpsc::{Receiver, Sender},
+    Notify, RwLock,
+};
+
+use crate::reactions::reaction_output_handler::{
+    ReactionControlSignal, ReactionHandlerError, ReactionHandlerMessage, ReactionHandlerPayload,
+    ReactionHandlerStatus, ReactionHandlerType, ReactionInvocation, ReactionOutputHandler,
+};
+
+// How long a single XREADGROUP blocks waiting for entries. This bounds how long the
+// reader takes to notice the handler has been paused or stopped.
+const READ_BLOCK_MS: usize = 1000;
+const READ_COUNT: usize = 100;
+
+#[derive(Clone, Debug)]
+pub struct RedisStreamReactionHandlerSettings {
+    pub url: String,
+    pub stream_key: String,
+    pub group: String,
+    pub consumer_name: String,
+    pub test_run_query_id: TestRunQueryId,
+}
+
+impl RedisStreamReactionHandlerSettings {
+    pub fn new(
+        id: TestRunQueryId,
+        definition: RedisStreamReactionHandlerDefinition,
+    ) -> anyhow::Result<Self> {
+        if definition.stream_key.is_empty() {
+            anyhow::bail!("RedisStreamReactionHandler requires a stream_key");
+        }
+
+        Ok(RedisStreamReactionHandlerSettings {
+            url: definition
+                .url
+                .unwrap_or_else(|| "redis://127.0.0.1:6379".to_string()),
+            stream_key: definition.stream_key,
+            group: definition
+                .group
+                .unwrap_or_else(|| "drasi-test-run-host".to_string()),
+            consumer_name: definition.consumer_name.unwrap_or_else(|| id.to_string()),
+            test_run_query_id: id,
+        })
+    }
+}
+
+pub struct RedisStreamReactionHandler {
+    notifier: Arc<Notify>,
+    settings: RedisStreamReactionHandlerSettings,
+    status: Arc<RwLock<ReactionHandlerStatus>>,
+}
+
+impl RedisStreamReactionHandler {
+    #[allow(clippy::new_ret_no_self)]
+    pub async fn new(
+        id: TestRunQueryId,
+        definition: RedisStreamReactionHandlerDefinition,
+    ) -> anyhow::Result<Box<dyn ReactionOutputHandler + Send + Sync>> {
+        let settings = RedisStreamReactionHandlerSettings::new(id, definition)?;
+        log::trace!(
+            "Creating RedisStreamReactionHandler with settings {:?}",
+            settings
+        );
+
+        Ok(Box::new(Self {
+            notifier: Arc::new(Notify::new()),
+            settings,
+            status: Arc::new(RwLock::new(ReactionHandlerStatus::Uninitialized)),
+        }))
+    }
+}
+
+#[async_trait]
+impl ReactionOutputHandler for RedisStreamReactionHandler {
+    async fn init(&self) -> anyhow::Result<Receiver<ReactionHandlerMessage>> {
+        log::debug!("Initializing RedisStreamReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    let (handler_tx_channel, handler_rx_channel) = tokio::sync::mpsc::channel(100);
+
+                    *status = ReactionHandlerStatus::Paused;
+
+                    tokio::spawn(reader_thread(
+                        self.settings.clone(),
+                        self.status.clone(),
+                        self.notifier.clone(),
+                        handler_tx_channel,
+                    ));
+
+                    Ok(handler_rx_channel)
+                }
+                ReactionHandlerStatus::Running => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Running");
+                }
+                ReactionHandlerStatus::Paused => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Paused");
+                }
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Init Handler, Handler currently Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn start(&self) -> anyhow::Result<()> {
+        log::debug!("Starting RedisStreamReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Can't Start Handler, Handler Uninitialized");
+                }
+                ReactionHandlerStatus::Running => Ok(()),
+                ReactionHandlerStatus::Paused => {
+                    *status = ReactionHandlerStatus::Running;
+                    self.notifier.notify_one();
+                    Ok(())
+                }
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Start Handler, Handler already Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn pause(&self) -> anyhow::Result<()> {
+        log::debug!("Pausing RedisStreamReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Can't Pause Handler, Handler Uninitialized");
+                }
+                ReactionHandlerStatus::Running => {
+                    *status = ReactionHandlerStatus::Paused;
+                    Ok(())
+                }
+                ReactionHandlerStatus::Paused => Ok(()),
+                ReactionHandlerStatus::Stopped => {
+                    anyhow::bail!("Can't Pause Handler, Handler already Stopped");
+                }
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn stop(&self) -> anyhow::Result<()> {
+        log::debug!("Stopping RedisStreamReactionHandler");
+
+        if let Ok(mut status) = self.status.try_write() {
+            match *status {
+                ReactionHandlerStatus::Uninitialized => {
+                    anyhow::bail!("Handler not initialized, current status: Uninitialized");
+                }
+                ReactionHandlerStatus::Running => {
+                    *status = ReactionHandlerStatus::Stopped;
+                    Ok(())
+                }
+                ReactionHandlerStatus::Paused => {
+                    // The reader is waiting to be notified, so wake it to see the Stop.
+                    *status = ReactionHandlerStatus::Stopped;
+                    self.notifier.notify_one();
+                    Ok(())
+                }
+                ReactionHandlerStatus::Stopped => Ok(()),
+                ReactionHandlerStatus::Error => {
+                    anyhow::bail!("Handler in Error state");
+                }
+            }
+        } else {
+            anyhow::bail!("Could not acquire status lock");
+        }
+    }
+
+    async fn status(&self) -> ReactionHandlerStatus {
+        *self.status.read().await
+    }
+}
+
+async fn send_error(
+    status: &Arc<RwLock<ReactionHandlerStatus>>,
+    handler_tx_channel: &Sender<ReactionHandlerMessage>,
+    message: String,
+) {
+    log::error!("{}", &message);
+    *status.write().await = ReactionHandlerStatus::Error;
+    if let Err(e) = handler_tx_channel
+        .send(ReactionHandlerMessage::Error(ReactionHandlerError::new(
+            message, false,
+        )))
+        .await
+    {
+        log::error!("Error sending error message: {:?}", e);
+    }
+}
+
+// Creates the consumer group at the start of the stream, creating the stream too if
+// needed. A BUSYGROUP error means the group already exists, which is fine.
+async fn ensure_consumer_group(
+    con: &mut MultiplexedConnection,
+    settings: &RedisStreamReactionHandlerSettings,
+) -> RedisResult<()> {
+    let result: RedisResult<()> = con
+        .xgroup_create_mkstream(&settings.stream_key, &settings.group, "0")
+        .await;
+
+    match result {
+        Err(e) if e.code() == Some("BUSYGROUP") => {
+            log::debug!(
+                "Consumer group {} already exists on stream {}",
+                settings.group,
+                settings.stream_key
+            );
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+async fn reader_thread(
+    settings: RedisStreamReactionHandlerSettings,
+    status: Arc<RwLock<ReactionHandlerStatus>>,
+    notify: Arc<Notify>,
+    handler_tx_channel: Sender<ReactionHandlerMessage>,
+) {
+    log::debug!("Starting RedisStreamReactionHandler Reader Thread");
+
+    let client = match redis::Client::open(settings.url.as_str()) {
+        Ok(client) => client,
+        Err(e) => {
+            send_error(
+                &status,
+                &handler_tx_channel,
+                format!("Redis client creation error: {:?}", e),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let mut con = match client.get_multiplexed_async_connection().await {
+        Ok(con) => {
+            log::debug!("Connected to Redis");
+            con
+        }
+        Err(e) => {
+            send_error(
+                &status,
+                &handler_tx_channel,
+                format!("Redis connection error: {:?}", e),
+            )
+            .await;
+            return;
+        }
+    };
+
+    if let Err(e) = ensure_consumer_group(&mut con, &settings).await {
+        send_error(
+            &status,
+            &handler_tx_channel,
+            format!(
+                "Error creating consumer group {} on stream {}: {:?}",
+                settings.group, settings.stream_key, e
+            ),
+        )
+        .await;
+        return;
+    }
+
+    let opts = StreamReadOptions::default()
+        .group(&settings.group, &settings.consumer_name)
+        .count(READ_COUNT)
+        .block(READ_BLOCK_MS);
+
+    loop {
+        let current_status = *status.read().await;
+
+        match current_status {
+            ReactionHandlerStatus::Uninitialized | ReactionHandlerStatus::Error => {
+                log::error!("Reader thread Uninitialized or Error, shutting down");
+                return;
+            }
+            ReactionHandlerStatus::Stopped => {
+                log::debug!("Reader thread Stopped, sending Stop message and shutting down");
+                if let Err(e) = handler_tx_channel
+                    .send(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+                    .await
+                {
+                    log::error!("Reader thread error sending Stop message: {:?}", e);
+                }
+                return;
+            }
+            ReactionHandlerStatus::Paused => {
+                log::debug!("Reader thread Paused, waiting to be notified");
+                notify.notified().await;
+            }
+            ReactionHandlerStatus::Running => {
+                // ">" asks for entries never delivered to any consumer in the group.
+                let read_result: RedisResult<StreamReadReply> = con
+                    .xread_options(&[&settings.stream_key], &[">"], &opts)
+                    .await;
+
+                let reply = match read_result {
+                    Ok(reply) => reply,
+                    Err(e) => {
+                        log::error!("Error reading from Redis stream: {:?}", e);
+                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+
+                for entry in reply.keys.into_iter().flat_map(|key| key.ids) {
+                    let invocation = stream_entry_to_invocation(&settings, &entry);
+                    if let Err(e) = handler_tx_channel
+                        .send(ReactionHandlerMessage::Invocation(invocation))
+                        .await
+                    {
+                        log::error!("Error sending reaction message: {:?}", e);
+                        return;
+                    }
+
+                    // Entries are only acknowledged once recorded, so an entry read
+                    // before a failure stays pending in the group.
+                    let ack_result: RedisResult<i64> = con
+                        .xack(&settings.stream_key, &settings.group, &[&entry.id])
+                        .await;
+                    if let Err(e) = ack_result {
+                        log::error!("Error acknowledging stream entry {}: {:?}", entry.id, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Entries with a `data` field carry the reaction output in it; otherwise all of the
+// entry's fields make up the value. Field values that are valid JSON are parsed.
+fn stream_entry_value(entry: &StreamId) -> serde_json::Value {
+    let fields: serde_json::Map<String, serde_json::Value> = entry
+        .map
+        .iter()
+        .map(|(field, value)| {
+            let text = redis::from_redis_value::<String>(value).unwrap_or_default();
+            let value = serde_json::from_str(&text).unwrap_or(serde_json::Value::String(text));
+            (field.clone(), value)
+        })
+        .collect();
+
+    match fields.get("data") {
+        Some(data) => data.clone(),
+        None => serde_json::Value::Object(fields),
+    }
+}
+
+// Stream ids are "<milliseconds>-<sequence>", so the entry's timestamp comes from the id.
+fn stream_entry_timestamp(id: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let millis = id.split_once('-').map_or(id, |(millis, _)| millis);
+    chrono::DateTime::from_timestamp_millis(millis.parse().ok()?)
+}
+
+fn stream_entry_to_invocation(
+    settings: &RedisStreamReactionHandlerSettings,
+    entry: &StreamId,
+) -> ReactionInvocation {
+    let mut field_names: Vec<&String> = entry.map.keys().collect();
+    field_names.sort();
+
+    let metadata = serde_json::json!({
+        "query_id": settings.test_run_query_id.test_query_id,
+        "stream_id": entry.id,
+        "stream_key": settings.stream_key,
+        "group": settings.group,
+        "consumer_name": settings.consumer_name,
+        "fields": field_names,
+    });
+
+    ReactionInvocation {
+        handler_type: ReactionHandlerType::RedisStream,
+        payload: ReactionHandlerPayload {
+            value: stream_entry_value(entry),
+            timestamp: stream_entry_timestamp(&entry.id).unwrap_or_else(chrono::Utc::now),
+            invocation_id: Some(entry.id.clone()),
+            metadata: Some(metadata),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use test_data_store::test_run_storage::TestRunId;
+
+    fn test_run_query_id() -> TestRunQueryId {
+        TestRunQueryId::new(&TestRunId::new("repo", "test", "run"), "query")
+    }
+
+    fn stream_entry(id: &str, fields: &[(&str, &str)]) -> StreamId {
+        StreamId {
+            id: id.to_string(),
+            map: fields
+                .iter()
+                .map(|(field, value)| {
+                    (
+                        field.to_string(),
+                        redis::Value::BulkString(value.as_bytes().to_vec()),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_stream_entry_to_invocation() -> anyhow::Result<()> {
+        let settings = RedisStreamReactionHandlerSettings::new(
+            test_run_query_id(),
+            RedisStreamReactionHandlerDefinition {
+                url: None,
+                stream_key: "reactions".to_string(),
+                group: None,
+                consumer_name: None,
+            },
+        )?;
+
+        let entry = stream_entry("1704067200000-3", &[("data", r#"{"id": 1}"#)]);
+        let invocation = stream_entry_to_invocation(&settings, &entry);
+        assert_eq!(invocation.handler_type, ReactionHandlerType::RedisStream);
+        assert_eq!(invocation.payload.value, serde_json::json!({ "id": 1 }));
+        assert_eq!(
+            invocation.payload.invocation_id.as_deref(),
+            Some("1704067200000-3")
+        );
+        assert_eq!(
+            invocation.payload.timestamp.timestamp_millis(),
+            1_704_067_200_000
+        );
+        let metadata = invocation.payload.metadata.unwrap();
+        assert_eq!(metadata["stream_id"], "1704067200000-3");
+        assert_eq!(metadata["group"], "drasi-test-run-host");
+
+        // Without a data field the value is made of all the fields.
+        let entry = stream_entry("1-0", &[("op", "i"), ("count", "2")]);
+        assert_eq!(
+            stream_entry_to_invocation(&settings, &entry).payload.value,
+            serde_json::json!({ "op": "i", "count": 2 })
+        );
+
+        Ok(())
+    }
+
+    // Run with a local Redis, e.g. `docker run -p 6379:6379 redis`, setting
+    // REDIS_TEST_URL if it isn't at redis://127.0.0.1:6379.
+    #[tokio::test]
+    #[ignore = "Requires a local Redis server"]
+    async fn test_redis_stream_reaction_handler() -> anyhow::Result<()> {
+        let url =
+            std::env::var("REDIS_TEST_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".into());
+        let stream_key = format!("test-reactions-{}", uuid::Uuid::new_v4());
+
+        let handler = RedisStreamReactionHandler::new(
+            test_run_query_id(),
+            RedisStreamReactionHandlerDefinition {
+                url: Some(url.clone()),
+                stream_key: stream_key.clone(),
+                group: Some("test-group".to_string()),
+                consumer_name: None,
+            },
+        )
+        .await?;
+
+        let mut rx = handler.init().await?;
+        handler.start().await?;
+
+        let mut con = redis::Client::open(url.as_str())?
+            .get_multiplexed_async_connection()
+            .await?;
+        let mut ids = Vec::new();
+        for i in 0..2 {
+            let id: String = con
+                .xadd(&stream_key, "*", &[("data", format!(r#"{{"id": {}}}"#, i))])
+                .await?;
+            ids.push(id);
+        }
+
+        for (i, id) in ids.iter().enumerate() {
+            let message =
+                tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await?;
+            match message {
+                Some(ReactionHandlerMessage::Invocation(invocation)) => {
+                    assert_eq!(invocation.payload.value, serde_json::json!({ "id": i }));
+                    assert_eq!(invocation.payload.metadata.unwrap()["stream_id"], *id);
+                }
+                other => panic!("Expected an invocation, got {:?}", other),
+            }
+        }
+
+        handler.stop().await?;
+        let message = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await?;
+        assert!(matches!(
+            message,
+            Some(ReactionHandlerMessage::Control(ReactionControlSignal::Stop))
+        ));
+
+        // Everything that was received has been acknowledged.
+        let pending: redis::streams::StreamPendingReply =
+            con.xpending(&stream_key, "test-group").await?;
+        assert_eq!(pending.count(), 0);
+
+        let _: () = con.del(&stream_key).await?;
+        Ok(())
+    }
+}
@@ -17,13 +17,19 @@
 //! This module provides an observer for reactions that handles
 //! HTTP callbacks and other reaction types using reaction-specific handlers.
 
-use std::{fmt, sync::Arc, time::SystemTime};
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use derive_more::Debug;
 
 use serde::Serialize;
 use test_data_store::{
-    test_repo_storage::models::{ReactionHandlerDefinition, StopTriggerDefinition},
+    test_repo_storage::models::{
+        ExpectedResultsDefinition, ReactionHandlerDefinition, StopTriggerDefinition,
+    },
     test_run_storage::{TestRunReactionId, TestRunReactionStorage},
 };
 use tokio::{
@@ -34,18 +40,28 @@ use tokio::{
 use crate::{
     common::{HandlerPayload, HandlerRecord},
     reactions::{
-        output_loggers::{OutputLogger, OutputLoggerConfig, OutputLoggerResult},
+        expected_results::{ExpectedResultsSummary, ExpectedResultsTracker},
+        output_loggers::{
+            JsonlFileOutputLogger, JsonlFileOutputLoggerConfig, OutputLogger, OutputLoggerConfig,
+            OutputLoggerFailure, OutputLoggerResult, PayloadProjection, ProjectingOutputLogger,
+        },
         reaction_output_handler::{
-            create_reaction_handler as create_handler, ReactionControlSignal,
-            ReactionHandlerMessage, ReactionHandlerStatus, ReactionHandlerType, ReactionInvocation,
-            ReactionOutputHandler,
+            create_reaction_handlers, ReactionControlSignal, ReactionHandlerMessage,
+            ReactionHandlerStatus, ReactionHandlerType, ReactionInvocation, ReactionOutputHandler,
+        },
+        stop_triggers::{
+            create_stop_trigger,
+            trace::{StopTriggerTraceInvocation, StopTriggerTraceRecord, StopTriggerTraceWriter},
+            StopTrigger, StopTriggerEvaluation,
         },
-        stop_triggers::{create_stop_trigger, StopTrigger},
     },
 };
 
 use super::TestRunReactionOverrides;
 
+// How often time based stop triggers are checked while the observer is Running.
+const STOP_TRIGGER_TIMER_INTERVAL: Duration = Duration::from_millis(100);
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ReactionObserverStatus {
     Running,
@@ -86,28 +102,56 @@ pub struct ReactionObserverCommandResponse {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ReactionObserverSettings {
-    pub definition: ReactionHandlerDefinition,
+    pub definitions: Vec<ReactionHandlerDefinition>,
+    pub expected_results: Option<ExpectedResultsDefinition>,
     pub id: TestRunReactionId,
     pub output_storage: TestRunReactionStorage,
     pub loggers: Vec<OutputLoggerConfig>,
+    pub project: Vec<String>,
+    pub raw_output_logger: Option<JsonlFileOutputLoggerConfig>,
     pub stop_triggers: Vec<StopTriggerDefinition>,
+    pub stop_trigger_trace: bool,
 }
 
 impl ReactionObserverSettings {
     pub async fn new(
         test_run_reaction_id: TestRunReactionId,
-        definition: ReactionHandlerDefinition,
+        definitions: Vec<ReactionHandlerDefinition>,
         output_storage: TestRunReactionStorage,
         loggers: Vec<OutputLoggerConfig>,
         stop_triggers: Vec<StopTriggerDefinition>,
-        _test_run_overrides: Option<TestRunReactionOverrides>,
+        test_run_overrides: Option<TestRunReactionOverrides>,
+        expected_results: Option<ExpectedResultsDefinition>,
     ) -> anyhow::Result<Self> {
+        let overrides = test_run_overrides.as_ref();
+        let stop_trigger_trace = overrides
+            .and_then(|overrides| overrides.stop_trigger_trace)
+            .unwrap_or(false);
+        let project = overrides
+            .and_then(|overrides| overrides.project.clone())
+            .unwrap_or_default();
+        let raw_output_logger = overrides.and_then(|overrides| overrides.raw_output_logger.clone());
+
+        // Check the paths now, rather than when the observer is started.
+        if !project.is_empty() {
+            PayloadProjection::new(&project)?;
+        } else if raw_output_logger.is_some() {
+            anyhow::bail!(
+                "ReactionObserver {} has a raw_output_logger but no project paths",
+                test_run_reaction_id
+            );
+        }
+
         Ok(Self {
-            definition,
+            definitions,
+            expected_results,
             id: test_run_reaction_id,
             output_storage,
             loggers,
+            project,
+            raw_output_logger,
             stop_triggers,
+            stop_trigger_trace,
         })
     }
 
@@ -140,10 +184,15 @@ pub struct ReactionObserverMessageResponse {
 pub struct ReactionObserverExternalState {
     pub status: ReactionObserverStatus,
     pub handler_status: ReactionHandlerStatus,
+    // The handler's metrics. For a reaction with more than one handler, these are keyed by
+    // handler type.
+    pub handler_metrics: Option<serde_json::Value>,
     pub error_message: Option<String>,
     pub result_summary: ReactionObserverSummary,
     pub settings: ReactionObserverSettings,
     pub logger_results: Vec<OutputLoggerResult>,
+    pub failed_loggers: Vec<OutputLoggerFailure>,
+    pub expected_results: Option<ExpectedResultsSummary>,
 }
 
 #[derive(Clone, Debug, Serialize, Default)]
@@ -235,8 +284,13 @@ struct ReactionObserverInternalState {
     #[debug(skip)]
     loggers: Vec<Box<dyn OutputLogger + Send + Sync>>,
     logger_results: Vec<OutputLoggerResult>,
+    failed_loggers: Vec<OutputLoggerFailure>,
     #[debug(skip)]
     stop_triggers: Vec<Box<dyn StopTrigger + Send + Sync>>,
+    #[debug(skip)]
+    stop_trigger_trace: Option<StopTriggerTraceWriter>,
+    #[debug(skip)]
+    expected_results: Option<ExpectedResultsTracker>,
 }
 
 impl ReactionObserverInternalState {
@@ -256,7 +310,10 @@ impl ReactionObserverInternalState {
             },
             loggers: vec![],
             logger_results: vec![],
+            failed_loggers: vec![],
             stop_triggers: vec![],
+            stop_trigger_trace: None,
+            expected_results: None,
         }
     }
 }
@@ -274,11 +331,12 @@ pub struct ReactionObserver {
 impl ReactionObserver {
     pub async fn new(
         id: TestRunReactionId,
-        definition: ReactionHandlerDefinition,
+        definitions: Vec<ReactionHandlerDefinition>,
         output_storage: TestRunReactionStorage,
         loggers: Vec<OutputLoggerConfig>,
         stop_triggers: Vec<StopTriggerDefinition>,
         test_run_overrides: Option<TestRunReactionOverrides>,
+        expected_results: Option<ExpectedResultsDefinition>,
     ) -> anyhow::Result<Self> {
         log::info!(
             "ReactionObserver::new() for {} with {} loggers: {:?}",
@@ -290,24 +348,26 @@ impl ReactionObserver {
         let settings = Arc::new(
             ReactionObserverSettings::new(
                 id.clone(),
-                definition.clone(),
+                definitions.clone(),
                 output_storage,
                 loggers,
                 stop_triggers,
                 test_run_overrides,
+                expected_results,
             )
             .await?,
         );
 
         let internal_state = Arc::new(Mutex::new(ReactionObserverInternalState::new()));
 
-        // Create output handler
+        // Create output handler, combining the handlers if the reaction has more than one
         // Note: We convert the reaction ID to a query ID for compatibility with the handler
         let handler_query_id = test_data_store::test_run_storage::TestRunQueryId::new(
             &id.test_run_id,
             &id.test_reaction_id,
         );
-        let output_handler = Arc::new(create_handler(handler_query_id, definition).await?);
+        let output_handler =
+            Arc::new(create_reaction_handlers(handler_query_id, definitions).await?);
 
         Ok(Self {
             settings,
@@ -323,10 +383,16 @@ impl ReactionObserver {
         let external_state = ReactionObserverExternalState {
             status: internal_state.status,
             handler_status: internal_state.handler_status,
+            handler_metrics: self.output_handler.metrics().await,
             error_message: internal_state.error_message.clone(),
             result_summary: ReactionObserverSummary::from(&internal_state.metrics),
             settings: (*self.settings).clone(),
             logger_results: internal_state.logger_results.clone(),
+            failed_loggers: internal_state.failed_loggers.clone(),
+            expected_results: internal_state
+                .expected_results
+                .as_ref()
+                .map(|tracker| tracker.summary()),
         };
 
         Ok(ReactionObserverCommandResponse {
@@ -358,10 +424,16 @@ impl ReactionObserver {
         let external_state = ReactionObserverExternalState {
             status: internal_state.status,
             handler_status: internal_state.handler_status,
+            handler_metrics: self.output_handler.metrics().await,
             error_message: internal_state.error_message.clone(),
             result_summary: ReactionObserverSummary::from(&internal_state.metrics),
             settings: (*self.settings).clone(),
             logger_results: internal_state.logger_results.clone(),
+            failed_loggers: internal_state.failed_loggers.clone(),
+            expected_results: internal_state
+                .expected_results
+                .as_ref()
+                .map(|tracker| tracker.summary()),
         };
 
         Ok(ReactionObserverCommandResponse {
@@ -391,17 +463,18 @@ impl ReactionObserver {
                 internal_state.logger_results.extend(results);
 
                 // Create new loggers
-                internal_state.loggers = create_reaction_loggers(
-                    self.settings.id.clone(),
-                    &self.settings.loggers,
-                    &self.settings.output_storage,
-                )
-                .await?;
+                internal_state.loggers = create_reaction_loggers(&self.settings).await?;
                 internal_state.logger_results = vec![];
+                internal_state.failed_loggers = vec![];
 
                 // Create new stop triggers
                 internal_state.stop_triggers =
                     create_reaction_stop_triggers(&self.settings.stop_triggers).await?;
+                close_stop_trigger_trace(&mut internal_state).await;
+                internal_state.stop_trigger_trace =
+                    create_stop_trigger_trace(&self.settings).await?;
+
+                internal_state.expected_results = load_expected_results(&self.settings).await?;
 
                 internal_state.handler_status = self.output_handler.status().await;
                 internal_state.error_message = None;
@@ -411,7 +484,16 @@ impl ReactionObserver {
                 };
             }
             ReactionObserverStatus::Stopped => {
-                return Err(ReactionObserverError::AlreadyStopped.into());
+                // Stopped is the initial state and start recreates the handler, loggers and
+                // stop triggers from it, so only the results of the previous run are cleared.
+                internal_state.logger_results = vec![];
+                internal_state.failed_loggers = vec![];
+                internal_state.expected_results = load_expected_results(&self.settings).await?;
+                internal_state.error_message = None;
+                internal_state.metrics = ReactionObserverMetrics {
+                    observer_create_time_ns: internal_state.metrics.observer_create_time_ns,
+                    ..Default::default()
+                };
             }
             ReactionObserverStatus::Error => {
                 return Err(ReactionObserverError::Error(internal_state.status).into());
@@ -421,10 +503,16 @@ impl ReactionObserver {
         let external_state = ReactionObserverExternalState {
             status: internal_state.status,
             handler_status: internal_state.handler_status,
+            handler_metrics: self.output_handler.metrics().await,
             error_message: internal_state.error_message.clone(),
             result_summary: ReactionObserverSummary::from(&internal_state.metrics),
             settings: (*self.settings).clone(),
             logger_results: internal_state.logger_results.clone(),
+            failed_loggers: internal_state.failed_loggers.clone(),
+            expected_results: internal_state
+                .expected_results
+                .as_ref()
+                .map(|tracker| tracker.summary()),
         };
 
         Ok(ReactionObserverCommandResponse {
@@ -444,19 +532,29 @@ impl ReactionObserver {
                 self.output_handler.start().await?;
                 internal_state.status = ReactionObserverStatus::Running;
                 internal_state.handler_status = self.output_handler.status().await;
+
+                // A reset clears the start time, so the run duration restarts from here.
+                if internal_state.metrics.observer_start_time_ns == 0 {
+                    internal_state.metrics.observer_start_time_ns = SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .unwrap()
+                        .as_nanos()
+                        as u64;
+                }
             }
             ReactionObserverStatus::Stopped => {
                 // Initialize loggers
-                internal_state.loggers = create_reaction_loggers(
-                    self.settings.id.clone(),
-                    &self.settings.loggers,
-                    &self.settings.output_storage,
-                )
-                .await?;
+                internal_state.loggers = create_reaction_loggers(&self.settings).await?;
+                internal_state.failed_loggers = vec![];
 
                 // Initialize stop triggers
                 internal_state.stop_triggers =
                     create_reaction_stop_triggers(&self.settings.stop_triggers).await?;
+                internal_state.stop_trigger_trace =
+                    create_stop_trigger_trace(&self.settings).await?;
+
+                // Load the expected results before any invocations can arrive
+                internal_state.expected_results = load_expected_results(&self.settings).await?;
 
                 // Initialize and start the handler
                 log::info!("[ReactionObserver] Initializing output handler for reaction: {}", self.settings.id);
@@ -469,10 +567,12 @@ impl ReactionObserver {
                 let (command_tx, command_rx) = tokio::sync::mpsc::channel(100);
                 *self.observer_command_tx.lock().await = Some(command_tx);
 
+                let settings_clone = self.settings.clone();
                 let internal_state_clone = self.internal_state.clone();
                 let output_handler_clone = self.output_handler.clone();
                 let observer_task = tokio::spawn(async move {
                     observe_reaction_handler(
+                        settings_clone,
                         handler_rx_channel,
                         command_rx,
                         internal_state_clone,
@@ -499,10 +599,16 @@ impl ReactionObserver {
         let external_state = ReactionObserverExternalState {
             status: internal_state.status,
             handler_status: internal_state.handler_status,
+            handler_metrics: self.output_handler.metrics().await,
             error_message: internal_state.error_message.clone(),
             result_summary: ReactionObserverSummary::from(&internal_state.metrics),
             settings: (*self.settings).clone(),
             logger_results: internal_state.logger_results.clone(),
+            failed_loggers: internal_state.failed_loggers.clone(),
+            expected_results: internal_state
+                .expected_results
+                .as_ref()
+                .map(|tracker| tracker.summary()),
         };
 
         Ok(ReactionObserverCommandResponse {
@@ -554,6 +660,7 @@ impl ReactionObserver {
                 }
                 internal_state.logger_results.extend(results);
                 internal_state.loggers.clear();
+                close_stop_trigger_trace(&mut internal_state).await;
 
                 internal_state.status = ReactionObserverStatus::Stopped;
                 internal_state.handler_status = self.output_handler.status().await;
@@ -574,10 +681,16 @@ impl ReactionObserver {
         let external_state = ReactionObserverExternalState {
             status: internal_state.status,
             handler_status: internal_state.handler_status,
+            handler_metrics: self.output_handler.metrics().await,
             error_message: internal_state.error_message.clone(),
             result_summary: ReactionObserverSummary::from(&internal_state.metrics),
             settings: (*self.settings).clone(),
             logger_results: internal_state.logger_results.clone(),
+            failed_loggers: internal_state.failed_loggers.clone(),
+            expected_results: internal_state
+                .expected_results
+                .as_ref()
+                .map(|tracker| tracker.summary()),
         };
 
         Ok(ReactionObserverCommandResponse {
@@ -610,6 +723,7 @@ impl ReactionObserver {
 }
 
 async fn observe_reaction_handler(
+    settings: Arc<ReactionObserverSettings>,
     mut handler_rx: tokio::sync::mpsc::Receiver<ReactionHandlerMessage>,
     mut command_rx: tokio::sync::mpsc::Receiver<ReactionObserverMessage>,
     internal_state: Arc<Mutex<ReactionObserverInternalState>>,
@@ -617,6 +731,17 @@ async fn observe_reaction_handler(
 ) {
     log::debug!("Starting reaction observer task");
 
+    // Time based stop triggers must fire even if no invocations arrive, so when there are any,
+    // the stop triggers are also checked on a timer.
+    let has_timed_stop_triggers = settings.stop_triggers.iter().any(|def| {
+        matches!(
+            def,
+            StopTriggerDefinition::MaxDuration(_) | StopTriggerDefinition::ThroughputBelow(_)
+        )
+    });
+    let mut stop_trigger_timer = tokio::time::interval(STOP_TRIGGER_TIMER_INTERVAL);
+    stop_trigger_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
     loop {
         tokio::select! {
             Some(handler_msg) = handler_rx.recv() => {
@@ -627,60 +752,21 @@ async fn observe_reaction_handler(
                     }
                     ReactionHandlerMessage::Invocation(invocation) => {
                         let mut state = internal_state.lock().await;
+                        let invocation_id = invocation.payload.invocation_id.clone();
+                        let timestamp_ns = invocation
+                            .payload
+                            .timestamp
+                            .timestamp_nanos_opt()
+                            .unwrap_or(0) as u64;
                         handle_reaction_invocation(&mut state, invocation).await;
 
-                        // Check stop triggers
-                        let handler_status = output_handler.status().await;
-                        log::trace!(
-                            "Checking {} stop triggers after {} invocations",
-                            state.stop_triggers.len(),
-                            state.metrics.reaction_invocation_count
-                        );
-
-                        for (idx, trigger) in state.stop_triggers.iter().enumerate() {
-                            match trigger.is_true(&handler_status, &state.metrics).await {
-                                Ok(true) => {
-                                    log::error!(
-                                        "Stop trigger {} fired after {} invocations, stopping reaction observer",
-                                        idx,
-                                        state.metrics.reaction_invocation_count
-                                    );
-                                    state.status = ReactionObserverStatus::Stopped;
-
-                                // Close loggers and collect results before stopping
-                                log::info!("Closing {} loggers after stop trigger fired", state.loggers.len());
-                                let mut results = Vec::new();
-                                for (idx, logger) in state.loggers.iter_mut().enumerate() {
-                                    log::debug!("Calling end_test_run on logger {}", idx);
-                                    match logger.end_test_run().await {
-                                        Ok(result) => {
-                                            log::info!("Logger {} completed: {:?}", idx, result);
-                                            results.push(result);
-                                        }
-                                        Err(e) => {
-                                            log::error!("Logger {} failed to end test run: {}", idx, e);
-                                        }
-                                    }
-                                }
-                                state.logger_results.extend(results);
-                                state.loggers.clear();
-
-                                // Record stop time
-                                state.metrics.observer_stop_time_ns = SystemTime::now()
-                                    .duration_since(SystemTime::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_nanos() as u64;
-
-                                output_handler.stop().await.ok();
-                                return;
-                                }
-                                Ok(false) => {
-                                    log::trace!("Stop trigger {} not fired yet", idx);
-                                }
-                                Err(e) => {
-                                    log::error!("Error checking stop trigger {}: {}", idx, e);
-                                }
-                            }
+                        let invocation = StopTriggerTraceInvocation {
+                            invocation_id,
+                            reaction_invocation_count: state.metrics.reaction_invocation_count,
+                            timestamp_ns,
+                        };
+                        if check_stop_triggers(&settings, &mut state, &output_handler, Some(&invocation)).await {
+                            return;
                         }
                     }
                     ReactionHandlerMessage::Error(error) => {
@@ -694,6 +780,14 @@ async fn observe_reaction_handler(
                     }
                 }
             }
+            _ = stop_trigger_timer.tick(), if has_timed_stop_triggers => {
+                let mut state = internal_state.lock().await;
+                if state.status == ReactionObserverStatus::Running
+                    && check_stop_triggers(&settings, &mut state, &output_handler, None).await
+                {
+                    return;
+                }
+            }
             Some(command_msg) = command_rx.recv() => {
                 match command_msg.command {
                     ReactionObserverCommand::Stop => {
@@ -714,6 +808,96 @@ async fn observe_reaction_handler(
     log::debug!("Reaction observer task ending");
 }
 
+// Evaluates the stop triggers and stops the observer if any of them fires, returning true if
+// it was stopped. Evaluations made on the timer, without an invocation, are only traced when
+// a trigger fires.
+async fn check_stop_triggers(
+    settings: &ReactionObserverSettings,
+    state: &mut ReactionObserverInternalState,
+    output_handler: &Arc<Box<dyn ReactionOutputHandler + Send + Sync>>,
+    invocation: Option<&StopTriggerTraceInvocation>,
+) -> bool {
+    let handler_status = output_handler.status().await;
+    log::trace!(
+        "Checking {} stop triggers after {} invocations",
+        state.stop_triggers.len(),
+        state.metrics.reaction_invocation_count
+    );
+
+    let mut evaluations = Vec::with_capacity(state.stop_triggers.len());
+    for trigger in state.stop_triggers.iter() {
+        evaluations.push(trigger.evaluate(&handler_status, &state.metrics).await);
+    }
+
+    let fired = evaluations
+        .iter()
+        .any(|evaluation| matches!(evaluation, Ok(evaluation) if evaluation.outcome));
+    if let Some(trace) = state.stop_trigger_trace.as_mut() {
+        if invocation.is_some() || fired {
+            write_stop_trigger_trace(
+                trace,
+                &settings.stop_triggers,
+                handler_status,
+                invocation,
+                &evaluations,
+            )
+            .await;
+        }
+    }
+
+    for (idx, evaluation) in evaluations.iter().enumerate() {
+        match evaluation {
+            Ok(evaluation) if evaluation.outcome => {
+                log::error!(
+                    "Stop trigger {} fired after {} invocations, stopping reaction observer",
+                    idx,
+                    state.metrics.reaction_invocation_count
+                );
+                state.status = ReactionObserverStatus::Stopped;
+
+                // Close loggers and collect results before stopping
+                log::info!(
+                    "Closing {} loggers after stop trigger fired",
+                    state.loggers.len()
+                );
+                let mut results = Vec::new();
+                for (idx, logger) in state.loggers.iter_mut().enumerate() {
+                    log::debug!("Calling end_test_run on logger {}", idx);
+                    match logger.end_test_run().await {
+                        Ok(result) => {
+                            log::info!("Logger {} completed: {:?}", idx, result);
+                            results.push(result);
+                        }
+                        Err(e) => {
+                            log::error!("Logger {} failed to end test run: {}", idx, e);
+                        }
+                    }
+                }
+                state.logger_results.extend(results);
+                state.loggers.clear();
+                close_stop_trigger_trace(state).await;
+
+                // Record stop time
+                state.metrics.observer_stop_time_ns = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos() as u64;
+
+                output_handler.stop().await.ok();
+                return true;
+            }
+            Ok(_) => {
+                log::trace!("Stop trigger {} not fired yet", idx);
+            }
+            Err(e) => {
+                log::error!("Error checking stop trigger {}: {}", idx, e);
+            }
+        }
+    }
+
+    false
+}
+
 async fn handle_reaction_invocation(
     state: &mut ReactionObserverInternalState,
     invocation: ReactionInvocation,
@@ -731,6 +915,10 @@ async fn handle_reaction_invocation(
     }
     state.metrics.reaction_invocation_last_ns = timestamp_ns;
 
+    if let Some(tracker) = state.expected_results.as_mut() {
+        tracker.record(&invocation.payload.value);
+    }
+
     // Log the reaction
     log::debug!(
         "Reaction invoked: type={:?}, invocation_id={:?}, timestamp={}, total_count={}",
@@ -762,6 +950,8 @@ async fn handle_reaction_invocation(
                 ReactionHandlerType::Http => "Http".to_string(),
                 ReactionHandlerType::EventGrid => "EventGrid".to_string(),
                 ReactionHandlerType::Grpc => "Grpc".to_string(),
+                ReactionHandlerType::GraphQl => "GraphQl".to_string(),
+                ReactionHandlerType::RedisStream => "RedisStream".to_string(),
             },
             query_id: "unknown".to_string(), // TODO: Extract from payload if available
             request_method: invocation
@@ -803,33 +993,83 @@ async fn handle_reaction_invocation(
         state.loggers.len()
     );
 
-    for (idx, logger) in state.loggers.iter_mut().enumerate() {
+    log_handler_record_to_loggers(
+        &mut state.loggers,
+        &mut state.failed_loggers,
+        &handler_record,
+    )
+    .await;
+}
+
+// Sends the record to every logger that hasn't failed. A logger that returns an error is
+// marked as failed and receives no further records, so one broken logger can't disrupt
+// the observer or the other loggers.
+async fn log_handler_record_to_loggers(
+    loggers: &mut [Box<dyn OutputLogger + Send + Sync>],
+    failed_loggers: &mut Vec<OutputLoggerFailure>,
+    record: &HandlerRecord,
+) {
+    for (idx, logger) in loggers.iter_mut().enumerate() {
+        if failed_loggers.iter().any(|f| f.logger_index == idx) {
+            continue;
+        }
+
         log::trace!("Sending record to logger {}", idx);
-        if let Err(e) = logger.log_handler_record(&handler_record).await {
-            log::error!("Failed to log reaction invocation to logger {}: {}", idx, e);
+        if let Err(e) = logger.log_handler_record(record).await {
+            log::error!(
+                "Logger {} failed to log reaction invocation and will receive no more records: {}",
+                idx,
+                e
+            );
+            failed_loggers.push(OutputLoggerFailure {
+                logger_index: idx,
+                error: e.to_string(),
+                record_sequence: record.sequence,
+            });
         }
     }
 }
 
-// Helper function to create reaction loggers
+// Helper function to create reaction loggers. If the payloads are projected, every
+// configured logger receives projected records and the optional raw logger receives the
+// full records.
 async fn create_reaction_loggers(
-    reaction_id: TestRunReactionId,
-    configs: &Vec<OutputLoggerConfig>,
-    output_storage: &TestRunReactionStorage,
+    settings: &ReactionObserverSettings,
 ) -> anyhow::Result<Vec<Box<dyn OutputLogger + Send + Sync>>> {
     use crate::reactions::output_loggers::create_output_logger;
 
     log::info!(
         "create_reaction_loggers() for {} with {} configs, storage path: {:?}",
-        reaction_id,
-        configs.len(),
-        output_storage.reaction_output_path
+        settings.id,
+        settings.loggers.len(),
+        settings.output_storage.reaction_output_path
     );
 
+    let projection = if settings.project.is_empty() {
+        None
+    } else {
+        Some(PayloadProjection::new(&settings.project)?)
+    };
+
     let mut result = Vec::new();
-    for config in configs {
+    for config in settings.loggers.iter() {
         log::info!("Creating logger with config: {:?}", config);
-        result.push(create_output_logger(reaction_id.clone(), config, output_storage).await?);
+        let logger =
+            create_output_logger(settings.id.clone(), config, &settings.output_storage).await?;
+        match &projection {
+            Some(projection) => result.push(Box::new(ProjectingOutputLogger::new(
+                logger,
+                projection.clone(),
+            )) as Box<dyn OutputLogger + Send + Sync>),
+            None => result.push(logger),
+        }
+    }
+
+    if let Some(config) = &settings.raw_output_logger {
+        result.push(
+            JsonlFileOutputLogger::new_raw(settings.id.clone(), config, &settings.output_storage)
+                .await?,
+        );
     }
 
     log::info!("Successfully created {} loggers", result.len());
@@ -846,3 +1086,186 @@ async fn create_reaction_stop_triggers(
     }
     Ok(result)
 }
+
+async fn load_expected_results(
+    settings: &ReactionObserverSettings,
+) -> anyhow::Result<Option<ExpectedResultsTracker>> {
+    match &settings.expected_results {
+        Some(definition) => Ok(Some(ExpectedResultsTracker::load(definition).await?)),
+        None => Ok(None),
+    }
+}
+
+async fn create_stop_trigger_trace(
+    settings: &ReactionObserverSettings,
+) -> anyhow::Result<Option<StopTriggerTraceWriter>> {
+    if settings.stop_trigger_trace {
+        Ok(Some(
+            StopTriggerTraceWriter::new(&settings.output_storage).await?,
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn close_stop_trigger_trace(state: &mut ReactionObserverInternalState) {
+    if let Some(mut trace) = state.stop_trigger_trace.take() {
+        if let Err(e) = trace.close().await {
+            log::error!("Failed to close stop trigger trace: {}", e);
+        }
+    }
+}
+
+async fn write_stop_trigger_trace(
+    trace: &mut StopTriggerTraceWriter,
+    definitions: &[StopTriggerDefinition],
+    handler_status: ReactionHandlerStatus,
+    invocation: Option<&StopTriggerTraceInvocation>,
+    evaluations: &[anyhow::Result<StopTriggerEvaluation>],
+) {
+    let evaluation_time_ns = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64;
+
+    for (idx, evaluation) in evaluations.iter().enumerate() {
+        let record = StopTriggerTraceRecord {
+            evaluation_time_ns,
+            trigger_index: idx,
+            trigger: definitions.get(idx),
+            handler_status,
+            invocation,
+            evaluation: evaluation.as_ref().ok(),
+            error: evaluation.as_ref().err().map(|e| e.to_string()),
+        };
+
+        if let Err(e) = trace.write_record(&record).await {
+            log::error!("Failed to write stop trigger trace record: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+
+    struct CountingLogger {
+        count: Arc<AtomicU64>,
+        fail_on: Option<u64>,
+    }
+
+    #[async_trait]
+    impl OutputLogger for CountingLogger {
+        async fn end_test_run(&mut self) -> anyhow::Result<OutputLoggerResult> {
+            Ok(OutputLoggerResult {
+                has_output: false,
+                logger_name: "Counting".to_string(),
+                output_folder_path: None,
+                summary: None,
+            })
+        }
+
+        async fn log_handler_record(&mut self, record: &HandlerRecord) -> anyhow::Result<()> {
+            if self.fail_on == Some(record.sequence) {
+                anyhow::bail!("disk full");
+            }
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn record(sequence: u64) -> HandlerRecord {
+        HandlerRecord {
+            id: format!("record-{}", sequence),
+            sequence,
+            created_time_ns: 0,
+            processed_time_ns: 0,
+            traceparent: None,
+            tracestate: None,
+            payload: HandlerPayload::ReactionOutput {
+                reaction_output: serde_json::json!({}),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_logger_is_isolated() {
+        let failing_count = Arc::new(AtomicU64::new(0));
+        let healthy_count = Arc::new(AtomicU64::new(0));
+
+        let mut loggers: Vec<Box<dyn OutputLogger + Send + Sync>> = vec![
+            Box::new(CountingLogger {
+                count: failing_count.clone(),
+                fail_on: Some(2),
+            }),
+            Box::new(CountingLogger {
+                count: healthy_count.clone(),
+                fail_on: None,
+            }),
+        ];
+        let mut failed_loggers = Vec::new();
+
+        for sequence in 1..=4 {
+            log_handler_record_to_loggers(&mut loggers, &mut failed_loggers, &record(sequence))
+                .await;
+        }
+
+        // The failing logger stops receiving records after its first error
+        assert_eq!(failing_count.load(Ordering::SeqCst), 1);
+        assert_eq!(healthy_count.load(Ordering::SeqCst), 4);
+
+        assert_eq!(failed_loggers.len(), 1);
+        assert_eq!(failed_loggers[0].logger_index, 0);
+        assert_eq!(failed_loggers[0].record_sequence, 2);
+        assert_eq!(failed_loggers[0].error, "disk full");
+    }
+
+    fn invocation(value: serde_json::Value) -> ReactionInvocation {
+        ReactionInvocation {
+            handler_type: ReactionHandlerType::Http,
+            payload: crate::reactions::reaction_output_handler::ReactionHandlerPayload {
+                value,
+                timestamp: chrono::Utc::now(),
+                invocation_id: None,
+                metadata: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expected_results_summary() {
+        let definition = ExpectedResultsDefinition {
+            file: None,
+            key_paths: vec!["/id".to_string()],
+            normalizations: Vec::new(),
+            results: Some(vec![
+                serde_json::json!({"id": "a"}),
+                serde_json::json!({"id": "b"}),
+                serde_json::json!({"id": "c"}),
+            ]),
+        };
+
+        let mut state = ReactionObserverInternalState::new();
+        state.expected_results = Some(ExpectedResultsTracker::load(&definition).await.unwrap());
+
+        // "c" never arrives and "d" isn't expected.
+        for id in ["a", "b", "d"] {
+            handle_reaction_invocation(&mut state, invocation(serde_json::json!({ "id": id })))
+                .await;
+        }
+
+        assert_eq!(state.metrics.reaction_invocation_count, 3);
+        assert_eq!(
+            state.expected_results.as_ref().unwrap().summary(),
+            ExpectedResultsSummary {
+                matched: 2,
+                missing: 1,
+                unexpected: 1,
+            }
+        );
+    }
+}
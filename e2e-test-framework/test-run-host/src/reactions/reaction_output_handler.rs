@@ -96,6 +96,8 @@ pub enum ReactionHandlerType {
     Http,
     EventGrid,
     Grpc,
+    GraphQl,
+    RedisStream,
 }
 
 /// Reaction payload
@@ -259,5 +261,57 @@ pub async fn create_reaction_handler(
                 .await
                 .map(|h| h as Box<dyn ReactionOutputHandler + Send + Sync>)
         }
+        ReactionHandlerDefinition::GraphQl(def) => {
+            use super::reaction_handlers::graphql_reaction_handler::GraphQlReactionHandler;
+            GraphQlReactionHandler::new(id, def).await
+        }
+        ReactionHandlerDefinition::RedisStream(def) => {
+            use super::reaction_handlers::redis_stream_reaction_handler::RedisStreamReactionHandler;
+            RedisStreamReactionHandler::new(id, def).await
+        }
+    }
+}
+
+/// Create a single reaction output handler for one or more reaction handler definitions
+///
+/// With more than one definition, the handlers are combined so their invocations arrive
+/// on one channel, tagged with the type of the handler that received them.
+pub async fn create_reaction_handlers(
+    id: TestRunQueryId,
+    definitions: Vec<ReactionHandlerDefinition>,
+) -> anyhow::Result<Box<dyn ReactionOutputHandler + Send + Sync>> {
+    use super::reaction_handlers::multi_reaction_handler::MultiReactionHandler;
+
+    match definitions.len() {
+        0 => anyhow::bail!("No reaction handlers defined for {}", id),
+        1 => create_reaction_handler(id, definitions.into_iter().next().unwrap()).await,
+        _ => {
+            let mut handlers = Vec::with_capacity(definitions.len());
+            for definition in definitions {
+                // Handlers are named by kind, with the position appended to repeated kinds.
+                let kind = reaction_handler_kind(&definition);
+                let name = if handlers.iter().any(|(name, _)| name == &kind) {
+                    format!("{}-{}", kind, handlers.len())
+                } else {
+                    kind
+                };
+                handlers.push((name, create_reaction_handler(id.clone(), definition).await?));
+            }
+            Ok(Box::new(MultiReactionHandler::new(handlers)))
+        }
+    }
+}
+
+/// The `kind` of a reaction handler definition, e.g. "Http"
+fn reaction_handler_kind(definition: &ReactionHandlerDefinition) -> String {
+    match definition {
+        ReactionHandlerDefinition::Http(_) => "Http",
+        ReactionHandlerDefinition::EventGrid(_) => "EventGrid",
+        ReactionHandlerDefinition::Grpc(_) => "Grpc",
+        ReactionHandlerDefinition::DrasiServerCallback(_) => "DrasiServerCallback",
+        ReactionHandlerDefinition::DrasiServerChannel(_) => "DrasiServerChannel",
+        ReactionHandlerDefinition::GraphQl(_) => "GraphQl",
+        ReactionHandlerDefinition::RedisStream(_) => "RedisStream",
     }
+    .to_string()
 }
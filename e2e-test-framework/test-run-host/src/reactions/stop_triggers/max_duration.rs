@@ -0,0 +1,83 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+
+use test_data_store::test_repo_storage::models::MaxDurationStopTriggerDefinition;
+
+use crate::reactions::{
+    reaction_observer::ReactionObserverMetrics, reaction_output_handler::ReactionHandlerStatus,
+};
+
+use super::StopTrigger;
+
+#[derive(Debug)]
+pub struct MaxDurationStopTriggerSettings {
+    pub max_duration_ns: u64,
+}
+
+impl MaxDurationStopTriggerSettings {
+    pub fn new(cfg: &MaxDurationStopTriggerDefinition) -> anyhow::Result<Self> {
+        Ok(Self {
+            max_duration_ns: cfg.seconds.saturating_mul(1_000_000_000),
+        })
+    }
+}
+
+// Fires once the observer has been running for the configured duration. The observer checks
+// this trigger on a timer as well as after each invocation, so it fires even if the reaction
+// receives no invocations.
+pub struct MaxDurationStopTrigger {
+    settings: MaxDurationStopTriggerSettings,
+}
+
+impl MaxDurationStopTrigger {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        def: &MaxDurationStopTriggerDefinition,
+    ) -> anyhow::Result<Box<dyn StopTrigger + Send + Sync>> {
+        log::debug!("Creating MaxDurationStopTrigger from {:?}, ", def);
+
+        let settings = MaxDurationStopTriggerSettings::new(def)?;
+        log::trace!(
+            "Creating MaxDurationStopTrigger with settings {:?}, ",
+            settings
+        );
+
+        Ok(Box::new(Self { settings }))
+    }
+}
+
+#[async_trait]
+impl StopTrigger for MaxDurationStopTrigger {
+    async fn is_true(
+        &self,
+        _handler_status: &ReactionHandlerStatus,
+        stats: &ReactionObserverMetrics,
+    ) -> anyhow::Result<bool> {
+        // The observer hasn't started running yet.
+        if stats.observer_start_time_ns == 0 {
+            return Ok(false);
+        }
+
+        let now_ns = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+
+        Ok(now_ns.saturating_sub(stats.observer_start_time_ns) >= self.settings.max_duration_ns)
+    }
+}
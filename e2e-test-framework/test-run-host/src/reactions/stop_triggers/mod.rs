@@ -13,15 +13,21 @@
 // limitations under the License.
 
 use async_trait::async_trait;
+use serde::Serialize;
 
+use max_duration::MaxDurationStopTrigger;
 use record_count::RecordCountStopTrigger;
 use test_data_store::test_repo_storage::models::StopTriggerDefinition;
+use throughput_below::ThroughputBelowStopTrigger;
 
 use crate::reactions::reaction_output_handler::ReactionHandlerStatus;
 
 use super::reaction_observer::ReactionObserverMetrics;
 
+pub mod max_duration;
 pub mod record_count;
+pub mod throughput_below;
+pub mod trace;
 
 #[derive(Debug, thiserror::Error)]
 pub enum StopTriggerError {
@@ -38,6 +44,15 @@ impl std::fmt::Display for StopTriggerError {
     }
 }
 
+// The outcome of evaluating a StopTrigger. Triggers composed of other triggers
+// report the outcome of each child so the evaluation can be traced.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct StopTriggerEvaluation {
+    pub outcome: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<StopTriggerEvaluation>,
+}
+
 #[async_trait]
 pub trait StopTrigger: Send + Sync {
     async fn is_true(
@@ -45,6 +60,17 @@ pub trait StopTrigger: Send + Sync {
         handler_status: &ReactionHandlerStatus,
         stats: &ReactionObserverMetrics,
     ) -> anyhow::Result<bool>;
+
+    async fn evaluate(
+        &self,
+        handler_status: &ReactionHandlerStatus,
+        stats: &ReactionObserverMetrics,
+    ) -> anyhow::Result<StopTriggerEvaluation> {
+        Ok(StopTriggerEvaluation {
+            outcome: self.is_true(handler_status, stats).await?,
+            children: Vec::new(),
+        })
+    }
 }
 
 #[async_trait]
@@ -56,6 +82,14 @@ impl StopTrigger for Box<dyn StopTrigger + Send + Sync> {
     ) -> anyhow::Result<bool> {
         (**self).is_true(handler_status, stats).await
     }
+
+    async fn evaluate(
+        &self,
+        handler_status: &ReactionHandlerStatus,
+        stats: &ReactionObserverMetrics,
+    ) -> anyhow::Result<StopTriggerEvaluation> {
+        (**self).evaluate(handler_status, stats).await
+    }
 }
 
 pub async fn create_stop_trigger(
@@ -63,6 +97,8 @@ pub async fn create_stop_trigger(
 ) -> anyhow::Result<Box<dyn StopTrigger + Send + Sync>> {
     match def {
         StopTriggerDefinition::RecordCount(def) => RecordCountStopTrigger::new(def),
+        StopTriggerDefinition::MaxDuration(def) => MaxDurationStopTrigger::new(def),
+        StopTriggerDefinition::ThroughputBelow(def) => ThroughputBelowStopTrigger::new(def),
         StopTriggerDefinition::RecordSequenceNumber(_) => {
             // RecordSequenceNumber is not applicable for reactions
             // Return a trigger that never fires
@@ -18,8 +18,9 @@ mod tests {
     use crate::reactions::reaction_output_handler::ReactionHandlerStatus;
     use crate::reactions::stop_triggers::*;
     use test_data_store::test_repo_storage::models::{
-        RecordCountStopTriggerDefinition, RecordSequenceNumberStopTriggerDefinition,
-        StopTriggerDefinition,
+        MaxDurationStopTriggerDefinition, RecordCountStopTriggerDefinition,
+        RecordSequenceNumberStopTriggerDefinition, StopTriggerDefinition,
+        ThroughputBelowStopTriggerDefinition,
     };
 
     #[tokio::test]
@@ -61,6 +62,106 @@ mod tests {
         assert!(trigger.is_true(&handler_status, &metrics).await.unwrap());
     }
 
+    #[tokio::test]
+    async fn test_max_duration_stop_trigger() {
+        let definition = MaxDurationStopTriggerDefinition { seconds: 60 };
+
+        let trigger = max_duration::MaxDurationStopTrigger::new(&definition).unwrap();
+        let handler_status = ReactionHandlerStatus::Running;
+        let mut metrics = ReactionObserverMetrics::default();
+
+        // Doesn't fire before the observer has started
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        let now_ns = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+
+        metrics.observer_start_time_ns = now_ns - 30_000_000_000;
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        metrics.observer_start_time_ns = now_ns - 60_000_000_000;
+        assert!(trigger.is_true(&handler_status, &metrics).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_throughput_below_stop_trigger() {
+        use crate::utils::ManualClock;
+        use std::sync::Arc;
+
+        let definition = ThroughputBelowStopTriggerDefinition {
+            events_per_sec: 2.0,
+            for_seconds: 5,
+        };
+
+        let clock = Arc::new(ManualClock::new(1_000_000_000_000));
+        let trigger =
+            throughput_below::ThroughputBelowStopTrigger::with_clock(&definition, clock.clone())
+                .unwrap();
+        let handler_status = ReactionHandlerStatus::Running;
+        let mut metrics = ReactionObserverMetrics::default();
+
+        // Doesn't fire before any results have been seen, however long it waits.
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+        clock.advance(60_000_000_000);
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        // A burst of 20 results, one every 100 ms.
+        for _ in 0..20 {
+            metrics.reaction_invocation_count += 1;
+            assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+            clock.advance(100_000_000);
+        }
+
+        // Then silence. The burst is still inside the window for a while.
+        clock.advance(2_000_000_000);
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        // The last 10 results are still in the window, which is exactly 2/s over 5s.
+        clock.advance(2_000_000_000);
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        // Once the window holds fewer than 10 results, throughput is below the threshold.
+        clock.advance(1_000_000_000);
+        assert!(trigger.is_true(&handler_status, &metrics).await.unwrap());
+
+        // More results bring throughput back up to the threshold.
+        for _ in 0..10 {
+            metrics.reaction_invocation_count += 1;
+            trigger.is_true(&handler_status, &metrics).await.unwrap();
+            clock.advance(100_000_000);
+        }
+        assert!(!trigger.is_true(&handler_status, &metrics).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_throughput_below_stop_trigger_invalid() {
+        let definition = ThroughputBelowStopTriggerDefinition {
+            events_per_sec: 0.0,
+            for_seconds: 5,
+        };
+        assert!(throughput_below::ThroughputBelowStopTrigger::new(&definition).is_err());
+
+        let definition = ThroughputBelowStopTriggerDefinition {
+            events_per_sec: 1.0,
+            for_seconds: 0,
+        };
+        assert!(throughput_below::ThroughputBelowStopTrigger::new(&definition).is_err());
+
+        // Queries don't support the trigger.
+        let definition =
+            StopTriggerDefinition::ThroughputBelow(ThroughputBelowStopTriggerDefinition {
+                events_per_sec: 1.0,
+                for_seconds: 5,
+            });
+        assert!(
+            crate::queries::stop_triggers::create_stop_trigger(&definition)
+                .await
+                .is_err()
+        );
+    }
+
     #[tokio::test]
     async fn test_never_stop_trigger() {
         // Test that NeverStopTrigger always returns false
@@ -121,4 +222,79 @@ mod tests {
             .await
             .unwrap());
     }
+
+    #[tokio::test]
+    async fn test_stop_trigger_default_evaluate() {
+        let definition = RecordCountStopTriggerDefinition { record_count: 5 };
+
+        let trigger = RecordCountStopTrigger::new(&definition).unwrap();
+        let handler_status = ReactionHandlerStatus::Running;
+        let mut metrics = ReactionObserverMetrics::default();
+
+        metrics.reaction_invocation_count = 4;
+        let evaluation = trigger.evaluate(&handler_status, &metrics).await.unwrap();
+        assert!(!evaluation.outcome);
+        assert!(evaluation.children.is_empty());
+
+        metrics.reaction_invocation_count = 5;
+        let evaluation = trigger.evaluate(&handler_status, &metrics).await.unwrap();
+        assert!(evaluation.outcome);
+    }
+
+    #[tokio::test]
+    async fn test_stop_trigger_trace_writer() -> anyhow::Result<()> {
+        use crate::reactions::stop_triggers::trace::{
+            StopTriggerTraceInvocation, StopTriggerTraceRecord, StopTriggerTraceWriter,
+        };
+        use test_data_store::{
+            test_run_storage::{TestRunId, TestRunReactionId},
+            TestDataStore,
+        };
+
+        let data_store = TestDataStore::new_temp(None).await?;
+        let test_run_id = TestRunId::new("test-repo", "test-001", "run-001");
+        let reaction_id = TestRunReactionId::new(&test_run_id, "reaction-001");
+        let storage = data_store
+            .get_test_run_reaction_storage(&reaction_id)
+            .await?;
+
+        let definition = StopTriggerDefinition::RecordCount(RecordCountStopTriggerDefinition {
+            record_count: 10,
+        });
+        let invocation = StopTriggerTraceInvocation {
+            invocation_id: Some("inv-1".to_string()),
+            reaction_invocation_count: 1,
+            timestamp_ns: 1000,
+        };
+        let evaluation = StopTriggerEvaluation {
+            outcome: false,
+            children: vec![],
+        };
+
+        let mut writer = StopTriggerTraceWriter::new(&storage).await?;
+        writer
+            .write_record(&StopTriggerTraceRecord {
+                evaluation_time_ns: 2000,
+                trigger_index: 0,
+                trigger: Some(&definition),
+                handler_status: ReactionHandlerStatus::Running,
+                invocation: Some(&invocation),
+                evaluation: Some(&evaluation),
+                error: None,
+            })
+            .await?;
+        writer.close().await?;
+
+        let content = std::fs::read_to_string(storage.path.join("stop_trigger_trace.jsonl"))?;
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let record: serde_json::Value = serde_json::from_str(lines[0])?;
+        assert_eq!(record["trigger"]["kind"], "RecordCount");
+        assert_eq!(record["invocation"]["invocation_id"], "inv-1");
+        assert_eq!(record["evaluation"]["outcome"], false);
+        assert!(record.get("error").is_none());
+
+        Ok(())
+    }
 }
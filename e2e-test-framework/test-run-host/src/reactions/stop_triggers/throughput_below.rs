@@ -0,0 +1,158 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+
+use test_data_store::test_repo_storage::models::ThroughputBelowStopTriggerDefinition;
+
+use crate::{
+    reactions::{
+        reaction_observer::ReactionObserverMetrics, reaction_output_handler::ReactionHandlerStatus,
+    },
+    utils::{system_clock, Clock},
+};
+
+use super::StopTrigger;
+
+#[derive(Debug)]
+pub struct ThroughputBelowStopTriggerSettings {
+    pub events_per_sec: f64,
+    pub window_ns: u64,
+}
+
+impl ThroughputBelowStopTriggerSettings {
+    pub fn new(cfg: &ThroughputBelowStopTriggerDefinition) -> anyhow::Result<Self> {
+        if !cfg.events_per_sec.is_finite() || cfg.events_per_sec <= 0.0 {
+            anyhow::bail!(
+                "ThroughputBelow events_per_sec must be greater than 0, got {}",
+                cfg.events_per_sec
+            );
+        }
+
+        if cfg.for_seconds == 0 {
+            anyhow::bail!("ThroughputBelow for_seconds must be greater than 0");
+        }
+
+        Ok(Self {
+            events_per_sec: cfg.events_per_sec,
+            window_ns: cfg.for_seconds.saturating_mul(1_000_000_000),
+        })
+    }
+
+    // The number of invocations the window must hold for throughput to be at or above the threshold.
+    fn min_window_count(&self) -> f64 {
+        self.events_per_sec * (self.window_ns as f64 / 1_000_000_000.0)
+    }
+}
+
+#[derive(Debug, Default)]
+struct ThroughputBelowStopTriggerState {
+    // Arrival times of the invocations that fall inside the sliding window.
+    arrivals_ns: VecDeque<u64>,
+    first_arrival_ns: Option<u64>,
+    last_invocation_count: u64,
+}
+
+// Fires once the invocation rate over a sliding window of for_seconds has stayed below
+// events_per_sec. The window only starts after the first result arrives, so the trigger can't
+// fire while the reaction is still waiting for its first invocation. The observer checks this
+// trigger on a timer as well as after each invocation, so it fires even once the results stop.
+pub struct ThroughputBelowStopTrigger {
+    settings: ThroughputBelowStopTriggerSettings,
+    clock: Arc<dyn Clock>,
+    state: Mutex<ThroughputBelowStopTriggerState>,
+}
+
+impl ThroughputBelowStopTrigger {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(
+        def: &ThroughputBelowStopTriggerDefinition,
+    ) -> anyhow::Result<Box<dyn StopTrigger + Send + Sync>> {
+        Self::with_clock(def, system_clock())
+    }
+
+    pub fn with_clock(
+        def: &ThroughputBelowStopTriggerDefinition,
+        clock: Arc<dyn Clock>,
+    ) -> anyhow::Result<Box<dyn StopTrigger + Send + Sync>> {
+        log::debug!("Creating ThroughputBelowStopTrigger from {:?}, ", def);
+
+        let settings = ThroughputBelowStopTriggerSettings::new(def)?;
+        log::trace!(
+            "Creating ThroughputBelowStopTrigger with settings {:?}, ",
+            settings
+        );
+
+        Ok(Box::new(Self {
+            settings,
+            clock,
+            state: Mutex::new(ThroughputBelowStopTriggerState::default()),
+        }))
+    }
+
+    fn is_true_at(&self, stats: &ReactionObserverMetrics, now_ns: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        // The observer was reset, so start again from scratch.
+        if stats.reaction_invocation_count < state.last_invocation_count {
+            *state = ThroughputBelowStopTriggerState::default();
+        }
+
+        // Invocations seen since the last evaluation are recorded as arriving now. The observer
+        // evaluates after every invocation, so this is normally exactly one.
+        let new_invocations = stats.reaction_invocation_count - state.last_invocation_count;
+        if new_invocations > 0 {
+            state.first_arrival_ns.get_or_insert(now_ns);
+            for _ in 0..new_invocations {
+                state.arrivals_ns.push_back(now_ns);
+            }
+            state.last_invocation_count = stats.reaction_invocation_count;
+        }
+
+        let window_start_ns = now_ns.saturating_sub(self.settings.window_ns);
+        while state
+            .arrivals_ns
+            .front()
+            .is_some_and(|ns| *ns < window_start_ns)
+        {
+            state.arrivals_ns.pop_front();
+        }
+
+        // No results yet, or not enough time since the first result to fill a window.
+        let Some(first_arrival_ns) = state.first_arrival_ns else {
+            return false;
+        };
+        if now_ns.saturating_sub(first_arrival_ns) < self.settings.window_ns {
+            return false;
+        }
+
+        (state.arrivals_ns.len() as f64) < self.settings.min_window_count()
+    }
+}
+
+#[async_trait]
+impl StopTrigger for ThroughputBelowStopTrigger {
+    async fn is_true(
+        &self,
+        _handler_status: &ReactionHandlerStatus,
+        stats: &ReactionObserverMetrics,
+    ) -> anyhow::Result<bool> {
+        Ok(self.is_true_at(stats, self.clock.now_ns()))
+    }
+}
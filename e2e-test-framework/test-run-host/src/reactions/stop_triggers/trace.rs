@@ -0,0 +1,94 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tokio::{
+    fs::File,
+    io::{AsyncWriteExt, BufWriter},
+};
+
+use test_data_store::{
+    test_repo_storage::models::StopTriggerDefinition, test_run_storage::TestRunReactionStorage,
+};
+
+use crate::reactions::reaction_output_handler::ReactionHandlerStatus;
+
+use super::{StopTriggerError, StopTriggerEvaluation};
+
+const STOP_TRIGGER_TRACE_FILE_NAME: &str = "stop_trigger_trace.jsonl";
+
+// The reaction invocation that caused the stop triggers to be evaluated.
+#[derive(Clone, Debug, Serialize)]
+pub struct StopTriggerTraceInvocation {
+    pub invocation_id: Option<String>,
+    pub reaction_invocation_count: u64,
+    pub timestamp_ns: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StopTriggerTraceRecord<'a> {
+    pub evaluation_time_ns: u64,
+    pub trigger_index: usize,
+    pub trigger: Option<&'a StopTriggerDefinition>,
+    pub handler_status: ReactionHandlerStatus,
+    // None when the triggers were checked on the observer's timer rather than after an invocation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invocation: Option<&'a StopTriggerTraceInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaluation: Option<&'a StopTriggerEvaluation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Writes a record of every stop trigger evaluation made by a ReactionObserver to
+// stop_trigger_trace.jsonl in the reaction's storage folder.
+pub struct StopTriggerTraceWriter {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl StopTriggerTraceWriter {
+    pub async fn new(output_storage: &TestRunReactionStorage) -> anyhow::Result<Self> {
+        let path = output_storage.path.join(STOP_TRIGGER_TRACE_FILE_NAME);
+        log::debug!("Writing stop trigger trace to {:?}", path);
+
+        let file = File::create(&path).await.map_err(StopTriggerError::Io)?;
+
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    pub async fn write_record(
+        &mut self,
+        record: &StopTriggerTraceRecord<'_>,
+    ) -> anyhow::Result<()> {
+        let mut json = serde_json::to_string(record).map_err(StopTriggerError::Serde)?;
+        json.push('\n');
+        self.writer
+            .write_all(json.as_bytes())
+            .await
+            .map_err(StopTriggerError::Io)?;
+        Ok(())
+    }
+
+    pub async fn close(&mut self) -> anyhow::Result<()> {
+        self.writer.flush().await.map_err(StopTriggerError::Io)?;
+        log::debug!("Closed stop trigger trace {:?}", self.path);
+        Ok(())
+    }
+}
@@ -19,7 +19,9 @@ mod tests {
     use std::sync::Arc;
     use tempfile::TempDir;
     use test_data_store::{
-        test_repo_storage::models::{HttpReactionHandlerDefinition, ReactionHandlerDefinition},
+        test_repo_storage::models::{
+            GrpcReactionHandlerDefinition, HttpReactionHandlerDefinition, ReactionHandlerDefinition,
+        },
         test_run_storage::{TestRunId, TestRunReactionId, TestRunReactionStorage},
         TestDataStore,
     };
@@ -53,21 +55,27 @@ mod tests {
             port: Some(8080),
             path: Some("/callback".to_string()),
             correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: None,
         });
 
         // Configure JSONL logger
         let logger_config = OutputLoggerConfig::JsonlFile(JsonlFileOutputLoggerConfig {
+            max_file_bytes: None,
+            max_files: None,
             max_lines_per_file: Some(10000),
         });
 
         // Create reaction observer with logger
         let observer = reaction_observer::ReactionObserver::new(
             reaction_id.clone(),
-            handler_def,
+            vec![handler_def],
             reaction_storage.clone(),
             vec![logger_config],
             vec![], // stop_triggers
             None,
+            None,
         )
         .await?;
 
@@ -114,11 +122,16 @@ mod tests {
             port: Some(8080),
             path: Some("/callback".to_string()),
             correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: None,
         });
 
         // Configure multiple loggers as OutputLoggerConfig
         let output_loggers = vec![
             OutputLoggerConfig::JsonlFile(JsonlFileOutputLoggerConfig {
+                max_file_bytes: None,
+                max_files: None,
                 max_lines_per_file: Some(10000),
             }),
             OutputLoggerConfig::Console(output_loggers::ConsoleOutputLoggerConfig {
@@ -131,14 +144,16 @@ mod tests {
             test_data_store::test_repo_storage::models::TestReactionDefinition {
                 test_reaction_id: "reaction-001".to_string(),
                 output_handler: Some(handler_def.clone()),
+                output_handlers: vec![],
                 stop_triggers: Some(vec![]), // Empty stop triggers for this test
+                expected_results: None,
             };
 
         // Create test run reaction
         let definition = TestRunReactionDefinition {
             id: reaction_id.clone(),
             start_immediately: false,
-            reaction_handler_definition: handler_def,
+            reaction_handler_definitions: vec![handler_def],
             test_reaction_definition: test_reaction_def,
             test_run_overrides: None,
             output_loggers,
@@ -162,6 +177,162 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_reaction_with_multiple_handlers() -> anyhow::Result<()> {
+        let (_data_store, reaction_id, reaction_storage, _temp_dir) = setup_test_env().await?;
+
+        let handler_defs = vec![
+            ReactionHandlerDefinition::Http(HttpReactionHandlerDefinition {
+                host: Some("localhost".to_string()),
+                port: Some(8091),
+                path: Some("/callback".to_string()),
+                correlation_header: None,
+                forward_to: None,
+                backpressure: false,
+                reaction_type_field: None,
+            }),
+            ReactionHandlerDefinition::Grpc(GrpcReactionHandlerDefinition {
+                host: Some("127.0.0.1".to_string()),
+                port: Some(50191),
+                correlation_metadata_key: None,
+                query_ids: vec![],
+                include_initial_state: None,
+                tls: None,
+            }),
+        ];
+
+        let observer = reaction_observer::ReactionObserver::new(
+            reaction_id.clone(),
+            handler_defs,
+            reaction_storage.clone(),
+            vec![],
+            vec![], // stop_triggers
+            None,
+            None,
+        )
+        .await?;
+
+        // Both handlers are started and stopped as one reaction.
+        let result = observer.start().await?;
+        assert_eq!(result.state.settings.definitions.len(), 2);
+        assert_eq!(
+            result.state.status,
+            reaction_observer::ReactionObserverStatus::Running
+        );
+        assert_eq!(result.state.handler_status, ReactionHandlerStatus::Running);
+
+        let result = observer.stop().await?;
+        assert_eq!(
+            result.state.status,
+            reaction_observer::ReactionObserverStatus::Stopped
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reaction_with_http_and_grpc_output_handler_list() -> anyhow::Result<()> {
+        use crate::grpc_converters::drasi::v1::{
+            reaction_service_client::ReactionServiceClient, ProcessResultsRequest, QueryResult,
+        };
+
+        let (_data_store, reaction_id, reaction_storage, _temp_dir) = setup_test_env().await?;
+        let http_port = portpicker::pick_unused_port().expect("No free port");
+        let grpc_port = portpicker::pick_unused_port().expect("No free port");
+
+        // One reaction that fans out over both HTTP and gRPC, with its handlers in a list.
+        let test_reaction_def: test_data_store::test_repo_storage::models::TestReactionDefinition =
+            serde_json::from_value(serde_json::json!({
+                "test_reaction_id": "reaction-001",
+                "output_handler": [
+                    { "kind": "Http", "host": "127.0.0.1", "port": http_port, "path": "/callback" },
+                    { "kind": "Grpc", "host": "127.0.0.1", "port": grpc_port, "query_ids": [] }
+                ]
+            }))?;
+
+        let definition = TestRunReactionDefinition {
+            id: reaction_id.clone(),
+            start_immediately: false,
+            reaction_handler_definitions: test_reaction_def.handler_definitions(),
+            test_reaction_definition: test_reaction_def,
+            test_run_overrides: None,
+            output_loggers: vec![OutputLoggerConfig::JsonlFile(JsonlFileOutputLoggerConfig {
+                max_file_bytes: None,
+                max_files: None,
+                max_lines_per_file: None,
+            })],
+        };
+
+        let reaction = TestRunReaction::new(definition, reaction_storage.clone()).await?;
+        reaction.start_reaction_observer().await?;
+
+        let response = reqwest::Client::new()
+            .post(format!("http://127.0.0.1:{}/callback", http_port))
+            .json(&serde_json::json!({ "type": "added", "data": { "id": 1 } }))
+            .send()
+            .await?;
+        assert!(response.status().is_success());
+
+        let mut client =
+            ReactionServiceClient::connect(format!("http://127.0.0.1:{}", grpc_port)).await?;
+        client
+            .process_results(ProcessResultsRequest {
+                results: Some(QueryResult {
+                    query_id: "query-001".to_string(),
+                    results: vec![],
+                    timestamp: None,
+                }),
+                metadata: Default::default(),
+            })
+            .await?;
+
+        let mut state = reaction.get_state().await?;
+        for _ in 0..50 {
+            if state
+                .reaction_observer
+                .result_summary
+                .reaction_invocation_count
+                >= 2
+            {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            state = reaction.get_state().await?;
+        }
+        assert_eq!(
+            state
+                .reaction_observer
+                .result_summary
+                .reaction_invocation_count,
+            2
+        );
+
+        // The handler metrics are keyed by handler type.
+        let handler_metrics = state.reaction_observer.handler_metrics.unwrap();
+        assert!(handler_metrics.get("Grpc").is_some());
+
+        reaction.stop_reaction_observer().await?;
+
+        // Both invocations went to the shared logger, each with the type of its handler.
+        let mut reaction_types = Vec::new();
+        let log_dir = reaction_storage.reaction_output_path.join("jsonl_file");
+        for entry in std::fs::read_dir(log_dir)? {
+            for line in std::fs::read_to_string(entry?.path())?.lines() {
+                let record: serde_json::Value = serde_json::from_str(line)?;
+                reaction_types.push(
+                    record["payload"]["reaction_type"]
+                        .as_str()
+                        .unwrap()
+                        .to_string(),
+                );
+            }
+        }
+        reaction_types.sort();
+        assert_eq!(reaction_types, vec!["Grpc", "Http"]);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_reaction_logger_output_paths() -> anyhow::Result<()> {
         let (_data_store, reaction_id, reaction_storage, _temp_dir) = setup_test_env().await?;
@@ -171,20 +342,26 @@ mod tests {
             port: Some(8080),
             path: Some("/callback".to_string()),
             correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: None,
         });
 
         // Configure logger
         let logger_config = OutputLoggerConfig::JsonlFile(JsonlFileOutputLoggerConfig {
+            max_file_bytes: None,
+            max_files: None,
             max_lines_per_file: Some(10000),
         });
 
         let observer = reaction_observer::ReactionObserver::new(
             reaction_id.clone(),
-            handler_def,
+            vec![handler_def],
             reaction_storage.clone(),
             vec![logger_config],
             vec![], // stop_triggers
             None,
+            None,
         )
         .await?;
 
@@ -209,6 +386,9 @@ mod tests {
             port: Some(8080),
             path: Some("/callback".to_string()),
             correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: None,
         });
 
         // Create test reaction definition
@@ -216,13 +396,15 @@ mod tests {
             test_data_store::test_repo_storage::models::TestReactionDefinition {
                 test_reaction_id: "reaction-001".to_string(),
                 output_handler: Some(handler_def.clone()),
+                output_handlers: vec![],
                 stop_triggers: Some(vec![]), // Empty stop triggers for this test
+                expected_results: None,
             };
 
         let definition = TestRunReactionDefinition {
             id: reaction_id.clone(),
             start_immediately: true, // Should start immediately
-            reaction_handler_definition: handler_def,
+            reaction_handler_definitions: vec![handler_def],
             test_reaction_definition: test_reaction_def,
             test_run_overrides: None,
             output_loggers: vec![],
@@ -254,4 +436,65 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_reaction_observer_max_duration_stop_trigger() -> anyhow::Result<()> {
+        use test_data_store::test_repo_storage::models::{
+            MaxDurationStopTriggerDefinition, RecordCountStopTriggerDefinition,
+            StopTriggerDefinition,
+        };
+
+        let (_data_store, reaction_id, reaction_storage, _temp_dir) = setup_test_env().await?;
+
+        let handler_def = ReactionHandlerDefinition::Http(HttpReactionHandlerDefinition {
+            host: Some("localhost".to_string()),
+            port: portpicker::pick_unused_port(),
+            path: Some("/callback".to_string()),
+            correlation_header: None,
+            forward_to: None,
+            backpressure: false,
+            reaction_type_field: None,
+        });
+
+        // The RecordCount trigger never fires because no invocations arrive.
+        let stop_triggers = vec![
+            StopTriggerDefinition::RecordCount(RecordCountStopTriggerDefinition {
+                record_count: 1000,
+            }),
+            StopTriggerDefinition::MaxDuration(MaxDurationStopTriggerDefinition { seconds: 1 }),
+        ];
+
+        let observer = reaction_observer::ReactionObserver::new(
+            reaction_id,
+            vec![handler_def],
+            reaction_storage,
+            vec![],
+            stop_triggers,
+            None,
+            None,
+        )
+        .await?;
+
+        let started = std::time::Instant::now();
+        observer.start().await?;
+
+        let mut status = reaction_observer::ReactionObserverStatus::Running;
+        while status == reaction_observer::ReactionObserverStatus::Running
+            && started.elapsed() < std::time::Duration::from_secs(5)
+        {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            status = observer.get_state().await?.state.status;
+        }
+
+        let elapsed = started.elapsed();
+        assert_eq!(status, reaction_observer::ReactionObserverStatus::Stopped);
+        assert!(elapsed >= std::time::Duration::from_secs(1));
+        assert!(
+            elapsed < std::time::Duration::from_millis(1500),
+            "Observer stopped after {:?}",
+            elapsed
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,226 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use test_data_store::{
+    test_repo_storage::{
+        models::{FileBootstrapDataGeneratorDefinition, TimeMode},
+        TestSourceStorage,
+    },
+    test_run_storage::TestRunSourceId,
+};
+
+use super::{BootstrapData, BootstrapDataGenerator};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct FileBootstrapDataGenerator {
+    pub file_path: PathBuf,
+    pub test_run_source_id: TestRunSourceId,
+    pub time_mode: TimeMode,
+}
+
+impl FileBootstrapDataGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: FileBootstrapDataGeneratorDefinition,
+        input_storage: TestSourceStorage,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            file_path: input_storage.path.join(&definition.file),
+            test_run_source_id,
+            time_mode: definition.common.time_mode.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl BootstrapDataGenerator for FileBootstrapDataGenerator {
+    async fn get_data(
+        &self,
+        node_labels: &HashSet<String>,
+        rel_labels: &HashSet<String>,
+    ) -> anyhow::Result<BootstrapData> {
+        log::debug!(
+            "Node labels: [{:?}], Rel labels: [{:?}]",
+            node_labels,
+            rel_labels
+        );
+
+        let content = tokio::fs::read_to_string(&self.file_path)
+            .await
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Error reading bootstrap data file {:?}: {}",
+                    self.file_path,
+                    e
+                )
+            })?;
+        let data: BootstrapData = serde_json::from_str(&content).map_err(|e| {
+            anyhow::anyhow!(
+                "Error parsing bootstrap data file {:?}: {}",
+                self.file_path,
+                e
+            )
+        })?;
+
+        Ok(BootstrapData {
+            nodes: filter_by_label(data.nodes, node_labels),
+            rels: filter_by_label(data.rels, rel_labels),
+        })
+    }
+}
+
+// An empty label set means the caller wants everything.
+fn filter_by_label<T>(
+    records: HashMap<String, Vec<T>>,
+    labels: &HashSet<String>,
+) -> HashMap<String, Vec<T>> {
+    if labels.is_empty() {
+        return records;
+    }
+
+    records
+        .into_iter()
+        .filter(|(label, _)| labels.contains(label))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_data_store::{
+        test_repo_storage::models::{BootstrapDataGeneratorDefinition, TestSourceDefinition},
+        test_run_storage::TestRunId,
+    };
+
+    use super::*;
+
+    const BOOTSTRAP_DATA: &str = r#"
+    {
+        "nodes": {
+            "Room": [
+                { "id": "room_01", "labels": ["Room"], "properties": { "temp": 20 } },
+                { "id": "room_02", "labels": ["Room"], "properties": { "temp": 22 } }
+            ],
+            "Floor": [
+                { "id": "floor_01", "labels": ["Floor"] }
+            ]
+        },
+        "rels": {
+            "PART_OF": [
+                {
+                    "id": "rel_01",
+                    "labels": ["PART_OF"],
+                    "start_id": "room_01",
+                    "start_label": "Room",
+                    "end_id": "floor_01",
+                    "end_label": "Floor"
+                }
+            ]
+        }
+    }
+    "#;
+
+    async fn create_generator(dir: &std::path::Path) -> FileBootstrapDataGenerator {
+        tokio::fs::create_dir_all(dir.join("bootstrap"))
+            .await
+            .unwrap();
+        tokio::fs::write(dir.join("bootstrap/data.json"), BOOTSTRAP_DATA)
+            .await
+            .unwrap();
+
+        let test_source_definition: TestSourceDefinition = serde_json::from_str(
+            r#"{
+                "kind": "Script",
+                "test_source_id": "source1",
+                "bootstrap_data_generator": { "kind": "File", "file": "bootstrap/data.json" }
+            }"#,
+        )
+        .unwrap();
+        let definition = match &test_source_definition {
+            TestSourceDefinition::Script(def) => match &def.bootstrap_data_generator {
+                Some(BootstrapDataGeneratorDefinition::File(def)) => def.clone(),
+                _ => panic!("Expected FileBootstrapDataGeneratorDefinition"),
+            },
+            _ => panic!("Expected ScriptTestSourceDefinition"),
+        };
+
+        let input_storage = TestSourceStorage {
+            id: "source1".to_string(),
+            path: dir.to_path_buf(),
+            repo_id: "repo".to_string(),
+            test_id: "test".to_string(),
+            test_source_definition,
+        };
+        let test_run_id = TestRunId::new("repo", "test", "run");
+
+        FileBootstrapDataGenerator::new(
+            TestRunSourceId::new(&test_run_id, "source1"),
+            definition,
+            input_storage,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn labels(labels: &[&str]) -> HashSet<String> {
+        labels.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_get_data_filters_by_label() {
+        let dir = tempfile::tempdir().unwrap();
+        let generator = create_generator(dir.path()).await;
+
+        let data = generator
+            .get_data(&labels(&["Room", "Building"]), &labels(&["CONTAINS"]))
+            .await
+            .unwrap();
+        assert_eq!(data.nodes.len(), 1);
+        assert_eq!(data.nodes["Room"].len(), 2);
+        assert_eq!(data.nodes["Room"][1].properties["temp"], 22);
+        assert!(data.rels.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_data_with_empty_labels_returns_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let generator = create_generator(dir.path()).await;
+
+        let data = generator
+            .get_data(&HashSet::new(), &HashSet::new())
+            .await
+            .unwrap();
+        assert_eq!(data.nodes.len(), 2);
+        assert_eq!(data.nodes["Floor"][0].id, "floor_01");
+        assert_eq!(data.rels["PART_OF"][0].end_id, "floor_01");
+    }
+
+    #[tokio::test]
+    async fn test_get_data_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut generator = create_generator(dir.path()).await;
+        generator.file_path = dir.path().join("missing.json");
+
+        assert!(generator
+            .get_data(&HashSet::new(), &HashSet::new())
+            .await
+            .is_err());
+    }
+}
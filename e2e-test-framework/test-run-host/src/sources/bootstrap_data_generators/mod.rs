@@ -16,6 +16,7 @@ use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 
+use bootstrap_file_reader::FileBootstrapDataGenerator;
 use bootstrap_script_player::ScriptBootstrapDataGenerator;
 use serde::{Deserialize, Serialize};
 use test_data_store::{
@@ -24,6 +25,7 @@ use test_data_store::{
     test_run_storage::{TestRunSourceId, TestRunSourceStorage},
 };
 
+mod bootstrap_file_reader;
 mod bootstrap_script_player;
 
 #[derive(Debug, thiserror::Error)]
@@ -83,6 +85,10 @@ pub async fn create_bootstrap_data_generator(
 ) -> anyhow::Result<Option<Box<dyn BootstrapDataGenerator + Send + Sync>>> {
     match definition {
         None => Ok(None),
+        Some(BootstrapDataGeneratorDefinition::File(definition)) => Ok(Some(Box::new(
+            FileBootstrapDataGenerator::new(id, definition, input_storage).await?,
+        )
+            as Box<dyn BootstrapDataGenerator + Send + Sync>)),
         Some(BootstrapDataGeneratorDefinition::Script(definition)) => Ok(Some(Box::new(
             ScriptBootstrapDataGenerator::new(id, definition, input_storage, output_storage)
                 .await?,
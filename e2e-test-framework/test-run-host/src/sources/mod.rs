@@ -12,9 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{collections::HashSet, fmt, str::FromStr};
+use std::{
+    collections::HashSet,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use serde::{
     de::{self, Deserializer},
     Deserialize, Serialize,
@@ -23,7 +32,10 @@ use serde::{
 use bootstrap_data_generators::BootstrapData;
 use model_test_run_source::ModelTestRunSource;
 use script_test_run_source::ScriptTestRunSource;
-use source_change_generators::{SourceChangeGeneratorCommandResponse, SourceChangeGeneratorState};
+use source_change_generators::{
+    SourceChangeEventSummary, SourceChangeGenerator, SourceChangeGeneratorCommandResponse,
+    SourceChangeGeneratorDescription, SourceChangeGeneratorError, SourceChangeGeneratorState,
+};
 use test_data_store::{
     test_repo_storage::{
         models::{
@@ -36,6 +48,7 @@ use test_data_store::{
         TestRunSourceStorage,
     },
 };
+use tokio::{sync::broadcast, task::JoinHandle};
 
 pub mod bootstrap_data_generators;
 pub mod model_data_generators;
@@ -43,6 +56,7 @@ pub mod model_test_run_source;
 pub mod script_test_run_source;
 pub mod source_change_dispatchers;
 pub mod source_change_generators;
+pub mod stall_watcher;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum SourceStartMode {
@@ -92,7 +106,7 @@ impl<'de> Deserialize<'de> for SourceStartMode {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TestRunSourceOverrides {
     pub bootstrap_data_generator: Option<TestRunBootstrapDataGeneratorOverrides>,
     pub model_data_generator: Option<TestRunModelDataGeneratorOverrides>,
@@ -107,22 +121,41 @@ pub struct TestRunBootstrapDataGeneratorOverrides {
     pub time_mode: Option<TimeMode>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TestRunModelDataGeneratorOverrides {
     pub seed: Option<u64>,
     pub spacing_mode: Option<SpacingMode>,
+    #[serde(default)]
+    pub speed_factor: Option<f64>,
     pub time_mode: Option<TimeMode>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TestRunSourceChangeGeneratorOverrides {
     pub spacing_mode: Option<SpacingMode>,
+    #[serde(default)]
+    pub speed_factor: Option<f64>,
     pub time_mode: Option<TimeMode>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TestRunSourceConfig {
+    #[serde(default)]
+    pub auto_pause_after_ms: Option<u64>,
+    // Pause the source at this wall-clock time if it is running, e.g. to hand over to another
+    // source at an agreed time. If auto_pause_after_ms is set too, whichever comes first wins.
+    #[serde(default)]
+    pub auto_pause_at: Option<DateTime<Utc>>,
+    // Stop a stalled source change generator, which fails the TestRun when it is stopped.
+    #[serde(default)]
+    pub fail_on_stall: bool,
+    // Flag the source as stalled if its source change generator is Running but produces no
+    // events for this long.
+    #[serde(default)]
+    pub stall_timeout_ms: Option<u64>,
     pub start_mode: Option<SourceStartMode>,
+    #[serde(default)]
+    pub start_jitter_ms: Option<u64>,
     pub test_source_id: String,
     pub test_run_overrides: Option<TestRunSourceOverrides>,
     // Legacy fields for backward compatibility - will be set by TestRun
@@ -134,6 +167,35 @@ pub struct TestRunSourceConfig {
     pub test_run_id: Option<String>,
 }
 
+impl TestRunSourceConfig {
+    // Creates a config for the TestSource with the same defaults used when deserializing.
+    pub fn new(test_source_id: &str) -> Self {
+        Self {
+            auto_pause_after_ms: None,
+            auto_pause_at: None,
+            fail_on_stall: false,
+            stall_timeout_ms: None,
+            start_mode: None,
+            start_jitter_ms: None,
+            test_source_id: test_source_id.to_string(),
+            test_run_overrides: None,
+            test_id: None,
+            test_repo_id: None,
+            test_run_id: None,
+        }
+    }
+
+    // Overrides the model data generator's seed, so the source generates the same changes as
+    // a source that was created with the given seed.
+    pub fn pin_model_data_generator_seed(&mut self, seed: u64) {
+        self.test_run_overrides
+            .get_or_insert_with(Default::default)
+            .model_data_generator
+            .get_or_insert_with(Default::default)
+            .seed = Some(seed);
+    }
+}
+
 impl TryFrom<&TestRunSourceConfig> for TestRunId {
     type Error = ParseTestRunIdError;
 
@@ -178,11 +240,95 @@ impl fmt::Display for TestRunSourceConfig {
 pub struct TestRunSourceState {
     pub id: TestRunSourceId,
     pub source_change_generator: SourceChangeGeneratorState,
+    pub stalled: bool,
+    pub fail_on_stall: bool,
     pub start_mode: SourceStartMode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_jitter_ms: Option<u64>,
+}
+
+// Returns how long to delay the start of a TestRunSource given its start_jitter_ms.
+// The delay is drawn from [0, max_ms] using an RNG seeded from the source ID, so a
+// given source always gets the same delay while different sources are spread out.
+// The seed is an FNV-1a hash of the ID, which unlike DefaultHasher is stable across
+// Rust releases.
+pub fn start_jitter_delay(id: &TestRunSourceId, max_ms: u64) -> Duration {
+    let seed = id
+        .to_string()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        });
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    Duration::from_millis(rng.random_range(0..=max_ms))
+}
+
+// How long after a start the source should be auto-paused: auto_pause_after_ms, or until
+// auto_pause_at, whichever is sooner. Once auto_pause_at has passed it no longer applies, so a
+// source that was auto-paused at that time can be started again.
+pub fn auto_pause_delay(
+    auto_pause_after_ms: Option<u64>,
+    auto_pause_at: Option<DateTime<Utc>>,
+) -> Option<Duration> {
+    let after = auto_pause_after_ms.map(Duration::from_millis);
+    let until_at = auto_pause_at.and_then(|at| (at - Utc::now()).to_std().ok());
+
+    match (after, until_at) {
+        (Some(after), Some(until_at)) => Some(after.min(until_at)),
+        (after, until_at) => after.or(until_at),
+    }
+}
+
+// Pauses a SourceChangeGenerator a fixed time after it is started, see auto_pause_delay.
+// The timer is re-armed on every start and must be cancelled when the generator is
+// manually paused, stopped or reset so that a stale timer doesn't pause a later run.
+#[derive(Debug, Default)]
+pub struct AutoPauseTimer {
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AutoPauseTimer {
+    pub fn arm<G>(&self, id: TestRunSourceId, after: Duration, generator: Arc<G>)
+    where
+        G: SourceChangeGenerator + ?Sized + 'static,
+    {
+        log::debug!(
+            "Arming auto-pause timer for TestRunSource {} to fire in {:?}",
+            id,
+            after
+        );
+
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(after).await;
+
+            log::info!("Auto-pausing TestRunSource {} after {:?}", id, after);
+            if let Err(e) = generator.pause().await {
+                log::warn!("Auto-pause of TestRunSource {} failed: {:?}", id, e);
+            }
+        });
+
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    pub fn cancel(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for AutoPauseTimer {
+    fn drop(&mut self) {
+        self.cancel();
+    }
 }
 
 #[async_trait]
 pub trait TestRunSource: Send + Sync + std::fmt::Debug {
+    fn describe_source_change_generator(&self) -> anyhow::Result<SourceChangeGeneratorDescription>;
     async fn get_bootstrap_data(
         &self,
         node_labels: &HashSet<String>,
@@ -196,6 +342,7 @@ pub trait TestRunSource: Send + Sync + std::fmt::Debug {
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
     async fn reset_source_change_generator(
         &self,
+        seed: Option<u64>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
     async fn skip_source_change_generator(
         &self,
@@ -210,9 +357,25 @@ pub trait TestRunSource: Send + Sync + std::fmt::Debug {
         steps: u64,
         spacing_mode: Option<SpacingMode>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    async fn step_to_source_change_generator(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    async fn skip_to_end_source_change_generator(
+        &self,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
     async fn stop_source_change_generator(
         &self,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    fn subscribe_source_change_events(
+        &self,
+    ) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        Err(SourceChangeGeneratorError::EventSubscriptionsNotSupported(
+            "this TestRunSource".to_string(),
+        )
+        .into())
+    }
 
     /// Sets the TestRunHost for dispatchers that need it (optional)
     fn set_test_run_host(&self, _test_run_host: std::sync::Arc<crate::TestRunHost>) {
@@ -222,6 +385,10 @@ pub trait TestRunSource: Send + Sync + std::fmt::Debug {
 
 #[async_trait]
 impl TestRunSource for Box<dyn TestRunSource + Send + Sync> {
+    fn describe_source_change_generator(&self) -> anyhow::Result<SourceChangeGeneratorDescription> {
+        (**self).describe_source_change_generator()
+    }
+
     async fn get_bootstrap_data(
         &self,
         node_labels: &HashSet<String>,
@@ -248,8 +415,9 @@ impl TestRunSource for Box<dyn TestRunSource + Send + Sync> {
 
     async fn reset_source_change_generator(
         &self,
+        seed: Option<u64>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
-        (**self).reset_source_change_generator().await
+        (**self).reset_source_change_generator(seed).await
     }
 
     async fn skip_source_change_generator(
@@ -278,12 +446,34 @@ impl TestRunSource for Box<dyn TestRunSource + Send + Sync> {
             .await
     }
 
+    async fn step_to_source_change_generator(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self)
+            .step_to_source_change_generator(target_seq, spacing_mode)
+            .await
+    }
+
+    async fn skip_to_end_source_change_generator(
+        &self,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).skip_to_end_source_change_generator().await
+    }
+
     async fn stop_source_change_generator(
         &self,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         (**self).stop_source_change_generator().await
     }
 
+    fn subscribe_source_change_events(
+        &self,
+    ) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        (**self).subscribe_source_change_events()
+    }
+
     fn set_test_run_host(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         (**self).set_test_run_host(test_run_host)
     }
@@ -304,3 +494,36 @@ pub async fn create_test_run_source(
         ) as Box<dyn TestRunSource + Send + Sync>),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Duration};
+
+    use test_data_store::test_run_storage::{TestRunId, TestRunSourceId};
+
+    use super::start_jitter_delay;
+
+    #[test]
+    fn test_start_jitter_delay() {
+        let test_run_id = TestRunId::new("test-repo", "test-001", "run-001");
+
+        let delays: HashSet<Duration> = (0..20)
+            .map(|i| {
+                let id = TestRunSourceId::new(&test_run_id, &format!("source-{}", i));
+                let delay = start_jitter_delay(&id, 1000);
+
+                // The same source always gets the same delay, within [0, max_ms].
+                assert_eq!(delay, start_jitter_delay(&id, 1000));
+                assert!(delay <= Duration::from_millis(1000));
+                delay
+            })
+            .collect();
+
+        // Different sources are spread across the range.
+        assert!(delays.len() > 1);
+
+        // No jitter means no delay.
+        let id = TestRunSourceId::new(&test_run_id, "source-0");
+        assert_eq!(start_jitter_delay(&id, 0), Duration::ZERO);
+    }
+}
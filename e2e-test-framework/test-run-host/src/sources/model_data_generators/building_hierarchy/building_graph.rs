@@ -25,7 +25,7 @@ use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Normal};
 use serde::Serialize;
 use serde_json::{Map, Value};
-use test_data_store::test_repo_storage::models::SensorDefinition;
+use test_data_store::test_repo_storage::models::{SensorDefinition, SensorSeasonalityDefinition};
 
 use super::BuildingHierarchyDataGeneratorSettings;
 
@@ -582,6 +582,8 @@ pub enum SensorValue {
 
 #[derive(Debug, Clone)]
 pub struct FloatNormalDistSensorValue {
+    // The random walk, before any seasonality is added.
+    pub base_value: f64,
     pub effective_from: u64,
     pub id: Location,
     pub momentum: i32,
@@ -610,6 +612,25 @@ pub struct FloatNormalDistSensorValueGenerator {
     pub value_change_dist: Normal<f64>,
     pub value_init_dist: Normal<f64>,
     pub value_range: (f64, f64),
+    pub seasonality: Option<SensorSeasonalityDefinition>,
+}
+
+impl FloatNormalDistSensorValueGenerator {
+    // The sensor's value is its random walk plus the seasonal component at the elapsed
+    // virtual time, kept within the value range.
+    fn sensor_value(&self, base_value: f64, elapsed_ns: u64) -> f64 {
+        let seasonal = match &self.seasonality {
+            Some(seasonality) => {
+                let cycle =
+                    (elapsed_ns % seasonality.period_ns) as f64 / seasonality.period_ns as f64;
+                seasonality.amplitude
+                    * (2.0 * std::f64::consts::PI * cycle + seasonality.phase).sin()
+            }
+            None => 0.0,
+        };
+
+        (base_value + seasonal).clamp(self.value_range.0, self.value_range.1)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -629,6 +650,8 @@ pub struct GraphChangeGenerator {
     pub rng: ChaCha8Rng,
     pub room_count_dist: Normal<f64>,
     pub room_sensor_value_generators: Vec<SensorValueGenerator>,
+    // Virtual time of the first sensor update, from which seasonality is computed.
+    pub seasonality_origin_ns: Option<u64>,
 }
 
 impl GraphChangeGenerator {
@@ -650,6 +673,7 @@ impl GraphChangeGenerator {
             room_count_dist: Normal::new(settings.room_count.0 as f64, settings.room_count.1)
                 .unwrap(),
             room_sensor_value_generators: Vec::new(),
+            seasonality_origin_ns: None,
         };
 
         for sensor in &settings.room_sensors {
@@ -669,6 +693,7 @@ impl GraphChangeGenerator {
                         value_change_dist: Normal::new(change_mean, change_std_dev).unwrap(),
                         value_init_dist: Normal::new(init_mean, init_std_dev).unwrap(),
                         value_range: (range_min, range_max),
+                        seasonality: def.seasonality.clone(),
                     };
                     change_generator
                         .room_sensor_value_generators
@@ -726,25 +751,34 @@ impl GraphChangeGenerator {
         }
     }
 
+    fn seasonality_elapsed_ns(&self, effective_from: u64) -> u64 {
+        self.seasonality_origin_ns
+            .map(|origin| effective_from.saturating_sub(origin))
+            .unwrap_or(0)
+    }
+
     pub fn initialize_sensor_values(
         &mut self,
         room_id: &Location,
         effective_from: u64,
     ) -> anyhow::Result<Vec<SensorValue>> {
         let mut sensor_values = Vec::new();
+        let elapsed_ns = self.seasonality_elapsed_ns(effective_from);
 
         for sensor in &self.room_sensor_value_generators {
             match sensor {
                 SensorValueGenerator::NormalFloat(svg) => {
+                    let base_value = svg
+                        .value_init_dist
+                        .sample(&mut self.rng)
+                        .clamp(svg.value_range.0, svg.value_range.1);
                     let mut val = FloatNormalDistSensorValue {
+                        base_value,
                         effective_from,
                         id: room_id.with_sensor(svg.id.clone())?,
                         momentum: (svg.momentum_init_dist.sample(&mut self.rng).round() as i32)
                             .max(1),
-                        value: svg
-                            .value_init_dist
-                            .sample(&mut self.rng)
-                            .clamp(svg.value_range.0, svg.value_range.1),
+                        value: svg.sensor_value(base_value, elapsed_ns),
                     };
 
                     if self.rng.random_bool(svg.momentum_reverse_prob) {
@@ -776,6 +810,9 @@ impl GraphChangeGenerator {
     }
 
     pub fn update_sensor_values(&mut self, effective_from: u64, sensor_values: &mut [SensorValue]) {
+        self.seasonality_origin_ns.get_or_insert(effective_from);
+        let elapsed_ns = self.seasonality_elapsed_ns(effective_from);
+
         let sensor_to_update =
             self.get_usize_in_range(0, self.room_sensor_value_generators.len(), false);
 
@@ -788,7 +825,7 @@ impl GraphChangeGenerator {
 
                     match sensor_value.momentum.cmp(&0) {
                         std::cmp::Ordering::Greater => {
-                            sensor_value.value = (sensor_value.value + value_change)
+                            sensor_value.base_value = (sensor_value.base_value + value_change)
                                 .clamp(svg.value_range.0, svg.value_range.1);
 
                             if sensor_value.momentum > 1 {
@@ -803,7 +840,7 @@ impl GraphChangeGenerator {
                             }
                         }
                         std::cmp::Ordering::Less => {
-                            sensor_value.value = (sensor_value.value - value_change)
+                            sensor_value.base_value = (sensor_value.base_value - value_change)
                                 .clamp(svg.value_range.0, svg.value_range.1);
 
                             if sensor_value.momentum < -1 {
@@ -820,6 +857,7 @@ impl GraphChangeGenerator {
                         std::cmp::Ordering::Equal => {}
                     }
 
+                    sensor_value.value = svg.sensor_value(sensor_value.base_value, elapsed_ns);
                     sensor_value.effective_from = effective_from;
                 }
             }
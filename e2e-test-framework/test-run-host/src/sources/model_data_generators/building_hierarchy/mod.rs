@@ -13,30 +13,31 @@
 // limitations under the License.
 
 use std::{
-    collections::HashSet,
+    collections::{HashSet, VecDeque},
     fmt::{self, Debug, Formatter},
     num::NonZeroU32,
     sync::Arc,
-    time::SystemTime,
+    time::Instant,
 };
 
 use async_trait::async_trait;
 use building_graph::{BuildingGraph, GraphElementType, ModelChange};
 use futures::future::join_all;
 use governor::{
-    clock::{QuantaClock, QuantaInstant},
     middleware::NoOpMiddleware,
+    nanos::Nanos,
     state::{InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use rand_distr::{Distribution, Normal};
+use rand_distr::{Distribution, Exp, Normal, Uniform};
 use serde::Serialize;
 use time::{format_description, OffsetDateTime};
 use tokio::{
     sync::{
-        mpsc::{Receiver, Sender},
+        broadcast,
+        mpsc::{error::TrySendError, Receiver, Sender},
         oneshot, Mutex,
     },
     task::JoinHandle,
@@ -49,8 +50,9 @@ use test_data_store::{
     },
     test_repo_storage::{
         models::{
-            BuildingHierarchyDataGeneratorDefinition, SensorDefinition,
-            SourceChangeDispatcherDefinition, SpacingMode, TimeMode,
+            BuildingHierarchyDataGeneratorDefinition, ChangeIntervalDistribution, SensorDefinition,
+            SourceChangeDispatcherDefinition, SourceChangeEventSchemaDefinition, SpacingMode,
+            TimeMode,
         },
         TestSourceStorage,
     },
@@ -59,17 +61,25 @@ use test_data_store::{
 
 use crate::sources::{
     bootstrap_data_generators::{BootstrapData, BootstrapDataGenerator},
-    source_change_dispatchers::{create_source_change_dispatcher, SourceChangeDispatcher},
+    source_change_dispatchers::{event_field_names::EventFieldNames, SourceChangeDispatcher},
     source_change_generators::{
-        SourceChangeGenerator, SourceChangeGeneratorCommandResponse, SourceChangeGeneratorState,
-        SourceChangeGeneratorStatus,
+        create_source_change_dispatchers, dispatcher_kinds, estimate_run_duration,
+        event_schema_validator::{SourceChangeEventValidationStats, SourceChangeEventValidator},
+        log_estimated_run_duration, max_events_per_second, scale_delay_ns, validate_spacing_rate,
+        validate_speed_factor, BurstTracker, MaxEpsLimiter, SourceChangeEventFeed,
+        SourceChangeEventSummary, SourceChangeGenerator, SourceChangeGeneratorCommandResponse,
+        SourceChangeGeneratorDescription, SourceChangeGeneratorState, SourceChangeGeneratorStatus,
     },
 };
 
+use crate::utils::{system_clock, Clock};
+
 use super::ModelDataGenerator;
 
 mod building_graph;
 
+pub const DEFAULT_CHANGE_CHANNEL_CAPACITY: usize = 1000;
+
 #[derive(Debug, thiserror::Error)]
 pub enum BuildingHierarchyDataGeneratorError {
     #[error("BuildingHierarchyDataGenerator is already finished. Reset to start over.")]
@@ -88,6 +98,8 @@ pub enum BuildingHierarchyDataGeneratorError {
     PauseToStep,
     #[error("BuildingHierarchyDataGenerator is currently Running. Pause before trying to Reset.")]
     PauseToReset,
+    #[error("BuildingHierarchyDataGenerator can't StepTo event_seq_num {target_seq}, it is already at {current_seq}.")]
+    StepToTargetNotAhead { target_seq: u64, current_seq: u64 },
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -95,15 +107,28 @@ pub struct BuildingHierarchyDataGeneratorSettings {
     pub building_count: (u32, f64),
     pub floor_count: (u32, f64),
     pub room_count: (u32, f64),
+    pub change_channel_capacity: usize,
     pub change_count: u64,
     pub change_interval: (u64, f64, u64, u64),
+    pub change_interval_distribution: ChangeIntervalDistribution,
+    // Where the generator reads the time from; tests swap in a ManualClock.
+    #[serde(skip)]
+    pub clock: Arc<dyn Clock>,
     pub dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    pub drain_on_stop: bool,
+    pub event_field_names: EventFieldNames,
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
     pub id: TestRunSourceId,
     pub input_storage: TestSourceStorage,
+    pub max_eps: Option<NonZeroU32>,
+    pub max_in_flight_dispatches: Option<usize>,
     pub output_storage: TestRunSourceStorage,
     pub room_sensors: Vec<SensorDefinition>,
     pub seed: u64,
     pub spacing_mode: SpacingMode,
+    pub speed_factor: f64,
+    pub stop_after_events: Option<u64>,
+    pub throughput_window_secs: u64,
     pub time_mode: TimeMode,
     pub send_initial_inserts: bool,
 }
@@ -116,10 +141,47 @@ impl BuildingHierarchyDataGeneratorSettings {
         output_storage: TestRunSourceStorage,
         dispatchers: Vec<SourceChangeDispatcherDefinition>,
     ) -> anyhow::Result<Self> {
-        Ok(BuildingHierarchyDataGeneratorSettings {
+        validate_spacing_rate(
+            &definition.common.spacing_mode,
+            definition.common.max_spacing_rate,
+        )?;
+        validate_speed_factor(definition.common.speed_factor)?;
+        validate_change_interval_distribution(&definition.common.change_interval_distribution)?;
+        if definition.common.stop_after_events == Some(0) {
+            anyhow::bail!("stop_after_events must be greater than 0");
+        }
+        if definition.common.throughput_window_secs == 0 {
+            anyhow::bail!("throughput_window_secs must be greater than 0");
+        }
+        if definition.common.change_channel_capacity == Some(0) {
+            anyhow::bail!("change_channel_capacity must be greater than 0");
+        }
+        if definition.common.max_in_flight_dispatches == Some(0) {
+            anyhow::bail!("max_in_flight_dispatches must be greater than 0");
+        }
+        for sensor in &definition.room_sensors {
+            if let SensorDefinition::NormalFloat(sensor) = sensor {
+                if sensor
+                    .seasonality
+                    .as_ref()
+                    .is_some_and(|s| s.period_ns == 0)
+                {
+                    anyhow::bail!(
+                        "Sensor {} seasonality period_ns must be greater than 0",
+                        sensor.id
+                    );
+                }
+            }
+        }
+
+        let settings = BuildingHierarchyDataGeneratorSettings {
             building_count: definition.building_count.unwrap_or((1, 0.0)),
             floor_count: definition.floor_count.unwrap_or((5, 0.0)),
             room_count: definition.room_count.unwrap_or((10, 0.0)),
+            change_channel_capacity: definition
+                .common
+                .change_channel_capacity
+                .unwrap_or(DEFAULT_CHANGE_CHANNEL_CAPACITY),
             change_count: definition.common.change_count.unwrap_or(100000),
             change_interval: definition.common.change_interval.unwrap_or((
                 1000000000,
@@ -127,21 +189,52 @@ impl BuildingHierarchyDataGeneratorSettings {
                 u64::MIN,
                 u64::MAX,
             )),
+            change_interval_distribution: definition.common.change_interval_distribution,
+            clock: system_clock(),
             dispatchers,
+            drain_on_stop: definition.common.drain_on_stop,
+            event_field_names: EventFieldNames::new(definition.common.event_field_names),
+            event_schema: definition.common.event_schema,
             id: test_run_source_id,
             input_storage,
+            max_eps: definition.common.max_eps,
+            max_in_flight_dispatches: definition.common.max_in_flight_dispatches,
             output_storage,
             room_sensors: definition.room_sensors,
             seed: definition.common.seed.unwrap_or(rand::rng().random()),
             spacing_mode: definition.common.spacing_mode,
+            speed_factor: definition.common.speed_factor,
+            stop_after_events: definition.common.stop_after_events,
+            throughput_window_secs: definition.common.throughput_window_secs,
             time_mode: definition.common.time_mode,
             send_initial_inserts: definition.send_initial_inserts,
-        })
+        };
+
+        log_estimated_run_duration(
+            &settings.id,
+            estimate_run_duration(
+                &settings.spacing_mode,
+                settings.change_count,
+                scale_delay_ns(settings.mean_change_interval_ns(), settings.speed_factor),
+                settings.max_eps,
+            ),
+        );
+
+        Ok(settings)
     }
 
     pub fn get_id(&self) -> TestRunSourceId {
         self.id.clone()
     }
+
+    // The expected time between changes for the change_interval_distribution, before clamping.
+    pub fn mean_change_interval_ns(&self) -> u64 {
+        match &self.change_interval_distribution {
+            ChangeIntervalDistribution::Normal => self.change_interval.0,
+            ChangeIntervalDistribution::Exponential { rate } => (1_000_000_000.0 / rate) as u64,
+            ChangeIntervalDistribution::Uniform { min_ns, max_ns } => min_ns / 2 + max_ns / 2,
+        }
+    }
 }
 
 // Enum of BuildingHierarchyDataGenerator commands sent from Web API handler functions.
@@ -151,13 +244,17 @@ pub enum BuildingHierarchyDataGeneratorCommand {
     GetState,
     // Command to pause the BuildingHierarchyDataGenerator.
     Pause,
-    // Command to reset the BuildingHierarchyDataGenerator.
-    Reset,
+    // Command to reset the BuildingHierarchyDataGenerator, optionally with a new seed.
+    Reset {
+        seed: Option<u64>,
+    },
     // Command to skip the BuildingHierarchyDataGenerator forward a specified number of ChangeScriptRecords.
     Skip {
         skips: u64,
         spacing_mode: Option<SpacingMode>,
     },
+    // Command to skip the BuildingHierarchyDataGenerator forward past all of its remaining changes.
+    SkipToEnd,
     // Command to start the BuildingHierarchyDataGenerator.
     Start,
     // Command to step the BuildingHierarchyDataGenerator forward a specified number of ChangeScriptRecords.
@@ -165,6 +262,11 @@ pub enum BuildingHierarchyDataGeneratorCommand {
         steps: u64,
         spacing_mode: Option<SpacingMode>,
     },
+    // Command to step the BuildingHierarchyDataGenerator forward until its event_seq_num reaches target_seq.
+    StepTo {
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    },
     // Command to stop the BuildingHierarchyDataGenerator.
     Stop,
     // Command to set TestRunHost on dispatchers
@@ -210,6 +312,8 @@ pub struct BuildingHierarchyDataGenerator {
     building_graph: Arc<Mutex<BuildingGraph>>,
     settings: BuildingHierarchyDataGeneratorSettings,
     #[serde(skip_serializing)]
+    event_feed: SourceChangeEventFeed,
+    #[serde(skip_serializing)]
     model_host_tx_channel: Sender<BuildingHierarchyDataGeneratorMessage>,
     #[serde(skip_serializing)]
     _model_host_thread_handle: Arc<Mutex<JoinHandle<anyhow::Result<()>>>>,
@@ -238,16 +342,19 @@ impl BuildingHierarchyDataGenerator {
 
         let building_graph = Arc::new(Mutex::new(BuildingGraph::new(&settings)?));
 
+        let event_feed = SourceChangeEventFeed::default();
         let (model_host_tx_channel, model_host_rx_channel) = tokio::sync::mpsc::channel(500);
         let model_host_thread_handle = tokio::spawn(model_host_thread(
             model_host_rx_channel,
             settings.clone(),
             building_graph.clone(),
+            event_feed.clone(),
         ));
 
         Ok(Self {
             building_graph,
             settings,
+            event_feed,
             model_host_tx_channel,
             _model_host_thread_handle: Arc::new(Mutex::new(model_host_thread_handle)),
         })
@@ -278,10 +385,14 @@ impl BuildingHierarchyDataGenerator {
         match r {
             Ok(_) => {
                 let player_response = response_rx.await?;
+                let stats = &player_response.state.stats;
+                let event_count =
+                    stats.num_source_change_events - stats.num_skipped_source_change_events;
 
                 Ok(SourceChangeGeneratorCommandResponse {
                     result: player_response.result,
                     state: SourceChangeGeneratorState {
+                        event_count,
                         status: player_response.state.status,
                         state: serde_json::to_value(player_response.state).unwrap(),
                     },
@@ -414,6 +525,59 @@ impl BootstrapDataGenerator for BuildingHierarchyDataGenerator {
 
 #[async_trait]
 impl SourceChangeGenerator for BuildingHierarchyDataGenerator {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        let settings = &self.settings;
+
+        // Counts are sampled from normal distributions, so the expected sizes use the means.
+        let buildings = settings.building_count.0 as u64;
+        let floors = buildings * settings.floor_count.0 as u64;
+        let rooms = floors * settings.room_count.0 as u64;
+        let estimated_duration = estimate_run_duration(
+            &settings.spacing_mode,
+            settings.change_count,
+            scale_delay_ns(settings.mean_change_interval_ns(), settings.speed_factor),
+            settings.max_eps,
+        );
+        let max_eps = max_events_per_second(&settings.spacing_mode, settings.max_eps);
+
+        SourceChangeGeneratorDescription::new(
+            "BuildingHierarchy",
+            serde_json::json!({
+                "building_count": settings.building_count,
+                "change_count": settings.change_count,
+                "change_interval": settings.change_interval,
+                "change_interval_distribution": settings.change_interval_distribution,
+                "dispatchers": dispatcher_kinds(&settings.dispatchers),
+                "event_field_names": settings.event_field_names,
+                "event_schema": settings.event_schema.is_some(),
+                "floor_count": settings.floor_count,
+                "max_eps": settings.max_eps,
+                "room_count": settings.room_count,
+                "room_sensors": settings.room_sensors,
+                "seed": settings.seed,
+                "send_initial_inserts": settings.send_initial_inserts,
+                "spacing_mode": settings.spacing_mode,
+                "speed_factor": settings.speed_factor,
+                "stop_after_events": settings.stop_after_events,
+                "throughput_window_secs": settings.throughput_window_secs,
+                "time_mode": settings.time_mode,
+            }),
+            serde_json::json!({
+                "change_interval_ns": {
+                    "min": settings.change_interval.2,
+                    "max": settings.change_interval.3,
+                },
+                "estimated_duration_ms": estimated_duration.map(|d| d.as_millis() as u64),
+                "expected_buildings": buildings,
+                "expected_floors": floors,
+                "expected_rooms": rooms,
+                "expected_change_events": settings.change_count,
+                "max_events_per_second": max_eps,
+                "sensors_per_room": settings.room_sensors.len(),
+            }),
+        )
+    }
+
     async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         self.send_command(BuildingHierarchyDataGeneratorCommand::GetState)
             .await
@@ -424,8 +588,11 @@ impl SourceChangeGenerator for BuildingHierarchyDataGenerator {
             .await
     }
 
-    async fn reset(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
-        self.send_command(BuildingHierarchyDataGeneratorCommand::Reset)
+    async fn reset(
+        &self,
+        seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(BuildingHierarchyDataGeneratorCommand::Reset { seed })
             .await
     }
 
@@ -441,6 +608,11 @@ impl SourceChangeGenerator for BuildingHierarchyDataGenerator {
         .await
     }
 
+    async fn skip_to_end(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(BuildingHierarchyDataGeneratorCommand::SkipToEnd)
+            .await
+    }
+
     async fn start(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         self.send_command(BuildingHierarchyDataGeneratorCommand::Start)
             .await
@@ -458,11 +630,27 @@ impl SourceChangeGenerator for BuildingHierarchyDataGenerator {
         .await
     }
 
+    async fn step_to(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(BuildingHierarchyDataGeneratorCommand::StepTo {
+            target_seq,
+            spacing_mode,
+        })
+        .await
+    }
+
     async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         self.send_command(BuildingHierarchyDataGeneratorCommand::Stop)
             .await
     }
 
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        Ok(self.event_feed.subscribe())
+    }
+
     fn set_test_run_host_on_dispatchers(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         // Send command to thread to set TestRunHost on dispatchers
         log::info!("BuildingHierarchyDataGenerator: Sending SetTestRunHost command to thread");
@@ -485,25 +673,82 @@ impl SourceChangeGenerator for BuildingHierarchyDataGenerator {
     }
 }
 
+// Checks that the parameters of a ChangeIntervalDistribution describe a valid distribution.
+fn validate_change_interval_distribution(
+    distribution: &ChangeIntervalDistribution,
+) -> anyhow::Result<()> {
+    match distribution {
+        ChangeIntervalDistribution::Normal => {}
+        ChangeIntervalDistribution::Exponential { rate } => {
+            if !rate.is_finite() || *rate <= 0.0 {
+                anyhow::bail!(
+                    "Exponential change_interval_distribution rate must be a number greater than 0, got {}",
+                    rate
+                );
+            }
+        }
+        ChangeIntervalDistribution::Uniform { min_ns, max_ns } => {
+            if min_ns > max_ns {
+                anyhow::bail!(
+                    "Uniform change_interval_distribution min_ns ({}) must not be greater than max_ns ({})",
+                    min_ns,
+                    max_ns
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+// The distribution the time between changes is sampled from, in nanoseconds.
+enum IntervalDistribution {
+    Normal(Normal<f64>),
+    // Sampled in seconds, since the rate is in changes per second.
+    Exponential(Exp<f64>),
+    Uniform(Uniform<u64>),
+}
+
 struct ChangeIntervalGenerator {
-    interval_dist: Normal<f64>,
+    interval_dist: IntervalDistribution,
     interval_range: (u64, u64),
     rng: ChaCha8Rng,
 }
 
 impl ChangeIntervalGenerator {
-    fn new(seed: u64, change_interval: (u64, f64, u64, u64)) -> anyhow::Result<Self> {
+    fn new(
+        seed: u64,
+        change_interval: (u64, f64, u64, u64),
+        distribution: &ChangeIntervalDistribution,
+    ) -> anyhow::Result<Self> {
         let (mean, std_dev, range_min, range_max) = change_interval;
 
+        let interval_dist = match distribution {
+            ChangeIntervalDistribution::Normal => {
+                IntervalDistribution::Normal(Normal::new(mean as f64, std_dev).unwrap())
+            }
+            ChangeIntervalDistribution::Exponential { rate } => {
+                IntervalDistribution::Exponential(Exp::new(*rate)?)
+            }
+            ChangeIntervalDistribution::Uniform { min_ns, max_ns } => {
+                IntervalDistribution::Uniform(Uniform::new_inclusive(*min_ns, *max_ns)?)
+            }
+        };
+
         Ok(Self {
-            interval_dist: Normal::new(mean as f64, std_dev).unwrap(),
+            interval_dist,
             interval_range: (range_min, range_max),
             rng: ChaCha8Rng::seed_from_u64(seed),
         })
     }
 
     fn next(&mut self) -> u64 {
-        let mut interval = self.interval_dist.sample(&mut self.rng) as u64;
+        let mut interval = match &self.interval_dist {
+            IntervalDistribution::Normal(dist) => dist.sample(&mut self.rng) as u64,
+            IntervalDistribution::Exponential(dist) => {
+                (dist.sample(&mut self.rng) * 1_000_000_000.0) as u64
+            }
+            IntervalDistribution::Uniform(dist) => dist.sample(&mut self.rng),
+        };
 
         if interval < self.interval_range.0 {
             interval = self.interval_range.0;
@@ -520,14 +765,18 @@ impl ModelDataGenerator for BuildingHierarchyDataGenerator {}
 
 #[derive(Debug, Serialize)]
 pub struct BuildingHierarchyDataGeneratorExternalState {
+    pub current_events_per_sec: f64,
     pub error_messages: Vec<String>,
     pub event_seq_num: u64,
+    pub event_validation: Option<SourceChangeEventValidationStats>,
     pub next_event: Option<SourceChangeEvent>,
     pub previous_event: Option<ProcessedChangeEvent>,
+    pub seed: u64,
     pub skips_remaining: u64,
     pub spacing_mode: SpacingMode,
     pub stats: BuildingHierarchyDataGeneratorStats,
     pub status: SourceChangeGeneratorStatus,
+    pub status_reason: String,
     pub steps_remaining: u64,
     pub test_run_source_id: TestRunSourceId,
     pub time_mode: TimeMode,
@@ -542,14 +791,20 @@ impl From<&mut BuildingHierarchyDataGeneratorInternalState>
 {
     fn from(state: &mut BuildingHierarchyDataGeneratorInternalState) -> Self {
         Self {
+            current_events_per_sec: state
+                .throughput
+                .events_per_sec(state.settings.clock.now_ns()),
             error_messages: state.error_messages.clone(),
             event_seq_num: state.event_seq_num,
+            event_validation: state.event_validator.stats(),
             next_event: state.next_event.clone(),
             previous_event: state.previous_event.clone(),
+            seed: state.seed,
             skips_remaining: state.skips_remaining,
             spacing_mode: state.settings.spacing_mode.clone(),
             stats: state.stats.clone(),
             status: state.status,
+            status_reason: state.status_reason.clone(),
             steps_remaining: state.steps_remaining,
             test_run_source_id: state.settings.id.clone(),
             time_mode: state.settings.time_mode.clone(),
@@ -561,21 +816,155 @@ impl From<&mut BuildingHierarchyDataGeneratorInternalState>
     }
 }
 
+// Lets the rate limiter read the time from the generator's Clock, so it follows a ManualClock
+// in tests.
+#[derive(Clone, Debug)]
+struct RateLimiterClock(Arc<dyn Clock>);
+
+impl governor::clock::Clock for RateLimiterClock {
+    type Instant = Nanos;
+
+    fn now(&self) -> Nanos {
+        Nanos::from(self.0.now_ns())
+    }
+}
+
+type ChangeRateLimiter =
+    RateLimiter<NotKeyed, InMemoryState, RateLimiterClock, NoOpMiddleware<Nanos>>;
+
+// Creates the rate limiter for SpacingMode::Rate. A new limiter allows a full second's worth
+// of changes at once, so that allowance is used up to start at the steady rate.
+fn new_rate_limiter(spacing_mode: &SpacingMode, clock: &Arc<dyn Clock>) -> ChangeRateLimiter {
+    let clock = RateLimiterClock(clock.clone());
+    match spacing_mode {
+        SpacingMode::Rate(rate) => {
+            let rate_limiter = RateLimiter::direct_with_clock(Quota::per_second(*rate), clock);
+            // The quota's burst size is rate, so a new limiter always has the capacity for
+            // this and the result can be ignored.
+            let _ = rate_limiter.check_n(*rate);
+            rate_limiter
+        }
+        // SpacingMode::Burst is unlimited too: changes within a burst go back-to-back, and the
+        // waits between bursts come from the BurstTracker in schedule_next_change_event.
+        _ => unlimited_rate_limiter(clock),
+    }
+}
+
+fn unlimited_rate_limiter(clock: RateLimiterClock) -> ChangeRateLimiter {
+    RateLimiter::direct_with_clock(Quota::per_second(NonZeroU32::new(u32::MAX).unwrap()), clock)
+}
+
+// Rolling count of the changes dispatched over the last window_secs seconds. Changes are
+// counted in one-second buckets, so the memory used doesn't grow with the change rate.
+struct ThroughputWindow {
+    // (seconds since origin, number of changes dispatched in that second)
+    buckets: VecDeque<(u64, u64)>,
+    origin_ns: u64,
+    window_secs: u64,
+}
+
+impl ThroughputWindow {
+    fn new(window_secs: u64, now_ns: u64) -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            origin_ns: now_ns,
+            window_secs,
+        }
+    }
+
+    fn second(&self, now_ns: u64) -> u64 {
+        now_ns.saturating_sub(self.origin_ns) / 1_000_000_000
+    }
+
+    fn record(&mut self, now_ns: u64, count: u64) {
+        let second = self.second(now_ns);
+        match self.buckets.back_mut() {
+            Some((bucket_second, bucket_count)) if *bucket_second == second => {
+                *bucket_count += count
+            }
+            _ => self.buckets.push_back((second, count)),
+        }
+
+        while let Some((bucket_second, _)) = self.buckets.front() {
+            if bucket_second + self.window_secs > second {
+                break;
+            }
+            self.buckets.pop_front();
+        }
+    }
+
+    // Buckets that have aged out are ignored, so the rate decays to zero once changes stop.
+    fn events_per_sec(&self, now_ns: u64) -> f64 {
+        let second = self.second(now_ns);
+        let count: u64 = self
+            .buckets
+            .iter()
+            .filter(|(bucket_second, _)| bucket_second + self.window_secs > second)
+            .map(|(_, bucket_count)| bucket_count)
+            .sum();
+        count as f64 / self.window_secs as f64
+    }
+}
+
+// Runs a dispatcher on its own task when max_in_flight_dispatches is set. Batches are queued
+// for the task, and the dispatcher is handed back once the queue is closed and drained. The
+// task stops at the first error, which is handed back along with the dispatcher.
+struct DispatcherWorker {
+    batch_tx_channel: Sender<Vec<SourceChangeEvent>>,
+    handle: JoinHandle<(Box<dyn SourceChangeDispatcher + Send>, anyhow::Result<()>)>,
+}
+
+impl DispatcherWorker {
+    fn spawn(mut dispatcher: Box<dyn SourceChangeDispatcher + Send>, max_in_flight: usize) -> Self {
+        let (batch_tx_channel, mut batch_rx_channel) =
+            tokio::sync::mpsc::channel::<Vec<SourceChangeEvent>>(max_in_flight);
+
+        let handle = tokio::spawn(async move {
+            while let Some(events) = batch_rx_channel.recv().await {
+                if let Err(e) = dispatcher
+                    .dispatch_source_change_events(events.iter().collect())
+                    .await
+                {
+                    log::error!("Error dispatching SourceChangeEvents: {:?}", e);
+                    return (dispatcher, Err(e));
+                }
+            }
+            (dispatcher, Ok(()))
+        });
+
+        Self {
+            batch_tx_channel,
+            handle,
+        }
+    }
+}
+
 pub struct BuildingHierarchyDataGeneratorInternalState {
     building_graph: Arc<Mutex<BuildingGraph>>,
+    burst_tracker: BurstTracker,
     change_interval_generator: ChangeIntervalGenerator,
     change_tx_channel: Sender<ScheduledChangeEventMessage>,
+    // The dispatchers are moved into the workers while max_in_flight_dispatches is in use.
+    dispatcher_workers: Vec<DispatcherWorker>,
     dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>>,
     error_messages: Vec<String>,
+    event_feed: SourceChangeEventFeed,
     event_seq_num: u64,
+    event_validator: SourceChangeEventValidator,
+    max_eps_limiter: MaxEpsLimiter,
     next_event: Option<SourceChangeEvent>,
     previous_event: Option<ProcessedChangeEvent>,
-    rate_limiter: RateLimiter<NotKeyed, InMemoryState, QuantaClock, NoOpMiddleware<QuantaInstant>>,
+    rate_limiter: ChangeRateLimiter,
+    // The seed in use, which is the configured seed unless a Reset gave a different one.
+    seed: u64,
     settings: BuildingHierarchyDataGeneratorSettings,
     skips_remaining: u64,
     status: SourceChangeGeneratorStatus,
+    // Why the generator is in its current status, e.g. "paused by Pause command".
+    status_reason: String,
     stats: BuildingHierarchyDataGeneratorStats,
     steps_remaining: u64,
+    throughput: ThroughputWindow,
     virtual_time_ns_current: u64,
     virtual_time_ns_next: u64,
     virtual_time_ns_rebase_adjustment: i64, // Add to current time to get rebased virtual time.
@@ -586,53 +975,60 @@ impl BuildingHierarchyDataGeneratorInternalState {
     async fn initialize(
         settings: BuildingHierarchyDataGeneratorSettings,
         building_graph: Arc<Mutex<BuildingGraph>>,
+        event_feed: SourceChangeEventFeed,
     ) -> anyhow::Result<(Self, Receiver<ScheduledChangeEventMessage>)> {
         log::debug!(
             "Initializing BuildingHierarchyDataGenerator using {:?}",
             settings
         );
 
+        // Compile the event_schema, if any.
+        let event_validator = SourceChangeEventValidator::new(settings.event_schema.as_ref())?;
+
         // Create the dispatchers
-        let mut dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>> = Vec::new();
-        for def in settings.dispatchers.iter() {
-            match create_source_change_dispatcher(def, &settings.output_storage).await {
-                Ok(dispatcher) => dispatchers.push(dispatcher),
-                Err(e) => {
-                    anyhow::bail!(
-                        "Error creating SourceChangeDispatcher: {:?}; Error: {:?}",
-                        def,
-                        e
-                    );
-                }
-            }
-        }
+        let dispatchers = create_source_change_dispatchers(
+            &settings.dispatchers,
+            &settings.output_storage,
+            &settings.event_field_names,
+            &event_feed,
+        )
+        .await?;
 
-        let rate_limiter = match settings.spacing_mode {
-            SpacingMode::Rate(rate) => RateLimiter::direct(Quota::per_second(rate)),
-            _ => RateLimiter::direct(Quota::per_second(NonZeroU32::new(u32::MAX).unwrap())),
-        };
+        let rate_limiter = new_rate_limiter(&settings.spacing_mode, &settings.clock);
+        let throughput =
+            ThroughputWindow::new(settings.throughput_window_secs, settings.clock.now_ns());
 
         // Create the channels and threads used for message passing.
-        let (change_tx_channel, change_rx_channel) = tokio::sync::mpsc::channel(1000);
+        let (change_tx_channel, change_rx_channel) =
+            tokio::sync::mpsc::channel(settings.change_channel_capacity);
 
         let state = Self {
             building_graph,
+            burst_tracker: BurstTracker::default(),
             change_interval_generator: ChangeIntervalGenerator::new(
                 settings.seed,
                 settings.change_interval,
+                &settings.change_interval_distribution,
             )?,
             change_tx_channel,
+            dispatcher_workers: Vec::new(),
             dispatchers,
             error_messages: Vec::new(),
+            event_feed,
             event_seq_num: 0,
+            event_validator,
+            max_eps_limiter: MaxEpsLimiter::new(settings.max_eps),
             next_event: None,
             previous_event: None,
             rate_limiter,
+            seed: settings.seed,
             settings,
             skips_remaining: 0,
             status: SourceChangeGeneratorStatus::Paused,
+            status_reason: "paused: initialized".to_string(),
             stats: BuildingHierarchyDataGeneratorStats::default(),
             steps_remaining: 0,
+            throughput,
             virtual_time_ns_current: 0,
             virtual_time_ns_next: 0,
             virtual_time_ns_rebase_adjustment: 0,
@@ -642,21 +1038,44 @@ impl BuildingHierarchyDataGeneratorInternalState {
         Ok((state, change_rx_channel))
     }
 
-    async fn close_dispatchers(&mut self) {
+    // Closes the workers' queues and waits for them to dispatch what is queued, so the
+    // dispatchers can be used directly again. Returns the first error a worker hit.
+    async fn stop_dispatcher_workers(&mut self) -> anyhow::Result<()> {
+        let mut result = Ok(());
+        for worker in self.dispatcher_workers.drain(..) {
+            drop(worker.batch_tx_channel);
+            let worker_result = match worker.handle.await {
+                Ok((dispatcher, worker_result)) => {
+                    self.dispatchers.push(dispatcher);
+                    worker_result
+                }
+                Err(e) => Err(anyhow::anyhow!(
+                    "SourceChangeDispatcher worker failed: {:?}",
+                    e
+                )),
+            };
+            if result.is_ok() {
+                result = worker_result;
+            }
+        }
+        result
+    }
+
+    async fn close_dispatchers(&mut self) -> anyhow::Result<()> {
+        // Close the dispatchers even if a worker failed, then report the first error.
+        let workers_result = self.stop_dispatcher_workers().await;
         let dispatchers = &mut self.dispatchers;
 
         log::debug!("Closing dispatchers - #dispatchers:{}", dispatchers.len());
 
         let futures: Vec<_> = dispatchers
             .iter_mut()
-            .map(|dispatcher| async move {
-                let _ = dispatcher.close().await;
-            })
+            .map(|dispatcher| async move { dispatcher.close().await })
             .collect();
 
-        // Wait for all of them to complete
-        // TODO - Handle errors properly.
-        let _ = join_all(futures).await;
+        // Wait for all of them to complete, returning the first error.
+        let close_result: anyhow::Result<()> = join_all(futures).await.into_iter().collect();
+        workers_result.and(close_result)
     }
 
     async fn send_initial_inserts(&mut self) -> anyhow::Result<()> {
@@ -666,10 +1085,7 @@ impl BuildingHierarchyDataGeneratorInternalState {
         );
 
         // Get current time
-        let now_ns = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        let now_ns = self.settings.clock.now_ns();
 
         // Get all nodes and relations from current state
         let building_graph = self.building_graph.lock().await;
@@ -795,17 +1211,18 @@ impl BuildingHierarchyDataGeneratorInternalState {
         if !insert_events.is_empty() {
             log::info!("Dispatching {} initial insert events", insert_events.len());
             let events_refs: Vec<&SourceChangeEvent> = insert_events.iter().collect();
-            self.dispatch_source_change_events(events_refs).await;
+            self.dispatch_source_change_events(events_refs).await?;
             self.stats.num_source_change_events += insert_events.len() as u64;
         }
 
         Ok(())
     }
 
-    fn set_test_run_host_on_dispatchers(
+    async fn set_test_run_host_on_dispatchers(
         &mut self,
         test_run_host: std::sync::Arc<crate::TestRunHost>,
-    ) {
+    ) -> anyhow::Result<()> {
+        let workers_result = self.stop_dispatcher_workers().await;
         log::info!(
             "Setting TestRunHost on {} dispatchers for source {}",
             self.dispatchers.len(),
@@ -815,9 +1232,24 @@ impl BuildingHierarchyDataGeneratorInternalState {
         for dispatcher in self.dispatchers.iter_mut() {
             dispatcher.set_test_run_host(test_run_host.clone());
         }
+
+        workers_result
     }
 
-    async fn dispatch_source_change_events(&mut self, events: Vec<&SourceChangeEvent>) {
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        // Validate the events against the event_schema before any are dispatched.
+        self.event_validator.validate(&events)?;
+
+        // Enforce the max_eps cap after any spacing has been applied.
+        self.max_eps_limiter.until_ready(events.len()).await;
+
+        if let Some(max_in_flight) = self.settings.max_in_flight_dispatches {
+            return self.queue_source_change_events(events, max_in_flight).await;
+        }
+
         let dispatchers = &mut self.dispatchers;
 
         log::debug!(
@@ -830,15 +1262,66 @@ impl BuildingHierarchyDataGeneratorInternalState {
             .iter_mut()
             .map(|dispatcher| {
                 let events = events.clone();
-                async move {
-                    let _ = dispatcher.dispatch_source_change_events(events).await;
-                }
+                async move { dispatcher.dispatch_source_change_events(events).await }
             })
             .collect();
 
-        // Wait for all of them to complete
-        // TODO - Handle errors properly.
-        let _ = join_all(futures).await;
+        // Wait for all of them to complete, returning the first error so the generator
+        // enters its error state.
+        join_all(futures).await.into_iter().collect()
+    }
+
+    // Queues the events for each dispatcher's worker. Each dispatcher still gets the events in
+    // order, but the dispatchers no longer move in lock-step: a slow one falls behind the others
+    // until its queue is full, and only then does the generator wait for it.
+    async fn queue_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+        max_in_flight: usize,
+    ) -> anyhow::Result<()> {
+        if self.dispatcher_workers.is_empty() {
+            self.dispatcher_workers = self
+                .dispatchers
+                .drain(..)
+                .map(|dispatcher| DispatcherWorker::spawn(dispatcher, max_in_flight))
+                .collect();
+        }
+
+        let events: Vec<SourceChangeEvent> = events.into_iter().cloned().collect();
+        let mut worker_stopped = false;
+        for worker in self.dispatcher_workers.iter() {
+            match worker.batch_tx_channel.try_send(events.clone()) {
+                Ok(()) => {}
+                Err(TrySendError::Full(events)) => {
+                    let wait_start = Instant::now();
+                    self.stats.num_dispatch_backpressure_waits += 1;
+                    if worker.batch_tx_channel.send(events).await.is_err() {
+                        worker_stopped = true;
+                        break;
+                    }
+                    self.stats.dispatch_backpressure_wait_ns +=
+                        wait_start.elapsed().as_nanos() as u64;
+                }
+                Err(TrySendError::Closed(_)) => {
+                    worker_stopped = true;
+                    break;
+                }
+            }
+        }
+
+        // A worker only stops early when its dispatcher fails, so report that error.
+        if worker_stopped {
+            self.stop_dispatcher_workers().await?;
+            anyhow::bail!("SourceChangeDispatcher worker has stopped");
+        }
+
+        Ok(())
+    }
+
+    // Sets the status along with the reason reported for it in the external state.
+    fn set_status(&mut self, status: SourceChangeGeneratorStatus, reason: impl Into<String>) {
+        self.status = status;
+        self.status_reason = reason.into();
     }
 
     // Function to log the Player State at varying levels of detail.
@@ -850,6 +1333,25 @@ impl BuildingHierarchyDataGeneratorInternalState {
         }
     }
 
+    // The generator finishes once it has generated change_count changes, or dispatched
+    // stop_after_events changes, whichever comes first.
+    fn is_finished(&self) -> bool {
+        let dispatched =
+            self.stats.num_source_change_events - self.stats.num_skipped_source_change_events;
+        self.stats.num_source_change_events >= self.settings.change_count
+            || self
+                .settings
+                .stop_after_events
+                .is_some_and(|limit| dispatched >= limit)
+    }
+
+    // Processes a message from the change stream, entering the error state if it fails.
+    async fn handle_change_stream_message(&mut self, message: ScheduledChangeEventMessage) {
+        if let Err(e) = self.process_change_stream_message(message).await {
+            self.transition_to_error_state("Error calling process_change_stream_message", Some(&e));
+        }
+    }
+
     async fn process_change_stream_message(
         &mut self,
         message: ScheduledChangeEventMessage,
@@ -861,10 +1363,7 @@ impl BuildingHierarchyDataGeneratorInternalState {
 
         let source_change_event = match self.next_event.as_mut() {
             Some(source_change_event) => {
-                let now_ns = SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
+                let now_ns = self.settings.clock.now_ns();
 
                 source_change_event.reactivator_end_ns = now_ns;
 
@@ -890,7 +1389,7 @@ impl BuildingHierarchyDataGeneratorInternalState {
             SourceChangeGeneratorStatus::Running => {
                 // Dispatch the SourceChangeEvent.
                 self.dispatch_source_change_events(vec![&source_change_event])
-                    .await;
+                    .await?;
 
                 self.previous_event = Some(ProcessedChangeEvent {
                     dispatch_status: self.status,
@@ -899,8 +1398,9 @@ impl BuildingHierarchyDataGeneratorInternalState {
                 });
                 self.event_seq_num += 1;
                 self.stats.num_source_change_events += 1;
+                self.throughput.record(self.settings.clock.now_ns(), 1);
 
-                if self.stats.num_source_change_events >= self.settings.change_count {
+                if self.is_finished() {
                     self.transition_to_finished_state().await;
                 } else {
                     self.schedule_next_change_event().await?;
@@ -910,7 +1410,7 @@ impl BuildingHierarchyDataGeneratorInternalState {
                 if self.steps_remaining > 0 {
                     // Dispatch the SourceChangeEvent.
                     self.dispatch_source_change_events(vec![&source_change_event])
-                        .await;
+                        .await?;
 
                     self.previous_event = Some(ProcessedChangeEvent {
                         dispatch_status: self.status,
@@ -919,13 +1419,17 @@ impl BuildingHierarchyDataGeneratorInternalState {
                     });
                     self.event_seq_num += 1;
                     self.stats.num_source_change_events += 1;
+                    self.throughput.record(self.settings.clock.now_ns(), 1);
 
-                    if self.stats.num_source_change_events >= self.settings.change_count {
+                    if self.is_finished() {
                         self.transition_to_finished_state().await;
                     } else {
                         self.steps_remaining -= 1;
                         if self.steps_remaining == 0 {
-                            self.status = SourceChangeGeneratorStatus::Paused;
+                            self.set_status(
+                                SourceChangeGeneratorStatus::Paused,
+                                "paused: completed all steps",
+                            );
                             self.schedule_next_change_event().await?;
                         } else {
                             self.schedule_next_change_event().await?;
@@ -950,12 +1454,15 @@ impl BuildingHierarchyDataGeneratorInternalState {
                     self.stats.num_source_change_events += 1;
                     self.stats.num_skipped_source_change_events += 1;
 
-                    if self.stats.num_source_change_events >= self.settings.change_count {
+                    if self.is_finished() {
                         self.transition_to_finished_state().await;
                     } else {
                         self.skips_remaining -= 1;
                         if self.skips_remaining == 0 {
-                            self.status = SourceChangeGeneratorStatus::Paused;
+                            self.set_status(
+                                SourceChangeGeneratorStatus::Paused,
+                                "paused: completed all skips",
+                            );
                             self.schedule_next_change_event().await?;
                         } else {
                             self.schedule_next_change_event().await?;
@@ -1035,43 +1542,60 @@ impl BuildingHierarchyDataGeneratorInternalState {
         Ok(())
     }
 
-    async fn reset(&mut self) -> anyhow::Result<()> {
-        log::debug!("Resetting BuildingHierarchyDataGenerator");
+    async fn reset(&mut self, seed: Option<u64>) -> anyhow::Result<()> {
+        log::debug!(
+            "Resetting BuildingHierarchyDataGenerator with seed {:?}",
+            seed
+        );
 
-        // Create the new dispatchers
-        self.close_dispatchers().await;
-        let mut dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>> = Vec::new();
-        for def in self.settings.dispatchers.iter() {
-            match create_source_change_dispatcher(def, &self.settings.output_storage).await {
-                Ok(dispatcher) => dispatchers.push(dispatcher),
-                Err(e) => {
-                    anyhow::bail!(
-                        "Error creating SourceChangeDispatcher: {:?}; Error: {:?}",
-                        def,
-                        e
-                    );
-                }
-            }
+        // Create the new dispatchers. The old ones are being replaced, so a failure to close
+        // them shouldn't prevent the reset.
+        if let Err(e) = self.close_dispatchers().await {
+            log::warn!("Error closing dispatchers during reset: {:?}", e);
         }
+        let dispatchers = create_source_change_dispatchers(
+            &self.settings.dispatchers,
+            &self.settings.output_storage,
+            &self.settings.event_field_names,
+            &self.event_feed,
+        )
+        .await?;
         // These fields do not get reset:
         //   change_tx_channel
+        //   dispatcher_workers, which close_dispatchers has already stopped
         //   delayer_tx_channel
+        //   max_eps_limiter
         //   rate_limiter
         //   rate_limiter_tx_channel
         //   settings
 
-        self.building_graph = Arc::new(Mutex::new(BuildingGraph::new(&self.settings)?));
-        self.change_interval_generator =
-            ChangeIntervalGenerator::new(self.settings.seed, self.settings.change_interval)?;
+        // Without a seed, the configured seed is reused so the reset replays the same changes.
+        self.seed = seed.unwrap_or(self.settings.seed);
+        let seeded_settings = BuildingHierarchyDataGeneratorSettings {
+            seed: self.seed,
+            ..self.settings.clone()
+        };
+        self.building_graph = Arc::new(Mutex::new(BuildingGraph::new(&seeded_settings)?));
+        self.burst_tracker.reset();
+        self.change_interval_generator = ChangeIntervalGenerator::new(
+            self.seed,
+            self.settings.change_interval,
+            &self.settings.change_interval_distribution,
+        )?;
         self.dispatchers = dispatchers;
         self.error_messages = Vec::new();
         self.event_seq_num = 0;
+        self.event_validator.reset();
         self.next_event = None;
         self.previous_event = None;
         self.skips_remaining = 0;
-        self.status = SourceChangeGeneratorStatus::Paused;
+        self.set_status(SourceChangeGeneratorStatus::Paused, "paused: reset");
         self.stats = BuildingHierarchyDataGeneratorStats::default();
         self.steps_remaining = 0;
+        self.throughput = ThroughputWindow::new(
+            self.settings.throughput_window_secs,
+            self.settings.clock.now_ns(),
+        );
         self.virtual_time_ns_current = 0;
         self.virtual_time_ns_next = 0;
         self.virtual_time_ns_rebase_adjustment = 0;
@@ -1083,14 +1607,25 @@ impl BuildingHierarchyDataGeneratorInternalState {
     async fn schedule_next_change_event(&mut self) -> anyhow::Result<()> {
         log::debug!("Scheduling next change event");
 
-        // Throttle the event generation to the configured rate.
-        self.rate_limiter.until_ready().await;
+        // Throttle the event generation to the configured rate. The waits go through the
+        // clock, so a ManualClock skips them instead of the test waiting.
+        while let Err(not_until) = self.rate_limiter.check() {
+            let wait = not_until.wait_time_from(Nanos::from(self.settings.clock.now_ns()));
+            self.settings.clock.sleep(wait).await;
+        }
+        if let SpacingMode::Burst {
+            burst_size,
+            burst_interval_ns,
+        } = self.settings.spacing_mode
+        {
+            let delay = self.burst_tracker.next_delay(burst_size, burst_interval_ns);
+            if !delay.is_zero() {
+                self.settings.clock.sleep(delay).await;
+            }
+        }
 
         // Calculate times
-        let now_ns = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        let now_ns = self.settings.clock.now_ns();
 
         if self.previous_event.is_none() {
             // First event after start, initialize times.
@@ -1117,9 +1652,13 @@ impl BuildingHierarchyDataGeneratorInternalState {
                 }
             }
         } else {
-            // Calculate the next event time based on the current time and the configured event interval.
-            self.virtual_time_ns_next =
-                self.virtual_time_ns_current + self.change_interval_generator.next();
+            // Calculate the next event time based on the current time and the configured event interval,
+            // scaled by the speed_factor.
+            self.virtual_time_ns_next = self.virtual_time_ns_current
+                + scale_delay_ns(
+                    self.change_interval_generator.next(),
+                    self.settings.speed_factor,
+                );
         };
 
         let update = {
@@ -1186,10 +1725,10 @@ impl BuildingHierarchyDataGeneratorInternalState {
         );
 
         match command {
-            BuildingHierarchyDataGeneratorCommand::Reset => self.reset().await,
+            BuildingHierarchyDataGeneratorCommand::Reset { seed } => self.reset(*seed).await,
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
             _ => Err(BuildingHierarchyDataGeneratorError::Error(self.status).into()),
         }
@@ -1206,10 +1745,10 @@ impl BuildingHierarchyDataGeneratorInternalState {
         );
 
         match command {
-            BuildingHierarchyDataGeneratorCommand::Reset => self.reset().await,
+            BuildingHierarchyDataGeneratorCommand::Reset { seed } => self.reset(*seed).await,
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
             _ => Err(BuildingHierarchyDataGeneratorError::AlreadyFinished.into()),
         }
@@ -1228,7 +1767,7 @@ impl BuildingHierarchyDataGeneratorInternalState {
         match command {
             BuildingHierarchyDataGeneratorCommand::GetState => Ok(()),
             BuildingHierarchyDataGeneratorCommand::Pause => Ok(()),
-            BuildingHierarchyDataGeneratorCommand::Reset => self.reset().await,
+            BuildingHierarchyDataGeneratorCommand::Reset { seed } => self.reset(*seed).await,
             BuildingHierarchyDataGeneratorCommand::Skip { skips, .. } => {
                 log::info!(
                     "Script Skipping {} skips for TestRunSource {}",
@@ -1236,21 +1775,58 @@ impl BuildingHierarchyDataGeneratorInternalState {
                     self.settings.id
                 );
 
-                self.status = SourceChangeGeneratorStatus::Skipping;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Skipping,
+                    format!("skipping {} events by Skip command", skips),
+                );
                 self.skips_remaining = *skips;
                 // self.skips_spacing_mode = spacing_mode.clone();
                 self.schedule_next_change_event().await
             }
+            BuildingHierarchyDataGeneratorCommand::SkipToEnd => {
+                // Skipped changes count towards change_count, so skipping the rest of them
+                // leaves the generator Finished. The initial inserts count towards it too and
+                // aren't capped by it, so there may be nothing left to skip.
+                let skips = self
+                    .settings
+                    .change_count
+                    .saturating_sub(self.stats.num_source_change_events);
+                if skips == 0 {
+                    self.transition_to_finished_state().await;
+                    return Ok(());
+                }
+                log::info!(
+                    "Script Skipping {} skips to the end for TestRunSource {}",
+                    skips,
+                    self.settings.id
+                );
+
+                self.set_status(
+                    SourceChangeGeneratorStatus::Skipping,
+                    format!("skipping {} events to the end by SkipToEnd command", skips),
+                );
+                self.skips_remaining = skips;
+                self.schedule_next_change_event().await
+            }
             BuildingHierarchyDataGeneratorCommand::Start => {
                 log::info!("Script Started for TestRunSource {}", self.settings.id);
 
-                self.status = SourceChangeGeneratorStatus::Running;
+                // The rate limiter builds up a burst allowance while paused, so replace it to
+                // carry on at the configured rate instead of sending a burst of changes.
+                self.rate_limiter =
+                    new_rate_limiter(&self.settings.spacing_mode, &self.settings.clock);
+                self.burst_tracker.reset();
 
-                // If send_initial_inserts is true, send insert events for all current state
+                self.set_status(
+                    SourceChangeGeneratorStatus::Running,
+                    "started by Start command",
+                );
+
+                // If send_initial_inserts is true, send insert events for all current state.
+                // This fails if an event violates the event_schema with halt_on_violation set, or
+                // if a dispatcher fails.
                 if self.settings.send_initial_inserts {
-                    if let Err(e) = self.send_initial_inserts().await {
-                        log::error!("Failed to send initial inserts: {}", e);
-                    }
+                    self.send_initial_inserts().await?;
                 }
 
                 self.schedule_next_change_event().await
@@ -1262,18 +1838,50 @@ impl BuildingHierarchyDataGeneratorInternalState {
                     self.settings.id
                 );
 
-                self.status = SourceChangeGeneratorStatus::Stepping;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Stepping,
+                    format!("stepping {} events by Step command", steps),
+                );
                 self.steps_remaining = *steps;
                 // self.steps_spacing_mode = spacing_mode.clone();
                 self.schedule_next_change_event().await
             }
+            BuildingHierarchyDataGeneratorCommand::StepTo { target_seq, .. } => {
+                if *target_seq <= self.event_seq_num {
+                    return Err(BuildingHierarchyDataGeneratorError::StepToTargetNotAhead {
+                        target_seq: *target_seq,
+                        current_seq: self.event_seq_num,
+                    }
+                    .into());
+                }
+
+                // Each step dispatches one event and advances event_seq_num by one, so the
+                // generator pauses with event_seq_num at the target.
+                let steps = target_seq - self.event_seq_num;
+                log::info!(
+                    "Script Stepping {} steps to event_seq_num {} for TestRunSource {}",
+                    steps,
+                    target_seq,
+                    self.settings.id
+                );
+
+                self.set_status(
+                    SourceChangeGeneratorStatus::Stepping,
+                    format!(
+                        "stepping {} events to event_seq_num {} by StepTo command",
+                        steps, target_seq
+                    ),
+                );
+                self.steps_remaining = steps;
+                self.schedule_next_change_event().await
+            }
             BuildingHierarchyDataGeneratorCommand::Stop => {
                 self.transition_to_stopped_state().await;
                 Ok(())
             }
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
         }
     }
@@ -1291,26 +1899,36 @@ impl BuildingHierarchyDataGeneratorInternalState {
         match command {
             BuildingHierarchyDataGeneratorCommand::GetState => Ok(()),
             BuildingHierarchyDataGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 Ok(())
             }
-            BuildingHierarchyDataGeneratorCommand::Reset => {
+            BuildingHierarchyDataGeneratorCommand::Reset { .. } => {
                 Err(BuildingHierarchyDataGeneratorError::PauseToReset.into())
             }
-            BuildingHierarchyDataGeneratorCommand::Skip { .. } => {
+            BuildingHierarchyDataGeneratorCommand::Skip { .. }
+            | BuildingHierarchyDataGeneratorCommand::SkipToEnd => {
                 Err(BuildingHierarchyDataGeneratorError::PauseToSkip.into())
             }
             BuildingHierarchyDataGeneratorCommand::Start => Ok(()),
-            BuildingHierarchyDataGeneratorCommand::Step { .. } => {
+            BuildingHierarchyDataGeneratorCommand::Step { .. }
+            | BuildingHierarchyDataGeneratorCommand::StepTo { .. } => {
                 Err(BuildingHierarchyDataGeneratorError::PauseToStep.into())
             }
             BuildingHierarchyDataGeneratorCommand::Stop => {
+                if self.settings.drain_on_stop {
+                    if let Err(e) = self.drain_next_event().await {
+                        log::error!("Error draining next change on stop: {:?}", e);
+                    }
+                }
                 self.transition_to_stopped_state().await;
                 Ok(())
             }
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
         }
     }
@@ -1328,7 +1946,10 @@ impl BuildingHierarchyDataGeneratorInternalState {
         match command {
             BuildingHierarchyDataGeneratorCommand::GetState => Ok(()),
             BuildingHierarchyDataGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 self.skips_remaining = 0;
                 Ok(())
             }
@@ -1336,15 +1957,17 @@ impl BuildingHierarchyDataGeneratorInternalState {
                 self.transition_to_stopped_state().await;
                 Ok(())
             }
-            BuildingHierarchyDataGeneratorCommand::Reset
+            BuildingHierarchyDataGeneratorCommand::Reset { .. }
             | BuildingHierarchyDataGeneratorCommand::Skip { .. }
+            | BuildingHierarchyDataGeneratorCommand::SkipToEnd
             | BuildingHierarchyDataGeneratorCommand::Start
-            | BuildingHierarchyDataGeneratorCommand::Step { .. } => Err(
+            | BuildingHierarchyDataGeneratorCommand::Step { .. }
+            | BuildingHierarchyDataGeneratorCommand::StepTo { .. } => Err(
                 BuildingHierarchyDataGeneratorError::CurrentlySkipping(self.skips_remaining).into(),
             ),
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
         }
     }
@@ -1362,7 +1985,10 @@ impl BuildingHierarchyDataGeneratorInternalState {
         match command {
             BuildingHierarchyDataGeneratorCommand::GetState => Ok(()),
             BuildingHierarchyDataGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 self.steps_remaining = 0;
                 Ok(())
             }
@@ -1370,15 +1996,17 @@ impl BuildingHierarchyDataGeneratorInternalState {
                 self.transition_to_stopped_state().await;
                 Ok(())
             }
-            BuildingHierarchyDataGeneratorCommand::Reset
+            BuildingHierarchyDataGeneratorCommand::Reset { .. }
             | BuildingHierarchyDataGeneratorCommand::Skip { .. }
+            | BuildingHierarchyDataGeneratorCommand::SkipToEnd
             | BuildingHierarchyDataGeneratorCommand::Start
-            | BuildingHierarchyDataGeneratorCommand::Step { .. } => Err(
+            | BuildingHierarchyDataGeneratorCommand::Step { .. }
+            | BuildingHierarchyDataGeneratorCommand::StepTo { .. } => Err(
                 BuildingHierarchyDataGeneratorError::CurrentlyStepping(self.steps_remaining).into(),
             ),
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
         }
     }
@@ -1394,10 +2022,10 @@ impl BuildingHierarchyDataGeneratorInternalState {
         );
 
         match command {
-            BuildingHierarchyDataGeneratorCommand::Reset => self.reset().await,
+            BuildingHierarchyDataGeneratorCommand::Reset { seed } => self.reset(*seed).await,
             BuildingHierarchyDataGeneratorCommand::SetTestRunHost { test_run_host } => {
-                self.set_test_run_host_on_dispatchers(test_run_host.clone());
-                Ok(())
+                self.set_test_run_host_on_dispatchers(test_run_host.clone())
+                    .await
             }
             _ => Err(BuildingHierarchyDataGeneratorError::AlreadyStopped.into()),
         }
@@ -1406,41 +2034,88 @@ impl BuildingHierarchyDataGeneratorInternalState {
     async fn transition_to_finished_state(&mut self) {
         log::info!("Script Finished for TestRunSource {}", self.settings.id);
 
-        self.status = SourceChangeGeneratorStatus::Finished;
-        self.stats.actual_end_time_ns = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        let reason = if self.stats.num_source_change_events >= self.settings.change_count {
+            format!(
+                "finished: reached change_count {}",
+                self.settings.change_count
+            )
+        } else {
+            format!(
+                "finished: reached stop_after_events {}",
+                self.settings.stop_after_events.unwrap_or_default()
+            )
+        };
+        self.set_status(SourceChangeGeneratorStatus::Finished, reason);
+        self.stats.actual_end_time_ns = self.settings.clock.now_ns();
         self.skips_remaining = 0;
         self.steps_remaining = 0;
 
-        self.close_dispatchers().await;
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
         self.write_result_summary().await.ok();
     }
 
+    // Dispatches the change that was prepared but not yet dispatched when the generator was
+    // stopped, so it isn't lost.
+    async fn drain_next_event(&mut self) -> anyhow::Result<()> {
+        let mut source_change_event = match self.next_event.take() {
+            Some(source_change_event) => source_change_event,
+            None => return Ok(()),
+        };
+
+        log::info!(
+            "Draining next change before stopping TestRunSource {}",
+            self.settings.id
+        );
+
+        self.virtual_time_ns_current = self.virtual_time_ns_next;
+        source_change_event.reactivator_end_ns = self.settings.clock.now_ns();
+
+        self.dispatch_source_change_events(vec![&source_change_event])
+            .await?;
+
+        self.previous_event = Some(ProcessedChangeEvent {
+            dispatch_status: self.status,
+            event: source_change_event,
+            seq: self.event_seq_num,
+        });
+        self.event_seq_num += 1;
+        self.stats.num_source_change_events += 1;
+        self.stats.drained_event_on_stop = true;
+        self.throughput.record(self.settings.clock.now_ns(), 1);
+
+        Ok(())
+    }
+
     async fn transition_to_stopped_state(&mut self) {
         log::info!("Script Stopped for TestRunSource {}", self.settings.id);
 
-        self.status = SourceChangeGeneratorStatus::Stopped;
-        self.stats.actual_end_time_ns = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos() as u64;
+        self.set_status(
+            SourceChangeGeneratorStatus::Stopped,
+            "stopped by Stop command",
+        );
+        self.stats.actual_end_time_ns = self.settings.clock.now_ns();
         self.skips_remaining = 0;
         self.steps_remaining = 0;
 
-        self.close_dispatchers().await;
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
         self.write_result_summary().await.ok();
     }
 
     fn transition_to_error_state(&mut self, error_message: &str, error: Option<&anyhow::Error>) {
-        self.status = SourceChangeGeneratorStatus::Error;
-
         let msg = match error {
             Some(e) => format!("{}: {:?}", error_message, e),
             None => error_message.to_string(),
         };
 
+        self.set_status(
+            SourceChangeGeneratorStatus::Error,
+            format!("error: {}", msg),
+        );
+
         self.log_state(&msg);
 
         self.error_messages.push(msg);
@@ -1471,12 +2146,15 @@ impl Debug for BuildingHierarchyDataGeneratorInternalState {
         f.debug_struct("BuildingHierarchyDataGeneratorInternalState")
             .field("error_messages", &self.error_messages)
             .field("event_seq_num", &self.event_seq_num)
+            .field("event_validation", &self.event_validator.stats())
             .field("next_event", &self.next_event)
             .field("previous_record", &self.previous_event)
+            .field("seed", &self.seed)
             .field("settings", &self.settings)
             .field("skips_remaining", &self.skips_remaining)
             .field("spacing_mode", &self.settings.spacing_mode)
             .field("status", &self.status)
+            .field("status_reason", &self.status_reason)
             .field("stats", &self.stats)
             .field("steps_remaining", &self.steps_remaining)
             .field("time_mode", &self.settings.time_mode)
@@ -1495,6 +2173,12 @@ impl Debug for BuildingHierarchyDataGeneratorInternalState {
 pub struct BuildingHierarchyDataGeneratorStats {
     pub actual_start_time_ns: u64,
     pub actual_end_time_ns: u64,
+    // Number of times a dispatcher's queue was full, and the total time spent waiting for
+    // space, when max_in_flight_dispatches is set.
+    pub dispatch_backpressure_wait_ns: u64,
+    // Whether a Stop command with drain_on_stop set dispatched the prepared change.
+    pub drained_event_on_stop: bool,
+    pub num_dispatch_backpressure_waits: u64,
     pub num_source_change_events: u64,
     pub num_skipped_source_change_events: u64,
 }
@@ -1507,9 +2191,11 @@ pub struct BuildingHierarchyDataGeneratorResultSummary {
     pub actual_end_time_ns: u64,
     pub run_duration_ns: u64,
     pub run_duration_sec: f64,
+    pub drained_event_on_stop: bool,
     pub num_source_change_events: u64,
     pub num_skipped_source_events: u64,
     pub processing_rate: f64,
+    pub status_reason: String,
     pub test_run_source_id: String,
 }
 
@@ -1537,9 +2223,11 @@ impl From<&mut BuildingHierarchyDataGeneratorInternalState>
             actual_end_time_ns: state.stats.actual_end_time_ns,
             run_duration_ns,
             run_duration_sec,
+            drained_event_on_stop: state.stats.drained_event_on_stop,
             num_source_change_events: state.stats.num_source_change_events,
             num_skipped_source_events: state.stats.num_skipped_source_change_events,
             processing_rate: state.stats.num_source_change_events as f64 / run_duration_sec,
+            status_reason: state.status_reason.clone(),
             test_run_source_id: state.settings.id.to_string(),
         }
     }
@@ -1557,8 +2245,10 @@ impl Debug for BuildingHierarchyDataGeneratorResultSummary {
             self.run_duration_sec, self.run_duration_ns,
         );
         let source_change_events = format!(
-            "{} (skipped:{})",
-            self.num_source_change_events, self.num_skipped_source_events
+            "{} (skipped:{}, drained on stop:{})",
+            self.num_source_change_events,
+            self.num_skipped_source_events,
+            self.drained_event_on_stop
         );
         let processing_rate = format!("{:.2} changes / sec", self.processing_rate);
 
@@ -1569,6 +2259,7 @@ impl Debug for BuildingHierarchyDataGeneratorResultSummary {
             .field("run_duration", &run_duration)
             .field("source_change_events", &source_change_events)
             .field("processing_rate", &processing_rate)
+            .field("status_reason", &self.status_reason)
             .finish()
     }
 }
@@ -1580,6 +2271,7 @@ pub async fn model_host_thread(
     mut command_rx_channel: Receiver<BuildingHierarchyDataGeneratorMessage>,
     settings: BuildingHierarchyDataGeneratorSettings,
     building_graph: Arc<Mutex<BuildingGraph>>,
+    event_feed: SourceChangeEventFeed,
 ) -> anyhow::Result<()> {
     log::info!(
         "Script processor thread started for TestRunSource {} ...",
@@ -1588,8 +2280,12 @@ pub async fn model_host_thread(
 
     // The BuildingHierarchyDataGenerator always starts with the model initialized and Paused.
     let (mut state, mut change_rx_channel) =
-        match BuildingHierarchyDataGeneratorInternalState::initialize(settings, building_graph)
-            .await
+        match BuildingHierarchyDataGeneratorInternalState::initialize(
+            settings,
+            building_graph,
+            event_feed,
+        )
+        .await
         {
             Ok((state, change_rx_channel)) => (state, change_rx_channel),
             Err(e) => {
@@ -1630,8 +2326,7 @@ pub async fn model_host_thread(
                         // This avoids dealing with delayed messages from the delayer thread that are no longer relevant.
                         log::trace!("Received change stream message: {:?}", change_stream_message);
                         if change_stream_message.seq_num == state.event_seq_num && state.status.is_processing() {
-                            state.process_change_stream_message(change_stream_message).await
-                                .inspect_err(|e| state.transition_to_error_state("Error calling process_change_stream_message", Some(e))).ok();
+                            state.handle_change_stream_message(change_stream_message).await;
                         }
                     }
                     None => {
@@ -1653,3 +2348,582 @@ pub async fn model_host_thread(
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::source_change_dispatchers::test_support::{
+        FailingDispatcher, RecordingDispatcher,
+    };
+    use crate::utils::ManualClock;
+    use serde_json::json;
+    use std::time::{Duration, Instant};
+
+    async fn test_settings(dir: &std::path::Path) -> BuildingHierarchyDataGeneratorSettings {
+        let id = TestRunSourceId::try_from("test-repo.test-001.run-001.source-001").unwrap();
+        let definition: BuildingHierarchyDataGeneratorDefinition = serde_json::from_value(json!({
+            "seed": 1,
+            "room_sensors": [{ "kind": "NormalFloat", "id": "temperature" }]
+        }))
+        .unwrap();
+        let input_storage = TestSourceStorage {
+            id: "source-001".to_string(),
+            path: dir.join("input"),
+            repo_id: "test-repo".to_string(),
+            test_id: "test-001".to_string(),
+            test_source_definition: serde_json::from_value(json!({
+                "kind": "Model",
+                "test_source_id": "source-001"
+            }))
+            .unwrap(),
+        };
+        let output_storage = TestRunSourceStorage {
+            id: id.clone(),
+            path: dir.join("output"),
+            source_change_path: dir.join("output").join("source_change_dispatchers"),
+        };
+
+        let mut settings = BuildingHierarchyDataGeneratorSettings::new(
+            id,
+            definition,
+            input_storage,
+            output_storage,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+        // Rebase the changes so their times don't depend on when the test runs.
+        settings.time_mode = TimeMode::Rebased(1_000_000_000);
+        settings
+    }
+
+    async fn test_state(
+        settings: BuildingHierarchyDataGeneratorSettings,
+    ) -> (
+        BuildingHierarchyDataGeneratorInternalState,
+        Receiver<ScheduledChangeEventMessage>,
+    ) {
+        let building_graph = Arc::new(Mutex::new(BuildingGraph::new(&settings).unwrap()));
+        BuildingHierarchyDataGeneratorInternalState::initialize(
+            settings,
+            building_graph,
+            SourceChangeEventFeed::default(),
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn first_change_after_reset(
+        state: &mut BuildingHierarchyDataGeneratorInternalState,
+        seed: Option<u64>,
+    ) -> (serde_json::Value, serde_json::Value) {
+        state.reset(seed).await.unwrap();
+        state.schedule_next_change_event().await.unwrap();
+        let event = state.next_event.clone().unwrap();
+        (event.payload.before, event.payload.after)
+    }
+
+    #[tokio::test]
+    async fn test_reset_with_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path()).await;
+        let (mut state, _change_rx_channel) = test_state(settings).await;
+
+        let first = first_change_after_reset(&mut state, Some(42)).await;
+        assert_eq!(state.seed, 42);
+        assert_eq!(first_change_after_reset(&mut state, Some(42)).await, first);
+        assert_ne!(first_change_after_reset(&mut state, Some(43)).await, first);
+
+        // Without a seed, the reset goes back to the configured seed.
+        first_change_after_reset(&mut state, None).await;
+        assert_eq!(state.seed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_stop_after_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_count = 10;
+        settings.stop_after_events = Some(3);
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        // Skip 2 changes, which don't count towards stop_after_events.
+        state.skips_remaining = 2;
+        state.set_status(SourceChangeGeneratorStatus::Skipping, "skipping");
+        state.schedule_next_change_event().await.unwrap();
+        while state.status == SourceChangeGeneratorStatus::Skipping {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Paused);
+
+        // Run until the generator finishes.
+        state.set_status(SourceChangeGeneratorStatus::Running, "running");
+        let mut message = ScheduledChangeEventMessage {
+            delay_ns: 0,
+            seq_num: state.event_seq_num,
+        };
+        loop {
+            state.process_change_stream_message(message).await.unwrap();
+            if state.status != SourceChangeGeneratorStatus::Running {
+                break;
+            }
+            message = change_rx_channel.recv().await.unwrap();
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(state.stats.num_skipped_source_change_events, 2);
+        assert_eq!(state.stats.num_source_change_events, 5);
+    }
+
+    #[tokio::test]
+    async fn test_skip_to_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_count = 10;
+        settings.spacing_mode = SpacingMode::None;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        // Dispatch a few changes first, so only the remaining ones are skipped.
+        let step = BuildingHierarchyDataGeneratorCommand::Step {
+            steps: 3,
+            spacing_mode: None,
+        };
+        state.transition_from_paused_state(&step).await.unwrap();
+        while state.status == SourceChangeGeneratorStatus::Stepping {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+
+        // SkipToEnd needs the generator to be paused.
+        let skip_to_end = BuildingHierarchyDataGeneratorCommand::SkipToEnd;
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        assert!(state
+            .transition_from_running_state(&skip_to_end)
+            .await
+            .is_err());
+        let pause = BuildingHierarchyDataGeneratorCommand::Pause;
+        state.transition_from_running_state(&pause).await.unwrap();
+        while change_rx_channel.try_recv().is_ok() {}
+
+        state
+            .transition_from_paused_state(&skip_to_end)
+            .await
+            .unwrap();
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Skipping);
+        assert_eq!(state.skips_remaining, 7);
+        while state.status == SourceChangeGeneratorStatus::Skipping {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(state.stats.num_source_change_events, 10);
+        assert_eq!(state.stats.num_skipped_source_change_events, 7);
+    }
+
+    #[tokio::test]
+    async fn test_skip_to_end_after_initial_inserts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_count = 2;
+        settings.send_initial_inserts = true;
+        settings.spacing_mode = SpacingMode::None;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        // Starting sends more initial inserts than change_count.
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        let pause = BuildingHierarchyDataGeneratorCommand::Pause;
+        state.transition_from_running_state(&pause).await.unwrap();
+        while change_rx_channel.try_recv().is_ok() {}
+        let num_initial_inserts = state.stats.num_source_change_events;
+        assert!(num_initial_inserts > 2);
+
+        let skip_to_end = BuildingHierarchyDataGeneratorCommand::SkipToEnd;
+        state
+            .transition_from_paused_state(&skip_to_end)
+            .await
+            .unwrap();
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(state.stats.num_source_change_events, num_initial_inserts);
+        assert_eq!(state.stats.num_skipped_source_change_events, 0);
+    }
+
+    async fn process_changes(
+        state: &mut BuildingHierarchyDataGeneratorInternalState,
+        change_rx_channel: &mut Receiver<ScheduledChangeEventMessage>,
+        count: usize,
+    ) {
+        for _ in 0..count {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rate_after_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        let clock = Arc::new(ManualClock::new(1_000_000_000));
+        settings.clock = clock.clone();
+        settings.spacing_mode = SpacingMode::Rate(NonZeroU32::new(5).unwrap());
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        let pause = BuildingHierarchyDataGeneratorCommand::Pause;
+
+        state.transition_from_paused_state(&start).await.unwrap();
+        process_changes(&mut state, &mut change_rx_channel, 2).await;
+        state.transition_from_running_state(&pause).await.unwrap();
+        while change_rx_channel.try_recv().is_ok() {}
+
+        // Stay paused long enough to build up more than a second's worth of changes.
+        clock.advance(3_000_000_000);
+
+        // At 5 changes per second, the first 5 changes after resuming take at least a second.
+        let resumed_at_ns = clock.now_ns();
+        state.transition_from_paused_state(&start).await.unwrap();
+        process_changes(&mut state, &mut change_rx_channel, 5).await;
+        assert!(clock.now_ns() - resumed_at_ns >= 1_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_burst_spacing() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_count = 100;
+        settings.clock = Arc::new(ManualClock::new(1_000_000_000));
+        settings.spacing_mode = SpacingMode::Burst {
+            burst_size: NonZeroU32::new(3).unwrap(),
+            burst_interval_ns: 200_000_000,
+        };
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        state
+            .transition_from_paused_state(&BuildingHierarchyDataGeneratorCommand::Start)
+            .await
+            .unwrap();
+        let mut emitted_ns = Vec::new();
+        for _ in 0..9 {
+            process_changes(&mut state, &mut change_rx_channel, 1).await;
+            let event = &state.previous_event.as_ref().unwrap().event;
+            emitted_ns.push(event.reactivator_start_ns);
+        }
+
+        // Changes within a burst go back-to-back, and each burst starts after the interval.
+        let gaps: Vec<u64> = emitted_ns
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect();
+        assert_eq!(gaps, vec![0, 0, 200_000_000, 0, 0, 200_000_000, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight_dispatches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_count = 100;
+        settings.spacing_mode = SpacingMode::None;
+        settings.max_in_flight_dispatches = Some(2);
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        let slow = RecordingDispatcher::with_delay(Duration::from_millis(50));
+        let fast = RecordingDispatcher::new();
+        state.dispatchers = vec![Box::new(slow.clone()), Box::new(fast.clone())];
+
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        process_changes(&mut state, &mut change_rx_channel, 10).await;
+
+        // The fast dispatcher gets every change while the slow one falls behind.
+        let waited_at = Instant::now();
+        while fast.count() < 10 {
+            assert!(waited_at.elapsed() < Duration::from_secs(5));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(slow.count() < 10);
+        assert!(state.stats.num_dispatch_backpressure_waits > 0);
+        assert!(state.stats.dispatch_backpressure_wait_ns > 0);
+
+        // Closing the dispatchers waits for the queued changes to be dispatched.
+        state.close_dispatchers().await.unwrap();
+        assert_eq!(slow.count(), 10);
+        assert_eq!(state.dispatchers.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_drain_on_stop() {
+        let mut dispatched_counts = Vec::new();
+        for drain_on_stop in [false, true] {
+            let dir = tempfile::tempdir().unwrap();
+            let mut settings = test_settings(dir.path()).await;
+            settings.spacing_mode = SpacingMode::None;
+            settings.drain_on_stop = drain_on_stop;
+            let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+            let dispatcher = RecordingDispatcher::new();
+            state.dispatchers = vec![Box::new(dispatcher.clone())];
+
+            let start = BuildingHierarchyDataGeneratorCommand::Start;
+            state.transition_from_paused_state(&start).await.unwrap();
+            process_changes(&mut state, &mut change_rx_channel, 3).await;
+
+            let stop = BuildingHierarchyDataGeneratorCommand::Stop;
+            state.transition_from_running_state(&stop).await.unwrap();
+            assert_eq!(state.status, SourceChangeGeneratorStatus::Stopped);
+            assert_eq!(state.stats.drained_event_on_stop, drain_on_stop);
+            assert_eq!(state.stats.num_source_change_events, dispatcher.count());
+
+            dispatched_counts.push(dispatcher.count());
+        }
+
+        assert_eq!(dispatched_counts, vec![3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_current_events_per_sec() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        let clock = Arc::new(ManualClock::new(1_000_000_000));
+        settings.clock = clock.clone();
+        settings.change_interval = (10_000_000, 0.0, 0, u64::MAX);
+        settings.throughput_window_secs = 1;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        let current_events_per_sec = |state: &mut BuildingHierarchyDataGeneratorInternalState| {
+            BuildingHierarchyDataGeneratorExternalState::from(state).current_events_per_sec
+        };
+        assert_eq!(current_events_per_sec(&mut state), 0.0);
+
+        state
+            .transition_from_paused_state(&BuildingHierarchyDataGeneratorCommand::Start)
+            .await
+            .unwrap();
+        process_changes(&mut state, &mut change_rx_channel, 5).await;
+        assert_eq!(current_events_per_sec(&mut state), 5.0);
+
+        state
+            .transition_from_running_state(&BuildingHierarchyDataGeneratorCommand::Pause)
+            .await
+            .unwrap();
+
+        // Once the window has passed with nothing dispatched, the rate is back to zero.
+        clock.advance(2_000_000_000);
+        assert_eq!(current_events_per_sec(&mut state), 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_step_to() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.change_interval = (1_000_000, 0.0, 0, u64::MAX);
+        settings.spacing_mode = SpacingMode::None;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        let step_to = |target_seq| BuildingHierarchyDataGeneratorCommand::StepTo {
+            target_seq,
+            spacing_mode: None,
+        };
+
+        state
+            .transition_from_paused_state(&step_to(7))
+            .await
+            .unwrap();
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Stepping);
+        assert_eq!(state.steps_remaining, 7);
+        while state.status == SourceChangeGeneratorStatus::Stepping {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Paused);
+        assert_eq!(state.event_seq_num, 7);
+        assert_eq!(state.stats.num_source_change_events, 7);
+
+        // Targets at or below the current sequence are rejected without changing state.
+        for target_seq in [7, 3] {
+            let err = state
+                .transition_from_paused_state(&step_to(target_seq))
+                .await
+                .unwrap_err();
+            assert!(err.to_string().contains("already at 7"));
+            assert_eq!(state.status, SourceChangeGeneratorStatus::Paused);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sensor_seasonality() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        // Without any random variance, the sensor's value is the sine wave around 50.
+        settings.room_sensors = vec![serde_json::from_value(json!({
+            "kind": "NormalFloat",
+            "id": "temperature",
+            "value_init": [50.0, 0.0],
+            "value_change": [0.0, 0.0],
+            "seasonality": { "amplitude": 10.0, "period_ns": 1_000_000_000, "phase": 0.5 }
+        }))
+        .unwrap()];
+
+        let mut change_generator = building_graph::GraphChangeGenerator::new(&settings);
+        let room_id = building_graph::Location::Room(0, 0, 0);
+        let mut sensor_values = change_generator
+            .initialize_sensor_values(&room_id, 0)
+            .unwrap();
+
+        let expected = |elapsed_ns: u64| {
+            let cycle = elapsed_ns as f64 / 1_000_000_000.0;
+            50.0 + 10.0 * (2.0 * std::f64::consts::PI * cycle + 0.5).sin()
+        };
+        let value = |sensor_values: &[building_graph::SensorValue]| match &sensor_values[0] {
+            building_graph::SensorValue::NormalFloat(sensor) => sensor.value,
+            other => panic!("Unexpected sensor value: {:?}", other),
+        };
+        assert!((value(&sensor_values) - expected(0)).abs() < 1e-9);
+
+        // Time is measured from the first update, so the start of the virtual clock doesn't
+        // matter.
+        let origin_ns = 1_700_000_000_000_000_000;
+        for elapsed_ns in (0..=2_000_000_000).step_by(125_000_000) {
+            change_generator.update_sensor_values(origin_ns + elapsed_ns, &mut sensor_values);
+            assert!(
+                (value(&sensor_values) - expected(elapsed_ns)).abs() < 1e-9,
+                "elapsed_ns: {}",
+                elapsed_ns
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sensor_seasonality_rejects_zero_period() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path()).await;
+        let definition: BuildingHierarchyDataGeneratorDefinition = serde_json::from_value(json!({
+            "room_sensors": [{
+                "kind": "NormalFloat",
+                "id": "temperature",
+                "seasonality": { "amplitude": 10.0, "period_ns": 0 }
+            }]
+        }))
+        .unwrap();
+
+        let err = BuildingHierarchyDataGeneratorSettings::new(
+            settings.id.clone(),
+            definition,
+            settings.input_storage.clone(),
+            settings.output_storage.clone(),
+            Vec::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("period_ns"));
+    }
+
+    fn mean_interval_ns(distribution: ChangeIntervalDistribution) -> f64 {
+        let mut generator =
+            ChangeIntervalGenerator::new(7, (1_000_000, 100_000.0, 0, u64::MAX), &distribution)
+                .unwrap();
+        let samples = 100_000;
+        (0..samples).map(|_| generator.next() as f64).sum::<f64>() / samples as f64
+    }
+
+    #[test]
+    fn test_change_interval_distribution_means() {
+        let within_2_pct = |mean: f64, expected: f64| (mean - expected).abs() / expected < 0.02;
+
+        let mean = mean_interval_ns(ChangeIntervalDistribution::Normal);
+        assert!(within_2_pct(mean, 1_000_000.0), "normal mean {}", mean);
+
+        // At 500 changes per second, changes are 2ms apart on average.
+        let mean = mean_interval_ns(ChangeIntervalDistribution::Exponential { rate: 500.0 });
+        assert!(within_2_pct(mean, 2_000_000.0), "exponential mean {}", mean);
+
+        let mean = mean_interval_ns(ChangeIntervalDistribution::Uniform {
+            min_ns: 1_000_000,
+            max_ns: 5_000_000,
+        });
+        assert!(within_2_pct(mean, 3_000_000.0), "uniform mean {}", mean);
+    }
+
+    #[test]
+    fn test_change_interval_distribution_clamped() {
+        let mut generator = ChangeIntervalGenerator::new(
+            7,
+            (0, 0.0, 1_500_000, 2_500_000),
+            &ChangeIntervalDistribution::Exponential { rate: 500.0 },
+        )
+        .unwrap();
+        assert!((0..1000)
+            .map(|_| generator.next())
+            .all(|interval| (1_500_000..=2_500_000).contains(&interval)));
+    }
+
+    #[test]
+    fn test_change_interval_distribution_validation() {
+        assert!(validate_change_interval_distribution(&ChangeIntervalDistribution::Normal).is_ok());
+        for rate in [0.0, -1.0, f64::NAN] {
+            let distribution = ChangeIntervalDistribution::Exponential { rate };
+            assert!(validate_change_interval_distribution(&distribution).is_err());
+        }
+        let distribution = ChangeIntervalDistribution::Uniform {
+            min_ns: 2,
+            max_ns: 1,
+        };
+        assert!(validate_change_interval_distribution(&distribution).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_duration_from_clock() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        let clock = Arc::new(ManualClock::new(5_000_000_000));
+        settings.change_count = 3;
+        settings.clock = clock.clone();
+        settings.spacing_mode = SpacingMode::None;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        assert_eq!(state.stats.actual_start_time_ns, 5_000_000_000);
+        assert_eq!(
+            state.next_event.as_ref().unwrap().reactivator_start_ns,
+            5_000_000_000
+        );
+
+        // Each change takes a quarter of a second on the clock.
+        while state.status == SourceChangeGeneratorStatus::Running {
+            clock.advance(250_000_000);
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(state.stats.actual_end_time_ns, 5_750_000_000);
+        let summary = BuildingHierarchyDataGeneratorResultSummary::from(&mut state);
+        assert_eq!(summary.run_duration_ns, 750_000_000);
+        assert_eq!(summary.run_duration_sec, 0.75);
+        assert_eq!(summary.processing_rate, 4.0);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path()).await;
+        settings.spacing_mode = SpacingMode::None;
+        let (mut state, mut change_rx_channel) = test_state(settings).await;
+
+        state.dispatchers = vec![Box::new(FailingDispatcher)];
+
+        let start = BuildingHierarchyDataGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        let message = change_rx_channel.recv().await.unwrap();
+        state.handle_change_stream_message(message).await;
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Error);
+        assert_eq!(state.stats.num_source_change_events, 0);
+        assert_eq!(state.error_messages.len(), 1);
+    }
+}
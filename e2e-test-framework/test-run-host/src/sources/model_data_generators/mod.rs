@@ -15,6 +15,7 @@
 use std::collections::HashSet;
 
 use async_trait::async_trait;
+use tokio::sync::broadcast;
 
 use building_hierarchy::BuildingHierarchyDataGenerator;
 use test_data_store::{
@@ -27,7 +28,10 @@ use test_data_store::{
 
 use super::{
     bootstrap_data_generators::{BootstrapData, BootstrapDataGenerator},
-    source_change_generators::{SourceChangeGenerator, SourceChangeGeneratorCommandResponse},
+    source_change_generators::{
+        SourceChangeEventSummary, SourceChangeGenerator, SourceChangeGeneratorCommandResponse,
+        SourceChangeGeneratorDescription,
+    },
 };
 
 pub mod building_hierarchy;
@@ -55,6 +59,10 @@ impl BootstrapDataGenerator for Box<dyn ModelDataGenerator + Send + Sync> {
 
 #[async_trait]
 impl SourceChangeGenerator for Box<dyn ModelDataGenerator + Send + Sync> {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        (**self).describe()
+    }
+
     async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         (**self).get_state().await
     }
@@ -63,8 +71,11 @@ impl SourceChangeGenerator for Box<dyn ModelDataGenerator + Send + Sync> {
         (**self).pause().await
     }
 
-    async fn reset(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
-        (**self).reset().await
+    async fn reset(
+        &self,
+        seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).reset(seed).await
     }
 
     async fn skip(
@@ -87,9 +98,25 @@ impl SourceChangeGenerator for Box<dyn ModelDataGenerator + Send + Sync> {
         (**self).step(steps, spacing_mode).await
     }
 
+    async fn step_to(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).step_to(target_seq, spacing_mode).await
+    }
+
+    async fn skip_to_end(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).skip_to_end().await
+    }
+
     async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         (**self).stop().await
     }
+
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        (**self).subscribe_events()
+    }
 }
 
 pub async fn create_model_data_generator(
@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use derive_more::Debug;
 
 use test_data_store::{
@@ -27,30 +28,46 @@ use test_data_store::{
     },
     test_run_storage::{TestRunSourceId, TestRunSourceStorage},
 };
+use tokio::sync::broadcast;
 
 use crate::sources::{
+    auto_pause_delay,
     bootstrap_data_generators::BootstrapData,
     model_data_generators::{create_model_data_generator, ModelDataGenerator},
-    source_change_generators::{SourceChangeGeneratorCommandResponse, SourceChangeGeneratorState},
-    SourceStartMode, TestRunSource, TestRunSourceConfig, TestRunSourceState,
+    source_change_generators::{
+        SourceChangeEventSummary, SourceChangeGeneratorCommandResponse,
+        SourceChangeGeneratorDescription, SourceChangeGeneratorError, SourceChangeGeneratorState,
+    },
+    stall_watcher::StallWatcher,
+    AutoPauseTimer, SourceStartMode, TestRunSource, TestRunSourceConfig, TestRunSourceState,
 };
 
 #[derive(Clone, Debug)]
 pub struct ModelTestRunSourceSettings {
+    pub auto_pause_after_ms: Option<u64>,
+    pub auto_pause_at: Option<DateTime<Utc>>,
+    pub fail_on_stall: bool,
     pub id: TestRunSourceId,
     pub source_change_dispatcher_defs: Vec<SourceChangeDispatcherDefinition>,
     pub model_data_generator_def: Option<ModelDataGeneratorDefinition>,
+    pub stall_timeout_ms: Option<u64>,
     pub start_mode: SourceStartMode,
+    pub start_jitter_ms: Option<u64>,
     pub subscribers: Vec<QueryId>,
 }
 
 impl ModelTestRunSourceSettings {
     pub fn new(cfg: &TestRunSourceConfig, def: &ModelTestSourceDefinition) -> anyhow::Result<Self> {
         let mut settings = Self {
+            auto_pause_after_ms: cfg.auto_pause_after_ms,
+            auto_pause_at: cfg.auto_pause_at,
+            fail_on_stall: cfg.fail_on_stall,
             id: TestRunSourceId::try_from(cfg)?,
             source_change_dispatcher_defs: def.common.source_change_dispatchers.clone(),
             model_data_generator_def: def.model_data_generator.clone(),
+            stall_timeout_ms: cfg.stall_timeout_ms,
             start_mode: cfg.start_mode.clone().unwrap_or_default(),
+            start_jitter_ms: cfg.start_jitter_ms,
             subscribers: def.common.subscribers.clone(),
         };
 
@@ -58,9 +75,15 @@ impl ModelTestRunSourceSettings {
             if let Some(mdg_overrides) = &overrides.model_data_generator {
                 match &mut settings.model_data_generator_def {
                     Some(ModelDataGeneratorDefinition::BuildingHierarchy(mdg_def)) => {
+                        if let Some(seed) = mdg_overrides.seed {
+                            mdg_def.common.seed = Some(seed);
+                        }
                         if let Some(spacing_mode) = &mdg_overrides.spacing_mode {
                             mdg_def.common.spacing_mode = spacing_mode.clone();
                         }
+                        if let Some(speed_factor) = mdg_overrides.speed_factor {
+                            mdg_def.common.speed_factor = speed_factor;
+                        }
                         if let Some(time_mode) = &mdg_overrides.time_mode {
                             mdg_def.common.time_mode = time_mode.clone();
                         }
@@ -84,9 +107,14 @@ impl ModelTestRunSourceSettings {
 
 #[derive(Debug)]
 pub struct ModelTestRunSource {
+    pub auto_pause_after_ms: Option<u64>,
+    pub auto_pause_at: Option<DateTime<Utc>>,
+    auto_pause_timer: AutoPauseTimer,
     pub id: TestRunSourceId,
-    pub model_data_generator: Option<Box<dyn ModelDataGenerator + Send + Sync>>,
+    pub model_data_generator: Option<Arc<dyn ModelDataGenerator + Send + Sync>>,
+    stall_watcher: StallWatcher,
     pub start_mode: SourceStartMode,
+    pub start_jitter_ms: Option<u64>,
     pub subscribers: Vec<QueryId>,
 }
 
@@ -109,9 +137,14 @@ impl ModelTestRunSource {
         .await?;
 
         let trs = Self {
+            auto_pause_after_ms: settings.auto_pause_after_ms,
+            auto_pause_at: settings.auto_pause_at,
+            auto_pause_timer: AutoPauseTimer::default(),
             id: settings.id.clone(),
-            model_data_generator,
+            model_data_generator: model_data_generator.map(Arc::from),
+            stall_watcher: StallWatcher::new(settings.stall_timeout_ms, settings.fail_on_stall),
             start_mode: settings.start_mode,
+            start_jitter_ms: settings.start_jitter_ms,
             subscribers: settings.subscribers,
         };
 
@@ -126,6 +159,18 @@ impl ModelTestRunSource {
 
 #[async_trait]
 impl TestRunSource for ModelTestRunSource {
+    fn describe_source_change_generator(&self) -> anyhow::Result<SourceChangeGeneratorDescription> {
+        match &self.model_data_generator {
+            Some(generator) => Ok(generator.describe()),
+            None => {
+                anyhow::bail!(
+                    "ModelGenerator not configured for ModelTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
     async fn get_bootstrap_data(
         &self,
         node_labels: &HashSet<String>,
@@ -158,7 +203,10 @@ impl TestRunSource for ModelTestRunSource {
         Ok(TestRunSourceState {
             id: self.id.clone(),
             source_change_generator: self.get_source_change_generator_state().await?,
+            stalled: self.stall_watcher.is_stalled(),
+            fail_on_stall: self.stall_watcher.fail_on_stall(),
             start_mode: self.start_mode.clone(),
+            start_jitter_ms: self.start_jitter_ms,
         })
     }
 
@@ -184,6 +232,7 @@ impl TestRunSource for ModelTestRunSource {
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.model_data_generator {
             Some(generator) => {
+                self.auto_pause_timer.cancel();
                 let response = generator.pause().await?;
                 Ok(response)
             }
@@ -198,10 +247,13 @@ impl TestRunSource for ModelTestRunSource {
 
     async fn reset_source_change_generator(
         &self,
+        seed: Option<u64>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.model_data_generator {
             Some(generator) => {
-                let response = generator.reset().await?;
+                self.auto_pause_timer.cancel();
+                self.stall_watcher.reset();
+                let response = generator.reset(seed).await?;
                 Ok(response)
             }
             None => {
@@ -238,6 +290,12 @@ impl TestRunSource for ModelTestRunSource {
         match &self.model_data_generator {
             Some(generator) => {
                 let response = generator.start().await?;
+                if let Some(after) = auto_pause_delay(self.auto_pause_after_ms, self.auto_pause_at)
+                {
+                    self.auto_pause_timer
+                        .arm(self.id.clone(), after, generator.clone());
+                }
+                self.stall_watcher.arm(self.id.clone(), generator.clone());
                 Ok(response)
             }
             None => {
@@ -268,11 +326,49 @@ impl TestRunSource for ModelTestRunSource {
         }
     }
 
+    async fn step_to_source_change_generator(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        match &self.model_data_generator {
+            Some(generator) => {
+                let response = generator.step_to(target_seq, spacing_mode).await?;
+                Ok(response)
+            }
+            None => {
+                anyhow::bail!(
+                    "ModelGenerator not configured for ModelTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
+    async fn skip_to_end_source_change_generator(
+        &self,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        match &self.model_data_generator {
+            Some(generator) => {
+                let response = generator.skip_to_end().await?;
+                Ok(response)
+            }
+            None => {
+                anyhow::bail!(
+                    "ModelGenerator not configured for ModelTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
     async fn stop_source_change_generator(
         &self,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.model_data_generator {
             Some(generator) => {
+                self.auto_pause_timer.cancel();
+                self.stall_watcher.cancel();
                 let response = generator.stop().await?;
                 Ok(response)
             }
@@ -285,6 +381,21 @@ impl TestRunSource for ModelTestRunSource {
         }
     }
 
+    fn subscribe_source_change_events(
+        &self,
+    ) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        match &self.model_data_generator {
+            Some(generator) => generator.subscribe_events(),
+            None => Err(
+                SourceChangeGeneratorError::EventSubscriptionsNotSupported(format!(
+                    "ModelTestRunSource {} without a ModelDataGenerator",
+                    self.id
+                ))
+                .into(),
+            ),
+        }
+    }
+
     fn set_test_run_host(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         // Pass TestRunHost to the model data generator
         if let Some(generator) = &self.model_data_generator {
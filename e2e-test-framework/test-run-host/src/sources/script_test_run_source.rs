@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 
 use test_data_store::{
     test_repo_storage::{
@@ -26,25 +27,34 @@ use test_data_store::{
     },
     test_run_storage::{TestRunSourceId, TestRunSourceStorage},
 };
+use tokio::sync::broadcast;
 
 use crate::sources::{
+    auto_pause_delay,
     bootstrap_data_generators::{
         create_bootstrap_data_generator, BootstrapData, BootstrapDataGenerator,
     },
     source_change_generators::{
-        create_source_change_generator, SourceChangeGenerator,
-        SourceChangeGeneratorCommandResponse, SourceChangeGeneratorState,
+        create_source_change_generator, SourceChangeEventSummary, SourceChangeGenerator,
+        SourceChangeGeneratorCommandResponse, SourceChangeGeneratorDescription,
+        SourceChangeGeneratorError, SourceChangeGeneratorState,
     },
-    SourceStartMode, TestRunSource, TestRunSourceConfig, TestRunSourceState,
+    stall_watcher::StallWatcher,
+    AutoPauseTimer, SourceStartMode, TestRunSource, TestRunSourceConfig, TestRunSourceState,
 };
 
 #[derive(Clone, Debug)]
 pub struct ScriptTestRunSourceSettings {
+    pub auto_pause_after_ms: Option<u64>,
+    pub auto_pause_at: Option<DateTime<Utc>>,
     pub bootstrap_data_generator_def: Option<BootstrapDataGeneratorDefinition>,
+    pub fail_on_stall: bool,
     pub id: TestRunSourceId,
     pub source_change_dispatcher_defs: Vec<SourceChangeDispatcherDefinition>,
     pub source_change_generator_def: Option<SourceChangeGeneratorDefinition>,
+    pub stall_timeout_ms: Option<u64>,
     pub start_mode: SourceStartMode,
+    pub start_jitter_ms: Option<u64>,
     pub subscribers: Vec<QueryId>,
 }
 
@@ -54,17 +64,27 @@ impl ScriptTestRunSourceSettings {
         def: &ScriptTestSourceDefinition,
     ) -> anyhow::Result<Self> {
         let mut settings = Self {
+            auto_pause_after_ms: cfg.auto_pause_after_ms,
+            auto_pause_at: cfg.auto_pause_at,
             bootstrap_data_generator_def: def.bootstrap_data_generator.clone(),
+            fail_on_stall: cfg.fail_on_stall,
             id: TestRunSourceId::try_from(cfg)?,
             source_change_dispatcher_defs: def.common.source_change_dispatchers.clone(),
             source_change_generator_def: def.source_change_generator.clone(),
+            stall_timeout_ms: cfg.stall_timeout_ms,
             start_mode: cfg.start_mode.clone().unwrap_or_default(),
+            start_jitter_ms: cfg.start_jitter_ms,
             subscribers: def.common.subscribers.clone(),
         };
 
         if let Some(overrides) = &cfg.test_run_overrides {
             if let Some(bdg_overrides) = &overrides.bootstrap_data_generator {
                 match &mut settings.bootstrap_data_generator_def {
+                    Some(BootstrapDataGeneratorDefinition::File(bs_def)) => {
+                        if let Some(time_mode) = &bdg_overrides.time_mode {
+                            bs_def.common.time_mode = time_mode.clone();
+                        }
+                    }
                     Some(BootstrapDataGeneratorDefinition::Script(bs_def)) => {
                         if let Some(time_mode) = &bdg_overrides.time_mode {
                             bs_def.common.time_mode = time_mode.clone();
@@ -80,10 +100,60 @@ impl ScriptTestRunSourceSettings {
                         if let Some(spacing_mode) = &scg_overrides.spacing_mode {
                             sc_def.common.spacing_mode = spacing_mode.clone();
                         }
+                        if let Some(speed_factor) = scg_overrides.speed_factor {
+                            sc_def.common.speed_factor = speed_factor;
+                        }
                         if let Some(time_mode) = &scg_overrides.time_mode {
                             sc_def.common.time_mode = time_mode.clone();
                         }
                     }
+                    Some(SourceChangeGeneratorDefinition::MergedScript(ms_def)) => {
+                        if let Some(spacing_mode) = &scg_overrides.spacing_mode {
+                            ms_def.common.spacing_mode = spacing_mode.clone();
+                        }
+                        if let Some(speed_factor) = scg_overrides.speed_factor {
+                            ms_def.common.speed_factor = speed_factor;
+                        }
+                        if let Some(time_mode) = &scg_overrides.time_mode {
+                            ms_def.common.time_mode = time_mode.clone();
+                        }
+                    }
+                    Some(SourceChangeGeneratorDefinition::CsvFile(csv_def)) => {
+                        if let Some(spacing_mode) = &scg_overrides.spacing_mode {
+                            csv_def.common.spacing_mode = spacing_mode.clone();
+                        }
+                        if let Some(speed_factor) = scg_overrides.speed_factor {
+                            csv_def.common.speed_factor = speed_factor;
+                        }
+                        if let Some(time_mode) = &scg_overrides.time_mode {
+                            csv_def.common.time_mode = time_mode.clone();
+                        }
+                    }
+                    Some(SourceChangeGeneratorDefinition::DebeziumFile(debezium_def)) => {
+                        if let Some(spacing_mode) = &scg_overrides.spacing_mode {
+                            debezium_def.common.spacing_mode = spacing_mode.clone();
+                        }
+                        if let Some(speed_factor) = scg_overrides.speed_factor {
+                            debezium_def.common.speed_factor = speed_factor;
+                        }
+                        if let Some(time_mode) = &scg_overrides.time_mode {
+                            debezium_def.common.time_mode = time_mode.clone();
+                        }
+                    }
+                    Some(SourceChangeGeneratorDefinition::HttpPoll(hp_def)) => {
+                        if let Some(spacing_mode) = &scg_overrides.spacing_mode {
+                            hp_def.common.spacing_mode = spacing_mode.clone();
+                        }
+                        if let Some(time_mode) = &scg_overrides.time_mode {
+                            hp_def.common.time_mode = time_mode.clone();
+                        }
+                    }
+                    Some(SourceChangeGeneratorDefinition::LogTail(lt_def)) => {
+                        // Changes are read as they are appended, so only the time mode applies.
+                        if let Some(time_mode) = &scg_overrides.time_mode {
+                            lt_def.time_mode = time_mode.clone();
+                        }
+                    }
                     None => {}
                 }
             }
@@ -103,10 +173,15 @@ impl ScriptTestRunSourceSettings {
 
 #[derive(Debug)]
 pub struct ScriptTestRunSource {
+    pub auto_pause_after_ms: Option<u64>,
+    pub auto_pause_at: Option<DateTime<Utc>>,
+    auto_pause_timer: AutoPauseTimer,
     pub bootstrap_data_generator: Option<Box<dyn BootstrapDataGenerator + Send + Sync>>,
     pub id: TestRunSourceId,
-    pub source_change_generator: Option<Box<dyn SourceChangeGenerator + Send + Sync>>,
+    pub source_change_generator: Option<Arc<dyn SourceChangeGenerator + Send + Sync>>,
+    stall_watcher: StallWatcher,
     pub start_mode: SourceStartMode,
+    pub start_jitter_ms: Option<u64>,
     pub subscribers: Vec<QueryId>,
 }
 
@@ -137,10 +212,15 @@ impl ScriptTestRunSource {
         .await?;
 
         let trs = Self {
+            auto_pause_after_ms: definition.auto_pause_after_ms,
+            auto_pause_at: definition.auto_pause_at,
+            auto_pause_timer: AutoPauseTimer::default(),
             id: definition.id.clone(),
             bootstrap_data_generator,
-            source_change_generator,
+            source_change_generator: source_change_generator.map(Arc::from),
+            stall_watcher: StallWatcher::new(definition.stall_timeout_ms, definition.fail_on_stall),
             start_mode: definition.start_mode,
+            start_jitter_ms: definition.start_jitter_ms,
             subscribers: definition.subscribers,
         };
 
@@ -155,6 +235,18 @@ impl ScriptTestRunSource {
 
 #[async_trait]
 impl TestRunSource for ScriptTestRunSource {
+    fn describe_source_change_generator(&self) -> anyhow::Result<SourceChangeGeneratorDescription> {
+        match &self.source_change_generator {
+            Some(generator) => Ok(generator.describe()),
+            None => {
+                anyhow::bail!(
+                    "SourceChangeGenerator not configured for ScriptTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
     async fn get_bootstrap_data(
         &self,
         node_labels: &HashSet<String>,
@@ -187,7 +279,10 @@ impl TestRunSource for ScriptTestRunSource {
         Ok(TestRunSourceState {
             id: self.id.clone(),
             source_change_generator: self.get_source_change_generator_state().await?,
+            stalled: self.stall_watcher.is_stalled(),
+            fail_on_stall: self.stall_watcher.fail_on_stall(),
             start_mode: self.start_mode.clone(),
+            start_jitter_ms: self.start_jitter_ms,
         })
     }
 
@@ -213,6 +308,7 @@ impl TestRunSource for ScriptTestRunSource {
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.source_change_generator {
             Some(generator) => {
+                self.auto_pause_timer.cancel();
                 let response = generator.pause().await?;
                 Ok(response)
             }
@@ -227,10 +323,13 @@ impl TestRunSource for ScriptTestRunSource {
 
     async fn reset_source_change_generator(
         &self,
+        seed: Option<u64>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.source_change_generator {
             Some(generator) => {
-                let response = generator.reset().await?;
+                self.auto_pause_timer.cancel();
+                self.stall_watcher.reset();
+                let response = generator.reset(seed).await?;
                 Ok(response)
             }
             None => {
@@ -267,6 +366,12 @@ impl TestRunSource for ScriptTestRunSource {
         match &self.source_change_generator {
             Some(generator) => {
                 let response = generator.start().await?;
+                if let Some(after) = auto_pause_delay(self.auto_pause_after_ms, self.auto_pause_at)
+                {
+                    self.auto_pause_timer
+                        .arm(self.id.clone(), after, generator.clone());
+                }
+                self.stall_watcher.arm(self.id.clone(), generator.clone());
                 Ok(response)
             }
             None => {
@@ -297,11 +402,49 @@ impl TestRunSource for ScriptTestRunSource {
         }
     }
 
+    async fn step_to_source_change_generator(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        match &self.source_change_generator {
+            Some(generator) => {
+                let response = generator.step_to(target_seq, spacing_mode).await?;
+                Ok(response)
+            }
+            None => {
+                anyhow::bail!(
+                    "SourceChangeGenerator not configured for ScriptTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
+    async fn skip_to_end_source_change_generator(
+        &self,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        match &self.source_change_generator {
+            Some(generator) => {
+                let response = generator.skip_to_end().await?;
+                Ok(response)
+            }
+            None => {
+                anyhow::bail!(
+                    "SourceChangeGenerator not configured for ScriptTestRunSource: {:?}",
+                    &self.id
+                );
+            }
+        }
+    }
+
     async fn stop_source_change_generator(
         &self,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         match &self.source_change_generator {
             Some(generator) => {
+                self.auto_pause_timer.cancel();
+                self.stall_watcher.cancel();
                 let response = generator.stop().await?;
                 Ok(response)
             }
@@ -314,6 +457,21 @@ impl TestRunSource for ScriptTestRunSource {
         }
     }
 
+    fn subscribe_source_change_events(
+        &self,
+    ) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        match &self.source_change_generator {
+            Some(generator) => generator.subscribe_events(),
+            None => Err(
+                SourceChangeGeneratorError::EventSubscriptionsNotSupported(format!(
+                    "ScriptTestRunSource {} without a SourceChangeGenerator",
+                    self.id
+                ))
+                .into(),
+            ),
+        }
+    }
+
     fn set_test_run_host(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         // Pass TestRunHost to the source change generator
         if let Some(generator) = &self.source_change_generator {
@@ -321,3 +479,159 @@ impl TestRunSource for ScriptTestRunSource {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicU64, Ordering},
+        time::Duration,
+    };
+
+    use test_data_store::test_run_storage::TestRunId;
+
+    use crate::sources::source_change_generators::SourceChangeGeneratorStatus;
+
+    use super::*;
+
+    // Counts the pause commands it is sent.
+    #[derive(Debug, Default)]
+    struct PauseCountingGenerator {
+        pauses: AtomicU64,
+    }
+
+    impl PauseCountingGenerator {
+        fn response(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            Ok(SourceChangeGeneratorCommandResponse {
+                result: Ok(()),
+                state: SourceChangeGeneratorState {
+                    event_count: 0,
+                    state: serde_json::Value::Null,
+                    status: SourceChangeGeneratorStatus::Running,
+                },
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SourceChangeGenerator for PauseCountingGenerator {
+        async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+
+        async fn pause(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.pauses.fetch_add(1, Ordering::SeqCst);
+            self.response()
+        }
+
+        async fn reset(
+            &self,
+            _seed: Option<u64>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+
+        async fn skip(
+            &self,
+            _skips: u64,
+            _spacing_mode: Option<SpacingMode>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+
+        async fn start(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+
+        async fn step(
+            &self,
+            _steps: u64,
+            _spacing_mode: Option<SpacingMode>,
+        ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+
+        async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+            self.response()
+        }
+    }
+
+    fn create_source(
+        generator: Arc<PauseCountingGenerator>,
+        auto_pause_after_ms: Option<u64>,
+        auto_pause_at: Option<DateTime<Utc>>,
+    ) -> ScriptTestRunSource {
+        let test_run_id = TestRunId::new("repo", "test", "run");
+        ScriptTestRunSource {
+            auto_pause_after_ms,
+            auto_pause_at,
+            auto_pause_timer: AutoPauseTimer::default(),
+            bootstrap_data_generator: None,
+            id: TestRunSourceId::new(&test_run_id, "source-001"),
+            source_change_generator: Some(generator),
+            stall_watcher: StallWatcher::new(None, false),
+            start_mode: SourceStartMode::Manual,
+            start_jitter_ms: None,
+            subscribers: Vec::new(),
+        }
+    }
+
+    // Moves tokio's paused clock forward, letting the auto-pause timer task start sleeping
+    // before the clock moves and run once it has.
+    async fn advance(ms: u64) {
+        tokio::task::yield_now().await;
+        tokio::time::advance(Duration::from_millis(ms)).await;
+        tokio::task::yield_now().await;
+    }
+
+    #[tokio::test]
+    async fn test_manual_pause_cancels_auto_pause() {
+        tokio::time::pause();
+        let generator = Arc::new(PauseCountingGenerator::default());
+        let source = create_source(generator.clone(), Some(200), None);
+
+        // A manual pause before the timer fires cancels it.
+        source.start_source_change_generator().await.unwrap();
+        advance(50).await;
+        source.pause_source_change_generator().await.unwrap();
+        advance(250).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 1);
+
+        // Resuming re-arms the timer for the full duration, so the resumed run isn't paused
+        // early, and is auto-paused once the duration has passed.
+        source.start_source_change_generator().await.unwrap();
+        advance(150).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 1);
+        advance(100).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_auto_pause_at() {
+        tokio::time::pause();
+        let generator = Arc::new(PauseCountingGenerator::default());
+
+        // The delay is taken from the wall clock when the source starts, and then timed on
+        // tokio's paused clock.
+        let at = Utc::now() + chrono::Duration::milliseconds(200);
+        let source = create_source(generator.clone(), None, Some(at));
+        source.start_source_change_generator().await.unwrap();
+        advance(150).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 0);
+        advance(100).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 1);
+
+        // With auto_pause_after_ms too, whichever comes first pauses the source.
+        let at = Utc::now() + chrono::Duration::seconds(10);
+        let source = create_source(generator.clone(), Some(100), Some(at));
+        source.start_source_change_generator().await.unwrap();
+        advance(150).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 2);
+
+        // A time that has already passed doesn't pause a source that is started again.
+        let at = Utc::now() - chrono::Duration::seconds(1);
+        let source = create_source(generator.clone(), None, Some(at));
+        source.start_source_change_generator().await.unwrap();
+        advance(1000).await;
+        assert_eq!(generator.pauses.load(Ordering::SeqCst), 2);
+    }
+}
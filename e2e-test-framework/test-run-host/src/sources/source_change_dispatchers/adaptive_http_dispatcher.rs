@@ -26,7 +26,9 @@ use test_data_store::{
 };
 
 use crate::utils::{AdaptiveBatcher, AdaptiveBatchConfig};
-use super::SourceChangeDispatcher;
+use super::{
+    event_field_names::EventFieldNames, http_dispatcher::build_headers, SourceChangeDispatcher,
+};
 
 use log::{debug, error, info};
 use serde::{Deserialize, Serialize};
@@ -34,18 +36,15 @@ use serde::{Deserialize, Serialize};
 /// Batch event request that wraps multiple events
 #[derive(Debug, Serialize, Deserialize)]
 struct BatchEventRequest {
-    events: Vec<HttpChangeEvent>,
+    events: Vec<serde_json::Value>,
 }
 
-/// HTTP change event format matching Drasi Server's expectation
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct HttpChangeEvent {
-    op: String,
-    #[serde(rename = "reactivatorStart_ns", skip_serializing_if = "Option::is_none")]
-    reactivator_start_ns: Option<i64>,
-    #[serde(rename = "reactivatorEnd_ns", skip_serializing_if = "Option::is_none")]
-    reactivator_end_ns: Option<i64>,
-    payload: serde_json::Value,
+/// Converts an event to the JSON sent to Drasi Server, applying any field renames
+fn to_http_event(
+    event: &SourceChangeEvent,
+    event_field_names: &EventFieldNames,
+) -> serde_json::Result<serde_json::Value> {
+    serde_json::to_value(event_field_names.apply(event))
 }
 
 /// Response from the HTTP endpoint
@@ -72,6 +71,7 @@ pub struct AdaptiveHttpSourceChangeDispatcher {
     batcher_handle: Option<Arc<Mutex<Option<JoinHandle<()>>>>>,
     client: Arc<Client>,
     batch_enabled: bool,
+    event_field_names: EventFieldNames,
 }
 
 impl AdaptiveHttpSourceChangeDispatcher {
@@ -118,8 +118,11 @@ impl AdaptiveHttpSourceChangeDispatcher {
         // Create HTTP client with connection pooling (HTTP/1.1 for compatibility)
         let client = Client::builder()
             .timeout(Duration::from_secs(definition.timeout_seconds.unwrap_or(30)))
-            .pool_idle_timeout(Duration::from_secs(90))
-            .pool_max_idle_per_host(10)
+            .pool_idle_timeout(Duration::from_secs(
+                definition.pool_idle_timeout_seconds.unwrap_or(90),
+            ))
+            .pool_max_idle_per_host(definition.pool_max_idle_per_host.unwrap_or(10))
+            .default_headers(build_headers(definition)?)
             // Don't use http2_prior_knowledge as it can cause broken pipe errors
             .build()
             .unwrap_or_else(|_| Client::new());
@@ -136,6 +139,7 @@ impl AdaptiveHttpSourceChangeDispatcher {
             batcher_handle: None,
             client: Arc::new(client),
             batch_enabled,
+            event_field_names: EventFieldNames::default(),
         })
     }
     
@@ -157,6 +161,7 @@ impl AdaptiveHttpSourceChangeDispatcher {
         let adaptive_config = self.adaptive_config.clone();
         let client = self.client.clone();
         let batch_enabled = self.batch_enabled;
+        let event_field_names = self.event_field_names.clone();
         
         // Spawn batcher task
         let handle = tokio::spawn(async move {
@@ -177,30 +182,13 @@ impl AdaptiveHttpSourceChangeDispatcher {
                 
                 debug!("Adaptive HTTP batch ready with {} events", batch_size);
                 
-                // Convert events to HttpChangeEvent format
-                let http_events: Vec<HttpChangeEvent> = batch.into_iter()
-                    .filter_map(|event| {
-                        // Convert SourceChangeEvent to HttpChangeEvent
-                        // The payload already has the correct structure, just convert it to Value
-                        match serde_json::to_value(&event.payload) {
-                            Ok(payload_value) => {
-                                // Ensure the payload has the expected structure
-                                if payload_value.get("source").is_some() {
-                                    Some(HttpChangeEvent {
-                                        op: event.op,
-                                        reactivator_start_ns: Some(event.reactivator_start_ns as i64),
-                                        reactivator_end_ns: Some(event.reactivator_end_ns as i64),
-                                        payload: payload_value,
-                                    })
-                                } else {
-                                    error!("Payload missing 'source' field: {:?}", payload_value);
-                                    None
-                                }
-                            },
-                            Err(e) => {
-                                error!("Failed to serialize event payload: {}", e);
-                                None
-                            }
+                // Convert events to the JSON sent to Drasi Server
+                let http_events: Vec<serde_json::Value> = batch.iter()
+                    .filter_map(|event| match to_http_event(event, &event_field_names) {
+                        Ok(http_event) => Some(http_event),
+                        Err(e) => {
+                            error!("Failed to serialize event: {}", e);
+                            None
                         }
                     })
                     .collect();
@@ -295,15 +283,7 @@ impl AdaptiveHttpSourceChangeDispatcher {
     async fn send_single_event(&self, event: &SourceChangeEvent) -> anyhow::Result<()> {
         let url = format!("{}:{}{}", self.url, self.port, self.endpoint);
         
-        // Convert to HttpChangeEvent format
-        let payload = serde_json::to_value(&event.payload)?;
-        
-        let http_event = HttpChangeEvent {
-            op: event.op.clone(),
-            reactivator_start_ns: Some(event.reactivator_start_ns as i64),
-            reactivator_end_ns: Some(event.reactivator_end_ns as i64),
-            payload,
-        };
+        let http_event = to_http_event(event, &self.event_field_names)?;
         
         let response = self.client
             .post(&url)
@@ -377,4 +357,105 @@ impl SourceChangeDispatcher for AdaptiveHttpSourceChangeDispatcher {
         
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::PathBuf, sync::Mutex as StdMutex};
+
+    use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+    use test_data_store::test_run_storage::{TestRunId, TestRunSourceId};
+
+    use super::*;
+
+    // Records the body of every event request.
+    async fn start_recording_server() -> (u16, Arc<StdMutex<Vec<serde_json::Value>>>) {
+        let bodies = Arc::new(StdMutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/sources/test-source/events",
+                post(
+                    |State(bodies): State<Arc<StdMutex<Vec<serde_json::Value>>>>,
+                     Json(body): Json<serde_json::Value>| async move {
+                        bodies.lock().unwrap().push(body);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(bodies.clone());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        (port, bodies)
+    }
+
+    #[tokio::test]
+    async fn test_event_field_names_are_applied() {
+        let (port, bodies) = start_recording_server().await;
+
+        let definition = HttpSourceChangeDispatcherDefinition {
+            url: "http://127.0.0.1".to_string(),
+            port,
+            endpoint: None,
+            timeout_seconds: Some(5),
+            batch_events: Some(false),
+            adaptive_enabled: Some(true),
+            batch_size: None,
+            batch_timeout_ms: None,
+            source_id: None,
+            headers: HashMap::new(),
+            bearer_token: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_seconds: None,
+            propagate_trace_context: false,
+        };
+        let storage = TestRunSourceStorage {
+            id: TestRunSourceId::new(&TestRunId::new("repo", "test", "run"), "test-source"),
+            path: PathBuf::from("/tmp"),
+            source_change_path: PathBuf::from("/tmp"),
+        };
+        let mut dispatcher = AdaptiveHttpSourceChangeDispatcher::new(&definition, storage).unwrap();
+        dispatcher.set_event_field_names(EventFieldNames::new(HashMap::from([
+            ("op".to_string(), "operation".to_string()),
+            ("after".to_string(), "new".to_string()),
+        ])));
+
+        let event = SourceChangeEvent::try_from(
+            r#"{
+                "op": "i",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "db", "table": "node", "ts_ns": 0, "lsn": 1 },
+                    "before": {},
+                    "after": { "id": "n1", "labels": ["Room"], "properties": {} }
+                }
+            }"#,
+        )
+        .unwrap();
+        dispatcher
+            .dispatch_source_change_events(vec![&event])
+            .await
+            .unwrap();
+
+        // Closing waits for the batcher to send the event.
+        dispatcher.close().await.unwrap();
+
+        let bodies = bodies.lock().unwrap().clone();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0]["operation"], "i");
+        assert!(bodies[0].get("op").is_none());
+        assert_eq!(bodies[0]["payload"]["new"]["id"], "n1");
+        assert!(bodies[0]["payload"].get("after").is_none());
+    }
+}
@@ -22,7 +22,7 @@ use test_data_store::{
     test_run_storage::TestRunSourceStorage,
 };
 
-use super::SourceChangeDispatcher;
+use super::{event_field_names::EventFieldNames, SourceChangeDispatcher};
 
 #[derive(Debug)]
 pub struct DaprSourceChangeDispatcherSettings {
@@ -54,6 +54,7 @@ impl DaprSourceChangeDispatcherSettings {
 
 pub struct DaprSourceChangeDispatcher {
     _settings: DaprSourceChangeDispatcherSettings,
+    event_field_names: EventFieldNames,
     publisher: Option<DaprHttpPublisher>,
 }
 
@@ -80,6 +81,7 @@ impl DaprSourceChangeDispatcher {
 
         Ok(DaprSourceChangeDispatcher {
             _settings: settings,
+            event_field_names: EventFieldNames::default(),
             publisher: Some(publisher),
         })
     }
@@ -100,7 +102,7 @@ impl SourceChangeDispatcher for DaprSourceChangeDispatcher {
 
         let publisher = self.publisher.as_mut().unwrap();
 
-        let data = serde_json::to_value(events)?;
+        let data = serde_json::to_value(self.event_field_names.apply_all(&events))?;
 
         let headers: std::collections::HashMap<String, String> = std::collections::HashMap::new();
         // let traceparent = "000".to_string();
@@ -116,4 +118,8 @@ impl SourceChangeDispatcher for DaprSourceChangeDispatcher {
             }
         }
     }
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
 }
@@ -22,7 +22,7 @@ use test_data_store::{
     test_run_storage::{TestRunDrasiServerId, TestRunSourceStorage},
 };
 
-use super::SourceChangeDispatcher;
+use super::{event_field_names::EventFieldNames, SourceChangeDispatcher};
 
 #[derive(Debug)]
 pub struct DrasiServerApiSourceChangeDispatcherSettings {
@@ -55,6 +55,7 @@ impl DrasiServerApiSourceChangeDispatcherSettings {
 pub struct DrasiServerApiSourceChangeDispatcher {
     settings: DrasiServerApiSourceChangeDispatcherSettings,
     client: Client,
+    event_field_names: EventFieldNames,
     test_run_host: Option<std::sync::Arc<crate::TestRunHost>>,
 }
 
@@ -81,6 +82,7 @@ impl DrasiServerApiSourceChangeDispatcher {
         Ok(Self {
             settings,
             client,
+            event_field_names: EventFieldNames::default(),
             test_run_host: None,
         })
     }
@@ -164,15 +166,17 @@ impl SourceChangeDispatcher for DrasiServerApiSourceChangeDispatcher {
         );
 
         if self.settings.batch_events {
+            let body = self.event_field_names.apply_all(&events);
+
             // Log request body at debug level
             log::debug!(
                 "Drasi Server API dispatcher sending batch request to {}: {}",
                 url,
-                serde_json::to_string_pretty(&events)
+                serde_json::to_string_pretty(&body)
                     .unwrap_or_else(|e| format!("Failed to serialize: {}", e))
             );
 
-            let response = match self.client.post(&url).json(&events).send().await {
+            let response = match self.client.post(&url).json(&body).send().await {
                 Ok(resp) => resp,
                 Err(e) => {
                     log::error!("Failed to connect to {}: {}", url, e);
@@ -209,15 +213,17 @@ impl SourceChangeDispatcher for DrasiServerApiSourceChangeDispatcher {
         } else {
             let event_count = events.len();
             for event in &events {
+                let body = self.event_field_names.apply(event);
+
                 // Log request body at debug level
                 log::debug!(
                     "Drasi Server API dispatcher sending individual event to {}: {}",
                     url,
-                    serde_json::to_string_pretty(event)
+                    serde_json::to_string_pretty(&body)
                         .unwrap_or_else(|e| format!("Failed to serialize: {}", e))
                 );
 
-                let response = self.client.post(&url).json(event).send().await?;
+                let response = self.client.post(&url).json(&body).send().await?;
 
                 let status = response.status();
                 let response_body = response.text().await.unwrap_or_default();
@@ -250,4 +256,8 @@ impl SourceChangeDispatcher for DrasiServerApiSourceChangeDispatcher {
 
         Ok(())
     }
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
 }
@@ -24,6 +24,8 @@ use test_data_store::{
 
 use super::SourceChangeDispatcher;
 
+pub const DEFAULT_CHANNEL_BUFFER_SIZE: usize = 1024;
+
 #[derive(Debug)]
 pub struct DrasiServerChannelSourceChangeDispatcherSettings {
     pub drasi_server_id: TestRunDrasiServerId,
@@ -45,7 +47,9 @@ impl DrasiServerChannelSourceChangeDispatcherSettings {
         Ok(Self {
             drasi_server_id,
             source_id: definition.source_id.clone(),
-            buffer_size: definition.buffer_size.unwrap_or(1024),
+            buffer_size: definition
+                .buffer_size
+                .unwrap_or(DEFAULT_CHANNEL_BUFFER_SIZE),
         })
     }
 }
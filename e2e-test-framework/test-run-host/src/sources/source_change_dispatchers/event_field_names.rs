@@ -0,0 +1,176 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use test_data_store::scripts::SourceChangeEvent;
+
+// Renames the keys of SourceChangeEvents as dispatchers serialize them, so the same generator
+// can drive source connectors that expect different envelope field names, e.g. "operation"
+// instead of "op" or "old"/"new" instead of "before"/"after". Renames apply to the keys of the
+// event, its payload, the payload's source, and the before and after elements, but never to
+// the properties of an element. Keys that aren't mapped pass through unchanged.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct EventFieldNames {
+    renames: HashMap<String, String>,
+}
+
+impl EventFieldNames {
+    pub fn new(renames: HashMap<String, String>) -> Self {
+        Self { renames }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty()
+    }
+
+    // Wraps the event so that serializing it produces the renamed keys.
+    pub fn apply<'a>(&'a self, event: &'a SourceChangeEvent) -> RenamedSourceChangeEvent<'a> {
+        RenamedSourceChangeEvent {
+            event,
+            field_names: self,
+        }
+    }
+
+    pub fn apply_all<'a>(
+        &'a self,
+        events: &[&'a SourceChangeEvent],
+    ) -> Vec<RenamedSourceChangeEvent<'a>> {
+        events.iter().map(|event| self.apply(event)).collect()
+    }
+
+    fn rename_event(&self, event: &mut Value) {
+        if let Value::Object(event) = event {
+            if let Some(Value::Object(payload)) = event.get_mut("payload") {
+                for key in ["source", "before", "after"] {
+                    if let Some(Value::Object(inner)) = payload.get_mut(key) {
+                        self.rename_keys(inner);
+                    }
+                }
+                self.rename_keys(payload);
+            }
+            self.rename_keys(event);
+        }
+    }
+
+    // Builds a new map rather than renaming in place, so that swapping two names works.
+    fn rename_keys(&self, map: &mut Map<String, Value>) {
+        *map = std::mem::take(map)
+            .into_iter()
+            .map(|(key, value)| match self.renames.get(&key) {
+                Some(renamed) => (renamed.clone(), value),
+                None => (key, value),
+            })
+            .collect();
+    }
+}
+
+pub struct RenamedSourceChangeEvent<'a> {
+    event: &'a SourceChangeEvent,
+    field_names: &'a EventFieldNames,
+}
+
+impl Serialize for RenamedSourceChangeEvent<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Without renames the event is serialized exactly as it would be on its own.
+        if self.field_names.is_empty() {
+            return self.event.serialize(serializer);
+        }
+
+        let mut value = serde_json::to_value(self.event).map_err(serde::ser::Error::custom)?;
+        self.field_names.rename_event(&mut value);
+        value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn create_event() -> SourceChangeEvent {
+        SourceChangeEvent::try_from(
+            r#"{
+                "op": "u",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "db", "table": "node", "ts_ns": 10, "lsn": 1 },
+                    "before": { "id": "n1", "labels": ["Room"], "properties": { "op": 1 } },
+                    "after": { "id": "n1", "labels": ["Room"], "properties": { "op": 2 } }
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    fn renames(pairs: &[(&str, &str)]) -> EventFieldNames {
+        EventFieldNames::new(
+            pairs
+                .iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_renamed_keys() {
+        let field_names = renames(&[
+            ("op", "operation"),
+            ("before", "old"),
+            ("after", "new"),
+            ("ts_ns", "timestamp"),
+        ]);
+        let event = create_event();
+
+        assert_eq!(
+            serde_json::to_value(field_names.apply(&event)).unwrap(),
+            json!({
+                "operation": "u",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "db", "table": "node", "timestamp": 10, "lsn": 1 },
+                    "old": { "id": "n1", "labels": ["Room"], "properties": { "op": 1 } },
+                    "new": { "id": "n1", "labels": ["Room"], "properties": { "op": 2 } }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_swapped_keys() {
+        let field_names = renames(&[("before", "after"), ("after", "before")]);
+        let event = create_event();
+
+        let value = serde_json::to_value(field_names.apply(&event)).unwrap();
+        assert_eq!(value["payload"]["before"]["properties"]["op"], 2);
+        assert_eq!(value["payload"]["after"]["properties"]["op"], 1);
+    }
+
+    #[test]
+    fn test_no_renames() {
+        let field_names = EventFieldNames::default();
+        let event = create_event();
+
+        assert_eq!(
+            serde_json::to_string(&field_names.apply_all(&[&event])).unwrap(),
+            serde_json::to_string(&vec![&event]).unwrap()
+        );
+    }
+}
@@ -19,9 +19,16 @@ use test_data_store::{
     test_run_storage::TestRunSourceStorage,
 };
 
-use super::SourceChangeDispatcher;
+use crate::env_substitution::{process_env_lookup, substitute_env_vars_in_str};
 
-use reqwest::Client;
+use super::{
+    event_field_names::EventFieldNames, trace_context::TraceContext, SourceChangeDispatcher,
+};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION},
+    Client,
+};
 use std::time::Duration;
 
 use tracing::{debug, error, trace};
@@ -34,6 +41,9 @@ pub struct HttpSourceChangeDispatcherSettings {
     pub timeout_seconds: u64,
     pub batch_events: bool,
     pub source_id: String,
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_seconds: u64,
+    pub headers: HeaderMap,
 }
 
 impl HttpSourceChangeDispatcherSettings {
@@ -55,6 +65,9 @@ impl HttpSourceChangeDispatcherSettings {
             timeout_seconds: definition.timeout_seconds.unwrap_or(30),
             batch_events: definition.batch_events.unwrap_or(true),
             source_id,
+            pool_max_idle_per_host: definition.pool_max_idle_per_host.unwrap_or(10),
+            pool_idle_timeout_seconds: definition.pool_idle_timeout_seconds.unwrap_or(90),
+            headers: build_headers(definition)?,
         })
     }
 
@@ -63,9 +76,41 @@ impl HttpSourceChangeDispatcherSettings {
     }
 }
 
+// Resolves the definition's headers and bearer token, substituting ${VAR} references from the
+// environment. A reference to an unset variable is an error, so a missing secret fails
+// dispatcher creation instead of sending unauthenticated requests.
+pub(crate) fn build_headers(
+    definition: &HttpSourceChangeDispatcherDefinition,
+) -> anyhow::Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in definition.headers.iter() {
+        let value = substitute_env_vars_in_str(value, &process_env_lookup).map_err(|e| {
+            anyhow::anyhow!("Invalid value for HTTP dispatcher header {}: {}", name, e)
+        })?;
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes())?,
+            HeaderValue::from_str(&value)?,
+        );
+    }
+
+    if let Some(token) = &definition.bearer_token {
+        let token = substitute_env_vars_in_str(token.expose_secret(), &process_env_lookup)
+            .map_err(|e| anyhow::anyhow!("Invalid HTTP dispatcher bearer_token: {}", e))?;
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token))?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    Ok(headers)
+}
+
 pub struct HttpSourceChangeDispatcher {
     settings: HttpSourceChangeDispatcherSettings,
-    client: Client,
+    // A single client is used for the life of the dispatcher so its pooled connections are
+    // reused across dispatches. It is dropped on close.
+    client: Option<Client>,
+    event_field_names: EventFieldNames,
+    trace_context: Option<TraceContext>,
 }
 
 impl HttpSourceChangeDispatcher {
@@ -87,9 +132,21 @@ impl HttpSourceChangeDispatcher {
 
         let client = Client::builder()
             .timeout(Duration::from_secs(settings.timeout_seconds))
+            .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+            .pool_idle_timeout(Duration::from_secs(settings.pool_idle_timeout_seconds))
+            .default_headers(settings.headers.clone())
             .build()?;
 
-        Ok(Self { settings, client })
+        let trace_context = definition
+            .propagate_trace_context
+            .then(|| TraceContext::new(&storage.id));
+
+        Ok(Self {
+            settings,
+            client: Some(client),
+            event_field_names: EventFieldNames::default(),
+            trace_context,
+        })
     }
 }
 
@@ -97,6 +154,7 @@ impl HttpSourceChangeDispatcher {
 impl SourceChangeDispatcher for HttpSourceChangeDispatcher {
     async fn close(&mut self) -> anyhow::Result<()> {
         debug!("Closing HTTP source change dispatcher");
+        self.client = None;
         Ok(())
     }
 
@@ -110,8 +168,13 @@ impl SourceChangeDispatcher for HttpSourceChangeDispatcher {
             return Ok(());
         }
 
+        let client = match &self.client {
+            Some(client) => client,
+            None => anyhow::bail!("HTTP source change dispatcher is closed"),
+        };
+
         let url = self.settings.full_url();
-        
+
         log::info!(
             "HTTP dispatcher sending {} events to {} (source_id: {}, batch: {})",
             events.len(),
@@ -121,15 +184,23 @@ impl SourceChangeDispatcher for HttpSourceChangeDispatcher {
         );
 
         if self.settings.batch_events {
+            let body = self.event_field_names.apply_all(&events);
+
             // Log request body at debug level
             debug!(
                 "HTTP dispatcher sending batch request to {}: {}",
                 url,
-                serde_json::to_string_pretty(&events)
+                serde_json::to_string_pretty(&body)
                     .unwrap_or_else(|e| format!("Failed to serialize: {}", e))
             );
 
-            let response = match self.client.post(&url).json(&events).send().await {
+            // A batch is sent under the span of its first event.
+            let mut request = client.post(&url).json(&body);
+            if let Some(trace_context) = &self.trace_context {
+                request = request.header("traceparent", trace_context.traceparent(events[0]));
+            }
+
+            let response = match request.send().await {
                 Ok(resp) => resp,
                 Err(e) => {
                     error!("Failed to connect to {}: {}", url, e);
@@ -163,15 +234,22 @@ impl SourceChangeDispatcher for HttpSourceChangeDispatcher {
         } else {
             let event_count = events.len();
             for event in &events {
+                let body = self.event_field_names.apply(event);
+
                 // Log request body at debug level
                 debug!(
                     "HTTP dispatcher sending individual event to {}: {}",
                     url,
-                    serde_json::to_string_pretty(event)
+                    serde_json::to_string_pretty(&body)
                         .unwrap_or_else(|e| format!("Failed to serialize: {}", e))
                 );
 
-                let response = self.client.post(&url).json(event).send().await?;
+                let mut request = client.post(&url).json(&body);
+                if let Some(trace_context) = &self.trace_context {
+                    request = request.header("traceparent", trace_context.traceparent(event));
+                }
+
+                let response = request.send().await?;
 
                 let status = response.status();
                 let response_body = response.text().await.unwrap_or_default();
@@ -199,10 +277,29 @@ impl SourceChangeDispatcher for HttpSourceChangeDispatcher {
 
         Ok(())
     }
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        net::SocketAddr,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{
+        extract::{ConnectInfo, State},
+        http::{HeaderMap, StatusCode},
+        routing::post,
+        Router,
+    };
+    use test_data_store::test_run_storage::{TestRunId, TestRunSourceId};
+
     use super::*;
 
     #[test]
@@ -217,6 +314,11 @@ mod tests {
             batch_size: None,
             batch_timeout_ms: None,
             source_id: None,
+            headers: HashMap::new(),
+            bearer_token: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_seconds: None,
+            propagate_trace_context: false,
         };
 
         let source_id = "test-source".to_string();
@@ -227,6 +329,8 @@ mod tests {
         assert_eq!(settings.endpoint, "/sources/test-source/events");
         assert_eq!(settings.timeout_seconds, 30);
         assert!(settings.batch_events);
+        assert_eq!(settings.pool_max_idle_per_host, 10);
+        assert_eq!(settings.pool_idle_timeout_seconds, 90);
         assert_eq!(
             settings.full_url(),
             "http://localhost:8080/sources/test-source/events"
@@ -245,6 +349,11 @@ mod tests {
             batch_size: None,
             batch_timeout_ms: None,
             source_id: None,
+            headers: HashMap::new(),
+            bearer_token: None,
+            pool_max_idle_per_host: Some(4),
+            pool_idle_timeout_seconds: Some(30),
+            propagate_trace_context: false,
         };
 
         let source_id = "test-source".to_string();
@@ -255,9 +364,262 @@ mod tests {
         assert_eq!(settings.endpoint, "/webhooks/changes");
         assert_eq!(settings.timeout_seconds, 60);
         assert!(!settings.batch_events);
+        assert_eq!(settings.pool_max_idle_per_host, 4);
+        assert_eq!(settings.pool_idle_timeout_seconds, 30);
         assert_eq!(
             settings.full_url(),
             "https://api.example.com:443/webhooks/changes"
         );
     }
+
+    // Records the peer address of every request, so the test can count the connections used.
+    async fn start_mock_server() -> (u16, Arc<Mutex<Vec<SocketAddr>>>) {
+        let peers = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/sources/test-source/events",
+                post(
+                    |State(peers): State<Arc<Mutex<Vec<SocketAddr>>>>,
+                     ConnectInfo(peer): ConnectInfo<SocketAddr>| async move {
+                        peers.lock().unwrap().push(peer);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(peers.clone());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>()),
+        );
+
+        (port, peers)
+    }
+
+    fn create_event(lsn: u64) -> SourceChangeEvent {
+        SourceChangeEvent::try_from(
+            format!(
+                r#"{{
+                    "op": "u",
+                    "reactivatorStart_ns": 0,
+                    "reactivatorEnd_ns": 0,
+                    "payload": {{
+                        "source": {{ "db": "db", "table": "node", "ts_ns": 0, "lsn": {} }},
+                        "before": {{}},
+                        "after": {{}}
+                    }}
+                }}"#,
+                lsn
+            )
+            .as_str(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_connections_are_reused() {
+        let (port, peers) = start_mock_server().await;
+
+        let definition = HttpSourceChangeDispatcherDefinition {
+            url: "http://127.0.0.1".to_string(),
+            port,
+            endpoint: None,
+            timeout_seconds: Some(5),
+            batch_events: None,
+            adaptive_enabled: None,
+            batch_size: None,
+            batch_timeout_ms: None,
+            source_id: None,
+            headers: HashMap::new(),
+            bearer_token: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_seconds: None,
+            propagate_trace_context: false,
+        };
+        let storage = TestRunSourceStorage {
+            id: TestRunSourceId::new(&TestRunId::new("repo", "test", "run"), "test-source"),
+            path: PathBuf::from("/tmp"),
+            source_change_path: PathBuf::from("/tmp"),
+        };
+        let mut dispatcher = HttpSourceChangeDispatcher::new(&definition, storage).unwrap();
+
+        for batch in 0..50 {
+            let events = [create_event(batch * 2), create_event(batch * 2 + 1)];
+            dispatcher
+                .dispatch_source_change_events(events.iter().collect())
+                .await
+                .unwrap();
+        }
+
+        // Every batch was sent over the same pooled connection.
+        let peers = peers.lock().unwrap().clone();
+        assert_eq!(peers.len(), 50);
+        assert_eq!(peers.iter().collect::<HashSet<_>>().len(), 1);
+
+        // Closing drops the client, so later dispatches fail.
+        dispatcher.close().await.unwrap();
+        assert!(dispatcher
+            .dispatch_source_change_events(vec![&create_event(100)])
+            .await
+            .is_err());
+    }
+
+    // Records the headers of every request.
+    async fn start_header_recording_server() -> (u16, Arc<Mutex<Vec<HeaderMap>>>) {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route(
+                "/sources/test-source/events",
+                post(
+                    |State(requests): State<Arc<Mutex<Vec<HeaderMap>>>>,
+                     headers: HeaderMap| async move {
+                        requests.lock().unwrap().push(headers);
+                        StatusCode::OK
+                    },
+                ),
+            )
+            .with_state(requests.clone());
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(app.into_make_service()),
+        );
+
+        (port, requests)
+    }
+
+    fn definition_with_headers(
+        port: u16,
+        headers: &[(&str, &str)],
+        bearer_token: Option<&str>,
+    ) -> HttpSourceChangeDispatcherDefinition {
+        HttpSourceChangeDispatcherDefinition {
+            url: "http://127.0.0.1".to_string(),
+            port,
+            endpoint: None,
+            timeout_seconds: Some(5),
+            batch_events: Some(false),
+            adaptive_enabled: None,
+            batch_size: None,
+            batch_timeout_ms: None,
+            source_id: None,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            bearer_token: bearer_token.map(|token| token.into()),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout_seconds: None,
+            propagate_trace_context: false,
+        }
+    }
+
+    fn test_storage() -> TestRunSourceStorage {
+        TestRunSourceStorage {
+            id: TestRunSourceId::new(&TestRunId::new("repo", "test", "run"), "test-source"),
+            path: PathBuf::from("/tmp"),
+            source_change_path: PathBuf::from("/tmp"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headers_are_sent_on_every_request() {
+        let (port, requests) = start_header_recording_server().await;
+        std::env::set_var("HTTP_DISPATCHER_TEST_TOKEN", "s3cr3t");
+
+        let definition = definition_with_headers(
+            port,
+            &[("X-Tenant", "tenant-1")],
+            Some("${HTTP_DISPATCHER_TEST_TOKEN}"),
+        );
+        let mut dispatcher = HttpSourceChangeDispatcher::new(&definition, test_storage()).unwrap();
+
+        let events = [create_event(1), create_event(2), create_event(3)];
+        dispatcher
+            .dispatch_source_change_events(events.iter().collect())
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 3);
+        for headers in requests.iter() {
+            assert_eq!(headers["x-tenant"], "tenant-1");
+            assert_eq!(headers["authorization"], "Bearer s3cr3t");
+        }
+    }
+
+    #[test]
+    fn test_missing_token_env_var_fails_creation() {
+        let definition =
+            definition_with_headers(8080, &[], Some("${HTTP_DISPATCHER_TEST_UNSET_TOKEN}"));
+
+        let err = HttpSourceChangeDispatcher::new(&definition, test_storage())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("bearer_token"));
+        assert!(err.to_string().contains("HTTP_DISPATCHER_TEST_UNSET_TOKEN"));
+    }
+
+    #[tokio::test]
+    async fn test_traceparent_is_sent_on_every_request() {
+        use crate::sources::source_change_dispatchers::trace_context::is_valid_traceparent;
+
+        let (port, requests) = start_header_recording_server().await;
+
+        let mut definition = definition_with_headers(port, &[("tracestate", "vendor=value")], None);
+        definition.propagate_trace_context = true;
+        let mut dispatcher = HttpSourceChangeDispatcher::new(&definition, test_storage()).unwrap();
+
+        let events = [create_event(1), create_event(2), create_event(3)];
+        dispatcher
+            .dispatch_source_change_events(events.iter().collect())
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 3);
+
+        let traceparents: Vec<String> = requests
+            .iter()
+            .map(|headers| headers["traceparent"].to_str().unwrap().to_string())
+            .collect();
+        for traceparent in traceparents.iter() {
+            assert!(is_valid_traceparent(traceparent), "{}", traceparent);
+        }
+
+        // Every request shares the run's trace ID, with a span per event.
+        let trace_context = TraceContext::new(&test_storage().id);
+        for (traceparent, event) in traceparents.iter().zip(events.iter()) {
+            assert_eq!(*traceparent, trace_context.traceparent(event));
+        }
+        assert_eq!(traceparents.iter().collect::<HashSet<_>>().len(), 3);
+
+        // A configured tracestate is passed through alongside it.
+        for headers in requests.iter() {
+            assert_eq!(headers["tracestate"], "vendor=value");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_traceparent_is_not_sent_by_default() {
+        let (port, requests) = start_header_recording_server().await;
+
+        let definition = definition_with_headers(port, &[], None);
+        let mut dispatcher = HttpSourceChangeDispatcher::new(&definition, test_storage()).unwrap();
+
+        dispatcher
+            .dispatch_source_change_events(vec![&create_event(1)])
+            .await
+            .unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 1);
+        assert!(!requests[0].contains_key("traceparent"));
+    }
 }
@@ -28,7 +28,10 @@ use test_data_store::{
     test_run_storage::TestRunSourceStorage,
 };
 
-use super::{SourceChangeDispatcher, SourceChangeDispatcherError};
+use super::{
+    event_field_names::{EventFieldNames, RenamedSourceChangeEvent},
+    SourceChangeDispatcher, SourceChangeDispatcherError,
+};
 
 #[derive(Debug)]
 pub struct JsonlFileSourceChangeDispatcherSettings {
@@ -51,6 +54,7 @@ impl JsonlFileSourceChangeDispatcherSettings {
 pub struct JsonlFileSourceChangeDispatcher {
     #[allow(dead_code)]
     settings: JsonlFileSourceChangeDispatcherSettings,
+    event_field_names: EventFieldNames,
     writer: SourceChangeEventLogWriter,
 }
 
@@ -86,7 +90,11 @@ impl JsonlFileSourceChangeDispatcher {
         )
         .await?;
 
-        Ok(Self { settings, writer })
+        Ok(Self {
+            settings,
+            event_field_names: EventFieldNames::default(),
+            writer,
+        })
     }
 }
 
@@ -103,10 +111,16 @@ impl SourceChangeDispatcher for JsonlFileSourceChangeDispatcher {
         log::trace!("Dispatch source change events");
 
         for event in events {
-            self.writer.write_source_change_event(event).await?;
+            self.writer
+                .write_source_change_event(&self.event_field_names.apply(event))
+                .await?;
         }
         Ok(())
     }
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -147,7 +161,7 @@ impl SourceChangeEventLogWriter {
 
     pub async fn write_source_change_event(
         &mut self,
-        event: &SourceChangeEvent,
+        event: &RenamedSourceChangeEvent<'_>,
     ) -> anyhow::Result<()> {
         if let Some(writer) = &mut self.current_writer {
             let json = format!(
@@ -0,0 +1,282 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use test_data_store::{
+    scripts::SourceChangeEvent,
+    test_repo_storage::models::LsnOrderValidatorSourceChangeDispatcherDefinition,
+    test_run_storage::TestRunSourceStorage,
+};
+
+use super::SourceChangeDispatcher;
+
+const LSN_ORDER_VIOLATIONS_FILE_NAME: &str = "lsn_order_violations.json";
+
+#[derive(Debug)]
+pub struct LsnOrderValidatorSourceChangeDispatcherSettings {
+    pub error_on_violation: bool,
+    pub violations_file_path: PathBuf,
+}
+
+impl LsnOrderValidatorSourceChangeDispatcherSettings {
+    pub fn new(
+        def: &LsnOrderValidatorSourceChangeDispatcherDefinition,
+        output_storage: &TestRunSourceStorage,
+    ) -> Self {
+        Self {
+            error_on_violation: def.error_on_violation.unwrap_or(false),
+            violations_file_path: output_storage.path.join(LSN_ORDER_VIOLATIONS_FILE_NAME),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LsnOrderViolation {
+    pub previous_lsn: u64,
+    pub lsn: u64,
+    pub op: String,
+    pub table: String,
+}
+
+pub struct LsnOrderValidatorSourceChangeDispatcher {
+    dispatcher: Box<dyn SourceChangeDispatcher + Send + Sync>,
+    previous_lsn: Option<u64>,
+    settings: LsnOrderValidatorSourceChangeDispatcherSettings,
+    violations: Vec<LsnOrderViolation>,
+}
+
+impl LsnOrderValidatorSourceChangeDispatcher {
+    pub fn new(
+        def: &LsnOrderValidatorSourceChangeDispatcherDefinition,
+        dispatcher: Box<dyn SourceChangeDispatcher + Send + Sync>,
+        output_storage: &TestRunSourceStorage,
+    ) -> Self {
+        log::debug!(
+            "Creating LsnOrderValidatorSourceChangeDispatcher from {:?}, ",
+            def
+        );
+
+        let settings = LsnOrderValidatorSourceChangeDispatcherSettings::new(def, output_storage);
+        log::trace!(
+            "Creating LsnOrderValidatorSourceChangeDispatcher with settings {:?}, ",
+            settings
+        );
+
+        Self {
+            dispatcher,
+            previous_lsn: None,
+            settings,
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn get_violations(&self) -> &[LsnOrderViolation] {
+        &self.violations
+    }
+
+    // Checks the LSNs of a batch of events against the last dispatched LSN, returning the
+    // violations found without updating any state.
+    fn check_events(&self, events: &[&SourceChangeEvent]) -> (Vec<LsnOrderViolation>, Option<u64>) {
+        let mut violations = Vec::new();
+        let mut previous_lsn = self.previous_lsn;
+
+        for event in events {
+            let lsn = event.payload.source.lsn;
+            if let Some(prev) = previous_lsn {
+                if lsn <= prev {
+                    violations.push(LsnOrderViolation {
+                        previous_lsn: prev,
+                        lsn,
+                        op: event.op.clone(),
+                        table: event.payload.source.table.clone(),
+                    });
+                }
+            }
+            previous_lsn = Some(lsn);
+        }
+
+        (violations, previous_lsn)
+    }
+}
+
+#[async_trait]
+impl SourceChangeDispatcher for LsnOrderValidatorSourceChangeDispatcher {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        if !self.violations.is_empty() {
+            log::warn!(
+                "LsnOrderValidatorSourceChangeDispatcher recorded {} LSN order violations, writing to {:?}",
+                self.violations.len(),
+                self.settings.violations_file_path
+            );
+            tokio::fs::write(
+                &self.settings.violations_file_path,
+                serde_json::to_string_pretty(&self.violations)?,
+            )
+            .await?;
+        }
+
+        self.dispatcher.close().await
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        log::trace!("Dispatch source change events");
+
+        let (violations, last_lsn) = self.check_events(&events);
+
+        if !violations.is_empty() {
+            for violation in &violations {
+                log::warn!("LSN order violation: {:?}", violation);
+            }
+
+            let first = violations[0].clone();
+            self.violations.extend(violations);
+
+            if self.settings.error_on_violation {
+                anyhow::bail!(
+                    "LSN order violation - LSN {} is not greater than previous LSN {}",
+                    first.lsn,
+                    first.previous_lsn
+                );
+            }
+        }
+
+        self.previous_lsn = last_lsn;
+        self.dispatcher.dispatch_source_change_events(events).await
+    }
+
+    fn set_test_run_host(&mut self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
+        self.dispatcher.set_test_run_host(test_run_host)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sources::source_change_dispatchers::test_support::RecordingDispatcher;
+
+    use test_data_store::{
+        test_repo_storage::models::{
+            ConsoleSourceChangeDispatcherDefinition, SourceChangeDispatcherDefinition,
+        },
+        test_run_storage::{TestRunId, TestRunSourceId},
+    };
+
+    fn create_event(lsn: u64) -> SourceChangeEvent {
+        SourceChangeEvent::try_from(
+            format!(
+                r#"{{
+                    "op": "u",
+                    "reactivatorStart_ns": 0,
+                    "reactivatorEnd_ns": 0,
+                    "payload": {{
+                        "source": {{ "db": "db", "table": "node", "ts_ns": 0, "lsn": {} }},
+                        "before": {{}},
+                        "after": {{}}
+                    }}
+                }}"#,
+                lsn
+            )
+            .as_str(),
+        )
+        .unwrap()
+    }
+
+    fn create_dispatcher(
+        error_on_violation: bool,
+    ) -> (LsnOrderValidatorSourceChangeDispatcher, RecordingDispatcher) {
+        let dispatched = RecordingDispatcher::new();
+        let def = LsnOrderValidatorSourceChangeDispatcherDefinition {
+            dispatcher: Box::new(SourceChangeDispatcherDefinition::Console(
+                ConsoleSourceChangeDispatcherDefinition {
+                    date_time_format: None,
+                },
+            )),
+            error_on_violation: Some(error_on_violation),
+        };
+        let output_storage = TestRunSourceStorage {
+            id: TestRunSourceId::new(&TestRunId::new("repo", "test", "run"), "source"),
+            path: PathBuf::from("/tmp"),
+            source_change_path: PathBuf::from("/tmp"),
+        };
+
+        let dispatcher = LsnOrderValidatorSourceChangeDispatcher::new(
+            &def,
+            Box::new(dispatched.clone()),
+            &output_storage,
+        );
+
+        (dispatcher, dispatched)
+    }
+
+    #[tokio::test]
+    async fn test_increasing_lsns_are_dispatched() {
+        let (mut dispatcher, dispatched) = create_dispatcher(true);
+
+        let events = [create_event(1), create_event(2), create_event(3)];
+        dispatcher
+            .dispatch_source_change_events(events.iter().collect())
+            .await
+            .unwrap();
+        dispatcher
+            .dispatch_source_change_events(vec![&create_event(4)])
+            .await
+            .unwrap();
+
+        assert!(dispatcher.get_violations().is_empty());
+        assert_eq!(dispatched.lsns(), vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_regression_is_recorded() {
+        let (mut dispatcher, dispatched) = create_dispatcher(false);
+
+        dispatcher
+            .dispatch_source_change_events(vec![&create_event(5)])
+            .await
+            .unwrap();
+        dispatcher
+            .dispatch_source_change_events(vec![&create_event(5)])
+            .await
+            .unwrap();
+
+        assert_eq!(dispatcher.get_violations().len(), 1);
+        assert_eq!(dispatcher.get_violations()[0].previous_lsn, 5);
+        assert_eq!(dispatched.lsns(), vec![5, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_regression_errors_when_configured() {
+        let (mut dispatcher, dispatched) = create_dispatcher(true);
+
+        dispatcher
+            .dispatch_source_change_events(vec![&create_event(5)])
+            .await
+            .unwrap();
+        assert!(dispatcher
+            .dispatch_source_change_events(vec![&create_event(3)])
+            .await
+            .is_err());
+
+        assert_eq!(dispatcher.get_violations().len(), 1);
+        assert_eq!(dispatched.lsns(), vec![5]);
+    }
+}
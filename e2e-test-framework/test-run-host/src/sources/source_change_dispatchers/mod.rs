@@ -19,16 +19,24 @@ use test_data_store::{
     test_run_storage::TestRunSourceStorage,
 };
 
+use event_field_names::EventFieldNames;
+
 pub mod adaptive_grpc_dispatcher;
 pub mod adaptive_http_dispatcher;
 pub mod console_dispatcher;
 pub mod dapr_dispatcher;
 pub mod drasi_server_api_dispatcher;
 pub mod drasi_server_channel_dispatcher;
+pub mod event_field_names;
 pub mod grpc_dispatcher;
 pub mod http_dispatcher;
 pub mod jsonl_file_dispatcher;
+pub mod lsn_order_validator_dispatcher;
+pub mod postgres_dispatcher;
 pub mod redis_stream_disspatcher;
+#[cfg(test)]
+pub mod test_support;
+pub mod trace_context;
 
 #[derive(Debug, thiserror::Error)]
 pub enum SourceChangeDispatcherError {
@@ -57,6 +65,11 @@ pub trait SourceChangeDispatcher: Send + Sync {
     fn set_test_run_host(&mut self, _test_run_host: std::sync::Arc<crate::TestRunHost>) {
         // Default implementation does nothing - only some dispatchers need this
     }
+
+    /// Sets the field names used when serializing events, for dispatchers that send JSON (optional)
+    fn set_event_field_names(&mut self, _event_field_names: EventFieldNames) {
+        // Default implementation does nothing - dispatchers that don't send JSON ignore renames
+    }
 }
 
 #[async_trait]
@@ -73,13 +86,17 @@ impl SourceChangeDispatcher for Box<dyn SourceChangeDispatcher + Send + Sync> {
     fn set_test_run_host(&mut self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         (**self).set_test_run_host(test_run_host)
     }
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        (**self).set_event_field_names(event_field_names)
+    }
 }
 
 pub async fn create_source_change_dispatcher(
     def: &SourceChangeDispatcherDefinition,
     output_storage: &TestRunSourceStorage,
+    event_field_names: &EventFieldNames,
 ) -> anyhow::Result<Box<dyn SourceChangeDispatcher + Send + Sync>> {
-    match def {
+    let mut dispatcher = match def {
         SourceChangeDispatcherDefinition::Console(def) => Ok(Box::new(
             console_dispatcher::ConsoleSourceChangeDispatcher::new(def, output_storage)?,
         )
@@ -136,5 +153,31 @@ pub async fn create_source_change_dispatcher(
             )?,
         )
             as Box<dyn SourceChangeDispatcher + Send + Sync>),
+        SourceChangeDispatcherDefinition::LsnOrderValidator(def) => {
+            // The wrapped dispatcher is created recursively, so the future must be boxed.
+            let dispatcher = Box::pin(create_source_change_dispatcher(
+                &def.dispatcher,
+                output_storage,
+                event_field_names,
+            ))
+            .await?;
+            Ok(Box::new(
+                lsn_order_validator_dispatcher::LsnOrderValidatorSourceChangeDispatcher::new(
+                    def,
+                    dispatcher,
+                    output_storage,
+                ),
+            ) as Box<dyn SourceChangeDispatcher + Send + Sync>)
+        }
+        SourceChangeDispatcherDefinition::Postgres(def) => Ok(Box::new(
+            postgres_dispatcher::PostgresSourceChangeDispatcher::new(def, output_storage).await?,
+        )
+            as Box<dyn SourceChangeDispatcher + Send + Sync>),
+    }?;
+
+    if !event_field_names.is_empty() {
+        dispatcher.set_event_field_names(event_field_names.clone());
     }
+
+    Ok(dispatcher)
 }
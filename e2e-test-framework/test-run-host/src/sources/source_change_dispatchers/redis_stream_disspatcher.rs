@@ -26,11 +26,14 @@ use test_data_store::{
     test_run_storage::TestRunSourceStorage,
 };
 
-use super::SourceChangeDispatcher;
+use super::{
+    event_field_names::EventFieldNames, trace_context::TraceContext, SourceChangeDispatcher,
+};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct SourceChangeQueueEvent {
-    pub data: Vec<SourceChangeEvent>,
+    // The serialized SourceChangeEvents, with any field renames applied.
+    pub data: serde_json::Value,
     pub datacontenttype: String,
     pub id: String,
     pub pubsubname: String,
@@ -50,7 +53,7 @@ pub struct SourceChangeQueueEvent {
 impl SourceChangeQueueEvent {
     /// Creates a new SourceChangeQueueEvent with automatically generated unique trace IDs
     pub fn new(
-        data: Vec<SourceChangeEvent>,
+        data: serde_json::Value,
         id: String,
         source_id: String,
         time: String,
@@ -100,8 +103,10 @@ impl RedisStreamSourceChangeDispatcherSettings {
 pub struct RedisStreamSourceChangeDispatcher {
     connection: Option<MultiplexedConnection>,
     client: Option<Client>,
+    event_field_names: EventFieldNames,
     rng: StdRng,
     settings: RedisStreamSourceChangeDispatcherSettings,
+    trace_context: Option<TraceContext>,
 }
 
 impl RedisStreamSourceChangeDispatcher {
@@ -142,44 +147,41 @@ impl RedisStreamSourceChangeDispatcher {
         Ok(RedisStreamSourceChangeDispatcher {
             connection: Some(connection),
             client: Some(client),
+            event_field_names: EventFieldNames::default(),
             rng: StdRng::from_os_rng(),
             settings,
+            trace_context: def
+                .propagate_trace_context
+                .then(|| TraceContext::new(&output_storage.id)),
         })
     }
-}
 
-#[async_trait]
-impl SourceChangeDispatcher for RedisStreamSourceChangeDispatcher {
-    async fn close(&mut self) -> anyhow::Result<()> {
-        // Connection will be closed when dropped
-        self.connection = None;
-        self.client = None;
-        Ok(())
-    }
-
-    async fn dispatch_source_change_events(
+    // Wraps the events in the queue event added to the stream.
+    fn create_queue_event(
         &mut self,
-        events: Vec<&SourceChangeEvent>,
-    ) -> anyhow::Result<()> {
-        log::trace!(
-            "Dispatching {} source change events to Redis stream",
-            events.len()
-        );
-
-        // Generate trace ID (16 random bytes for a root span)
-        let mut trace_id_bytes = [0u8; 16];
-        self.rng.fill(&mut trace_id_bytes);
-        let trace_id_hex = hex::encode(trace_id_bytes);
+        events: &[&SourceChangeEvent],
+    ) -> anyhow::Result<SourceChangeQueueEvent> {
+        // The queue event is sent under the span of its first event when trace context is
+        // propagated. Otherwise it gets a random root span.
+        let traceparent = match (&self.trace_context, events.first()) {
+            (Some(trace_context), Some(event)) => trace_context.traceparent(event),
+            _ => {
+                // Generate trace ID (16 random bytes for a root span)
+                let mut trace_id_bytes = [0u8; 16];
+                self.rng.fill(&mut trace_id_bytes);
+                let trace_id_hex = hex::encode(trace_id_bytes);
 
-        // Generate span ID (8 random bytes)
-        let mut span_id_bytes = [0u8; 8];
-        self.rng.fill(&mut span_id_bytes);
-        let span_id_hex = hex::encode(span_id_bytes);
+                // Generate span ID (8 random bytes)
+                let mut span_id_bytes = [0u8; 8];
+                self.rng.fill(&mut span_id_bytes);
+                let span_id_hex = hex::encode(span_id_bytes);
 
-        // Format traceparent according to W3C trace context spec
-        // Format: 00-<trace-id>-<span-id>-01
-        // where 00 is version and 01 is flags (sampled)
-        let traceparent = format!("00-{}-{}-01", trace_id_hex, span_id_hex);
+                // Format traceparent according to W3C trace context spec
+                // Format: 00-<trace-id>-<span-id>-01
+                // where 00 is version and 01 is flags (sampled)
+                format!("00-{}-{}-01", trace_id_hex, span_id_hex)
+            }
+        };
 
         // For a root span, traceid follows the same format in this schema
         let traceid = traceparent.clone();
@@ -191,15 +193,36 @@ impl SourceChangeDispatcher for RedisStreamSourceChangeDispatcher {
         let now = chrono::Utc::now();
         let time = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
-        let q_event = SourceChangeQueueEvent::new(
-            events.iter().cloned().cloned().collect(),
+        Ok(SourceChangeQueueEvent::new(
+            serde_json::to_value(self.event_field_names.apply_all(events))?,
             id,
             self.settings.stream_name.clone(),
             time,
             traceid,
             traceparent,
+        ))
+    }
+}
+
+#[async_trait]
+impl SourceChangeDispatcher for RedisStreamSourceChangeDispatcher {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        // Connection will be closed when dropped
+        self.connection = None;
+        self.client = None;
+        Ok(())
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        log::trace!(
+            "Dispatching {} source change events to Redis stream",
+            events.len()
         );
 
+        let q_event = self.create_queue_event(&events)?;
         let q_event_json = serde_json::to_string(&q_event)?;
 
         let conn = self.connection.as_mut().unwrap();
@@ -212,4 +235,60 @@ impl SourceChangeDispatcher for RedisStreamSourceChangeDispatcher {
 
         Ok(())
     }
+
+    fn set_event_field_names(&mut self, event_field_names: EventFieldNames) {
+        self.event_field_names = event_field_names;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_event_field_names_are_applied() {
+        // The dispatcher isn't connected, since only the queue event is checked.
+        let mut dispatcher = RedisStreamSourceChangeDispatcher {
+            connection: None,
+            client: None,
+            event_field_names: EventFieldNames::new(HashMap::from([
+                ("op".to_string(), "operation".to_string()),
+                ("after".to_string(), "new".to_string()),
+            ])),
+            rng: StdRng::seed_from_u64(0),
+            settings: RedisStreamSourceChangeDispatcherSettings {
+                host: "127.0.0.1".to_string(),
+                port: 6379,
+                stream_name: "test-source-change".to_string(),
+            },
+            trace_context: None,
+        };
+
+        let event = SourceChangeEvent::try_from(
+            r#"{
+                "op": "i",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "db", "table": "node", "ts_ns": 0, "lsn": 1 },
+                    "before": {},
+                    "after": { "id": "n1", "labels": ["Room"], "properties": {} }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let q_event = dispatcher.create_queue_event(&[&event]).unwrap();
+        let q_event_json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&q_event).unwrap()).unwrap();
+
+        let data = &q_event_json["data"][0];
+        assert_eq!(data["operation"], "i");
+        assert!(data.get("op").is_none());
+        assert_eq!(data["payload"]["new"]["id"], "n1");
+        assert!(data["payload"].get("after").is_none());
+        assert_eq!(q_event_json["topic"], "test-source-change");
+    }
 }
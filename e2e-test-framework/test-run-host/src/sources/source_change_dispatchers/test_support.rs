@@ -0,0 +1,101 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Test doubles for the tests of the dispatchers and of the generators that drive them.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+
+use test_data_store::scripts::SourceChangeEvent;
+
+use super::SourceChangeDispatcher;
+
+// Records the events it is sent, taking delay to dispatch each batch. Clones share the
+// recorded events, so a test can keep a clone to inspect after handing the dispatcher over.
+#[derive(Clone, Default)]
+pub struct RecordingDispatcher {
+    events: Arc<Mutex<Vec<SourceChangeEvent>>>,
+    delay: Duration,
+}
+
+impl RecordingDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_delay(delay: Duration) -> Self {
+        Self {
+            delay,
+            ..Self::default()
+        }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.events.lock().unwrap().len() as u64
+    }
+
+    pub fn events(&self) -> Vec<SourceChangeEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn lsns(&self) -> Vec<u64> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.payload.source.lsn)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl SourceChangeDispatcher for RecordingDispatcher {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        tokio::time::sleep(self.delay).await;
+        self.events
+            .lock()
+            .unwrap()
+            .extend(events.into_iter().cloned());
+        Ok(())
+    }
+}
+
+// Fails every dispatch, for testing that dispatch errors reach the generators.
+#[derive(Clone, Default)]
+pub struct FailingDispatcher;
+
+#[async_trait]
+impl SourceChangeDispatcher for FailingDispatcher {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        _events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("FailingDispatcher always fails")
+    }
+}
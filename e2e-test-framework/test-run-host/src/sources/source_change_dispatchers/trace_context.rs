@@ -0,0 +1,170 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use test_data_store::{scripts::SourceChangeEvent, test_run_storage::TestRunSourceId};
+
+// W3C trace context that dispatchers attach to the events they send, so a trace can link a
+// source change to the reaction invocations it causes. The trace ID is derived from the
+// TestRun ID, so every event dispatched during a run shares it and it can be grepped for.
+// The span ID is derived from the TestRunSource ID and the event's lsn, so a reaction that
+// sees the event can work out the span that sent it.
+#[derive(Clone, Debug)]
+pub struct TraceContext {
+    source_id: String,
+    trace_id: String,
+}
+
+impl TraceContext {
+    pub fn new(id: &TestRunSourceId) -> Self {
+        let test_run_id = id.test_run_id.to_string();
+
+        Self {
+            source_id: id.to_string(),
+            trace_id: format!(
+                "{:016x}{:016x}",
+                non_zero_hash(&("trace_id_high", &test_run_id)),
+                non_zero_hash(&("trace_id_low", &test_run_id))
+            ),
+        }
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn span_id(&self, event: &SourceChangeEvent) -> String {
+        format!(
+            "{:016x}",
+            non_zero_hash(&(&self.source_id, event.payload.source.lsn))
+        )
+    }
+
+    // Version 00 with the sampled flag set.
+    pub fn traceparent(&self, event: &SourceChangeEvent) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id(event))
+    }
+}
+
+// All zero trace and span IDs are invalid, so a zero hash is bumped to 1.
+fn non_zero_hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish().max(1)
+}
+
+// Checks a traceparent has the version-format-00 shape: 00-<32 hex>-<16 hex>-<2 hex>, with
+// non-zero trace and span IDs.
+pub fn is_valid_traceparent(traceparent: &str) -> bool {
+    let parts: Vec<&str> = traceparent.split('-').collect();
+    let is_lower_hex = |s: &str, len: usize| {
+        s.len() == len
+            && s.chars()
+                .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+    };
+
+    parts.len() == 4
+        && parts[0] == "00"
+        && is_lower_hex(parts[1], 32)
+        && parts[1].chars().any(|c| c != '0')
+        && is_lower_hex(parts[2], 16)
+        && parts[2].chars().any(|c| c != '0')
+        && is_lower_hex(parts[3], 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_data_store::test_run_storage::TestRunId;
+
+    use super::*;
+
+    fn create_event(lsn: u64) -> SourceChangeEvent {
+        SourceChangeEvent::try_from(
+            format!(
+                r#"{{
+                    "op": "i",
+                    "reactivatorStart_ns": 0,
+                    "reactivatorEnd_ns": 0,
+                    "payload": {{
+                        "source": {{ "db": "db", "table": "node", "ts_ns": 0, "lsn": {} }},
+                        "before": {{}},
+                        "after": {{}}
+                    }}
+                }}"#,
+                lsn
+            )
+            .as_str(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_trace_context() {
+        let run_1 = TestRunId::new("repo", "test", "run-1");
+        let run_2 = TestRunId::new("repo", "test", "run-2");
+        let source_a = TraceContext::new(&TestRunSourceId::new(&run_1, "source-a"));
+        let source_b = TraceContext::new(&TestRunSourceId::new(&run_1, "source-b"));
+        let other_run = TraceContext::new(&TestRunSourceId::new(&run_2, "source-a"));
+
+        // The trace ID is the same for every source in a run, and differs between runs.
+        assert_eq!(source_a.trace_id(), source_b.trace_id());
+        assert_ne!(source_a.trace_id(), other_run.trace_id());
+        assert_eq!(
+            source_a.trace_id(),
+            TraceContext::new(&TestRunSourceId::new(&run_1, "source-a")).trace_id()
+        );
+
+        // The span ID is derived from the source and the event's lsn.
+        let event_1 = create_event(1);
+        let event_2 = create_event(2);
+        assert_eq!(
+            source_a.span_id(&event_1),
+            source_a.span_id(&create_event(1))
+        );
+        assert_ne!(source_a.span_id(&event_1), source_a.span_id(&event_2));
+        assert_ne!(source_a.span_id(&event_1), source_b.span_id(&event_1));
+
+        let traceparent = source_a.traceparent(&event_1);
+        assert!(is_valid_traceparent(&traceparent));
+        assert_eq!(
+            traceparent,
+            format!(
+                "00-{}-{}-01",
+                source_a.trace_id(),
+                source_a.span_id(&event_1)
+            )
+        );
+    }
+
+    #[test]
+    fn test_is_valid_traceparent() {
+        assert!(is_valid_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        ));
+        assert!(!is_valid_traceparent(
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01"
+        ));
+        assert!(!is_valid_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01"
+        ));
+        assert!(!is_valid_traceparent(
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01"
+        ));
+        assert!(!is_valid_traceparent(
+            "00-4bf92f3577b34da6-00f067aa0ba902b7-01"
+        ));
+        assert!(!is_valid_traceparent("garbage"));
+    }
+}
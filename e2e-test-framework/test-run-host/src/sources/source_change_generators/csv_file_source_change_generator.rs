@@ -0,0 +1,571 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+};
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use test_data_store::{
+    test_repo_storage::{
+        models::{
+            CsvFileSourceChangeGeneratorDefinition, CsvTimestampUnit,
+            SourceChangeDispatcherDefinition,
+        },
+        TestSourceStorage,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use super::{
+    dispatcher_kinds,
+    file_replay_source_change_generator::{
+        FileReplayChange, FileReplayChanges, FileReplayParser, FileReplaySettings,
+        FileReplaySourceChangeGenerator,
+    },
+    max_events_per_second,
+    source_change_producer::SourceChangeGeneratorCommonSettings,
+    SourceChangeGeneratorDescription,
+};
+
+const DEFAULT_CHANGE_INTERVAL_NS: u64 = 1_000_000_000;
+const DEFAULT_OP_COLUMN: &str = "op";
+
+// The columns of the CSV file that have a special meaning. All other columns are properties.
+#[derive(Clone, Debug, Serialize)]
+pub struct CsvFileColumns {
+    pub delimiter: u8,
+    pub end_id_column: Option<String>,
+    pub key_column: String,
+    pub op_column: String,
+    pub start_id_column: Option<String>,
+    pub timestamp_column: Option<String>,
+    pub timestamp_unit: CsvTimestampUnit,
+}
+
+impl CsvFileColumns {
+    fn is_property(&self, column: &str) -> bool {
+        column != self.op_column
+            && Some(column) != self.timestamp_column.as_deref()
+            && Some(column) != self.start_id_column.as_deref()
+            && Some(column) != self.end_id_column.as_deref()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CsvFileSourceChangeGeneratorSettings {
+    pub change_interval_ns: u64,
+    pub columns: CsvFileColumns,
+    pub common: SourceChangeGeneratorCommonSettings,
+    pub label: String,
+    pub replay: FileReplaySettings,
+}
+
+impl CsvFileSourceChangeGeneratorSettings {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: CsvFileSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        if definition.csv_file.is_empty() {
+            anyhow::bail!("CsvFileSourceChangeGenerator csv_file must not be empty");
+        }
+        if definition.key_column.is_empty() {
+            anyhow::bail!("CsvFileSourceChangeGenerator key_column must not be empty");
+        }
+        if definition.start_id_column.is_some() != definition.end_id_column.is_some() {
+            anyhow::bail!(
+                "CsvFileSourceChangeGenerator start_id_column and end_id_column must be set together"
+            );
+        }
+
+        let delimiter = match definition.delimiter {
+            None => b',',
+            Some(c) if c.is_ascii() => c as u8,
+            Some(c) => anyhow::bail!(
+                "CsvFileSourceChangeGenerator delimiter must be an ASCII character, found {:?}",
+                c
+            ),
+        };
+
+        let change_interval_ns = definition
+            .change_interval
+            .unwrap_or(DEFAULT_CHANGE_INTERVAL_NS);
+
+        Ok(CsvFileSourceChangeGeneratorSettings {
+            change_interval_ns,
+            columns: CsvFileColumns {
+                delimiter,
+                end_id_column: definition.end_id_column,
+                key_column: definition.key_column,
+                op_column: definition
+                    .op_column
+                    .unwrap_or_else(|| DEFAULT_OP_COLUMN.to_string()),
+                start_id_column: definition.start_id_column,
+                timestamp_column: definition.timestamp_column,
+                timestamp_unit: definition.timestamp_unit,
+            },
+            replay: FileReplaySettings::new(
+                definition.csv_file,
+                input_storage,
+                &definition.common,
+            )?,
+            label: definition
+                .label
+                .unwrap_or_else(|| test_run_source_id.test_source_id.clone()),
+            common: SourceChangeGeneratorCommonSettings::new(
+                test_run_source_id,
+                &definition.common,
+                output_storage,
+                dispatchers,
+            ),
+        })
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        self.common.id.clone()
+    }
+}
+
+pub type CsvFileSourceChangeGenerator = FileReplaySourceChangeGenerator<CsvFileParser>;
+
+impl CsvFileSourceChangeGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: CsvFileSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        let settings = CsvFileSourceChangeGeneratorSettings::new(
+            test_run_source_id,
+            definition,
+            input_storage,
+            output_storage,
+            dispatchers,
+        )
+        .await?;
+
+        Ok(Self::spawn(settings))
+    }
+}
+
+// Turns the rows of a CSV file into changes to nodes, or to relations if the file has
+// start and end id columns.
+pub struct CsvFileParser {
+    change_interval_ns: u64,
+    columns: CsvFileColumns,
+    // The current value of each element, used as the before of updates and deletes.
+    elements: HashMap<String, Value>,
+    label: String,
+}
+
+impl FileReplayParser for CsvFileParser {
+    type Record = CsvChangeRecord;
+    type Settings = CsvFileSourceChangeGeneratorSettings;
+
+    const NAME: &'static str = "CsvFileSourceChangeGenerator";
+    const FILE_KIND: &'static str = "CSV";
+    const FILE_SETTING: &'static str = "csv_file";
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings {
+        &settings.common
+    }
+
+    fn replay_settings(settings: &Self::Settings) -> &FileReplaySettings {
+        &settings.replay
+    }
+
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription {
+        let common = &settings.common;
+        let replay = &settings.replay;
+        let max_eps = max_events_per_second(&replay.spacing_mode, common.max_eps);
+
+        // The number of events depends on the CSV file, which isn't read until the
+        // generator is initialized, so only the rate can be bounded up front.
+        SourceChangeGeneratorDescription::new(
+            "CsvFile",
+            serde_json::json!({
+                "change_interval": settings.change_interval_ns,
+                "csv_file": replay.file,
+                "dispatchers": dispatcher_kinds(&common.dispatchers),
+                "end_id_column": settings.columns.end_id_column,
+                "event_field_names": common.event_field_names,
+                "event_schema": common.event_schema.is_some(),
+                "key_column": settings.columns.key_column,
+                "label": settings.label,
+                "max_eps": common.max_eps,
+                "op_column": settings.columns.op_column,
+                "spacing_mode": replay.spacing_mode,
+                "speed_factor": replay.speed_factor,
+                "start_id_column": settings.columns.start_id_column,
+                "time_mode": common.time_mode,
+                "timestamp_column": settings.columns.timestamp_column,
+                "timestamp_unit": settings.columns.timestamp_unit,
+            }),
+            serde_json::json!({
+                "max_events_per_second": max_eps,
+            }),
+        )
+    }
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            change_interval_ns: settings.change_interval_ns,
+            columns: settings.columns.clone(),
+            elements: HashMap::new(),
+            label: settings.label.clone(),
+        }
+    }
+
+    fn offset_ns(record: &CsvChangeRecord) -> u64 {
+        record.offset_ns
+    }
+
+    fn parse(&self, content: &[u8]) -> anyhow::Result<CsvChangeFile> {
+        read_csv_changes(content, &self.columns, self.change_interval_ns)
+    }
+
+    fn change(&mut self, record: &CsvChangeRecord) -> FileReplayChange {
+        let mut element = serde_json::json!({
+            "id": record.key,
+            "labels": [self.label],
+            "properties": record.properties,
+        });
+        let table = match (&record.start_id, &record.end_id) {
+            (Some(start_id), Some(end_id)) => {
+                element["start_id"] = Value::from(start_id.clone());
+                element["end_id"] = Value::from(end_id.clone());
+                "relation"
+            }
+            _ => "node",
+        };
+
+        let (before, after) = match record.op.as_str() {
+            "i" => {
+                self.elements.insert(record.key.clone(), element.clone());
+                (Value::Null, element)
+            }
+            "u" => {
+                let before = self
+                    .elements
+                    .insert(record.key.clone(), element.clone())
+                    .unwrap_or(Value::Null);
+                (before, element)
+            }
+            _ => {
+                let before = self.elements.remove(&record.key).unwrap_or(element);
+                (before, Value::Null)
+            }
+        };
+
+        FileReplayChange {
+            op: record.op.clone(),
+            table,
+            before,
+            after,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.elements = HashMap::new();
+    }
+}
+
+impl Debug for CsvFileParser {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsvFileParser")
+            .field("columns", &self.columns)
+            .field("element_count", &self.elements.len())
+            .field("label", &self.label)
+            .finish()
+    }
+}
+
+// A change read from one row of the CSV file.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CsvChangeRecord {
+    // Position of the row in the file, starting at 1 for the first row after the header.
+    pub row_num: u64,
+    pub op: String,
+    pub key: String,
+    // Time of the change relative to the first change in the file.
+    pub offset_ns: u64,
+    pub properties: Map<String, Value>,
+    pub start_id: Option<String>,
+    pub end_id: Option<String>,
+}
+
+// The start time is the timestamp of the first change, if the file has a timestamp column.
+pub type CsvChangeFile = FileReplayChanges<CsvChangeRecord>;
+
+// Reads the changes from a CSV file. Rows with a timestamp are offset from the first row's
+// timestamp and must be in timestamp order; without a timestamp column, rows are
+// change_interval_ns apart.
+pub fn read_csv_changes(
+    content: &[u8],
+    columns: &CsvFileColumns,
+    change_interval_ns: u64,
+) -> anyhow::Result<CsvChangeFile> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(columns.delimiter)
+        .trim(csv::Trim::All)
+        .from_reader(content);
+
+    let headers: Vec<String> = reader.headers()?.iter().map(|h| h.to_string()).collect();
+    let column_idx = |name: &str| -> anyhow::Result<usize> {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| anyhow::anyhow!("CSV file has no {} column", name))
+    };
+
+    let key_idx = column_idx(&columns.key_column)?;
+    let op_idx = column_idx(&columns.op_column)?;
+    let timestamp_idx = columns
+        .timestamp_column
+        .as_deref()
+        .map(column_idx)
+        .transpose()?;
+    let start_id_idx = columns
+        .start_id_column
+        .as_deref()
+        .map(column_idx)
+        .transpose()?;
+    let end_id_idx = columns
+        .end_id_column
+        .as_deref()
+        .map(column_idx)
+        .transpose()?;
+
+    let mut file = CsvChangeFile::default();
+    let mut previous_offset_ns = 0;
+
+    for (row_idx, row) in reader.records().enumerate() {
+        let row = row?;
+        let row_num = row_idx as u64 + 1;
+        let cell = |idx: usize| row.get(idx).unwrap_or("");
+
+        let op = parse_op(cell(op_idx))
+            .map_err(|e| anyhow::anyhow!("Invalid op in CSV row {}: {}", row_num, e))?;
+
+        let key = cell(key_idx);
+        if key.is_empty() {
+            anyhow::bail!("CSV row {} has no {} value", row_num, columns.key_column);
+        }
+
+        let offset_ns = match timestamp_idx {
+            Some(idx) => {
+                let timestamp_ns =
+                    parse_timestamp(cell(idx), columns.timestamp_unit).map_err(|e| {
+                        anyhow::anyhow!("Invalid timestamp in CSV row {}: {}", row_num, e)
+                    })?;
+                let start_time_ns = *file.start_time_ns.get_or_insert(timestamp_ns);
+                if timestamp_ns < start_time_ns + previous_offset_ns {
+                    anyhow::bail!("CSV row {} is not in timestamp order", row_num);
+                }
+                timestamp_ns - start_time_ns
+            }
+            None => (row_num - 1).saturating_mul(change_interval_ns),
+        };
+        previous_offset_ns = offset_ns;
+
+        let relation_id = |idx: Option<usize>| -> anyhow::Result<Option<String>> {
+            match idx.map(cell) {
+                Some("") => anyhow::bail!(
+                    "CSV row {} is a relation without a start and end id",
+                    row_num
+                ),
+                other => Ok(other.map(|id| id.to_string())),
+            }
+        };
+        // A delete only needs the key, so the relation ends can be left empty.
+        let (start_id, end_id) = match op {
+            "d" => (
+                start_id_idx
+                    .map(cell)
+                    .filter(|id| !id.is_empty())
+                    .map(String::from),
+                end_id_idx
+                    .map(cell)
+                    .filter(|id| !id.is_empty())
+                    .map(String::from),
+            ),
+            _ => (relation_id(start_id_idx)?, relation_id(end_id_idx)?),
+        };
+
+        let properties = headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| columns.is_property(header))
+            .filter_map(|(idx, header)| parse_cell(cell(idx)).map(|v| (header.clone(), v)))
+            .collect();
+
+        file.records.push(CsvChangeRecord {
+            row_num,
+            op: op.to_string(),
+            key: key.to_string(),
+            offset_ns,
+            properties,
+            start_id,
+            end_id,
+        });
+    }
+
+    Ok(file)
+}
+
+fn parse_op(value: &str) -> anyhow::Result<&'static str> {
+    match value.to_lowercase().as_str() {
+        "i" | "insert" => Ok("i"),
+        "u" | "update" => Ok("u"),
+        "d" | "delete" => Ok("d"),
+        _ => anyhow::bail!("expected i, u or d, found {:?}", value),
+    }
+}
+
+// Parses an RFC 3339 timestamp, or an integer number of timestamp units since the Unix epoch.
+fn parse_timestamp(value: &str, unit: CsvTimestampUnit) -> anyhow::Result<u64> {
+    if let Ok(count) = value.parse::<u64>() {
+        return count
+            .checked_mul(unit.nanos_per_unit())
+            .ok_or_else(|| anyhow::anyhow!("{} is out of range", value));
+    }
+
+    match chrono::DateTime::parse_from_rfc3339(value) {
+        Ok(t) => match t.timestamp_nanos_opt() {
+            Some(nanos) if nanos >= 0 => Ok(nanos as u64),
+            _ => anyhow::bail!("{} is out of range", value),
+        },
+        Err(e) => anyhow::bail!("{:?} is not a timestamp: {}", value, e),
+    }
+}
+
+// CSV cells are untyped, so numbers and booleans are inferred. Empty cells are omitted.
+fn parse_cell(value: &str) -> Option<Value> {
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(b) = value.parse::<bool>() {
+        return Some(Value::Bool(b));
+    }
+    if let Ok(i) = value.parse::<i64>() {
+        return Some(Value::from(i));
+    }
+    if let Some(n) = value
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        return Some(Value::Number(n));
+    }
+    Some(Value::String(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn columns() -> CsvFileColumns {
+        CsvFileColumns {
+            delimiter: b',',
+            end_id_column: None,
+            key_column: "room_id".to_string(),
+            op_column: "op".to_string(),
+            start_id_column: None,
+            timestamp_column: None,
+            timestamp_unit: CsvTimestampUnit::Nanoseconds,
+        }
+    }
+
+    #[test]
+    fn test_read_csv_changes() {
+        let content = "op,room_id,temperature,occupied,name\n\
+                       i,room-1,70.5,true,Lobby\n\
+                       update,room-1,71,false,\n\
+                       D,room-1,,,\n";
+
+        let changes = read_csv_changes(content.as_bytes(), &columns(), 500).unwrap();
+
+        assert_eq!(changes.start_time_ns, None);
+        let ops: Vec<&str> = changes.records.iter().map(|r| r.op.as_str()).collect();
+        assert_eq!(ops, vec!["i", "u", "d"]);
+        let offsets: Vec<u64> = changes.records.iter().map(|r| r.offset_ns).collect();
+        assert_eq!(offsets, vec![0, 500, 1000]);
+
+        assert_eq!(
+            Value::Object(changes.records[0].properties.clone()),
+            json!({ "room_id": "room-1", "temperature": 70.5, "occupied": true, "name": "Lobby" })
+        );
+        assert_eq!(
+            Value::Object(changes.records[1].properties.clone()),
+            json!({ "room_id": "room-1", "temperature": 71, "occupied": false })
+        );
+    }
+
+    #[test]
+    fn test_read_csv_changes_with_timestamps() {
+        let columns = CsvFileColumns {
+            delimiter: b';',
+            end_id_column: Some("to".to_string()),
+            start_id_column: Some("from".to_string()),
+            timestamp_column: Some("ts".to_string()),
+            timestamp_unit: CsvTimestampUnit::Milliseconds,
+            ..columns()
+        };
+        let content = "op;room_id;from;to;ts\n\
+                       i;rel-1;floor-1;room-1;1000\n\
+                       i;rel-2;floor-1;room-2;1970-01-01T00:00:01.250Z\n\
+                       d;rel-1;;;3000\n";
+
+        let changes = read_csv_changes(content.as_bytes(), &columns, 0).unwrap();
+
+        assert_eq!(changes.start_time_ns, Some(1_000_000_000));
+        let offsets: Vec<u64> = changes.records.iter().map(|r| r.offset_ns).collect();
+        assert_eq!(offsets, vec![0, 250_000_000, 2_000_000_000]);
+
+        let record = &changes.records[1];
+        assert_eq!(record.start_id.as_deref(), Some("floor-1"));
+        assert_eq!(record.end_id.as_deref(), Some("room-2"));
+        assert_eq!(
+            Value::Object(record.properties.clone()),
+            json!({ "room_id": "rel-2" })
+        );
+        assert_eq!(changes.records[2].start_id, None);
+    }
+
+    #[test]
+    fn test_read_csv_changes_errors() {
+        let read = |content: &str, columns: &CsvFileColumns| {
+            read_csv_changes(content.as_bytes(), columns, 0)
+        };
+        let timestamped = CsvFileColumns {
+            timestamp_column: Some("ts".to_string()),
+            ..columns()
+        };
+
+        assert!(read("op,id\ni,room-1\n", &columns()).is_err());
+        assert!(read("op,room_id\nx,room-1\n", &columns()).is_err());
+        assert!(read("op,room_id\ni,\n", &columns()).is_err());
+        assert!(read("op,room_id\ni,room-1\n", &timestamped).is_err());
+        assert!(read("op,room_id,ts\ni,room-1,20\nu,room-1,10\n", &timestamped).is_err());
+        assert!(read("op,room_id,ts\ni,room-1,soon\n", &timestamped).is_err());
+    }
+}
@@ -0,0 +1,564 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use test_data_store::{
+    test_repo_storage::{
+        models::{DebeziumFileSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition},
+        TestSourceStorage,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use super::{
+    dispatcher_kinds,
+    file_replay_source_change_generator::{
+        FileReplayChange, FileReplayChanges, FileReplayParser, FileReplaySettings,
+        FileReplaySourceChangeGenerator,
+    },
+    max_events_per_second,
+    source_change_producer::SourceChangeGeneratorCommonSettings,
+    SourceChangeGeneratorDescription,
+};
+
+const DEFAULT_KEY_FIELD: &str = "id";
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DebeziumFileSourceChangeGeneratorSettings {
+    pub common: SourceChangeGeneratorCommonSettings,
+    pub key_field: String,
+    pub label: Option<String>,
+    pub replay: FileReplaySettings,
+}
+
+impl DebeziumFileSourceChangeGeneratorSettings {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: DebeziumFileSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        if definition.debezium_file.is_empty() {
+            anyhow::bail!("DebeziumFileSourceChangeGenerator debezium_file must not be empty");
+        }
+        if definition.key_field.as_deref() == Some("") {
+            anyhow::bail!("DebeziumFileSourceChangeGenerator key_field must not be empty");
+        }
+
+        Ok(DebeziumFileSourceChangeGeneratorSettings {
+            common: SourceChangeGeneratorCommonSettings::new(
+                test_run_source_id,
+                &definition.common,
+                output_storage,
+                dispatchers,
+            ),
+            key_field: definition
+                .key_field
+                .unwrap_or_else(|| DEFAULT_KEY_FIELD.to_string()),
+            label: definition.label,
+            replay: FileReplaySettings::new(
+                definition.debezium_file,
+                input_storage,
+                &definition.common,
+            )?,
+        })
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        self.common.id.clone()
+    }
+}
+
+pub type DebeziumFileSourceChangeGenerator = FileReplaySourceChangeGenerator<DebeziumFileParser>;
+
+impl DebeziumFileSourceChangeGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: DebeziumFileSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        let settings = DebeziumFileSourceChangeGeneratorSettings::new(
+            test_run_source_id,
+            definition,
+            input_storage,
+            output_storage,
+            dispatchers,
+        )
+        .await?;
+
+        Ok(Self::spawn(settings))
+    }
+}
+
+// Replays Debezium events, whose before and after rows are converted to nodes when the
+// file is read.
+#[derive(Debug)]
+pub struct DebeziumFileParser {
+    // Label used for events without a source.table when no label is set.
+    default_label: String,
+    key_field: String,
+    label: Option<String>,
+}
+
+impl FileReplayParser for DebeziumFileParser {
+    type Record = DebeziumChangeRecord;
+    type Settings = DebeziumFileSourceChangeGeneratorSettings;
+
+    const NAME: &'static str = "DebeziumFileSourceChangeGenerator";
+    const FILE_KIND: &'static str = "Debezium";
+    const FILE_SETTING: &'static str = "debezium_file";
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings {
+        &settings.common
+    }
+
+    fn replay_settings(settings: &Self::Settings) -> &FileReplaySettings {
+        &settings.replay
+    }
+
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription {
+        let common = &settings.common;
+        let replay = &settings.replay;
+        let max_eps = max_events_per_second(&replay.spacing_mode, common.max_eps);
+
+        // The number of events depends on the Debezium file, which isn't read until the
+        // generator is initialized, so only the rate can be bounded up front.
+        SourceChangeGeneratorDescription::new(
+            "DebeziumFile",
+            serde_json::json!({
+                "debezium_file": replay.file,
+                "dispatchers": dispatcher_kinds(&common.dispatchers),
+                "event_field_names": common.event_field_names,
+                "event_schema": common.event_schema.is_some(),
+                "key_field": settings.key_field,
+                "label": settings.label,
+                "max_eps": common.max_eps,
+                "spacing_mode": replay.spacing_mode,
+                "speed_factor": replay.speed_factor,
+                "time_mode": common.time_mode,
+            }),
+            serde_json::json!({
+                "max_events_per_second": max_eps,
+            }),
+        )
+    }
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            default_label: settings.common.id.test_source_id.clone(),
+            key_field: settings.key_field.clone(),
+            label: settings.label.clone(),
+        }
+    }
+
+    fn offset_ns(record: &DebeziumChangeRecord) -> u64 {
+        record.offset_ns
+    }
+
+    fn parse(&self, content: &[u8]) -> anyhow::Result<DebeziumChangeFile> {
+        read_debezium_changes(
+            content,
+            &self.key_field,
+            self.label.as_deref(),
+            &self.default_label,
+        )
+    }
+
+    fn change(&mut self, record: &DebeziumChangeRecord) -> FileReplayChange {
+        FileReplayChange {
+            op: record.op.clone(),
+            table: "node",
+            before: record.before.clone(),
+            after: record.after.clone(),
+        }
+    }
+}
+
+// A change read from one Debezium event, with the before and after rows already
+// converted to elements.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DebeziumChangeRecord {
+    // Position of the event in the file, starting at 1.
+    pub event_num: u64,
+    // The Debezium op: c, r, u or d.
+    pub debezium_op: String,
+    // The equivalent SourceChangeEvent op: i, u or d.
+    pub op: String,
+    pub key: String,
+    // Time of the change relative to the first change in the file.
+    pub offset_ns: u64,
+    pub before: Value,
+    pub after: Value,
+}
+
+// The start time is the source.ts_ms of the first change, in nanoseconds.
+pub type DebeziumChangeFile = FileReplayChanges<DebeziumChangeRecord>;
+
+// Reads the Debezium events from a file of JSON values, which may be JSON Lines, a JSON
+// array, or concatenated objects. Tombstones (null values) are ignored. Each event is
+// offset from the first event's source.ts_ms, falling back to the envelope's ts_ms. Events
+// out of timestamp order are given the previous event's offset, so replay never goes back
+// in time.
+pub fn read_debezium_changes(
+    content: &[u8],
+    key_field: &str,
+    label: Option<&str>,
+    default_label: &str,
+) -> anyhow::Result<DebeziumChangeFile> {
+    let mut events = Vec::new();
+    for value in serde_json::Deserializer::from_slice(content).into_iter::<Value>() {
+        match value? {
+            Value::Array(values) => events.extend(values),
+            value => events.push(value),
+        }
+    }
+
+    let mut file = DebeziumChangeFile::default();
+    let mut previous_offset_ns = 0;
+
+    for (event_idx, event) in events
+        .into_iter()
+        .filter(|event| !event.is_null())
+        .enumerate()
+    {
+        let event_num = event_idx as u64 + 1;
+
+        // Events serialized with the JSON converter's schemas enabled wrap the envelope in
+        // a payload.
+        let envelope = match event.get("payload") {
+            Some(payload) if payload.get("op").is_some() => payload,
+            _ => &event,
+        };
+
+        let debezium_op = envelope
+            .get("op")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Debezium event {} has no op", event_num))?;
+        let op = parse_op(debezium_op)
+            .map_err(|e| anyhow::anyhow!("Invalid op in Debezium event {}: {}", event_num, e))?;
+
+        let source = envelope.get("source");
+        let timestamp_ms = source
+            .and_then(|source| source.get("ts_ms"))
+            .or_else(|| envelope.get("ts_ms"))
+            .and_then(Value::as_u64)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Debezium event {} has no source.ts_ms or ts_ms", event_num)
+            })?;
+        let timestamp_ns = timestamp_ms
+            .checked_mul(1_000_000)
+            .ok_or_else(|| anyhow::anyhow!("Debezium event {} ts_ms is out of range", event_num))?;
+        let start_time_ns = *file.start_time_ns.get_or_insert(timestamp_ns);
+        let offset_ns = timestamp_ns
+            .saturating_sub(start_time_ns)
+            .max(previous_offset_ns);
+        previous_offset_ns = offset_ns;
+
+        let label = label
+            .or_else(|| {
+                source
+                    .and_then(|source| source.get("table"))
+                    .and_then(Value::as_str)
+            })
+            .unwrap_or(default_label);
+
+        // Deletes only have a before row, and inserts only an after row.
+        let row = |field: &str| match envelope.get(field) {
+            Some(row) if row.is_object() => Ok(row),
+            _ => Err(anyhow::anyhow!(
+                "Debezium event {} with op {} has no {} row",
+                event_num,
+                debezium_op,
+                field
+            )),
+        };
+        let (before, after) = match op {
+            "i" => (None, Some(row("after")?)),
+            // The before row is only captured if the table is configured for it.
+            "u" => (
+                envelope.get("before").filter(|row| row.is_object()),
+                Some(row("after")?),
+            ),
+            _ => (Some(row("before")?), None),
+        };
+
+        let key = match after.or(before).and_then(|row| row.get(key_field)) {
+            Some(Value::String(key)) => key.clone(),
+            Some(key @ Value::Number(_)) => key.to_string(),
+            _ => anyhow::bail!("Debezium event {} has no {} value", event_num, key_field),
+        };
+
+        let element = |row: Option<&Value>| match row {
+            Some(row) => serde_json::json!({
+                "id": key,
+                "labels": [label],
+                "properties": row,
+            }),
+            None => Value::Null,
+        };
+
+        file.records.push(DebeziumChangeRecord {
+            event_num,
+            debezium_op: debezium_op.to_string(),
+            op: op.to_string(),
+            key: key.clone(),
+            offset_ns,
+            before: element(before),
+            after: element(after),
+        });
+    }
+
+    Ok(file)
+}
+
+// Snapshot reads are the rows that existed when the capture started, so they are inserts.
+fn parse_op(value: &str) -> anyhow::Result<&'static str> {
+    match value {
+        "c" | "r" => Ok("i"),
+        "u" => Ok("u"),
+        "d" => Ok("d"),
+        _ => anyhow::bail!("expected c, r, u or d, found {:?}", value),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::sources::{
+        source_change_dispatchers::test_support::{FailingDispatcher, RecordingDispatcher},
+        source_change_generators::{
+            file_replay_source_change_generator::FileReplayProducer,
+            source_change_producer::{
+                SourceChangeProducerCommand, SourceChangeProducerInternalState,
+            },
+            SourceChangeEventFeed, SourceChangeGeneratorStatus,
+        },
+    };
+
+    // A snapshot read, a create wrapped with its schema, an update, a tombstone and a delete,
+    // as written by the Debezium JSON converter.
+    const DEBEZIUM_FIXTURE: &str = r#"
+{"before":null,"after":{"id":1,"name":"Lobby","temperature":70.5},"source":{"db":"building","table":"room","ts_ms":1704067200000},"op":"r","ts_ms":1704067200100}
+{"schema":{"type":"struct"},"payload":{"before":null,"after":{"id":2,"name":"Kitchen","temperature":68.0},"source":{"db":"building","table":"room","ts_ms":1704067201000},"op":"c","ts_ms":1704067201100}}
+{"before":{"id":1,"name":"Lobby","temperature":70.5},"after":{"id":1,"name":"Lobby","temperature":72.0},"source":{"db":"building","table":"room","ts_ms":1704067201500},"op":"u","ts_ms":1704067201600}
+null
+{"before":{"id":2,"name":"Kitchen","temperature":68.0},"after":null,"source":{"db":"building","table":"room","ts_ms":1704067203000},"op":"d","ts_ms":1704067203100}
+"#;
+
+    #[test]
+    fn test_read_debezium_changes() {
+        let changes =
+            read_debezium_changes(DEBEZIUM_FIXTURE.as_bytes(), "id", None, "source-001").unwrap();
+
+        assert_eq!(changes.start_time_ns, Some(1_704_067_200_000_000_000));
+        let ops: Vec<(&str, &str)> = changes
+            .records
+            .iter()
+            .map(|r| (r.debezium_op.as_str(), r.op.as_str()))
+            .collect();
+        assert_eq!(ops, vec![("r", "i"), ("c", "i"), ("u", "u"), ("d", "d")]);
+        let offsets: Vec<u64> = changes.records.iter().map(|r| r.offset_ns).collect();
+        assert_eq!(
+            offsets,
+            vec![0, 1_000_000_000, 1_500_000_000, 3_000_000_000]
+        );
+
+        let update = &changes.records[2];
+        assert_eq!(update.key, "1");
+        assert_eq!(
+            update.before,
+            json!({
+                "id": "1",
+                "labels": ["room"],
+                "properties": { "id": 1, "name": "Lobby", "temperature": 70.5 }
+            })
+        );
+        assert_eq!(update.after["properties"]["temperature"], json!(72.0));
+
+        let delete = &changes.records[3];
+        assert_eq!(delete.key, "2");
+        assert_eq!(delete.before["properties"]["name"], json!("Kitchen"));
+        assert_eq!(delete.after, Value::Null);
+
+        // The label setting overrides the table.
+        let changes = read_debezium_changes(
+            DEBEZIUM_FIXTURE.as_bytes(),
+            "id",
+            Some("Room"),
+            "source-001",
+        )
+        .unwrap();
+        assert_eq!(changes.records[0].after["labels"], json!(["Room"]));
+    }
+
+    #[test]
+    fn test_read_debezium_changes_as_array() {
+        let content = r#"[
+            {"after":{"room_id":"a"},"op":"c","ts_ms":2000},
+            {"after":{"room_id":"b"},"op":"c","ts_ms":1000}
+        ]"#;
+
+        let changes = read_debezium_changes(content.as_bytes(), "room_id", None, "rooms").unwrap();
+
+        let keys: Vec<&str> = changes.records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "b"]);
+        assert_eq!(changes.records[0].after["labels"], json!(["rooms"]));
+        // Events out of timestamp order don't go back in time.
+        let offsets: Vec<u64> = changes.records.iter().map(|r| r.offset_ns).collect();
+        assert_eq!(offsets, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_read_debezium_changes_errors() {
+        let read = |content: &str| read_debezium_changes(content.as_bytes(), "id", None, "src");
+
+        assert!(read(r#"{"after":{"id":1},"ts_ms":1}"#).is_err());
+        assert!(read(r#"{"after":{"id":1},"op":"t","ts_ms":1}"#).is_err());
+        assert!(read(r#"{"after":{"id":1},"op":"c"}"#).is_err());
+        assert!(read(r#"{"after":{"name":"Lobby"},"op":"c","ts_ms":1}"#).is_err());
+        assert!(read(r#"{"before":null,"after":null,"op":"d","ts_ms":1}"#).is_err());
+        assert!(read(r#"{"after":{"id":1},"op":"c","ts_ms":1"#).is_err());
+    }
+
+    // Initializes a replay of DEBEZIUM_FIXTURE, with no spacing between the changes.
+    async fn replay_state(
+        dir: &std::path::Path,
+        event_feed: SourceChangeEventFeed,
+    ) -> SourceChangeProducerInternalState<FileReplayProducer<DebeziumFileParser>> {
+        let input_path = dir.join("input");
+        tokio::fs::create_dir_all(&input_path).await.unwrap();
+        tokio::fs::write(input_path.join("rooms.jsonl"), DEBEZIUM_FIXTURE)
+            .await
+            .unwrap();
+
+        let id = TestRunSourceId::try_from("test-repo.test-001.run-001.source-001").unwrap();
+        let definition: DebeziumFileSourceChangeGeneratorDefinition =
+            serde_json::from_value(json!({
+                "debezium_file": "rooms.jsonl",
+                "spacing_mode": "none",
+                "time_mode": "recorded"
+            }))
+            .unwrap();
+        let input_storage = TestSourceStorage {
+            id: "source-001".to_string(),
+            path: input_path,
+            repo_id: "test-repo".to_string(),
+            test_id: "test-001".to_string(),
+            test_source_definition: serde_json::from_value(json!({
+                "kind": "Script",
+                "test_source_id": "source-001"
+            }))
+            .unwrap(),
+        };
+        let output_storage = TestRunSourceStorage {
+            id: id.clone(),
+            path: dir.join("output"),
+            source_change_path: dir.join("output").join("source_change_dispatchers"),
+        };
+        let settings = DebeziumFileSourceChangeGeneratorSettings::new(
+            id,
+            definition,
+            input_storage,
+            output_storage,
+            Vec::new(),
+        )
+        .await
+        .unwrap();
+
+        SourceChangeProducerInternalState::initialize(settings, event_feed)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_debezium_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let event_feed = SourceChangeEventFeed::default();
+        let mut events_rx = event_feed.subscribe();
+        let mut state = replay_state(dir.path(), event_feed).await;
+        let dispatcher = RecordingDispatcher::new();
+        state.dispatchers.push(Box::new(dispatcher.clone()));
+
+        state
+            .transition(&SourceChangeProducerCommand::Start)
+            .await
+            .unwrap();
+        while state.status == SourceChangeGeneratorStatus::Running {
+            state.process_next_change().await;
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(state.stats.num_source_change_events, 4);
+
+        // The event feed is dispatched to alongside the configured dispatchers.
+        let mut seqs = Vec::new();
+        while let Ok(summary) = events_rx.try_recv() {
+            seqs.push(summary.seq);
+        }
+        assert_eq!(seqs, vec![1, 2, 3, 4]);
+
+        let events = dispatcher.events();
+        let ops: Vec<&str> = events.iter().map(|e| e.op.as_str()).collect();
+        assert_eq!(ops, vec!["i", "i", "u", "d"]);
+        // Under TimeMode::Recorded the changes keep their Debezium source.ts_ms.
+        let ts_ns: Vec<u64> = events.iter().map(|e| e.payload.source.ts_ns).collect();
+        assert_eq!(
+            ts_ns,
+            vec![
+                1_704_067_200_000_000_000,
+                1_704_067_201_000_000_000,
+                1_704_067_201_500_000_000,
+                1_704_067_203_000_000_000
+            ]
+        );
+        assert_eq!(
+            events[1].payload.after,
+            json!({
+                "id": "2",
+                "labels": ["room"],
+                "properties": { "id": 2, "name": "Kitchen", "temperature": 68.0 }
+            })
+        );
+        assert_eq!(events[1].payload.before, Value::Null);
+        assert_eq!(events[3].payload.before["id"], json!("2"));
+        assert_eq!(events[3].payload.after, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut state = replay_state(dir.path(), SourceChangeEventFeed::default()).await;
+        state.dispatchers.push(Box::new(FailingDispatcher));
+
+        state
+            .transition(&SourceChangeProducerCommand::Start)
+            .await
+            .unwrap();
+        while state.status == SourceChangeGeneratorStatus::Running {
+            state.process_next_change().await;
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Error);
+        assert_eq!(state.stats.num_source_change_events, 0);
+        assert!(state
+            .status_reason
+            .starts_with("error: Error dispatching SourceChangeEvents"));
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use jsonschema::JSONSchema;
+use serde::Serialize;
+use serde_json::Value;
+
+use test_data_store::{
+    scripts::SourceChangeEvent, test_repo_storage::models::SourceChangeEventSchemaDefinition,
+};
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SourceChangeEventValidationStats {
+    pub num_validated_events: u64,
+    pub num_violations: u64,
+    pub last_violation: Option<String>,
+}
+
+// Validates the before and after of each SourceChangeEvent against the event_schema from
+// the generator definition before the event is dispatched. The schema is compiled once when
+// the validator is created. Without an event_schema, validation is a no-op.
+#[derive(Debug)]
+pub struct SourceChangeEventValidator {
+    halt_on_violation: bool,
+    schema: Option<JSONSchema>,
+    stats: SourceChangeEventValidationStats,
+}
+
+impl SourceChangeEventValidator {
+    pub fn new(definition: Option<&SourceChangeEventSchemaDefinition>) -> anyhow::Result<Self> {
+        let (schema, halt_on_violation) = match definition {
+            Some(definition) => {
+                let schema = JSONSchema::compile(&definition.schema)
+                    .map_err(|e| anyhow::anyhow!("Invalid event_schema: {}", e))?;
+                (Some(schema), definition.halt_on_violation)
+            }
+            None => (None, false),
+        };
+
+        Ok(Self {
+            halt_on_violation,
+            schema,
+            stats: SourceChangeEventValidationStats::default(),
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.stats = SourceChangeEventValidationStats::default();
+    }
+
+    // Returns the validation stats, or None if there is no event_schema.
+    pub fn stats(&self) -> Option<SourceChangeEventValidationStats> {
+        self.schema.as_ref().map(|_| self.stats.clone())
+    }
+
+    // Validates the events, recording any violations. Returns an error on the first
+    // violation if halt_on_violation is set, in which case none of the events should be
+    // dispatched.
+    pub fn validate(&mut self, events: &[&SourceChangeEvent]) -> anyhow::Result<()> {
+        let Some(schema) = &self.schema else {
+            return Ok(());
+        };
+
+        for event in events {
+            self.stats.num_validated_events += 1;
+
+            let violations: Vec<String> = [
+                ("before", &event.payload.before),
+                ("after", &event.payload.after),
+            ]
+            .into_iter()
+            .filter(|(_, value)| !is_empty_element(value))
+            .flat_map(|(name, value)| match schema.validate(value) {
+                Ok(_) => Vec::new(),
+                Err(errors) => errors
+                    .map(|e| format!("{}{}: {}", name, e.instance_path, e))
+                    .collect(),
+            })
+            .collect();
+
+            if !violations.is_empty() {
+                let violation = format!(
+                    "SourceChangeEvent (op: {}, lsn: {}) violates event_schema - {}",
+                    event.op,
+                    event.payload.source.lsn,
+                    violations.join("; ")
+                );
+                log::warn!("{}", violation);
+
+                self.stats.num_violations += 1;
+                self.stats.last_violation = Some(violation.clone());
+
+                if self.halt_on_violation {
+                    anyhow::bail!(violation);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Inserts have no before and deletes have no after; these are not validated.
+fn is_empty_element(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Object(map) => map.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn definition(halt_on_violation: bool) -> SourceChangeEventSchemaDefinition {
+        SourceChangeEventSchemaDefinition {
+            schema: json!({
+                "type": "object",
+                "required": ["id", "labels", "properties"],
+                "properties": {
+                    "properties": {
+                        "type": "object",
+                        "properties": { "temperature": { "type": "number" } }
+                    }
+                }
+            }),
+            halt_on_violation,
+        }
+    }
+
+    fn event(op: &str, before: Value, after: Value) -> SourceChangeEvent {
+        serde_json::from_value(json!({
+            "op": op,
+            "reactivatorStart_ns": 0,
+            "reactivatorEnd_ns": 0,
+            "payload": {
+                "source": { "db": "test", "table": "node", "ts_ns": 0, "lsn": 7 },
+                "before": before,
+                "after": after
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_events() {
+        let mut validator = SourceChangeEventValidator::new(Some(&definition(true))).unwrap();
+        let node = json!({ "id": "r1", "labels": ["Room"], "properties": { "temperature": 21.5 } });
+
+        let insert = event("i", json!({}), node.clone());
+        let delete = event("d", node, Value::Null);
+        assert!(validator.validate(&[&insert, &delete]).is_ok());
+
+        let stats = validator.stats().unwrap();
+        assert_eq!(stats.num_validated_events, 2);
+        assert_eq!(stats.num_violations, 0);
+    }
+
+    #[test]
+    fn test_violation_recorded() {
+        let mut validator = SourceChangeEventValidator::new(Some(&definition(false))).unwrap();
+        let bad = event(
+            "u",
+            json!({ "id": "r1", "labels": ["Room"], "properties": {} }),
+            json!({ "id": "r1", "properties": { "temperature": "hot" } }),
+        );
+
+        assert!(validator.validate(&[&bad]).is_ok());
+
+        let stats = validator.stats().unwrap();
+        assert_eq!(stats.num_violations, 1);
+        let violation = stats.last_violation.unwrap();
+        assert!(violation.contains("lsn: 7"));
+        assert!(violation.contains("after/properties/temperature"));
+    }
+
+    #[test]
+    fn test_halt_on_violation() {
+        let mut validator = SourceChangeEventValidator::new(Some(&definition(true))).unwrap();
+        let bad = event("i", Value::Null, json!({ "id": "r1" }));
+
+        assert!(validator.validate(&[&bad]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_schema() {
+        let definition = SourceChangeEventSchemaDefinition {
+            schema: json!({ "type": "not-a-type" }),
+            halt_on_violation: false,
+        };
+
+        assert!(SourceChangeEventValidator::new(Some(&definition)).is_err());
+    }
+
+    #[test]
+    fn test_no_schema() {
+        let mut validator = SourceChangeEventValidator::new(None).unwrap();
+        let event = event("i", Value::Null, json!("anything"));
+
+        assert!(validator.validate(&[&event]).is_ok());
+        assert!(validator.stats().is_none());
+    }
+}
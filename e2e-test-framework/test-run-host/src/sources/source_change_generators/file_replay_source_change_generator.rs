@@ -0,0 +1,342 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::Instant;
+
+use test_data_store::{
+    scripts::{SourceChangeEvent, SourceChangeEventPayload, SourceChangeEventSourceInfo},
+    test_repo_storage::{
+        models::{CommonSourceChangeGeneratorDefinition, SpacingMode, TimeMode},
+        TestSourceStorage,
+    },
+};
+
+use super::{
+    scale_delay_ns,
+    source_change_producer::{
+        now_ns, ProducedChanges, SourceChangeGeneratorCommonSettings, SourceChangeProducer,
+        SourceChangeProducerGenerator,
+    },
+    validate_spacing_rate, validate_speed_factor, BurstTracker, SourceChangeGeneratorDescription,
+};
+
+// The settings of a generator that replays the changes recorded in a file.
+#[derive(Clone, Debug, Serialize)]
+pub struct FileReplaySettings {
+    // Path of the file, relative to the test source folder.
+    pub file: String,
+    pub input_storage: TestSourceStorage,
+    pub spacing_mode: SpacingMode,
+    pub speed_factor: f64,
+}
+
+impl FileReplaySettings {
+    pub fn new(
+        file: String,
+        input_storage: TestSourceStorage,
+        definition: &CommonSourceChangeGeneratorDefinition,
+    ) -> anyhow::Result<Self> {
+        validate_spacing_rate(&definition.spacing_mode, definition.max_spacing_rate)?;
+        validate_speed_factor(definition.speed_factor)?;
+
+        Ok(Self {
+            file,
+            input_storage,
+            spacing_mode: definition.spacing_mode.clone(),
+            speed_factor: definition.speed_factor,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileReplayChanges<R> {
+    // Recorded time of the first change, if the file records the time of its changes.
+    pub start_time_ns: Option<u64>,
+    pub records: Vec<R>,
+}
+
+impl<R> Default for FileReplayChanges<R> {
+    fn default() -> Self {
+        Self {
+            start_time_ns: None,
+            records: Vec::new(),
+        }
+    }
+}
+
+// The SourceChangeEvent fields that come from a record of the file.
+#[derive(Debug)]
+pub struct FileReplayChange {
+    pub op: String,
+    pub table: &'static str,
+    pub before: Value,
+    pub after: Value,
+}
+
+// Reads the changes from a file and turns each record into a change. Everything else about
+// replaying the file is done by the FileReplayProducer.
+pub trait FileReplayParser: Debug + Send + Sized + 'static {
+    type Record: Clone + Debug + Send + Sync + Serialize;
+    type Settings: Clone + Debug + Send + Sync + 'static;
+
+    // The name of the generator, used in errors and log messages.
+    const NAME: &'static str;
+    // The kind of file in log and error messages, e.g. "CSV".
+    const FILE_KIND: &'static str;
+    // The setting that names the file, e.g. "csv_file".
+    const FILE_SETTING: &'static str;
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings;
+    fn replay_settings(settings: &Self::Settings) -> &FileReplaySettings;
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription;
+    fn new(settings: &Self::Settings) -> Self;
+    fn offset_ns(record: &Self::Record) -> u64;
+
+    fn parse(&self, content: &[u8]) -> anyhow::Result<FileReplayChanges<Self::Record>>;
+    // Called for every record in order, including skipped records.
+    fn change(&mut self, record: &Self::Record) -> FileReplayChange;
+    // Called when the generator is reset to the start of the file.
+    fn reset(&mut self) {}
+}
+
+pub type FileReplaySourceChangeGenerator<P> = SourceChangeProducerGenerator<FileReplayProducer<P>>;
+
+// Replays the records of a file one at a time, spaced by the SpacingMode.
+pub struct FileReplayProducer<P: FileReplayParser> {
+    burst_tracker: BurstTracker,
+    changes: FileReplayChanges<P::Record>,
+    event_seq_num: u64,
+    next_record_idx: usize,
+    parser: P,
+    previous_offset_ns: u64,
+    settings: P::Settings,
+    virtual_time_ns_current: u64,
+    virtual_time_ns_start: u64,
+}
+
+impl<P: FileReplayParser> Debug for FileReplayProducer<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileReplayProducer")
+            .field("event_seq_num", &self.event_seq_num)
+            .field(
+                "next_record",
+                &self.changes.records.get(self.next_record_idx),
+            )
+            .field("parser", &self.parser)
+            .field("record_count", &self.changes.records.len())
+            .field("virtual_time_ns_current", &self.virtual_time_ns_current)
+            .field("virtual_time_ns_start", &self.virtual_time_ns_start)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReplayProducerState<R> {
+    pub event_seq_num: u64,
+    pub next_record: Option<R>,
+    pub record_count: usize,
+    pub spacing_mode: SpacingMode,
+    pub virtual_time_ns_current: u64,
+    pub virtual_time_ns_start: u64,
+}
+
+#[async_trait]
+impl<P: FileReplayParser> SourceChangeProducer for FileReplayProducer<P> {
+    type Settings = P::Settings;
+    type State = FileReplayProducerState<P::Record>;
+    type Stats = ();
+
+    const NAME: &'static str = P::NAME;
+    const STEP_UNIT: &'static str = "records";
+
+    fn common_settings(settings: &P::Settings) -> &SourceChangeGeneratorCommonSettings {
+        P::common_settings(settings)
+    }
+
+    fn describe(settings: &P::Settings) -> SourceChangeGeneratorDescription {
+        P::describe(settings)
+    }
+
+    async fn initialize(settings: P::Settings) -> anyhow::Result<Self> {
+        let replay_settings = P::replay_settings(&settings);
+        let path = replay_settings
+            .input_storage
+            .path
+            .join(&replay_settings.file);
+        let content = tokio::fs::read(&path).await.map_err(|e| {
+            anyhow::anyhow!("Error reading {} file {:?}: {}", P::FILE_KIND, path, e)
+        })?;
+
+        let parser = P::new(&settings);
+        let changes = parser.parse(&content).map_err(|e| {
+            anyhow::anyhow!("Error parsing {} file {:?}: {}", P::FILE_KIND, path, e)
+        })?;
+        log::info!(
+            "Read {} changes from {} file {:?} for TestRunSource {}",
+            changes.records.len(),
+            P::FILE_KIND,
+            path,
+            P::common_settings(&settings).id
+        );
+
+        Ok(Self {
+            burst_tracker: BurstTracker::default(),
+            changes,
+            event_seq_num: 0,
+            next_record_idx: 0,
+            parser,
+            previous_offset_ns: 0,
+            settings,
+            virtual_time_ns_current: 0,
+            virtual_time_ns_start: 0,
+        })
+    }
+
+    fn settings(&self) -> &P::Settings {
+        &self.settings
+    }
+
+    fn input(&self) -> (&'static str, String) {
+        (
+            P::FILE_SETTING,
+            P::replay_settings(&self.settings).file.clone(),
+        )
+    }
+
+    fn state(&self) -> Self::State {
+        FileReplayProducerState {
+            event_seq_num: self.event_seq_num,
+            next_record: self.changes.records.get(self.next_record_idx).cloned(),
+            record_count: self.changes.records.len(),
+            spacing_mode: P::replay_settings(&self.settings).spacing_mode.clone(),
+            virtual_time_ns_current: self.virtual_time_ns_current,
+            virtual_time_ns_start: self.virtual_time_ns_start,
+        }
+    }
+
+    fn stats(&self) -> Self::Stats {}
+
+    fn start(&mut self, start_time_ns: u64) {
+        // Without recorded timestamps, the changes are replayed from the start time.
+        self.virtual_time_ns_start = self.changes.start_time_ns.unwrap_or(start_time_ns);
+    }
+
+    fn reset(&mut self) {
+        // These fields do not get reset:
+        //   self.changes
+        //   self.settings
+        self.burst_tracker.reset();
+        self.event_seq_num = 0;
+        self.next_record_idx = 0;
+        self.parser.reset();
+        self.previous_offset_ns = 0;
+        self.virtual_time_ns_current = 0;
+        self.virtual_time_ns_start = 0;
+    }
+
+    fn next_change_at(
+        &mut self,
+        spacing_mode: Option<SpacingMode>,
+        _started_at: Option<Instant>,
+    ) -> Instant {
+        let replay_settings = P::replay_settings(&self.settings);
+        let next_offset_ns = self
+            .changes
+            .records
+            .get(self.next_record_idx)
+            .map(P::offset_ns);
+
+        let spacing_mode = spacing_mode.unwrap_or_else(|| replay_settings.spacing_mode.clone());
+        let delay = match (spacing_mode, next_offset_ns) {
+            (_, None) | (SpacingMode::None, _) => Duration::ZERO,
+            (SpacingMode::Rate(rate), _) => Duration::from_secs_f64(1.0 / rate.get() as f64),
+            (
+                SpacingMode::Burst {
+                    burst_size,
+                    burst_interval_ns,
+                },
+                _,
+            ) => self.burst_tracker.next_delay(burst_size, burst_interval_ns),
+            (SpacingMode::Recorded, Some(offset_ns)) => Duration::from_nanos(scale_delay_ns(
+                offset_ns.saturating_sub(self.previous_offset_ns),
+                replay_settings.speed_factor,
+            )),
+        };
+        Instant::now() + delay
+    }
+
+    // Replays the next record of the file. Skipped records still go through the parser so
+    // it can track the elements they change.
+    async fn produce(
+        &mut self,
+        _skipping: bool,
+        _limit: Option<u64>,
+    ) -> anyhow::Result<ProducedChanges> {
+        let Some(record) = self.changes.records.get(self.next_record_idx) else {
+            return Ok(ProducedChanges {
+                finished: true,
+                ..Default::default()
+            });
+        };
+
+        // Time Mode controls the time given to a change, as in the ScriptSourceChangeGenerator.
+        let offset_ns = P::offset_ns(record);
+        let common_settings = P::common_settings(&self.settings);
+        self.virtual_time_ns_current = match common_settings.time_mode {
+            TimeMode::Live => now_ns(),
+            TimeMode::Recorded => self.virtual_time_ns_start + offset_ns,
+            TimeMode::Rebased(nanos) => nanos + offset_ns,
+        };
+
+        let change = self.parser.change(record);
+        self.event_seq_num += 1;
+        self.next_record_idx += 1;
+        self.previous_offset_ns = offset_ns;
+
+        let now_ns = now_ns();
+        let event = SourceChangeEvent {
+            op: change.op,
+            reactivator_start_ns: now_ns,
+            reactivator_end_ns: now_ns + 1,
+            payload: SourceChangeEventPayload {
+                source: SourceChangeEventSourceInfo {
+                    db: common_settings.id.test_source_id.to_string(),
+                    lsn: self.event_seq_num,
+                    table: change.table.to_string(),
+                    ts_ns: self.virtual_time_ns_current,
+                },
+                before: change.before,
+                after: change.after,
+            },
+        };
+
+        Ok(ProducedChanges {
+            events: vec![event],
+            steps: 1,
+            finished: self.next_record_idx >= self.changes.records.len(),
+        })
+    }
+
+    fn finished_reason(&self) -> String {
+        format!("finished: reached the end of the {} file", P::FILE_KIND)
+    }
+}
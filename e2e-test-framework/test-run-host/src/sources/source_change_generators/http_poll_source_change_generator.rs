@@ -0,0 +1,661 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client,
+};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::time::Instant;
+
+use test_data_store::{
+    scripts::{SourceChangeEvent, SourceChangeEventPayload, SourceChangeEventSourceInfo},
+    secret::Secret,
+    test_repo_storage::models::{
+        HttpPollSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition, SpacingMode,
+        TimeMode,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use super::{
+    dispatcher_kinds,
+    source_change_producer::{
+        now_ns, ProducedChanges, SourceChangeGeneratorCommonSettings, SourceChangeProducer,
+        SourceChangeProducerGenerator,
+    },
+    SourceChangeGeneratorDescription,
+};
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 1000;
+const DEFAULT_MAX_RESPONSE_BYTES: u64 = 64 * 1024 * 1024;
+const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HttpPollSourceChangeGeneratorSettings {
+    pub common: SourceChangeGeneratorCommonSettings,
+    pub headers: HashMap<String, Secret<String>>,
+    pub items_path: Option<String>,
+    pub key_field: String,
+    pub label: String,
+    pub max_response_bytes: u64,
+    pub poll_interval: Duration,
+    pub timeout: Duration,
+    pub url: String,
+}
+
+impl HttpPollSourceChangeGeneratorSettings {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: HttpPollSourceChangeGeneratorDefinition,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        if definition.key_field.is_empty() {
+            anyhow::bail!("HttpPollSourceChangeGenerator key_field must not be empty");
+        }
+
+        let poll_interval_ms = definition
+            .poll_interval_ms
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS);
+        if poll_interval_ms == 0 {
+            anyhow::bail!("HttpPollSourceChangeGenerator poll_interval_ms must be greater than 0");
+        }
+
+        Ok(HttpPollSourceChangeGeneratorSettings {
+            headers: definition.headers,
+            label: definition
+                .label
+                .unwrap_or_else(|| test_run_source_id.test_source_id.clone()),
+            common: SourceChangeGeneratorCommonSettings::new(
+                test_run_source_id,
+                &definition.common,
+                output_storage,
+                dispatchers,
+            ),
+            items_path: definition.items_path,
+            key_field: definition.key_field,
+            max_response_bytes: definition
+                .max_response_bytes
+                .unwrap_or(DEFAULT_MAX_RESPONSE_BYTES),
+            poll_interval: Duration::from_millis(poll_interval_ms),
+            timeout: Duration::from_secs(
+                definition
+                    .timeout_seconds
+                    .unwrap_or(DEFAULT_TIMEOUT_SECONDS),
+            ),
+            url: definition.url,
+        })
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        self.common.id.clone()
+    }
+}
+
+pub type HttpPollSourceChangeGenerator = SourceChangeProducerGenerator<HttpPollProducer>;
+
+impl HttpPollSourceChangeGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: HttpPollSourceChangeGeneratorDefinition,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        let settings = HttpPollSourceChangeGeneratorSettings::new(
+            test_run_source_id,
+            definition,
+            output_storage,
+            dispatchers,
+        )
+        .await?;
+
+        Ok(Self::spawn(settings))
+    }
+}
+
+// A change to a single item of the polled collection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PolledItemChange {
+    Insert {
+        key: String,
+        after: Value,
+    },
+    Update {
+        key: String,
+        before: Value,
+        after: Value,
+    },
+    Delete {
+        key: String,
+        before: Value,
+    },
+}
+
+// Diffs the items returned by a poll against the snapshot from the previous poll, using
+// key_field to match items. Returns the changes and the new snapshot. Inserts and updates
+// are returned in response order, followed by deletes in key order.
+pub fn diff_polled_items(
+    previous: &HashMap<String, Value>,
+    items: Vec<Value>,
+    key_field: &str,
+) -> anyhow::Result<(Vec<PolledItemChange>, HashMap<String, Value>)> {
+    let mut changes = Vec::new();
+    let mut snapshot = HashMap::with_capacity(items.len());
+
+    for item in items {
+        let key = match item.get(key_field) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            Some(other) => anyhow::bail!(
+                "Polled item key field {} must be a string or number, found {}",
+                key_field,
+                other
+            ),
+            None => anyhow::bail!("Polled item is missing key field {}: {}", key_field, item),
+        };
+
+        match previous.get(&key) {
+            None => changes.push(PolledItemChange::Insert {
+                key: key.clone(),
+                after: item.clone(),
+            }),
+            Some(before) if *before != item => changes.push(PolledItemChange::Update {
+                key: key.clone(),
+                before: before.clone(),
+                after: item.clone(),
+            }),
+            Some(_) => {}
+        }
+
+        if snapshot.insert(key.clone(), item).is_some() {
+            anyhow::bail!("Polled collection contains duplicate key {}", key);
+        }
+    }
+
+    let mut deleted: Vec<(&String, &Value)> = previous
+        .iter()
+        .filter(|(key, _)| !snapshot.contains_key(*key))
+        .collect();
+    deleted.sort_by(|a, b| a.0.cmp(b.0));
+    changes.extend(
+        deleted
+            .into_iter()
+            .map(|(key, before)| PolledItemChange::Delete {
+                key: key.clone(),
+                before: before.clone(),
+            }),
+    );
+
+    Ok((changes, snapshot))
+}
+
+// Extracts the collection from a poll response, following the dot separated items_path if provided.
+pub fn extract_polled_items(
+    response: Value,
+    items_path: Option<&str>,
+) -> anyhow::Result<Vec<Value>> {
+    let mut collection = response;
+
+    if let Some(path) = items_path {
+        for segment in path.split('.').filter(|s| !s.is_empty()) {
+            collection = match collection {
+                Value::Object(mut map) => match map.remove(segment) {
+                    Some(value) => value,
+                    None => anyhow::bail!(
+                        "Poll response has no field {} in items_path {}",
+                        segment,
+                        path
+                    ),
+                },
+                _ => anyhow::bail!(
+                    "Poll response items_path {} does not resolve to an object at {}",
+                    path,
+                    segment
+                ),
+            };
+        }
+    }
+
+    match collection {
+        Value::Array(items) => Ok(items),
+        other => anyhow::bail!(
+            "Poll response collection must be a JSON array, found {}",
+            other
+        ),
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct HttpPollSourceChangeGeneratorStats {
+    pub num_polls: u64,
+    pub num_skipped_polls: u64,
+    pub num_poll_errors: u64,
+    pub num_inserts: u64,
+    pub num_updates: u64,
+    pub num_deletes: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HttpPollProducerState {
+    pub item_count: usize,
+    pub key_field: String,
+    pub last_poll_error: Option<String>,
+    pub poll_interval_ms: u64,
+}
+
+// Polls a URL for a collection of items, producing the changes since the previous poll.
+pub struct HttpPollProducer {
+    client: Client,
+    event_seq_num: u64,
+    last_poll_error: Option<String>,
+    settings: HttpPollSourceChangeGeneratorSettings,
+    snapshot: HashMap<String, Value>,
+    start_time_ns: u64,
+    stats: HttpPollSourceChangeGeneratorStats,
+}
+
+impl HttpPollProducer {
+    // Reads the response body in chunks so an oversized response is rejected without
+    // buffering all of it.
+    async fn fetch_items(&self) -> anyhow::Result<Vec<Value>> {
+        let mut response = self
+            .client
+            .get(&self.settings.url)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        if let Some(len) = response.content_length() {
+            if len > self.settings.max_response_bytes {
+                anyhow::bail!(
+                    "Poll response of {} bytes exceeds max_response_bytes {}",
+                    len,
+                    self.settings.max_response_bytes
+                );
+            }
+        }
+
+        let mut body = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+        while let Some(chunk) = response.chunk().await? {
+            if (body.len() + chunk.len()) as u64 > self.settings.max_response_bytes {
+                anyhow::bail!(
+                    "Poll response exceeds max_response_bytes {}",
+                    self.settings.max_response_bytes
+                );
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let response: Value = serde_json::from_slice(&body)?;
+        extract_polled_items(response, self.settings.items_path.as_deref())
+    }
+
+    fn create_source_change_events(
+        &mut self,
+        changes: Vec<PolledItemChange>,
+    ) -> Vec<SourceChangeEvent> {
+        let now_ns = now_ns();
+        let ts_ns = match self.settings.common.time_mode {
+            TimeMode::Rebased(nanos) => nanos + now_ns.saturating_sub(self.start_time_ns),
+            TimeMode::Live | TimeMode::Recorded => now_ns,
+        };
+
+        changes
+            .into_iter()
+            .map(|change| {
+                self.event_seq_num += 1;
+
+                let (op, before, after) = match change {
+                    PolledItemChange::Insert { key, after } => {
+                        self.stats.num_inserts += 1;
+                        ("i", Value::Null, self.to_node(&key, after))
+                    }
+                    PolledItemChange::Update { key, before, after } => {
+                        self.stats.num_updates += 1;
+                        ("u", self.to_node(&key, before), self.to_node(&key, after))
+                    }
+                    PolledItemChange::Delete { key, before } => {
+                        self.stats.num_deletes += 1;
+                        ("d", self.to_node(&key, before), Value::Null)
+                    }
+                };
+
+                SourceChangeEvent {
+                    op: op.to_string(),
+                    reactivator_start_ns: now_ns,
+                    reactivator_end_ns: now_ns + 1,
+                    payload: SourceChangeEventPayload {
+                        source: SourceChangeEventSourceInfo {
+                            db: self.settings.common.id.test_source_id.to_string(),
+                            lsn: self.event_seq_num,
+                            table: "node".to_string(),
+                            ts_ns,
+                        },
+                        before,
+                        after,
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn to_node(&self, key: &str, item: Value) -> Value {
+        serde_json::json!({
+            "id": key,
+            "labels": [self.settings.label],
+            "properties": item
+        })
+    }
+}
+
+#[async_trait]
+impl SourceChangeProducer for HttpPollProducer {
+    type Settings = HttpPollSourceChangeGeneratorSettings;
+    type State = HttpPollProducerState;
+    type Stats = HttpPollSourceChangeGeneratorStats;
+
+    const NAME: &'static str = "HttpPollSourceChangeGenerator";
+    const STEP_UNIT: &'static str = "polls";
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings {
+        &settings.common
+    }
+
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription {
+        let common = &settings.common;
+
+        // Header values are often credentials, so only the names are reported.
+        let mut header_names: Vec<&String> = settings.headers.keys().collect();
+        header_names.sort();
+
+        // The generator polls until stopped, so the event count is unbounded.
+        SourceChangeGeneratorDescription::new(
+            "HttpPoll",
+            serde_json::json!({
+                "dispatchers": dispatcher_kinds(&common.dispatchers),
+                "event_field_names": common.event_field_names,
+                "event_schema": common.event_schema.is_some(),
+                "header_names": header_names,
+                "items_path": settings.items_path,
+                "key_field": settings.key_field,
+                "label": settings.label,
+                "max_eps": common.max_eps,
+                "max_response_bytes": settings.max_response_bytes,
+                "poll_interval_ms": settings.poll_interval.as_millis() as u64,
+                "time_mode": common.time_mode,
+                "timeout_seconds": settings.timeout.as_secs(),
+                "url": settings.url,
+            }),
+            serde_json::json!({
+                "max_events_per_second": common.max_eps,
+                "max_polls_per_second": 1000.0 / settings.poll_interval.as_millis() as f64,
+                "max_response_bytes": settings.max_response_bytes,
+            }),
+        )
+    }
+
+    async fn initialize(settings: Self::Settings) -> anyhow::Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in settings.headers.iter() {
+            let mut value = HeaderValue::from_str(value.expose_secret())?;
+            value.set_sensitive(true);
+            headers.insert(HeaderName::from_bytes(name.as_bytes())?, value);
+        }
+
+        let client = Client::builder()
+            .timeout(settings.timeout)
+            .default_headers(headers)
+            .build()?;
+
+        Ok(Self {
+            client,
+            event_seq_num: 0,
+            last_poll_error: None,
+            settings,
+            snapshot: HashMap::new(),
+            start_time_ns: 0,
+            stats: HttpPollSourceChangeGeneratorStats::default(),
+        })
+    }
+
+    fn settings(&self) -> &Self::Settings {
+        &self.settings
+    }
+
+    fn input(&self) -> (&'static str, String) {
+        ("url", self.settings.url.clone())
+    }
+
+    fn state(&self) -> Self::State {
+        HttpPollProducerState {
+            item_count: self.snapshot.len(),
+            key_field: self.settings.key_field.clone(),
+            last_poll_error: self.last_poll_error.clone(),
+            poll_interval_ms: self.settings.poll_interval.as_millis() as u64,
+        }
+    }
+
+    fn stats(&self) -> Self::Stats {
+        self.stats.clone()
+    }
+
+    fn start(&mut self, start_time_ns: u64) {
+        self.start_time_ns = start_time_ns;
+    }
+
+    fn reset(&mut self) {
+        // These fields do not get reset:
+        //   self.client
+        //   self.settings
+        self.event_seq_num = 0;
+        self.last_poll_error = None;
+        self.snapshot = HashMap::new();
+        self.start_time_ns = 0;
+        self.stats = HttpPollSourceChangeGeneratorStats::default();
+    }
+
+    // Polls are always poll_interval apart, so there is no spacing to apply.
+    fn next_change_at(
+        &mut self,
+        _spacing_mode: Option<SpacingMode>,
+        started_at: Option<Instant>,
+    ) -> Instant {
+        started_at.map_or_else(Instant::now, |at| at + self.settings.poll_interval)
+    }
+
+    // Polls the URL and, unless skipping, produces the changes since the previous poll.
+    // A failed poll is recorded but leaves the snapshot unchanged so the next poll picks
+    // up any changes that were missed.
+    async fn produce(
+        &mut self,
+        skipping: bool,
+        _limit: Option<u64>,
+    ) -> anyhow::Result<ProducedChanges> {
+        let poll = ProducedChanges {
+            steps: 1,
+            ..Default::default()
+        };
+        self.stats.num_polls += 1;
+
+        let items = match self.fetch_items().await {
+            Ok(items) => items,
+            Err(e) => {
+                log::warn!("Error polling {}: {:?}", self.settings.url, e);
+                self.stats.num_poll_errors += 1;
+                self.last_poll_error = Some(format!("{:?}", e));
+                return Ok(poll);
+            }
+        };
+
+        let (changes, snapshot) =
+            match diff_polled_items(&self.snapshot, items, &self.settings.key_field) {
+                Ok(result) => result,
+                Err(e) => {
+                    log::warn!(
+                        "Error diffing poll response from {}: {:?}",
+                        self.settings.url,
+                        e
+                    );
+                    self.stats.num_poll_errors += 1;
+                    self.last_poll_error = Some(format!("{:?}", e));
+                    return Ok(poll);
+                }
+            };
+        self.snapshot = snapshot;
+        self.last_poll_error = None;
+
+        if skipping {
+            self.stats.num_skipped_polls += 1;
+            return Ok(poll);
+        }
+
+        Ok(ProducedChanges {
+            events: self.create_source_change_events(changes),
+            ..poll
+        })
+    }
+}
+
+impl Debug for HttpPollProducer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpPollProducer")
+            .field("event_seq_num", &self.event_seq_num)
+            .field("item_count", &self.snapshot.len())
+            .field("last_poll_error", &self.last_poll_error)
+            .field("stats", &self.stats)
+            .field("url", &self.settings.url)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot(items: Vec<Value>) -> HashMap<String, Value> {
+        diff_polled_items(&HashMap::new(), items, "id").unwrap().1
+    }
+
+    #[test]
+    fn test_diff_polled_items() {
+        let previous = snapshot(vec![
+            json!({ "id": "a", "temp": 20 }),
+            json!({ "id": "b", "temp": 21 }),
+            json!({ "id": "c", "temp": 22 }),
+        ]);
+
+        let (changes, next) = diff_polled_items(
+            &previous,
+            vec![
+                json!({ "id": "d", "temp": 23 }),
+                json!({ "id": "b", "temp": 25 }),
+                json!({ "id": "c", "temp": 22 }),
+            ],
+            "id",
+        )
+        .unwrap();
+
+        assert_eq!(
+            changes,
+            vec![
+                PolledItemChange::Insert {
+                    key: "d".to_string(),
+                    after: json!({ "id": "d", "temp": 23 }),
+                },
+                PolledItemChange::Update {
+                    key: "b".to_string(),
+                    before: json!({ "id": "b", "temp": 21 }),
+                    after: json!({ "id": "b", "temp": 25 }),
+                },
+                PolledItemChange::Delete {
+                    key: "a".to_string(),
+                    before: json!({ "id": "a", "temp": 20 }),
+                },
+            ]
+        );
+        assert_eq!(next.len(), 3);
+    }
+
+    #[test]
+    fn test_diff_polled_items_invalid_keys() {
+        let previous = HashMap::new();
+
+        // Numeric keys are allowed.
+        let (changes, _) = diff_polled_items(&previous, vec![json!({ "id": 1 })], "id").unwrap();
+        assert_eq!(changes.len(), 1);
+
+        assert!(diff_polled_items(&previous, vec![json!({ "name": "x" })], "id").is_err());
+        assert!(diff_polled_items(
+            &previous,
+            vec![json!({ "id": "a" }), json!({ "id": "a" })],
+            "id"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_extract_polled_items() {
+        let items = extract_polled_items(
+            json!({ "data": { "items": [{ "id": "a" }] } }),
+            Some("data.items"),
+        )
+        .unwrap();
+        assert_eq!(items, vec![json!({ "id": "a" })]);
+
+        let items = extract_polled_items(json!([{ "id": "a" }]), None).unwrap();
+        assert_eq!(items.len(), 1);
+
+        assert!(extract_polled_items(json!({ "data": [] }), None).is_err());
+        assert!(extract_polled_items(json!({ "data": [] }), Some("items")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_settings_debug_redacts_header_values() {
+        let definition: HttpPollSourceChangeGeneratorDefinition = serde_json::from_value(json!({
+            "url": "http://localhost:8080/api/rooms",
+            "key_field": "room_id",
+            "headers": { "Authorization": "Bearer s3cr3t" }
+        }))
+        .unwrap();
+        let id = TestRunSourceId::try_from("test-repo.test-001.run-001.source-001").unwrap();
+        let output_storage = TestRunSourceStorage {
+            id: id.clone(),
+            path: "/tmp".into(),
+            source_change_path: "/tmp".into(),
+        };
+
+        let settings =
+            HttpPollSourceChangeGeneratorSettings::new(id, definition, output_storage, Vec::new())
+                .await
+                .unwrap();
+
+        let debug = format!("{:?}", settings);
+        assert!(debug.contains("Authorization"));
+        assert!(!debug.contains("s3cr3t"));
+        assert_eq!(
+            settings.headers["Authorization"].expose_secret(),
+            "Bearer s3cr3t"
+        );
+    }
+}
@@ -0,0 +1,489 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt, SeekFrom},
+    time::Instant,
+};
+
+use test_data_store::{
+    scripts::SourceChangeEvent,
+    test_repo_storage::{
+        models::{
+            LogTailSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition, SpacingMode,
+            TimeMode,
+        },
+        TestSourceStorage,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use crate::sources::source_change_dispatchers::event_field_names::EventFieldNames;
+
+use super::{
+    dispatcher_kinds,
+    source_change_producer::{
+        now_ns, ProducedChanges, SourceChangeGeneratorCommonSettings, SourceChangeProducer,
+        SourceChangeProducerGenerator,
+    },
+    SourceChangeGeneratorDescription,
+};
+
+const DEFAULT_POLL_INTERVAL_MS: u64 = 100;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct LogTailSourceChangeGeneratorSettings {
+    pub change_count: Option<u64>,
+    pub common: SourceChangeGeneratorCommonSettings,
+    pub log_file: String,
+    pub log_file_path: PathBuf,
+    pub poll_interval: Duration,
+}
+
+impl LogTailSourceChangeGeneratorSettings {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: LogTailSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        if definition.log_file.is_empty() {
+            anyhow::bail!("LogTailSourceChangeGenerator log_file must not be empty");
+        }
+
+        let poll_interval_ms = definition
+            .poll_interval_ms
+            .unwrap_or(DEFAULT_POLL_INTERVAL_MS);
+        if poll_interval_ms == 0 {
+            anyhow::bail!("LogTailSourceChangeGenerator poll_interval_ms must be greater than 0");
+        }
+
+        Ok(LogTailSourceChangeGeneratorSettings {
+            change_count: definition.change_count,
+            common: SourceChangeGeneratorCommonSettings {
+                dispatchers,
+                event_field_names: EventFieldNames::new(definition.event_field_names),
+                event_schema: definition.event_schema,
+                id: test_run_source_id,
+                max_eps: definition.max_eps,
+                output_storage,
+                time_mode: definition.time_mode,
+            },
+            log_file_path: input_storage.path.join(&definition.log_file),
+            log_file: definition.log_file,
+            poll_interval: Duration::from_millis(poll_interval_ms),
+        })
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        self.common.id.clone()
+    }
+}
+
+pub type LogTailSourceChangeGenerator = SourceChangeProducerGenerator<LogTailProducer>;
+
+impl LogTailSourceChangeGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: LogTailSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        let settings = LogTailSourceChangeGeneratorSettings::new(
+            test_run_source_id,
+            definition,
+            input_storage,
+            output_storage,
+            dispatchers,
+        )
+        .await?;
+
+        Ok(Self::spawn(settings))
+    }
+}
+
+// Where the generator is up to in the tailed file. The file_id identifies the file that was
+// read so that a rotated file is detected even if it has grown past the offset.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct LogTailPosition {
+    pub file_id: Option<u64>,
+    pub offset: u64,
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+// Reads up to max_lines complete lines appended to the file since the position, advancing the
+// position past them. A trailing line without a newline is left for a later read, and blank
+// lines are skipped. If the file has been truncated or replaced, reading restarts from the
+// top of the file and the returned flag is true. A missing file has no lines.
+pub async fn read_appended_lines(
+    path: &Path,
+    position: &mut LogTailPosition,
+    max_lines: Option<u64>,
+) -> anyhow::Result<(Vec<String>, bool)> {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), false)),
+        Err(e) => return Err(e.into()),
+    };
+
+    let id = file_id(&metadata);
+    let replaced = position.file_id.is_some() && id != position.file_id;
+    let restarted = replaced || metadata.len() < position.offset;
+    if restarted {
+        position.offset = 0;
+    }
+    position.file_id = id;
+
+    let mut lines = Vec::new();
+    if metadata.len() == position.offset || max_lines == Some(0) {
+        return Ok((lines, restarted));
+    }
+
+    let mut file = File::open(path).await?;
+    file.seek(SeekFrom::Start(position.offset)).await?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).await?;
+
+    let mut consumed = 0;
+    while let Some(len) = buffer[consumed..].iter().position(|b| *b == b'\n') {
+        let line = String::from_utf8_lossy(&buffer[consumed..consumed + len]);
+        consumed += len + 1;
+
+        let line = line.trim();
+        if !line.is_empty() {
+            lines.push(line.to_string());
+            if max_lines.is_some_and(|max| lines.len() as u64 >= max) {
+                break;
+            }
+        }
+    }
+    position.offset += consumed as u64;
+
+    Ok((lines, restarted))
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct LogTailSourceChangeGeneratorStats {
+    pub num_invalid_lines: u64,
+    pub num_restarts: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogTailProducerState {
+    pub change_count: Option<u64>,
+    pub last_invalid_line_error: Option<String>,
+    pub position: LogTailPosition,
+}
+
+// Tails the log file, producing a change for each line appended to it.
+#[derive(Debug)]
+pub struct LogTailProducer {
+    // The recorded time of the first change read, used to rebase the changes.
+    first_change_time_ns: Option<u64>,
+    last_invalid_line_error: Option<String>,
+    // The number of changes read, whether dispatched or skipped, counted toward change_count.
+    num_changes: u64,
+    position: LogTailPosition,
+    settings: LogTailSourceChangeGeneratorSettings,
+    stats: LogTailSourceChangeGeneratorStats,
+}
+
+impl LogTailProducer {
+    fn time_shift(&mut self, mut event: SourceChangeEvent) -> SourceChangeEvent {
+        let time_ns = match self.settings.common.time_mode {
+            // Live - Use the actual current time.
+            TimeMode::Live => now_ns(),
+            // Recorded - Use the time recorded in the line.
+            TimeMode::Recorded => return event,
+            // Rebased - Shift the recorded times so the first change is at the rebased time.
+            TimeMode::Rebased(nanos) => {
+                let recorded_ns = event.payload.source.ts_ns;
+                let first_ns = *self.first_change_time_ns.get_or_insert(recorded_ns);
+                nanos + recorded_ns.saturating_sub(first_ns)
+            }
+        };
+
+        event.reactivator_start_ns = time_ns;
+        event.reactivator_end_ns = time_ns + 1;
+        event.payload.source.ts_ns = time_ns;
+        event
+    }
+}
+
+#[async_trait]
+impl SourceChangeProducer for LogTailProducer {
+    type Settings = LogTailSourceChangeGeneratorSettings;
+    type State = LogTailProducerState;
+    type Stats = LogTailSourceChangeGeneratorStats;
+
+    const NAME: &'static str = "LogTailSourceChangeGenerator";
+    const STEP_UNIT: &'static str = "changes";
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings {
+        &settings.common
+    }
+
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription {
+        let common = &settings.common;
+
+        // Unless a change_count is set, the generator tails the file until stopped.
+        SourceChangeGeneratorDescription::new(
+            "LogTail",
+            serde_json::json!({
+                "change_count": settings.change_count,
+                "dispatchers": dispatcher_kinds(&common.dispatchers),
+                "event_field_names": common.event_field_names,
+                "event_schema": common.event_schema.is_some(),
+                "log_file": settings.log_file,
+                "max_eps": common.max_eps,
+                "poll_interval_ms": settings.poll_interval.as_millis() as u64,
+                "time_mode": common.time_mode,
+            }),
+            serde_json::json!({
+                "change_count": settings.change_count,
+                "max_events_per_second": common.max_eps,
+            }),
+        )
+    }
+
+    async fn initialize(settings: Self::Settings) -> anyhow::Result<Self> {
+        Ok(Self {
+            first_change_time_ns: None,
+            last_invalid_line_error: None,
+            num_changes: 0,
+            position: LogTailPosition::default(),
+            settings,
+            stats: LogTailSourceChangeGeneratorStats::default(),
+        })
+    }
+
+    fn settings(&self) -> &Self::Settings {
+        &self.settings
+    }
+
+    fn input(&self) -> (&'static str, String) {
+        ("log_file", self.settings.log_file.clone())
+    }
+
+    fn state(&self) -> Self::State {
+        LogTailProducerState {
+            change_count: self.settings.change_count,
+            last_invalid_line_error: self.last_invalid_line_error.clone(),
+            position: self.position.clone(),
+        }
+    }
+
+    fn stats(&self) -> Self::Stats {
+        self.stats.clone()
+    }
+
+    fn reset(&mut self) {
+        // These fields do not get reset:
+        //   self.settings
+        self.first_change_time_ns = None;
+        self.last_invalid_line_error = None;
+        self.num_changes = 0;
+        self.position = LogTailPosition::default();
+        self.stats = LogTailSourceChangeGeneratorStats::default();
+    }
+
+    // The file is checked each poll_interval, so there is no spacing to apply.
+    fn next_change_at(
+        &mut self,
+        _spacing_mode: Option<SpacingMode>,
+        started_at: Option<Instant>,
+    ) -> Instant {
+        started_at.map_or_else(Instant::now, |at| at + self.settings.poll_interval)
+    }
+
+    // Reads the lines appended to the file since the last read, up to the end of the current
+    // Step or Skip, or the change_count. Lines that aren't SourceChangeEvents are counted
+    // and skipped.
+    async fn produce(
+        &mut self,
+        skipping: bool,
+        limit: Option<u64>,
+    ) -> anyhow::Result<ProducedChanges> {
+        let count_remaining = self
+            .settings
+            .change_count
+            .map(|count| count.saturating_sub(self.num_changes));
+        let max_lines = match (limit, count_remaining) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        let (lines, restarted) =
+            read_appended_lines(&self.settings.log_file_path, &mut self.position, max_lines)
+                .await
+                .context("Error reading log file")?;
+
+        if restarted {
+            log::info!(
+                "Log file {:?} was truncated or replaced, reading from the start for TestRunSource {}",
+                self.settings.log_file_path,
+                self.settings.common.id
+            );
+            self.stats.num_restarts += 1;
+        }
+
+        let mut events = Vec::with_capacity(lines.len());
+        for line in lines {
+            match SourceChangeEvent::try_from(line.as_str()) {
+                Ok(event) => events.push(event),
+                Err(e) => {
+                    log::warn!("Skipping invalid line in log file: {:?}", e);
+                    self.stats.num_invalid_lines += 1;
+                    self.last_invalid_line_error = Some(format!("{}: {}", e, line));
+                }
+            }
+        }
+
+        let steps = events.len() as u64;
+        self.num_changes += steps;
+
+        let events = if skipping {
+            events
+        } else {
+            events.into_iter().map(|e| self.time_shift(e)).collect()
+        };
+
+        Ok(ProducedChanges {
+            events,
+            steps,
+            finished: self
+                .settings
+                .change_count
+                .is_some_and(|count| self.num_changes >= count),
+        })
+    }
+
+    fn finished_reason(&self) -> String {
+        "finished: reached the change_count".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn append(path: &Path, content: &str) {
+        use tokio::io::AsyncWriteExt;
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .unwrap();
+        file.write_all(content.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_appended_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("changes.jsonl");
+        let mut position = LogTailPosition::default();
+
+        // A missing file has no lines.
+        let (lines, restarted) = read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+        assert!(lines.is_empty() && !restarted);
+
+        // The partial line is left until its newline is written.
+        append(&path, "a\n\nb\nc").await;
+        let (lines, _) = read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+        assert_eq!(position.offset, 5);
+
+        append(&path, "\nd\n").await;
+        let (lines, _) = read_appended_lines(&path, &mut position, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["c"]);
+        let (lines, restarted) = read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["d"]);
+        assert!(!restarted);
+    }
+
+    #[tokio::test]
+    async fn test_read_appended_lines_after_truncation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("changes.jsonl");
+        let mut position = LogTailPosition::default();
+
+        append(&path, "a\nb\n").await;
+        read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+
+        tokio::fs::write(&path, "c\n").await.unwrap();
+        let (lines, restarted) = read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["c"]);
+        assert!(restarted);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_read_appended_lines_after_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("changes.jsonl");
+        let mut position = LogTailPosition::default();
+
+        append(&path, "a\n").await;
+        read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+
+        // The replacement file is longer than the offset, so only its identity shows the
+        // file was rotated.
+        tokio::fs::rename(&path, dir.path().join("changes.jsonl.1"))
+            .await
+            .unwrap();
+        append(&path, "b\nc\n").await;
+        let (lines, restarted) = read_appended_lines(&path, &mut position, None)
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["b", "c"]);
+        assert!(restarted);
+    }
+}
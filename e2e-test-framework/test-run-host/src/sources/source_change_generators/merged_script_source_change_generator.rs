@@ -0,0 +1,172 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use test_data_store::{
+    test_repo_storage::{
+        models::{
+            MergedScriptSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition,
+            SpacingMode,
+        },
+        TestSourceStorage,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use crate::sources::source_change_dispatchers::event_field_names::EventFieldNames;
+
+use super::{
+    dispatcher_kinds, max_events_per_second,
+    script_source_change_generator::{
+        ScriptSourceChangeGenerator, ScriptSourceChangeGeneratorSettings,
+    },
+    validate_spacing_rate, validate_speed_factor, SourceChangeEventSummary, SourceChangeGenerator,
+    SourceChangeGeneratorCommandResponse, SourceChangeGeneratorDescription,
+};
+
+// Plays several change scripts as a single script. The scripts are merged by recorded time as
+// they are read, then played by a ScriptSourceChangeGenerator, so the merged script supports
+// the same commands, spacing and time modes as a single script.
+#[derive(Clone, Debug, Serialize)]
+pub struct MergedScriptSourceChangeGenerator {
+    script_file_folders: Vec<String>,
+    script_generator: ScriptSourceChangeGenerator,
+}
+
+impl MergedScriptSourceChangeGenerator {
+    pub async fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: MergedScriptSourceChangeGeneratorDefinition,
+        input_storage: TestSourceStorage,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> anyhow::Result<Self> {
+        validate_spacing_rate(
+            &definition.common.spacing_mode,
+            definition.common.max_spacing_rate,
+        )?;
+        validate_speed_factor(definition.common.speed_factor)?;
+
+        if definition.script_file_folders.is_empty() {
+            anyhow::bail!("script_file_folders must list at least one folder");
+        }
+
+        if definition.auto_pause_after == Some(0) {
+            anyhow::bail!("auto_pause_after must be greater than 0");
+        }
+
+        let settings = ScriptSourceChangeGeneratorSettings {
+            auto_pause_after: definition.auto_pause_after,
+            auto_pause_repeat: definition.auto_pause_repeat,
+            dispatchers,
+            event_field_names: EventFieldNames::new(definition.common.event_field_names),
+            event_schema: definition.common.event_schema,
+            id: test_run_source_id,
+            ignore_scripted_pause_commands: definition.ignore_scripted_pause_commands,
+            input_storage,
+            max_eps: definition.common.max_eps,
+            output_storage,
+            spacing_mode: definition.common.spacing_mode,
+            speed_factor: definition.common.speed_factor,
+            start_offset: definition.start_offset,
+            time_mode: definition.common.time_mode,
+        };
+
+        Ok(Self {
+            script_file_folders: definition.script_file_folders,
+            script_generator: ScriptSourceChangeGenerator::from_settings(settings),
+        })
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        self.script_generator.get_id()
+    }
+}
+
+#[async_trait]
+impl SourceChangeGenerator for MergedScriptSourceChangeGenerator {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        let settings = self.script_generator.get_settings();
+        let max_eps = max_events_per_second(&settings.spacing_mode, settings.max_eps);
+
+        SourceChangeGeneratorDescription::new(
+            "MergedScript",
+            serde_json::json!({
+                "dispatchers": dispatcher_kinds(&settings.dispatchers),
+                "event_field_names": settings.event_field_names,
+                "event_schema": settings.event_schema.is_some(),
+                "ignore_scripted_pause_commands": settings.ignore_scripted_pause_commands,
+                "max_eps": settings.max_eps,
+                "script_file_folders": self.script_file_folders,
+                "spacing_mode": settings.spacing_mode,
+                "speed_factor": settings.speed_factor,
+                "time_mode": settings.time_mode,
+            }),
+            serde_json::json!({
+                "max_events_per_second": max_eps,
+            }),
+        )
+    }
+
+    async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.get_state().await
+    }
+
+    async fn pause(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.pause().await
+    }
+
+    async fn reset(
+        &self,
+        seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.reset(seed).await
+    }
+
+    async fn skip(
+        &self,
+        skips: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.skip(skips, spacing_mode).await
+    }
+
+    async fn start(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.start().await
+    }
+
+    async fn step(
+        &self,
+        steps: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.step(steps, spacing_mode).await
+    }
+
+    async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.script_generator.stop().await
+    }
+
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        self.script_generator.subscribe_events()
+    }
+
+    fn set_test_run_host_on_dispatchers(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
+        self.script_generator
+            .set_test_run_host_on_dispatchers(test_run_host)
+    }
+}
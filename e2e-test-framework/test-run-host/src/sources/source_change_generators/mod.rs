@@ -12,24 +12,192 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::{num::NonZeroU32, str::FromStr, time::Duration};
+
 use async_trait::async_trait;
-use serde::Serialize;
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use serde::{Deserialize, Deserializer, Serialize};
 use test_data_store::{
+    scripts::SourceChangeEvent,
     test_repo_storage::{
         models::{SourceChangeDispatcherDefinition, SourceChangeGeneratorDefinition, SpacingMode},
         TestSourceStorage,
     },
     test_run_storage::{TestRunSourceId, TestRunSourceStorage},
 };
-use tokio::sync::oneshot;
+use tokio::sync::{broadcast, oneshot};
 
+use super::source_change_dispatchers::{
+    create_source_change_dispatcher, event_field_names::EventFieldNames, SourceChangeDispatcher,
+};
+use csv_file_source_change_generator::CsvFileSourceChangeGenerator;
+use debezium_file_source_change_generator::DebeziumFileSourceChangeGenerator;
+use http_poll_source_change_generator::HttpPollSourceChangeGenerator;
+use log_tail_source_change_generator::LogTailSourceChangeGenerator;
+use merged_script_source_change_generator::MergedScriptSourceChangeGenerator;
 use script_source_change_generator::ScriptSourceChangeGenerator;
 
+pub mod csv_file_source_change_generator;
+pub mod debezium_file_source_change_generator;
+pub mod event_schema_validator;
+pub mod file_replay_source_change_generator;
+pub mod http_poll_source_change_generator;
+pub mod log_tail_source_change_generator;
+pub mod merged_script_source_change_generator;
 pub mod script_source_change_generator;
+pub mod source_change_producer;
+
+// A hard ceiling on the number of SourceChangeEvents per second a generator dispatches,
+// applied after any spacing. This is a safety valve to protect shared infrastructure
+// from a misconfigured run, so if the spacing already keeps the rate below the cap it
+// has no effect.
+pub struct MaxEpsLimiter {
+    limiter: Option<DefaultDirectRateLimiter>,
+}
+
+impl MaxEpsLimiter {
+    pub fn new(max_eps: Option<NonZeroU32>) -> Self {
+        Self {
+            limiter: max_eps.map(|max_eps| RateLimiter::direct(Quota::per_second(max_eps))),
+        }
+    }
+
+    // Waits until event_count more events can be dispatched without exceeding the cap.
+    pub async fn until_ready(&self, event_count: usize) {
+        if let Some(limiter) = &self.limiter {
+            for _ in 0..event_count {
+                limiter.until_ready().await;
+            }
+        }
+    }
+}
+
+// Tracks how many events remain in the current burst when spacing with SpacingMode::Burst.
+// The first burst after creation or reset starts without waiting.
+#[derive(Debug, Default)]
+pub struct BurstTracker {
+    remaining: Option<u32>,
+}
+
+impl BurstTracker {
+    // Returns how long to wait before the next event: nothing within a burst, and
+    // burst_interval_ns before the first event of each burst after the first.
+    pub fn next_delay(&mut self, burst_size: NonZeroU32, burst_interval_ns: u64) -> Duration {
+        let (delay, remaining) = match self.remaining {
+            Some(0) => (Duration::from_nanos(burst_interval_ns), burst_size.get()),
+            Some(remaining) => (Duration::ZERO, remaining),
+            None => (Duration::ZERO, burst_size.get()),
+        };
+        self.remaining = Some(remaining - 1);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.remaining = None;
+    }
+}
+
+// Default upper bound on SpacingMode::Rate. Beyond this the rate limiter can't keep up and
+// the spacing silently becomes best-effort.
+pub const DEFAULT_MAX_SPACING_RATE: u32 = 100_000;
+
+// Runs estimated to take longer than this are logged as a warning when the generator is created.
+pub const LONG_RUN_WARNING_THRESHOLD: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Checks that a SpacingMode::Rate is no higher than max_spacing_rate (or the default max).
+pub fn validate_spacing_rate(
+    spacing_mode: &SpacingMode,
+    max_spacing_rate: Option<NonZeroU32>,
+) -> anyhow::Result<()> {
+    if let SpacingMode::Rate(rate) = spacing_mode {
+        let max_rate = max_spacing_rate.map_or(DEFAULT_MAX_SPACING_RATE, |r| r.get());
+        if rate.get() > max_rate {
+            anyhow::bail!(
+                "SpacingMode rate of {} events/sec exceeds the maximum of {} events/sec. Set max_spacing_rate to allow a higher rate.",
+                rate,
+                max_rate
+            );
+        }
+    }
+    Ok(())
+}
+
+// Checks that a speed_factor can be used to scale the time between changes.
+pub fn validate_speed_factor(speed_factor: f64) -> anyhow::Result<()> {
+    if !speed_factor.is_finite() || speed_factor <= 0.0 {
+        anyhow::bail!(
+            "speed_factor must be a number greater than 0, got {}",
+            speed_factor
+        );
+    }
+    Ok(())
+}
+
+// Scales the time between changes by the speed_factor. A factor above 1.0 shortens the time
+// and a factor below 1.0 lengthens it.
+pub fn scale_delay_ns(delay_ns: u64, speed_factor: f64) -> u64 {
+    (delay_ns as f64 / speed_factor).round() as u64
+}
+
+// Estimates how long it will take to generate change_count changes, given the spacing mode,
+// the mean interval between changes used by SpacingMode::Recorded, and any max_eps cap.
+// Returns None if the changes are not spaced at all.
+pub fn estimate_run_duration(
+    spacing_mode: &SpacingMode,
+    change_count: u64,
+    recorded_interval_ns: u64,
+    max_eps: Option<NonZeroU32>,
+) -> Option<Duration> {
+    let spaced = match spacing_mode {
+        SpacingMode::None => None,
+        SpacingMode::Rate(rate) => Some(Duration::from_secs_f64(
+            change_count as f64 / rate.get() as f64,
+        )),
+        SpacingMode::Recorded => Some(Duration::from_nanos(
+            change_count.saturating_mul(recorded_interval_ns),
+        )),
+        // Only the waits between bursts take time.
+        SpacingMode::Burst {
+            burst_size,
+            burst_interval_ns,
+        } => Some(Duration::from_nanos(
+            change_count
+                .div_ceil(burst_size.get() as u64)
+                .saturating_sub(1)
+                .saturating_mul(*burst_interval_ns),
+        )),
+    };
+    let capped =
+        max_eps.map(|max_eps| Duration::from_secs_f64(change_count as f64 / max_eps.get() as f64));
+
+    match (spaced, capped) {
+        (Some(spaced), Some(capped)) => Some(spaced.max(capped)),
+        (spaced, capped) => spaced.or(capped),
+    }
+}
+
+// Logs the estimated run duration, warning if it exceeds LONG_RUN_WARNING_THRESHOLD.
+pub fn log_estimated_run_duration(id: &TestRunSourceId, estimate: Option<Duration>) {
+    match estimate {
+        Some(estimate) if estimate > LONG_RUN_WARNING_THRESHOLD => log::warn!(
+            "TestRunSource {} is estimated to take {:.1} hours to generate all changes.",
+            id,
+            estimate.as_secs_f64() / 3600.0
+        ),
+        Some(estimate) => log::info!(
+            "TestRunSource {} is estimated to take {:.1} seconds to generate all changes.",
+            id,
+            estimate.as_secs_f64()
+        ),
+        None => {}
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum SourceChangeGeneratorError {
     // NotConfigured
+    #[error("Event subscriptions are not supported by {0}")]
+    EventSubscriptionsNotSupported(String),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -81,6 +249,33 @@ impl Serialize for SourceChangeGeneratorStatus {
     }
 }
 
+impl FromStr for SourceChangeGeneratorStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "running" => Ok(Self::Running),
+            "stepping" => Ok(Self::Stepping),
+            "skipping" => Ok(Self::Skipping),
+            "paused" => Ok(Self::Paused),
+            "stopped" => Ok(Self::Stopped),
+            "finished" => Ok(Self::Finished),
+            "error" => Ok(Self::Error),
+            _ => anyhow::bail!("Invalid SourceChangeGeneratorStatus value:{}", s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SourceChangeGeneratorStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 #[derive(Debug)]
 pub enum SourceChangeGeneratorAction {
     GetState,
@@ -111,15 +306,188 @@ pub struct SourceChangeGeneratorCommandResponse {
 
 #[derive(Debug, Serialize)]
 pub struct SourceChangeGeneratorState {
+    // Number of SourceChangeEvents dispatched since the generator was created or last reset.
+    pub event_count: u64,
     pub state: serde_json::Value,
     pub status: SourceChangeGeneratorStatus,
 }
 
+// A static description of what a generator will do, derived from its configuration. Unlike
+// SourceChangeGeneratorState, this is available before the generator is started.
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceChangeGeneratorDescription {
+    pub kind: String,
+    pub supported: bool,
+    // The effective configuration, with defaults applied.
+    pub configuration: serde_json::Value,
+    // Bounds derived from the configuration, e.g. the expected number of events.
+    pub bounds: serde_json::Value,
+}
+
+impl SourceChangeGeneratorDescription {
+    pub fn new(kind: &str, configuration: serde_json::Value, bounds: serde_json::Value) -> Self {
+        Self {
+            kind: kind.to_string(),
+            supported: true,
+            configuration,
+            bounds,
+        }
+    }
+
+    pub fn unsupported() -> Self {
+        Self {
+            kind: "Unknown".to_string(),
+            supported: false,
+            configuration: serde_json::Value::Null,
+            bounds: serde_json::Value::Null,
+        }
+    }
+}
+
+// Number of event summaries a feed subscriber can fall behind before it misses events.
+pub const EVENT_FEED_CAPACITY: usize = 1024;
+
+// Compact summary of a dispatched SourceChangeEvent, published to event feed subscribers.
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceChangeEventSummary {
+    pub seq: u64,
+    pub op: String,
+    pub ts_ns: u64,
+}
+
+impl From<&SourceChangeEvent> for SourceChangeEventSummary {
+    fn from(event: &SourceChangeEvent) -> Self {
+        Self {
+            seq: event.payload.source.lsn,
+            op: event.op.clone(),
+            ts_ns: event.payload.source.ts_ns,
+        }
+    }
+}
+
+// Fans out summaries of the events a generator dispatches, e.g. to a live dashboard. Publishing
+// never waits for subscribers; one that falls more than EVENT_FEED_CAPACITY events behind gets
+// a Lagged error on its next receive instead of holding up the generator.
+#[derive(Clone, Debug)]
+pub struct SourceChangeEventFeed {
+    tx: broadcast::Sender<SourceChangeEventSummary>,
+}
+
+impl SourceChangeEventFeed {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self { tx }
+    }
+
+    pub fn publish(&self, events: &[&SourceChangeEvent]) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+
+        for event in events {
+            // Only fails if every subscriber has gone away since the check above.
+            let _ = self.tx.send(SourceChangeEventSummary::from(*event));
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SourceChangeEventSummary> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for SourceChangeEventFeed {
+    fn default() -> Self {
+        Self::new(EVENT_FEED_CAPACITY)
+    }
+}
+
+// The feed is dispatched to like any other dispatcher, after the events have been validated
+// and spaced, so every generator publishes exactly the events it dispatches.
+#[async_trait]
+impl SourceChangeDispatcher for SourceChangeEventFeed {
+    async fn close(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        self.publish(&events);
+        Ok(())
+    }
+}
+
+// Creates a generator's configured dispatchers, followed by one that publishes to its event feed.
+pub async fn create_source_change_dispatchers(
+    defs: &[SourceChangeDispatcherDefinition],
+    output_storage: &TestRunSourceStorage,
+    event_field_names: &EventFieldNames,
+    event_feed: &SourceChangeEventFeed,
+) -> anyhow::Result<Vec<Box<dyn SourceChangeDispatcher + Send>>> {
+    let mut dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>> = Vec::new();
+    for def in defs.iter() {
+        match create_source_change_dispatcher(def, output_storage, event_field_names).await {
+            Ok(dispatcher) => dispatchers.push(dispatcher),
+            Err(e) => {
+                anyhow::bail!(
+                    "Error creating SourceChangeDispatcher: {:?}; Error: {:?}",
+                    def,
+                    e
+                );
+            }
+        }
+    }
+    dispatchers.push(Box::new(event_feed.clone()));
+    Ok(dispatchers)
+}
+
+// The kinds of the configured dispatchers. Dispatcher definitions can contain connection
+// details, so descriptions only report the kinds.
+pub fn dispatcher_kinds(dispatchers: &[SourceChangeDispatcherDefinition]) -> Vec<String> {
+    dispatchers
+        .iter()
+        .map(|def| match serde_json::to_value(def) {
+            Ok(serde_json::Value::Object(map)) => map
+                .get("kind")
+                .and_then(|kind| kind.as_str())
+                .unwrap_or("Unknown")
+                .to_string(),
+            _ => "Unknown".to_string(),
+        })
+        .collect()
+}
+
+// The highest event rate the generator can reach, given its SpacingMode and any max_eps cap.
+// Returns None if the rate is unbounded.
+pub fn max_events_per_second(
+    spacing_mode: &SpacingMode,
+    max_eps: Option<NonZeroU32>,
+) -> Option<u32> {
+    let spacing_rate = match spacing_mode {
+        SpacingMode::Rate(rate) => Some(rate.get()),
+        _ => None,
+    };
+
+    match (spacing_rate, max_eps.map(|r| r.get())) {
+        (Some(rate), Some(max_eps)) => Some(rate.min(max_eps)),
+        (rate, max_eps) => rate.or(max_eps),
+    }
+}
+
 #[async_trait]
 pub trait SourceChangeGenerator: Send + Sync + std::fmt::Debug {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        SourceChangeGeneratorDescription::unsupported()
+    }
     async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
     async fn pause(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
-    async fn reset(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    // Resets the generator to its initial state. Generators that produce random changes are
+    // re-seeded with the seed if given; otherwise they reuse their configured seed.
+    async fn reset(
+        &self,
+        seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
     async fn skip(
         &self,
         skips: u64,
@@ -131,7 +499,29 @@ pub trait SourceChangeGenerator: Send + Sync + std::fmt::Debug {
         steps: u64,
         spacing_mode: Option<SpacingMode>,
     ) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    // Steps the generator until its event sequence number reaches target_seq, then pauses.
+    // Only generators that track an event sequence number support this.
+    async fn step_to(
+        &self,
+        _target_seq: u64,
+        _spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        anyhow::bail!("StepTo is not supported by this SourceChangeGenerator")
+    }
+    // Skips all of the generator's remaining changes without dispatching them, leaving it
+    // Finished. Only generators with a known number of changes support this.
+    async fn skip_to_end(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        anyhow::bail!("SkipToEnd is not supported by this SourceChangeGenerator")
+    }
     async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse>;
+    // Subscribes to summaries of the events the generator dispatches from now on. Only
+    // generators that publish an event feed support this.
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        Err(SourceChangeGeneratorError::EventSubscriptionsNotSupported(
+            "this SourceChangeGenerator".to_string(),
+        )
+        .into())
+    }
 
     /// Sets the TestRunHost for dispatchers that need it (optional)
     fn set_test_run_host_on_dispatchers(&self, _test_run_host: std::sync::Arc<crate::TestRunHost>) {
@@ -141,6 +531,10 @@ pub trait SourceChangeGenerator: Send + Sync + std::fmt::Debug {
 
 #[async_trait]
 impl SourceChangeGenerator for Box<dyn SourceChangeGenerator + Send + Sync> {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        (**self).describe()
+    }
+
     async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         (**self).get_state().await
     }
@@ -149,8 +543,11 @@ impl SourceChangeGenerator for Box<dyn SourceChangeGenerator + Send + Sync> {
         (**self).pause().await
     }
 
-    async fn reset(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
-        (**self).reset().await
+    async fn reset(
+        &self,
+        seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).reset(seed).await
     }
 
     async fn skip(
@@ -173,10 +570,26 @@ impl SourceChangeGenerator for Box<dyn SourceChangeGenerator + Send + Sync> {
         (**self).step(steps, spacing_mode).await
     }
 
+    async fn step_to(
+        &self,
+        target_seq: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).step_to(target_seq, spacing_mode).await
+    }
+
+    async fn skip_to_end(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        (**self).skip_to_end().await
+    }
+
     async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         (**self).stop().await
     }
 
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        (**self).subscribe_events()
+    }
+
     fn set_test_run_host_on_dispatchers(&self, test_run_host: std::sync::Arc<crate::TestRunHost>) {
         (**self).set_test_run_host_on_dispatchers(test_run_host)
     }
@@ -191,6 +604,54 @@ pub async fn create_source_change_generator(
 ) -> anyhow::Result<Option<Box<dyn SourceChangeGenerator + Send + Sync>>> {
     match definition {
         None => Ok(None),
+        Some(SourceChangeGeneratorDefinition::CsvFile(definition)) => Ok(Some(Box::new(
+            CsvFileSourceChangeGenerator::new(
+                id,
+                definition,
+                input_storage,
+                output_storage,
+                dispatchers,
+            )
+            .await?,
+        )
+            as Box<dyn SourceChangeGenerator + Send + Sync>)),
+        Some(SourceChangeGeneratorDefinition::DebeziumFile(definition)) => Ok(Some(Box::new(
+            DebeziumFileSourceChangeGenerator::new(
+                id,
+                definition,
+                input_storage,
+                output_storage,
+                dispatchers,
+            )
+            .await?,
+        )
+            as Box<dyn SourceChangeGenerator + Send + Sync>)),
+        Some(SourceChangeGeneratorDefinition::HttpPoll(definition)) => Ok(Some(Box::new(
+            HttpPollSourceChangeGenerator::new(id, definition, output_storage, dispatchers).await?,
+        )
+            as Box<dyn SourceChangeGenerator + Send + Sync>)),
+        Some(SourceChangeGeneratorDefinition::LogTail(definition)) => Ok(Some(Box::new(
+            LogTailSourceChangeGenerator::new(
+                id,
+                definition,
+                input_storage,
+                output_storage,
+                dispatchers,
+            )
+            .await?,
+        )
+            as Box<dyn SourceChangeGenerator + Send + Sync>)),
+        Some(SourceChangeGeneratorDefinition::MergedScript(definition)) => Ok(Some(Box::new(
+            MergedScriptSourceChangeGenerator::new(
+                id,
+                definition,
+                input_storage,
+                output_storage,
+                dispatchers,
+            )
+            .await?,
+        )
+            as Box<dyn SourceChangeGenerator + Send + Sync>)),
         Some(SourceChangeGeneratorDefinition::Script(definition)) => Ok(Some(Box::new(
             ScriptSourceChangeGenerator::new(
                 id,
@@ -204,3 +665,132 @@ pub async fn create_source_change_generator(
             as Box<dyn SourceChangeGenerator + Send + Sync>)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_spacing_rate() {
+        let rate = |r| SpacingMode::Rate(NonZeroU32::new(r).unwrap());
+
+        assert!(validate_spacing_rate(&rate(DEFAULT_MAX_SPACING_RATE), None).is_ok());
+        assert!(validate_spacing_rate(&rate(DEFAULT_MAX_SPACING_RATE + 1), None).is_err());
+        assert!(validate_spacing_rate(&rate(500), NonZeroU32::new(100)).is_err());
+        assert!(validate_spacing_rate(&rate(500_000), NonZeroU32::new(1_000_000)).is_ok());
+        assert!(validate_spacing_rate(&SpacingMode::None, NonZeroU32::new(1)).is_ok());
+    }
+
+    #[test]
+    fn test_burst_tracker() {
+        let burst_size = NonZeroU32::new(3).unwrap();
+        let mut tracker = BurstTracker::default();
+
+        let delays: Vec<u64> = (0..7)
+            .map(|_| tracker.next_delay(burst_size, 100).as_nanos() as u64)
+            .collect();
+        assert_eq!(delays, vec![0, 0, 0, 100, 0, 0, 100]);
+
+        // After a reset, the next burst starts without waiting.
+        tracker.reset();
+        assert_eq!(tracker.next_delay(burst_size, 100), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_speed_factor() {
+        assert!(validate_speed_factor(1.0).is_ok());
+        assert!(validate_speed_factor(0.25).is_ok());
+        assert!(validate_speed_factor(0.0).is_err());
+        assert!(validate_speed_factor(-2.0).is_err());
+        assert!(validate_speed_factor(f64::NAN).is_err());
+        assert!(validate_speed_factor(f64::INFINITY).is_err());
+
+        assert_eq!(scale_delay_ns(1_000_000_000, 1.0), 1_000_000_000);
+        assert_eq!(scale_delay_ns(1_000_000_000, 10.0), 100_000_000);
+        assert_eq!(scale_delay_ns(1_000_000_000, 0.5), 2_000_000_000);
+    }
+
+    #[test]
+    fn test_estimate_run_duration() {
+        let rate = SpacingMode::Rate(NonZeroU32::new(10).unwrap());
+
+        assert_eq!(
+            estimate_run_duration(&rate, 1000, 0, None),
+            Some(Duration::from_secs(100))
+        );
+        assert_eq!(
+            estimate_run_duration(&SpacingMode::Recorded, 100, 1_000_000_000, None),
+            Some(Duration::from_secs(100))
+        );
+        assert_eq!(
+            estimate_run_duration(&SpacingMode::None, 1000, 0, None),
+            None
+        );
+        let burst = SpacingMode::Burst {
+            burst_size: NonZeroU32::new(10).unwrap(),
+            burst_interval_ns: 1_000_000_000,
+        };
+        assert_eq!(
+            estimate_run_duration(&burst, 95, 0, None),
+            Some(Duration::from_secs(9))
+        );
+
+        // A max_eps cap below the spacing rate lengthens the run.
+        assert_eq!(
+            estimate_run_duration(&rate, 1000, 0, NonZeroU32::new(5)),
+            Some(Duration::from_secs(200))
+        );
+        assert_eq!(
+            estimate_run_duration(&SpacingMode::None, 1000, 0, NonZeroU32::new(5)),
+            Some(Duration::from_secs(200))
+        );
+    }
+
+    #[test]
+    fn test_max_events_per_second() {
+        let rate = SpacingMode::Rate(NonZeroU32::new(10).unwrap());
+
+        assert_eq!(max_events_per_second(&rate, None), Some(10));
+        assert_eq!(max_events_per_second(&rate, NonZeroU32::new(5)), Some(5));
+        assert_eq!(
+            max_events_per_second(&SpacingMode::Recorded, NonZeroU32::new(5)),
+            Some(5)
+        );
+        assert_eq!(max_events_per_second(&SpacingMode::None, None), None);
+    }
+
+    #[test]
+    fn test_dispatcher_kinds() {
+        let dispatchers: Vec<SourceChangeDispatcherDefinition> = serde_json::from_str(
+            r#"[
+                { "kind": "Console" },
+                { "kind": "JsonlFile", "max_events_per_file": 100 }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(dispatcher_kinds(&dispatchers), vec!["Console", "JsonlFile"]);
+    }
+
+    #[test]
+    fn test_event_feed_drops_lagging_subscriber_events() {
+        let event_feed = SourceChangeEventFeed::new(2);
+        let mut events_rx = event_feed.subscribe();
+
+        // Publishing never blocks, so a subscriber that doesn't keep up misses the oldest events.
+        for _ in 0..3 {
+            event_feed
+                .tx
+                .send(SourceChangeEventSummary {
+                    seq: 0,
+                    op: "i".to_string(),
+                    ts_ns: 0,
+                })
+                .unwrap();
+        }
+        assert!(matches!(
+            events_rx.try_recv(),
+            Err(broadcast::error::TryRecvError::Lagged(1))
+        ));
+    }
+}
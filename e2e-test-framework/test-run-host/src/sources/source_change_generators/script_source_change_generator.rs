@@ -15,6 +15,7 @@
 use std::{
     fmt::{self, Debug, Formatter},
     num::NonZeroU32,
+    path::PathBuf,
     pin::Pin,
     sync::Arc,
     time::{Duration, SystemTime},
@@ -27,6 +28,7 @@ use serde::Serialize;
 use time::{format_description, OffsetDateTime};
 use tokio::{
     sync::{
+        broadcast,
         mpsc::{Receiver, Sender},
         oneshot, Mutex,
     },
@@ -37,13 +39,14 @@ use tokio_stream::StreamExt;
 
 use test_data_store::{
     scripts::{
-        change_script_file_reader::ChangeScriptReader, ChangeHeaderRecord, ChangeScriptRecord,
-        SequencedChangeScriptRecord, SourceChangeEvent,
+        change_script_file_reader::ChangeScriptReader,
+        merged_change_script_reader::MergedChangeScriptReader, ChangeHeaderRecord,
+        ChangeScriptRecord, SequencedChangeScriptRecord, SourceChangeEvent,
     },
     test_repo_storage::{
         models::{
-            ScriptSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition, SpacingMode,
-            TimeMode,
+            ScriptSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition,
+            SourceChangeEventSchemaDefinition, SpacingMode, TimeMode,
         },
         TestSourceStorage,
     },
@@ -51,11 +54,16 @@ use test_data_store::{
 };
 
 use crate::sources::source_change_dispatchers::{
-    create_source_change_dispatcher, SourceChangeDispatcher,
+    event_field_names::EventFieldNames, SourceChangeDispatcher,
 };
 
 use super::{
-    SourceChangeGenerator, SourceChangeGeneratorCommandResponse, SourceChangeGeneratorStatus,
+    create_source_change_dispatchers, dispatcher_kinds,
+    event_schema_validator::{SourceChangeEventValidationStats, SourceChangeEventValidator},
+    max_events_per_second, scale_delay_ns, validate_spacing_rate, validate_speed_factor,
+    BurstTracker, MaxEpsLimiter, SourceChangeEventFeed, SourceChangeEventSummary,
+    SourceChangeGenerator, SourceChangeGeneratorCommandResponse, SourceChangeGeneratorDescription,
+    SourceChangeGeneratorStatus,
 };
 
 type ChangeStream = Pin<Box<dyn Stream<Item = anyhow::Result<SequencedChangeScriptRecord>> + Send>>;
@@ -82,12 +90,19 @@ pub enum ScriptSourceChangeGeneratorError {
 
 #[derive(Clone, Debug, Serialize)]
 pub struct ScriptSourceChangeGeneratorSettings {
+    pub auto_pause_after: Option<u64>,
+    pub auto_pause_repeat: bool,
     pub dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    pub event_field_names: EventFieldNames,
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
     pub id: TestRunSourceId,
     pub ignore_scripted_pause_commands: bool,
     pub input_storage: TestSourceStorage,
+    pub max_eps: Option<NonZeroU32>,
     pub output_storage: TestRunSourceStorage,
     pub spacing_mode: SpacingMode,
+    pub speed_factor: f64,
+    pub start_offset: Option<u64>,
     pub time_mode: TimeMode,
 }
 
@@ -99,13 +114,43 @@ impl ScriptSourceChangeGeneratorSettings {
         output_storage: TestRunSourceStorage,
         dispatchers: Vec<SourceChangeDispatcherDefinition>,
     ) -> anyhow::Result<Self> {
+        validate_spacing_rate(
+            &definition.common.spacing_mode,
+            definition.common.max_spacing_rate,
+        )?;
+        validate_speed_factor(definition.common.speed_factor)?;
+
+        if definition.auto_pause_after == Some(0) {
+            anyhow::bail!("auto_pause_after must be greater than 0");
+        }
+
+        // Check the start_offset leaves records to dispatch.
+        if let Some(start_offset) = definition.start_offset {
+            let scripts = get_change_scripts(&input_storage).await?;
+            let record_count = count_source_change_records(&scripts)?;
+            if start_offset >= record_count {
+                anyhow::bail!(
+                    "start_offset {} must be less than the {} SourceChange records in the change scripts",
+                    start_offset,
+                    record_count
+                );
+            }
+        }
+
         Ok(ScriptSourceChangeGeneratorSettings {
+            auto_pause_after: definition.auto_pause_after,
+            auto_pause_repeat: definition.auto_pause_repeat,
             dispatchers,
+            event_field_names: EventFieldNames::new(definition.common.event_field_names),
+            event_schema: definition.common.event_schema,
             id: test_run_source_id,
             ignore_scripted_pause_commands: definition.ignore_scripted_pause_commands,
             input_storage,
+            max_eps: definition.common.max_eps,
             output_storage,
             spacing_mode: definition.common.spacing_mode,
+            speed_factor: definition.common.speed_factor,
+            start_offset: definition.start_offset,
             time_mode: definition.common.time_mode,
         })
     }
@@ -175,6 +220,8 @@ pub struct ProcessedChangeScriptRecord {
 pub struct ScriptSourceChangeGenerator {
     settings: ScriptSourceChangeGeneratorSettings,
     #[serde(skip_serializing)]
+    event_feed: SourceChangeEventFeed,
+    #[serde(skip_serializing)]
     script_processor_tx_channel: Sender<ScriptSourceChangeGeneratorMessage>,
     #[serde(skip_serializing)]
     _script_processor_thread_handle: Arc<Mutex<JoinHandle<anyhow::Result<()>>>>,
@@ -192,24 +239,32 @@ impl ScriptSourceChangeGenerator {
             test_run_source_id,
             definition,
             input_storage,
-            output_storage.clone(),
+            output_storage,
             dispatchers,
         )
         .await?;
+
+        Ok(Self::from_settings(settings))
+    }
+
+    pub fn from_settings(settings: ScriptSourceChangeGeneratorSettings) -> Self {
         log::debug!("Creating ScriptSourceChangeGenerator from {:?}", &settings);
 
+        let event_feed = SourceChangeEventFeed::default();
         let (script_processor_tx_channel, script_processor_rx_channel) =
             tokio::sync::mpsc::channel(100);
         let script_processor_thread_handle = tokio::spawn(script_processor_thread(
             script_processor_rx_channel,
             settings.clone(),
+            event_feed.clone(),
         ));
 
-        Ok(Self {
+        Self {
             settings,
+            event_feed,
             script_processor_tx_channel,
             _script_processor_thread_handle: Arc::new(Mutex::new(script_processor_thread_handle)),
-        })
+        }
     }
 
     pub fn get_id(&self) -> TestRunSourceId {
@@ -237,10 +292,14 @@ impl ScriptSourceChangeGenerator {
         match r {
             Ok(_) => {
                 let player_response = response_rx.await?;
+                let stats = &player_response.state.stats;
+                let event_count =
+                    stats.num_source_change_records - stats.num_skipped_source_change_records;
 
                 Ok(SourceChangeGeneratorCommandResponse {
                     result: player_response.result,
                     state: super::SourceChangeGeneratorState {
+                        event_count,
                         status: player_response.state.status,
                         state: serde_json::to_value(player_response.state).unwrap(),
                     },
@@ -256,6 +315,30 @@ impl ScriptSourceChangeGenerator {
 
 #[async_trait]
 impl SourceChangeGenerator for ScriptSourceChangeGenerator {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        let settings = &self.settings;
+        let max_eps = max_events_per_second(&settings.spacing_mode, settings.max_eps);
+
+        // The number of events depends on the change scripts, which aren't read until the
+        // generator is initialized, so only the rate can be bounded up front.
+        SourceChangeGeneratorDescription::new(
+            "Script",
+            serde_json::json!({
+                "dispatchers": dispatcher_kinds(&settings.dispatchers),
+                "event_field_names": settings.event_field_names,
+                "event_schema": settings.event_schema.is_some(),
+                "ignore_scripted_pause_commands": settings.ignore_scripted_pause_commands,
+                "max_eps": settings.max_eps,
+                "spacing_mode": settings.spacing_mode,
+                "speed_factor": settings.speed_factor,
+                "time_mode": settings.time_mode,
+            }),
+            serde_json::json!({
+                "max_events_per_second": max_eps,
+            }),
+        )
+    }
+
     async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
         self.send_command(ScriptSourceChangeGeneratorCommand::GetState)
             .await
@@ -266,7 +349,11 @@ impl SourceChangeGenerator for ScriptSourceChangeGenerator {
             .await
     }
 
-    async fn reset(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+    async fn reset(
+        &self,
+        _seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        // Changes aren't randomized, so there is nothing to seed.
         self.send_command(ScriptSourceChangeGeneratorCommand::Reset)
             .await
     }
@@ -305,6 +392,10 @@ impl SourceChangeGenerator for ScriptSourceChangeGenerator {
             .await
     }
 
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        Ok(self.event_feed.subscribe())
+    }
+
     fn set_test_run_host_on_dispatchers(&self, _test_run_host: std::sync::Arc<crate::TestRunHost>) {
         // This generator uses a thread-based architecture, so we can't directly access dispatchers
         // The TestRunHost will be set when the dispatchers are recreated on reset
@@ -314,7 +405,9 @@ impl SourceChangeGenerator for ScriptSourceChangeGenerator {
 
 #[derive(Debug, Serialize)]
 pub struct ScriptSourceChangeGeneratorExternalState {
+    pub auto_pause_remaining: Option<u64>,
     pub error_messages: Vec<String>,
+    pub event_validation: Option<SourceChangeEventValidationStats>,
     pub ignore_scripted_pause_commands: bool,
     pub header_record: ChangeHeaderRecord,
     pub next_record: Option<SequencedChangeScriptRecord>,
@@ -322,7 +415,9 @@ pub struct ScriptSourceChangeGeneratorExternalState {
     pub skips_remaining: u64,
     pub skips_spacing_mode: Option<SpacingMode>,
     pub spacing_mode: SpacingMode,
+    pub stats: ScriptSourceChangeGeneratorStats,
     pub status: SourceChangeGeneratorStatus,
+    pub status_reason: String,
     pub steps_remaining: u64,
     pub steps_spacing_mode: Option<SpacingMode>,
     pub test_run_source_id: TestRunSourceId,
@@ -337,7 +432,9 @@ impl From<&mut ScriptSourceChangeGeneratorInternalState>
 {
     fn from(state: &mut ScriptSourceChangeGeneratorInternalState) -> Self {
         Self {
+            auto_pause_remaining: state.auto_pause_remaining,
             error_messages: state.error_messages.clone(),
+            event_validation: state.event_validator.stats(),
             ignore_scripted_pause_commands: state.settings.ignore_scripted_pause_commands,
             header_record: state.header_record.clone(),
             next_record: state.next_record.clone(),
@@ -345,7 +442,9 @@ impl From<&mut ScriptSourceChangeGeneratorInternalState>
             skips_remaining: state.skips_remaining,
             skips_spacing_mode: state.skips_spacing_mode.clone(),
             spacing_mode: state.settings.spacing_mode.clone(),
+            stats: state.stats.clone(),
             status: state.status,
+            status_reason: state.status_reason.clone(),
             steps_remaining: state.steps_remaining,
             steps_spacing_mode: state.steps_spacing_mode.clone(),
             test_run_source_id: state.settings.id.clone(),
@@ -358,13 +457,19 @@ impl From<&mut ScriptSourceChangeGeneratorInternalState>
 }
 
 pub struct ScriptSourceChangeGeneratorInternalState {
+    // SourceChange records left to dispatch while Running before the generator pauses itself,
+    // or None if it won't auto-pause.
+    pub auto_pause_remaining: Option<u64>,
     pub change_stream:
         Pin<Box<dyn Stream<Item = Result<SequencedChangeScriptRecord, anyhow::Error>> + Send>>,
     pub change_tx_channel: Sender<ScheduledChangeScriptRecordMessage>,
     pub delayer_tx_channel: Sender<ScheduledChangeScriptRecordMessage>,
     pub dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>>,
     pub error_messages: Vec<String>,
+    pub event_feed: SourceChangeEventFeed,
+    pub event_validator: SourceChangeEventValidator,
     pub header_record: ChangeHeaderRecord,
+    pub max_eps_limiter: MaxEpsLimiter,
     pub message_seq_num: u64,
     pub next_record: Option<SequencedChangeScriptRecord>,
     pub previous_record: Option<ProcessedChangeScriptRecord>,
@@ -373,6 +478,8 @@ pub struct ScriptSourceChangeGeneratorInternalState {
     pub skips_remaining: u64,
     pub skips_spacing_mode: Option<SpacingMode>,
     pub status: SourceChangeGeneratorStatus,
+    // Why the generator is in its current status, e.g. "paused by Pause command".
+    pub status_reason: String,
     pub stats: ScriptSourceChangeGeneratorStats,
     pub steps_remaining: u64,
     pub steps_spacing_mode: Option<SpacingMode>,
@@ -384,6 +491,7 @@ pub struct ScriptSourceChangeGeneratorInternalState {
 impl ScriptSourceChangeGeneratorInternalState {
     async fn initialize(
         settings: ScriptSourceChangeGeneratorSettings,
+        event_feed: SourceChangeEventFeed,
     ) -> anyhow::Result<(Self, Receiver<ScheduledChangeScriptRecordMessage>)> {
         log::debug!(
             "Initializing ScriptSourceChangeGenerator using {:?}",
@@ -391,17 +499,10 @@ impl ScriptSourceChangeGeneratorInternalState {
         );
 
         // Get the list of script files from the input storage.
-        let script_files = match settings.input_storage.get_script_files().await {
-            Ok(ds) => ds.source_change_script_files,
-            Err(e) => {
-                anyhow::bail!("Error getting script files from input storage: {:?}", e);
-            }
-        };
+        let scripts = get_change_scripts(&settings.input_storage).await?;
 
         // Create the change stream.
-        let reader = ChangeScriptReader::new(script_files)?;
-        let header_record = reader.get_header();
-        let mut change_stream = Box::pin(reader) as ChangeStream;
+        let (header_record, mut change_stream) = open_change_stream(scripts)?;
         let next_record = match change_stream.next().await {
             Some(Ok(seq_record)) => Some(seq_record),
             Some(Err(e)) => {
@@ -410,20 +511,17 @@ impl ScriptSourceChangeGeneratorInternalState {
             None => None,
         };
 
+        // Compile the event_schema, if any.
+        let event_validator = SourceChangeEventValidator::new(settings.event_schema.as_ref())?;
+
         // Create the dispatchers
-        let mut dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>> = Vec::new();
-        for def in settings.dispatchers.iter() {
-            match create_source_change_dispatcher(def, &settings.output_storage).await {
-                Ok(dispatcher) => dispatchers.push(dispatcher),
-                Err(e) => {
-                    anyhow::bail!(
-                        "Error creating SourceChangeDispatcher: {:?}; Error: {:?}",
-                        def,
-                        e
-                    );
-                }
-            }
-        }
+        let dispatchers = create_source_change_dispatchers(
+            &settings.dispatchers,
+            &settings.output_storage,
+            &settings.event_field_names,
+            &event_feed,
+        )
+        .await?;
 
         // Create the channels and threads used for message passing.
         let (change_tx_channel, change_rx_channel) = tokio::sync::mpsc::channel(1000);
@@ -444,12 +542,16 @@ impl ScriptSourceChangeGeneratorInternalState {
         ));
 
         let state = Self {
+            auto_pause_remaining: settings.auto_pause_after,
             change_stream,
             change_tx_channel,
             delayer_tx_channel,
             dispatchers,
             error_messages: Vec::new(),
+            event_feed,
+            event_validator,
             header_record,
+            max_eps_limiter: MaxEpsLimiter::new(settings.max_eps),
             message_seq_num: 0,
             next_record,
             previous_record: None,
@@ -458,6 +560,7 @@ impl ScriptSourceChangeGeneratorInternalState {
             skips_remaining: 0,
             skips_spacing_mode: None,
             status: SourceChangeGeneratorStatus::Paused,
+            status_reason: "paused: initialized".to_string(),
             stats: ScriptSourceChangeGeneratorStats::default(),
             steps_remaining: 0,
             steps_spacing_mode: None,
@@ -502,24 +605,30 @@ impl ScriptSourceChangeGeneratorInternalState {
     //     Ok(delay_ns)
     // }
 
-    async fn close_dispatchers(&mut self) {
+    async fn close_dispatchers(&mut self) -> anyhow::Result<()> {
         let dispatchers = &mut self.dispatchers;
 
         log::debug!("Closing dispatchers - #dispatchers:{}", dispatchers.len());
 
         let futures: Vec<_> = dispatchers
             .iter_mut()
-            .map(|dispatcher| async move {
-                let _ = dispatcher.close().await;
-            })
+            .map(|dispatcher| async move { dispatcher.close().await })
             .collect();
 
-        // Wait for all of them to complete
-        // TODO - Handle errors properly.
-        let _ = join_all(futures).await;
+        // Wait for all of them to complete, returning the first error.
+        join_all(futures).await.into_iter().collect()
     }
 
-    async fn dispatch_source_change_events(&mut self, events: Vec<&SourceChangeEvent>) {
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        // Validate the events against the event_schema before any are dispatched.
+        self.event_validator.validate(&events)?;
+
+        // Enforce the max_eps cap after any spacing has been applied.
+        self.max_eps_limiter.until_ready(events.len()).await;
+
         let dispatchers = &mut self.dispatchers;
 
         log::debug!(
@@ -532,15 +641,13 @@ impl ScriptSourceChangeGeneratorInternalState {
             .iter_mut()
             .map(|dispatcher| {
                 let events = events.clone();
-                async move {
-                    let _ = dispatcher.dispatch_source_change_events(events).await;
-                }
+                async move { dispatcher.dispatch_source_change_events(events).await }
             })
             .collect();
 
-        // Wait for all of them to complete
-        // TODO - Handle errors properly.
-        let _ = join_all(futures).await;
+        // Wait for all of them to complete, returning the first error so the generator
+        // enters its error state.
+        join_all(futures).await.into_iter().collect()
     }
 
     async fn load_next_change_stream_record(&mut self) -> anyhow::Result<()> {
@@ -564,6 +671,75 @@ impl ScriptSourceChangeGeneratorInternalState {
         Ok(())
     }
 
+    // Advances past the first start_offset SourceChange records without dispatching them.
+    // The virtual time follows the skipped records so replay continues from where they end.
+    async fn skip_to_start_offset(&mut self, start_offset: u64) -> anyhow::Result<()> {
+        log::info!(
+            "Script skipping to start_offset {} for TestRunSource {}",
+            start_offset,
+            self.settings.id
+        );
+
+        self.set_status(
+            SourceChangeGeneratorStatus::Skipping,
+            format!("skipping to start_offset {}", start_offset),
+        );
+
+        let mut skipped = 0;
+        while skipped < start_offset {
+            let next_record = match self.next_record.as_ref() {
+                Some(record) => record.clone(),
+                None => anyhow::bail!("Reached end of change stream before start_offset"),
+            };
+
+            match &next_record.record {
+                ChangeScriptRecord::SourceChange(_) => {
+                    self.stats.num_source_change_records += 1;
+                    self.stats.num_skipped_source_change_records += 1;
+                    skipped += 1;
+                }
+                ChangeScriptRecord::PauseCommand(_) => self.stats.num_pause_records += 1,
+                ChangeScriptRecord::Label(_) => self.stats.num_label_records += 1,
+                ChangeScriptRecord::Finish(_) => {
+                    anyhow::bail!("Reached Finish record before start_offset {}", start_offset)
+                }
+                _ => {}
+            }
+
+            self.time_shift(next_record)?;
+            self.load_next_change_stream_record().await?;
+        }
+
+        Ok(())
+    }
+
+    // Sets the status along with the reason reported for it in the external state.
+    fn set_status(&mut self, status: SourceChangeGeneratorStatus, reason: impl Into<String>) {
+        self.status = status;
+        self.status_reason = reason.into();
+    }
+
+    // Counts a SourceChange record dispatched while Running against auto_pause_after, and
+    // returns true if the generator should now pause. The count is re-armed for the next
+    // Start if auto_pause_repeat is set.
+    fn count_auto_pause(&mut self) -> bool {
+        match self.auto_pause_remaining {
+            Some(remaining) if remaining > 1 => {
+                self.auto_pause_remaining = Some(remaining - 1);
+                false
+            }
+            Some(_) => {
+                self.auto_pause_remaining = if self.settings.auto_pause_repeat {
+                    self.settings.auto_pause_after
+                } else {
+                    None
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
     // Function to log the Player State at varying levels of detail.
     fn log_state(&self, msg: &str) {
         match log::max_level() {
@@ -573,6 +749,13 @@ impl ScriptSourceChangeGeneratorInternalState {
         }
     }
 
+    // Processes a message from the change stream, entering the error state if it fails.
+    async fn handle_change_stream_message(&mut self, message: ScheduledChangeScriptRecordMessage) {
+        if let Err(e) = self.process_change_stream_message(message).await {
+            self.transition_to_error_state("Error calling process_change_stream_message", Some(&e));
+        }
+    }
+
     async fn process_change_stream_message(
         &mut self,
         message: ScheduledChangeScriptRecordMessage,
@@ -601,9 +784,20 @@ impl ScriptSourceChangeGeneratorInternalState {
                         self.dispatch_source_change_events(vec![
                             &change_record.source_change_event,
                         ])
-                        .await;
+                        .await?;
                         self.load_next_change_stream_record().await?;
-                        self.schedule_next_change_stream_record().await?;
+
+                        if self.count_auto_pause() {
+                            self.set_status(
+                                SourceChangeGeneratorStatus::Paused,
+                                format!(
+                                    "paused: auto-paused after {} records",
+                                    self.settings.auto_pause_after.unwrap_or_default()
+                                ),
+                            );
+                        } else {
+                            self.schedule_next_change_stream_record().await?;
+                        }
                     }
                     SourceChangeGeneratorStatus::Stepping => {
                         if self.steps_remaining > 0 {
@@ -611,13 +805,16 @@ impl ScriptSourceChangeGeneratorInternalState {
                             self.dispatch_source_change_events(vec![
                                 &change_record.source_change_event,
                             ])
-                            .await;
+                            .await?;
 
                             self.load_next_change_stream_record().await?;
 
                             self.steps_remaining -= 1;
                             if self.steps_remaining == 0 {
-                                self.status = SourceChangeGeneratorStatus::Paused;
+                                self.set_status(
+                                    SourceChangeGeneratorStatus::Paused,
+                                    "paused: completed all steps",
+                                );
                                 self.steps_spacing_mode = None;
                             } else {
                                 self.schedule_next_change_stream_record().await?;
@@ -640,7 +837,10 @@ impl ScriptSourceChangeGeneratorInternalState {
 
                             self.skips_remaining -= 1;
                             if self.skips_remaining == 0 {
-                                self.status = SourceChangeGeneratorStatus::Paused;
+                                self.set_status(
+                                    SourceChangeGeneratorStatus::Paused,
+                                    "paused: completed all skips",
+                                );
                                 self.skips_spacing_mode = None;
                             } else {
                                 self.schedule_next_change_stream_record().await?;
@@ -669,7 +869,10 @@ impl ScriptSourceChangeGeneratorInternalState {
                 if self.settings.ignore_scripted_pause_commands {
                     log::debug!("Ignoring Change Script Pause Command: {:?}", shifted_record);
                 } else {
-                    self.status = SourceChangeGeneratorStatus::Paused;
+                    self.set_status(
+                        SourceChangeGeneratorStatus::Paused,
+                        "paused by change script PauseCommand",
+                    );
                 }
             }
             ChangeScriptRecord::Label(label_record) => {
@@ -758,17 +961,10 @@ impl ScriptSourceChangeGeneratorInternalState {
 
     async fn reset(&mut self) -> anyhow::Result<()> {
         // Get the list of script files from the input storage.
-        let script_files = match self.settings.input_storage.get_script_files().await {
-            Ok(ds) => ds.source_change_script_files,
-            Err(e) => {
-                anyhow::bail!("Error getting script files from input storage: {:?}", e);
-            }
-        };
+        let scripts = get_change_scripts(&self.settings.input_storage).await?;
 
         // Create the change stream.
-        let reader = ChangeScriptReader::new(script_files)?;
-        let header_record = reader.get_header();
-        let mut change_stream = Box::pin(reader) as ChangeStream;
+        let (header_record, mut change_stream) = open_change_stream(scripts)?;
         let next_record = match change_stream.next().await {
             Some(Ok(seq_record)) => Some(seq_record),
             Some(Err(e)) => {
@@ -777,36 +973,36 @@ impl ScriptSourceChangeGeneratorInternalState {
             None => None,
         };
 
-        // Create the new dispatchers
-        self.close_dispatchers().await;
-        let mut dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>> = Vec::new();
-        for def in self.settings.dispatchers.iter() {
-            match create_source_change_dispatcher(def, &self.settings.output_storage).await {
-                Ok(dispatcher) => dispatchers.push(dispatcher),
-                Err(e) => {
-                    anyhow::bail!(
-                        "Error creating SourceChangeDispatcher: {:?}; Error: {:?}",
-                        def,
-                        e
-                    );
-                }
-            }
+        // Create the new dispatchers. The old ones are being replaced, so a failure to close
+        // them shouldn't prevent the reset.
+        if let Err(e) = self.close_dispatchers().await {
+            log::warn!("Error closing dispatchers during reset: {:?}", e);
         }
+        let dispatchers = create_source_change_dispatchers(
+            &self.settings.dispatchers,
+            &self.settings.output_storage,
+            &self.settings.event_field_names,
+            &self.event_feed,
+        )
+        .await?;
         // These fields do not get reset:
         //   state.change_tx_channel
         //   state.delayer_tx_channel
+        //   state.max_eps_limiter
         //   state.settings
 
+        self.auto_pause_remaining = self.settings.auto_pause_after;
         self.dispatchers = dispatchers;
         self.change_stream = change_stream;
         self.error_messages = Vec::new();
+        self.event_validator.reset();
         self.header_record = header_record;
         self.message_seq_num = 0;
         self.next_record = next_record;
         self.previous_record = None;
         self.skips_remaining = 0;
         self.skips_spacing_mode = None;
-        self.status = SourceChangeGeneratorStatus::Paused;
+        self.set_status(SourceChangeGeneratorStatus::Paused, "paused: reset");
         self.stats = ScriptSourceChangeGeneratorStats::default();
         self.steps_remaining = 0;
         self.steps_spacing_mode = None;
@@ -842,16 +1038,14 @@ impl ScriptSourceChangeGeneratorInternalState {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
                     }
                 }
-                Some(SpacingMode::Rate(_)) => {
+                Some(SpacingMode::Rate(_) | SpacingMode::Burst { .. }) => {
                     if let Err(e) = self.rate_limiter_tx_channel.send(sch_msg).await {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
                     }
                 }
                 Some(SpacingMode::Recorded) => {
-                    if next_record.offset_ns > self.virtual_time_ns_offset {
-                        sch_msg.delay_ns = next_record.offset_ns - self.virtual_time_ns_offset;
-                        sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
-                    }
+                    sch_msg.delay_ns = self.recorded_delay_ns(&next_record);
+                    sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
 
                     if let Err(e) = self.delayer_tx_channel.send(sch_msg).await {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
@@ -866,7 +1060,7 @@ impl ScriptSourceChangeGeneratorInternalState {
                             );
                         }
                     }
-                    SpacingMode::Rate(_) => {
+                    SpacingMode::Rate(_) | SpacingMode::Burst { .. } => {
                         if let Err(e) = self.rate_limiter_tx_channel.send(sch_msg).await {
                             anyhow::bail!(
                                 "Error sending ScheduledChangeScriptRecordMessage: {:?}",
@@ -875,10 +1069,8 @@ impl ScriptSourceChangeGeneratorInternalState {
                         }
                     }
                     SpacingMode::Recorded => {
-                        if next_record.offset_ns > self.virtual_time_ns_offset {
-                            sch_msg.delay_ns = next_record.offset_ns - self.virtual_time_ns_offset;
-                            sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
-                        }
+                        sch_msg.delay_ns = self.recorded_delay_ns(&next_record);
+                        sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
 
                         if let Err(e) = self.delayer_tx_channel.send(sch_msg).await {
                             anyhow::bail!(
@@ -895,16 +1087,14 @@ impl ScriptSourceChangeGeneratorInternalState {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
                     }
                 }
-                SpacingMode::Rate(_) => {
+                SpacingMode::Rate(_) | SpacingMode::Burst { .. } => {
                     if let Err(e) = self.rate_limiter_tx_channel.send(sch_msg).await {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
                     }
                 }
                 SpacingMode::Recorded => {
-                    if next_record.offset_ns > self.virtual_time_ns_offset {
-                        sch_msg.delay_ns = next_record.offset_ns - self.virtual_time_ns_offset;
-                        sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
-                    }
+                    sch_msg.delay_ns = self.recorded_delay_ns(&next_record);
+                    sch_msg.virtual_time_ns_replay += sch_msg.delay_ns;
 
                     if let Err(e) = self.delayer_tx_channel.send(sch_msg).await {
                         anyhow::bail!("Error sending ScheduledChangeScriptRecordMessage: {:?}", e);
@@ -920,6 +1110,23 @@ impl ScriptSourceChangeGeneratorInternalState {
         Ok(())
     }
 
+    // The time to wait before the next record when replaying with SpacingMode::Recorded,
+    // scaled by the speed_factor.
+    fn recorded_delay_ns(&self, next_record: &SequencedChangeScriptRecord) -> u64 {
+        match self.settings.time_mode {
+            // In Live mode the offset is the real time since the start, so the record is due
+            // once the real time reaches its scaled offset.
+            TimeMode::Live => scale_delay_ns(next_record.offset_ns, self.settings.speed_factor)
+                .saturating_sub(self.virtual_time_ns_offset),
+            TimeMode::Recorded | TimeMode::Rebased(_) => scale_delay_ns(
+                next_record
+                    .offset_ns
+                    .saturating_sub(self.virtual_time_ns_offset),
+                self.settings.speed_factor,
+            ),
+        }
+    }
+
     fn time_shift(
         &mut self,
         next_record: SequencedChangeScriptRecord,
@@ -1071,7 +1278,10 @@ impl ScriptSourceChangeGeneratorInternalState {
                     self.settings.id
                 );
 
-                self.status = SourceChangeGeneratorStatus::Skipping;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Skipping,
+                    format!("skipping {} records by Skip command", skips),
+                );
                 self.skips_remaining = *skips;
                 self.skips_spacing_mode = spacing_mode.clone();
                 self.schedule_next_change_stream_record().await
@@ -1079,7 +1289,17 @@ impl ScriptSourceChangeGeneratorInternalState {
             ScriptSourceChangeGeneratorCommand::Start => {
                 log::info!("Script Started for TestRunSource {}", self.settings.id);
 
-                self.status = SourceChangeGeneratorStatus::Running;
+                // On the first Start, skip to the configured start_offset.
+                if self.previous_record.is_none() {
+                    if let Some(start_offset) = self.settings.start_offset {
+                        self.skip_to_start_offset(start_offset).await?;
+                    }
+                }
+
+                self.set_status(
+                    SourceChangeGeneratorStatus::Running,
+                    "started by Start command",
+                );
                 self.schedule_next_change_stream_record().await
             }
             ScriptSourceChangeGeneratorCommand::Step {
@@ -1092,7 +1312,10 @@ impl ScriptSourceChangeGeneratorInternalState {
                     self.settings.id
                 );
 
-                self.status = SourceChangeGeneratorStatus::Stepping;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Stepping,
+                    format!("stepping {} records by Step command", steps),
+                );
                 self.steps_remaining = *steps;
                 self.steps_spacing_mode = spacing_mode.clone();
                 self.schedule_next_change_stream_record().await
@@ -1117,7 +1340,10 @@ impl ScriptSourceChangeGeneratorInternalState {
         match command {
             ScriptSourceChangeGeneratorCommand::GetState => Ok(()),
             ScriptSourceChangeGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 Ok(())
             }
             ScriptSourceChangeGeneratorCommand::Reset => {
@@ -1150,7 +1376,10 @@ impl ScriptSourceChangeGeneratorInternalState {
         match command {
             ScriptSourceChangeGeneratorCommand::GetState => Ok(()),
             ScriptSourceChangeGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 self.skips_remaining = 0;
                 self.skips_spacing_mode = None;
                 Ok(())
@@ -1181,7 +1410,10 @@ impl ScriptSourceChangeGeneratorInternalState {
         match command {
             ScriptSourceChangeGeneratorCommand::GetState => Ok(()),
             ScriptSourceChangeGeneratorCommand::Pause => {
-                self.status = SourceChangeGeneratorStatus::Paused;
+                self.set_status(
+                    SourceChangeGeneratorStatus::Paused,
+                    "paused by Pause command",
+                );
                 self.steps_remaining = 0;
                 self.steps_spacing_mode = None;
                 Ok(())
@@ -1219,7 +1451,10 @@ impl ScriptSourceChangeGeneratorInternalState {
     async fn transition_to_finished_state(&mut self) {
         log::info!("Script Finished for TestRunSource {}", self.settings.id);
 
-        self.status = SourceChangeGeneratorStatus::Finished;
+        self.set_status(
+            SourceChangeGeneratorStatus::Finished,
+            "finished: reached the end of the change script",
+        );
         self.stats.actual_end_time_ns = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
@@ -1229,14 +1464,19 @@ impl ScriptSourceChangeGeneratorInternalState {
         self.steps_remaining = 0;
         self.steps_spacing_mode = None;
 
-        self.close_dispatchers().await;
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
         self.write_result_summary().await.ok();
     }
 
     async fn transition_to_stopped_state(&mut self) {
         log::info!("Script Stopped for TestRunSource {}", self.settings.id);
 
-        self.status = SourceChangeGeneratorStatus::Stopped;
+        self.set_status(
+            SourceChangeGeneratorStatus::Stopped,
+            "stopped by Stop command",
+        );
         self.stats.actual_end_time_ns = SystemTime::now()
             .duration_since(SystemTime::UNIX_EPOCH)
             .unwrap()
@@ -1246,18 +1486,23 @@ impl ScriptSourceChangeGeneratorInternalState {
         self.steps_remaining = 0;
         self.steps_spacing_mode = None;
 
-        self.close_dispatchers().await;
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
         self.write_result_summary().await.ok();
     }
 
     fn transition_to_error_state(&mut self, error_message: &str, error: Option<&anyhow::Error>) {
-        self.status = SourceChangeGeneratorStatus::Error;
-
         let msg = match error {
             Some(e) => format!("{}: {:?}", error_message, e),
             None => error_message.to_string(),
         };
 
+        self.set_status(
+            SourceChangeGeneratorStatus::Error,
+            format!("error: {}", msg),
+        );
+
         self.log_state(&msg);
 
         self.error_messages.push(msg);
@@ -1286,7 +1531,9 @@ impl ScriptSourceChangeGeneratorInternalState {
 impl Debug for ScriptSourceChangeGeneratorInternalState {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("ScriptSourceChangeGeneratorInternalState")
+            .field("auto_pause_remaining", &self.auto_pause_remaining)
             .field("error_messages", &self.error_messages)
+            .field("event_validation", &self.event_validator.stats())
             .field(
                 "ignore_scripted_pause_commands",
                 &self.settings.ignore_scripted_pause_commands,
@@ -1298,6 +1545,7 @@ impl Debug for ScriptSourceChangeGeneratorInternalState {
             .field("skips_spacing_mode", &self.skips_spacing_mode)
             .field("spacing_mode", &self.settings.spacing_mode)
             .field("status", &self.status)
+            .field("status_reason", &self.status_reason)
             .field("stats", &self.stats)
             .field("steps_remaining", &self.steps_remaining)
             .field("steps_spacing_mode", &self.steps_spacing_mode)
@@ -1332,6 +1580,7 @@ pub struct ScriptSourceChangeGeneratorResultSummary {
     pub num_label_records: u64,
     pub num_pause_records: u64,
     pub processing_rate: f64,
+    pub status_reason: String,
     pub test_run_source_id: String,
 }
 
@@ -1364,6 +1613,7 @@ impl From<&mut ScriptSourceChangeGeneratorInternalState>
             num_label_records: state.stats.num_label_records,
             num_pause_records: state.stats.num_pause_records,
             processing_rate: state.stats.num_source_change_records as f64 / run_duration_sec,
+            status_reason: state.status_reason.clone(),
             test_run_source_id: state.settings.id.to_string(),
         }
     }
@@ -1396,16 +1646,65 @@ impl Debug for ScriptSourceChangeGeneratorResultSummary {
             .field("run_duration", &run_duration)
             .field("source_change_records", &source_change_records)
             .field("processing_rate", &processing_rate)
+            .field("status_reason", &self.status_reason)
             .finish()
     }
 }
 
+// Counts the SourceChange records in the change script files, reading up to the Finish record.
+// Merging scripts doesn't change how many SourceChange records they hold, so each script is
+// counted on its own.
+fn count_source_change_records(scripts: &[Vec<PathBuf>]) -> anyhow::Result<u64> {
+    let mut count = 0;
+    for script_files in scripts {
+        for seq_record in ChangeScriptReader::new(script_files.clone())? {
+            match seq_record?.record {
+                ChangeScriptRecord::SourceChange(_) => count += 1,
+                ChangeScriptRecord::Finish(_) => break,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+// Gets the change scripts to play: the files of a single script, or of each script a
+// MergedScript generator merges.
+async fn get_change_scripts(
+    input_storage: &TestSourceStorage,
+) -> anyhow::Result<Vec<Vec<PathBuf>>> {
+    match input_storage.get_script_files().await {
+        Ok(ds) if ds.merged_source_change_script_files.is_empty() => {
+            Ok(vec![ds.source_change_script_files])
+        }
+        Ok(ds) => Ok(ds.merged_source_change_script_files),
+        Err(e) => {
+            anyhow::bail!("Error getting script files from input storage: {:?}", e);
+        }
+    }
+}
+
+// Opens a stream over the change scripts, merging them by recorded time if there is more than one.
+fn open_change_stream(
+    mut scripts: Vec<Vec<PathBuf>>,
+) -> anyhow::Result<(ChangeHeaderRecord, ChangeStream)> {
+    if scripts.len() == 1 {
+        let reader = ChangeScriptReader::new(scripts.remove(0))?;
+        Ok((reader.get_header(), Box::pin(reader) as ChangeStream))
+    } else {
+        let reader = MergedChangeScriptReader::new(scripts)?;
+        Ok((reader.get_header(), Box::pin(reader) as ChangeStream))
+    }
+}
+
 // Function that defines the operation of the ScriptSourceChangeGenerator thread.
 // The ScriptSourceChangeGenerator thread processes ChangeScriptPlayerCommands sent to it from the Web API handler functions.
 // The Web API function communicate via a channel and provide oneshot channels for the ScriptSourceChangeGenerator to send responses back.
 pub async fn script_processor_thread(
     mut command_rx_channel: Receiver<ScriptSourceChangeGeneratorMessage>,
     settings: ScriptSourceChangeGeneratorSettings,
+    event_feed: SourceChangeEventFeed,
 ) -> anyhow::Result<()> {
     log::info!(
         "Script processor thread started for TestRunSource {} ...",
@@ -1414,7 +1713,7 @@ pub async fn script_processor_thread(
 
     // The ScriptSourceChangeGenerator always starts with the first script record loaded and Paused.
     let (mut state, mut change_rx_channel) =
-        match ScriptSourceChangeGeneratorInternalState::initialize(settings).await {
+        match ScriptSourceChangeGeneratorInternalState::initialize(settings, event_feed).await {
             Ok((state, change_rx_channel)) => (state, change_rx_channel),
             Err(e) => {
                 // If initialization fails, don't dont transition to an error state, just log an error and exit the thread.
@@ -1453,8 +1752,7 @@ pub async fn script_processor_thread(
                         // Only process the message if the seq_num matches the expected one.
                         // This avoids dealing with delayed messages from the delayer thread that are no longer relevant.
                         if change_stream_message.seq_num == state.message_seq_num && state.status.is_processing() {
-                            state.process_change_stream_message(change_stream_message).await
-                                .inspect_err(|e| state.transition_to_error_state("Error calling process_change_stream_message", Some(e))).ok();
+                            state.handle_change_stream_message(change_stream_message).await;
                         }
                     }
                     None => {
@@ -1513,11 +1811,23 @@ pub async fn rate_limiter_thread(
         SpacingMode::Rate(rate) => RateLimiter::direct(Quota::per_second(rate)),
         _ => RateLimiter::direct(Quota::per_second(NonZeroU32::new(u32::MAX).unwrap())),
     };
+    let mut burst_tracker = BurstTracker::default();
 
     loop {
         match delayer_rx_channel.recv().await {
             Some(message) => {
-                limiter.until_ready().await;
+                if let SpacingMode::Burst {
+                    burst_size,
+                    burst_interval_ns,
+                } = spacing_mode
+                {
+                    let delay = burst_tracker.next_delay(burst_size, burst_interval_ns);
+                    if !delay.is_zero() {
+                        sleep(delay).await;
+                    }
+                } else {
+                    limiter.until_ready().await;
+                }
                 if let Err(e) = change_tx_channel.send(message).await {
                     log::error!("Error sending ScheduledChangeScriptRecordMessage to change_tx_channel: {:?}", e);
                 }
@@ -1529,3 +1839,428 @@ pub async fn rate_limiter_thread(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use test_data_store::test_repo_storage::models::{
+        ConsoleSourceChangeDispatcherDefinition, LsnOrderValidatorSourceChangeDispatcherDefinition,
+    };
+
+    use crate::sources::source_change_dispatchers::{
+        lsn_order_validator_dispatcher::LsnOrderValidatorSourceChangeDispatcher,
+        test_support::{FailingDispatcher, RecordingDispatcher},
+    };
+
+    fn source_change_record(lsn: u64, offset_ns: u64) -> serde_json::Value {
+        json!({
+            "kind": "SourceChange",
+            "offset_ns": offset_ns,
+            "source_change_event": {
+                "op": "i",
+                "reactivatorStart_ns": 0,
+                "reactivatorEnd_ns": 0,
+                "payload": {
+                    "source": { "db": "source-001", "lsn": lsn, "table": "node", "ts_ns": 0 },
+                    "before": null,
+                    "after": {
+                        "id": format!("room_{:02}", lsn),
+                        "labels": ["Room"],
+                        "properties": {}
+                    }
+                }
+            }
+        })
+    }
+
+    // Writes a change script with 5 SourceChange records, with lsns 0 to 4, and returns the
+    // arguments for creating a generator that plays it.
+    async fn test_generator_args(
+        dir: &std::path::Path,
+        start_offset: Option<u64>,
+    ) -> anyhow::Result<(
+        TestRunSourceId,
+        ScriptSourceChangeGeneratorDefinition,
+        TestSourceStorage,
+        TestRunSourceStorage,
+    )> {
+        let scripts_path = dir.join("input").join("source_change_scripts");
+        tokio::fs::create_dir_all(&scripts_path).await?;
+
+        let mut records = vec![json!({ "kind": "Header", "start_time": "2024-01-01T00:00:00Z" })];
+        records.push(json!({ "kind": "Label", "offset_ns": 0, "label": "start" }));
+        for lsn in 0..5 {
+            records.push(source_change_record(lsn, lsn * 1000));
+        }
+        records.push(json!({ "kind": "Finish", "offset_ns": 5000 }));
+        let script: Vec<String> = records.iter().map(|r| r.to_string()).collect();
+        tokio::fs::write(scripts_path.join("changes_00001.jsonl"), script.join("\n")).await?;
+
+        let generator_definition = json!({
+            "kind": "Script",
+            "script_file_folder": "source_change_scripts",
+            "spacing_mode": "none",
+            "start_offset": start_offset
+        });
+        let definition: ScriptSourceChangeGeneratorDefinition =
+            serde_json::from_value(generator_definition.clone())?;
+
+        let id = TestRunSourceId::try_from("test-repo.test-001.run-001.source-001")?;
+        let input_storage = TestSourceStorage {
+            id: "source-001".to_string(),
+            path: dir.join("input"),
+            repo_id: "test-repo".to_string(),
+            test_id: "test-001".to_string(),
+            test_source_definition: serde_json::from_value(json!({
+                "kind": "Script",
+                "test_source_id": "source-001",
+                "source_change_generator": generator_definition
+            }))?,
+        };
+        let output_storage = TestRunSourceStorage {
+            id: id.clone(),
+            path: dir.join("output"),
+            source_change_path: dir.join("output").join("source_change_dispatchers"),
+        };
+
+        Ok((id, definition, input_storage, output_storage))
+    }
+
+    async fn test_settings(
+        dir: &std::path::Path,
+        start_offset: Option<u64>,
+    ) -> anyhow::Result<ScriptSourceChangeGeneratorSettings> {
+        let (id, definition, input_storage, output_storage) =
+            test_generator_args(dir, start_offset).await?;
+        ScriptSourceChangeGeneratorSettings::new(
+            id,
+            definition,
+            input_storage,
+            output_storage,
+            Vec::new(),
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_start_offset() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), Some(2)).await.unwrap();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = RecordingDispatcher::new();
+        state.dispatchers = vec![Box::new(dispatcher.clone())];
+
+        let start = ScriptSourceChangeGeneratorCommand::Start;
+        state.transition_from_paused_state(&start).await.unwrap();
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Running);
+        assert_eq!(state.stats.num_skipped_source_change_records, 2);
+
+        while state.status == SourceChangeGeneratorStatus::Running {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.process_change_stream_message(message).await.unwrap();
+        }
+
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(dispatcher.lsns(), vec![2, 3, 4]);
+        assert_eq!(state.stats.num_source_change_records, 5);
+    }
+
+    #[tokio::test]
+    async fn test_rebased_time_mode_forms() {
+        let mut virtual_time_ns_starts = Vec::new();
+        for time_mode in [
+            json!("2024-01-01T00:00:00Z"),
+            json!(1_704_067_200_000_000_000u64),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let mut settings = test_settings(dir.path(), None).await.unwrap();
+            settings.time_mode = serde_json::from_value(time_mode).unwrap();
+            let (mut state, _change_rx_channel) =
+                ScriptSourceChangeGeneratorInternalState::initialize(
+                    settings,
+                    SourceChangeEventFeed::default(),
+                )
+                .await
+                .unwrap();
+
+            let start = ScriptSourceChangeGeneratorCommand::Start;
+            state.transition_from_paused_state(&start).await.unwrap();
+            virtual_time_ns_starts.push(state.virtual_time_ns_start);
+        }
+
+        assert_eq!(
+            virtual_time_ns_starts,
+            vec![1_704_067_200_000_000_000, 1_704_067_200_000_000_000]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_offset_beyond_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let (id, definition, input_storage, output_storage) =
+            test_generator_args(dir.path(), Some(5)).await.unwrap();
+
+        // The generator is rejected when it is created, rather than failing once it is running.
+        let err = ScriptSourceChangeGenerator::new(
+            id,
+            definition,
+            input_storage,
+            output_storage,
+            Vec::new(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("start_offset 5 must be less than the 5 SourceChange records"));
+    }
+
+    // Runs the generator until it leaves the Running status, returning the new status.
+    async fn run_until_not_running(
+        state: &mut ScriptSourceChangeGeneratorInternalState,
+        change_rx_channel: &mut Receiver<ScheduledChangeScriptRecordMessage>,
+    ) -> SourceChangeGeneratorStatus {
+        state
+            .transition_from_paused_state(&ScriptSourceChangeGeneratorCommand::Start)
+            .await
+            .unwrap();
+        while state.status == SourceChangeGeneratorStatus::Running {
+            let message = change_rx_channel.recv().await.unwrap();
+            state.handle_change_stream_message(message).await;
+        }
+        state.status
+    }
+
+    #[tokio::test]
+    async fn test_auto_pause_after() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path(), None).await.unwrap();
+        settings.auto_pause_after = Some(2);
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = RecordingDispatcher::new();
+        state.dispatchers = vec![Box::new(dispatcher.clone())];
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Paused);
+        assert_eq!(dispatcher.lsns(), vec![0, 1]);
+        assert_eq!(state.auto_pause_remaining, None);
+
+        // Without auto_pause_repeat, the next Start runs to the end.
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(dispatcher.lsns(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_auto_pause_after_repeat() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut settings = test_settings(dir.path(), None).await.unwrap();
+        settings.auto_pause_after = Some(2);
+        settings.auto_pause_repeat = true;
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = RecordingDispatcher::new();
+        state.dispatchers = vec![Box::new(dispatcher.clone())];
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Paused);
+        assert_eq!(dispatcher.lsns(), vec![0, 1]);
+        assert_eq!(state.auto_pause_remaining, Some(2));
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Paused);
+        assert_eq!(dispatcher.lsns(), vec![0, 1, 2, 3]);
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(dispatcher.lsns(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_event_feed() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        let event_feed = SourceChangeEventFeed::default();
+        let mut events_rx = event_feed.subscribe();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(settings, event_feed)
+                .await
+                .unwrap();
+
+        // The feed is one of the generator's dispatchers, so it sees exactly what they dispatch.
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Finished);
+
+        let mut seqs = Vec::new();
+        while let Ok(summary) = events_rx.try_recv() {
+            assert_eq!(summary.op, "i");
+            seqs.push(summary.seq);
+        }
+        assert_eq!(seqs, vec![0, 1, 2, 3, 4]);
+    }
+
+    fn status_reason(state: &mut ScriptSourceChangeGeneratorInternalState) -> String {
+        ScriptSourceChangeGeneratorExternalState::from(state).status_reason
+    }
+
+    #[tokio::test]
+    async fn test_status_reason() {
+        // Finished at the end of the script.
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(status_reason(&mut state), "paused: initialized");
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Finished);
+        assert_eq!(
+            status_reason(&mut state),
+            "finished: reached the end of the change script"
+        );
+
+        // Stopped by a command.
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        let (mut state, _change_rx_channel) = ScriptSourceChangeGeneratorInternalState::initialize(
+            settings,
+            SourceChangeEventFeed::default(),
+        )
+        .await
+        .unwrap();
+
+        state
+            .transition_from_paused_state(&ScriptSourceChangeGeneratorCommand::Stop)
+            .await
+            .unwrap();
+        assert_eq!(state.status, SourceChangeGeneratorStatus::Stopped);
+        assert_eq!(status_reason(&mut state), "stopped by Stop command");
+
+        // An error, here from a Header record in the middle of the script.
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        let header = json!({ "kind": "Header", "start_time": "2024-01-01T00:00:00Z" });
+        let finish = json!({ "kind": "Finish", "offset_ns": 0 });
+        tokio::fs::write(
+            dir.path()
+                .join("input")
+                .join("source_change_scripts")
+                .join("changes_00001.jsonl"),
+            format!("{}\n{}\n{}", header, header, finish),
+        )
+        .await
+        .unwrap();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Error);
+        assert!(status_reason(&mut state).starts_with("error: Unexpected Change Script Header"));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = RecordingDispatcher::new();
+        state.dispatchers = vec![Box::new(dispatcher.clone()), Box::new(FailingDispatcher)];
+
+        // The other dispatchers still get the events the failing one didn't accept.
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Error);
+        assert_eq!(dispatcher.lsns(), vec![0]);
+        assert!(status_reason(&mut state)
+            .starts_with("error: Error calling process_change_stream_message"));
+    }
+
+    #[tokio::test]
+    async fn test_lsn_order_violation_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = test_settings(dir.path(), None).await.unwrap();
+        // Rewrite the script so the third change goes back to an earlier lsn.
+        let records = [
+            json!({ "kind": "Header", "start_time": "2024-01-01T00:00:00Z" }),
+            source_change_record(0, 0),
+            source_change_record(2, 1000),
+            source_change_record(1, 2000),
+            json!({ "kind": "Finish", "offset_ns": 3000 }),
+        ];
+        let script: Vec<String> = records.iter().map(|r| r.to_string()).collect();
+        tokio::fs::write(
+            dir.path()
+                .join("input")
+                .join("source_change_scripts")
+                .join("changes_00001.jsonl"),
+            script.join("\n"),
+        )
+        .await
+        .unwrap();
+        let (mut state, mut change_rx_channel) =
+            ScriptSourceChangeGeneratorInternalState::initialize(
+                settings,
+                SourceChangeEventFeed::default(),
+            )
+            .await
+            .unwrap();
+
+        let dispatcher = RecordingDispatcher::new();
+        let definition = LsnOrderValidatorSourceChangeDispatcherDefinition {
+            dispatcher: Box::new(SourceChangeDispatcherDefinition::Console(
+                ConsoleSourceChangeDispatcherDefinition {
+                    date_time_format: None,
+                },
+            )),
+            error_on_violation: Some(true),
+        };
+        state.dispatchers = vec![Box::new(LsnOrderValidatorSourceChangeDispatcher::new(
+            &definition,
+            Box::new(dispatcher.clone()),
+            &state.settings.output_storage,
+        ))];
+
+        let status = run_until_not_running(&mut state, &mut change_rx_channel).await;
+        assert_eq!(status, SourceChangeGeneratorStatus::Error);
+        assert_eq!(dispatcher.lsns(), vec![0, 2]);
+        assert!(status_reason(&mut state).contains("LSN order violation"));
+    }
+}
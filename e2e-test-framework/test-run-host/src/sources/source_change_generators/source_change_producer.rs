@@ -0,0 +1,1043 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    num::NonZeroU32,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use time::{format_description, OffsetDateTime};
+use tokio::{
+    sync::{broadcast, mpsc::Receiver, mpsc::Sender, oneshot, Mutex},
+    task::JoinHandle,
+    time::{sleep_until, Instant},
+};
+
+use test_data_store::{
+    scripts::SourceChangeEvent,
+    test_repo_storage::models::{
+        CommonSourceChangeGeneratorDefinition, SourceChangeDispatcherDefinition,
+        SourceChangeEventSchemaDefinition, SpacingMode, TimeMode,
+    },
+    test_run_storage::{TestRunSourceId, TestRunSourceStorage},
+};
+
+use crate::sources::source_change_dispatchers::{
+    event_field_names::EventFieldNames, SourceChangeDispatcher,
+};
+
+use super::{
+    create_source_change_dispatchers,
+    event_schema_validator::{SourceChangeEventValidationStats, SourceChangeEventValidator},
+    MaxEpsLimiter, SourceChangeEventFeed, SourceChangeEventSummary, SourceChangeGenerator,
+    SourceChangeGeneratorCommandResponse, SourceChangeGeneratorDescription,
+    SourceChangeGeneratorState, SourceChangeGeneratorStatus,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourceChangeProducerError {
+    #[error("{0} is already finished. Reset to start over.")]
+    AlreadyFinished(&'static str),
+    #[error("{0} is already stopped. Reset to start over.")]
+    AlreadyStopped(&'static str),
+    #[error("{0} is currently Skipping. {1} skips remaining. Pause before Skip, Step, or Reset.")]
+    CurrentlySkipping(&'static str, u64),
+    #[error("{0} is currently Stepping. {1} steps remaining. Pause before Skip, Step, or Reset.")]
+    CurrentlyStepping(&'static str, u64),
+    #[error("{0} is currently in an Error state - {1:?}")]
+    Error(&'static str, SourceChangeGeneratorStatus),
+    #[error("{0} is currently Running. Pause before trying to Skip.")]
+    PauseToSkip(&'static str),
+    #[error("{0} is currently Running. Pause before trying to Step.")]
+    PauseToStep(&'static str),
+    #[error("{0} is currently Running. Pause before trying to Reset.")]
+    PauseToReset(&'static str),
+}
+
+// The settings every generator built on a SourceChangeProducer needs to dispatch its changes.
+#[derive(Clone, Debug, Serialize)]
+pub struct SourceChangeGeneratorCommonSettings {
+    pub dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    pub event_field_names: EventFieldNames,
+    pub event_schema: Option<SourceChangeEventSchemaDefinition>,
+    pub id: TestRunSourceId,
+    pub max_eps: Option<NonZeroU32>,
+    pub output_storage: TestRunSourceStorage,
+    pub time_mode: TimeMode,
+}
+
+impl SourceChangeGeneratorCommonSettings {
+    pub fn new(
+        test_run_source_id: TestRunSourceId,
+        definition: &CommonSourceChangeGeneratorDefinition,
+        output_storage: TestRunSourceStorage,
+        dispatchers: Vec<SourceChangeDispatcherDefinition>,
+    ) -> Self {
+        Self {
+            dispatchers,
+            event_field_names: EventFieldNames::new(definition.event_field_names.clone()),
+            event_schema: definition.event_schema.clone(),
+            id: test_run_source_id,
+            max_eps: definition.max_eps,
+            output_storage,
+            time_mode: definition.time_mode.clone(),
+        }
+    }
+}
+
+pub fn now_ns() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64
+}
+
+// The changes from one call to SourceChangeProducer::produce.
+#[derive(Debug, Default)]
+pub struct ProducedChanges {
+    pub events: Vec<SourceChangeEvent>,
+    // How much of the current Step or Skip the changes use up, e.g. one per record or poll.
+    pub steps: u64,
+    // Set once the producer has no more changes, which finishes the generator.
+    pub finished: bool,
+}
+
+// Produces the SourceChangeEvents of a SourceChangeProducerGenerator. The commands, status
+// transitions, dispatching and result summary are the same for every generator, so a
+// producer only decides where its changes come from and when the next ones are due.
+#[async_trait]
+pub trait SourceChangeProducer: Debug + Send + Sized + 'static {
+    type Settings: Clone + Debug + Send + Sync + 'static;
+    // The producer's fields in the generator's external state.
+    type State: Debug + Send + Serialize;
+    // The producer's stats, reported alongside the generator's stats.
+    type Stats: Clone + Debug + Send + Serialize;
+
+    // The name of the generator, used in errors and log messages.
+    const NAME: &'static str;
+    // What a Step or Skip counts, e.g. "records" or "polls".
+    const STEP_UNIT: &'static str;
+
+    fn common_settings(settings: &Self::Settings) -> &SourceChangeGeneratorCommonSettings;
+    fn describe(settings: &Self::Settings) -> SourceChangeGeneratorDescription;
+    async fn initialize(settings: Self::Settings) -> anyhow::Result<Self>;
+
+    fn settings(&self) -> &Self::Settings;
+    // The setting the changes are read from and its value, e.g. ("csv_file", "rooms.csv").
+    fn input(&self) -> (&'static str, String);
+    fn state(&self) -> Self::State;
+    fn stats(&self) -> Self::Stats;
+
+    // Called with the actual start time when the generator is first started, stepped or skipped.
+    fn start(&mut self, _start_time_ns: u64) {}
+    // Returns the producer to the start of its changes.
+    fn reset(&mut self);
+    // When the next changes are due. spacing_mode overrides the configured spacing for the
+    // current Step or Skip, and started_at is when the previous changes started being
+    // produced, or None if processing is only now starting.
+    fn next_change_at(
+        &mut self,
+        spacing_mode: Option<SpacingMode>,
+        started_at: Option<Instant>,
+    ) -> Instant;
+    // Produces the next changes. Skipped changes aren't dispatched, so while skipping a
+    // producer can leave out events it would only build to dispatch. limit is the number of
+    // steps or skips remaining, if any.
+    async fn produce(
+        &mut self,
+        skipping: bool,
+        limit: Option<u64>,
+    ) -> anyhow::Result<ProducedChanges>;
+    // The status_reason given when the producer runs out of changes.
+    fn finished_reason(&self) -> String {
+        "finished: no more changes".to_string()
+    }
+}
+
+// Enum of commands sent to a SourceChangeProducerGenerator from Web API handler functions.
+#[derive(Debug)]
+pub enum SourceChangeProducerCommand {
+    // Command to get the current state of the generator.
+    GetState,
+    // Command to pause the generator.
+    Pause,
+    // Command to reset the generator to the start of its changes.
+    Reset,
+    // Command to skip the generator forward a specified number of steps without dispatching.
+    Skip {
+        skips: u64,
+        spacing_mode: Option<SpacingMode>,
+    },
+    // Command to start the generator.
+    Start,
+    // Command to step the generator forward a specified number of steps.
+    Step {
+        steps: u64,
+        spacing_mode: Option<SpacingMode>,
+    },
+    // Command to stop the generator.
+    Stop,
+}
+
+// Struct for messages sent to a SourceChangeProducerGenerator from the functions in the Web API.
+#[derive(Debug)]
+pub struct SourceChangeProducerMessage<P: SourceChangeProducer> {
+    // Command sent to the generator.
+    pub command: SourceChangeProducerCommand,
+    // One-shot channel for the generator to send a response back to the caller.
+    pub response_tx: Option<oneshot::Sender<SourceChangeProducerMessageResponse<P>>>,
+}
+
+// A struct for the Response sent back from a SourceChangeProducerGenerator to the calling Web API handler.
+#[derive(Debug)]
+pub struct SourceChangeProducerMessageResponse<P: SourceChangeProducer> {
+    // Result of the command.
+    pub result: anyhow::Result<()>,
+    // State of the generator after the command.
+    pub state: SourceChangeProducerExternalState<P>,
+}
+
+// A SourceChangeGenerator whose changes come from a SourceChangeProducer running on its own
+// processor thread.
+pub struct SourceChangeProducerGenerator<P: SourceChangeProducer> {
+    settings: P::Settings,
+    event_feed: SourceChangeEventFeed,
+    processor_tx_channel: Sender<SourceChangeProducerMessage<P>>,
+    _processor_thread_handle: Arc<Mutex<JoinHandle<anyhow::Result<()>>>>,
+}
+
+impl<P: SourceChangeProducer> SourceChangeProducerGenerator<P> {
+    pub fn spawn(settings: P::Settings) -> Self {
+        log::debug!("Creating {} from {:?}", P::NAME, &settings);
+
+        let event_feed = SourceChangeEventFeed::default();
+        let (processor_tx_channel, processor_rx_channel) = tokio::sync::mpsc::channel(100);
+        let processor_thread_handle = tokio::spawn(producer_processor_thread::<P>(
+            processor_rx_channel,
+            settings.clone(),
+            event_feed.clone(),
+        ));
+
+        Self {
+            settings,
+            event_feed,
+            processor_tx_channel,
+            _processor_thread_handle: Arc::new(Mutex::new(processor_thread_handle)),
+        }
+    }
+
+    pub fn get_id(&self) -> TestRunSourceId {
+        P::common_settings(&self.settings).id.clone()
+    }
+
+    pub fn get_settings(&self) -> P::Settings {
+        self.settings.clone()
+    }
+
+    async fn send_command(
+        &self,
+        command: SourceChangeProducerCommand,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        let r = self
+            .processor_tx_channel
+            .send(SourceChangeProducerMessage {
+                command,
+                response_tx: Some(response_tx),
+            })
+            .await;
+
+        match r {
+            Ok(_) => {
+                let response = response_rx.await?;
+
+                Ok(SourceChangeGeneratorCommandResponse {
+                    result: response.result,
+                    state: SourceChangeGeneratorState {
+                        event_count: response.state.stats.generator.num_source_change_events,
+                        status: response.state.status,
+                        state: serde_json::to_value(response.state).unwrap(),
+                    },
+                })
+            }
+            Err(e) => anyhow::bail!("Error sending command to {}: {:?}", P::NAME, e),
+        }
+    }
+}
+
+impl<P: SourceChangeProducer> Debug for SourceChangeProducerGenerator<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct(P::NAME)
+            .field("settings", &self.settings)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<P: SourceChangeProducer> SourceChangeGenerator for SourceChangeProducerGenerator<P> {
+    fn describe(&self) -> SourceChangeGeneratorDescription {
+        P::describe(&self.settings)
+    }
+
+    async fn get_state(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::GetState)
+            .await
+    }
+
+    async fn pause(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::Pause).await
+    }
+
+    async fn reset(
+        &self,
+        _seed: Option<u64>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        // Changes aren't randomized, so there is nothing to seed.
+        self.send_command(SourceChangeProducerCommand::Reset).await
+    }
+
+    async fn skip(
+        &self,
+        skips: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::Skip {
+            skips,
+            spacing_mode,
+        })
+        .await
+    }
+
+    async fn start(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::Start).await
+    }
+
+    async fn step(
+        &self,
+        steps: u64,
+        spacing_mode: Option<SpacingMode>,
+    ) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::Step {
+            steps,
+            spacing_mode,
+        })
+        .await
+    }
+
+    async fn stop(&self) -> anyhow::Result<SourceChangeGeneratorCommandResponse> {
+        self.send_command(SourceChangeProducerCommand::Stop).await
+    }
+
+    fn subscribe_events(&self) -> anyhow::Result<broadcast::Receiver<SourceChangeEventSummary>> {
+        Ok(self.event_feed.subscribe())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SourceChangeProducerStats {
+    pub actual_start_time_ns: u64,
+    pub actual_end_time_ns: u64,
+    pub num_source_change_events: u64,
+    pub num_skipped_source_change_events: u64,
+}
+
+// The generator's stats followed by the producer's own, reported as a single stats object.
+#[derive(Debug, Serialize)]
+pub struct SourceChangeProducerExternalStats<S> {
+    #[serde(flatten)]
+    pub generator: SourceChangeProducerStats,
+    #[serde(flatten)]
+    pub producer: S,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceChangeProducerExternalState<P: SourceChangeProducer> {
+    pub error_messages: Vec<String>,
+    pub event_validation: Option<SourceChangeEventValidationStats>,
+    #[serde(flatten)]
+    pub input: Map<String, Value>,
+    pub skips_remaining: u64,
+    pub stats: SourceChangeProducerExternalStats<P::Stats>,
+    pub status: SourceChangeGeneratorStatus,
+    pub status_reason: String,
+    pub steps_remaining: u64,
+    pub test_run_source_id: TestRunSourceId,
+    pub time_mode: TimeMode,
+    #[serde(flatten)]
+    pub producer: P::State,
+}
+
+impl<P: SourceChangeProducer> From<&mut SourceChangeProducerInternalState<P>>
+    for SourceChangeProducerExternalState<P>
+{
+    fn from(state: &mut SourceChangeProducerInternalState<P>) -> Self {
+        let settings = state.common_settings();
+
+        Self {
+            error_messages: state.error_messages.clone(),
+            event_validation: state.event_validator.stats(),
+            input: state.input(),
+            skips_remaining: state.skips_remaining,
+            stats: SourceChangeProducerExternalStats {
+                generator: state.stats.clone(),
+                producer: state.producer.stats(),
+            },
+            status: state.status,
+            status_reason: state.status_reason.clone(),
+            steps_remaining: state.steps_remaining,
+            test_run_source_id: settings.id.clone(),
+            time_mode: settings.time_mode.clone(),
+            producer: state.producer.state(),
+        }
+    }
+}
+
+pub struct SourceChangeProducerInternalState<P: SourceChangeProducer> {
+    pub dispatchers: Vec<Box<dyn SourceChangeDispatcher + Send>>,
+    pub error_messages: Vec<String>,
+    pub event_feed: SourceChangeEventFeed,
+    pub event_validator: SourceChangeEventValidator,
+    pub max_eps_limiter: MaxEpsLimiter,
+    pub next_change_at: Instant,
+    pub producer: P,
+    pub skips_remaining: u64,
+    pub skips_spacing_mode: Option<SpacingMode>,
+    pub stats: SourceChangeProducerStats,
+    pub status: SourceChangeGeneratorStatus,
+    // Why the generator is in its current status, e.g. "paused by Pause command".
+    pub status_reason: String,
+    pub steps_remaining: u64,
+    pub steps_spacing_mode: Option<SpacingMode>,
+}
+
+impl<P: SourceChangeProducer> SourceChangeProducerInternalState<P> {
+    pub(crate) async fn initialize(
+        settings: P::Settings,
+        event_feed: SourceChangeEventFeed,
+    ) -> anyhow::Result<Self> {
+        log::debug!("Initializing {} using {:?}", P::NAME, settings);
+
+        let common_settings = P::common_settings(&settings);
+        let event_validator =
+            SourceChangeEventValidator::new(common_settings.event_schema.as_ref())?;
+        let dispatchers = create_dispatchers(common_settings, &event_feed).await?;
+        let max_eps_limiter = MaxEpsLimiter::new(common_settings.max_eps);
+
+        Ok(Self {
+            dispatchers,
+            error_messages: Vec::new(),
+            event_feed,
+            event_validator,
+            max_eps_limiter,
+            next_change_at: Instant::now(),
+            producer: P::initialize(settings).await?,
+            skips_remaining: 0,
+            skips_spacing_mode: None,
+            stats: SourceChangeProducerStats::default(),
+            status: SourceChangeGeneratorStatus::Paused,
+            status_reason: "paused: initialized".to_string(),
+            steps_remaining: 0,
+            steps_spacing_mode: None,
+        })
+    }
+
+    fn common_settings(&self) -> &SourceChangeGeneratorCommonSettings {
+        P::common_settings(self.producer.settings())
+    }
+
+    fn input(&self) -> Map<String, Value> {
+        let (name, value) = self.producer.input();
+        let mut input = Map::new();
+        input.insert(name.to_string(), Value::from(value));
+        input
+    }
+
+    // Closes every dispatcher, returning the first error if any of them failed to close.
+    async fn close_dispatchers(&mut self) -> anyhow::Result<()> {
+        let dispatchers = &mut self.dispatchers;
+
+        log::debug!("Closing dispatchers - #dispatchers:{}", dispatchers.len());
+
+        let futures: Vec<_> = dispatchers
+            .iter_mut()
+            .map(|dispatcher| async move { dispatcher.close().await })
+            .collect();
+
+        join_all(futures).await.into_iter().collect()
+    }
+
+    async fn dispatch_source_change_events(
+        &mut self,
+        events: Vec<&SourceChangeEvent>,
+    ) -> anyhow::Result<()> {
+        // Validate the events against the event_schema before any are dispatched.
+        self.event_validator.validate(&events)?;
+
+        // Enforce the max_eps cap.
+        self.max_eps_limiter.until_ready(events.len()).await;
+
+        let dispatchers = &mut self.dispatchers;
+
+        log::debug!(
+            "Dispatching SourceChangeEvents - #dispatchers:{}, #events:{}",
+            dispatchers.len(),
+            events.len()
+        );
+
+        // Every dispatcher is given the events even if another fails; the first error is
+        // returned so the generator enters its error state.
+        let futures: Vec<_> = dispatchers
+            .iter_mut()
+            .map(|dispatcher| {
+                let events = events.clone();
+                async move { dispatcher.dispatch_source_change_events(events).await }
+            })
+            .collect();
+
+        join_all(futures).await.into_iter().collect()
+    }
+
+    // Sets the status along with the reason reported for it in the external state.
+    fn set_status(&mut self, status: SourceChangeGeneratorStatus, reason: impl Into<String>) {
+        self.status = status;
+        self.status_reason = reason.into();
+    }
+
+    // Function to log the Generator State at varying levels of detail.
+    fn log_state(&self, msg: &str) {
+        match log::max_level() {
+            log::LevelFilter::Trace => log::trace!("{} - {:#?}", msg, self),
+            log::LevelFilter::Debug => log::debug!("{} - {:?}", msg, self),
+            _ => {}
+        }
+    }
+
+    // The SpacingMode given with the current Step or Skip, if any. Skips are not spaced by
+    // default.
+    fn spacing_mode_override(&self) -> Option<SpacingMode> {
+        match self.status {
+            SourceChangeGeneratorStatus::Skipping => {
+                Some(self.skips_spacing_mode.clone().unwrap_or(SpacingMode::None))
+            }
+            SourceChangeGeneratorStatus::Stepping => self.steps_spacing_mode.clone(),
+            _ => None,
+        }
+    }
+
+    fn schedule_next_change(&mut self, started_at: Option<Instant>) {
+        let spacing_mode = self.spacing_mode_override();
+        self.next_change_at = self.producer.next_change_at(spacing_mode, started_at);
+    }
+
+    // Produces the next changes and, unless skipping, dispatches them.
+    pub(crate) async fn process_next_change(&mut self) {
+        let started_at = Instant::now();
+        let skipping = self.status == SourceChangeGeneratorStatus::Skipping;
+        let limit = match self.status {
+            SourceChangeGeneratorStatus::Stepping => Some(self.steps_remaining),
+            SourceChangeGeneratorStatus::Skipping => Some(self.skips_remaining),
+            _ => None,
+        };
+
+        let produced = match self.producer.produce(skipping, limit).await {
+            Ok(produced) => produced,
+            Err(e) => {
+                self.transition_to_error_state("Error producing SourceChangeEvents", Some(&e));
+                return;
+            }
+        };
+
+        let event_count = produced.events.len() as u64;
+        if skipping {
+            self.stats.num_skipped_source_change_events += event_count;
+        } else if event_count > 0 {
+            if let Err(e) = self
+                .dispatch_source_change_events(produced.events.iter().collect())
+                .await
+            {
+                self.transition_to_error_state("Error dispatching SourceChangeEvents", Some(&e));
+                return;
+            }
+            self.stats.num_source_change_events += event_count;
+        }
+
+        match self.status {
+            SourceChangeGeneratorStatus::Stepping => {
+                self.steps_remaining = self.steps_remaining.saturating_sub(produced.steps);
+                if self.steps_remaining == 0 {
+                    self.set_status(
+                        SourceChangeGeneratorStatus::Paused,
+                        "paused: completed all steps",
+                    );
+                }
+            }
+            SourceChangeGeneratorStatus::Skipping => {
+                self.skips_remaining = self.skips_remaining.saturating_sub(produced.steps);
+                if self.skips_remaining == 0 {
+                    self.set_status(
+                        SourceChangeGeneratorStatus::Paused,
+                        "paused: completed all skips",
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        if produced.finished {
+            self.transition_to_finished_state().await;
+        } else {
+            self.schedule_next_change(Some(started_at));
+        }
+    }
+
+    async fn process_command_message(
+        &mut self,
+        message: SourceChangeProducerMessage<P>,
+    ) -> anyhow::Result<()> {
+        log::debug!("Received command message: {:?}", message.command);
+
+        let result = match message.command {
+            SourceChangeProducerCommand::GetState => Ok(()),
+            _ => self.transition(&message.command).await,
+        };
+
+        if let Some(response_tx) = message.response_tx {
+            let message_response = SourceChangeProducerMessageResponse {
+                result,
+                state: self.into(),
+            };
+
+            if let Err(e) = response_tx.send(message_response) {
+                anyhow::bail!("Error sending message response back to caller: {:?}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reset(&mut self) -> anyhow::Result<()> {
+        // The dispatchers are replaced below, so a failure to close the old ones (e.g. after
+        // the error that is being reset) shouldn't prevent the reset.
+        if let Err(e) = self.close_dispatchers().await {
+            log::warn!("Error closing dispatchers during reset: {:?}", e);
+        }
+        self.dispatchers = create_dispatchers(self.common_settings(), &self.event_feed).await?;
+
+        // These fields do not get reset:
+        //   state.max_eps_limiter
+        self.error_messages = Vec::new();
+        self.event_validator.reset();
+        self.next_change_at = Instant::now();
+        self.producer.reset();
+        self.skips_remaining = 0;
+        self.skips_spacing_mode = None;
+        self.stats = SourceChangeProducerStats::default();
+        self.set_status(SourceChangeGeneratorStatus::Paused, "paused: reset");
+        self.steps_remaining = 0;
+        self.steps_spacing_mode = None;
+
+        Ok(())
+    }
+
+    pub(crate) async fn transition(
+        &mut self,
+        command: &SourceChangeProducerCommand,
+    ) -> anyhow::Result<()> {
+        log::debug!(
+            "Transitioning from {:?} state via command: {:?}",
+            self.status,
+            command
+        );
+
+        match self.status {
+            SourceChangeGeneratorStatus::Running => match command {
+                SourceChangeProducerCommand::Pause => {
+                    self.set_status(
+                        SourceChangeGeneratorStatus::Paused,
+                        "paused by Pause command",
+                    );
+                    Ok(())
+                }
+                SourceChangeProducerCommand::Reset => {
+                    Err(SourceChangeProducerError::PauseToReset(P::NAME).into())
+                }
+                SourceChangeProducerCommand::Skip { .. } => {
+                    Err(SourceChangeProducerError::PauseToSkip(P::NAME).into())
+                }
+                SourceChangeProducerCommand::Step { .. } => {
+                    Err(SourceChangeProducerError::PauseToStep(P::NAME).into())
+                }
+                SourceChangeProducerCommand::Stop => {
+                    self.transition_to_stopped_state().await;
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            SourceChangeGeneratorStatus::Stepping | SourceChangeGeneratorStatus::Skipping => {
+                match command {
+                    SourceChangeProducerCommand::Pause => {
+                        self.set_status(
+                            SourceChangeGeneratorStatus::Paused,
+                            "paused by Pause command",
+                        );
+                        self.skips_remaining = 0;
+                        self.steps_remaining = 0;
+                        Ok(())
+                    }
+                    SourceChangeProducerCommand::Stop => {
+                        self.transition_to_stopped_state().await;
+                        Ok(())
+                    }
+                    _ if self.status == SourceChangeGeneratorStatus::Stepping => Err(
+                        SourceChangeProducerError::CurrentlyStepping(P::NAME, self.steps_remaining)
+                            .into(),
+                    ),
+                    _ => Err(SourceChangeProducerError::CurrentlySkipping(
+                        P::NAME,
+                        self.skips_remaining,
+                    )
+                    .into()),
+                }
+            }
+            SourceChangeGeneratorStatus::Paused => {
+                if self.stats.actual_start_time_ns == 0
+                    && matches!(
+                        command,
+                        SourceChangeProducerCommand::Start
+                            | SourceChangeProducerCommand::Step { .. }
+                            | SourceChangeProducerCommand::Skip { .. }
+                    )
+                {
+                    self.stats.actual_start_time_ns = now_ns();
+                    self.producer.start(self.stats.actual_start_time_ns);
+                }
+
+                match command {
+                    SourceChangeProducerCommand::Reset => self.reset().await,
+                    SourceChangeProducerCommand::Skip {
+                        skips,
+                        spacing_mode,
+                    } => {
+                        if *skips > 0 {
+                            self.set_status(
+                                SourceChangeGeneratorStatus::Skipping,
+                                format!("skipping {} {} by Skip command", skips, P::STEP_UNIT),
+                            );
+                            self.skips_remaining = *skips;
+                            self.skips_spacing_mode = spacing_mode.clone();
+                            self.schedule_next_change(None);
+                        }
+                        Ok(())
+                    }
+                    SourceChangeProducerCommand::Start => {
+                        self.set_status(
+                            SourceChangeGeneratorStatus::Running,
+                            "started by Start command",
+                        );
+                        self.schedule_next_change(None);
+                        Ok(())
+                    }
+                    SourceChangeProducerCommand::Step {
+                        steps,
+                        spacing_mode,
+                    } => {
+                        if *steps > 0 {
+                            self.set_status(
+                                SourceChangeGeneratorStatus::Stepping,
+                                format!("stepping {} {} by Step command", steps, P::STEP_UNIT),
+                            );
+                            self.steps_remaining = *steps;
+                            self.steps_spacing_mode = spacing_mode.clone();
+                            self.schedule_next_change(None);
+                        }
+                        Ok(())
+                    }
+                    SourceChangeProducerCommand::Stop => {
+                        self.transition_to_stopped_state().await;
+                        Ok(())
+                    }
+                    _ => Ok(()),
+                }
+            }
+            SourceChangeGeneratorStatus::Stopped => match command {
+                SourceChangeProducerCommand::Reset => self.reset().await,
+                _ => Err(SourceChangeProducerError::AlreadyStopped(P::NAME).into()),
+            },
+            SourceChangeGeneratorStatus::Finished => match command {
+                SourceChangeProducerCommand::Reset => self.reset().await,
+                _ => Err(SourceChangeProducerError::AlreadyFinished(P::NAME).into()),
+            },
+            SourceChangeGeneratorStatus::Error => match command {
+                SourceChangeProducerCommand::Reset => self.reset().await,
+                _ => Err(SourceChangeProducerError::Error(P::NAME, self.status).into()),
+            },
+        }
+    }
+
+    async fn transition_to_finished_state(&mut self) {
+        log::info!(
+            "{} Finished for TestRunSource {}",
+            P::NAME,
+            self.common_settings().id
+        );
+
+        let reason = self.producer.finished_reason();
+        self.set_status(SourceChangeGeneratorStatus::Finished, reason);
+        self.stats.actual_end_time_ns = now_ns();
+        self.skips_remaining = 0;
+        self.steps_remaining = 0;
+
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
+        self.write_result_summary().await.ok();
+    }
+
+    async fn transition_to_stopped_state(&mut self) {
+        log::info!(
+            "{} Stopped for TestRunSource {}",
+            P::NAME,
+            self.common_settings().id
+        );
+
+        self.set_status(
+            SourceChangeGeneratorStatus::Stopped,
+            "stopped by Stop command",
+        );
+        self.stats.actual_end_time_ns = now_ns();
+        self.skips_remaining = 0;
+        self.steps_remaining = 0;
+
+        if let Err(e) = self.close_dispatchers().await {
+            self.transition_to_error_state("Error closing dispatchers", Some(&e));
+        }
+        self.write_result_summary().await.ok();
+    }
+
+    fn transition_to_error_state(&mut self, error_message: &str, error: Option<&anyhow::Error>) {
+        let msg = match error {
+            Some(e) => format!("{}: {:?}", error_message, e),
+            None => error_message.to_string(),
+        };
+
+        self.set_status(
+            SourceChangeGeneratorStatus::Error,
+            format!("error: {}", msg),
+        );
+
+        self.log_state(&msg);
+
+        self.error_messages.push(msg);
+    }
+
+    pub async fn write_result_summary(&mut self) -> anyhow::Result<()> {
+        let result_summary: SourceChangeProducerResultSummary<P::Stats> = self.into();
+        log::info!("Stats for TestRunSource:\n{:#?}", &result_summary);
+
+        let result_summary_value = serde_json::to_value(result_summary).unwrap();
+        match self
+            .common_settings()
+            .output_storage
+            .write_test_run_summary(&result_summary_value)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                log::error!("Error writing result summary to output storage: {:?}", e);
+                Err(e)
+            }
+        }
+    }
+}
+
+impl<P: SourceChangeProducer> Debug for SourceChangeProducerInternalState<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SourceChangeProducerInternalState")
+            .field("error_messages", &self.error_messages)
+            .field("event_validation", &self.event_validator.stats())
+            .field("producer", &self.producer)
+            .field("skips_remaining", &self.skips_remaining)
+            .field("stats", &self.stats)
+            .field("status", &self.status)
+            .field("status_reason", &self.status_reason)
+            .field("steps_remaining", &self.steps_remaining)
+            .finish()
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct SourceChangeProducerResultSummary<S> {
+    pub actual_start_time: String,
+    pub actual_start_time_ns: u64,
+    pub actual_end_time: String,
+    pub actual_end_time_ns: u64,
+    #[serde(flatten)]
+    pub input: Map<String, Value>,
+    pub run_duration_ns: u64,
+    pub run_duration_sec: f64,
+    pub num_source_change_events: u64,
+    pub num_skipped_source_events: u64,
+    pub processing_rate: f64,
+    #[serde(flatten)]
+    pub producer_stats: S,
+    pub status_reason: String,
+    pub test_run_source_id: String,
+}
+
+impl<P: SourceChangeProducer> From<&mut SourceChangeProducerInternalState<P>>
+    for SourceChangeProducerResultSummary<P::Stats>
+{
+    fn from(state: &mut SourceChangeProducerInternalState<P>) -> Self {
+        let run_duration_ns = state
+            .stats
+            .actual_end_time_ns
+            .saturating_sub(state.stats.actual_start_time_ns);
+        let run_duration_sec = run_duration_ns as f64 / 1_000_000_000.0;
+
+        Self {
+            actual_start_time: OffsetDateTime::from_unix_timestamp_nanos(
+                state.stats.actual_start_time_ns as i128,
+            )
+            .expect("Invalid timestamp")
+            .format(&format_description::well_known::Rfc3339)
+            .unwrap(),
+            actual_start_time_ns: state.stats.actual_start_time_ns,
+            actual_end_time: OffsetDateTime::from_unix_timestamp_nanos(
+                state.stats.actual_end_time_ns as i128,
+            )
+            .expect("Invalid timestamp")
+            .format(&format_description::well_known::Rfc3339)
+            .unwrap(),
+            actual_end_time_ns: state.stats.actual_end_time_ns,
+            input: state.input(),
+            run_duration_ns,
+            run_duration_sec,
+            num_source_change_events: state.stats.num_source_change_events,
+            num_skipped_source_events: state.stats.num_skipped_source_change_events,
+            processing_rate: state.stats.num_source_change_events as f64 / run_duration_sec,
+            producer_stats: state.producer.stats(),
+            status_reason: state.status_reason.clone(),
+            test_run_source_id: state.common_settings().id.to_string(),
+        }
+    }
+}
+
+impl<S: Debug> Debug for SourceChangeProducerResultSummary<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let start_time = format!(
+            "{} ({} ns)",
+            self.actual_start_time, self.actual_start_time_ns
+        );
+        let end_time = format!("{} ({} ns)", self.actual_end_time, self.actual_end_time_ns);
+        let run_duration = format!(
+            "{} sec ({} ns)",
+            self.run_duration_sec, self.run_duration_ns,
+        );
+        let source_change_events = format!(
+            "{} (skipped:{})",
+            self.num_source_change_events, self.num_skipped_source_events
+        );
+        let processing_rate = format!("{:.2} changes / sec", self.processing_rate);
+
+        f.debug_struct("SourceChangeProducerResultSummary")
+            .field("test_run_source_id", &self.test_run_source_id)
+            .field("input", &self.input)
+            .field("start_time", &start_time)
+            .field("end_time", &end_time)
+            .field("run_duration", &run_duration)
+            .field("source_change_events", &source_change_events)
+            .field("processing_rate", &processing_rate)
+            .field("producer_stats", &self.producer_stats)
+            .field("status_reason", &self.status_reason)
+            .finish()
+    }
+}
+
+async fn create_dispatchers(
+    settings: &SourceChangeGeneratorCommonSettings,
+    event_feed: &SourceChangeEventFeed,
+) -> anyhow::Result<Vec<Box<dyn SourceChangeDispatcher + Send>>> {
+    create_source_change_dispatchers(
+        &settings.dispatchers,
+        &settings.output_storage,
+        &settings.event_field_names,
+        event_feed,
+    )
+    .await
+}
+
+// Function that defines the operation of a SourceChangeProducerGenerator thread.
+// Commands from the Web API handler functions are always processed first; while the
+// generator is processing, the producer is asked for the next changes once they are due.
+pub async fn producer_processor_thread<P: SourceChangeProducer>(
+    mut command_rx_channel: Receiver<SourceChangeProducerMessage<P>>,
+    settings: P::Settings,
+    event_feed: SourceChangeEventFeed,
+) -> anyhow::Result<()> {
+    log::info!(
+        "{} processor thread started for TestRunSource {} ...",
+        P::NAME,
+        P::common_settings(&settings).id
+    );
+
+    // The generator always starts Paused at the start of its changes.
+    let mut state =
+        match SourceChangeProducerInternalState::<P>::initialize(settings, event_feed).await {
+            Ok(state) => state,
+            Err(e) => {
+                let msg = format!("Error initializing {}: {:?}", P::NAME, e);
+                log::error!("{}", msg);
+                anyhow::bail!(msg);
+            }
+        };
+
+    loop {
+        state.log_state("Top of processor loop");
+
+        tokio::select! {
+            biased;
+
+            command_message = command_rx_channel.recv() => {
+                match command_message {
+                    Some(command_message) => {
+                        state.process_command_message(command_message).await
+                            .inspect_err(|e| state.transition_to_error_state("Error calling process_command_message.", Some(e))).ok();
+                    }
+                    None => {
+                        state.transition_to_error_state("Command channel closed.", None);
+                        break;
+                    }
+                }
+            },
+
+            _ = sleep_until(state.next_change_at), if state.status.is_processing() => {
+                state.process_next_change().await;
+            },
+        }
+    }
+
+    log::info!(
+        "{} processor thread exiting for TestRunSource {} ...",
+        P::NAME,
+        state.common_settings().id
+    );
+    Ok(())
+}
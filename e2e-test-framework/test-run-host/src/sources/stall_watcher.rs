@@ -0,0 +1,281 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::{task::JoinHandle, time::Instant};
+
+use test_data_store::test_run_storage::TestRunSourceId;
+
+use super::source_change_generators::{SourceChangeGenerator, SourceChangeGeneratorStatus};
+
+// Upper bound on how often the watcher checks the generator, so short timeouts are
+// detected promptly without polling long timeouts more than necessary.
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, PartialEq)]
+enum StallCheck {
+    Progressing,
+    Stalled,
+    Recovered,
+    Unchanged,
+}
+
+// Tracks the event count of a generator across periodic snapshots. A Running generator
+// whose event count hasn't changed for the timeout is stalled. Time spent in any other
+// status doesn't count towards the timeout.
+#[derive(Debug)]
+struct StallDetector {
+    timeout: Duration,
+    last_event_count: Option<u64>,
+    last_progress: Instant,
+    stalled: bool,
+}
+
+impl StallDetector {
+    fn new(timeout: Duration, now: Instant) -> Self {
+        Self {
+            timeout,
+            last_event_count: None,
+            last_progress: now,
+            stalled: false,
+        }
+    }
+
+    // event_count is None if the generator's state couldn't be read in time, which
+    // is treated as a Running generator that made no progress.
+    fn observe(
+        &mut self,
+        status: SourceChangeGeneratorStatus,
+        event_count: Option<u64>,
+        now: Instant,
+    ) -> StallCheck {
+        if status != SourceChangeGeneratorStatus::Running {
+            self.last_progress = now;
+            return StallCheck::Unchanged;
+        }
+
+        if event_count.is_some() && event_count != self.last_event_count {
+            let first_snapshot = self.last_event_count.is_none();
+            self.last_event_count = event_count;
+            self.last_progress = now;
+
+            if self.stalled {
+                self.stalled = false;
+                return StallCheck::Recovered;
+            }
+            if !first_snapshot {
+                return StallCheck::Progressing;
+            }
+            return StallCheck::Unchanged;
+        }
+
+        if !self.stalled && now.duration_since(self.last_progress) >= self.timeout {
+            self.stalled = true;
+            return StallCheck::Stalled;
+        }
+
+        StallCheck::Unchanged
+    }
+}
+
+// Watches a running SourceChangeGenerator for stalls, e.g. a generator wedged on a full
+// channel or a blocked dispatcher, which stops producing events without reporting an
+// error. A stalled generator is flagged and, if fail_on_stall is set, stopped.
+// Like the AutoPauseTimer, the watcher is re-armed on every start and must be cancelled
+// when the generator is stopped or reset.
+#[derive(Debug)]
+pub struct StallWatcher {
+    fail_on_stall: bool,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    stall_timeout_ms: Option<u64>,
+    stalled: Arc<AtomicBool>,
+}
+
+impl StallWatcher {
+    pub fn new(stall_timeout_ms: Option<u64>, fail_on_stall: bool) -> Self {
+        Self {
+            fail_on_stall,
+            handle: Mutex::new(None),
+            stall_timeout_ms,
+            stalled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn fail_on_stall(&self) -> bool {
+        self.fail_on_stall
+    }
+
+    pub fn is_stalled(&self) -> bool {
+        self.stalled.load(Ordering::SeqCst)
+    }
+
+    // Does nothing if no stall_timeout_ms is configured.
+    pub fn arm<G>(&self, id: TestRunSourceId, generator: Arc<G>)
+    where
+        G: SourceChangeGenerator + ?Sized + 'static,
+    {
+        let Some(stall_timeout_ms) = self.stall_timeout_ms else {
+            return;
+        };
+
+        let timeout = Duration::from_millis(stall_timeout_ms.max(1));
+        let handle = tokio::spawn(watch_generator(
+            id,
+            generator,
+            timeout,
+            self.fail_on_stall,
+            self.stalled.clone(),
+        ));
+
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+    }
+
+    pub fn cancel(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    pub fn reset(&self) {
+        self.cancel();
+        self.stalled.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Drop for StallWatcher {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+async fn watch_generator<G>(
+    id: TestRunSourceId,
+    generator: Arc<G>,
+    timeout: Duration,
+    fail_on_stall: bool,
+    stalled: Arc<AtomicBool>,
+) where
+    G: SourceChangeGenerator + ?Sized + 'static,
+{
+    let check_interval = (timeout / 4).clamp(Duration::from_millis(1), MAX_CHECK_INTERVAL);
+    log::debug!(
+        "Watching TestRunSource {} for stalls - timeout: {:?}, check interval: {:?}",
+        id,
+        timeout,
+        check_interval
+    );
+
+    let mut detector = StallDetector::new(timeout, Instant::now());
+    let mut ticker = tokio::time::interval(check_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+
+        // A wedged generator may not answer at all, so don't wait longer than the interval.
+        let (status, event_count) =
+            match tokio::time::timeout(check_interval, generator.get_state()).await {
+                Ok(Ok(response)) => (response.state.status, Some(response.state.event_count)),
+                Ok(Err(e)) => {
+                    log::debug!("Stall watcher for TestRunSource {} exiting: {:?}", id, e);
+                    return;
+                }
+                Err(_) => (SourceChangeGeneratorStatus::Running, None),
+            };
+
+        if !status.is_active() {
+            return;
+        }
+
+        match detector.observe(status, event_count, Instant::now()) {
+            StallCheck::Stalled => {
+                stalled.store(true, Ordering::SeqCst);
+                log::warn!(
+                    "TestRunSource {} is stalled - no source change events for {:?} while Running",
+                    id,
+                    timeout
+                );
+
+                if fail_on_stall {
+                    log::error!("Stopping stalled TestRunSource {}", id);
+                    if let Err(e) = generator.stop().await {
+                        log::warn!("Stopping stalled TestRunSource {} failed: {:?}", id, e);
+                    }
+                    return;
+                }
+            }
+            StallCheck::Recovered => {
+                stalled.store(false, Ordering::SeqCst);
+                log::info!("TestRunSource {} is producing events again", id);
+            }
+            StallCheck::Progressing | StallCheck::Unchanged => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use SourceChangeGeneratorStatus::*;
+    use StallCheck::*;
+
+    #[test]
+    fn test_stall_detected_after_timeout() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let mut detector = StallDetector::new(Duration::from_millis(100), start);
+
+        assert_eq!(detector.observe(Running, Some(0), ms(0)), Unchanged);
+        assert_eq!(detector.observe(Running, Some(5), ms(50)), Progressing);
+        assert_eq!(detector.observe(Running, Some(5), ms(140)), Unchanged);
+        assert_eq!(detector.observe(Running, Some(5), ms(150)), Stalled);
+
+        // A stall is only reported once, until the generator recovers.
+        assert_eq!(detector.observe(Running, None, ms(300)), Unchanged);
+        assert_eq!(detector.observe(Running, Some(6), ms(310)), Recovered);
+    }
+
+    #[test]
+    fn test_paused_time_not_counted() {
+        let start = Instant::now();
+        let ms = |ms| start + Duration::from_millis(ms);
+        let mut detector = StallDetector::new(Duration::from_millis(100), start);
+
+        assert_eq!(detector.observe(Running, Some(5), ms(0)), Unchanged);
+        assert_eq!(detector.observe(Paused, Some(5), ms(500)), Unchanged);
+        assert_eq!(detector.observe(Running, Some(5), ms(550)), Unchanged);
+        assert_eq!(detector.observe(Running, Some(5), ms(600)), Stalled);
+    }
+
+    #[test]
+    fn test_unreadable_state_counts_as_no_progress() {
+        let start = Instant::now();
+        let mut detector = StallDetector::new(Duration::from_millis(100), start);
+
+        assert_eq!(
+            detector.observe(Running, None, start + Duration::from_millis(100)),
+            Stalled
+        );
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of the result summaries written by two TestRuns.
+//!
+//! Every numeric value in the component summaries (throughput, counts, latencies,
+//! error counts, ...) is treated as a metric identified by its path, e.g.
+//! `reactions.reaction-001.reaction_invocation_count`. Metrics present in either
+//! run are compared and those whose relative change exceeds the threshold are flagged.
+//! Normalizations are applied to the summaries first, with field paths matching the
+//! metric names, e.g. `sources.*.throughput.events_per_second`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use test_data_store::test_run_storage::{TestRunId, TestRunSummaries};
+
+use crate::utils::{normalize_value, FieldNormalization};
+
+pub const DEFAULT_COMPARISON_THRESHOLD_PCT: f64 = 10.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SummaryMetricDelta {
+    pub metric: String,
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+    // b - a; None if the metric is missing from either run.
+    pub delta: Option<f64>,
+    // Change relative to a, as a percentage; None if the metric is missing from either
+    // run or a is zero.
+    pub delta_pct: Option<f64>,
+    // True if the relative change exceeds the threshold, or the metric is missing from
+    // one of the runs.
+    pub exceeds_threshold: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TestRunSummaryComparison {
+    pub run_a: TestRunId,
+    pub run_b: TestRunId,
+    pub threshold_pct: f64,
+    pub metrics: Vec<SummaryMetricDelta>,
+    pub exceeds_threshold_count: usize,
+}
+
+pub fn compare_summaries(
+    run_a: TestRunId,
+    summaries_a: &TestRunSummaries,
+    run_b: TestRunId,
+    summaries_b: &TestRunSummaries,
+    threshold_pct: f64,
+    normalizations: &[FieldNormalization],
+) -> TestRunSummaryComparison {
+    let metrics_a = flatten_summaries(summaries_a, normalizations);
+    let metrics_b = flatten_summaries(summaries_b, normalizations);
+
+    let mut names: Vec<&String> = metrics_a.keys().chain(metrics_b.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let metrics: Vec<SummaryMetricDelta> = names
+        .into_iter()
+        .map(|name| {
+            compare_metric(
+                name,
+                metrics_a.get(name).copied(),
+                metrics_b.get(name).copied(),
+                threshold_pct,
+            )
+        })
+        .collect();
+
+    TestRunSummaryComparison {
+        run_a,
+        run_b,
+        threshold_pct,
+        exceeds_threshold_count: metrics.iter().filter(|m| m.exceeds_threshold).count(),
+        metrics,
+    }
+}
+
+fn compare_metric(
+    name: &str,
+    a: Option<f64>,
+    b: Option<f64>,
+    threshold_pct: f64,
+) -> SummaryMetricDelta {
+    let (delta, delta_pct, exceeds_threshold) = match (a, b) {
+        (Some(a), Some(b)) => {
+            let delta = b - a;
+            let delta_pct = if a != 0.0 {
+                Some(delta / a.abs() * 100.0)
+            } else {
+                None
+            };
+            let exceeds_threshold = match delta_pct {
+                Some(pct) => pct.abs() > threshold_pct,
+                // Any change from zero is an unbounded relative change.
+                None => delta != 0.0,
+            };
+            (Some(delta), delta_pct, exceeds_threshold)
+        }
+        _ => (None, None, true),
+    };
+
+    SummaryMetricDelta {
+        metric: name.to_string(),
+        a,
+        b,
+        delta,
+        delta_pct,
+        exceeds_threshold,
+    }
+}
+
+fn flatten_summaries(
+    summaries: &TestRunSummaries,
+    normalizations: &[FieldNormalization],
+) -> BTreeMap<String, f64> {
+    let components: [(&str, &HashMap<String, Value>); 4] = [
+        ("drasi_servers", &summaries.drasi_servers),
+        ("queries", &summaries.queries),
+        ("reactions", &summaries.reactions),
+        ("sources", &summaries.sources),
+    ];
+
+    let summaries: serde_json::Map<String, Value> = components
+        .into_iter()
+        .map(|(component_type, component_summaries)| {
+            let component_summaries = component_summaries
+                .iter()
+                .map(|(id, summary)| (id.clone(), summary.clone()))
+                .collect();
+            (
+                component_type.to_string(),
+                Value::Object(component_summaries),
+            )
+        })
+        .collect();
+
+    let mut metrics = BTreeMap::new();
+    if let Value::Object(summaries) = normalize_value(&Value::Object(summaries), normalizations) {
+        for (component_type, component_summaries) in summaries {
+            flatten_value(&component_type, &component_summaries, &mut metrics);
+        }
+    }
+
+    metrics
+}
+
+fn flatten_value(path: &str, value: &Value, metrics: &mut BTreeMap<String, f64>) {
+    match value {
+        Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                metrics.insert(path.to_string(), n);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten_value(&format!("{}.{}", path, key), value, metrics);
+            }
+        }
+        Value::Array(items) => {
+            for (idx, value) in items.iter().enumerate() {
+                flatten_value(&format!("{}.{}", path, idx), value, metrics);
+            }
+        }
+        // Strings, booleans and nulls are not comparable metrics.
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn summaries(reaction_count: u64, eps: f64) -> TestRunSummaries {
+        let mut summaries = TestRunSummaries::default();
+        summaries.reactions.insert(
+            "reaction-001".to_string(),
+            json!({ "reaction_invocation_count": reaction_count, "observer_runtime_s": "1.0 seconds" }),
+        );
+        summaries.sources.insert(
+            "source-001".to_string(),
+            json!({ "throughput": { "events_per_second": eps } }),
+        );
+        summaries
+    }
+
+    fn run_id(run: &str) -> TestRunId {
+        TestRunId::new("test-repo", "test-001", run)
+    }
+
+    #[test]
+    fn test_compare_summaries_within_threshold() {
+        let comparison = compare_summaries(
+            run_id("a"),
+            &summaries(100, 1000.0),
+            run_id("b"),
+            &summaries(105, 950.0),
+            DEFAULT_COMPARISON_THRESHOLD_PCT,
+            &[],
+        );
+
+        // Non-numeric values are ignored
+        assert_eq!(comparison.metrics.len(), 2);
+        assert_eq!(comparison.exceeds_threshold_count, 0);
+
+        let eps = &comparison.metrics[1];
+        assert_eq!(
+            eps.metric,
+            "sources.source-001.throughput.events_per_second"
+        );
+        assert_eq!(eps.delta, Some(-50.0));
+        assert_eq!(eps.delta_pct, Some(-5.0));
+    }
+
+    #[test]
+    fn test_compare_summaries_beyond_threshold() {
+        let comparison = compare_summaries(
+            run_id("a"),
+            &summaries(100, 1000.0),
+            run_id("b"),
+            &summaries(100, 500.0),
+            DEFAULT_COMPARISON_THRESHOLD_PCT,
+            &[],
+        );
+
+        assert_eq!(comparison.exceeds_threshold_count, 1);
+        assert!(comparison.metrics[1].exceeds_threshold);
+        assert_eq!(comparison.metrics[1].delta_pct, Some(-50.0));
+    }
+
+    #[test]
+    fn test_compare_summaries_missing_metric() {
+        let mut b = summaries(100, 1000.0);
+        b.queries
+            .insert("query-001".to_string(), json!({ "result_count": 0 }));
+
+        let comparison = compare_summaries(
+            run_id("a"),
+            &summaries(100, 1000.0),
+            run_id("b"),
+            &b,
+            DEFAULT_COMPARISON_THRESHOLD_PCT,
+            &[],
+        );
+
+        let missing = comparison
+            .metrics
+            .iter()
+            .find(|m| m.metric == "queries.query-001.result_count")
+            .unwrap();
+        assert_eq!(missing.a, None);
+        assert_eq!(missing.b, Some(0.0));
+        assert!(missing.exceeds_threshold);
+        assert_eq!(comparison.exceeds_threshold_count, 1);
+    }
+
+    #[test]
+    fn test_compare_summaries_normalized() {
+        let mut a = summaries(100, 1000.0);
+        a.sources.insert(
+            "source-002".to_string(),
+            json!({ "throughput": { "events_per_second": 0.001 } }),
+        );
+        let mut b = summaries(100, 1000.0);
+        b.sources.insert(
+            "source-002".to_string(),
+            json!({ "throughput": { "events_per_second": 0.004 } }),
+        );
+
+        let comparison = compare_summaries(
+            run_id("a"),
+            &a,
+            run_id("b"),
+            &b,
+            DEFAULT_COMPARISON_THRESHOLD_PCT,
+            &[],
+        );
+        assert_eq!(comparison.exceeds_threshold_count, 1);
+
+        // Rounded to 2 decimals, both rates are 0 and the change disappears.
+        let normalizations: Vec<FieldNormalization> = serde_json::from_value(json!([
+            { "field": "sources.*.throughput.events_per_second", "kind": "Float", "decimals": 2 }
+        ]))
+        .unwrap();
+        let comparison = compare_summaries(
+            run_id("a"),
+            &a,
+            run_id("b"),
+            &b,
+            DEFAULT_COMPARISON_THRESHOLD_PCT,
+            &normalizations,
+        );
+        assert_eq!(comparison.exceeds_threshold_count, 0);
+
+        let eps = comparison
+            .metrics
+            .iter()
+            .find(|m| m.metric == "sources.source-002.throughput.events_per_second")
+            .unwrap();
+        assert_eq!(eps.a, Some(0.0));
+        assert_eq!(eps.delta, Some(0.0));
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fluent construction of a [`TestRunConfig`] for tests that use the TestRunHost as a library.
+//!
+//! The builder sets the `test_repo_id`, `test_id` and `test_run_id` of every component config
+//! from the TestRun, and checks that component IDs are unique within each kind. If no
+//! `test_run_id` is given, a timestamp is used, as when a TestRunConfig is deserialized.
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use test_data_store::TestDataStore;
+//! use test_run_host::{
+//!     queries::TestRunQueryConfig, reactions::TestRunReactionConfig,
+//!     sources::TestRunSourceConfig, TestRunConfigBuilder, TestRunHost, TestRunHostConfig,
+//! };
+//!
+//! # async fn run(data_store: Arc<TestDataStore>) -> anyhow::Result<()> {
+//! let config = TestRunConfigBuilder::default()
+//!     .test_ids("test-repo", "building-comfort", "run-001")
+//!     .source(TestRunSourceConfig::new("facilities-db"))
+//!     .query(TestRunQueryConfig::new("room-comfort-level"))
+//!     .reaction(TestRunReactionConfig::new("comfort-alerts"))
+//!     .build()?;
+//!
+//! let test_run_host = TestRunHost::new(TestRunHostConfig::default(), data_store).await?;
+//! let test_run_id = test_run_host.add_test_run(config).await?;
+//! test_run_host.start_test_run(&test_run_id).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashSet;
+
+use crate::{
+    drasi_servers::TestRunDrasiServerConfig, progress_webhook::ProgressWebhookConfig,
+    queries::TestRunQueryConfig, reactions::TestRunReactionConfig, sources::TestRunSourceConfig,
+    ArtifactRetention, TestRunConfig, TestRunLimits,
+};
+
+#[derive(Clone, Debug)]
+pub struct TestRunConfigBuilder {
+    config: TestRunConfig,
+}
+
+impl TestRunConfigBuilder {
+    pub fn new(test_repo_id: &str, test_id: &str, test_run_id: &str) -> Self {
+        Self {
+            config: TestRunConfig {
+                test_id: test_id.to_string(),
+                test_repo_id: test_repo_id.to_string(),
+                test_run_id: test_run_id.to_string(),
+                artifact_retention: ArtifactRetention::default(),
+                drasi_servers: Vec::new(),
+                limits: TestRunLimits::default(),
+                progress_webhook: None,
+                queries: Vec::new(),
+                reactions: Vec::new(),
+                sources: Vec::new(),
+            },
+        }
+    }
+
+    // An empty test_run_id is replaced with a timestamp when the config is built.
+    pub fn test_ids(mut self, test_repo_id: &str, test_id: &str, test_run_id: &str) -> Self {
+        self.config.test_repo_id = test_repo_id.to_string();
+        self.config.test_id = test_id.to_string();
+        self.config.test_run_id = test_run_id.to_string();
+        self
+    }
+
+    pub fn artifact_retention(mut self, artifact_retention: ArtifactRetention) -> Self {
+        self.config.artifact_retention = artifact_retention;
+        self
+    }
+
+    pub fn drasi_server(mut self, drasi_server: TestRunDrasiServerConfig) -> Self {
+        self.config.drasi_servers.push(drasi_server);
+        self
+    }
+
+    pub fn limits(mut self, limits: TestRunLimits) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
+    pub fn progress_webhook(mut self, progress_webhook: ProgressWebhookConfig) -> Self {
+        self.config.progress_webhook = Some(progress_webhook);
+        self
+    }
+
+    pub fn query(mut self, query: TestRunQueryConfig) -> Self {
+        self.config.queries.push(query);
+        self
+    }
+
+    pub fn reaction(mut self, reaction: TestRunReactionConfig) -> Self {
+        self.config.reactions.push(reaction);
+        self
+    }
+
+    pub fn source(mut self, source: TestRunSourceConfig) -> Self {
+        self.config.sources.push(source);
+        self
+    }
+
+    pub fn build(self) -> anyhow::Result<TestRunConfig> {
+        let mut config = self.config;
+
+        if config.test_run_id.is_empty() {
+            config.test_run_id = chrono::Utc::now().format("%Y%m%d%H%M%S").to_string();
+        }
+
+        for (name, value) in [
+            ("test_repo_id", &config.test_repo_id),
+            ("test_id", &config.test_id),
+        ] {
+            if value.is_empty() {
+                anyhow::bail!("TestRunConfig {} must not be empty", name);
+            }
+        }
+
+        check_unique_ids(
+            "Drasi Server",
+            config.drasi_servers.iter().map(|s| &s.test_drasi_server_id),
+        )?;
+        check_unique_ids("Query", config.queries.iter().map(|q| &q.test_query_id))?;
+        check_unique_ids(
+            "Reaction",
+            config.reactions.iter().map(|r| &r.test_reaction_id),
+        )?;
+        check_unique_ids("Source", config.sources.iter().map(|s| &s.test_source_id))?;
+
+        let ids = || {
+            (
+                Some(config.test_id.clone()),
+                Some(config.test_repo_id.clone()),
+                Some(config.test_run_id.clone()),
+            )
+        };
+        for server in config.drasi_servers.iter_mut() {
+            (server.test_id, server.test_repo_id, server.test_run_id) = ids();
+        }
+        for query in config.queries.iter_mut() {
+            (query.test_id, query.test_repo_id, query.test_run_id) = ids();
+        }
+        for reaction in config.reactions.iter_mut() {
+            (
+                reaction.test_id,
+                reaction.test_repo_id,
+                reaction.test_run_id,
+            ) = ids();
+        }
+        for source in config.sources.iter_mut() {
+            (source.test_id, source.test_repo_id, source.test_run_id) = ids();
+        }
+
+        Ok(config)
+    }
+}
+
+// A builder without IDs, for use with test_ids.
+impl Default for TestRunConfigBuilder {
+    fn default() -> Self {
+        Self::new("", "", "")
+    }
+}
+
+impl TestRunConfig {
+    pub fn builder(test_repo_id: &str, test_id: &str, test_run_id: &str) -> TestRunConfigBuilder {
+        TestRunConfigBuilder::new(test_repo_id, test_id, test_run_id)
+    }
+}
+
+fn check_unique_ids<'a>(kind: &str, ids: impl Iterator<Item = &'a String>) -> anyhow::Result<()> {
+    let mut seen = HashSet::new();
+    for id in ids {
+        if id.is_empty() {
+            anyhow::bail!("TestRunConfig has a {} with an empty ID", kind);
+        }
+        if !seen.insert(id) {
+            anyhow::bail!("TestRunConfig has more than one {} with ID {}", kind, id);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_propagates_ids() {
+        let mut query = TestRunQueryConfig::new("query-001");
+        query.start_immediately = true;
+
+        let config = TestRunConfig::builder("test-repo", "test-001", "run-001")
+            .source(TestRunSourceConfig::new("source-001"))
+            .query(query)
+            .reaction(TestRunReactionConfig::new("reaction-001"))
+            .artifact_retention(ArtifactRetention::OnFailure)
+            .build()
+            .unwrap();
+
+        assert_eq!(config.artifact_retention, ArtifactRetention::OnFailure);
+        assert_eq!(config.sources[0].test_repo_id.as_deref(), Some("test-repo"));
+        assert_eq!(config.sources[0].test_id.as_deref(), Some("test-001"));
+        assert_eq!(config.queries[0].test_run_id.as_deref(), Some("run-001"));
+        assert!(config.queries[0].start_immediately);
+        assert_eq!(config.reactions[0].test_run_id.as_deref(), Some("run-001"));
+        assert!(config.drasi_servers.is_empty());
+    }
+
+    #[test]
+    fn test_build_rejects_duplicate_ids() {
+        let result = TestRunConfig::builder("test-repo", "test-001", "run-001")
+            .source(TestRunSourceConfig::new("source-001"))
+            .source(TestRunSourceConfig::new("source-001"))
+            .build();
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("more than one Source"));
+    }
+
+    #[test]
+    fn test_build_defaults_test_run_id() {
+        let config = TestRunConfigBuilder::default()
+            .test_ids("test-repo", "test-001", "")
+            .source(TestRunSourceConfig::new("source-001"))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.test_repo_id, "test-repo");
+        assert_eq!(config.test_id, "test-001");
+        assert_eq!(config.test_run_id.len(), "YYYYMMDDHHMMSS".len());
+        assert!(config.test_run_id.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(
+            config.sources[0].test_run_id.as_deref(),
+            Some(config.test_run_id.as_str())
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_empty_ids() {
+        assert!(TestRunConfig::builder("test-repo", "", "run-001")
+            .build()
+            .is_err());
+        assert!(TestRunConfig::builder("test-repo", "test-001", "run-001")
+            .query(TestRunQueryConfig::new(""))
+            .build()
+            .is_err());
+    }
+}
@@ -0,0 +1,250 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Limits on the resources a single TestRun can use.
+//!
+//! Every limit is unlimited unless it is set. Channel capacity is the total number of slots
+//! in the bounded channels allocated by the TestRun's sources and reactions: the change
+//! channel of model data generators, and the buffers of DrasiServerChannel dispatchers and
+//! reaction handlers.
+
+use serde::{Deserialize, Serialize};
+use test_data_store::{
+    test_repo_storage::models::{
+        ModelDataGeneratorDefinition, ReactionHandlerDefinition, SourceChangeDispatcherDefinition,
+        TestSourceDefinition,
+    },
+    test_run_storage::TestRunId,
+};
+
+use crate::{
+    reactions::reaction_handlers::drasi_server_channel_handler,
+    sources::{
+        model_data_generators::building_hierarchy::DEFAULT_CHANGE_CHANNEL_CAPACITY,
+        source_change_dispatchers::drasi_server_channel_dispatcher, TestRunSourceConfig,
+    },
+};
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct TestRunLimits {
+    #[serde(default)]
+    pub max_sources: Option<usize>,
+    #[serde(default)]
+    pub max_queries: Option<usize>,
+    #[serde(default)]
+    pub max_reactions: Option<usize>,
+    #[serde(default)]
+    pub max_channel_capacity: Option<usize>,
+}
+
+impl TestRunLimits {
+    pub fn check_sources(&self, test_run_id: &TestRunId, count: usize) -> anyhow::Result<()> {
+        check_limit(
+            test_run_id,
+            "max_sources",
+            "sources",
+            count,
+            self.max_sources,
+        )
+    }
+
+    pub fn check_queries(&self, test_run_id: &TestRunId, count: usize) -> anyhow::Result<()> {
+        check_limit(
+            test_run_id,
+            "max_queries",
+            "queries",
+            count,
+            self.max_queries,
+        )
+    }
+
+    pub fn check_reactions(&self, test_run_id: &TestRunId, count: usize) -> anyhow::Result<()> {
+        check_limit(
+            test_run_id,
+            "max_reactions",
+            "reactions",
+            count,
+            self.max_reactions,
+        )
+    }
+
+    pub fn check_channel_capacity(
+        &self,
+        test_run_id: &TestRunId,
+        capacity: usize,
+    ) -> anyhow::Result<()> {
+        check_limit(
+            test_run_id,
+            "max_channel_capacity",
+            "total channel capacity",
+            capacity,
+            self.max_channel_capacity,
+        )
+    }
+}
+
+fn check_limit(
+    test_run_id: &TestRunId,
+    limit_name: &str,
+    what: &str,
+    value: usize,
+    limit: Option<usize>,
+) -> anyhow::Result<()> {
+    match limit {
+        Some(limit) if value > limit => anyhow::bail!(
+            "TestRun {} would have {} {}, which exceeds its {} limit of {}",
+            test_run_id,
+            value,
+            what,
+            limit_name,
+            limit
+        ),
+        _ => Ok(()),
+    }
+}
+
+// The channel capacity a TestRunSource allocates, taking the dispatcher overrides into account.
+pub fn source_channel_capacity(
+    definition: &TestSourceDefinition,
+    config: &TestRunSourceConfig,
+) -> usize {
+    let (common, generator_capacity) = match definition {
+        TestSourceDefinition::Model(def) => {
+            let capacity = match &def.model_data_generator {
+                Some(ModelDataGeneratorDefinition::BuildingHierarchy(generator)) => generator
+                    .common
+                    .change_channel_capacity
+                    .unwrap_or(DEFAULT_CHANGE_CHANNEL_CAPACITY),
+                None => 0,
+            };
+            (&def.common, capacity)
+        }
+        TestSourceDefinition::Script(def) => (&def.common, 0),
+    };
+
+    let dispatchers = config
+        .test_run_overrides
+        .as_ref()
+        .and_then(|overrides| overrides.source_change_dispatchers.as_ref())
+        .unwrap_or(&common.source_change_dispatchers);
+
+    generator_capacity
+        + dispatchers
+            .iter()
+            .map(dispatcher_channel_capacity)
+            .sum::<usize>()
+}
+
+fn dispatcher_channel_capacity(definition: &SourceChangeDispatcherDefinition) -> usize {
+    match definition {
+        SourceChangeDispatcherDefinition::DrasiServerChannel(def) => def
+            .buffer_size
+            .unwrap_or(drasi_server_channel_dispatcher::DEFAULT_CHANNEL_BUFFER_SIZE),
+        SourceChangeDispatcherDefinition::LsnOrderValidator(def) => {
+            dispatcher_channel_capacity(&def.dispatcher)
+        }
+        _ => 0,
+    }
+}
+
+// The channel capacity a TestRunReaction allocates for its handlers.
+pub fn reaction_channel_capacity(handlers: &[ReactionHandlerDefinition]) -> usize {
+    handlers
+        .iter()
+        .map(|handler| match handler {
+            ReactionHandlerDefinition::DrasiServerChannel(def) => def
+                .buffer_size
+                .unwrap_or(drasi_server_channel_handler::DEFAULT_CHANNEL_BUFFER_SIZE),
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_data_store::test_repo_storage::models::{
+        CommonTestSourceDefinition, DrasiServerChannelSourceChangeDispatcherDefinition,
+        LsnOrderValidatorSourceChangeDispatcherDefinition, ScriptTestSourceDefinition,
+    };
+
+    use crate::sources::TestRunSourceOverrides;
+
+    use super::*;
+
+    fn channel_dispatcher(buffer_size: Option<usize>) -> SourceChangeDispatcherDefinition {
+        SourceChangeDispatcherDefinition::DrasiServerChannel(
+            DrasiServerChannelSourceChangeDispatcherDefinition {
+                drasi_server_id: "server-001".to_string(),
+                source_id: "source-001".to_string(),
+                buffer_size,
+            },
+        )
+    }
+
+    fn script_source(dispatchers: Vec<SourceChangeDispatcherDefinition>) -> TestSourceDefinition {
+        TestSourceDefinition::Script(ScriptTestSourceDefinition {
+            bootstrap_data_generator: None,
+            common: CommonTestSourceDefinition {
+                test_source_id: "source-001".to_string(),
+                source_change_dispatchers: dispatchers,
+                subscribers: Vec::new(),
+            },
+            source_change_generator: None,
+        })
+    }
+
+    #[test]
+    fn test_check_limit() {
+        let test_run_id = TestRunId::new("repo", "test", "run");
+        let limits = TestRunLimits {
+            max_sources: Some(2),
+            ..Default::default()
+        };
+
+        assert!(limits.check_sources(&test_run_id, 2).is_ok());
+        let err = limits.check_sources(&test_run_id, 3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TestRun repo.test.run would have 3 sources, which exceeds its max_sources limit of 2"
+        );
+
+        // Unset limits are unlimited.
+        assert!(limits.check_queries(&test_run_id, usize::MAX).is_ok());
+        assert!(limits
+            .check_channel_capacity(&test_run_id, usize::MAX)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_source_channel_capacity() {
+        let definition = script_source(vec![
+            channel_dispatcher(None),
+            SourceChangeDispatcherDefinition::LsnOrderValidator(
+                LsnOrderValidatorSourceChangeDispatcherDefinition {
+                    dispatcher: Box::new(channel_dispatcher(Some(10))),
+                    error_on_violation: None,
+                },
+            ),
+        ]);
+        let mut config = TestRunSourceConfig::new("source-001");
+        assert_eq!(source_channel_capacity(&definition, &config), 1034);
+
+        // Overridden dispatchers replace the ones in the definition.
+        config.test_run_overrides = Some(TestRunSourceOverrides {
+            source_change_dispatchers: Some(vec![channel_dispatcher(Some(5))]),
+            ..Default::default()
+        });
+        assert_eq!(source_channel_capacity(&definition, &config), 5);
+    }
+}
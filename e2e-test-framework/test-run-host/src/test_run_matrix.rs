@@ -0,0 +1,247 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Expansion of a [`TestRunConfig`] over a matrix of parameters, for sweep testing.
+//!
+//! Each axis overrides one field of the base config, identified by a path such as
+//! `sources[0].test_run_overrides.model_data_generator.seed`. Path segments are field names
+//! or array indexes, and missing objects along the path are created. The matrix produces
+//! one config for every combination of axis values, with a `test_run_id` derived from the
+//! base `test_run_id` and the axis values, e.g. `run-001-rate_1000-seed_7`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use test_data_store::secret::with_secrets_exposed;
+
+use crate::TestRunConfig;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TestRunMatrixConfig {
+    pub base: TestRunConfig,
+    pub axes: Vec<TestRunMatrixAxis>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TestRunMatrixAxis {
+    // Short name for the axis, used in the derived test_run_ids.
+    pub name: String,
+    pub path: String,
+    pub values: Vec<Value>,
+}
+
+impl TestRunMatrixConfig {
+    pub fn expand(&self) -> anyhow::Result<Vec<TestRunConfig>> {
+        if self.axes.is_empty() {
+            anyhow::bail!("TestRunMatrixConfig must have at least one axis");
+        }
+
+        let mut axis_paths = Vec::new();
+        for axis in self.axes.iter() {
+            if axis.name.is_empty() {
+                anyhow::bail!("TestRunMatrixConfig axis names must not be empty");
+            }
+            if axis.values.is_empty() {
+                anyhow::bail!("TestRunMatrixConfig axis {} has no values", axis.name);
+            }
+            axis_paths.push(parse_path(&axis.path)?);
+        }
+
+        // The configs are built from the JSON, so it must keep the base config's secrets.
+        let base = with_secrets_exposed(|| serde_json::to_value(&self.base))?;
+        let mut configs = Vec::new();
+
+        // Iterate over every combination of value indexes, with the last axis varying fastest.
+        let mut indexes = vec![0; self.axes.len()];
+        loop {
+            let mut config = base.clone();
+            let mut test_run_id = self.base.test_run_id.clone();
+
+            for (axis_idx, axis) in self.axes.iter().enumerate() {
+                let value = &axis.values[indexes[axis_idx]];
+                set_path(&mut config, &axis_paths[axis_idx], value.clone())
+                    .map_err(|e| anyhow::anyhow!("Can't apply matrix axis {}: {}", axis.name, e))?;
+                test_run_id.push_str(&format!("-{}_{}", axis.name, value_label(value)));
+            }
+
+            let mut config: TestRunConfig = serde_json::from_value(config).map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid TestRunConfig for matrix run {}: {}",
+                    test_run_id,
+                    e
+                )
+            })?;
+            config.test_run_id = test_run_id;
+            configs.push(config);
+
+            if !advance(&mut indexes, &self.axes) {
+                return Ok(configs);
+            }
+        }
+    }
+}
+
+// Moves to the next combination of value indexes, returning false once every combination
+// has been visited.
+fn advance(indexes: &mut [usize], axes: &[TestRunMatrixAxis]) -> bool {
+    for (idx, axis) in indexes.iter_mut().zip(axes).rev() {
+        *idx += 1;
+        if *idx < axis.values.len() {
+            return true;
+        }
+        *idx = 0;
+    }
+    false
+}
+
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+}
+
+fn parse_path(path: &str) -> anyhow::Result<Vec<PathSegment>> {
+    let normalized = path.replace('[', ".").replace(']', "");
+    normalized
+        .split('.')
+        .map(|segment| match segment {
+            "" => anyhow::bail!("Invalid matrix axis path {:?}", path),
+            _ => Ok(match segment.parse::<usize>() {
+                Ok(idx) => PathSegment::Index(idx),
+                Err(_) => PathSegment::Field(segment.to_string()),
+            }),
+        })
+        .collect()
+}
+
+fn set_path(target: &mut Value, path: &[PathSegment], value: Value) -> anyhow::Result<()> {
+    let Some((segment, rest)) = path.split_first() else {
+        *target = value;
+        return Ok(());
+    };
+
+    let child = match segment {
+        PathSegment::Field(name) => {
+            if target.is_null() {
+                *target = Value::Object(Map::new());
+            }
+            match target {
+                Value::Object(fields) => fields.entry(name.clone()).or_insert(Value::Null),
+                _ => anyhow::bail!("{} is not a field of an object", name),
+            }
+        }
+        PathSegment::Index(idx) => match target {
+            Value::Array(elements) => match elements.get_mut(*idx) {
+                Some(element) => element,
+                None => anyhow::bail!("index {} is out of range", idx),
+            },
+            _ => anyhow::bail!("index {} is not in an array", idx),
+        },
+    };
+
+    set_path(child, rest, value)
+}
+
+// The part of a derived test_run_id that identifies an axis value. TestRunIds are dot
+// separated, so anything other than letters, digits, '-' and '_' is replaced with '_'.
+fn value_label(value: &Value) -> String {
+    let label = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    label
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn matrix(axes: Value) -> TestRunMatrixConfig {
+        serde_json::from_value(json!({
+            "base": {
+                "test_id": "test-001",
+                "test_repo_id": "test-repo",
+                "test_run_id": "run-001",
+                "sources": [
+                    { "test_source_id": "source-001" }
+                ]
+            },
+            "axes": axes,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_expand_matrix() {
+        let matrix = matrix(json!([
+            {
+                "name": "rate",
+                "path": "sources[0].test_run_overrides.model_data_generator.spacing_mode",
+                "values": ["none", "1000"]
+            },
+            {
+                "name": "seed",
+                "path": "sources.0.test_run_overrides.model_data_generator.seed",
+                "values": [1, 2, 3]
+            }
+        ]));
+
+        let configs = matrix.expand().unwrap();
+        let ids: Vec<&str> = configs.iter().map(|c| c.test_run_id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "run-001-rate_none-seed_1",
+                "run-001-rate_none-seed_2",
+                "run-001-rate_none-seed_3",
+                "run-001-rate_1000-seed_1",
+                "run-001-rate_1000-seed_2",
+                "run-001-rate_1000-seed_3",
+            ]
+        );
+
+        let overrides = configs[4].sources[0]
+            .test_run_overrides
+            .as_ref()
+            .unwrap()
+            .model_data_generator
+            .as_ref()
+            .unwrap();
+        assert_eq!(overrides.seed, Some(2));
+        assert!(overrides.spacing_mode.is_some());
+    }
+
+    #[test]
+    fn test_expand_matrix_errors() {
+        let out_of_range = matrix(json!([
+            { "name": "seed", "path": "sources[1].test_run_overrides", "values": [null] }
+        ]));
+        assert!(out_of_range.expand().is_err());
+
+        let invalid_value = matrix(json!([
+            { "name": "retention", "path": "artifact_retention", "values": ["sometimes"] }
+        ]));
+        assert!(invalid_value.expand().is_err());
+
+        let no_values = matrix(json!([{ "name": "seed", "path": "test_id", "values": [] }]));
+        assert!(no_values.expand().is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+
+// Source of the wall clock times that generators record, so tests can control them
+#[async_trait]
+pub trait Clock: Debug + Send + Sync {
+    // Nanoseconds since the Unix epoch
+    fn now_ns(&self) -> u64;
+
+    // Waits until duration has passed on this clock
+    async fn sleep(&self, duration: Duration);
+}
+
+// Clock that reads the system time
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now_ns(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+// The clock used unless a test provides its own
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+// Clock that only moves when it is set or advanced. Sleeping advances it instead of waiting.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now_ns: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(now_ns: u64) -> Self {
+        Self {
+            now_ns: AtomicU64::new(now_ns),
+        }
+    }
+
+    pub fn advance(&self, ns: u64) {
+        self.now_ns.fetch_add(ns, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, now_ns: u64) {
+        self.now_ns.store(now_ns, Ordering::SeqCst);
+    }
+}
+
+#[async_trait]
+impl Clock for ManualClock {
+    fn now_ns(&self) -> u64 {
+        self.now_ns.load(Ordering::SeqCst)
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.advance(duration.as_nanos() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_manual_clock() {
+        let clock = ManualClock::new(1_000);
+        assert_eq!(clock.now_ns(), 1_000);
+
+        clock.advance(500);
+        assert_eq!(clock.now_ns(), 1_500);
+
+        clock.sleep(Duration::from_nanos(250)).await;
+        assert_eq!(clock.now_ns(), 1_750);
+
+        clock.set(10);
+        assert_eq!(clock.now_ns(), 10);
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let before = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        assert!(system_clock().now_ns() >= before);
+    }
+}
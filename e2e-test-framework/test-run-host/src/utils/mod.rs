@@ -1,3 +1,7 @@
 pub mod adaptive_batcher;
+pub mod clock;
+pub mod value_normalization;
 
-pub use adaptive_batcher::*;
\ No newline at end of file
+pub use adaptive_batcher::*;
+pub use clock::*;
+pub use value_normalization::*;
@@ -0,0 +1,188 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::DateTime;
+use serde_json::{Number, Value};
+
+pub use test_data_store::test_repo_storage::models::{
+    FieldNormalization, PrecisionNormalization, TimestampPrecision,
+};
+
+// Returns a copy of the value with each normalization applied to the matching fields.
+pub fn normalize_value(value: &Value, normalizations: &[FieldNormalization]) -> Value {
+    let mut normalized = value.clone();
+    for normalization in normalizations {
+        let path: Vec<&str> = normalization.field.split('.').collect();
+        normalize_path(&mut normalized, &path, &normalization.precision);
+    }
+    normalized
+}
+
+fn normalize_path(value: &mut Value, path: &[&str], precision: &PrecisionNormalization) {
+    let Some((segment, rest)) = path.split_first() else {
+        normalize_field(value, precision);
+        return;
+    };
+
+    match value {
+        Value::Object(map) => {
+            if *segment == "*" {
+                for child in map.values_mut() {
+                    normalize_path(child, rest, precision);
+                }
+            } else if let Some(child) = map.get_mut(*segment) {
+                normalize_path(child, rest, precision);
+            }
+        }
+        Value::Array(items) => {
+            if *segment == "*" {
+                for child in items.iter_mut() {
+                    normalize_path(child, rest, precision);
+                }
+            } else if let Some(child) = segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|idx| items.get_mut(idx))
+            {
+                normalize_path(child, rest, precision);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn normalize_field(value: &mut Value, precision: &PrecisionNormalization) {
+    let normalized = match precision {
+        PrecisionNormalization::Timestamp { precision } => timestamp_to_nanos(value)
+            .map(|ns| Value::from(ns.div_euclid(nanos_per_unit(precision)))),
+        PrecisionNormalization::Float { decimals } => value.as_f64().and_then(|f| {
+            let factor = 10f64.powi(*decimals as i32);
+            Number::from_f64((f * factor).round() / factor).map(Value::Number)
+        }),
+    };
+
+    // Values that can't be normalized are left as is so they still take part in the comparison.
+    if let Some(normalized) = normalized {
+        *value = normalized;
+    }
+}
+
+fn nanos_per_unit(precision: &TimestampPrecision) -> i64 {
+    match precision {
+        TimestampPrecision::Seconds => 1_000_000_000,
+        TimestampPrecision::Milliseconds => 1_000_000,
+        TimestampPrecision::Microseconds => 1_000,
+        TimestampPrecision::Nanoseconds => 1,
+    }
+}
+
+fn timestamp_to_nanos(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => {
+            let ts = n.as_i64().or_else(|| n.as_f64().map(|f| f as i64))?;
+            let magnitude = ts.unsigned_abs();
+            if magnitude >= 100_000_000_000_000_000 {
+                Some(ts)
+            } else if magnitude >= 100_000_000_000_000 {
+                ts.checked_mul(1_000)
+            } else if magnitude >= 100_000_000_000 {
+                ts.checked_mul(1_000_000)
+            } else {
+                ts.checked_mul(1_000_000_000)
+            }
+        }
+        Value::String(s) => DateTime::parse_from_rfc3339(s)
+            .ok()
+            .and_then(|dt| dt.timestamp_nanos_opt()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn values_match(
+        expected: &Value,
+        observed: &Value,
+        normalizations: &[FieldNormalization],
+    ) -> bool {
+        normalize_value(expected, normalizations) == normalize_value(observed, normalizations)
+    }
+
+    fn timestamp(field: &str, precision: TimestampPrecision) -> FieldNormalization {
+        FieldNormalization {
+            field: field.to_string(),
+            precision: PrecisionNormalization::Timestamp { precision },
+        }
+    }
+
+    #[test]
+    fn test_timestamp_ns_vs_ms() {
+        let normalizations = vec![timestamp("ts", TimestampPrecision::Milliseconds)];
+
+        let expected = json!({ "ts": 1_700_000_000_123_i64, "id": 1 });
+        let observed = json!({ "ts": 1_700_000_000_123_456_789_i64, "id": 1 });
+
+        assert!(!values_match(&expected, &observed, &[]));
+        assert!(values_match(&expected, &observed, &normalizations));
+    }
+
+    #[test]
+    fn test_timestamp_string_rounded_to_seconds() {
+        let normalizations = vec![timestamp("after.*.ts", TimestampPrecision::Seconds)];
+
+        let expected = json!({ "after": { "a": { "ts": 1_700_000_000 } } });
+        let observed = json!({ "after": { "a": { "ts": "2023-11-14T22:13:20.999Z" } } });
+
+        assert!(values_match(&expected, &observed, &normalizations));
+    }
+
+    #[test]
+    fn test_float_decimals() {
+        let normalizations = vec![FieldNormalization {
+            field: "readings.*".to_string(),
+            precision: PrecisionNormalization::Float { decimals: 2 },
+        }];
+
+        let expected = json!({ "readings": [21.5, 0.33] });
+        let observed = json!({ "readings": [21.50001, 0.3349] });
+
+        assert!(values_match(&expected, &observed, &normalizations));
+        assert!(!values_match(
+            &json!({ "readings": [0.34] }),
+            &observed,
+            &normalizations
+        ));
+    }
+
+    #[test]
+    fn test_unmatched_fields_untouched() {
+        let normalizations = vec![timestamp("missing.ts", TimestampPrecision::Seconds)];
+        let value = json!({ "ts": "not a timestamp", "n": 1.23456 });
+
+        assert_eq!(normalize_value(&value, &normalizations), value);
+    }
+
+    #[test]
+    fn test_deserialize_field_normalization() {
+        let normalization: FieldNormalization = serde_json::from_str(
+            r#"{ "field": "ts", "kind": "Timestamp", "precision": "Seconds" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(normalization, timestamp("ts", TimestampPrecision::Seconds));
+    }
+}
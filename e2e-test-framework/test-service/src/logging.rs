@@ -0,0 +1,48 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+// The format of the TestService's log output. Log levels are still set through RUST_LOG.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    // The env_logger default, for reading in a terminal.
+    #[default]
+    Text,
+    // One JSON object per line, for log pipelines.
+    Json,
+}
+
+pub fn init_logging(format: LogFormat) {
+    let mut builder = env_logger::Builder::from_default_env();
+    if format == LogFormat::Json {
+        builder.format(|buf, record| writeln!(buf, "{}", json_log_line(record)));
+    }
+    builder.init();
+}
+
+// serde_json escapes newlines in the message, so every record stays on one line.
+fn json_log_line(record: &log::Record) -> String {
+    serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true),
+        "level": record.level().to_string(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    })
+    .to_string()
+}
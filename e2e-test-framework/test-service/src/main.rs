@@ -20,6 +20,9 @@ use serde::{Deserialize, Serialize};
 use test_data_store::{TestDataStore, TestDataStoreConfig};
 use test_run_host::{TestRunHost, TestRunHostConfig};
 
+use logging::LogFormat;
+
+mod logging;
 mod openapi;
 mod web_api;
 
@@ -52,6 +55,23 @@ pub struct HostParams {
         default_value_t = 63123
     )]
     pub port: u16,
+
+    // The path prefix all Web API routes are served under, e.g. "/test-service" when running
+    // behind a reverse proxy that routes a subpath to the TestService.
+    // If not provided, routes are served from the root.
+    #[arg(short = 'b', long = "base-path", env = "DRASI_BASE_PATH")]
+    pub base_path: Option<String>,
+
+    // The format of the log output, either text or json. JSON logs have one object per line
+    // with timestamp, level, target and message fields.
+    // If not provided, logs are written as text.
+    #[arg(
+        long = "log-format",
+        env = "DRASI_LOG_FORMAT",
+        value_enum,
+        default_value_t = LogFormat::Text
+    )]
+    pub log_format: LogFormat,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -67,11 +87,11 @@ pub struct TestServiceConfig {
 // The main function that starts the starts the Test Service.
 #[tokio::main]
 async fn main() {
-    // Initialize env_logger - back to simple init to respect RUST_LOG env var
-    env_logger::init();
-
     // Parse the command line and env var args. If the args are invalid, return an error.
     let host_params = HostParams::parse();
+
+    // Initialize env_logger, which respects the RUST_LOG env var, in the requested format.
+    logging::init_logging(host_params.log_format);
     log::info!("Started Test Service with - {:?}", host_params);
 
     // Load the config from a file if a path is specified in the HostParams.
@@ -151,6 +171,7 @@ async fn main() {
     // Start the Web API.
     web_api::start_web_api(
         host_params.port,
+        host_params.base_path,
         test_data_store,
         test_run_host,
         data_collector,
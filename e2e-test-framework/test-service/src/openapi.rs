@@ -18,8 +18,8 @@ use serde_json::json;
 use utoipa::{OpenApi, ToSchema};
 
 use crate::web_api::{
-    repo, test_runs, DataCollectorStateResponse, TestDataStoreStateResponse,
-    TestRunHostStateResponse, TestRunSummary, TestServiceStateResponse,
+    data_collections, repo, test_runs, DataCollectorStateResponse, HealthResponse,
+    TestDataStoreStateResponse, TestRunHostStateResponse, TestRunSummary, TestServiceStateResponse,
 };
 
 /// Standard error response for all API endpoints
@@ -38,6 +38,7 @@ pub struct ErrorResponse {
 #[openapi(
     paths(
         crate::web_api::get_service_info_handler,
+        crate::web_api::get_health_handler,
         // Repository endpoints
         repo::get_test_repo_list_handler,
         repo::get_test_repo_handler,
@@ -48,22 +49,37 @@ pub struct ErrorResponse {
         repo::get_test_repo_test_source_list_handler,
         repo::get_test_repo_test_source_handler,
         repo::post_test_repo_test_source_handler,
+        // Data Collection endpoints
+        data_collections::get_data_collection_handler,
+        data_collections::get_data_collection_records_handler,
         // Test Run endpoints
         test_runs::create_test_run,
         test_runs::list_test_runs,
         test_runs::get_test_run,
         test_runs::delete_test_run,
+        test_runs::export_test_run,
+        test_runs::import_test_run,
         test_runs::start_test_run,
         test_runs::stop_test_run,
+        test_runs::reset_test_run,
+        test_runs::wait_for_test_run,
+        test_runs::compare_test_runs,
+        test_runs::start_all_test_runs,
+        test_runs::stop_all_test_runs,
         // Test Run Source endpoints
+        test_runs::list_all_test_run_sources,
         test_runs::list_test_run_sources,
         test_runs::create_test_run_source,
         test_runs::get_test_run_source,
+        test_runs::describe_test_run_source,
+        test_runs::stream_test_run_source_events,
         test_runs::delete_test_run_source,
         test_runs::start_test_run_source,
         test_runs::stop_test_run_source,
         test_runs::pause_test_run_source,
         test_runs::reset_test_run_source,
+        test_runs::step_test_run_source_to,
+        test_runs::skip_test_run_source_to_end,
         // Test Run Query endpoints
         test_runs::list_test_run_queries,
         test_runs::create_test_run_query,
@@ -73,6 +89,7 @@ pub struct ErrorResponse {
         test_runs::stop_test_run_query,
         test_runs::pause_test_run_query,
         test_runs::reset_test_run_query,
+        test_runs::get_test_run_query_logger_output,
         // Test Run Reaction endpoints
         test_runs::list_test_run_reactions,
         test_runs::create_test_run_reaction,
@@ -98,21 +115,27 @@ pub struct ErrorResponse {
             TestRunHostStateResponse,
             TestRunSummary,
             DataCollectorStateResponse,
+            HealthResponse,
             // Repository schemas
             repo::TestRepoResponse,
             repo::TestPostBody,
             repo::TestResponse,
             repo::TestSourcePostBody,
             repo::TestSourceResponse,
+            // Data Collection schemas
+            data_collections::DataCollectionRecordsParams,
             // Test Run schemas
             test_runs::TestRunCreatedResponse,
             test_runs::TestRunInfo,
+            test_runs::TestRunSourceListEntry,
+            test_runs::TestRunWaitResponse,
         )
     ),
     tags(
         (name = "service", description = "Test Service general information"),
         (name = "test-runs", description = "Test Run management API - hierarchical structure for organizing test components"),
-        (name = "repos", description = "Test repository management API")
+        (name = "repos", description = "Test repository management API"),
+        (name = "data-collections", description = "Data collection inspection API")
     ),
     info(
         title = "Drasi Test Service API",
@@ -0,0 +1,139 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use test_data_store::{data_collection_storage::DataCollectionStorage, TestDataStore};
+
+use super::TestServiceWebApiError;
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DataCollectionRecordsParams {
+    /// Index of the first record to return. Defaults to 0.
+    #[serde(default)]
+    pub offset: usize,
+    /// Maximum number of records to return. If not set, all remaining records are returned.
+    pub limit: Option<usize>,
+}
+
+pub fn get_data_collection_routes() -> Router {
+    Router::new()
+        .route("/:data_collection_id", get(get_data_collection_handler))
+        .route(
+            "/:data_collection_id/records",
+            get(get_data_collection_records_handler),
+        )
+}
+
+// Looks up an existing data collection. Getting the storage of an unknown collection would
+// create it, so check for it first.
+async fn get_existing_data_collection(
+    test_data_store: &TestDataStore,
+    data_collection_id: &str,
+) -> anyhow::Result<DataCollectionStorage, TestServiceWebApiError> {
+    if !test_data_store
+        .contains_data_collection(data_collection_id)
+        .await?
+    {
+        return Err(TestServiceWebApiError::NotFound(
+            "DataCollection".to_string(),
+            data_collection_id.to_string(),
+        ));
+    }
+
+    Ok(test_data_store
+        .get_data_collection_storage(data_collection_id)
+        .await?)
+}
+
+/// Get a data collection's metadata
+///
+/// Returns the number of records stored in the collection, in total and per source, along
+/// with the number of files, their total size in bytes, and when the collection was created.
+#[utoipa::path(
+    get,
+    path = "/data_collections/{data_collection_id}",
+    tag = "data-collections",
+    params(
+        ("data_collection_id" = String, Path, description = "Data collection identifier")
+    ),
+    responses(
+        (status = 200, description = "Data collection metadata"),
+        (status = 404, description = "Data collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_data_collection_handler(
+    Path(data_collection_id): Path<String>,
+    test_data_store: Extension<Arc<TestDataStore>>,
+) -> anyhow::Result<impl IntoResponse, TestServiceWebApiError> {
+    log::info!(
+        "Processing call - get_data_collection - data_collection_id:{}",
+        data_collection_id
+    );
+
+    let data_collection =
+        get_existing_data_collection(&test_data_store, &data_collection_id).await?;
+    Ok(Json(data_collection.get_summary().await?).into_response())
+}
+
+/// Get a page of a data collection's records
+///
+/// Returns the records stored in the collection's JSONL files from `offset`, up to `limit`
+/// of them, along with the total number of records.
+#[utoipa::path(
+    get,
+    path = "/data_collections/{data_collection_id}/records",
+    tag = "data-collections",
+    params(
+        ("data_collection_id" = String, Path, description = "Data collection identifier"),
+        ("offset" = Option<usize>, Query, description = "Index of the first record to return (default 0)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of records to return (default all)")
+    ),
+    responses(
+        (status = 200, description = "A page of the data collection's records"),
+        (status = 404, description = "Data collection not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+pub async fn get_data_collection_records_handler(
+    Path(data_collection_id): Path<String>,
+    Query(params): Query<DataCollectionRecordsParams>,
+    test_data_store: Extension<Arc<TestDataStore>>,
+) -> anyhow::Result<impl IntoResponse, TestServiceWebApiError> {
+    log::info!(
+        "Processing call - get_data_collection_records - data_collection_id:{}, offset:{}, limit:{:?}",
+        data_collection_id,
+        params.offset,
+        params.limit
+    );
+
+    let data_collection =
+        get_existing_data_collection(&test_data_store, &data_collection_id).await?;
+    Ok(Json(
+        data_collection
+            .get_record_page(params.offset, params.limit)
+            .await?,
+    )
+    .into_response())
+}
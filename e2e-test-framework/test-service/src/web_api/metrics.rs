@@ -0,0 +1,213 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use axum::{extract::Extension, http::header, response::IntoResponse};
+
+use test_data_store::test_run_storage::{
+    TestRunId, TestRunQueryId, TestRunReactionId, TestRunSourceId,
+};
+use test_run_host::{TestRunHost, TestRunStatus};
+
+use super::TestServiceWebApiError;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+const TEST_RUN_STATUSES: [&str; 4] = ["Initialized", "Running", "Stopped", "Error"];
+
+// A metric family in the Prometheus text exposition format. Samples are keyed by their
+// rendered label set so the output is stable between scrapes.
+struct MetricFamily {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: BTreeMap<String, u64>,
+}
+
+impl MetricFamily {
+    fn new(name: &'static str, help: &'static str, kind: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            samples: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, labels: &[(&str, &str)], value: u64) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape_label_value(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.samples.insert(labels, value);
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind);
+        for (labels, value) in self.samples.iter() {
+            let _ = writeln!(out, "{}{{{}}} {}", self.name, labels, value);
+        }
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn test_run_status_name(status: &TestRunStatus) -> &'static str {
+    match status {
+        TestRunStatus::Initialized => "Initialized",
+        TestRunStatus::Running => "Running",
+        TestRunStatus::Stopped => "Stopped",
+        TestRunStatus::Error(_) => "Error",
+    }
+}
+
+// Renders the TestRunHost's TestRuns and their components as Prometheus metrics. The values
+// come from the same state the rest of the API reports. Components that are removed while
+// the metrics are being collected are skipped.
+pub(super) async fn get_metrics_handler(
+    test_run_host: Extension<Arc<TestRunHost>>,
+) -> anyhow::Result<impl IntoResponse, TestServiceWebApiError> {
+    log::debug!("Processing call - metrics");
+
+    let mut test_run_status = MetricFamily::new(
+        "drasi_test_run_status",
+        "Whether the TestRun is in the given status.",
+        "gauge",
+    );
+    let mut source_change_events = MetricFamily::new(
+        "drasi_test_source_change_events_total",
+        "Source change events dispatched by the TestRunSource since it was created or reset.",
+        "counter",
+    );
+    let mut query_bootstrap_results = MetricFamily::new(
+        "drasi_test_query_bootstrap_results_total",
+        "Bootstrap result records received by the TestRunQuery.",
+        "counter",
+    );
+    let mut query_change_results = MetricFamily::new(
+        "drasi_test_query_change_results_total",
+        "Change result records received by the TestRunQuery.",
+        "counter",
+    );
+    let mut reaction_invocations = MetricFamily::new(
+        "drasi_test_reaction_invocations_total",
+        "Invocations received by the TestRunReaction.",
+        "counter",
+    );
+
+    for id in test_run_host.get_test_run_ids().await? {
+        let test_run_id = match TestRunId::try_from(id.as_str()) {
+            Ok(test_run_id) => test_run_id,
+            Err(_) => continue,
+        };
+        let status = match test_run_host.get_test_run_status(&test_run_id).await {
+            Ok(status) => status,
+            Err(_) => continue,
+        };
+        let current = test_run_status_name(&status);
+        for name in TEST_RUN_STATUSES {
+            test_run_status.add(
+                &[
+                    ("test_repo_id", test_run_id.test_repo_id.as_str()),
+                    ("test_id", test_run_id.test_id.as_str()),
+                    ("test_run_id", test_run_id.test_run_id.as_str()),
+                    ("status", name),
+                ],
+                (name == current) as u64,
+            );
+        }
+    }
+
+    for id in test_run_host.get_test_source_ids().await? {
+        if let (Ok(source_id), Ok(state)) = (
+            TestRunSourceId::try_from(id.as_str()),
+            test_run_host.get_test_source_state(&id).await,
+        ) {
+            source_change_events.add(
+                &component_labels(
+                    &source_id.test_run_id,
+                    "source_id",
+                    &source_id.test_source_id,
+                ),
+                state.source_change_generator.event_count,
+            );
+        }
+    }
+
+    for id in test_run_host.get_test_query_ids().await? {
+        if let (Ok(query_id), Ok(state)) = (
+            TestRunQueryId::try_from(id.as_str()),
+            test_run_host.get_test_query_state(&id).await,
+        ) {
+            let labels =
+                component_labels(&query_id.test_run_id, "query_id", &query_id.test_query_id);
+            let metrics = &state.query_observer.result_summary.observer_metrics;
+            query_bootstrap_results.add(&labels, metrics.result_stream_bootstrap_record_count);
+            query_change_results.add(&labels, metrics.result_stream_change_record_count);
+        }
+    }
+
+    for id in test_run_host.get_test_reaction_ids().await? {
+        if let (Ok(reaction_id), Ok(state)) = (
+            TestRunReactionId::try_from(id.as_str()),
+            test_run_host.get_test_reaction_state(&id).await,
+        ) {
+            reaction_invocations.add(
+                &component_labels(
+                    &reaction_id.test_run_id,
+                    "reaction_id",
+                    &reaction_id.test_reaction_id,
+                ),
+                state
+                    .reaction_observer
+                    .result_summary
+                    .reaction_invocation_count,
+            );
+        }
+    }
+
+    let mut body = String::new();
+    for family in [
+        &test_run_status,
+        &source_change_events,
+        &query_bootstrap_results,
+        &query_change_results,
+        &reaction_invocations,
+    ] {
+        family.render(&mut body);
+    }
+
+    Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], body))
+}
+
+fn component_labels<'a>(
+    test_run_id: &'a TestRunId,
+    component_label: &'a str,
+    component_id: &'a str,
+) -> [(&'a str, &'a str); 4] {
+    [
+        ("test_repo_id", test_run_id.test_repo_id.as_str()),
+        ("test_id", test_run_id.test_id.as_str()),
+        ("test_run_id", test_run_id.test_run_id.as_str()),
+        (component_label, component_id),
+    ]
+}
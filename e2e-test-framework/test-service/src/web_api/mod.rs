@@ -12,21 +12,29 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use axum::{
-    extract::Extension,
-    http::StatusCode,
+    body::{Body, BoxBody},
+    extract::{Extension, Query},
+    http::{HeaderMap, Request, StatusCode},
     response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{select, signal};
+use tower_http::{
+    request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer},
+    trace::TraceLayer,
+};
+use tracing::Span;
 use utoipa::{OpenApi, ToSchema};
 
+use data_collections::get_data_collection_routes;
 use data_collector::DataCollector;
+use metrics::get_metrics_handler;
 use repo::get_test_repo_routes;
 use test_data_store::{test_run_storage::TestRunId, TestDataStore};
 use test_run_host::TestRunHost;
@@ -36,6 +44,8 @@ use std::collections::HashMap;
 
 use crate::openapi::ApiDoc;
 
+pub mod data_collections;
+pub mod metrics;
 pub mod repo;
 pub mod test_runs;
 
@@ -45,10 +55,14 @@ pub enum TestServiceWebApiError {
     AnyhowError(anyhow::Error),
     #[error("{0} with ID {1} not found")]
     NotFound(String, String),
+    #[error("BadRequest: {0}")]
+    BadRequest(String),
     #[error("Serde Error: {0}")]
     SerdeJsonError(serde_json::Error),
     #[error("NotReady: {0}")]
     NotReady(String),
+    #[error("NotSupported: {0}")]
+    NotSupported(String),
     #[error("IO Error: {0}")]
     IOError(std::io::Error),
 }
@@ -82,12 +96,18 @@ impl IntoResponse for TestServiceWebApiError {
                 Json(format!("{} with ID {} not found", kind, id)),
             )
                 .into_response(),
+            TestServiceWebApiError::BadRequest(msg) => {
+                (StatusCode::BAD_REQUEST, Json(msg)).into_response()
+            }
             TestServiceWebApiError::SerdeJsonError(e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(e.to_string())).into_response()
             }
             TestServiceWebApiError::NotReady(msg) => {
                 (StatusCode::SERVICE_UNAVAILABLE, Json(msg)).into_response()
             }
+            TestServiceWebApiError::NotSupported(msg) => {
+                (StatusCode::BAD_REQUEST, Json(msg)).into_response()
+            }
             TestServiceWebApiError::IOError(e) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, Json(e.to_string())).into_response()
             }
@@ -207,33 +227,108 @@ pub struct DataCollectorStateResponse {
     pub data_collection_ids: Vec<String>,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "status": "Unhealthy",
+    "failing_components": ["test_repo.test_id.run_001.facilities-db"]
+}))]
+pub struct HealthResponse {
+    /// Either Healthy or Unhealthy
+    pub status: String,
+    /// IDs of the components that failed the health check
+    pub failing_components: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct HealthParams {
+    /// If true, Drasi Servers in an Error state also fail the health check.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+// Normalizes a configured base path to either an empty string (serve from the root) or a
+// path with a leading slash and no trailing slash, e.g. "test-service/" -> "/test-service".
+fn normalize_base_path(base_path: Option<String>) -> String {
+    match base_path {
+        Some(path) => {
+            let trimmed = path.trim().trim_matches('/');
+            if trimmed.is_empty() {
+                String::new()
+            } else {
+                format!("/{}", trimmed)
+            }
+        }
+        None => String::new(),
+    }
+}
+
 pub(crate) async fn start_web_api(
     port: u16,
+    base_path: Option<String>,
     test_data_store: Arc<TestDataStore>,
     test_run_host: Arc<TestRunHost>,
     data_collector: Arc<DataCollector>,
 ) {
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let base_path = normalize_base_path(base_path);
 
     // Create the main API router
     let api_router = Router::new()
         .route("/", get(get_service_info_handler))
+        .route("/metrics", get(get_metrics_handler))
+        .route("/health", get(get_health_handler))
         .nest("/test_repos", get_test_repo_routes())
+        .nest("/data_collections", get_data_collection_routes())
         // Hierarchical API routes
         .merge(get_test_runs_routes());
 
+    // Mount the API under the base path if one is configured.
+    let api_router = if base_path.is_empty() {
+        api_router
+    } else {
+        Router::new().nest(&base_path, api_router)
+    };
+
+    // The OpenAPI servers entry tells Swagger UI where to send requests, so it must include
+    // the base path.
+    let mut openapi = ApiDoc::openapi();
+    if !base_path.is_empty() {
+        openapi.servers = Some(vec![utoipa::openapi::Server::new(base_path.clone())]);
+    }
+
     // Create the complete application with Swagger UI
     let app = api_router
-        .merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .merge(
+            SwaggerUi::new(format!("{}/docs", base_path))
+                .url(format!("{}/api-docs/openapi.json", base_path), openapi),
+        )
         .layer(axum::extract::Extension(data_collector))
         .layer(axum::extract::Extension(test_data_store.clone()))
-        .layer(axum::extract::Extension(test_run_host));
+        .layer(axum::extract::Extension(test_run_host))
+        // Layers added last run first: the request ID is assigned before the request is
+        // traced, and copied to the response before the response is traced.
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .on_request(log_request)
+                .on_response(log_response),
+        )
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid));
 
-    log::info!("Test Service Web API listening on http://{}", addr);
-    log::info!("API Documentation available at http://{}/docs", addr);
     log::info!(
-        "OpenAPI JSON specification available at http://{}/api-docs/openapi.json",
-        addr
+        "Test Service Web API listening on http://{}{}",
+        addr,
+        base_path
+    );
+    log::info!(
+        "API Documentation available at http://{}{}/docs",
+        addr,
+        base_path
+    );
+    log::info!(
+        "OpenAPI JSON specification available at http://{}{}/api-docs/openapi.json",
+        addr,
+        base_path
     );
 
     let server = axum::Server::bind(&addr).serve(app.into_make_service());
@@ -248,6 +343,31 @@ pub(crate) async fn start_web_api(
     }
 }
 
+fn get_request_id(headers: &HeaderMap) -> &str {
+    headers
+        .get("x-request-id")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("-")
+}
+
+fn log_request(request: &Request<Body>, _span: &Span) {
+    log::info!(
+        "Web API request - id:{}, {} {}",
+        get_request_id(request.headers()),
+        request.method(),
+        request.uri().path()
+    );
+}
+
+fn log_response(response: &Response<BoxBody>, latency: Duration, _span: &Span) {
+    log::info!(
+        "Web API response - id:{}, status:{}, duration:{}ms",
+        get_request_id(response.headers()),
+        response.status(),
+        latency.as_millis()
+    );
+}
+
 /// Handles graceful shutdown signals (SIGINT/Ctrl+C and SIGTERM) for the test service.
 ///
 /// This function performs the following cleanup operations:
@@ -411,6 +531,52 @@ async fn get_service_info_handler(
     }))
 }
 
+/// Check the health of the service
+///
+/// Returns 200 if the TestRunHost is Running and no source, query, or reaction is in an Error
+/// state. Otherwise returns 503 with the ID of the first failing component found.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "service",
+    params(
+        ("strict" = Option<bool>, Query, description = "Also fail if a Drasi Server is in an Error state (default false)")
+    ),
+    responses(
+        (status = 200, description = "Service is healthy", body = HealthResponse),
+        (status = 503, description = "A component is in an Error state", body = HealthResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+async fn get_health_handler(
+    test_run_host: Extension<Arc<TestRunHost>>,
+    Query(params): Query<HealthParams>,
+) -> anyhow::Result<impl IntoResponse, TestServiceWebApiError> {
+    log::debug!("Processing call - health");
+
+    let response = match test_run_host
+        .find_unhealthy_component(params.strict)
+        .await?
+    {
+        None => (
+            StatusCode::OK,
+            Json(HealthResponse {
+                status: "Healthy".to_string(),
+                failing_components: Vec::new(),
+            }),
+        ),
+        Some(component_id) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(HealthResponse {
+                status: "Unhealthy".to_string(),
+                failing_components: vec![component_id],
+            }),
+        ),
+    };
+
+    Ok(response)
+}
+
 /// Extract test run ID from a full resource ID
 /// Format: test_repo_id.test_id.test_run_id.resource_id
 /// Returns: test_repo_id.test_id.test_run_id
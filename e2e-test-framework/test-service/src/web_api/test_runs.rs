@@ -12,20 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use axum::{
-    extract::{Extension, Path},
+    extract::{Extension, Path, Query},
     http::StatusCode,
-    response::IntoResponse,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
     routing::{get, post},
     Json, Router,
 };
+use futures::{stream, Stream};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
 use utoipa::ToSchema;
 
-use test_data_store::test_run_storage::TestRunId;
-use test_run_host::{TestRunConfig, TestRunStatus};
+use test_data_store::{
+    test_repo_storage::models::SpacingMode,
+    test_run_storage::{TestRunId, TestRunSourceId, TestRunSummaries},
+};
+use test_run_host::{
+    sources::source_change_generators::{
+        SourceChangeEventSummary, SourceChangeGeneratorError, SourceChangeGeneratorStatus,
+    },
+    summary_comparison::DEFAULT_COMPARISON_THRESHOLD_PCT,
+    TestRunConfig, TestRunStatus, TestSourceFilter,
+};
 
 use super::TestServiceWebApiError;
 
@@ -45,28 +59,114 @@ pub struct TestRunInfo {
     pub status: TestRunStatus,
 }
 
+// Bounds on how long a wait request may hold its connection open.
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 30_000;
+const MAX_WAIT_TIMEOUT_MS: u64 = 300_000;
+
+#[derive(Deserialize, ToSchema)]
+pub struct TestRunWaitParams {
+    /// How long to wait for the test run to complete, capped at 300000 ms.
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Default, Deserialize, ToSchema)]
+pub struct TestRunSourceListParams {
+    pub test_repo_id: Option<String>,
+    pub test_id: Option<String>,
+    pub test_run_id: Option<String>,
+    /// Only list sources whose change generator has this status, e.g. Running or Paused.
+    #[schema(value_type = Option<String>)]
+    pub status: Option<SourceChangeGeneratorStatus>,
+    /// Return each source's ID and status instead of just its ID.
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TestRunSourceListEntry {
+    pub id: String,
+    #[schema(value_type = String)]
+    pub status: SourceChangeGeneratorStatus,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TestRunWaitResponse {
+    pub id: String,
+    #[serde(serialize_with = "serialize_status")]
+    #[schema(value_type = String)]
+    pub status: TestRunStatus,
+    /// True if the test run did not reach a terminal status before the timeout.
+    pub timed_out: bool,
+    #[schema(value_type = Object)]
+    pub summary: TestRunSummaries,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TestRunSourceResetParams {
+    /// Seed used in place of the source's configured seed. If not set, the configured seed is reused.
+    pub seed: Option<u64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TestRunSourceStepToParams {
+    /// Event sequence number to step to. Must be greater than the source's current sequence number.
+    pub target_seq: u64,
+    /// Spacing mode used while stepping, e.g. none, recorded, or a rate in events per second.
+    #[schema(value_type = Option<String>)]
+    pub spacing_mode: Option<SpacingMode>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TestRunQueryLoggerOutputParams {
+    /// Index of the first result to return. Out of range offsets are clamped. Defaults to 0.
+    pub offset: Option<i64>,
+    /// Maximum number of results to return. If not set, all remaining results are returned.
+    pub limit: Option<usize>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TestRunCompareParams {
+    /// ID of the baseline test run.
+    pub a: String,
+    /// ID of the candidate test run.
+    pub b: String,
+    /// Relative change, as a percentage, beyond which a metric is flagged. Defaults to 10.
+    pub threshold_pct: Option<f64>,
+}
+
 fn serialize_status<S>(status: &TestRunStatus, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    let status_str = match status {
-        TestRunStatus::Initialized => "Initialized",
-        TestRunStatus::Running => "Running",
-        TestRunStatus::Stopped => "Stopped",
-        TestRunStatus::Error(msg) => return serializer.serialize_str(&format!("Error: {}", msg)),
-    };
-    serializer.serialize_str(status_str)
+    serializer.serialize_str(&status_str(status))
+}
+
+fn status_str(status: &TestRunStatus) -> String {
+    match status {
+        TestRunStatus::Initialized => "Initialized".to_string(),
+        TestRunStatus::Running => "Running".to_string(),
+        TestRunStatus::Stopped => "Stopped".to_string(),
+        TestRunStatus::Error(msg) => format!("Error: {}", msg),
+    }
 }
 
 pub fn get_test_runs_routes() -> Router {
     Router::new()
         .route("/api/test_runs", post(create_test_run).get(list_test_runs))
+        .route("/api/test_runs/compare", get(compare_test_runs))
+        .route("/api/test_runs/import", post(import_test_run))
+        .route("/api/test_runs/start_all", post(start_all_test_runs))
+        .route("/api/test_runs/stop_all", post(stop_all_test_runs))
+        .route("/api/sources", get(list_all_test_run_sources))
         .route(
             "/api/test_runs/:run_id",
             get(get_test_run).delete(delete_test_run),
         )
         .route("/api/test_runs/:run_id/start", post(start_test_run))
         .route("/api/test_runs/:run_id/stop", post(stop_test_run))
+        .route("/api/test_runs/:run_id/reset", post(reset_test_run))
+        .route("/api/test_runs/:run_id/wait", get(wait_for_test_run))
+        .route("/api/test_runs/:run_id/export", get(export_test_run))
         // Nested routes for components
         .route(
             "/api/test_runs/:run_id/sources",
@@ -76,6 +176,14 @@ pub fn get_test_runs_routes() -> Router {
             "/api/test_runs/:run_id/sources/:source_id",
             get(get_test_run_source).delete(delete_test_run_source),
         )
+        .route(
+            "/api/test_runs/:run_id/sources/:source_id/describe",
+            get(describe_test_run_source),
+        )
+        .route(
+            "/api/test_runs/:run_id/sources/:source_id/events",
+            get(stream_test_run_source_events),
+        )
         .route(
             "/api/test_runs/:run_id/sources/:source_id/start",
             post(start_test_run_source),
@@ -92,6 +200,14 @@ pub fn get_test_runs_routes() -> Router {
             "/api/test_runs/:run_id/sources/:source_id/reset",
             post(reset_test_run_source),
         )
+        .route(
+            "/api/test_runs/:run_id/sources/:source_id/step_to",
+            post(step_test_run_source_to),
+        )
+        .route(
+            "/api/test_runs/:run_id/sources/:source_id/skip_to_end",
+            post(skip_test_run_source_to_end),
+        )
         .route(
             "/api/test_runs/:run_id/queries",
             get(list_test_run_queries).post(create_test_run_query),
@@ -116,6 +232,10 @@ pub fn get_test_runs_routes() -> Router {
             "/api/test_runs/:run_id/queries/:query_id/reset",
             post(reset_test_run_query),
         )
+        .route(
+            "/api/test_runs/:run_id/queries/:query_id/logger_output",
+            get(get_test_run_query_logger_output),
+        )
         .route(
             "/api/test_runs/:run_id/reactions",
             get(list_test_run_reactions).post(create_test_run_reaction),
@@ -273,6 +393,72 @@ pub async fn delete_test_run(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Export a test run
+///
+/// Returns the test run's effective configuration, including any generated seeds, as a
+/// single JSON document that can be imported to recreate the test run. Secrets are redacted.
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/{run_id}/export",
+    params(
+        ("run_id" = String, Path, description = "Test run ID")
+    ),
+    responses(
+        (status = 200, description = "Exported test run", body = Object),
+        (status = 404, description = "Test run not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn export_test_run(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path(run_id): Path<String>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let test_run_id = TestRunId::try_from(run_id.as_str())
+        .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
+
+    if test_run_host
+        .get_test_run_status(&test_run_id)
+        .await
+        .is_err()
+    {
+        return Err(TestServiceWebApiError::NotFound(
+            "TestRun".to_string(),
+            run_id,
+        ));
+    }
+
+    let export = test_run_host.export_test_run(&test_run_id).await?;
+    Ok(Json(export))
+}
+
+/// Import a test run
+///
+/// Recreates a test run from a document returned by the export endpoint.
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/import",
+    request_body = Object,
+    responses(
+        (status = 201, description = "Test run created successfully", body = TestRunCreatedResponse),
+        (status = 400, description = "Invalid export"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn import_test_run(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Json(export): Json<serde_json::Value>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let config = test_run_host::TestRunHost::import_test_run(&export)?;
+    let id = test_run_host.add_test_run(config).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(TestRunCreatedResponse { id: id.to_string() }),
+    ))
+}
+
 /// Start a test run
 #[utoipa::path(
     post,
@@ -323,15 +509,280 @@ pub async fn stop_test_run(
     Ok(StatusCode::OK)
 }
 
+/// Reset a stopped test run
+///
+/// Resets every source, query and reaction of the test run to its initial state so the test
+/// run can be started again. The test run must be Stopped, and stays Stopped.
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/{run_id}/reset",
+    params(
+        ("run_id" = String, Path, description = "Test run ID")
+    ),
+    responses(
+        (status = 200, description = "Test run reset successfully"),
+        (status = 404, description = "Test run not found"),
+        (status = 500, description = "Test run is not stopped or a component failed to reset")
+    ),
+    tag = "test-runs"
+)]
+pub async fn reset_test_run(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path(run_id): Path<String>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let test_run_id = TestRunId::try_from(run_id.as_str())
+        .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
+
+    test_run_host.reset_test_run(&test_run_id).await?;
+    Ok(StatusCode::OK)
+}
+
+/// Start all test runs
+///
+/// Starts every test run that isn't already Running. A failure to start one test run doesn't
+/// prevent the others from starting; the response maps each test run ID to its resulting
+/// status, or the error that prevented it from starting.
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/start_all",
+    responses(
+        (status = 200, description = "Status of each test run", body = BTreeMap<String, String>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn start_all_test_runs(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let mut results = BTreeMap::new();
+
+    for run_id in test_run_host.get_test_run_ids().await? {
+        let result = async {
+            let test_run_id =
+                TestRunId::try_from(run_id.as_str()).map_err(|e| anyhow::anyhow!(e))?;
+            if test_run_host.get_test_run_status(&test_run_id).await? != TestRunStatus::Running {
+                test_run_host.start_test_run(&test_run_id).await?;
+            }
+            test_run_host.get_test_run_status(&test_run_id).await
+        }
+        .await;
+
+        results.insert(run_id, bulk_result_str(result));
+    }
+
+    Ok(Json(results))
+}
+
+/// Stop all test runs
+///
+/// Stops every test run that isn't already Stopped or in Error. A failure to stop one test run
+/// doesn't prevent the others from stopping; the response maps each test run ID to its
+/// resulting status, or the error that prevented it from stopping.
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/stop_all",
+    responses(
+        (status = 200, description = "Status of each test run", body = BTreeMap<String, String>),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn stop_all_test_runs(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let mut results = BTreeMap::new();
+
+    for run_id in test_run_host.get_test_run_ids().await? {
+        let result = async {
+            let test_run_id =
+                TestRunId::try_from(run_id.as_str()).map_err(|e| anyhow::anyhow!(e))?;
+            if !test_run_host
+                .get_test_run_status(&test_run_id)
+                .await?
+                .is_terminal()
+            {
+                test_run_host.stop_test_run(&test_run_id).await?;
+            }
+            test_run_host.get_test_run_status(&test_run_id).await
+        }
+        .await;
+
+        results.insert(run_id, bulk_result_str(result));
+    }
+
+    Ok(Json(results))
+}
+
+fn bulk_result_str(result: anyhow::Result<TestRunStatus>) -> String {
+    match result {
+        Ok(status) => status_str(&status),
+        Err(e) => format!("Error: {}", e),
+    }
+}
+
+/// Compare the summaries of two test runs
+///
+/// Returns the change in every numeric summary metric between the baseline run `a` and the
+/// candidate run `b`, flagging metrics whose relative change exceeds `threshold_pct`.
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/compare",
+    params(
+        ("a" = String, Query, description = "Baseline test run ID"),
+        ("b" = String, Query, description = "Candidate test run ID"),
+        ("threshold_pct" = Option<f64>, Query, description = "Relative change, as a percentage, beyond which a metric is flagged (default 10)")
+    ),
+    responses(
+        (status = 200, description = "Structured diff of the two test runs' summaries"),
+        (status = 404, description = "Test run not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn compare_test_runs(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Query(params): Query<TestRunCompareParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let run_a = TestRunId::try_from(params.a.as_str())
+        .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
+    let run_b = TestRunId::try_from(params.b.as_str())
+        .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
+
+    let comparison = test_run_host
+        .compare_summaries(
+            &run_a,
+            &run_b,
+            params
+                .threshold_pct
+                .unwrap_or(DEFAULT_COMPARISON_THRESHOLD_PCT),
+        )
+        .await?;
+
+    Ok(Json(comparison))
+}
+
+/// Wait for a test run to complete
+///
+/// Blocks until the test run is Stopped or in Error, or until the timeout elapses, then
+/// returns the final status along with the summaries written by the run's components.
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/{run_id}/wait",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("timeout_ms" = Option<u64>, Query, description = "Maximum time to wait in milliseconds (default 30000, max 300000)")
+    ),
+    responses(
+        (status = 200, description = "Final test run status and summary", body = TestRunWaitResponse),
+        (status = 400, description = "Invalid test run ID"),
+        (status = 404, description = "Test run not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn wait_for_test_run(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path(run_id): Path<String>,
+    Query(params): Query<TestRunWaitParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let test_run_id = TestRunId::try_from(run_id.as_str())
+        .map_err(|e| TestServiceWebApiError::BadRequest(e.to_string()))?;
+
+    if !test_run_host.contains_test_run(&test_run_id).await? {
+        return Err(TestServiceWebApiError::NotFound(
+            "TestRun".to_string(),
+            run_id,
+        ));
+    }
+
+    let timeout_ms = params
+        .timeout_ms
+        .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+        .min(MAX_WAIT_TIMEOUT_MS);
+
+    let status = test_run_host
+        .wait_for_test_run_completion(&test_run_id, std::time::Duration::from_millis(timeout_ms))
+        .await?;
+    let summary = test_run_host.get_test_run_summaries(&test_run_id).await?;
+
+    Ok(Json(TestRunWaitResponse {
+        id: run_id,
+        timed_out: !status.is_terminal(),
+        status,
+        summary,
+    }))
+}
+
 // Source-related endpoints
+
+// Lists the sources that match the filter, as bare IDs or, if verbose, with their status.
+async fn list_sources(
+    test_run_host: &test_run_host::TestRunHost,
+    params: TestRunSourceListParams,
+) -> Result<axum::response::Response, TestServiceWebApiError> {
+    let verbose = params.verbose;
+    let filter = TestSourceFilter {
+        test_repo_id: params.test_repo_id,
+        test_id: params.test_id,
+        test_run_id: params.test_run_id,
+        status: params.status,
+    };
+    let sources = test_run_host.get_test_sources(&filter).await?;
+
+    if verbose {
+        let entries: Vec<TestRunSourceListEntry> = sources
+            .into_iter()
+            .map(|source| TestRunSourceListEntry {
+                id: source.id,
+                status: source.status,
+            })
+            .collect();
+        Ok(Json(entries).into_response())
+    } else {
+        let ids: Vec<String> = sources.into_iter().map(|source| source.id).collect();
+        Ok(Json(ids).into_response())
+    }
+}
+
+/// List sources across all test runs
+///
+/// Returns source IDs, or the ID and status of each source if `verbose` is true. The
+/// results can be narrowed by test repo, test, test run, and source change generator status.
+#[utoipa::path(
+    get,
+    path = "/api/sources",
+    params(
+        ("test_repo_id" = Option<String>, Query, description = "Only list sources in this test repo"),
+        ("test_id" = Option<String>, Query, description = "Only list sources in this test"),
+        ("test_run_id" = Option<String>, Query, description = "Only list sources in test runs with this ID"),
+        ("status" = Option<String>, Query, description = "Only list sources with this status, e.g. Running or Paused"),
+        ("verbose" = Option<bool>, Query, description = "Return the ID and status of each source")
+    ),
+    responses(
+        (status = 200, description = "List of source IDs, or of TestRunSourceListEntry if verbose", body = Vec<String>),
+        (status = 400, description = "Invalid query parameters"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+pub async fn list_all_test_run_sources(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Query(params): Query<TestRunSourceListParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    list_sources(&test_run_host, params).await
+}
+
 #[utoipa::path(
     get,
     path = "/api/test_runs/{run_id}/sources",
     params(
-        ("run_id" = String, Path, description = "Test run ID")
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("status" = Option<String>, Query, description = "Only list sources with this status, e.g. Running or Paused"),
+        ("verbose" = Option<bool>, Query, description = "Return the ID and status of each source")
     ),
     responses(
-        (status = 200, description = "List of source IDs within the test run", body = Vec<String>),
+        (status = 200, description = "List of source IDs within the test run, or of TestRunSourceListEntry if verbose", body = Vec<String>),
+        (status = 400, description = "Invalid query parameters"),
         (status = 500, description = "Internal server error")
     ),
     tag = "test-runs"
@@ -339,18 +790,19 @@ pub async fn stop_test_run(
 async fn list_test_run_sources(
     Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
     Path(run_id): Path<String>,
+    Query(params): Query<TestRunSourceListParams>,
 ) -> Result<impl IntoResponse, TestServiceWebApiError> {
-    let _test_run_id = TestRunId::try_from(run_id.as_str())
+    let test_run_id = TestRunId::try_from(run_id.as_str())
         .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
 
-    // Get all source IDs and filter by test run
-    let all_ids = test_run_host.get_test_source_ids().await?;
-    let filtered: Vec<String> = all_ids
-        .into_iter()
-        .filter(|id| id.starts_with(&run_id))
-        .collect();
-
-    Ok(Json(filtered))
+    // The test run in the path takes the place of any test run filters in the query.
+    let params = TestRunSourceListParams {
+        test_repo_id: Some(test_run_id.test_repo_id),
+        test_id: Some(test_run_id.test_id),
+        test_run_id: Some(test_run_id.test_run_id),
+        ..params
+    };
+    list_sources(&test_run_host, params).await
 }
 
 #[utoipa::path(
@@ -418,6 +870,101 @@ async fn get_test_run_source(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/{run_id}/sources/{source_id}/describe",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("source_id" = String, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Source change generator configuration and derived bounds"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+async fn describe_test_run_source(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, source_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let full_id = format!("{}.{}", run_id, source_id);
+
+    match test_run_host.get_test_source_description(&full_id).await {
+        Ok(description) => Ok(Json(description)),
+        Err(_) => Err(TestServiceWebApiError::NotFound(
+            "Source".to_string(),
+            source_id,
+        )),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/{run_id}/sources/{source_id}/events",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("source_id" = String, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Server-sent event stream with a JSON summary (seq, op, ts_ns) of each dispatched source change event", content_type = "text/event-stream"),
+        (status = 400, description = "Source does not support event streams"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+async fn stream_test_run_source_events(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, source_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let full_id = format!("{}.{}", run_id, source_id);
+
+    if !test_run_host.contains_test_source(&full_id).await? {
+        return Err(TestServiceWebApiError::NotFound(
+            "Source".to_string(),
+            source_id,
+        ));
+    }
+
+    let events_rx = test_run_host
+        .subscribe_test_source_events(&full_id)
+        .await
+        .map_err(|e| match e.downcast_ref::<SourceChangeGeneratorError>() {
+            Some(e @ SourceChangeGeneratorError::EventSubscriptionsNotSupported(_)) => {
+                TestServiceWebApiError::NotSupported(e.to_string())
+            }
+            _ => TestServiceWebApiError::AnyhowError(e),
+        })?;
+
+    Ok(Sse::new(source_change_event_stream(full_id, events_rx)).keep_alive(KeepAlive::default()))
+}
+
+// The generator never waits for subscribers, so a client that falls too far behind is
+// disconnected rather than being sent a stream with gaps in it.
+fn source_change_event_stream(
+    full_id: String,
+    events_rx: broadcast::Receiver<SourceChangeEventSummary>,
+) -> impl Stream<Item = Result<Event, axum::Error>> {
+    stream::unfold(events_rx, move |mut events_rx| {
+        let full_id = full_id.clone();
+        async move {
+            match events_rx.recv().await {
+                Ok(summary) => Some((Event::default().json_data(&summary), events_rx)),
+                Err(RecvError::Lagged(missed)) => {
+                    log::warn!(
+                        "Closing event stream for TestRunSource {} after the subscriber missed {} events",
+                        full_id,
+                        missed
+                    );
+                    None
+                }
+                Err(RecvError::Closed) => None,
+            }
+        }
+    })
+}
+
 #[utoipa::path(
     delete,
     path = "/api/test_runs/{run_id}/sources/{source_id}",
@@ -433,13 +980,25 @@ async fn get_test_run_source(
     tag = "test-runs"
 )]
 async fn delete_test_run_source(
-    Path((_run_id, source_id)): Path<(String, String)>,
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, source_id)): Path<(String, String)>,
 ) -> Result<StatusCode, TestServiceWebApiError> {
-    // TODO: Implement source deletion
-    Err(TestServiceWebApiError::NotReady(format!(
-        "Source deletion not implemented: {}",
-        source_id
-    )))
+    let full_id = format!("{}.{}", run_id, source_id);
+
+    if !test_run_host.contains_test_source(&full_id).await? {
+        return Err(TestServiceWebApiError::NotFound(
+            "Source".to_string(),
+            source_id,
+        ));
+    }
+
+    let test_run_source_id = TestRunSourceId::try_from(full_id.as_str())
+        .map_err(|e| TestServiceWebApiError::AnyhowError(anyhow::anyhow!(e)))?;
+
+    test_run_host
+        .remove_test_source(&test_run_source_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
 #[utoipa::path(
@@ -516,7 +1075,8 @@ async fn pause_test_run_source(
     path = "/api/test_runs/{run_id}/sources/{source_id}/reset",
     params(
         ("run_id" = String, Path, description = "Test run ID"),
-        ("source_id" = String, Path, description = "Source ID")
+        ("source_id" = String, Path, description = "Source ID"),
+        ("seed" = Option<u64>, Query, description = "Seed for sources that generate random changes (default: the configured seed)")
     ),
     responses(
         (status = 200, description = "Source reset successfully"),
@@ -528,9 +1088,65 @@ async fn pause_test_run_source(
 async fn reset_test_run_source(
     Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
     Path((run_id, source_id)): Path<(String, String)>,
+    Query(params): Query<TestRunSourceResetParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let full_id = format!("{}.{}", run_id, source_id);
+    test_run_host
+        .test_source_reset(&full_id, params.seed)
+        .await?;
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/{run_id}/sources/{source_id}/step_to",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("source_id" = String, Path, description = "Source ID"),
+        ("target_seq" = u64, Query, description = "Event sequence number to step to"),
+        ("spacing_mode" = Option<String>, Query, description = "Spacing mode used while stepping (default: the configured spacing mode)")
+    ),
+    responses(
+        (status = 200, description = "Source is stepping to the target sequence number"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+async fn step_test_run_source_to(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, source_id)): Path<(String, String)>,
+    Query(params): Query<TestRunSourceStepToParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let full_id = format!("{}.{}", run_id, source_id);
+    let response = test_run_host
+        .test_source_step_to(&full_id, params.target_seq, params.spacing_mode)
+        .await?;
+    response.result?;
+    Ok(StatusCode::OK)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/test_runs/{run_id}/sources/{source_id}/skip_to_end",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("source_id" = String, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Source is skipping its remaining events"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+async fn skip_test_run_source_to_end(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, source_id)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, TestServiceWebApiError> {
     let full_id = format!("{}.{}", run_id, source_id);
-    test_run_host.test_source_reset(&full_id).await?;
+    let response = test_run_host.test_source_skip_to_end(&full_id).await?;
+    response.result?;
     Ok(StatusCode::OK)
 }
 
@@ -746,6 +1362,49 @@ async fn reset_test_run_query(
     Ok(StatusCode::OK)
 }
 
+/// Get a page of a query's result logger output
+///
+/// Returns the results written by the query's result stream loggers from `offset`, up to
+/// `limit` of them, along with the total number of results.
+#[utoipa::path(
+    get,
+    path = "/api/test_runs/{run_id}/queries/{query_id}/logger_output",
+    params(
+        ("run_id" = String, Path, description = "Test run ID"),
+        ("query_id" = String, Path, description = "Query ID"),
+        ("offset" = Option<i64>, Query, description = "Index of the first result to return (default 0)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of results to return (default all)")
+    ),
+    responses(
+        (status = 200, description = "A page of the query's logger results"),
+        (status = 404, description = "Query not found"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "test-runs"
+)]
+async fn get_test_run_query_logger_output(
+    Extension(test_run_host): Extension<Arc<test_run_host::TestRunHost>>,
+    Path((run_id, query_id)): Path<(String, String)>,
+    Query(params): Query<TestRunQueryLoggerOutputParams>,
+) -> Result<impl IntoResponse, TestServiceWebApiError> {
+    let full_id = format!("{}.{}", run_id, query_id);
+
+    match test_run_host
+        .get_test_query_result_logger_output_page(
+            &full_id,
+            params.offset.unwrap_or(0),
+            params.limit,
+        )
+        .await
+    {
+        Ok(page) => Ok(Json(page)),
+        Err(_) => Err(TestServiceWebApiError::NotFound(
+            "Query".to_string(),
+            query_id,
+        )),
+    }
+}
+
 
 // Reaction-related endpoints
 #[utoipa::path(
@@ -1085,3 +1744,199 @@ async fn delete_test_run_drasi_server(
     test_run_host.remove_test_drasi_server(&server_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, time::Duration};
+
+    use futures::StreamExt;
+
+    use test_data_store::{
+        test_repo_storage::{
+            models::LocalTestDefinition,
+            repo_clients::{CommonTestRepoConfig, LocalStorageTestRepoConfig, TestRepoConfig},
+        },
+        TestDataStore,
+    };
+    use test_run_host::{TestRunHost, TestRunHostConfig};
+
+    use super::*;
+
+    // Serves the test run routes for a TestRun with a Model source (source-001) and a Script
+    // source without a SourceChangeGenerator (source-002). Returns the base URL, the run ID
+    // and the TestRunHost.
+    async fn serve_test_run() -> anyhow::Result<(String, String, Arc<TestRunHost>)> {
+        let data_store = Arc::new(
+            TestDataStore::new_temp(Some(vec![TestRepoConfig::LocalStorage {
+                common_config: CommonTestRepoConfig {
+                    id: "test-repo".to_string(),
+                    local_tests: Vec::new(),
+                },
+                unique_config: LocalStorageTestRepoConfig { source_path: None },
+            }]))
+            .await?,
+        );
+        let test_definition: LocalTestDefinition = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "version": 1,
+            "description": null,
+            "test_folder": null,
+            "sources": [
+                {
+                    "kind": "Model",
+                    "test_source_id": "source-001",
+                    "model_data_generator": {
+                        "kind": "BuildingHierarchy",
+                        "room_sensors": [{
+                            "kind": "NormalFloat",
+                            "id": "temperature",
+                            "value_init": [70.0, 5.0],
+                            "value_change": [1.0, 0.5]
+                        }]
+                    }
+                },
+                { "kind": "Script", "test_source_id": "source-002" }
+            ],
+        }))?;
+        data_store
+            .add_local_test("test-repo", test_definition, false)
+            .await?;
+
+        let test_run_host =
+            Arc::new(TestRunHost::new(TestRunHostConfig::default(), data_store).await?);
+        let config: TestRunConfig = serde_json::from_value(serde_json::json!({
+            "test_id": "test-001",
+            "test_repo_id": "test-repo",
+            "test_run_id": "run-001",
+            "sources": [
+                { "test_source_id": "source-001", "start_mode": "manual" },
+                { "test_source_id": "source-002", "start_mode": "manual" }
+            ],
+        }))?;
+        let run_id = test_run_host.add_test_run(config).await?.to_string();
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let app = get_test_runs_routes().layer(Extension(test_run_host.clone()));
+        tokio::spawn(axum::Server::from_tcp(listener)?.serve(app.into_make_service()));
+
+        Ok((base_url, run_id, test_run_host))
+    }
+
+    #[tokio::test]
+    async fn test_stream_test_run_source_events() -> anyhow::Result<()> {
+        let (base_url, run_id, test_run_host) = serve_test_run().await?;
+        let events_url = |source_id: &str| {
+            format!(
+                "{}/api/test_runs/{}/sources/{}/events",
+                base_url, run_id, source_id
+            )
+        };
+        let client = reqwest::Client::new();
+
+        let response = client.get(events_url("source-003")).send().await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // A source without a generator has no events to stream.
+        let response = client.get(events_url("source-002")).send().await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let mut response = client.get(events_url("source-001")).send().await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The subscription is in place once the response starts, so no events are missed.
+        test_run_host
+            .test_source_step(
+                &format!("{}.source-001", run_id),
+                3,
+                Some(SpacingMode::None),
+            )
+            .await?;
+
+        let mut body = String::new();
+        while body.matches("data:").count() < 3 {
+            match tokio::time::timeout(Duration::from_secs(10), response.chunk()).await?? {
+                Some(chunk) => body.push_str(&String::from_utf8_lossy(&chunk)),
+                None => anyhow::bail!("Event stream ended early: {:?}", body),
+            }
+        }
+
+        let summaries: Vec<serde_json::Value> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|data| serde_json::from_str(data.trim()))
+            .collect::<Result<_, _>>()?;
+        for summary in summaries {
+            assert!(summary["seq"].is_u64());
+            assert!(summary["op"].is_string());
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_test_run() -> anyhow::Result<()> {
+        let (base_url, run_id, test_run_host) = serve_test_run().await?;
+        let wait_url = |run_id: &str| format!("{}/api/test_runs/{}/wait", base_url, run_id);
+        let client = reqwest::Client::new();
+
+        let response = client.get(wait_url("run-001")).send().await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = client
+            .get(wait_url("test-repo.test-001.run-002"))
+            .send()
+            .await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Stopping the TestRun while the request waits on it ends the wait early. source-002
+        // has no generator to stop, so the TestRun ends in Error rather than Stopped, but
+        // either way it is finished.
+        let wait = tokio::spawn(
+            client
+                .get(format!("{}?timeout_ms=30000", wait_url(&run_id)))
+                .send(),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = test_run_host
+            .stop_test_run(&TestRunId::try_from(run_id.as_str())?)
+            .await;
+
+        let response = tokio::time::timeout(Duration::from_secs(10), wait).await???;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = response.json().await?;
+        assert_eq!(body["timed_out"], false);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_source_change_event_stream_closes_when_lagging() {
+        let summary = |seq| SourceChangeEventSummary {
+            seq,
+            op: "i".to_string(),
+            ts_ns: 0,
+        };
+
+        // A subscriber that keeps up gets every event until the feed goes away.
+        let (tx, rx) = broadcast::channel(2);
+        tx.send(summary(1)).unwrap();
+        tx.send(summary(2)).unwrap();
+        drop(tx);
+        let events: Vec<_> = source_change_event_stream("run-001.source-001".to_string(), rx)
+            .collect()
+            .await;
+        assert_eq!(events.len(), 2);
+
+        // One that has fallen further behind than the buffer holds is disconnected rather
+        // than sent the events that are left.
+        let (tx, rx) = broadcast::channel(2);
+        for seq in 1..=3 {
+            tx.send(summary(seq)).unwrap();
+        }
+        let events: Vec<_> = source_change_event_stream("run-001.source-001".to_string(), rx)
+            .collect()
+            .await;
+        assert!(events.is_empty());
+    }
+}
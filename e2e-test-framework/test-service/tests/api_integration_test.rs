@@ -248,4 +248,129 @@ async fn test_repos_endpoint_exists() {
     
     // Should return OK (empty list) or similar success status
     assert_ne!(response.status(), StatusCode::NOT_FOUND);
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_start_all_and_stop_all_test_runs() {
+    let client = reqwest::Client::new();
+
+    let test_run_config = json!({
+        "test_id": "test_api_test",
+        "test_repo_id": "test_repo",
+        "test_run_id": "run_bulk",
+        "sources": [],
+        "queries": [],
+        "reactions": [],
+        "drasi_servers": []
+    });
+
+    let response = client
+        .post(format!("{}/api/test_runs", BASE_URL))
+        .json(&test_run_config)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: serde_json::Value = response.json().await.unwrap();
+    let run_id = created["id"].as_str().unwrap().to_string();
+
+    // Starting twice is idempotent for runs that are already Running.
+    for _ in 0..2 {
+        let response = client
+            .post(format!("{}/api/test_runs/start_all", BASE_URL))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let results: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(results[&run_id], "Running");
+    }
+
+    let response = client
+        .post(format!("{}/api/test_runs/stop_all", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let results: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(results[&run_id], "Stopped");
+
+    client
+        .delete(format!("{}/api/test_runs/{}", BASE_URL, run_id))
+        .send()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_metrics_endpoint() {
+    let client = reqwest::Client::new();
+
+    let test_run_config = json!({
+        "test_id": "test_api_test",
+        "test_repo_id": "test_repo",
+        "test_run_id": "run_metrics",
+        "sources": [],
+        "queries": [],
+        "reactions": [],
+        "drasi_servers": []
+    });
+
+    let response = client
+        .post(format!("{}/api/test_runs", BASE_URL))
+        .json(&test_run_config)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let created: serde_json::Value = response.json().await.unwrap();
+    let run_id = created["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .get(format!("{}/metrics", BASE_URL))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let body = response.text().await.unwrap();
+    assert!(body.contains("# TYPE drasi_test_run_status gauge"));
+    assert!(body.contains(
+        "drasi_test_run_status{test_repo_id=\"test_repo\",test_id=\"test_api_test\",test_run_id=\"run_metrics\",status=\"Running\"} 1"
+    ));
+
+    client
+        .delete(format!("{}/api/test_runs/{}", BASE_URL, run_id))
+        .send()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_unknown_data_collection_returns_404() {
+    let client = reqwest::Client::new();
+
+    for endpoint in [
+        "/data_collections/no_such_collection",
+        "/data_collections/no_such_collection/records?offset=0&limit=10",
+    ] {
+        let response = client
+            .get(format!("{}{}", BASE_URL, endpoint))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.status(),
+            StatusCode::NOT_FOUND,
+            "Endpoint {} should return 404",
+            endpoint
+        );
+    }
+}
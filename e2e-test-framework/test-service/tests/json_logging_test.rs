@@ -0,0 +1,77 @@
+// Copyright 2025 The Drasi Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+// Starts the test service and returns the first few lines it logs.
+fn read_startup_log_lines(log_format: Option<&str>, count: usize) -> anyhow::Result<Vec<String>> {
+    let temp_dir = TempDir::new()?;
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_test-service"));
+    command
+        .args(["--data", temp_dir.path().to_str().unwrap(), "--port", "0"])
+        .env("RUST_LOG", "info")
+        .env_remove("DRASI_LOG_FORMAT")
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    if let Some(log_format) = log_format {
+        command.args(["--log-format", log_format]);
+    }
+
+    let mut child = command.spawn()?;
+    let stderr = child.stderr.take().unwrap();
+    let lines = BufReader::new(stderr)
+        .lines()
+        .take(count)
+        .collect::<Result<Vec<_>, _>>();
+
+    child.kill()?;
+    child.wait()?;
+    Ok(lines?)
+}
+
+#[test]
+fn test_json_log_format() -> anyhow::Result<()> {
+    let lines = read_startup_log_lines(Some("json"), 2)?;
+    assert_eq!(lines.len(), 2);
+
+    for line in lines.iter() {
+        let record: serde_json::Value = serde_json::from_str(line)
+            .map_err(|e| anyhow::anyhow!("Log line {:?} is not JSON: {}", line, e))?;
+        for field in ["timestamp", "level", "target", "message"] {
+            assert!(record[field].is_string(), "{} missing from {}", field, line);
+        }
+    }
+
+    let first: serde_json::Value = serde_json::from_str(&lines[0])?;
+    assert_eq!(first["level"], "INFO");
+    assert!(first["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Started Test Service"));
+
+    Ok(())
+}
+
+#[test]
+fn test_text_log_format_is_default() -> anyhow::Result<()> {
+    let lines = read_startup_log_lines(None, 1)?;
+    assert_eq!(lines.len(), 1);
+    assert!(serde_json::from_str::<serde_json::Value>(&lines[0]).is_err());
+    assert!(lines[0].contains("Started Test Service"));
+
+    Ok(())
+}